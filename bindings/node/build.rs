@@ -0,0 +1,4 @@
+fn main() {
+    // Link flags for the Node.js addon
+    napi_build::setup();
+}
@@ -0,0 +1,90 @@
+//! Node.js bindings.
+//!
+//! Builds a native addon with napi-rs (`npm run build` in this directory,
+//! see `package.json`), so editors and viewers can run the analysis in-process
+//! instead of spawning the CLI. An `Analyzer` loads the standard library
+//! once and reuses it for every call:
+//!
+//! ```js
+//! const { Analyzer } = require("syster-cli-node");
+//!
+//! const analyzer = new Analyzer({ stdlib: true });
+//! const result = analyzer.analyze("models/");   // as --json
+//! const xmi = analyzer.export("models/", "xmi"); // Buffer
+//! const report = analyzer.diff("v1/", "v2/");    // as `syster semver --json`
+//! ```
+
+use napi::bindgen_prelude::Buffer;
+use napi_derive::napi;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use syster_cli::{AnalysisOptions, LoadedStdlib, semver_check};
+
+/// Options of a new [`Analyzer`].
+#[napi(object)]
+pub struct AnalyzerOptions {
+    /// Load the standard library (default: true, as on the command line).
+    pub stdlib: Option<bool>,
+    /// Standard library directory, instead of the default locations.
+    pub stdlib_path: Option<String>,
+}
+
+/// Analyzes models against a standard library loaded once.
+#[napi]
+pub struct Analyzer {
+    /// The standard library, copied for each call.
+    stdlib: LoadedStdlib,
+}
+
+#[napi]
+impl Analyzer {
+    /// Load the standard library, unless `options.stdlib` is false.
+    #[napi(constructor)]
+    pub fn new(options: Option<AnalyzerOptions>) -> napi::Result<Self> {
+        let options = options.unwrap_or(AnalyzerOptions {
+            stdlib: None,
+            stdlib_path: None,
+        });
+        let stdlib = LoadedStdlib::load(&AnalysisOptions {
+            load_stdlib: options.stdlib.unwrap_or(true),
+            stdlib_path: options.stdlib_path.map(PathBuf::from),
+            ..AnalysisOptions::default()
+        })
+        .map_err(napi::Error::from_reason)?;
+        Ok(Self { stdlib })
+    }
+
+    /// Analyze a file or directory; returns the `--json` result.
+    #[napi]
+    pub fn analyze(&self, path: String) -> napi::Result<Value> {
+        let result = self
+            .stdlib
+            .analyze(Path::new(&path), &AnalysisOptions::default())
+            .map_err(napi::Error::from_reason)?;
+        to_value(&result)
+    }
+
+    /// Export a file or directory to an interchange format (`xmi`, `kpar`,
+    /// `jsonld`, `json`, `yaml`).
+    #[napi]
+    pub fn export(&self, path: String, format: String) -> napi::Result<Buffer> {
+        let bytes = self
+            .stdlib
+            .export(Path::new(&path), &format, &AnalysisOptions::default())
+            .map_err(napi::Error::from_reason)?;
+        Ok(bytes.into())
+    }
+
+    /// Compare two releases of a model; returns the `syster semver` report.
+    #[napi]
+    pub fn diff(&self, a: String, b: String) -> napi::Result<Value> {
+        let report = semver_check(Path::new(&a), Path::new(&b), None, None, false)
+            .map_err(napi::Error::from_reason)?;
+        to_value(&report)
+    }
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> napi::Result<Value> {
+    serde_json::to_value(value)
+        .map_err(|e| napi::Error::from_reason(format!("Failed to serialize result: {}", e)))
+}
@@ -0,0 +1,90 @@
+#ifndef SYSTER_H
+#define SYSTER_H
+
+/* Generated by cbindgen from src/capi.rs (`make header`); do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// An analysis, as returned by [`syster_analyze_path`].
+typedef struct SysterAnalysis SysterAnalysis;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Analyze a file or directory, with the standard library if
+// `load_stdlib`. Returns `NULL` on failure.
+//
+// # Safety
+//
+// `path` must be `NULL` or a NUL-terminated string.
+SysterAnalysis *syster_analyze_path(const char *path, bool load_stdlib);
+
+// The analysis as the JSON written by `--json`: counts and diagnostics.
+// Returns `NULL` on failure; free the string with [`syster_string_free`].
+//
+// # Safety
+//
+// `analysis` must be `NULL` or returned by [`syster_analyze_path`] and
+// not yet freed.
+char *syster_get_diagnostics_json(const SysterAnalysis *analysis);
+
+// The number of errors of an analysis, or -1 if `analysis` is `NULL`.
+//
+// # Safety
+//
+// As for [`syster_get_diagnostics_json`].
+int64_t syster_error_count(const SysterAnalysis *analysis);
+
+// Export a file or directory to an interchange format (`xmi`, `kpar`,
+// `jsonld`, `json`, `yaml`). On success stores the bytes in `*out` and their
+// length in `*out_len` and returns 0; free the bytes with
+// [`syster_bytes_free`]. Returns -1 on failure.
+//
+// # Safety
+//
+// `path` and `format` must be `NULL` or NUL-terminated strings; `out` and
+// `out_len` must be `NULL` or valid for writes.
+int32_t syster_export_model_bytes(const char *path,
+                                  const char *format,
+                                  bool load_stdlib,
+                                  uint8_t **out,
+                                  size_t *out_len);
+
+// The message of the last failed call on this thread, or `NULL`. The
+// string stays valid until the next failing call on the thread.
+const char *syster_last_error(void);
+
+// Free an analysis.
+//
+// # Safety
+//
+// `analysis` must be `NULL` or returned by [`syster_analyze_path`] and
+// not yet freed.
+void syster_analysis_free(SysterAnalysis *analysis);
+
+// Free a string returned by this library.
+//
+// # Safety
+//
+// `s` must be `NULL` or a string returned by this library and not yet
+// freed.
+void syster_string_free(char *s);
+
+// Free bytes returned by [`syster_export_model_bytes`].
+//
+// # Safety
+//
+// `bytes` and `len` must be `NULL` and 0, or as returned by
+// [`syster_export_model_bytes`] and not yet freed.
+void syster_bytes_free(uint8_t *bytes, size_t len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SYSTER_H */
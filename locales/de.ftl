@@ -0,0 +1,25 @@
+## Berichtstexte von syster, auf Deutsch (siehe en.ftl).
+
+severity-error = Fehler
+severity-warning = Warnung
+severity-info = Info
+severity-hint = Hinweis
+
+analysis-ok = ✓ { $files } Dateien analysiert: { $symbols } Symbole, { $warnings } Warnungen
+analysis-failed = ✗ { $files } Dateien analysiert: { $errors } Fehler, { $warnings } Warnungen
+doc-coverage = Dokumentationsabdeckung: { $documented }/{ $total } ({ $percent } %)
+syntax-errors-excluded = { $files } Dateien mit Syntaxfehlern, ausgeschlossen
+syntax-errors-recovered = { $files } Dateien mit Syntaxfehlern, { $symbols } Symbole wiederhergestellt
+stdlib-diagnostics-hidden = { $count } Diagnosen in der Standardbibliothek nicht gemeldet (--include-stdlib-diagnostics)
+project-summary = { $project }: { $files } Dateien, { $errors } Fehler, { $warnings } Warnungen
+
+summary-by-package = Nach Paket:
+summary-by-file = Nach Datei:
+summary-by-severity = Nach Schweregrad:
+summary-row = { $symbols } Symbole, { $errors } Fehler, { $warnings } Warnungen
+
+diag-E0001 = nicht definierte Referenz: '{ $name }'
+diag-E0002 = mehrdeutige Referenz: '{ $name }' könnte sein: { $candidates }
+diag-E0004 = doppelte Definition: '{ $name }' ist bereits definiert
+diag-L0005 = { $kind } '{ $name }' ist nicht dokumentiert
+diag-L0007 = `{ $element }` hängt von `{ $target }` ab, aber die Schicht { $layers } darf nur von { $allowed } abhängen
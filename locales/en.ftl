@@ -0,0 +1,30 @@
+## Report text of syster, in English. Message IDs and the variables they
+## take are stable; each translation next to this file uses the same ones.
+
+severity-error = error
+severity-warning = warning
+severity-info = info
+severity-hint = hint
+
+analysis-ok = ✓ Analyzed { $files } files: { $symbols } symbols, { $warnings } warnings
+analysis-failed = ✗ Analyzed { $files } files: { $errors } errors, { $warnings } warnings
+doc-coverage = Documentation coverage: { $documented }/{ $total } ({ $percent }%)
+syntax-errors-excluded = { $files } files with syntax errors, excluded
+syntax-errors-recovered = { $files } files with syntax errors, { $symbols } symbols recovered
+stdlib-diagnostics-hidden = { $count } diagnostics inside the standard library not reported (--include-stdlib-diagnostics)
+project-summary = { $project }: { $files } files, { $errors } errors, { $warnings } warnings
+
+summary-by-package = By package:
+summary-by-file = By file:
+summary-by-severity = By severity:
+summary-row = { $symbols } symbols, { $errors } errors, { $warnings } warnings
+
+## Diagnostics, by code. The English message of a diagnostic is matched
+## against its pattern here to find the variables, so each pattern must
+## reproduce the message exactly.
+
+diag-E0001 = undefined reference: '{ $name }'
+diag-E0002 = ambiguous reference: '{ $name }' could be: { $candidates }
+diag-E0004 = duplicate definition: '{ $name }' is already defined
+diag-L0005 = { $kind } '{ $name }' has no documentation
+diag-L0007 = `{ $element }` depends on `{ $target }`, but layer { $layers } may only depend on { $allowed }
@@ -0,0 +1,25 @@
+## Textes des rapports de syster, en français (voir en.ftl).
+
+severity-error = erreur
+severity-warning = avertissement
+severity-info = info
+severity-hint = indication
+
+analysis-ok = ✓ { $files } fichiers analysés : { $symbols } symboles, { $warnings } avertissements
+analysis-failed = ✗ { $files } fichiers analysés : { $errors } erreurs, { $warnings } avertissements
+doc-coverage = Couverture de la documentation : { $documented }/{ $total } ({ $percent } %)
+syntax-errors-excluded = { $files } fichiers avec des erreurs de syntaxe, exclus
+syntax-errors-recovered = { $files } fichiers avec des erreurs de syntaxe, { $symbols } symboles récupérés
+stdlib-diagnostics-hidden = { $count } diagnostics dans la bibliothèque standard non signalés (--include-stdlib-diagnostics)
+project-summary = { $project } : { $files } fichiers, { $errors } erreurs, { $warnings } avertissements
+
+summary-by-package = Par paquetage :
+summary-by-file = Par fichier :
+summary-by-severity = Par gravité :
+summary-row = { $symbols } symboles, { $errors } erreurs, { $warnings } avertissements
+
+diag-E0001 = référence non définie : '{ $name }'
+diag-E0002 = référence ambiguë : '{ $name }' pourrait désigner : { $candidates }
+diag-E0004 = définition en double : '{ $name }' est déjà défini
+diag-L0005 = { $kind } '{ $name }' n'a pas de documentation
+diag-L0007 = `{ $element }` dépend de `{ $target }`, mais la couche { $layers } ne peut dépendre que de { $allowed }
@@ -0,0 +1,25 @@
+## syster のレポートの文言（日本語）。en.ftl を参照してください。
+
+severity-error = エラー
+severity-warning = 警告
+severity-info = 情報
+severity-hint = ヒント
+
+analysis-ok = ✓ { $files } 個のファイルを解析しました: シンボル { $symbols } 個、警告 { $warnings } 件
+analysis-failed = ✗ { $files } 個のファイルを解析しました: エラー { $errors } 件、警告 { $warnings } 件
+doc-coverage = ドキュメントの網羅率: { $documented }/{ $total } ({ $percent }%)
+syntax-errors-excluded = 構文エラーのあるファイル { $files } 個（除外）
+syntax-errors-recovered = 構文エラーのあるファイル { $files } 個、復元したシンボル { $symbols } 個
+stdlib-diagnostics-hidden = 標準ライブラリ内の診断 { $count } 個は報告していません（--include-stdlib-diagnostics）
+project-summary = { $project }: ファイル { $files } 個、エラー { $errors } 件、警告 { $warnings } 件
+
+summary-by-package = パッケージ別:
+summary-by-file = ファイル別:
+summary-by-severity = 重大度別:
+summary-row = シンボル { $symbols } 個、エラー { $errors } 件、警告 { $warnings } 件
+
+diag-E0001 = 未定義の参照: '{ $name }'
+diag-E0002 = あいまいな参照: '{ $name }' の候補: { $candidates }
+diag-E0004 = 重複した定義: '{ $name }' はすでに定義されています
+diag-L0005 = { $kind } '{ $name }' にドキュメントがありません
+diag-L0007 = `{ $element }` は `{ $target }` に依存していますが、レイヤー { $layers } が依存できるのは { $allowed } のみです
@@ -0,0 +1,508 @@
+//! Alias and re-export resolution report.
+//!
+//! [`resolve_aliases`] lists every `alias` in the workspace with the chain of
+//! names it goes through (an alias of an alias, ...) and the element it
+//! finally denotes, and every public import with the chain of namespaces it
+//! re-exports (the standard library's `ISQ` re-exports `ISQSpaceTime`, which
+//! may re-export further) and the members that become visible through it.
+//! This is the information needed to debug a name that resolves somewhere
+//! unexpected, or not at all.
+//!
+//! [`find_symbol`] searches the other way round: given a name, it lists the
+//! declarations, aliases and re-exports it is reachable as, each with the
+//! chain to the element it denotes (`ISQ::DurationValue ->
+//! ISQSpaceTime::DurationValue`), to find the canonical home of a name
+//! imported indirectly.
+
+use super::AnalysisOptions;
+use super::names::parent_scope;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use syster::hir::{HirSymbol, SymbolIndex, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// An alias and what it resolves to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AliasChain {
+    /// Qualified name of the alias.
+    pub alias: String,
+    /// File declaring the alias.
+    pub file: String,
+    /// Line of the declaration (1-indexed).
+    pub line: u32,
+    /// Names the alias goes through, ending with the final target (or the
+    /// name that failed to resolve).
+    pub chain: Vec<String>,
+    /// Element finally denoted, `None` if the chain does not resolve or is
+    /// circular.
+    pub target: Option<String>,
+}
+
+impl AliasChain {
+    /// Whether the chain leads back to a name already on it.
+    pub fn is_circular(&self) -> bool {
+        let Some((last, before)) = self.chain.split_last() else {
+            return false;
+        };
+        *last == self.alias || before.contains(last)
+    }
+}
+
+/// A public import and what it makes visible.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Reexport {
+    /// Namespace re-exporting.
+    pub scope: String,
+    /// File declaring the first import of the chain.
+    pub file: String,
+    /// Line of that import (1-indexed).
+    pub line: u32,
+    /// Re-exported namespaces (or element), outermost first; each one
+    /// publicly imports the next.
+    pub chain: Vec<String>,
+    /// Members visible through the last link, with their final targets.
+    pub members: Vec<ReexportedMember>,
+}
+
+/// A name made visible by a re-export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReexportedMember {
+    /// Name as visible in the re-exporting scope.
+    pub name: String,
+    /// Element it finally denotes, following aliases.
+    pub target: Option<String>,
+}
+
+/// Every alias and re-export chain of a workspace.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AliasReport {
+    /// Aliases, by qualified name.
+    pub aliases: Vec<AliasChain>,
+    /// Re-export chains, by scope.
+    pub reexports: Vec<Reexport>,
+}
+
+/// How a [`SymbolMatch`] is reachable under its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchKind {
+    /// The element is declared under the name.
+    Declaration,
+    /// An alias of that name.
+    Alias,
+    /// A public import makes the element visible under the name.
+    Reexport,
+}
+
+/// A name an element is reachable as.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolMatch {
+    /// Qualified name the element is reachable as.
+    pub name: String,
+    /// Whether the name is declared, an alias or re-exported.
+    pub via: MatchKind,
+    /// File declaring the element, the alias or the first import of the
+    /// re-export chain.
+    pub file: String,
+    /// Line of that declaration (1-indexed).
+    pub line: u32,
+    /// Names `name` goes through, ending with the final target (or the name
+    /// that failed to resolve); empty for a declaration.
+    pub chain: Vec<String>,
+    /// Element finally denoted, `None` if the chain does not resolve or is
+    /// circular.
+    pub target: Option<String>,
+}
+
+impl SymbolMatch {
+    /// The name and its chain, e.g. `ISQ::DurationValue ->
+    /// ISQSpaceTime::DurationValue`.
+    pub fn path(&self) -> String {
+        std::iter::once(&self.name)
+            .chain(&self.chain)
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" -> ")
+    }
+}
+
+/// A public import: the namespace or element it imports, resolved.
+struct PublicImport {
+    scope: Arc<str>,
+    target: String,
+    wildcard: bool,
+    file: String,
+    line: u32,
+}
+
+/// Report the aliases and re-exports of the workspace at `input`,
+/// standard library included.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `scope` - Only report aliases and re-exports declared in this namespace
+/// * `options` - Verbosity and standard library to load
+pub fn resolve_aliases(
+    input: &Path,
+    scope: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<AliasReport, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    super::load_input(&mut host, input, options.verbose)?;
+    let _analysis = host.analysis();
+    let index = host.symbol_index();
+
+    let in_scope = |name: &str| match scope {
+        Some(scope) => {
+            name == scope
+                || name
+                    .strip_prefix(scope)
+                    .is_some_and(|rest| rest.starts_with("::"))
+        }
+        None => true,
+    };
+
+    let mut paths: Vec<&PathBuf> = host.files().keys().collect();
+    paths.sort();
+    let mut report = AliasReport::default();
+    let mut imports: BTreeMap<Arc<str>, Vec<PublicImport>> = BTreeMap::new();
+    for path in paths {
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        let file = path.to_string_lossy().to_string();
+        for symbol in index.symbols_in_file(file_id) {
+            match symbol.kind {
+                SymbolKind::Alias if in_scope(&symbol.qualified_name) => {
+                    let (chain, target) = follow_alias(symbol, index);
+                    report.aliases.push(AliasChain {
+                        alias: symbol.qualified_name.to_string(),
+                        file: file.clone(),
+                        line: symbol.start_line + 1,
+                        chain,
+                        target,
+                    });
+                }
+                SymbolKind::Import if symbol.is_public => {
+                    if let Some(import) = public_import(symbol, &file, index) {
+                        imports
+                            .entry(import.scope.clone())
+                            .or_default()
+                            .push(import);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for (reexporting, scope_imports) in &imports {
+        if !in_scope(reexporting) {
+            continue;
+        }
+        for import in scope_imports {
+            let mut visited = HashSet::from([reexporting.to_string()]);
+            let mut chain = Vec::new();
+            collect_chains(
+                import,
+                &imports,
+                &mut visited,
+                &mut chain,
+                &mut |chain, last| {
+                    report.reexports.push(Reexport {
+                        scope: reexporting.to_string(),
+                        file: import.file.clone(),
+                        line: import.line,
+                        chain: chain.to_vec(),
+                        members: members(last, index),
+                    });
+                },
+            );
+        }
+    }
+
+    report.aliases.sort_by(|a, b| a.alias.cmp(&b.alias));
+    if options.verbose {
+        tracing::info!(
+            "Found {} aliases and {} re-export chains",
+            report.aliases.len(),
+            report.reexports.len()
+        );
+    }
+    Ok(report)
+}
+
+/// Find every declaration, alias and re-export `name` is reachable as in
+/// the workspace at `input`, standard library included.
+///
+/// A simple name matches any qualified name ending with it; a qualified name
+/// (`ISQ::DurationValue`) only itself. Declarations come first, then
+/// aliases, then re-exports, each by name.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `name` - Simple or qualified name to look for
+/// * `options` - Verbosity and standard library to load
+pub fn find_symbol(
+    input: &Path,
+    name: &str,
+    options: &AnalysisOptions,
+) -> Result<Vec<SymbolMatch>, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    super::load_input(&mut host, input, options.verbose)?;
+    let _analysis = host.analysis();
+    let index = host.symbol_index();
+
+    let simple_name = name.rsplit("::").next().unwrap_or(name);
+    let is_match = |qualified_name: &str| {
+        if name.contains("::") {
+            qualified_name == name
+        } else {
+            qualified_name.rsplit("::").next() == Some(name)
+        }
+    };
+
+    let mut paths: Vec<&PathBuf> = host.files().keys().collect();
+    paths.sort();
+    let mut matches = Vec::new();
+    let mut imports: BTreeMap<Arc<str>, Vec<PublicImport>> = BTreeMap::new();
+    for path in paths {
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        let file = path.to_string_lossy().to_string();
+        for symbol in index.symbols_in_file(file_id) {
+            match symbol.kind {
+                SymbolKind::Import => {
+                    if !symbol.is_public {
+                        continue;
+                    }
+                    if let Some(import) = public_import(symbol, &file, index) {
+                        imports
+                            .entry(import.scope.clone())
+                            .or_default()
+                            .push(import);
+                    }
+                }
+                _ if !is_match(&symbol.qualified_name) => {}
+                SymbolKind::Alias => {
+                    let (chain, target) = follow_alias(symbol, index);
+                    matches.push(SymbolMatch {
+                        name: symbol.qualified_name.to_string(),
+                        via: MatchKind::Alias,
+                        file: file.clone(),
+                        line: symbol.start_line + 1,
+                        chain,
+                        target,
+                    });
+                }
+                _ => matches.push(SymbolMatch {
+                    name: symbol.qualified_name.to_string(),
+                    via: MatchKind::Declaration,
+                    file: file.clone(),
+                    line: symbol.start_line + 1,
+                    chain: Vec::new(),
+                    target: Some(symbol.qualified_name.to_string()),
+                }),
+            }
+        }
+    }
+
+    for (reexporting, scope_imports) in &imports {
+        let reexported_name = format!("{}::{}", reexporting, simple_name);
+        if !is_match(&reexported_name) {
+            continue;
+        }
+        for import in scope_imports {
+            let mut visited = HashSet::from([reexporting.to_string()]);
+            let mut chain = Vec::new();
+            collect_chains(
+                import,
+                &imports,
+                &mut visited,
+                &mut chain,
+                &mut |chain, last| {
+                    if let Some((chain, target)) = reexport_chain(chain, last, simple_name, index) {
+                        matches.push(SymbolMatch {
+                            name: reexported_name.clone(),
+                            via: MatchKind::Reexport,
+                            file: import.file.clone(),
+                            line: import.line,
+                            chain,
+                            target,
+                        });
+                    }
+                },
+            );
+        }
+    }
+
+    matches.sort_by(|a, b| (a.via, &a.name, &a.chain).cmp(&(b.via, &b.name, &b.chain)));
+    matches.dedup();
+    if options.verbose {
+        tracing::info!("Found {} name(s) matching {}", matches.len(), name);
+    }
+    Ok(matches)
+}
+
+/// The names a re-export chain makes `simple_name` go through, and the
+/// element it finally denotes; `None` if the last link does not make the
+/// name visible.
+fn reexport_chain(
+    chain: &[String],
+    last: &PublicImport,
+    simple_name: &str,
+    index: &SymbolIndex,
+) -> Option<(Vec<String>, Option<String>)> {
+    let member = if last.wildcard {
+        index
+            .visibility_for_scope(&last.target)?
+            .lookup_direct(simple_name)?
+            .to_string()
+    } else if last.target.rsplit("::").next() == Some(simple_name) {
+        last.target.clone()
+    } else {
+        return None;
+    };
+    // Every link but the last is a namespace imported with a wildcard
+    let mut names: Vec<String> = chain[..chain.len() - 1]
+        .iter()
+        .map(|namespace| format!("{}::{}", namespace, simple_name))
+        .collect();
+    names.push(member.clone());
+    match index.lookup_qualified(&member) {
+        Some(symbol) if symbol.kind == SymbolKind::Alias => {
+            let (alias_chain, target) = follow_alias(symbol, index);
+            names.extend(alias_chain);
+            Some((names, target))
+        }
+        Some(symbol) => Some((names, Some(symbol.qualified_name.to_string()))),
+        None => Some((names, None)),
+    }
+}
+
+/// Follow an alias through aliases of aliases to the element it denotes.
+fn follow_alias(alias: &HirSymbol, index: &SymbolIndex) -> (Vec<String>, Option<String>) {
+    let mut chain = Vec::new();
+    let mut seen = HashSet::from([alias.qualified_name.to_string()]);
+    let mut current = alias.clone();
+    loop {
+        let Some((written, resolved)) = current
+            .type_refs
+            .iter()
+            .flat_map(|r| r.as_refs())
+            .next()
+            .map(|r| (r.target.to_string(), r.resolved_target.clone()))
+        else {
+            return (chain, None);
+        };
+        let target = resolved
+            .and_then(|name| index.lookup_qualified(&name).cloned())
+            .or_else(|| {
+                index
+                    .resolver_for_scope(parent_scope(&current.qualified_name))
+                    .resolve(&written)
+                    .symbol()
+                    .cloned()
+            });
+        let Some(target) = target else {
+            chain.push(written);
+            return (chain, None);
+        };
+        let name = target.qualified_name.to_string();
+        chain.push(name.clone());
+        if !seen.insert(name.clone()) {
+            return (chain, None);
+        }
+        if target.kind != SymbolKind::Alias {
+            return (chain, Some(name));
+        }
+        current = target;
+    }
+}
+
+/// The scope and resolved target of a public import symbol.
+fn public_import(symbol: &HirSymbol, file: &str, index: &SymbolIndex) -> Option<PublicImport> {
+    let written = symbol.type_refs.iter().flat_map(|r| r.as_refs()).next()?;
+    // Import symbols are named `<scope>::import:<path>`
+    let scope = symbol
+        .qualified_name
+        .strip_suffix(&format!("import:{}", symbol.name))
+        .map(|s| s.trim_end_matches("::"))
+        .unwrap_or_else(|| parent_scope(&symbol.qualified_name));
+    let target = written
+        .resolved_target
+        .as_ref()
+        .map(|t| t.to_string())
+        .or_else(|| {
+            index
+                .resolver_for_scope(scope)
+                .resolve(&written.target)
+                .symbol()
+                .map(|s| s.qualified_name.to_string())
+        })
+        .unwrap_or_else(|| written.target.to_string());
+    Some(PublicImport {
+        scope: Arc::from(scope),
+        target,
+        wildcard: symbol.name.ends_with("::*") || symbol.name.ends_with("::**"),
+        file: file.to_string(),
+        line: symbol.start_line + 1,
+    })
+}
+
+/// Report `import`'s chain, then every chain continuing through the
+/// namespace it re-exports.
+fn collect_chains(
+    import: &PublicImport,
+    imports: &BTreeMap<Arc<str>, Vec<PublicImport>>,
+    visited: &mut HashSet<String>,
+    chain: &mut Vec<String>,
+    report: &mut dyn FnMut(&[String], &PublicImport),
+) {
+    chain.push(import.target.clone());
+    report(chain, import);
+    if import.wildcard && visited.insert(import.target.clone()) {
+        for next in imports.get(import.target.as_str()).into_iter().flatten() {
+            collect_chains(next, imports, visited, chain, report);
+        }
+        visited.remove(&import.target);
+    }
+    chain.pop();
+}
+
+/// Names made visible by the last import of a chain, with final targets.
+fn members(import: &PublicImport, index: &SymbolIndex) -> Vec<ReexportedMember> {
+    let final_target = |qualified_name: &str| match index.lookup_qualified(qualified_name) {
+        Some(symbol) if symbol.kind == SymbolKind::Alias => follow_alias(symbol, index).1,
+        Some(symbol) => Some(symbol.qualified_name.to_string()),
+        None => None,
+    };
+    if !import.wildcard {
+        let name = import.target.rsplit("::").next().unwrap_or(&import.target);
+        return vec![ReexportedMember {
+            name: name.to_string(),
+            target: final_target(&import.target),
+        }];
+    }
+    let Some(visibility) = index.visibility_for_scope(&import.target) else {
+        return Vec::new();
+    };
+    let mut members: Vec<ReexportedMember> = visibility
+        .direct_defs()
+        .map(|(name, qualified_name)| ReexportedMember {
+            name: name.to_string(),
+            target: final_target(qualified_name),
+        })
+        .collect();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    members
+}
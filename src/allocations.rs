@@ -0,0 +1,459 @@
+//! Allocation consistency (`syster allocations`).
+//!
+//! Lists every `allocate source to target` of a model, with the elements
+//! both ends resolve to, and checks them:
+//!
+//! - dangling allocations: an end that does not resolve, or is missing;
+//! - allocations to an abstract definition, which has no instances to
+//!   carry what is allocated;
+//! - cardinality: every logical function — an action not nested in another
+//!   action, in the packages of the `[allocations]` table of `syster.toml`
+//!   — must be allocated as often as configured, by default exactly once.
+//!
+//! ```toml
+//! [allocations]
+//! logical = ["LogicalArchitecture"]
+//! cardinality = "1..*"    # default: "1"
+//! ```
+//!
+//! Globs are those of `[layers]`. Without `logical` globs only dangling and
+//! abstract allocations are checked.
+
+use super::AnalysisOptions;
+use super::layers::Glob;
+use super::names::qualify;
+use super::project::{find_project_file, load_table};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use syster::hir::{HirSymbol, Resolver, SymbolIndex, SymbolKind};
+use syster::ide::AnalysisHost;
+use syster::syntax::{NormalizedElement, NormalizedRelKind, RowanNormalizedIter, SyntaxFile};
+
+/// Logical packages and cardinality, from the `[allocations]` table of
+/// `syster.toml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AllocationConfig {
+    /// Globs of the packages holding the logical functions.
+    pub logical: Vec<String>,
+    /// Fewest allocations a logical function may have.
+    pub min: usize,
+    /// Most allocations a logical function may have (`None`: unbounded).
+    pub max: Option<usize>,
+}
+
+impl Default for AllocationConfig {
+    fn default() -> Self {
+        Self {
+            logical: Vec::new(),
+            min: 1,
+            max: Some(1),
+        }
+    }
+}
+
+/// The `[allocations]` table of `syster.toml`.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct AllocationsSection {
+    #[serde(default)]
+    logical: Vec<String>,
+    cardinality: Option<String>,
+}
+
+impl AllocationConfig {
+    /// Load the `[allocations]` table of a project file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let section: AllocationsSection = load_table(path, "allocations")?.unwrap_or_default();
+        let mut config = Self {
+            logical: section.logical,
+            ..Self::default()
+        };
+        if let Some(cardinality) = &section.cardinality {
+            config
+                .set_cardinality(cardinality)
+                .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        }
+        Ok(config)
+    }
+
+    /// Load the nearest `syster.toml` in `input` (or its directory) and its
+    /// ancestors, or the defaults if there is none.
+    pub fn discover(input: &Path) -> Result<Self, String> {
+        match find_project_file(input) {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Set the cardinality from a multiplicity: `1`, `0..1`, `1..3`, `1..*`.
+    pub fn set_cardinality(&mut self, text: &str) -> Result<(), String> {
+        let invalid = || {
+            format!(
+                "invalid cardinality '{}' (expected e.g. 1, 0..1, 1..*)",
+                text
+            )
+        };
+        let (min, max) = match text.trim().split_once("..") {
+            Some((min, max)) => (min.trim(), max.trim()),
+            None => (text.trim(), text.trim()),
+        };
+        let min: usize = min.parse().map_err(|_| invalid())?;
+        let max = match max {
+            "*" => None,
+            max => Some(max.parse::<usize>().map_err(|_| invalid())?),
+        };
+        if max.is_some_and(|max| max < min) {
+            return Err(invalid());
+        }
+        self.min = min;
+        self.max = max;
+        Ok(())
+    }
+
+    /// The cardinality as a multiplicity.
+    pub fn cardinality(&self) -> String {
+        match self.max {
+            Some(max) if max == self.min => max.to_string(),
+            Some(max) => format!("{}..{}", self.min, max),
+            None => format!("{}..*", self.min),
+        }
+    }
+}
+
+/// An `allocate source to target`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Allocation {
+    /// Qualified name of the allocation, if it is named.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Source as written.
+    pub source: String,
+    /// Target as written.
+    pub target: String,
+    /// Qualified name of the element the source resolves to.
+    pub source_element: Option<String>,
+    /// Qualified name of the element the target resolves to.
+    pub target_element: Option<String>,
+    /// File declaring the allocation.
+    pub file: String,
+    /// Line of the allocation (1-based).
+    pub line: u32,
+}
+
+/// An inconsistent allocation, or a function allocated too few or too
+/// many times.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "issue")]
+pub enum AllocationIssue {
+    /// An end of an allocation that does not resolve, or is missing.
+    Dangling {
+        end: String,
+        file: String,
+        line: u32,
+    },
+    /// An allocation to an abstract definition.
+    AbstractTarget {
+        source: String,
+        target: String,
+        file: String,
+        line: u32,
+    },
+    /// A logical function allocated a number of times outside the
+    /// cardinality.
+    Cardinality {
+        function: String,
+        allocations: usize,
+        cardinality: String,
+        file: String,
+        line: u32,
+    },
+}
+
+impl fmt::Display for AllocationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AllocationIssue::Dangling { end, file, line } if end.is_empty() => {
+                write!(
+                    f,
+                    "{}:{}: allocation without a source or target",
+                    file, line
+                )
+            }
+            AllocationIssue::Dangling { end, file, line } => {
+                write!(
+                    f,
+                    "{}:{}: dangling allocation: '{}' not found",
+                    file, line, end
+                )
+            }
+            AllocationIssue::AbstractTarget {
+                source,
+                target,
+                file,
+                line,
+            } => write!(
+                f,
+                "{}:{}: '{}' is allocated to abstract definition {}",
+                file, line, source, target
+            ),
+            AllocationIssue::Cardinality {
+                function,
+                allocations,
+                cardinality,
+                file,
+                line,
+            } => write!(
+                f,
+                "{}:{}: logical function {} is allocated {} times (expected {})",
+                file, line, function, allocations, cardinality
+            ),
+        }
+    }
+}
+
+/// The allocations of a model and their issues.
+#[derive(Debug, Clone, Serialize)]
+pub struct AllocationReport {
+    /// Every allocation, in file and line order.
+    pub allocations: Vec<Allocation>,
+    /// Issues found: dangling and abstract allocations in the order of
+    /// `allocations`, then cardinality by function name.
+    pub issues: Vec<AllocationIssue>,
+}
+
+impl AllocationReport {
+    /// Whether no issue was found.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Analyze `input` and check its allocations against `config`.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `config` - Logical packages and cardinality
+/// * `options` - Verbosity and standard library to load
+pub fn check_allocations(
+    input: &Path,
+    config: &AllocationConfig,
+    options: &AnalysisOptions,
+) -> Result<AllocationReport, String> {
+    let logical: Vec<Glob> = config
+        .logical
+        .iter()
+        .map(|glob| Glob::new(glob))
+        .collect::<Result<_, String>>()?;
+
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let library: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    super::load_input(&mut host, input, options.verbose)?;
+    let _analysis = host.analysis();
+    let index = host.symbol_index();
+
+    let mut allocations = Vec::new();
+    let mut paths: Vec<_> = host
+        .files()
+        .keys()
+        .filter(|path| !library.contains(&*path.to_string_lossy()))
+        .collect();
+    paths.sort();
+    for path in paths {
+        allocations.extend(file_allocations(
+            index,
+            &path.to_string_lossy(),
+            &host.files()[path],
+        ));
+    }
+
+    let mut issues = Vec::new();
+    for allocation in &allocations {
+        let ends = [
+            (&allocation.source, &allocation.source_element),
+            (&allocation.target, &allocation.target_element),
+        ];
+        for (written, element) in ends {
+            if element.is_none() {
+                issues.push(AllocationIssue::Dangling {
+                    end: written.clone(),
+                    file: allocation.file.clone(),
+                    line: allocation.line,
+                });
+            }
+        }
+        let target = allocation
+            .target_element
+            .as_deref()
+            .and_then(|name| index.lookup_qualified(name));
+        if let Some(target) = target.filter(|t| is_abstract_definition(t)) {
+            issues.push(AllocationIssue::AbstractTarget {
+                source: allocation.source.clone(),
+                target: target.qualified_name.to_string(),
+                file: allocation.file.clone(),
+                line: allocation.line,
+            });
+        }
+    }
+
+    if !logical.is_empty() {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for allocation in &allocations {
+            if let Some(source) = &allocation.source_element {
+                *counts.entry(source).or_default() += 1;
+            }
+        }
+        let mut functions: Vec<&HirSymbol> = index
+            .all_symbols()
+            .filter(|s| is_logical_function(index, s, &logical))
+            .filter(|s| {
+                host.get_file_path(s.file)
+                    .is_some_and(|path| !library.contains(path))
+            })
+            .collect();
+        functions.sort_by(|a, b| a.qualified_name.cmp(&b.qualified_name));
+        for function in functions {
+            let count = counts.get(&*function.qualified_name).copied().unwrap_or(0);
+            if count < config.min || config.max.is_some_and(|max| count > max) {
+                issues.push(AllocationIssue::Cardinality {
+                    function: function.qualified_name.to_string(),
+                    allocations: count,
+                    cardinality: config.cardinality(),
+                    file: host
+                        .get_file_path(function.file)
+                        .unwrap_or_default()
+                        .to_string(),
+                    line: function.start_line + 1,
+                });
+            }
+        }
+    }
+
+    if options.verbose {
+        tracing::info!(
+            "Checked {} allocations: {} issues",
+            allocations.len(),
+            issues.len()
+        );
+    }
+    Ok(AllocationReport {
+        allocations,
+        issues,
+    })
+}
+
+/// The allocations declared in one file, resolved in their scopes.
+fn file_allocations(index: &SymbolIndex, path: &str, file: &SyntaxFile) -> Vec<Allocation> {
+    let Some(source) = file.source_file() else {
+        return Vec::new();
+    };
+    let lines = file.line_index();
+    let mut allocations = Vec::new();
+    let mut pending: Vec<(String, NormalizedElement)> = RowanNormalizedIter::new(&source)
+        .map(|element| (String::new(), element))
+        .collect();
+    pending.reverse();
+    while let Some((scope, element)) = pending.pop() {
+        let (name, children) = match element {
+            NormalizedElement::Package(p) => (p.name, p.children),
+            NormalizedElement::Definition(d) => (d.name, d.children),
+            NormalizedElement::Usage(u) => {
+                let end = |kind: NormalizedRelKind| {
+                    u.relationships
+                        .iter()
+                        .find(|r| r.kind == kind)
+                        .map(|r| r.target.as_str().into_owned())
+                };
+                let (source, target) = (
+                    end(NormalizedRelKind::AllocateSource),
+                    end(NormalizedRelKind::AllocateTo),
+                );
+                if source.is_some() || target.is_some() {
+                    let source = source.unwrap_or_default();
+                    let target = target.unwrap_or_default();
+                    allocations.push(Allocation {
+                        name: u.name.as_deref().map(|n| qualify(&scope, n)),
+                        source_element: resolve(index, &scope, &source),
+                        target_element: resolve(index, &scope, &target),
+                        source,
+                        target,
+                        file: path.to_string(),
+                        line: u.range.map_or(0, |r| lines.line_col(r.start()).line) + 1,
+                    });
+                }
+                (u.name, u.children)
+            }
+            _ => continue,
+        };
+        let scope = match name {
+            Some(name) => qualify(&scope, &name),
+            None => scope,
+        };
+        pending.extend(
+            children
+                .into_iter()
+                .rev()
+                .map(|child| (scope.clone(), child)),
+        );
+    }
+    allocations
+}
+
+/// Resolve an allocation end written in `scope`: a name, or a feature
+/// chain whose every part is a member of the previous one.
+fn resolve(index: &SymbolIndex, scope: &str, written: &str) -> Option<String> {
+    if written.is_empty() {
+        return None;
+    }
+    let mut parts = written.split('.');
+    let first = parts.next()?;
+    let mut current = Resolver::new(index)
+        .with_scope(scope)
+        .resolve(first)
+        .symbol()?
+        .clone();
+    for part in parts {
+        let member = format!("{}::{}", current.qualified_name, part);
+        current = match index.lookup_qualified(&member) {
+            Some(symbol) => symbol.clone(),
+            None => Resolver::new(index)
+                .with_scope(current.qualified_name.clone())
+                .resolve(part)
+                .symbol()?
+                .clone(),
+        };
+    }
+    Some(current.qualified_name.to_string())
+}
+
+fn is_abstract_definition(symbol: &HirSymbol) -> bool {
+    symbol.is_abstract && symbol.kind.is_definition() && symbol.kind != SymbolKind::Package
+}
+
+/// Whether `symbol` is an action in a logical package and not nested in
+/// another action.
+fn is_logical_function(index: &SymbolIndex, symbol: &HirSymbol, logical: &[Glob]) -> bool {
+    if symbol.kind != SymbolKind::ActionUsage || symbol.name.starts_with('<') {
+        return false;
+    }
+    let Some((owner, _)) = symbol.qualified_name.rsplit_once("::") else {
+        return false;
+    };
+    if !logical.iter().any(|glob| glob.matches(owner)) {
+        return false;
+    }
+    !index.lookup_qualified(owner).is_some_and(|s| {
+        matches!(
+            s.kind,
+            SymbolKind::ActionUsage | SymbolKind::ActionDefinition
+        )
+    })
+}
@@ -0,0 +1,54 @@
+//! Element selection by metadata annotation.
+//!
+//! User-defined metadata (`#safety`, `@Itar;`) marks elements for downstream
+//! toolchains. Selecting by a metadata definition keeps the annotated
+//! elements, everything nested inside them, and the namespaces enclosing them
+//! so qualified names stay intact.
+
+use std::collections::BTreeSet;
+use syster::hir::HirSymbol;
+
+/// Whether `annotation`, as written in the model, names the metadata
+/// definition `name`. Either side may be qualified.
+pub fn matches_metadata(annotation: &str, name: &str) -> bool {
+    annotation == name
+        || annotation
+            .strip_suffix(name)
+            .is_some_and(|prefix| prefix.ends_with("::"))
+        || name
+            .strip_suffix(annotation)
+            .is_some_and(|prefix| prefix.ends_with("::"))
+}
+
+/// Whether `symbol` is annotated with any of `names`.
+pub fn is_annotated(symbol: &HirSymbol, names: &[String]) -> bool {
+    symbol
+        .metadata_annotations
+        .iter()
+        .any(|a| names.iter().any(|name| matches_metadata(a, name)))
+}
+
+/// Keep the symbols annotated with any of `names`, their contents and their
+/// enclosing namespaces.
+pub fn select_annotated(symbols: Vec<HirSymbol>, names: &[String]) -> Vec<HirSymbol> {
+    let tagged: BTreeSet<String> = symbols
+        .iter()
+        .filter(|sym| is_annotated(sym, names))
+        .map(|sym| sym.qualified_name.to_string())
+        .collect();
+
+    let selected = |name: &str| {
+        tagged.iter().any(|t| {
+            name == t
+                || name
+                    .strip_prefix(t.as_str())
+                    .is_some_and(|rest| rest.starts_with("::"))
+                || t.strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with("::"))
+        })
+    };
+    symbols
+        .into_iter()
+        .filter(|sym| selected(&sym.qualified_name))
+        .collect()
+}
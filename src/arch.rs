@@ -0,0 +1,440 @@
+//! Package dependency metrics and architecture smells (`syster arch-check`).
+//!
+//! A package depends on another when one of its members references a member
+//! of the other through an import, typing, specialization or any other
+//! relationship. Over that graph each package gets its fan-in (packages
+//! depending on it), fan-out (packages it depends on), instability
+//! (fan-out over fan-in plus fan-out) and cohesion (the share of its
+//! members' references that stay inside it). Three smells are flagged:
+//!
+//! - cycles: packages that depend on each other, directly or through others;
+//! - god packages: fan-in plus fan-out above a threshold;
+//! - layer violations: dependencies the `[arch]` table of `syster.toml`
+//!   forbids.
+//!
+//! ```toml
+//! [arch]
+//! max_coupling = 8    # default: 10
+//!
+//! [[arch.rules]]
+//! package = "Application"
+//! must_not_depend_on = ["Hardware", "Drivers"]
+//! ```
+//!
+//! A rule covers the packages nested in the ones it names. Packages of the
+//! standard library are left out of the graph.
+
+use super::AnalysisOptions;
+use super::closure::dependencies;
+use super::project::{find_project_file, load_table};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::path::Path;
+use syster::hir::{SymbolIndex, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// Default god-package threshold on fan-in plus fan-out.
+pub const DEFAULT_MAX_COUPLING: usize = 10;
+
+/// Thresholds and layer rules, from the `[arch]` table of `syster.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArchConfig {
+    /// Largest fan-in plus fan-out a package may have.
+    #[serde(default = "default_max_coupling")]
+    pub max_coupling: usize,
+    /// Forbidden dependencies.
+    #[serde(default)]
+    pub rules: Vec<LayerRule>,
+}
+
+/// A package, and the packages it must not depend on.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayerRule {
+    /// Qualified name of the constrained package.
+    pub package: String,
+    /// Qualified names of the packages it must not depend on.
+    pub must_not_depend_on: Vec<String>,
+}
+
+impl Default for ArchConfig {
+    fn default() -> Self {
+        Self {
+            max_coupling: DEFAULT_MAX_COUPLING,
+            rules: Vec::new(),
+        }
+    }
+}
+
+fn default_max_coupling() -> usize {
+    DEFAULT_MAX_COUPLING
+}
+
+impl ArchConfig {
+    /// Load the `[arch]` table of a project file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Ok(load_table(path, "arch")?.unwrap_or_default())
+    }
+
+    /// Load the nearest `syster.toml` in `input` (or its directory) and its
+    /// ancestors, or the defaults if there is none.
+    pub fn discover(input: &Path) -> Result<Self, String> {
+        match find_project_file(input) {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// Metrics of one package.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PackageMetrics {
+    /// Qualified name of the package.
+    pub package: String,
+    /// Number of elements directly in it, outside nested packages.
+    pub elements: usize,
+    /// Number of packages depending on it.
+    pub fan_in: usize,
+    /// Number of packages it depends on.
+    pub fan_out: usize,
+    /// Fan-out over fan-in plus fan-out: 0 for a package that depends on
+    /// nothing, 1 for one nothing depends on.
+    pub instability: f64,
+    /// Share of its members' references to analyzed packages that stay
+    /// inside it; `None` if they make none.
+    pub cohesion: Option<f64>,
+}
+
+impl PackageMetrics {
+    /// Fan-in plus fan-out.
+    pub fn coupling(&self) -> usize {
+        self.fan_in + self.fan_out
+    }
+}
+
+/// An architecture smell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "smell")]
+pub enum ArchSmell {
+    /// Packages depending on each other, in qualified-name order.
+    Cycle { packages: Vec<String> },
+    /// A package coupled to more packages than the threshold allows.
+    GodPackage {
+        package: String,
+        coupling: usize,
+        max_coupling: usize,
+    },
+    /// A dependency a layer rule forbids, with the elements making it.
+    LayerViolation {
+        package: String,
+        depends_on: String,
+        rule: String,
+        elements: Vec<String>,
+    },
+}
+
+impl fmt::Display for ArchSmell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchSmell::Cycle { packages } => {
+                write!(f, "Dependency cycle: {}", packages.join(" -> "))?;
+                write!(f, " -> {}", packages[0])
+            }
+            ArchSmell::GodPackage {
+                package,
+                coupling,
+                max_coupling,
+            } => write!(
+                f,
+                "God package {}: coupled to {} packages (at most {})",
+                package, coupling, max_coupling
+            ),
+            ArchSmell::LayerViolation {
+                package,
+                depends_on,
+                rule,
+                elements,
+            } => write!(
+                f,
+                "Layer violation: {} depends on {} ({}) through {}",
+                package,
+                depends_on,
+                rule,
+                elements.join(", ")
+            ),
+        }
+    }
+}
+
+/// Package metrics and smells of a model.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchReport {
+    /// Every analyzed package, in qualified-name order.
+    pub packages: Vec<PackageMetrics>,
+    /// Dependencies between packages: package → packages it depends on.
+    pub dependencies: BTreeMap<String, BTreeSet<String>>,
+    /// Smells found, cycles first, then god packages and layer violations.
+    pub smells: Vec<ArchSmell>,
+}
+
+impl ArchReport {
+    /// Whether no smell was found.
+    pub fn is_clean(&self) -> bool {
+        self.smells.is_empty()
+    }
+}
+
+/// Analyze `input` and check its package dependencies against `config`.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `config` - Thresholds and layer rules
+/// * `options` - Verbosity and standard library to load
+pub fn arch_check(
+    input: &Path,
+    config: &ArchConfig,
+    options: &AnalysisOptions,
+) -> Result<ArchReport, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let library: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    super::load_input(&mut host, input, options.verbose)?;
+
+    let analysis = host.analysis();
+    let index = analysis.symbol_index();
+    let packages: BTreeSet<String> = index
+        .all_symbols()
+        .filter(|s| s.kind == SymbolKind::Package)
+        .filter(|s| {
+            analysis
+                .get_file_path(s.file)
+                .is_some_and(|path| !library.contains(path))
+        })
+        .map(|s| s.qualified_name.to_string())
+        .collect();
+
+    let report = check_packages(index, &packages, config);
+    if options.verbose {
+        tracing::info!(
+            "Checked {} packages: {} smells",
+            report.packages.len(),
+            report.smells.len()
+        );
+    }
+    Ok(report)
+}
+
+/// Build the dependency graph of `packages` and check it.
+fn check_packages(
+    index: &SymbolIndex,
+    packages: &BTreeSet<String>,
+    config: &ArchConfig,
+) -> ArchReport {
+    let mut elements: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut internal: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut external: BTreeMap<&str, usize> = BTreeMap::new();
+    // (package, dependency) → elements making the dependency
+    let mut edges: BTreeMap<(&str, &str), BTreeSet<String>> = BTreeMap::new();
+
+    for symbol in index.all_symbols() {
+        let Some(from) = enclosing_package(packages, &symbol.qualified_name) else {
+            continue;
+        };
+        if symbol.kind != SymbolKind::Package {
+            *elements.entry(from).or_default() += 1;
+        }
+        for target in dependencies(index, symbol) {
+            let Some(to) = package_of(packages, &target) else {
+                continue;
+            };
+            if to == from {
+                *internal.entry(from).or_default() += 1;
+                continue;
+            }
+            *external.entry(from).or_default() += 1;
+            edges
+                .entry((from, to))
+                .or_default()
+                .insert(symbol.qualified_name.to_string());
+        }
+    }
+
+    let mut dependencies: BTreeMap<String, BTreeSet<String>> = packages
+        .iter()
+        .map(|p| (p.clone(), BTreeSet::new()))
+        .collect();
+    for (from, to) in edges.keys() {
+        if let Some(targets) = dependencies.get_mut(*from) {
+            targets.insert(to.to_string());
+        }
+    }
+
+    let metrics: Vec<PackageMetrics> = packages
+        .iter()
+        .map(|package| {
+            let fan_out = dependencies[package].len();
+            let fan_in = dependencies
+                .values()
+                .filter(|targets| targets.contains(package))
+                .count();
+            let inside = internal.get(package.as_str()).copied().unwrap_or(0);
+            let outside = external.get(package.as_str()).copied().unwrap_or(0);
+            PackageMetrics {
+                package: package.clone(),
+                elements: elements.get(package.as_str()).copied().unwrap_or(0),
+                fan_in,
+                fan_out,
+                instability: ratio(fan_out, fan_in + fan_out).unwrap_or(0.0),
+                cohesion: ratio(inside, inside + outside),
+            }
+        })
+        .collect();
+
+    let mut smells: Vec<ArchSmell> = cycles(&dependencies)
+        .into_iter()
+        .map(|packages| ArchSmell::Cycle { packages })
+        .collect();
+    smells.extend(
+        metrics
+            .iter()
+            .filter(|m| m.coupling() > config.max_coupling)
+            .map(|m| ArchSmell::GodPackage {
+                package: m.package.clone(),
+                coupling: m.coupling(),
+                max_coupling: config.max_coupling,
+            }),
+    );
+    for ((from, to), via) in &edges {
+        for rule in &config.rules {
+            let Some(forbidden) = rule
+                .must_not_depend_on
+                .iter()
+                .find(|layer| within(to, layer))
+            else {
+                continue;
+            };
+            if within(from, &rule.package) && !within(from, forbidden) {
+                smells.push(ArchSmell::LayerViolation {
+                    package: from.to_string(),
+                    depends_on: to.to_string(),
+                    rule: format!("{} must not depend on {}", rule.package, forbidden),
+                    elements: via.iter().cloned().collect(),
+                });
+            }
+        }
+    }
+
+    ArchReport {
+        packages: metrics,
+        dependencies,
+        smells,
+    }
+}
+
+/// The innermost package strictly enclosing `name`.
+pub(crate) fn enclosing_package<'p>(packages: &'p BTreeSet<String>, name: &str) -> Option<&'p str> {
+    let mut current = name;
+    while let Some((parent, _)) = current.rsplit_once("::") {
+        if let Some(package) = packages.get(parent) {
+            return Some(package.as_str());
+        }
+        current = parent;
+    }
+    None
+}
+
+/// The package `name` is, or the innermost one enclosing it.
+pub(crate) fn package_of<'p>(packages: &'p BTreeSet<String>, name: &str) -> Option<&'p str> {
+    packages
+        .get(name)
+        .map(String::as_str)
+        .or_else(|| enclosing_package(packages, name))
+}
+
+/// Whether `package` is `layer` or nested in it.
+fn within(package: &str, layer: &str) -> bool {
+    package == layer
+        || package
+            .strip_prefix(layer)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+fn ratio(part: usize, whole: usize) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+/// Strongly connected components with more than one package, found with
+/// Tarjan's algorithm; each in qualified-name order, ordered by first name.
+fn cycles(graph: &BTreeMap<String, BTreeSet<String>>) -> Vec<Vec<String>> {
+    struct Tarjan<'g> {
+        graph: &'g BTreeMap<String, BTreeSet<String>>,
+        next: usize,
+        index: BTreeMap<&'g str, usize>,
+        low: BTreeMap<&'g str, usize>,
+        stack: Vec<&'g str>,
+        on_stack: HashSet<&'g str>,
+        components: Vec<Vec<String>>,
+    }
+
+    impl<'g> Tarjan<'g> {
+        fn visit(&mut self, node: &'g str) {
+            self.index.insert(node, self.next);
+            self.low.insert(node, self.next);
+            self.next += 1;
+            self.stack.push(node);
+            self.on_stack.insert(node);
+
+            for target in self.graph.get(node).into_iter().flatten() {
+                let target = target.as_str();
+                if !self.index.contains_key(target) {
+                    self.visit(target);
+                    let low = self.low[node].min(self.low[target]);
+                    self.low.insert(node, low);
+                } else if self.on_stack.contains(target) {
+                    let low = self.low[node].min(self.index[target]);
+                    self.low.insert(node, low);
+                }
+            }
+
+            if self.low[node] == self.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack.remove(member);
+                    component.push(member.to_string());
+                    if member == node {
+                        break;
+                    }
+                }
+                if component.len() > 1 {
+                    component.sort();
+                    self.components.push(component);
+                }
+            }
+        }
+    }
+
+    let mut tarjan = Tarjan {
+        graph,
+        next: 0,
+        index: BTreeMap::new(),
+        low: BTreeMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    for node in graph.keys() {
+        if !tarjan.index.contains_key(node.as_str()) {
+            tarjan.visit(node);
+        }
+    }
+    tarjan.components.sort();
+    tarjan.components
+}
@@ -0,0 +1,203 @@
+//! Attestations of analysis results (`--attest`).
+//!
+//! A safety case citing an analysis report needs evidence that the report
+//! came from a specific version of the model. An attestation is an in-toto
+//! statement whose subject is the report (the result as `--json` writes
+//! it) and whose predicate is SLSA provenance recording how it was made:
+//! the tool version, the command-line arguments, and the SHA-256 of the
+//! input's SysML/KerML files, of the project file, `MODELOWNERS` and
+//! variant configuration read, of the libraries and of the standard
+//! library. Re-running the analysis on the same sources reproduces every
+//! digest.
+//!
+//! With a key, the statement is wrapped in a DSSE envelope signed with
+//! Ed25519, as in-toto tooling verifies it; without one it is written as
+//! is.
+
+use super::owners::find_owners_file;
+use super::project::find_project_file;
+use super::provenance::{hash_sources, timestamp};
+use super::{AnalysisOptions, to_hex};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// `_type` of an in-toto statement.
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+
+/// `predicateType` of SLSA provenance.
+pub const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+
+/// `buildType` of an analysis run.
+pub const BUILD_TYPE: &str = concat!(env!("CARGO_PKG_REPOSITORY"), "/attestation/analysis/v1");
+
+/// `payloadType` of a DSSE envelope holding an in-toto statement.
+pub const PAYLOAD_TYPE: &str = "application/vnd.in-toto+json";
+
+/// A file or directory an analysis read, with its digest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSource {
+    /// Path as the analysis found it.
+    pub name: String,
+    /// SHA-256 (hexadecimal) of the file, or of a directory's SysML/KerML
+    /// files and their relative paths.
+    pub sha256: String,
+}
+
+/// What an attestation binds together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attestation {
+    /// Name of the attested report.
+    pub report: String,
+    /// SHA-256 (hexadecimal) of the report.
+    pub report_sha256: String,
+    /// Analyzed file or directory.
+    pub input: String,
+    /// Command-line arguments of the run.
+    pub arguments: Vec<String>,
+    /// Everything the result depends on, the input first.
+    pub sources: Vec<ResolvedSource>,
+    /// Version of syster.
+    pub tool_version: String,
+    /// Time of the attestation as RFC 3339 UTC (from `SOURCE_DATE_EPOCH`
+    /// when set).
+    pub finished_on: String,
+}
+
+impl Attestation {
+    /// Attest `report`, named `name`, as the analysis of `input` with
+    /// `options` run with `arguments`.
+    pub fn collect(
+        input: &Path,
+        options: &AnalysisOptions,
+        arguments: Vec<String>,
+        name: &str,
+        report: &[u8],
+    ) -> Result<Self, String> {
+        let mut sources = vec![tree_source(input)?];
+        let files = [
+            find_project_file(input),
+            options
+                .owners
+                .as_ref()
+                .and_then(|_| find_owners_file(input)),
+            options.config.map(|config| config.path.clone()),
+        ];
+        for path in files.into_iter().flatten() {
+            let bytes = std::fs::read(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            sources.push(ResolvedSource {
+                name: path.to_string_lossy().to_string(),
+                sha256: to_hex(&Sha256::digest(&bytes)),
+            });
+        }
+        for library in &options.libraries {
+            sources.push(tree_source(library)?);
+        }
+        if options.load_stdlib {
+            if let Some(dir) = super::find_stdlib_dir(options.stdlib_path.as_deref())? {
+                sources.push(tree_source(&dir)?);
+            }
+        }
+
+        Ok(Self {
+            report: name.to_string(),
+            report_sha256: to_hex(&Sha256::digest(report)),
+            input: input.to_string_lossy().to_string(),
+            arguments,
+            sources,
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            finished_on: timestamp()?,
+        })
+    }
+
+    /// The in-toto statement.
+    pub fn statement(&self) -> Value {
+        let dependencies: Vec<Value> = self
+            .sources
+            .iter()
+            .map(|source| json!({"name": source.name, "digest": {"sha256": source.sha256}}))
+            .collect();
+        json!({
+            "_type": STATEMENT_TYPE,
+            "subject": [{"name": self.report, "digest": {"sha256": self.report_sha256}}],
+            "predicateType": PREDICATE_TYPE,
+            "predicate": {
+                "buildDefinition": {
+                    "buildType": BUILD_TYPE,
+                    "externalParameters": {
+                        "input": self.input,
+                        "arguments": self.arguments,
+                    },
+                    "resolvedDependencies": dependencies,
+                },
+                "runDetails": {
+                    "builder": {
+                        "id": env!("CARGO_PKG_REPOSITORY"),
+                        "version": {"syster": self.tool_version},
+                    },
+                    "metadata": {"finishedOn": self.finished_on},
+                },
+            },
+        })
+    }
+
+    /// The statement in a DSSE envelope signed with `key`.
+    #[cfg(feature = "signing")]
+    pub fn sign(&self, key: &ed25519_dalek::SigningKey) -> Value {
+        use ed25519_dalek::Signer;
+
+        let payload = self.statement().to_string();
+        let signature = key.sign(&pre_auth_encoding(PAYLOAD_TYPE, payload.as_bytes()));
+        json!({
+            "payloadType": PAYLOAD_TYPE,
+            "payload": base64(payload.as_bytes()),
+            "signatures": [{
+                "keyid": to_hex(key.verifying_key().as_bytes()),
+                "sig": base64(&signature.to_bytes()),
+            }],
+        })
+    }
+}
+
+/// `path` with the digest of its SysML/KerML files.
+fn tree_source(path: &Path) -> Result<ResolvedSource, String> {
+    let hash = hash_sources(path)?;
+    Ok(ResolvedSource {
+        name: path.to_string_lossy().to_string(),
+        sha256: hash.trim_start_matches("sha256:").to_string(),
+    })
+}
+
+/// What a DSSE signature covers: `DSSEv1 <len> <type> <len> <payload>`.
+pub fn pre_auth_encoding(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut bytes = format!(
+        "DSSEv1 {} {} {} ",
+        payload_type.len(),
+        payload_type,
+        payload.len()
+    )
+    .into_bytes();
+    bytes.extend_from_slice(payload);
+    bytes
+}
+
+/// Standard base64 with padding, which DSSE envelopes use.
+#[cfg(feature = "signing")]
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (u32::from(chunk[0]) << 16)
+            | (u32::from(chunk.get(1).copied().unwrap_or(0)) << 8)
+            | u32::from(chunk.get(2).copied().unwrap_or(0));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
@@ -0,0 +1,84 @@
+//! Model health badges.
+//!
+//! [`badge`] turns an analysis result into a shields.io endpoint badge (see
+//! <https://shields.io/badges/endpoint-badge>). Commit the JSON, or publish it
+//! from CI, and reference it from a README:
+//!
+//! ```markdown
+//! ![errors](https://img.shields.io/endpoint?url=https://example.com/badge.json)
+//! ```
+
+use super::AnalysisResult;
+use serde::Serialize;
+
+/// What a badge shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeMetric {
+    /// Number of errors: green when there are none, red otherwise.
+    Errors,
+    /// Number of warnings: green when there are none, yellow below 10,
+    /// orange otherwise.
+    Warnings,
+    /// Documentation coverage, from red below 25% to bright green from 90%.
+    DocCoverage,
+}
+
+/// A shields.io endpoint badge.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Badge {
+    /// Always 1.
+    pub schema_version: u32,
+    /// Left-hand text.
+    pub label: String,
+    /// Right-hand text.
+    pub message: String,
+    /// Right-hand color, as a shields.io color name.
+    pub color: String,
+}
+
+/// The badge showing `metric` of `result`, labelled `label` (default: the
+/// metric's name).
+pub fn badge(result: &AnalysisResult, metric: BadgeMetric, label: Option<&str>) -> Badge {
+    let (default_label, message, color) = match metric {
+        BadgeMetric::Errors => (
+            "errors",
+            result.error_count.to_string(),
+            if result.error_count == 0 {
+                "brightgreen"
+            } else {
+                "red"
+            },
+        ),
+        BadgeMetric::Warnings => (
+            "warnings",
+            result.warning_count.to_string(),
+            match result.warning_count {
+                0 => "brightgreen",
+                1..10 => "yellow",
+                _ => "orange",
+            },
+        ),
+        BadgeMetric::DocCoverage => {
+            let percent = result.doc_coverage.percent;
+            let color = if percent >= 90.0 {
+                "brightgreen"
+            } else if percent >= 75.0 {
+                "green"
+            } else if percent >= 50.0 {
+                "yellow"
+            } else if percent >= 25.0 {
+                "orange"
+            } else {
+                "red"
+            };
+            ("doc coverage", format!("{:.0}%", percent), color)
+        }
+    };
+    Badge {
+        schema_version: 1,
+        label: label.unwrap_or(default_label).to_string(),
+        message,
+        color: color.to_string(),
+    }
+}
@@ -0,0 +1,195 @@
+//! Named model baselines.
+//!
+//! `syster baseline create --tag R1` exports the model as XMI into
+//! `.syster/baselines/R1/model.xmi` (next to `syster.toml`, or in the model
+//! directory) together with a `baseline.json` record holding the export's
+//! SHA-256 and provenance. `syster baseline compare R1` checks that hash,
+//! then classifies the differences between the baseline and the current
+//! sources like [`semver_check`](super::semver::semver_check), giving a
+//! change report for formal baselining.
+
+use super::project::find_project_file;
+use super::provenance::Provenance;
+use super::semver::{Change, ChangeLevel, classify_changes};
+use super::to_hex;
+use super::{AnalysisOptions, ExportFilter};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use syster::interchange::{ModelFormat, Xmi};
+
+/// Directory holding baselines, relative to the project root.
+pub const BASELINE_DIR: &str = ".syster/baselines";
+
+/// Snapshot file inside a baseline directory.
+const MODEL_FILE: &str = "model.xmi";
+
+/// Record file inside a baseline directory.
+const RECORD_FILE: &str = "baseline.json";
+
+/// A stored baseline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Baseline tag.
+    pub tag: String,
+    /// SHA-256 of the stored XMI snapshot.
+    pub hash: String,
+    /// Number of elements in the snapshot.
+    pub elements: usize,
+    /// Where the snapshot came from.
+    pub provenance: Provenance,
+}
+
+/// Differences between a baseline and the current sources.
+#[derive(Debug, Serialize)]
+pub struct BaselineComparison {
+    /// The baseline compared against.
+    pub baseline: Baseline,
+    /// Largest change, `None` if the model is unchanged.
+    pub level: Option<ChangeLevel>,
+    /// Every change, most severe first.
+    pub changes: Vec<Change>,
+}
+
+/// Snapshot the model in `src` as baseline `tag`.
+///
+/// Fails if the baseline exists, unless `force` is set.
+///
+/// # Arguments
+/// * `src` - Source file or directory
+/// * `tag` - Baseline name (letters, digits, `.`, `-` and `_`)
+/// * `force` - Replace an existing baseline with the same tag
+/// * `options` - Verbosity, standard library to load and directory walk
+pub fn create_baseline(
+    src: &Path,
+    tag: &str,
+    force: bool,
+    options: &AnalysisOptions,
+) -> Result<Baseline, String> {
+    let dir = baseline_dir(src, tag)?;
+    if dir.exists() && !force {
+        return Err(format!(
+            "Baseline '{}' already exists (use --force to replace it)",
+            tag
+        ));
+    }
+
+    let (bytes, _) =
+        super::export_model_with_options(src, "xmi", options, false, &ExportFilter::default())?;
+    let model = Xmi.read(&bytes).map_err(|e| e.to_string())?;
+    let provenance = Provenance::from_export(&bytes)
+        .ok_or_else(|| "Export carries no provenance record".to_string())?;
+    let baseline = Baseline {
+        tag: tag.to_string(),
+        hash: hash(&bytes),
+        elements: model.elements.len(),
+        provenance,
+    };
+
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let model_path = dir.join(MODEL_FILE);
+    std::fs::write(&model_path, &bytes)
+        .map_err(|e| format!("Failed to write {}: {}", model_path.display(), e))?;
+    let json = serde_json::to_string_pretty(&baseline)
+        .map_err(|e| format!("Failed to serialize baseline: {}", e))?;
+    let record_path = dir.join(RECORD_FILE);
+    std::fs::write(&record_path, json)
+        .map_err(|e| format!("Failed to write {}: {}", record_path.display(), e))?;
+
+    if options.verbose {
+        tracing::info!("Stored baseline {} in {}", tag, dir.display());
+    }
+    Ok(baseline)
+}
+
+/// Compare the model in `src` against baseline `tag`.
+///
+/// Fails if the baseline does not exist or its snapshot no longer matches
+/// the recorded hash.
+///
+/// # Arguments
+/// * `src` - Source file or directory
+/// * `tag` - Baseline to compare against
+/// * `options` - Verbosity, standard library to load and directory walk
+pub fn compare_baseline(
+    src: &Path,
+    tag: &str,
+    options: &AnalysisOptions,
+) -> Result<BaselineComparison, String> {
+    let dir = baseline_dir(src, tag)?;
+    let record_path = dir.join(RECORD_FILE);
+    if !record_path.is_file() {
+        return Err(format!("Baseline '{}' not found in {}", tag, dir.display()));
+    }
+    let record = std::fs::read_to_string(&record_path)
+        .map_err(|e| format!("Failed to read {}: {}", record_path.display(), e))?;
+    let baseline: Baseline = serde_json::from_str(&record)
+        .map_err(|e| format!("Invalid {}: {}", record_path.display(), e))?;
+
+    let model_path = dir.join(MODEL_FILE);
+    let stored = std::fs::read(&model_path)
+        .map_err(|e| format!("Failed to read {}: {}", model_path.display(), e))?;
+    if hash(&stored) != baseline.hash {
+        return Err(format!(
+            "Baseline '{}' has been modified: {} does not match its recorded hash",
+            tag,
+            model_path.display()
+        ));
+    }
+
+    let old = Xmi.read(&stored).map_err(|e| e.to_string())?;
+    let (current, _) =
+        super::export_model_with_options(src, "xmi", options, false, &ExportFilter::default())?;
+    let new = Xmi.read(&current).map_err(|e| e.to_string())?;
+    if options.verbose {
+        tracing::info!(
+            "Comparing {} current elements with {} in baseline {}",
+            new.elements.len(),
+            old.elements.len(),
+            tag
+        );
+    }
+
+    let changes = classify_changes(&old, &new);
+    Ok(BaselineComparison {
+        baseline,
+        level: changes.iter().map(|c| c.level).max(),
+        changes,
+    })
+}
+
+/// `.syster/baselines/<tag>` under the directory of the `syster.toml`
+/// governing `src`, or under the model directory if there is none.
+fn baseline_dir(src: &Path, tag: &str) -> Result<PathBuf, String> {
+    let valid = !tag.is_empty()
+        && !tag.starts_with('.')
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if !valid {
+        return Err(format!(
+            "Invalid baseline tag '{}': use letters, digits, '.', '-' and '_'",
+            tag
+        ));
+    }
+
+    let root = match find_project_file(src) {
+        Some(project) => project
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from(".")),
+        None if src.is_dir() => src.to_path_buf(),
+        None => src
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+            .to_path_buf(),
+    };
+    Ok(root.join(BASELINE_DIR).join(tag))
+}
+
+/// `sha256:<hex>` of `bytes`.
+fn hash(bytes: &[u8]) -> String {
+    format!("sha256:{}", to_hex(&Sha256::digest(bytes)))
+}
@@ -0,0 +1,99 @@
+//! Ownership annotation for diagnostics.
+//!
+//! With `--blame`, each diagnostic records who last changed the offending
+//! line according to `git blame`, so issues can be routed to the modeler
+//! who owns them. Files outside a git work tree, or not yet committed, are
+//! left unannotated.
+
+use super::{DiagnosticInfo, format_rfc3339};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// The last change to a line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Blame {
+    /// Abbreviated commit hash (`0000000` for uncommitted changes).
+    pub commit: String,
+    /// Author name.
+    pub author: String,
+    /// Author e-mail address.
+    pub email: String,
+    /// Author date (`YYYY-MM-DDTHH:MM:SSZ`).
+    pub date: String,
+}
+
+/// Annotate every diagnostic with the last change to its start line.
+///
+/// `git blame` runs once per file.
+pub fn annotate_blame(diagnostics: &mut [DiagnosticInfo]) {
+    let mut files: HashMap<String, Option<Vec<Blame>>> = HashMap::new();
+    for diagnostic in diagnostics.iter_mut() {
+        let lines = files
+            .entry(diagnostic.file.clone())
+            .or_insert_with(|| blame_file(Path::new(&diagnostic.file)));
+        diagnostic.blame = lines
+            .as_ref()
+            .and_then(|lines| lines.get(diagnostic.line.saturating_sub(1) as usize))
+            .cloned();
+    }
+}
+
+/// Blame every line of `path`, or `None` if git cannot.
+fn blame_file(path: &Path) -> Option<Vec<Blame>> {
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty());
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir.unwrap_or(Path::new(".")))
+        .args(["blame", "--line-porcelain", "--"])
+        .arg(path.file_name()?)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_line_porcelain(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `git blame --line-porcelain` output into one entry per line.
+///
+/// Every line is a header `<sha> <orig> <final> [<count>]`, `key value`
+/// fields, and the line's content prefixed with a tab.
+fn parse_line_porcelain(output: &str) -> Vec<Blame> {
+    let mut lines = Vec::new();
+    let mut current: Option<Blame> = None;
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            lines.extend(current.take());
+            continue;
+        }
+        let Some(entry) = current.as_mut() else {
+            let sha = line.split(' ').next().unwrap_or_default();
+            current = Some(Blame {
+                commit: sha.chars().take(7).collect(),
+                author: String::new(),
+                email: String::new(),
+                date: String::new(),
+            });
+            continue;
+        };
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "author" => entry.author = value.to_string(),
+            "author-mail" => {
+                entry.email = value
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            }
+            "author-time" => {
+                entry.date = value.parse().map(format_rfc3339).unwrap_or_default();
+            }
+            _ => {}
+        }
+    }
+    lines
+}
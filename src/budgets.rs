@@ -0,0 +1,184 @@
+//! Model size budgets (`[budgets]` in `syster.toml`).
+//!
+//! A model that keeps growing in one place gets hard to review and to
+//! navigate. Budgets cap how far it may grow, each one optional:
+//!
+//! ```toml
+//! [budgets]
+//! max_package_elements = 200  # L0012: elements owned by one package
+//! max_file_lines = 1000       # L0013: lines of one file
+//! max_nesting_depth = 6       # L0014: levels of namespaces down to an element
+//! ```
+//!
+//! Every excess is an error, so analysis fails until the model is split up
+//! or the budget raised. A package owns the elements declared in it at any
+//! depth, except those of nested packages, which count towards their own;
+//! imports don't count. An element of a top-level package is nested two
+//! levels deep. Only the first element too deep is reported, not its
+//! members.
+
+use super::DiagnosticInfo;
+use super::lint::codes;
+use super::project::{find_project_file, load_table};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use syster::hir::{HirSymbol, Severity, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// Limits on the size of a model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budgets {
+    /// Most elements a package may own.
+    pub max_package_elements: Option<usize>,
+    /// Most lines a file may have.
+    pub max_file_lines: Option<usize>,
+    /// Most levels of namespaces an element may be nested in, counting the
+    /// element itself.
+    pub max_nesting_depth: Option<usize>,
+}
+
+impl Budgets {
+    /// Load the `[budgets]` table of a project file, if it has one.
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        load_table(path, "budgets")
+    }
+
+    /// Load the `[budgets]` table of the nearest `syster.toml` in `input`
+    /// (or its directory) and its ancestors, if there is one.
+    pub fn discover(input: &Path) -> Result<Option<Self>, String> {
+        match find_project_file(input) {
+            Some(path) => Self::load(&path),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Report where the model in `files` exceeds `budgets`.
+pub(crate) fn budget_diagnostics(
+    host: &AnalysisHost,
+    files: &HashSet<&str>,
+    budgets: &Budgets,
+) -> Vec<DiagnosticInfo> {
+    let mut diagnostics = Vec::new();
+    let mut symbols: Vec<(String, &HirSymbol)> = Vec::new();
+    let mut paths: Vec<_> = host.files().iter().collect();
+    paths.sort_by(|a, b| a.0.cmp(b.0));
+    for (path, syntax) in paths {
+        let file = path.to_string_lossy().to_string();
+        if !files.contains(file.as_str()) {
+            continue;
+        }
+        if let Some(max) = budgets.max_file_lines {
+            let lines = syntax.source_text().lines().count();
+            if lines > max {
+                diagnostics.push(budget_diagnostic(
+                    &file,
+                    (max as u32 + 1, 1, max as u32 + 1, 1),
+                    codes::FILE_LINES,
+                    format!("file has {} lines, over the budget of {}", lines, max),
+                ));
+            }
+        }
+        if let Some(file_id) = host.get_file_id_for_path(path) {
+            symbols.extend(
+                host.symbol_index()
+                    .symbols_in_file(file_id)
+                    .into_iter()
+                    .filter(|s| s.kind != SymbolKind::Import)
+                    .map(|s| (file.clone(), s)),
+            );
+        }
+    }
+
+    if let Some(max) = budgets.max_nesting_depth {
+        for (file, symbol) in &symbols {
+            let depth = symbol.qualified_name.split("::").count();
+            // Deeper members of a reported element are not reported again
+            if depth == max + 1 {
+                diagnostics.push(budget_diagnostic(
+                    file,
+                    span(symbol),
+                    codes::NESTING_DEPTH,
+                    format!(
+                        "{} '{}' is nested {} levels deep, over the budget of {}",
+                        symbol.kind.display().to_lowercase(),
+                        symbol.qualified_name,
+                        depth,
+                        max
+                    ),
+                ));
+            }
+        }
+    }
+
+    if let Some(max) = budgets.max_package_elements {
+        // A package spread over several files is reported at its first
+        // declaration
+        let packages: BTreeMap<&str, (&String, &HirSymbol)> = symbols
+            .iter()
+            .filter(|(_, s)| s.kind == SymbolKind::Package)
+            .map(|(file, s)| (s.qualified_name.as_ref(), (file, *s)))
+            .rev()
+            .collect();
+        let mut owned: BTreeMap<&str, usize> = BTreeMap::new();
+        for (_, symbol) in &symbols {
+            let mut scope = symbol.qualified_name.as_ref();
+            while let Some((parent, _)) = scope.rsplit_once("::") {
+                if packages.contains_key(parent) {
+                    *owned.entry(parent).or_default() += 1;
+                    break;
+                }
+                scope = parent;
+            }
+        }
+        for (package, count) in owned {
+            if count <= max {
+                continue;
+            }
+            let (file, symbol) = packages[package];
+            diagnostics.push(budget_diagnostic(
+                file,
+                span(symbol),
+                codes::PACKAGE_ELEMENTS,
+                format!(
+                    "package '{}' has {} elements, over the budget of {}",
+                    package, count, max
+                ),
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// 1-based start and end of the span of `symbol`.
+fn span(symbol: &HirSymbol) -> (u32, u32, u32, u32) {
+    (
+        symbol.start_line + 1,
+        symbol.start_col + 1,
+        symbol.end_line + 1,
+        symbol.end_col + 1,
+    )
+}
+
+fn budget_diagnostic(
+    file: &str,
+    (line, col, end_line, end_col): (u32, u32, u32, u32),
+    code: &str,
+    message: String,
+) -> DiagnosticInfo {
+    DiagnosticInfo {
+        file: file.to_string(),
+        line,
+        col,
+        end_line,
+        end_col,
+        message,
+        severity: Severity::Error,
+        code: Some(code.to_string()),
+        blame: None,
+        owners: Vec::new(),
+        related: Vec::new(),
+    }
+}
@@ -0,0 +1,260 @@
+//! Remote cache of analysis results (`--cache-url`).
+//!
+//! CI runners start from a clean checkout, so every run analyzes the model
+//! and the standard library from scratch even when nothing changed since
+//! the last one. With a cache URL, a run first asks the server for the
+//! result of an identical analysis and only analyzes on a miss, storing its
+//! result for the next runner:
+//!
+//! ```text
+//! SYSTER_CACHE_TOKEN=... syster ./models --cache-url https://cache.example.com/syster
+//! ```
+//!
+//! Results are stored as `<url>/<key>.json` with plain `GET` and `PUT`, so
+//! any HTTP store accepting uploads serves. Requests carry at most a bearer
+//! token; S3 and other stores that need signed (SigV4) requests work only
+//! behind a proxy that signs them. The key is a SHA-256 over everything
+//! the result depends on: the tool version, the input path (results name
+//! files by it), the files loaded from the model, its libraries and the
+//! standard library, the `.systerignore` (and, if respected, `.gitignore`)
+//! files choosing them, and the analysis options. Changing any of them
+//! misses the cache instead of returning a stale result.
+//!
+//! The cache only ever saves work: when the server can't be reached or
+//! returns something unreadable, the run analyzes as without it and warns.
+
+use super::http::{self, Auth};
+use super::systerignore::ignore_file_names;
+use super::{
+    AnalysisOptions, AnalysisResult, Budgets, LayerRules, ModelOwners, Profile, RequirementRules,
+    StdlibSubset, WalkOptions, severity_name, to_hex,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// An HTTP store of analysis results.
+#[derive(Debug, Clone)]
+pub struct RemoteCache {
+    /// Base URL results are stored under.
+    pub url: String,
+    /// Bearer token sent with every request, if any.
+    pub token: Option<String>,
+}
+
+impl RemoteCache {
+    /// A cache at `url`, authenticated with `token` if given.
+    pub fn new(url: &str, token: Option<String>) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    /// Analyze `input` as [`analyze`](super::analyze) does, reading the
+    /// result from the cache if it has it and storing it there if not.
+    pub fn analyze(
+        &self,
+        input: &Path,
+        options: &AnalysisOptions,
+    ) -> Result<AnalysisResult, String> {
+        let key = match cache_key(input, options) {
+            Ok(key) => key,
+            // Let the analysis report what can't be read
+            Err(_) => return super::analyze(input, options),
+        };
+        match self.get(&key) {
+            Ok(Some(result)) => {
+                if options.verbose {
+                    tracing::info!("Cache hit: {}", key);
+                }
+                return Ok(result);
+            }
+            Ok(None) => {
+                if options.verbose {
+                    tracing::info!("Cache miss: {}", key);
+                }
+            }
+            Err(e) => tracing::warn!("cache unavailable: {}", e),
+        }
+
+        let result = super::analyze(input, options)?;
+        if let Err(e) = self.put(&key, &result) {
+            tracing::warn!("failed to store result in cache: {}", e);
+        }
+        Ok(result)
+    }
+
+    /// The result stored under `key`, if any.
+    pub fn get(&self, key: &str) -> Result<Option<AnalysisResult>, String> {
+        let url = self.entry_url(key);
+        match http::get_if_found(&url, self.auth())? {
+            Some(value) => serde_json::from_value(value)
+                .map(Some)
+                .map_err(|e| format!("Invalid cache entry {}: {}", url, e)),
+            None => Ok(None),
+        }
+    }
+
+    /// Store `result` under `key`.
+    pub fn put(&self, key: &str, result: &AnalysisResult) -> Result<(), String> {
+        let body = serde_json::to_value(result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))?;
+        http::request("PUT", &self.entry_url(key), self.auth(), Some(&body))?;
+        Ok(())
+    }
+
+    fn entry_url(&self, key: &str) -> String {
+        format!("{}/{}.json", self.url, key)
+    }
+
+    fn auth(&self) -> Option<Auth<'_>> {
+        self.token.as_deref().map(Auth::Bearer)
+    }
+}
+
+/// Content-addressed key of the analysis of `input` with `options`.
+pub fn cache_key(input: &Path, options: &AnalysisOptions) -> Result<String, String> {
+    let mut hasher = Sha256::new();
+    let mut field = |value: &str| {
+        hasher.update((value.len() as u64).to_le_bytes());
+        hasher.update(value.as_bytes());
+    };
+    field(env!("CARGO_PKG_VERSION"));
+    field(&input.to_string_lossy());
+    field(&hash_model(input, &options.walk)?);
+    for library in &options.libraries {
+        field(&hash_model(library, &WalkOptions::default())?);
+    }
+    if options.load_stdlib {
+        if let Some(dir) = super::find_stdlib_dir(options.stdlib_path.as_deref())? {
+            field(&hash_model(&dir, &WalkOptions::default())?);
+        }
+    }
+    let settings = serde_json::to_string(&Settings::of(options))
+        .map_err(|e| format!("Failed to serialize options: {}", e))?;
+    field(&settings);
+    Ok(format!("sha256-{}", to_hex(&hasher.finalize())))
+}
+
+/// Hash of the files analysis loads from `input`, walked as `walk` says,
+/// and of the ignore files that decide which those are.
+fn hash_model(input: &Path, walk: &WalkOptions) -> Result<String, String> {
+    let mut files = if input.is_dir() {
+        super::model_files(input, false, walk)?
+    } else {
+        vec![input.to_path_buf()]
+    };
+    files.sort();
+    let mut ignore_files = ignore_files(input, walk);
+    ignore_files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files.iter().chain(&ignore_files) {
+        let name = file.strip_prefix(input).unwrap_or(file);
+        let content =
+            std::fs::read(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+        hasher.update(name.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+/// The `.systerignore` files, and `.gitignore` files if `walk` respects
+/// them, in `dir`.
+fn ignore_files(dir: &Path, walk: &WalkOptions) -> Vec<PathBuf> {
+    if !dir.is_dir() {
+        return Vec::new();
+    }
+    let names = ignore_file_names(walk.respect_gitignore);
+    WalkDir::new(dir)
+        .follow_links(walk.follow_symlinks)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != ".syster")
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_type().is_file()
+                && names
+                    .iter()
+                    .any(|name| e.file_name().to_str() == Some(*name))
+        })
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// The options a result depends on, in a form that serializes the same on
+/// every build. Where the libraries are and how verbose the run is don't
+/// change the result, so they are left out.
+#[derive(Serialize)]
+struct Settings<'a> {
+    load_stdlib: bool,
+    stdlib_subset: &'static str,
+    include_stdlib_diagnostics: bool,
+    shard: Option<(usize, usize)>,
+    variants: Option<&'a BTreeMap<String, String>>,
+    duplicates: Option<&'static str>,
+    require_docs: Vec<&'a str>,
+    /// Hashes of the dependency indexes.
+    indexes: Vec<String>,
+    profile: &'static str,
+    sysml_version: Option<String>,
+    strict_parse: bool,
+    follow_symlinks: bool,
+    max_depth: Option<usize>,
+    max_file_size: Option<u64>,
+    max_files: Option<usize>,
+    respect_gitignore: bool,
+    skip_nested_projects: bool,
+    min_severity: Option<&'static str>,
+    layers: Option<Vec<(&'a str, Vec<&'a str>)>>,
+    owners: Option<Vec<(&'a str, &'a [String])>>,
+    only_owners: &'a [String],
+    requirements: Option<&'a RequirementRules>,
+    budgets: Option<Budgets>,
+}
+
+impl<'a> Settings<'a> {
+    fn of(options: &'a AnalysisOptions) -> Self {
+        let walk = &options.walk;
+        Self {
+            load_stdlib: options.load_stdlib,
+            stdlib_subset: match options.stdlib_subset {
+                StdlibSubset::Full => "full",
+                StdlibSubset::Auto => "auto",
+            },
+            include_stdlib_diagnostics: options.include_stdlib_diagnostics,
+            shard: options.shard.map(|shard| (shard.index, shard.count)),
+            variants: options.config.map(|config| &config.variants),
+            duplicates: options.duplicates.map(severity_name),
+            require_docs: options.require_docs.iter().map(|k| k.display()).collect(),
+            indexes: options
+                .indexes
+                .iter()
+                .map(|index| to_hex(&Sha256::digest(index.to_bytes())))
+                .collect(),
+            profile: match options.profile {
+                Profile::Strict => "strict",
+                Profile::Standard => "standard",
+                Profile::Permissive => "permissive",
+            },
+            sysml_version: options.sysml_version.map(|v| v.to_string()),
+            strict_parse: options.strict_parse,
+            follow_symlinks: walk.follow_symlinks,
+            max_depth: walk.max_depth,
+            max_file_size: walk.max_file_size,
+            max_files: walk.max_files,
+            respect_gitignore: walk.respect_gitignore,
+            skip_nested_projects: walk.skip_nested_projects,
+            min_severity: options.min_severity.map(severity_name),
+            layers: options.layers.as_ref().map(LayerRules::globs),
+            owners: options.owners.as_ref().map(ModelOwners::rules),
+            only_owners: &options.only_owners,
+            requirements: options.requirements.as_ref(),
+            budgets: options.budgets,
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! C API (feature `capi`).
+//!
+//! Lets desktop tools embed the analysis through the library built as a
+//! cdylib (`make capi`), declared in `include/syster.h` (regenerate with
+//! `make header`):
+//!
+//! ```c
+//! SysterAnalysis *analysis = syster_analyze_path("models/", true);
+//! if (!analysis) {
+//!     fprintf(stderr, "%s\n", syster_last_error());
+//!     return 1;
+//! }
+//! char *json = syster_get_diagnostics_json(analysis);
+//! /* ... */
+//! syster_string_free(json);
+//! syster_analysis_free(analysis);
+//! ```
+//!
+//! Strings are UTF-8 and NUL-terminated. Everything returned is owned by
+//! the caller and released with the matching `_free` function. Failing
+//! calls return `NULL` (or a non-zero status) and leave a message for
+//! [`syster_last_error`] on the calling thread. A panic inside the library
+//! never unwinds into the caller: it fails the call the same way.
+
+use super::{AnalysisOptions, AnalysisResult, ExportFilter, export_json};
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// An analysis, as returned by [`syster_analyze_path`].
+pub struct SysterAnalysis {
+    result: AnalysisResult,
+}
+
+/// Analyze a file or directory, with the standard library if
+/// `load_stdlib`. Returns `NULL` on failure.
+///
+/// # Safety
+///
+/// `path` must be `NULL` or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn syster_analyze_path(
+    path: *const c_char,
+    load_stdlib: bool,
+) -> *mut SysterAnalysis {
+    catch_panic(std::ptr::null_mut(), || {
+        let path = match unsafe { read_str(path, "path") } {
+            Ok(path) => path,
+            Err(e) => return fail(e, std::ptr::null_mut()),
+        };
+        let options = AnalysisOptions {
+            load_stdlib,
+            ..AnalysisOptions::default()
+        };
+        match super::analyze(Path::new(path), &options) {
+            Ok(result) => Box::into_raw(Box::new(SysterAnalysis { result })),
+            Err(e) => fail(e, std::ptr::null_mut()),
+        }
+    })
+}
+
+/// The analysis as the JSON written by `--json`: counts and diagnostics.
+/// Returns `NULL` on failure; free the string with [`syster_string_free`].
+///
+/// # Safety
+///
+/// `analysis` must be `NULL` or returned by [`syster_analyze_path`] and
+/// not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn syster_get_diagnostics_json(
+    analysis: *const SysterAnalysis,
+) -> *mut c_char {
+    catch_panic(std::ptr::null_mut(), || {
+        let Some(analysis) = (unsafe { analysis.as_ref() }) else {
+            return fail("analysis is NULL".to_string(), std::ptr::null_mut());
+        };
+        match export_json(&analysis.result)
+            .and_then(|json| CString::new(json).map_err(|_| "JSON contains a NUL byte".to_string()))
+        {
+            Ok(json) => json.into_raw(),
+            Err(e) => fail(e, std::ptr::null_mut()),
+        }
+    })
+}
+
+/// The number of errors of an analysis, or -1 if `analysis` is `NULL`.
+///
+/// # Safety
+///
+/// As for [`syster_get_diagnostics_json`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn syster_error_count(analysis: *const SysterAnalysis) -> i64 {
+    catch_panic(-1, || match unsafe { analysis.as_ref() } {
+        Some(analysis) => analysis.result.error_count as i64,
+        None => -1,
+    })
+}
+
+/// Export a file or directory to an interchange format (`xmi`, `kpar`,
+/// `jsonld`, `json`, `yaml`). On success stores the bytes in `*out` and their
+/// length in `*out_len` and returns 0; free the bytes with
+/// [`syster_bytes_free`]. Returns -1 on failure.
+///
+/// # Safety
+///
+/// `path` and `format` must be `NULL` or NUL-terminated strings; `out` and
+/// `out_len` must be `NULL` or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn syster_export_model_bytes(
+    path: *const c_char,
+    format: *const c_char,
+    load_stdlib: bool,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    catch_panic(-1, || {
+        if out.is_null() || out_len.is_null() {
+            return fail("out and out_len must not be NULL".to_string(), -1);
+        }
+        let (path, format) = match unsafe { (read_str(path, "path"), read_str(format, "format")) } {
+            (Ok(path), Ok(format)) => (path, format),
+            (Err(e), _) | (_, Err(e)) => return fail(e, -1),
+        };
+        let options = AnalysisOptions {
+            load_stdlib,
+            ..AnalysisOptions::default()
+        };
+        match super::export_model_with_options(
+            Path::new(path),
+            format,
+            &options,
+            false,
+            &ExportFilter::default(),
+        ) {
+            Ok((bytes, _)) => {
+                let bytes = bytes.into_boxed_slice();
+                unsafe {
+                    *out_len = bytes.len();
+                    *out = Box::into_raw(bytes).cast::<u8>();
+                }
+                0
+            }
+            Err(e) => fail(e, -1),
+        }
+    })
+}
+
+/// The message of the last failed call on this thread, or `NULL`. The
+/// string stays valid until the next failing call on the thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn syster_last_error() -> *const c_char {
+    catch_panic(std::ptr::null(), || {
+        LAST_ERROR.with(|error| {
+            error
+                .borrow()
+                .as_ref()
+                .map_or(std::ptr::null(), |e| e.as_ptr())
+        })
+    })
+}
+
+/// Free an analysis.
+///
+/// # Safety
+///
+/// `analysis` must be `NULL` or returned by [`syster_analyze_path`] and
+/// not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn syster_analysis_free(analysis: *mut SysterAnalysis) {
+    catch_panic((), || {
+        if !analysis.is_null() {
+            drop(unsafe { Box::from_raw(analysis) });
+        }
+    })
+}
+
+/// Free a string returned by this library.
+///
+/// # Safety
+///
+/// `s` must be `NULL` or a string returned by this library and not yet
+/// freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn syster_string_free(s: *mut c_char) {
+    catch_panic((), || {
+        if !s.is_null() {
+            drop(unsafe { CString::from_raw(s) });
+        }
+    })
+}
+
+/// Free bytes returned by [`syster_export_model_bytes`].
+///
+/// # Safety
+///
+/// `bytes` and `len` must be `NULL` and 0, or as returned by
+/// [`syster_export_model_bytes`] and not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn syster_bytes_free(bytes: *mut u8, len: usize) {
+    catch_panic((), || {
+        if !bytes.is_null() {
+            drop(unsafe { Box::from_raw(std::ptr::slice_from_raw_parts_mut(bytes, len)) });
+        }
+    })
+}
+
+/// Run the body of a C function, failing the call with `on_panic` if it
+/// panics, as unwinding into the caller is undefined behaviour. Every
+/// function here runs through it; it is public for embedders adding their
+/// own `extern "C"` functions on top of the library.
+pub fn catch_panic<T>(on_panic: T, body: impl FnOnce() -> T) -> T {
+    match std::panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(value) => value,
+        Err(payload) => fail(format!("panic: {}", panic_message(&*payload)), on_panic),
+    }
+}
+
+/// The message a panic was raised with.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".to_string())
+}
+
+/// Record `error` for [`syster_last_error`] and return `value`.
+fn fail<T>(error: String, value: T) -> T {
+    let error = CString::new(error.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(error));
+    value
+}
+
+/// Borrow a C string argument.
+///
+/// # Safety
+///
+/// `s` must be `NULL` or a NUL-terminated string.
+unsafe fn read_str<'a>(s: *const c_char, name: &str) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("{} is NULL", name));
+    }
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| format!("{} is not UTF-8", name))
+}
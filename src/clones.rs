@@ -0,0 +1,368 @@
+//! Structurally duplicated definitions (clone detection).
+//!
+//! Copying a definition into another package and renaming it leaves two
+//! definitions of the same shape under different names, which the
+//! duplicate-definition check cannot see. Each definition of the model gets
+//! a structural hash instead: its kind, its relationships and the hashes of
+//! its members, each made of the member's kind, direction, multiplicity,
+//! relationships and own members, but no names. Definitions with the same
+//! hash are clones; definitions of the same kind sharing most of their
+//! features are near clones, typically a copy that was edited since. Both
+//! are candidates for consolidating into one definition.
+
+use super::AnalysisOptions;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use syster::hir::{HirSymbol, SymbolIndex, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// What [`find_clones`] compares.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CloneOptions {
+    /// Only compare definitions with at least this many members; smaller
+    /// ones (empty definitions above all) are alike by nature.
+    pub min_members: usize,
+    /// Report definitions sharing at least this fraction (0 to 1) of their
+    /// features as near clones.
+    pub similarity: f64,
+}
+
+impl Default for CloneOptions {
+    fn default() -> Self {
+        Self {
+            min_members: 2,
+            similarity: 0.8,
+        }
+    }
+}
+
+/// A definition taking part in a clone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CloneSite {
+    /// Qualified name of the definition.
+    pub qualified_name: String,
+    /// File declaring it.
+    pub file: String,
+    /// Line of the declaration (1-indexed).
+    pub line: u32,
+}
+
+/// Definitions of identical structure.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CloneGroup {
+    /// Structural hash shared by the definitions (hexadecimal).
+    pub fingerprint: String,
+    /// Kind of the definitions, e.g. `Part def`.
+    pub kind: String,
+    /// Number of members of each definition.
+    pub members: usize,
+    /// The definitions, by qualified name.
+    pub definitions: Vec<CloneSite>,
+}
+
+/// Two definitions sharing most, but not all, of their features.
+///
+/// Definitions of identical structure are compared once, through the
+/// first of their [`CloneGroup`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NearClone {
+    /// Kind of the definitions.
+    pub kind: String,
+    /// Shared features over all features of the two, between 0 and 1.
+    pub similarity: f64,
+    /// The definition first by qualified name.
+    pub first: CloneSite,
+    /// The other definition.
+    pub second: CloneSite,
+}
+
+/// Clones and near clones among the definitions of a model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CloneReport {
+    /// Definitions compared (those with enough members).
+    pub definitions: usize,
+    /// Groups of identical definitions, largest first.
+    pub groups: Vec<CloneGroup>,
+    /// Near clones, most similar first.
+    pub near: Vec<NearClone>,
+}
+
+impl CloneReport {
+    /// Definitions that could go, keeping one per group of clones.
+    pub fn redundant_count(&self) -> usize {
+        self.groups.iter().map(|g| g.definitions.len() - 1).sum()
+    }
+}
+
+/// A definition with its structural features.
+struct Shape<'a> {
+    symbol: &'a HirSymbol,
+    file: String,
+    members: usize,
+    /// Hashes of the relationships and members, sorted.
+    features: Vec<u64>,
+    fingerprint: u64,
+}
+
+/// Find the clones among the definitions of `input`.
+///
+/// The standard library is loaded so references resolve to the same
+/// elements wherever they are written, but its definitions are not
+/// compared.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `options` - Thresholds
+/// * `analysis` - Verbosity and standard library to load
+pub fn find_clones(
+    input: &Path,
+    options: &CloneOptions,
+    analysis: &AnalysisOptions,
+) -> Result<CloneReport, String> {
+    let verbose = analysis.verbose;
+    if !(0.0..=1.0).contains(&options.similarity) {
+        return Err(format!(
+            "Invalid similarity {}: expected a fraction between 0 and 1",
+            options.similarity
+        ));
+    }
+    let mut host = AnalysisHost::new();
+    if analysis.load_stdlib {
+        super::load_stdlib_files(&mut host, analysis.stdlib_path.as_deref(), verbose)?;
+    }
+    let library: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    super::load_input(&mut host, input, verbose)?;
+
+    let analysis = host.analysis();
+    let index = analysis.symbol_index();
+    let mut files = HashMap::new();
+    let mut members: HashMap<&str, Vec<&HirSymbol>> = HashMap::new();
+    for symbol in index.all_symbols() {
+        let Some(file) = analysis
+            .get_file_path(symbol.file)
+            .filter(|path| !library.contains(*path))
+        else {
+            continue;
+        };
+        files.insert(symbol.qualified_name.as_ref(), file.to_string());
+        if matches!(symbol.kind, SymbolKind::Import | SymbolKind::Comment) {
+            continue;
+        }
+        if let Some((owner, _)) = symbol.qualified_name.rsplit_once("::") {
+            members.entry(owner).or_default().push(symbol);
+        }
+    }
+
+    let mut hashes = HashMap::new();
+    let mut shapes: Vec<Shape> = index
+        .all_symbols()
+        .filter(|s| s.kind.is_definition() && s.kind != SymbolKind::Package)
+        .filter_map(|symbol| {
+            let file = files.get(symbol.qualified_name.as_ref())?.clone();
+            let own = members.get(symbol.qualified_name.as_ref())?;
+            if own.len() < options.min_members {
+                return None;
+            }
+            let mut features = relationships(symbol, index);
+            features.extend(
+                own.iter()
+                    .map(|member| member_hash(member, index, &members, &mut hashes)),
+            );
+            features.sort_unstable();
+            let fingerprint = hash_of(symbol.kind.display(), &features);
+            Some(Shape {
+                symbol,
+                file,
+                members: own.len(),
+                features,
+                fingerprint,
+            })
+        })
+        .collect();
+    shapes.sort_by(|a, b| a.symbol.qualified_name.cmp(&b.symbol.qualified_name));
+
+    let mut by_fingerprint: BTreeMap<u64, Vec<&Shape>> = BTreeMap::new();
+    for shape in &shapes {
+        by_fingerprint
+            .entry(shape.fingerprint)
+            .or_default()
+            .push(shape);
+    }
+    let mut groups: Vec<CloneGroup> = by_fingerprint
+        .iter()
+        .filter(|(_, shapes)| shapes.len() > 1)
+        .map(|(fingerprint, shapes)| CloneGroup {
+            fingerprint: format!("{:016x}", fingerprint),
+            kind: shapes[0].symbol.kind.display().to_string(),
+            members: shapes[0].members,
+            definitions: shapes.iter().copied().map(site).collect(),
+        })
+        .collect();
+    groups.sort_by(|a, b| {
+        (b.definitions.len(), b.members)
+            .cmp(&(a.definitions.len(), a.members))
+            .then_with(|| {
+                a.definitions[0]
+                    .qualified_name
+                    .cmp(&b.definitions[0].qualified_name)
+            })
+    });
+
+    // Compare one definition of each structure with the others of its kind
+    let representatives: Vec<&Shape> = by_fingerprint.values().map(|shapes| shapes[0]).collect();
+    let mut near = Vec::new();
+    for (i, a) in representatives.iter().enumerate() {
+        for b in &representatives[i + 1..] {
+            if a.symbol.kind != b.symbol.kind {
+                continue;
+            }
+            let (shorter, longer) = if a.features.len() <= b.features.len() {
+                (a.features.len(), b.features.len())
+            } else {
+                (b.features.len(), a.features.len())
+            };
+            // Sharing all of the shorter's features is the best case
+            if (shorter as f64) < options.similarity * longer as f64 {
+                continue;
+            }
+            let similarity = shared_fraction(&a.features, &b.features);
+            if similarity >= options.similarity {
+                let (first, second) = if a.symbol.qualified_name <= b.symbol.qualified_name {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                near.push(NearClone {
+                    kind: a.symbol.kind.display().to_string(),
+                    similarity,
+                    first: site(first),
+                    second: site(second),
+                });
+            }
+        }
+    }
+    near.sort_by(|a, b| {
+        b.similarity
+            .total_cmp(&a.similarity)
+            .then_with(|| a.first.qualified_name.cmp(&b.first.qualified_name))
+            .then_with(|| a.second.qualified_name.cmp(&b.second.qualified_name))
+    });
+
+    let report = CloneReport {
+        definitions: shapes.len(),
+        groups,
+        near,
+    };
+    if verbose {
+        tracing::info!(
+            "Compared {} definitions: {} clone groups, {} near clones",
+            report.definitions,
+            report.groups.len(),
+            report.near.len()
+        );
+    }
+    Ok(report)
+}
+
+fn site(shape: &Shape) -> CloneSite {
+    CloneSite {
+        qualified_name: shape.symbol.qualified_name.to_string(),
+        file: shape.file.clone(),
+        line: shape.symbol.start_line + 1,
+    }
+}
+
+/// Structural hash of a member: everything but its name, with the hashes
+/// of its own members.
+fn member_hash(
+    symbol: &HirSymbol,
+    index: &SymbolIndex,
+    members: &HashMap<&str, Vec<&HirSymbol>>,
+    hashes: &mut HashMap<String, u64>,
+) -> u64 {
+    if let Some(hash) = hashes.get(symbol.qualified_name.as_ref()) {
+        return *hash;
+    }
+    let mut features = relationships(symbol, index);
+    for member in members
+        .get(symbol.qualified_name.as_ref())
+        .into_iter()
+        .flatten()
+    {
+        features.push(member_hash(member, index, members, hashes));
+    }
+    features.sort_unstable();
+    let header = format!(
+        "{}|{:?}|{:?}|{}",
+        symbol.kind.display(),
+        symbol.direction,
+        symbol.multiplicity,
+        symbol.is_abstract
+    );
+    let hash = hash_of(&header, &features);
+    hashes.insert(symbol.qualified_name.to_string(), hash);
+    hash
+}
+
+/// Hashes of the relationships of `symbol`, each with its resolved target.
+fn relationships(symbol: &HirSymbol, index: &SymbolIndex) -> Vec<u64> {
+    let resolver = index.resolver_for_scope(&symbol.qualified_name);
+    symbol
+        .relationships
+        .iter()
+        .map(|rel| {
+            let target = match &rel.resolved_target {
+                Some(target) => target.to_string(),
+                None => resolver
+                    .resolve(&rel.target)
+                    .symbol()
+                    .map_or_else(|| rel.target.to_string(), |s| s.qualified_name.to_string()),
+            };
+            fnv1a(format!("{:?} {}", rel.kind, target).as_bytes())
+        })
+        .collect()
+}
+
+/// Hash of a header and sorted feature hashes.
+fn hash_of(header: &str, features: &[u64]) -> u64 {
+    let mut bytes = header.as_bytes().to_vec();
+    for feature in features {
+        bytes.extend_from_slice(&feature.to_le_bytes());
+    }
+    fnv1a(&bytes)
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` gives the same fingerprints
+/// with every Rust release.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Shared over all features of two sorted multisets of features.
+fn shared_fraction(a: &[u64], b: &[u64]) -> f64 {
+    let (mut i, mut j, mut shared) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                shared += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    let all = a.len() + b.len() - shared;
+    if all == 0 {
+        1.0
+    } else {
+        shared as f64 / all as f64
+    }
+}
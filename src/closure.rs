@@ -0,0 +1,202 @@
+//! Dependency closure of a package selection.
+//!
+//! Starting from the selected packages, every element they contain is
+//! required, and so is every element those reference through imports,
+//! typing, specialization and other relationships, transitively. A
+//! referenced element brings its own members along, and the namespaces
+//! enclosing any required element are kept so qualified names stay intact.
+
+use super::AnalysisOptions;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use syster::hir::{HirSymbol, SymbolIndex, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// Everything a package selection transitively requires.
+#[derive(Debug, Serialize)]
+pub struct ClosureReport {
+    /// The selected packages.
+    pub selection: Vec<String>,
+    /// Qualified names of the required packages, including the selection.
+    pub packages: Vec<String>,
+    /// Every required element, in qualified-name order.
+    pub elements: Vec<ClosureElement>,
+}
+
+/// A single element of a dependency closure.
+#[derive(Debug, Serialize)]
+pub struct ClosureElement {
+    /// Qualified name of the element.
+    pub qualified_name: String,
+    /// Kind of the element, e.g. `Part def`.
+    pub kind: String,
+    /// File declaring the element.
+    pub file: Option<String>,
+    /// Whether the element comes from the standard library.
+    pub library: bool,
+}
+
+impl ClosureReport {
+    /// Number of required elements from the standard library.
+    pub fn library_count(&self) -> usize {
+        self.elements.iter().filter(|e| e.library).count()
+    }
+}
+
+/// Analyze `input` and compute the dependency closure of `packages`.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `packages` - Qualified names of the selected packages
+/// * `options` - Verbosity and standard library to load
+pub fn dependency_closure(
+    input: &Path,
+    packages: &[String],
+    options: &AnalysisOptions,
+) -> Result<ClosureReport, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    super::load_input(&mut host, input, options.verbose)?;
+
+    let analysis = host.analysis();
+    let index = analysis.symbol_index();
+    let required = package_closure(index, packages)?;
+
+    let mut elements = Vec::new();
+    let mut required_packages = Vec::new();
+    for name in &required {
+        let Some(symbol) = index.lookup_qualified(name) else {
+            continue;
+        };
+        let file = analysis.get_file_path(symbol.file).map(str::to_string);
+        if symbol.kind == SymbolKind::Package {
+            required_packages.push(name.clone());
+        }
+        elements.push(ClosureElement {
+            qualified_name: name.clone(),
+            kind: symbol.kind.display().to_string(),
+            library: file
+                .as_deref()
+                .is_some_and(|path| path.contains("sysml.library")),
+            file,
+        });
+    }
+
+    if options.verbose {
+        tracing::info!(
+            "Closure of {}: {} elements in {} packages",
+            packages.join(", "),
+            elements.len(),
+            required_packages.len()
+        );
+    }
+
+    Ok(ClosureReport {
+        selection: packages.to_vec(),
+        packages: required_packages,
+        elements,
+    })
+}
+
+/// Qualified names of every symbol required by `packages`.
+///
+/// Fails if a selected package is not in the index.
+pub fn package_closure(
+    index: &SymbolIndex,
+    packages: &[String],
+) -> Result<BTreeSet<String>, String> {
+    let by_name: BTreeMap<&str, &HirSymbol> = index
+        .all_symbols()
+        .map(|s| (s.qualified_name.as_ref(), s))
+        .collect();
+
+    let mut queue = Vec::new();
+    for package in packages {
+        if !by_name.contains_key(package.as_str()) {
+            return Err(format!("Unknown package: {}", package));
+        }
+        queue.push(package.clone());
+    }
+
+    let mut required = BTreeSet::new();
+    while let Some(name) = queue.pop() {
+        for symbol in subtree(&by_name, &name) {
+            if !required.insert(symbol.qualified_name.to_string()) {
+                continue;
+            }
+            queue.extend(
+                dependencies(index, symbol)
+                    .into_iter()
+                    .filter(|target| !required.contains(target)),
+            );
+        }
+    }
+
+    let containers: Vec<String> = required
+        .iter()
+        .flat_map(|name| ancestors(name))
+        .filter(|name| by_name.contains_key(name.as_str()))
+        .collect();
+    required.extend(containers);
+
+    Ok(required)
+}
+
+/// The symbol named `name` and everything nested inside it.
+fn subtree<'a>(by_name: &BTreeMap<&'a str, &'a HirSymbol>, name: &str) -> Vec<&'a HirSymbol> {
+    let prefix = format!("{}::", name);
+    by_name
+        .get(name)
+        .into_iter()
+        .copied()
+        .chain(
+            by_name
+                .range(prefix.as_str()..)
+                .take_while(|(member, _)| member.starts_with(&prefix))
+                .map(|(_, symbol)| *symbol),
+        )
+        .collect()
+}
+
+/// Qualified names of the symbols `symbol` references.
+///
+/// Uses the target resolved during analysis when there is one and resolves
+/// from the symbol's scope otherwise; unresolvable references are skipped.
+pub(crate) fn dependencies(index: &SymbolIndex, symbol: &HirSymbol) -> Vec<String> {
+    let resolver = index.resolver_for_scope(&symbol.qualified_name);
+
+    let type_refs = symbol
+        .type_refs
+        .iter()
+        .flat_map(|r| r.as_refs())
+        .map(|r| (&r.target, &r.resolved_target));
+    let relationships = symbol
+        .relationships
+        .iter()
+        .map(|r| (&r.target, &r.resolved_target));
+
+    type_refs
+        .chain(relationships)
+        .filter_map(|(target, resolved)| match resolved {
+            Some(resolved) => Some(resolved.to_string()),
+            None => resolver
+                .resolve(target)
+                .symbol()
+                .map(|s| s.qualified_name.to_string()),
+        })
+        .collect()
+}
+
+/// Enclosing namespaces of a qualified name, innermost first.
+fn ancestors(name: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut current = name;
+    while let Some((parent, _)) = current.rsplit_once("::") {
+        result.push(parent.to_string());
+        current = parent;
+    }
+    result
+}
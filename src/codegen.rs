@@ -0,0 +1,373 @@
+//! Template-based code generation.
+//!
+//! The symbols of the loaded model are flattened into a [`CodegenModel`]
+//! and rendered through [Tera](https://keats.github.io/tera/) templates, so
+//! headers, tables or configuration files can be generated from the
+//! part/port/attribute structure without writing Rust.
+//!
+//! A template sees two variables:
+//! * `elements` - every [`CodegenElement`] in qualified-name order
+//! * `by_name` - the same elements keyed by qualified name, for following
+//!   `members`, `types` and `supertypes`
+
+use super::AnalysisOptions;
+use super::csv_field;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use syster::hir::{HirSymbol, RefKind, RelationshipKind, SymbolIndex, SymbolKind};
+use syster::ide::AnalysisHost;
+use syster::parser::Direction;
+
+/// Output format of the built-in documents (ICD, glossary).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    /// Markdown tables.
+    Markdown,
+    /// A standalone HTML page.
+    Html,
+    /// One CSV row per entry.
+    Csv,
+}
+
+/// Template context: the user model, without the standard library.
+#[derive(Debug, Serialize)]
+pub struct CodegenModel {
+    /// Every element, in qualified-name order.
+    pub elements: Vec<CodegenElement>,
+    /// The same elements keyed by qualified name.
+    pub by_name: BTreeMap<String, CodegenElement>,
+}
+
+/// A single model element as seen by templates.
+#[derive(Debug, Clone, Serialize)]
+pub struct CodegenElement {
+    /// Simple name.
+    pub name: String,
+    /// Fully qualified name.
+    pub qualified_name: String,
+    /// Kind of the element, e.g. `PartDefinition` or `PortUsage`.
+    pub kind: String,
+    /// Human-readable kind, e.g. `Part def` or `Port`.
+    pub kind_label: String,
+    /// Whether the element is a definition (SysML `def` or KerML type).
+    pub is_definition: bool,
+    /// Qualified name of the owning element, if any.
+    pub owner: Option<String>,
+    /// File declaring the element.
+    pub file: String,
+    /// Declaration line (1-indexed).
+    pub line: u32,
+    /// Documentation comment.
+    pub doc: Option<String>,
+    /// Whether the element is abstract.
+    pub is_abstract: bool,
+    /// `in`, `out` or `inout` for directed features.
+    pub direction: Option<String>,
+    /// Multiplicity, e.g. `1` or `0..*`.
+    pub multiplicity: Option<String>,
+    /// Qualified names of the types of a usage (`:`).
+    pub types: Vec<String>,
+    /// Qualified names of specialized or subsetted elements.
+    pub supertypes: Vec<String>,
+    /// Qualified names of directly owned elements, in declaration order.
+    pub members: Vec<String>,
+    /// Features a connection, interface or flow links, by qualified name
+    /// where resolved (otherwise as written, e.g. `a.p`).
+    pub ends: Vec<String>,
+    /// Names of the metadata applied to the element.
+    pub metadata: Vec<String>,
+}
+
+impl CodegenModel {
+    /// Analyze `input` and collect its elements.
+    ///
+    /// # Arguments
+    /// * `input` - Source file or directory
+    /// * `options` - Verbosity and standard library to load
+    pub fn load(input: &Path, options: &AnalysisOptions) -> Result<Self, String> {
+        let mut host = AnalysisHost::new();
+        if options.load_stdlib {
+            super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+        }
+        super::load_input(&mut host, input, options.verbose)?;
+
+        let analysis = host.analysis();
+        let index = analysis.symbol_index();
+        let mut symbols: Vec<(&HirSymbol, &str)> = index
+            .all_symbols()
+            .filter(|sym| sym.kind != SymbolKind::Import)
+            .filter_map(|sym| {
+                analysis
+                    .get_file_path(sym.file)
+                    .filter(|path| !path.contains("sysml.library"))
+                    .map(|path| (sym, path))
+            })
+            .collect();
+        symbols.sort_by(|(a, a_path), (b, b_path)| {
+            (a_path, a.start_line, a.start_col).cmp(&(b_path, b.start_line, b.start_col))
+        });
+
+        let mut by_name: BTreeMap<String, CodegenElement> = symbols
+            .iter()
+            .map(|(sym, path)| {
+                let element = element(index, sym, path);
+                (element.qualified_name.clone(), element)
+            })
+            .collect();
+
+        // Members in declaration order (symbols are sorted by position)
+        for (sym, _) in &symbols {
+            let owner = sym.qualified_name.rsplit_once("::").map(|(o, _)| o);
+            if let Some(owner) = owner.and_then(|o| by_name.get_mut(o)) {
+                owner.members.push(sym.qualified_name.to_string());
+            }
+        }
+        for element in by_name.values_mut() {
+            element.owner = element
+                .qualified_name
+                .rsplit_once("::")
+                .map(|(owner, _)| owner.to_string());
+        }
+
+        Ok(Self {
+            elements: by_name.values().cloned().collect(),
+            by_name,
+        })
+    }
+
+    /// Render a template given as source text.
+    pub fn render(&self, name: &str, template: &str) -> Result<String, String> {
+        render_template(self, name, template)
+    }
+}
+
+/// Render `template` with the fields of `context` as variables.
+///
+/// Templates named `*.html` are HTML-escaped. Besides Tera's built-in
+/// filters, `snake_case` and `csv` (quote a CSV field) are available.
+pub(crate) fn render_template(
+    context: &impl Serialize,
+    name: &str,
+    template: &str,
+) -> Result<String, String> {
+    let mut tera = tera::Tera::default();
+    tera.register_filter("snake_case", snake_case_filter);
+    tera.register_filter("csv", csv_filter);
+    tera.add_raw_template(name, template)
+        .map_err(|e| template_error(name, &e))?;
+    let context = tera::Context::from_serialize(context)
+        .map_err(|e| format!("Failed to build template context: {}", e))?;
+    tera.render(name, &context)
+        .map_err(|e| template_error(name, &e))
+}
+
+/// Build the template view of a symbol (without owner and members).
+fn element(index: &SymbolIndex, symbol: &HirSymbol, path: &str) -> CodegenElement {
+    let resolver = index.resolver_for_scope(&symbol.qualified_name);
+    let targets = |kinds: &[RelationshipKind]| -> Vec<String> {
+        symbol
+            .relationships
+            .iter()
+            .filter(|rel| kinds.contains(&rel.kind))
+            .map(|rel| match &rel.resolved_target {
+                Some(target) => target.to_string(),
+                None => resolver
+                    .resolve(&rel.target)
+                    .symbol()
+                    .map_or_else(|| rel.target.to_string(), |s| s.qualified_name.to_string()),
+            })
+            .collect()
+    };
+
+    let ends = match symbol.kind {
+        SymbolKind::ConnectionUsage
+        | SymbolKind::InterfaceUsage
+        | SymbolKind::FlowConnectionUsage => symbol
+            .type_refs
+            .iter()
+            .filter_map(|r| {
+                let parts = r.as_refs();
+                let last = parts.last()?;
+                (parts[0].kind == RefKind::Other).then(|| match &last.resolved_target {
+                    Some(target) => target.to_string(),
+                    None => parts
+                        .iter()
+                        .map(|p| p.target.as_ref())
+                        .collect::<Vec<_>>()
+                        .join("."),
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    CodegenElement {
+        name: symbol.name.to_string(),
+        qualified_name: symbol.qualified_name.to_string(),
+        kind: format!("{:?}", symbol.kind),
+        kind_label: symbol.kind.display().to_string(),
+        is_definition: is_definition(symbol.kind),
+        owner: None,
+        file: path.to_string(),
+        line: symbol.start_line + 1,
+        doc: symbol.doc.as_ref().map(|d| d.trim().to_string()),
+        is_abstract: symbol.is_abstract,
+        direction: symbol.direction.map(|d| {
+            match d {
+                Direction::In => "in",
+                Direction::Out => "out",
+                Direction::InOut => "inout",
+            }
+            .to_string()
+        }),
+        multiplicity: symbol.multiplicity.map(|m| {
+            let bound = |b: Option<u64>| b.map_or("*".to_string(), |n| n.to_string());
+            if m.lower == m.upper {
+                bound(m.upper)
+            } else {
+                format!("{}..{}", m.lower.unwrap_or(0), bound(m.upper))
+            }
+        }),
+        types: targets(&[RelationshipKind::TypedBy]),
+        supertypes: targets(&[RelationshipKind::Specializes, RelationshipKind::Subsets]),
+        members: Vec::new(),
+        ends,
+        metadata: symbol
+            .metadata_annotations
+            .iter()
+            .map(|m| m.to_string())
+            .collect(),
+    }
+}
+
+/// Whether `kind` declares a type rather than a usage, package or relationship.
+fn is_definition(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::PartDefinition
+            | SymbolKind::ItemDefinition
+            | SymbolKind::ActionDefinition
+            | SymbolKind::PortDefinition
+            | SymbolKind::AttributeDefinition
+            | SymbolKind::ConnectionDefinition
+            | SymbolKind::InterfaceDefinition
+            | SymbolKind::AllocationDefinition
+            | SymbolKind::RequirementDefinition
+            | SymbolKind::ConstraintDefinition
+            | SymbolKind::StateDefinition
+            | SymbolKind::CalculationDefinition
+            | SymbolKind::UseCaseDefinition
+            | SymbolKind::AnalysisCaseDefinition
+            | SymbolKind::ConcernDefinition
+            | SymbolKind::ViewDefinition
+            | SymbolKind::ViewpointDefinition
+            | SymbolKind::RenderingDefinition
+            | SymbolKind::EnumerationDefinition
+            | SymbolKind::MetadataDefinition
+            | SymbolKind::Interaction
+            | SymbolKind::DataType
+            | SymbolKind::Class
+            | SymbolKind::Structure
+            | SymbolKind::Behavior
+            | SymbolKind::Function
+            | SymbolKind::Association
+    )
+}
+
+/// Render every template into `out_dir`.
+///
+/// Each template is written to a file named after it, without a trailing
+/// `.tera` (e.g. `structs.h.tera` becomes `structs.h`).
+///
+/// # Returns
+/// The paths of the generated files.
+pub fn generate(
+    input: &Path,
+    templates: &[PathBuf],
+    out_dir: &Path,
+    options: &AnalysisOptions,
+) -> Result<Vec<PathBuf>, String> {
+    let model = CodegenModel::load(input, options)?;
+    std::fs::create_dir_all(out_dir)
+        .map_err(|e| format!("Failed to create {}: {}", out_dir.display(), e))?;
+
+    let mut generated = Vec::new();
+    for template in templates {
+        let source = std::fs::read_to_string(template)
+            .map_err(|e| format!("Failed to read {}: {}", template.display(), e))?;
+        let file_name = template
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| format!("Invalid template path: {}", template.display()))?;
+        let output_name = file_name
+            .strip_suffix(".tera")
+            .unwrap_or(&file_name)
+            .to_string();
+
+        let rendered = model.render(&file_name, &source)?;
+        let path = out_dir.join(output_name);
+        std::fs::write(&path, rendered)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        if options.verbose {
+            tracing::info!("Rendered {} -> {}", template.display(), path.display());
+        }
+        generated.push(path);
+    }
+    Ok(generated)
+}
+
+/// Tera errors keep the useful part (line, unknown variable) in their
+/// source chain, so include all of it.
+fn template_error(name: &str, error: &tera::Error) -> String {
+    let mut message = format!("Template {}: {}", name, error);
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
+}
+
+/// `{{ name | snake_case }}`: `VehicleMass` becomes `vehicle_mass`.
+fn snake_case_filter(
+    value: &tera::Value,
+    _args: &HashMap<String, tera::Value>,
+) -> tera::Result<tera::Value> {
+    let text = tera::try_get_value!("snake_case", "value", String, value);
+    Ok(tera::Value::String(snake_case(&text)))
+}
+
+/// `{{ text | csv }}`: quote a CSV field if it contains a separator, quote
+/// or line break.
+fn csv_filter(
+    value: &tera::Value,
+    _args: &HashMap<String, tera::Value>,
+) -> tera::Result<tera::Value> {
+    let text = match value {
+        tera::Value::String(s) => s.clone(),
+        tera::Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    Ok(tera::Value::String(csv_field(&text)))
+}
+
+/// Convert `CamelCase`, `kebab-case` or spaced names to `snake_case`.
+pub(crate) fn snake_case(text: &str) -> String {
+    let mut result = String::new();
+    let mut previous: Option<char> = None;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            let boundary = previous
+                .is_some_and(|p| c.is_uppercase() && (p.is_lowercase() || p.is_ascii_digit()));
+            if boundary {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else if !result.is_empty() && !result.ends_with('_') {
+            result.push('_');
+        }
+        previous = Some(c);
+    }
+    result.trim_end_matches('_').to_string()
+}
@@ -0,0 +1,111 @@
+//! Compressed exports (`--compress`).
+//!
+//! Self-contained exports that embed the standard library run to hundreds
+//! of megabytes. XMI, JSON-LD and the other text formats are compressed as
+//! a whole with gzip or zstd; a KPAR, already a ZIP archive, gets its
+//! entries compressed with zstd instead of deflate. Imports recognize gzip
+//! and zstd files by their magic bytes and decompress them first, so
+//! `model.xmi.gz` reads like `model.xmi`.
+
+use super::kpar::{read_entries, write_entries_with};
+use flate2::Compression as GzipLevel;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use zip::{CompressionMethod, ZipArchive};
+
+/// First bytes of a gzip stream.
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+
+/// First bytes of a zstd frame.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// How an export is compressed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Written as is.
+    #[default]
+    None,
+    /// The whole file gzip-compressed (`.gz`).
+    Gzip,
+    /// The whole file zstd-compressed (`.zst`), or for a KPAR, its entries.
+    Zstd,
+}
+
+/// Compress exported bytes of the given format.
+pub fn compress_export(
+    bytes: Vec<u8>,
+    format: &str,
+    compression: Compression,
+) -> Result<Vec<u8>, String> {
+    let kpar = format.eq_ignore_ascii_case("kpar");
+    match compression {
+        Compression::None => Ok(bytes),
+        Compression::Gzip if kpar => {
+            Err("KPAR entries can only be compressed with zstd (--compress zstd)".to_string())
+        }
+        Compression::Zstd if kpar => {
+            write_entries_with(&read_entries(&bytes)?, CompressionMethod::Zstd)
+        }
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), GzipLevel::default());
+            encoder
+                .write_all(&bytes)
+                .and_then(|_| encoder.finish())
+                .map_err(|e| format!("Failed to gzip export: {}", e))
+        }
+        Compression::Zstd => zstd::encode_all(bytes.as_slice(), 0)
+            .map_err(|e| format!("Failed to zstd-compress export: {}", e)),
+    }
+}
+
+/// Decompress gzip or zstd bytes; anything else is returned unchanged.
+pub fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    if bytes.starts_with(GZIP_MAGIC) {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut decompressed)
+            .map_err(|e| format!("Failed to decompress gzip: {}", e))?;
+        Ok(decompressed)
+    } else if bytes.starts_with(ZSTD_MAGIC) {
+        zstd::decode_all(bytes.as_slice()).map_err(|e| format!("Failed to decompress zstd: {}", e))
+    } else {
+        Ok(bytes)
+    }
+}
+
+/// The path whose extension names the format of a possibly compressed
+/// file: `model.xmi.gz` is read as `model.xmi`.
+pub(crate) fn uncompressed_path(path: &Path) -> PathBuf {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("gz") || ext.eq_ignore_ascii_case("zst") => {
+            path.with_extension("")
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+/// A KPAR with deflate entries, rewritten from zstd ones if it has any, for
+/// readers that only know deflate.
+pub(crate) fn deflate_kpar(bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    if has_zstd_entries(&bytes)? {
+        write_entries_with(&read_entries(&bytes)?, CompressionMethod::Deflated)
+    } else {
+        Ok(bytes)
+    }
+}
+
+fn has_zstd_entries(bytes: &[u8]) -> Result<bool, String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| format!("Not a valid KPAR archive: {}", e))?;
+    for i in 0..archive.len() {
+        let entry = archive
+            .by_index_raw(i)
+            .map_err(|e| format!("Failed to read entry {}: {}", i, e))?;
+        if entry.compression() == CompressionMethod::Zstd {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,241 @@
+//! Element ID conflicts on import (`--on-id-conflict`).
+//!
+//! Deliverables from different suppliers are often generated by tools that
+//! number elements the same way, so merging them into one workspace gives
+//! two elements one ID, and exports of the merge are no longer valid. An
+//! imported element conflicts when a workspace element with another
+//! qualified name already has its ID; importing the same element twice is
+//! not a conflict. Conflicts fail the import unless a policy resolves them,
+//! and every resolution applied is reported.
+
+use super::ids::DEFAULT_ID_NAMESPACE;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use syster::ide::AnalysisHost;
+use syster::interchange::{Element, ElementId, Model};
+use uuid::Uuid;
+
+/// What to do with an imported element whose ID is already taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdConflictPolicy {
+    /// Fail the import, listing the conflicts.
+    #[default]
+    Error,
+    /// Give the imported element a new ID.
+    Rename,
+    /// Leave the imported element out, with everything it contains.
+    Skip,
+    /// Keep the imported element's ID and give the workspace element a new
+    /// one.
+    Overwrite,
+}
+
+/// A conflict and how it was resolved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IdResolution {
+    /// The ID both elements had.
+    pub id: String,
+    /// Qualified name of the imported element.
+    pub imported: String,
+    /// Qualified name of the workspace element.
+    pub existing: String,
+    /// How it was resolved; never [`IdConflictPolicy::Error`].
+    pub action: IdConflictPolicy,
+    /// The new ID of the renamed element: the imported one for
+    /// [`IdConflictPolicy::Rename`], the workspace one for
+    /// [`IdConflictPolicy::Overwrite`].
+    pub new_id: Option<String>,
+}
+
+impl fmt::Display for IdResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let new_id = self.new_id.as_deref().unwrap_or_default();
+        match self.action {
+            IdConflictPolicy::Rename => write!(
+                f,
+                "Renamed {} from ID {} to {}; {} has the ID",
+                self.imported, self.id, new_id, self.existing
+            ),
+            IdConflictPolicy::Skip => write!(
+                f,
+                "Skipped {} and its members; {} has ID {}",
+                self.imported, self.existing, self.id
+            ),
+            IdConflictPolicy::Overwrite => write!(
+                f,
+                "Gave ID {} to {}; {} now has ID {}",
+                self.id, self.imported, self.existing, new_id
+            ),
+            IdConflictPolicy::Error => write!(
+                f,
+                "{} has ID {}, already taken by {}",
+                self.imported, self.id, self.existing
+            ),
+        }
+    }
+}
+
+/// Find the elements of `model` whose IDs elements of the workspace already
+/// have, and resolve the conflicts as `policy` says, changing `model` (and
+/// for [`IdConflictPolicy::Overwrite`], the workspace) before it is added.
+///
+/// # Returns
+/// The resolutions applied, in the model's element order.
+pub(crate) fn resolve_id_conflicts(
+    host: &mut AnalysisHost,
+    model: &mut Model,
+    policy: IdConflictPolicy,
+) -> Result<Vec<IdResolution>, String> {
+    let mut taken: HashMap<String, String> = HashMap::new();
+    for sym in host.analysis().symbol_index().all_symbols() {
+        let id = sym.element_id.as_ref();
+        if id.is_empty() || id.starts_with("00000000-0000-0000-0000") {
+            continue;
+        }
+        taken
+            .entry(id.to_string())
+            .or_insert_with(|| sym.qualified_name.to_string());
+    }
+
+    let mut conflicts: Vec<IdResolution> = model
+        .iter_elements()
+        .filter_map(|element| {
+            let imported = qualified_name(model, element);
+            let existing = taken.get(element.id.as_str())?;
+            (*existing != imported).then(|| IdResolution {
+                id: element.id.as_str().to_string(),
+                imported,
+                existing: existing.clone(),
+                action: policy,
+                new_id: None,
+            })
+        })
+        .collect();
+    if conflicts.is_empty() {
+        return Ok(conflicts);
+    }
+
+    let namespace = Uuid::parse_str(DEFAULT_ID_NAMESPACE).map_err(|e| e.to_string())?;
+    let new_id = |id: &str, name: &str| {
+        Uuid::new_v5(&namespace, format!("{}/{}", id, name).as_bytes()).to_string()
+    };
+    match policy {
+        IdConflictPolicy::Error => {
+            return Err(format!(
+                "{} imported element IDs are already in the workspace: {}",
+                conflicts.len(),
+                conflicts[0]
+            ));
+        }
+        IdConflictPolicy::Rename => {
+            let mut renamed = HashMap::new();
+            for conflict in &mut conflicts {
+                let id = new_id(&conflict.id, &conflict.imported);
+                renamed.insert(
+                    ElementId::new(conflict.id.as_str()),
+                    ElementId::new(id.as_str()),
+                );
+                conflict.new_id = Some(id);
+            }
+            rename_ids(model, &renamed);
+        }
+        IdConflictPolicy::Skip => {
+            let ids: Vec<ElementId> = conflicts
+                .iter()
+                .map(|c| ElementId::new(c.id.as_str()))
+                .collect();
+            remove_subtrees(model, &ids);
+        }
+        IdConflictPolicy::Overwrite => {
+            let mut renamed = HashMap::new();
+            for conflict in &mut conflicts {
+                let id = new_id(&conflict.id, &conflict.existing);
+                renamed.insert((conflict.id.clone(), conflict.existing.clone()), id.clone());
+                conflict.new_id = Some(id);
+            }
+            host.update_symbols(|sym| {
+                let key = (sym.element_id.to_string(), sym.qualified_name.to_string());
+                if let Some(id) = renamed.get(&key) {
+                    sym.element_id = id.as_str().into();
+                }
+            });
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Qualified name of an element: as recorded, or else the names along its
+/// owner chain.
+fn qualified_name(model: &Model, element: &Element) -> String {
+    if let Some(name) = &element.qualified_name {
+        return name.to_string();
+    }
+    let mut names = Vec::new();
+    let mut current = Some(element);
+    // Bounded, in case ownership is cyclic
+    for _ in 0..=model.elements.len() {
+        let Some(element) = current else { break };
+        if let Some(name) = &element.name {
+            names.push(name.to_string());
+        }
+        current = element.owner.as_ref().and_then(|owner| model.get(owner));
+    }
+    if names.is_empty() {
+        return element.id.as_str().to_string();
+    }
+    names.reverse();
+    names.join("::")
+}
+
+/// Replace element IDs everywhere the model refers to them.
+fn rename_ids(model: &mut Model, renamed: &HashMap<ElementId, ElementId>) {
+    let rename = |id: &mut ElementId| {
+        if let Some(new) = renamed.get(id) {
+            *id = new.clone();
+        }
+    };
+    model.elements = std::mem::take(&mut model.elements)
+        .into_iter()
+        .map(|(mut id, mut element)| {
+            rename(&mut id);
+            rename(&mut element.id);
+            if let Some(owner) = &mut element.owner {
+                rename(owner);
+            }
+            element.owned_elements.iter_mut().for_each(rename);
+            (id, element)
+        })
+        .collect();
+    for rel in &mut model.relationships {
+        rename(&mut rel.source);
+        rename(&mut rel.target);
+        if let Some(owner) = &mut rel.owner {
+            rename(owner);
+        }
+    }
+    model.roots.iter_mut().for_each(rename);
+}
+
+/// Remove elements with everything they own, and relationships to or from
+/// any of them.
+fn remove_subtrees(model: &mut Model, ids: &[ElementId]) {
+    let mut removed = HashSet::new();
+    let mut stack = ids.to_vec();
+    while let Some(id) = stack.pop() {
+        if let Some(element) = model.get(&id) {
+            stack.extend(element.owned_elements.iter().cloned());
+        }
+        removed.insert(id);
+    }
+
+    model.elements.retain(|id, _| !removed.contains(id));
+    for element in model.elements.values_mut() {
+        element.owned_elements.retain(|id| !removed.contains(id));
+    }
+    model
+        .relationships
+        .retain(|rel| !removed.contains(&rel.source) && !removed.contains(&rel.target));
+    model.roots.retain(|id| !removed.contains(id));
+}
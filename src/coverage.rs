@@ -0,0 +1,182 @@
+//! Documentation coverage.
+//!
+//! Coverage is the share of the model's own elements of the measured kinds
+//! that carry a non-empty `doc` comment: the kinds given to `--require-docs`,
+//! or every definition if none are. Undocumented elements of required kinds
+//! are reported as warnings, so documentation debt shows up next to the
+//! other diagnostics and can be gated with `--min-doc-coverage`.
+
+use super::DiagnosticInfo;
+use super::lint::codes;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use syster::hir::{Severity, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// Documented elements out of those measured.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DocCoverage {
+    /// Elements with a doc comment.
+    pub documented: usize,
+    /// Elements measured.
+    pub total: usize,
+    /// `documented / total` in percent (100 if nothing is measured).
+    pub percent: f64,
+}
+
+impl Default for DocCoverage {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl DocCoverage {
+    /// Coverage of `documented` out of `total` elements.
+    pub fn new(documented: usize, total: usize) -> Self {
+        let percent = if total == 0 {
+            100.0
+        } else {
+            documented as f64 * 100.0 / total as f64
+        };
+        Self {
+            documented,
+            total,
+            percent,
+        }
+    }
+
+    /// Combine the coverage of disjoint sets of files.
+    pub fn merge(self, other: Self) -> Self {
+        Self::new(self.documented + other.documented, self.total + other.total)
+    }
+}
+
+/// Parse a kind name for `--require-docs`: the kind's label without spaces,
+/// case-insensitive (`partdef`, `requirementdef`, `usecasedef`, `part`,
+/// `attribute`, `package`, ...).
+pub fn parse_doc_kind(name: &str) -> Result<SymbolKind, String> {
+    let wanted = name.trim().to_lowercase().replace(['_', '-', ' '], "");
+    DOCUMENTABLE_KINDS
+        .iter()
+        .copied()
+        .find(|kind| kind_key(*kind) == wanted)
+        .ok_or_else(|| {
+            let known: Vec<String> = DOCUMENTABLE_KINDS.iter().map(|k| kind_key(*k)).collect();
+            format!(
+                "Unknown element kind '{}' (expected one of: {})",
+                name,
+                known.join(", ")
+            )
+        })
+}
+
+/// Every documentable definition kind (what `--profile strict` requires
+/// docs on).
+pub(crate) fn definition_kinds() -> Vec<SymbolKind> {
+    DOCUMENTABLE_KINDS
+        .iter()
+        .copied()
+        .filter(|kind| kind.is_definition() && *kind != SymbolKind::Package)
+        .collect()
+}
+
+/// `Part def` → `partdef`.
+fn kind_key(kind: SymbolKind) -> String {
+    kind.display().to_lowercase().replace(' ', "")
+}
+
+/// Measure documentation coverage in `files` and warn about undocumented
+/// elements of the `required` kinds (coverage covers every definition if
+/// `required` is empty).
+pub(crate) fn doc_coverage(
+    host: &AnalysisHost,
+    files: &HashSet<&str>,
+    required: &[SymbolKind],
+) -> (DocCoverage, Vec<DiagnosticInfo>) {
+    let measured = |kind: SymbolKind| {
+        if required.is_empty() {
+            kind.is_definition() && kind != SymbolKind::Package
+        } else {
+            required.contains(&kind)
+        }
+    };
+
+    let (mut documented, mut total) = (0, 0);
+    let mut diagnostics = Vec::new();
+    for path in host.files().keys() {
+        let file = path.to_string_lossy().to_string();
+        if !files.contains(file.as_str()) {
+            continue;
+        }
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        for symbol in host.symbol_index().symbols_in_file(file_id) {
+            if !measured(symbol.kind) || symbol.name.starts_with('<') {
+                continue;
+            }
+            total += 1;
+            if symbol.doc.as_ref().is_some_and(|d| !d.trim().is_empty()) {
+                documented += 1;
+            } else if !required.is_empty() {
+                diagnostics.push(DiagnosticInfo {
+                    file: file.clone(),
+                    line: symbol.start_line + 1,
+                    col: symbol.start_col + 1,
+                    end_line: symbol.end_line + 1,
+                    end_col: symbol.end_col + 1,
+                    message: format!(
+                        "{} '{}' has no documentation",
+                        symbol.kind.display().to_lowercase(),
+                        symbol.qualified_name
+                    ),
+                    severity: Severity::Warning,
+                    code: Some(codes::MISSING_DOC.to_string()),
+                    blame: None,
+                    owners: Vec::new(),
+                    related: Vec::new(),
+                });
+            }
+        }
+    }
+    (DocCoverage::new(documented, total), diagnostics)
+}
+
+/// Kinds accepted by [`parse_doc_kind`].
+const DOCUMENTABLE_KINDS: &[SymbolKind] = &[
+    SymbolKind::Package,
+    SymbolKind::PartDefinition,
+    SymbolKind::ItemDefinition,
+    SymbolKind::ActionDefinition,
+    SymbolKind::PortDefinition,
+    SymbolKind::AttributeDefinition,
+    SymbolKind::ConnectionDefinition,
+    SymbolKind::InterfaceDefinition,
+    SymbolKind::AllocationDefinition,
+    SymbolKind::RequirementDefinition,
+    SymbolKind::ConstraintDefinition,
+    SymbolKind::StateDefinition,
+    SymbolKind::CalculationDefinition,
+    SymbolKind::UseCaseDefinition,
+    SymbolKind::AnalysisCaseDefinition,
+    SymbolKind::ConcernDefinition,
+    SymbolKind::ViewDefinition,
+    SymbolKind::ViewpointDefinition,
+    SymbolKind::RenderingDefinition,
+    SymbolKind::EnumerationDefinition,
+    SymbolKind::MetadataDefinition,
+    SymbolKind::Interaction,
+    SymbolKind::PartUsage,
+    SymbolKind::ItemUsage,
+    SymbolKind::ActionUsage,
+    SymbolKind::PortUsage,
+    SymbolKind::AttributeUsage,
+    SymbolKind::ConnectionUsage,
+    SymbolKind::InterfaceUsage,
+    SymbolKind::AllocationUsage,
+    SymbolKind::RequirementUsage,
+    SymbolKind::ConstraintUsage,
+    SymbolKind::StateUsage,
+    SymbolKind::CalculationUsage,
+    SymbolKind::ViewUsage,
+];
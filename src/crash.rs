@@ -0,0 +1,288 @@
+//! Crash report bundles (`--crash-report`).
+//!
+//! With a panic hook installed, a panic anywhere in a run is captured with
+//! its message, location and a backtrace. [`crash_report`] then looks for
+//! the model file that makes analysis panic on its own, and
+//! [`write_crash_report`] bundles everything into a zip archive to attach
+//! to an issue, together with the tool version, the platform and the
+//! command line.
+//!
+//! The offending file is redacted unless asked otherwise: in SysML and
+//! KerML files every name, string and comment is replaced while keywords,
+//! punctuation and line breaks stay, so the file still parses the same way;
+//! other files are left out. Paths under the home directory are shortened
+//! to `~`, and unless redaction is off, the paths of SysML and KerML files
+//! named in the panic message or the command line are replaced by
+//! `<model>.sysml` (or `.kerml`).
+
+use serde::Serialize;
+use std::backtrace::Backtrace;
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use syster::ide::AnalysisHost;
+use syster::parser::{Lexer, SyntaxKind};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// How much of the offending file a crash report includes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CrashRedaction {
+    /// The file as it is.
+    None,
+    /// The file with names, strings and comments replaced.
+    #[default]
+    Names,
+    /// Only the file's path and size.
+    Content,
+}
+
+/// What a crash report records.
+#[derive(Debug, Serialize)]
+pub struct CrashReport {
+    /// Version of syster-cli.
+    pub version: String,
+    /// Operating system and architecture.
+    pub platform: String,
+    /// Command line of the run.
+    pub args: Vec<String>,
+    /// Panic message.
+    pub message: String,
+    /// Source location of the panic.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Model file that makes analysis panic on its own, if one does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Size of that file in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_size: Option<u64>,
+    /// How the file was redacted.
+    pub redaction: CrashRedaction,
+    /// Backtrace of the panicking thread.
+    #[serde(skip)]
+    pub backtrace: String,
+    /// The (redacted) text of the file, if included.
+    #[serde(skip)]
+    pub file_text: Option<String>,
+}
+
+/// The last panic the hook captured.
+struct CapturedPanic {
+    message: String,
+    location: Option<String>,
+    backtrace: String,
+}
+
+static LAST_PANIC: Mutex<Option<CapturedPanic>> = Mutex::new(None);
+
+/// Set while model files are analyzed one by one to find the offending
+/// one, whose panics are expected.
+static PROBING: AtomicBool = AtomicBool::new(false);
+
+/// Capture the message, location and backtrace of every panic, before the
+/// default hook prints it.
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if PROBING.load(Ordering::Relaxed) {
+            return;
+        }
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        let captured = CapturedPanic {
+            message,
+            location: info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+            backtrace: Backtrace::force_capture().to_string(),
+        };
+        *LAST_PANIC.lock().unwrap_or_else(|e| e.into_inner()) = Some(captured);
+        default_hook(info);
+    }));
+}
+
+/// Report the last captured panic of a run over `input`.
+///
+/// If `input` is a file, it is the offending file; in a directory, each
+/// SysML and KerML file is analyzed on its own until one panics.
+pub fn crash_report(
+    input: Option<&Path>,
+    args: &[String],
+    redaction: CrashRedaction,
+) -> CrashReport {
+    let captured = LAST_PANIC.lock().unwrap_or_else(|e| e.into_inner()).take();
+    let (message, location, backtrace) = match captured {
+        Some(p) => (p.message, p.location, p.backtrace),
+        None => ("unknown panic".to_string(), None, String::new()),
+    };
+
+    let file = input.and_then(offending_file);
+    let file_size = file
+        .as_ref()
+        .and_then(|path| std::fs::metadata(path).ok())
+        .map(|m| m.len());
+    let file_text = file.as_ref().and_then(|path| {
+        let text = std::fs::read_to_string(path).ok()?;
+        match redaction {
+            CrashRedaction::None => Some(text),
+            CrashRedaction::Names if is_model_file(path) => Some(redact_source(&text)),
+            _ => None,
+        }
+    });
+
+    CrashReport {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        platform: format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH),
+        args: args.iter().map(|arg| redact_text(arg, redaction)).collect(),
+        message: redact_text(&message, redaction),
+        location,
+        file: file.map(|path| shorten_home(&path.to_string_lossy())),
+        file_size,
+        redaction,
+        backtrace,
+        file_text,
+    }
+}
+
+/// Write `report` to a zip archive at `path`: `crash.json`, `backtrace.txt`
+/// and the offending file under `file/`, if included.
+pub fn write_crash_report(report: &CrashReport, path: &Path) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut add = |name: &str, bytes: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to create {}: {}", name, e))?;
+        zip.write_all(bytes)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))
+    };
+    add("crash.json", json.as_bytes())?;
+    add("backtrace.txt", report.backtrace.as_bytes())?;
+    if let (Some(file), Some(text)) = (&report.file, &report.file_text) {
+        let name = Path::new(file)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "model.sysml".to_string());
+        add(&format!("file/{}", name), text.as_bytes())?;
+    }
+    let bytes = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish crash report: {}", e))?
+        .into_inner();
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Replace the names, strings and comments of SysML or KerML `text` with
+/// placeholders, keeping keywords, punctuation, numbers and line breaks.
+/// The same name always gets the same placeholder.
+pub fn redact_source(text: &str) -> String {
+    let mut names: HashMap<&str, String> = HashMap::new();
+    let mut result = String::with_capacity(text.len());
+    for token in Lexer::new(text) {
+        match token.kind {
+            kind if kind.is_keyword() => result.push_str(token.text),
+            SyntaxKind::IDENT | SyntaxKind::STRING => {
+                let next = names.len() + 1;
+                let name =
+                    names
+                        .entry(token.text)
+                        .or_insert_with(|| match token.text.chars().next() {
+                            Some(quote @ ('\'' | '"')) => format!("{}s{}{}", quote, next, quote),
+                            _ => format!("n{}", next),
+                        });
+                result.push_str(name);
+            }
+            SyntaxKind::LINE_COMMENT => result.push_str("//"),
+            SyntaxKind::BLOCK_COMMENT => {
+                result.push_str("/*");
+                result.extend(token.text.chars().filter(|&c| c == '\n'));
+                result.push_str("*/");
+            }
+            _ => result.push_str(token.text),
+        }
+    }
+    result
+}
+
+/// The file in `input` that makes analysis panic on its own.
+fn offending_file(input: &Path) -> Option<PathBuf> {
+    if input.is_file() {
+        return Some(input.to_path_buf());
+    }
+    PROBING.store(true, Ordering::Relaxed);
+    let found = WalkDir::new(input)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && is_model_file(path))
+        .find(|path| {
+            let Ok(text) = std::fs::read_to_string(path) else {
+                return false;
+            };
+            std::panic::catch_unwind(|| {
+                let mut host = AnalysisHost::new();
+                host.set_file_content(&path.to_string_lossy(), &text);
+                let _analysis = host.analysis();
+            })
+            .is_err()
+        });
+    PROBING.store(false, Ordering::Relaxed);
+    found
+}
+
+/// Whether `path` is a SysML or KerML file, by extension.
+fn is_model_file(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("sysml") | Some("kerml")
+    )
+}
+
+/// `text` with the home directory shortened and, unless `redaction` is
+/// [`CrashRedaction::None`], every SysML or KerML file path in it replaced
+/// by `<model>` and its extension.
+fn redact_text(text: &str, redaction: CrashRedaction) -> String {
+    let text = shorten_home(text);
+    if redaction == CrashRedaction::None {
+        return text;
+    }
+    text.split_inclusive(char::is_whitespace)
+        .map(|word| {
+            let Some((at, ext)) = [".sysml", ".kerml"]
+                .iter()
+                .find_map(|ext| word.find(ext).map(|at| (at, *ext)))
+            else {
+                return word.to_string();
+            };
+            // The path starts after any quote or `--flag=` in front of it
+            let start = word[..at]
+                .rfind(['\'', '"', '(', '[', '='])
+                .map_or(0, |i| i + 1);
+            format!(
+                "{}<model>{}{}",
+                &word[..start],
+                ext,
+                &word[at + ext.len()..]
+            )
+        })
+        .collect()
+}
+
+/// `text` with the home directory replaced by `~`.
+fn shorten_home(text: &str) -> String {
+    match std::env::var("HOME") {
+        Ok(home) if !home.is_empty() => text.replace(&home, "~"),
+        _ => text.to_string(),
+    }
+}
@@ -0,0 +1,244 @@
+//! Standalone HTML diagnostics dashboard.
+//!
+//! `--format html` writes a single HTML page with summary charts (by
+//! severity and top-level package), a diagnostics table that can be sorted
+//! by clicking a column and filtered by text and severity, and a section per
+//! file with the source lines around each diagnostic. The page has no
+//! external assets, so it can be attached to a CI run or mailed around. It
+//! is rendered with the template `src/templates/dashboard.html.tera`.
+
+use super::codegen::render_template;
+use super::{AnalysisResult, DiagnosticInfo, severity_name};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use syster::hir::Severity;
+
+/// Lines of source shown above and below a diagnostic.
+const CONTEXT_LINES: u32 = 2;
+
+/// Packages shown in the package chart.
+const CHART_PACKAGES: usize = 10;
+
+/// Dashboard data, as passed to the template.
+#[derive(Debug, Serialize)]
+struct Dashboard {
+    file_count: usize,
+    symbol_count: usize,
+    error_count: usize,
+    warning_count: usize,
+    /// Documentation coverage in percent, if anything was measured.
+    doc_coverage: Option<String>,
+    severities: Vec<Bar>,
+    packages: Vec<Bar>,
+    diagnostics: Vec<Row>,
+    files: Vec<FileReport>,
+}
+
+/// A bar of a chart, `percent` of the largest one.
+#[derive(Debug, Serialize)]
+struct Bar {
+    label: String,
+    count: usize,
+    percent: usize,
+}
+
+/// A row of the diagnostics table.
+#[derive(Debug, Serialize)]
+struct Row {
+    severity: &'static str,
+    code: String,
+    file: String,
+    /// Anchor of the file's section.
+    file_id: String,
+    line: u32,
+    col: u32,
+    message: String,
+}
+
+/// The diagnostics of one file.
+#[derive(Debug, Serialize)]
+struct FileReport {
+    id: String,
+    path: String,
+    errors: usize,
+    warnings: usize,
+    diagnostics: Vec<Snippet>,
+}
+
+/// A diagnostic with the source around it.
+#[derive(Debug, Serialize)]
+struct Snippet {
+    severity: &'static str,
+    code: String,
+    line: u32,
+    message: String,
+    /// Empty if the file can't be read.
+    source: Vec<SourceLine>,
+}
+
+/// A source line, split around the diagnostic's span on its first line.
+#[derive(Debug, Serialize)]
+struct SourceLine {
+    number: u32,
+    before: String,
+    marked: String,
+    after: String,
+}
+
+/// Export diagnostics as a standalone HTML dashboard.
+pub fn export_html(result: &AnalysisResult) -> Result<String, String> {
+    let _span = tracing::info_span!("export", format = "html").entered();
+    let mut by_file: BTreeMap<&str, Vec<&DiagnosticInfo>> = BTreeMap::new();
+    for diag in &result.diagnostics {
+        by_file.entry(diag.file.as_str()).or_default().push(diag);
+    }
+    let file_ids: HashMap<&str, String> = by_file
+        .keys()
+        .enumerate()
+        .map(|(i, file)| (*file, format!("file-{}", i + 1)))
+        .collect();
+
+    let diagnostics = result
+        .diagnostics
+        .iter()
+        .map(|diag| Row {
+            severity: severity_name(diag.severity),
+            code: diag.code.clone().unwrap_or_default(),
+            file: diag.file.clone(),
+            file_id: file_ids[diag.file.as_str()].clone(),
+            line: diag.line,
+            col: diag.col,
+            message: diag.message.clone(),
+        })
+        .collect();
+
+    let files = by_file
+        .iter()
+        .map(|(file, diagnostics)| {
+            let text = std::fs::read_to_string(file).unwrap_or_default();
+            let lines: Vec<&str> = text.lines().collect();
+            FileReport {
+                id: file_ids[file].clone(),
+                path: file.to_string(),
+                errors: count(diagnostics, Severity::Error),
+                warnings: count(diagnostics, Severity::Warning),
+                diagnostics: diagnostics
+                    .iter()
+                    .map(|diag| Snippet {
+                        severity: severity_name(diag.severity),
+                        code: diag.code.clone().unwrap_or_default(),
+                        line: diag.line,
+                        message: diag.message.clone(),
+                        source: source_lines(&lines, diag),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    let severities = bars(
+        [
+            Severity::Error,
+            Severity::Warning,
+            Severity::Info,
+            Severity::Hint,
+        ]
+        .into_iter()
+        .map(|severity| {
+            let count = result
+                .diagnostics
+                .iter()
+                .filter(|d| d.severity == severity)
+                .count();
+            (severity_name(severity).to_string(), count)
+        })
+        .collect(),
+    );
+    let mut packages: Vec<(String, usize)> = result
+        .packages
+        .iter()
+        .filter(|p| p.errors + p.warnings > 0)
+        .map(|p| (p.name.clone(), p.errors + p.warnings))
+        .collect();
+    packages.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    packages.truncate(CHART_PACKAGES);
+
+    let coverage = &result.doc_coverage;
+    let dashboard = Dashboard {
+        file_count: result.file_count,
+        symbol_count: result.symbol_count,
+        error_count: result.error_count,
+        warning_count: result.warning_count,
+        doc_coverage: (coverage.total > 0).then(|| format!("{:.1}", coverage.percent)),
+        severities,
+        packages: bars(packages),
+        diagnostics,
+        files,
+    };
+    render_template(
+        &dashboard,
+        "dashboard.html",
+        include_str!("templates/dashboard.html.tera"),
+    )
+}
+
+fn count(diagnostics: &[&DiagnosticInfo], severity: Severity) -> usize {
+    diagnostics
+        .iter()
+        .filter(|d| d.severity == severity)
+        .count()
+}
+
+/// Scale counts to the largest one.
+fn bars(counts: Vec<(String, usize)>) -> Vec<Bar> {
+    let max = counts.iter().map(|(_, n)| *n).max().unwrap_or(0).max(1);
+    counts
+        .into_iter()
+        .map(|(label, count)| Bar {
+            label,
+            count,
+            percent: count * 100 / max,
+        })
+        .collect()
+}
+
+/// The lines around `diag`, its span marked on its first line.
+fn source_lines(lines: &[&str], diag: &DiagnosticInfo) -> Vec<SourceLine> {
+    let first = diag.line.saturating_sub(CONTEXT_LINES).max(1);
+    let last = (diag.line + CONTEXT_LINES).min(lines.len() as u32);
+    (first..=last)
+        .map(|number| {
+            let text = lines[number as usize - 1];
+            if number != diag.line {
+                return SourceLine {
+                    number,
+                    before: text.to_string(),
+                    marked: String::new(),
+                    after: String::new(),
+                };
+            }
+            let start = (diag.col as usize).saturating_sub(1).min(text.len());
+            let end = if diag.end_line == diag.line {
+                (diag.end_col as usize)
+                    .saturating_sub(1)
+                    .clamp(start, text.len())
+            } else {
+                text.len()
+            };
+            match (text.get(..start), text.get(start..end), text.get(end..)) {
+                (Some(before), Some(marked), Some(after)) => SourceLine {
+                    number,
+                    before: before.to_string(),
+                    marked: marked.to_string(),
+                    after: after.to_string(),
+                },
+                _ => SourceLine {
+                    number,
+                    before: String::new(),
+                    marked: text.to_string(),
+                    after: String::new(),
+                },
+            }
+        })
+        .collect()
+}
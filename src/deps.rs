@@ -0,0 +1,345 @@
+//! Model package dependencies.
+//!
+//! Dependencies are declared in the `[dependencies]` table of `syster.toml`,
+//! each with a version and exactly one source:
+//!
+//! ```toml
+//! [dependencies]
+//! vehicles = { version = "1.2.0", git = "https://example.com/vehicles.git" }
+//! sensors = { version = "0.3.0", url = "https://example.com/sensors-0.3.0.kpar" }
+//! common = { path = "../common" }
+//! ```
+//!
+//! `syster fetch` downloads git and URL dependencies into
+//! `.syster/deps/<name>-<version>/` next to `syster.toml`: git sources are
+//! cloned at `rev` (default: tag `v<version>`), URLs are downloaded with
+//! `curl` and KPAR archives decompiled to SysML. As they name the cache
+//! directory, versions and revs may only contain letters, digits, `-`, `_`,
+//! `.` and `+`. Path dependencies are used in place. Analysis loads every
+//! dependency as a library: references resolve into it, but nothing inside
+//! it is reported.
+
+use super::project::{find_project_file, load_table};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory holding fetched dependencies, relative to `syster.toml`.
+pub const CACHE_DIR: &str = ".syster/deps";
+
+/// A dependency declared in `syster.toml`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    /// Dependency name (also its cache directory name).
+    pub name: String,
+    /// Requested version.
+    pub version: Option<String>,
+    /// Where the dependency comes from.
+    pub source: DependencySource,
+}
+
+/// Where a dependency comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DependencySource {
+    /// A git repository, checked out at a tag or branch.
+    Git {
+        /// Repository URL.
+        url: String,
+        /// Tag or branch (default: `v<version>`, or the default branch).
+        rev: Option<String>,
+    },
+    /// A `.kpar`, `.sysml` or `.kerml` file to download.
+    Url(String),
+    /// A local directory or file, relative to `syster.toml`.
+    Path(PathBuf),
+}
+
+/// What [`fetch`] did for a dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchStatus {
+    /// Downloaded into the cache.
+    Fetched,
+    /// Already in the cache.
+    Cached,
+    /// A path dependency, used in place.
+    Local,
+}
+
+/// The dependencies of a project.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Manifest {
+    /// Directory containing `syster.toml`.
+    pub root: PathBuf,
+    /// Dependencies, ordered by name.
+    pub dependencies: Vec<Dependency>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DependencySpec {
+    version: Option<String>,
+    git: Option<String>,
+    rev: Option<String>,
+    url: Option<String>,
+    path: Option<PathBuf>,
+}
+
+impl Manifest {
+    /// Read the dependencies of the `syster.toml` at `path`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let dependencies: BTreeMap<String, DependencySpec> =
+            load_table(path, "dependencies")?.unwrap_or_default();
+        let dependencies = dependencies
+            .into_iter()
+            .map(|(name, spec)| dependency(name, spec))
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        Ok(Self {
+            root: path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from(".")),
+            dependencies,
+        })
+    }
+
+    /// Find the `syster.toml` governing `input` (see
+    /// [`find_project_file`]) and read its dependencies.
+    pub fn discover(input: &Path) -> Result<Option<Self>, String> {
+        find_project_file(input)
+            .map(|path| Self::load(&path))
+            .transpose()
+    }
+
+    /// Where `dependency` lives on disk once fetched.
+    pub fn dependency_dir(&self, dependency: &Dependency) -> PathBuf {
+        let key = match &dependency.source {
+            DependencySource::Path(path) => return self.root.join(path),
+            DependencySource::Git { rev, .. } => dependency.version.as_deref().or(rev.as_deref()),
+            DependencySource::Url(_) => dependency.version.as_deref(),
+        };
+        self.root
+            .join(CACHE_DIR)
+            .join(format!("{}-{}", dependency.name, key.unwrap_or("latest")))
+    }
+
+    /// Files or directories to load as libraries, failing if a dependency
+    /// has not been fetched yet.
+    pub fn library_paths(&self) -> Result<Vec<PathBuf>, String> {
+        self.dependencies
+            .iter()
+            .map(|dependency| {
+                let dir = self.dependency_dir(dependency);
+                if dir.exists() {
+                    Ok(dir)
+                } else if matches!(dependency.source, DependencySource::Path(_)) {
+                    Err(format!(
+                        "Dependency '{}' not found at {}",
+                        dependency.name,
+                        dir.display()
+                    ))
+                } else {
+                    Err(format!(
+                        "Dependency '{}' has not been fetched (run `syster fetch`)",
+                        dependency.name
+                    ))
+                }
+            })
+            .collect()
+    }
+}
+
+/// Validate one `[dependencies]` entry.
+fn dependency(name: String, spec: DependencySpec) -> Result<Dependency, String> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "dependency name '{}' may only contain letters, digits, '-' and '_'",
+            name
+        ));
+    }
+    // Both end up in the name of the cache directory, which fetch replaces
+    for (key, value) in [("version", &spec.version), ("rev", &spec.rev)] {
+        if let Some(value) = value {
+            if value.is_empty()
+                || !value
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'))
+            {
+                return Err(format!(
+                    "dependency '{}': `{}` '{}' may only contain letters, digits, '-', '_', '.' and '+'",
+                    name, key, value
+                ));
+            }
+        }
+    }
+    if spec.rev.is_some() && spec.git.is_none() {
+        return Err(format!("dependency '{}': `rev` requires `git`", name));
+    }
+    let source = match (spec.git, spec.url, spec.path) {
+        (Some(url), None, None) => DependencySource::Git { url, rev: spec.rev },
+        (None, Some(url), None) => DependencySource::Url(url),
+        (None, None, Some(path)) => DependencySource::Path(path),
+        _ => {
+            return Err(format!(
+                "dependency '{}' needs exactly one of `git`, `url` or `path`",
+                name
+            ));
+        }
+    };
+    Ok(Dependency {
+        name,
+        version: spec.version,
+        source,
+    })
+}
+
+/// Download the git and URL dependencies of `manifest` that are not cached
+/// yet (all of them with `force`).
+pub fn fetch(
+    manifest: &Manifest,
+    force: bool,
+    verbose: bool,
+) -> Result<Vec<(String, FetchStatus)>, String> {
+    let mut statuses = Vec::new();
+    for dependency in &manifest.dependencies {
+        let dir = manifest.dependency_dir(dependency);
+        let status = match &dependency.source {
+            DependencySource::Path(_) => FetchStatus::Local,
+            _ if dir.exists() && !force => FetchStatus::Cached,
+            source => {
+                // Download next to the cache entry and move it in place once
+                // complete, so an interrupted fetch never looks cached
+                let mut partial = dir.clone().into_os_string();
+                partial.push(".partial");
+                let partial = PathBuf::from(partial);
+                remove_dir(&partial)?;
+                std::fs::create_dir_all(&partial)
+                    .map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+                let downloaded = match source {
+                    DependencySource::Git { url, rev } => {
+                        let rev = rev
+                            .clone()
+                            .or_else(|| dependency.version.as_ref().map(|v| format!("v{}", v)));
+                        clone(url, rev.as_deref(), &partial, verbose)
+                    }
+                    DependencySource::Url(url) => download(url, &partial, verbose),
+                    DependencySource::Path(_) => unreachable!(),
+                };
+                if let Err(e) = downloaded {
+                    let _ = std::fs::remove_dir_all(&partial);
+                    return Err(format!("Failed to fetch '{}': {}", dependency.name, e));
+                }
+                remove_dir(&dir)?;
+                std::fs::rename(&partial, &dir)
+                    .map_err(|e| format!("Failed to move {}: {}", partial.display(), e))?;
+                FetchStatus::Fetched
+            }
+        };
+        statuses.push((dependency.name.clone(), status));
+    }
+    Ok(statuses)
+}
+
+/// Shallow-clone `url` at `rev` into `dest`, without the `.git` directory.
+fn clone(url: &str, rev: Option<&str>, dest: &Path, verbose: bool) -> Result<(), String> {
+    if verbose {
+        tracing::info!(
+            "Cloning {}{}",
+            url,
+            rev.map(|r| format!(" at {}", r)).unwrap_or_default()
+        );
+    }
+    let mut command = Command::new("git");
+    command.args(["clone", "--quiet", "--depth", "1"]);
+    if let Some(rev) = rev {
+        command.args(["--branch", rev]);
+    }
+    // `--` keeps a URL starting with `-` from being read as an option
+    run(command.arg("--").arg(url).arg(dest), "git clone")?;
+    remove_dir(&dest.join(".git"))
+}
+
+/// Download `url` into `dest`, decompiling KPAR archives to SysML.
+fn download(url: &str, dest: &Path, verbose: bool) -> Result<(), String> {
+    let file_name = url
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.split(['?', '#']).next())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| format!("Cannot tell the file name of {}", url))?;
+    let target = dest.join(file_name);
+    if verbose {
+        tracing::info!("Downloading {}", url);
+    }
+    // As for git, `--` keeps a URL starting with `-` from being an option
+    run(
+        Command::new("curl")
+            .args([
+                "--fail",
+                "--silent",
+                "--show-error",
+                "--location",
+                "--output",
+            ])
+            .arg(&target)
+            .arg("--")
+            .arg(url),
+        "curl",
+    )?;
+
+    match Path::new(file_name).extension().and_then(|e| e.to_str()) {
+        Some("sysml") | Some("kerml") => Ok(()),
+        Some("kpar") => decompile_kpar(&target, verbose),
+        _ => Err(format!(
+            "Unsupported package {} (expected .kpar, .sysml or .kerml)",
+            file_name
+        )),
+    }
+}
+
+/// Replace a downloaded KPAR archive with its SysML text.
+#[cfg(feature = "interchange")]
+fn decompile_kpar(path: &Path, verbose: bool) -> Result<(), String> {
+    let result = super::decompile_model(path, Some("kpar"), verbose)?;
+    let sysml = path.with_extension("sysml");
+    std::fs::write(&sysml, result.sysml_text)
+        .map_err(|e| format!("Failed to write {}: {}", sysml.display(), e))?;
+    std::fs::remove_file(path).map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+#[cfg(not(feature = "interchange"))]
+fn decompile_kpar(path: &Path, _verbose: bool) -> Result<(), String> {
+    Err(format!(
+        "{}: KPAR dependencies require the `interchange` feature",
+        path.display()
+    ))
+}
+
+/// Run `command`, failing with its stderr.
+fn run(command: &mut Command, name: &str) -> Result<(), String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", name, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Remove `dir` if it exists.
+fn remove_dir(dir: &Path) -> Result<(), String> {
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)
+            .map_err(|e| format!("Failed to remove {}: {}", dir.display(), e))?;
+    }
+    Ok(())
+}
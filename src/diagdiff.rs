@@ -0,0 +1,109 @@
+//! Diagnostics diff between two saved runs.
+//!
+//! [`diff_diagnostics`] compares the diagnostics of two `--json` results
+//! and sorts them into new, fixed and persisting ones, so CI can fail on
+//! new issues while a legacy model is cleaned up. Diagnostics are matched on
+//! file, code and message; their lines may differ by a few lines, since
+//! edits above them move them around.
+
+use super::{AnalysisResult, DiagnosticInfo};
+use serde::Serialize;
+use std::collections::HashMap;
+use syster::hir::Severity;
+
+/// Lines a diagnostic may move between runs and still match, by default.
+pub const DEFAULT_LINE_FUZZ: u32 = 20;
+
+/// Diagnostics of a new run compared with an old one.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DiagnosticDiff {
+    /// Diagnostics of the new run without a match in the old one.
+    pub new: Vec<DiagnosticInfo>,
+    /// Diagnostics of the old run without a match in the new one.
+    pub fixed: Vec<DiagnosticInfo>,
+    /// Diagnostics of the new run that were already reported, at their new
+    /// locations.
+    pub persisting: Vec<DiagnosticInfo>,
+}
+
+/// Compare the diagnostics of `old` and `new`.
+///
+/// A diagnostic matches one with the same file, code and message at most
+/// `line_fuzz` lines away; the closest pairs are matched first.
+pub fn diff_diagnostics(
+    old: &AnalysisResult,
+    new: &AnalysisResult,
+    line_fuzz: u32,
+) -> DiagnosticDiff {
+    let key = |d: &DiagnosticInfo| (d.file.clone(), d.code.clone(), d.message.clone());
+    let mut by_key: HashMap<_, Vec<usize>> = HashMap::new();
+    for (i, diag) in old.diagnostics.iter().enumerate() {
+        by_key.entry(key(diag)).or_default().push(i);
+    }
+    let mut pairs: Vec<(u32, usize, usize)> = Vec::new();
+    for (j, new_diag) in new.diagnostics.iter().enumerate() {
+        for &i in by_key.get(&key(new_diag)).into_iter().flatten() {
+            let distance = old.diagnostics[i].line.abs_diff(new_diag.line);
+            if distance <= line_fuzz {
+                pairs.push((distance, i, j));
+            }
+        }
+    }
+    pairs.sort();
+
+    let mut old_matched = vec![false; old.diagnostics.len()];
+    let mut new_matched = vec![false; new.diagnostics.len()];
+    for (_, i, j) in pairs {
+        if !old_matched[i] && !new_matched[j] {
+            old_matched[i] = true;
+            new_matched[j] = true;
+        }
+    }
+
+    let mut diff = DiagnosticDiff::default();
+    for (diag, matched) in new.diagnostics.iter().zip(new_matched) {
+        if matched {
+            diff.persisting.push(diag.clone());
+        } else {
+            diff.new.push(diag.clone());
+        }
+    }
+    diff.fixed = old
+        .diagnostics
+        .iter()
+        .zip(old_matched)
+        .filter(|(_, matched)| !matched)
+        .map(|(diag, _)| diag.clone())
+        .collect();
+    diff
+}
+
+/// Render the new and fixed diagnostics, and the number persisting.
+pub fn render_diagnostic_diff(diff: &DiagnosticDiff) -> String {
+    let mut out = String::new();
+    for (title, diagnostics) in [("New", &diff.new), ("Fixed", &diff.fixed)] {
+        if diagnostics.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("{} ({}):\n", title, diagnostics.len()));
+        for diag in diagnostics {
+            let severity = match diag.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Info => "info",
+                Severity::Hint => "hint",
+            };
+            let code = diag
+                .code
+                .as_ref()
+                .map(|c| format!("[{}]", c))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "  {}{}: {}:{}:{}: {}\n",
+                severity, code, diag.file, diag.line, diag.col, diag.message
+            ));
+        }
+    }
+    out.push_str(&format!("Persisting: {}\n", diff.persisting.len()));
+    out
+}
@@ -0,0 +1,84 @@
+//! Workspace-wide duplicate-definition detection.
+//!
+//! syster-base reports duplicate definitions within one file. Copying a
+//! file and forgetting to rename its definitions instead yields the same
+//! qualified name in several files, which per-file checking cannot see.
+//! This pass groups the definitions of all loaded files by qualified name
+//! and reports every occurrence of a name defined in more than one file,
+//! pointing at the other occurrences through related information.
+
+use super::{DiagnosticInfo, RelatedInformation};
+use std::collections::{BTreeMap, BTreeSet};
+use syster::hir::{HirSymbol, Severity, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// Report definitions whose qualified name is defined in more than one file.
+///
+/// Packages are skipped, as spreading a package over several files is
+/// legitimate. Each occurrence gets one diagnostic of `severity`.
+pub(crate) fn duplicate_definitions(
+    host: &AnalysisHost,
+    severity: Severity,
+) -> Vec<DiagnosticInfo> {
+    let mut by_name: BTreeMap<&str, Vec<(String, &HirSymbol)>> = BTreeMap::new();
+    for path in host.files().keys() {
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        let file = path.to_string_lossy().to_string();
+        for symbol in host.symbol_index().symbols_in_file(file_id) {
+            if symbol.kind.is_definition() && symbol.kind != SymbolKind::Package {
+                by_name
+                    .entry(&symbol.qualified_name)
+                    .or_default()
+                    .push((file.clone(), symbol));
+            }
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for (qualified_name, mut definitions) in by_name {
+        let file_count = definitions
+            .iter()
+            .map(|(file, _)| file.as_str())
+            .collect::<BTreeSet<_>>()
+            .len();
+        if file_count < 2 {
+            continue;
+        }
+        definitions.sort_by(|(a_file, a), (b_file, b)| {
+            (a_file, a.start_line, a.start_col).cmp(&(b_file, b.start_line, b.start_col))
+        });
+
+        for (i, (file, symbol)) in definitions.iter().enumerate() {
+            let related = definitions
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(_, (other_file, other))| RelatedInformation {
+                    file: other_file.clone(),
+                    line: other.start_line + 1,
+                    col: other.start_col + 1,
+                    message: format!("'{}' is also defined here", qualified_name),
+                })
+                .collect();
+            diagnostics.push(DiagnosticInfo {
+                file: file.clone(),
+                line: symbol.start_line + 1,
+                col: symbol.start_col + 1,
+                end_line: symbol.end_line + 1,
+                end_col: symbol.end_col + 1,
+                message: format!(
+                    "duplicate definition: '{}' is defined in {} files",
+                    qualified_name, file_count
+                ),
+                severity,
+                code: None,
+                blame: None,
+                owners: Vec::new(),
+                related,
+            });
+        }
+    }
+    diagnostics
+}
@@ -0,0 +1,117 @@
+//! Source extents of declarations.
+//!
+//! The span of a symbol covers only its name (or, for imports, comments and
+//! anonymous usages, what stands in for one). Passes that need the whole
+//! declaration — its body, members and terminating `;` — look it up here by
+//! the start of the symbol's span, from the file's syntax tree.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use syster::base::{FileId, LineIndex, TextRange};
+use syster::hir::HirSymbol;
+use syster::ide::AnalysisHost;
+use syster::syntax::{NormalizedElement, NormalizedRelKind, RowanNormalizedIter, SyntaxFile};
+
+/// The declarations of one file, keyed by the start of their symbol spans.
+pub(crate) struct Extents {
+    lines: LineIndex,
+    ranges: HashMap<(u32, u32), TextRange>,
+}
+
+impl Extents {
+    /// Extents of the declarations in `file`.
+    pub(crate) fn of_file(file: &SyntaxFile) -> Self {
+        let lines = file.line_index();
+        let mut ranges = HashMap::new();
+        if let Some(source) = file.source_file() {
+            let mut pending: Vec<NormalizedElement> = RowanNormalizedIter::new(&source).collect();
+            while let Some(element) = pending.pop() {
+                let (span, range, children) = match element {
+                    NormalizedElement::Package(p) => {
+                        (p.name_range.or(p.range), p.range, p.children)
+                    }
+                    NormalizedElement::Definition(d) => {
+                        (d.name_range.or(d.range), d.range, d.children)
+                    }
+                    NormalizedElement::Usage(u) => {
+                        // Anonymous usages are spanned by their first relationship
+                        let span = u.name_range.or_else(|| {
+                            u.relationships
+                                .iter()
+                                .find(|r| !matches!(r.kind, NormalizedRelKind::Expression))
+                                .and_then(|r| r.range)
+                                .or(u.range)
+                        });
+                        (span, u.range, u.children)
+                    }
+                    NormalizedElement::Import(i) => (i.path_range.or(i.range), i.range, Vec::new()),
+                    NormalizedElement::Alias(a) => (a.name_range.or(a.range), a.range, Vec::new()),
+                    NormalizedElement::Comment(c) => (c.range, c.range, Vec::new()),
+                    NormalizedElement::Dependency(d) => (d.range, d.range, Vec::new()),
+                    _ => continue,
+                };
+                if let (Some(span), Some(range)) = (span, range) {
+                    let start = lines.line_col(span.start());
+                    ranges.entry((start.line, start.col)).or_insert(range);
+                }
+                pending.extend(children);
+            }
+        }
+        Self { lines, ranges }
+    }
+
+    /// Extents of the declarations in the file of `file_id`, if the host
+    /// has its syntax tree.
+    pub(crate) fn of(host: &AnalysisHost, file_id: FileId) -> Option<Self> {
+        let path = host.get_file_path_buf(file_id)?;
+        host.files().get(&path).map(Self::of_file)
+    }
+
+    /// Byte range of the declaration of `symbol`.
+    pub(crate) fn range(&self, symbol: &HirSymbol) -> Option<Range<usize>> {
+        self.ranges
+            .get(&(symbol.start_line, symbol.start_col))
+            .map(|range| usize::from(range.start())..usize::from(range.end()))
+    }
+
+    /// First and last line (0-based) of the declaration of `symbol`; those
+    /// of its span if the declaration is unknown.
+    pub(crate) fn lines(&self, symbol: &HirSymbol) -> (u32, u32) {
+        match self.ranges.get(&(symbol.start_line, symbol.start_col)) {
+            Some(range) => (
+                self.lines.line_col(range.start()).line,
+                self.lines.line_col(range.end()).line,
+            ),
+            None => (symbol.start_line, symbol.end_line),
+        }
+    }
+}
+
+/// `text` without the byte `ranges`, which may nest. A removed declaration
+/// takes the rest of its line along when nothing else is left on it.
+pub(crate) fn remove_ranges(text: &str, mut ranges: Vec<Range<usize>>) -> String {
+    ranges.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+    let mut result = String::with_capacity(text.len());
+    let mut copied = 0;
+    for range in ranges {
+        if range.start < copied {
+            continue;
+        }
+        let mut start = range.start;
+        let mut end = range.end.min(text.len());
+        let before = &text[copied..start];
+        let indent = before.len() - before.trim_end_matches([' ', '\t']).len();
+        let line_start = start - indent == 0 || text[..start - indent].ends_with('\n');
+        let rest = &text[end..];
+        let trailing = rest.len() - rest.trim_start_matches([' ', '\t', '\r']).len();
+        let line_end = end + trailing == text.len() || rest[trailing..].starts_with('\n');
+        if line_start && line_end {
+            start -= indent;
+            end = (end + trailing + 1).min(text.len());
+        }
+        result.push_str(&text[copied..start]);
+        copied = end;
+    }
+    result.push_str(&text[copied..]);
+    result
+}
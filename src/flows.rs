@@ -0,0 +1,648 @@
+//! Port direction and flow consistency (`syster flows`).
+//!
+//! Every connection, interface and flow is checked against the directed
+//! features (`in`, `out`, `inout`) of the ports at its ends:
+//!
+//! - a connection must link each feature its two ends share from a
+//!   producer to a consumer: an `out` to an `in`, or either to an `inout`;
+//! - a flow must leave an end that produces something and enter an end that
+//!   consumes it; with `flow of Item` only features of that item count.
+//!
+//! A port typed `~Def` has the directions of `Def` reversed. An end that
+//! starts at a port rather than a part (`fuelIn`, not `engine.fuelIn`) is a
+//! port of the element declaring the connection, seen from inside: its `in`
+//! features produce and its `out` features consume, so delegating an `in`
+//! to the `in` of a nested part is consistent.
+//!
+//! The hops found are joined into end-to-end paths per signal — a directed
+//! feature of a port definition — from each port that first produces it to
+//! each port that finally consumes it.
+
+use super::AnalysisOptions;
+use super::names::qualify;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use syster::FileId;
+use syster::hir::{HirSymbol, RefKind, Resolver, SymbolIndex, SymbolKind};
+use syster::ide::AnalysisHost;
+use syster::parser::Direction;
+use syster::syntax::{NormalizedElement, NormalizedRelKind, RowanNormalizedIter, SyntaxFile};
+
+/// Direction of a feature, as seen from one end of a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortDirection {
+    In,
+    Out,
+    InOut,
+}
+
+impl PortDirection {
+    fn reversed(self) -> Self {
+        match self {
+            PortDirection::In => PortDirection::Out,
+            PortDirection::Out => PortDirection::In,
+            PortDirection::InOut => PortDirection::InOut,
+        }
+    }
+
+    fn produces(self) -> bool {
+        self != PortDirection::In
+    }
+
+    fn consumes(self) -> bool {
+        self != PortDirection::Out
+    }
+}
+
+impl From<Direction> for PortDirection {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::In => PortDirection::In,
+            Direction::Out => PortDirection::Out,
+            Direction::InOut => PortDirection::InOut,
+        }
+    }
+}
+
+impl fmt::Display for PortDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PortDirection::In => "in",
+            PortDirection::Out => "out",
+            PortDirection::InOut => "inout",
+        })
+    }
+}
+
+/// A signal passed from one port to another by a connection or flow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FlowHop {
+    /// Qualified name of the port producing the signal.
+    pub producer: String,
+    /// Qualified name of the port consuming the signal.
+    pub consumer: String,
+    /// Qualified name of the connection or flow, or how it is written if
+    /// it is anonymous.
+    pub via: String,
+    /// File declaring the connection or flow.
+    pub file: String,
+    /// Line of the connection or flow (1-based).
+    pub line: u32,
+}
+
+/// The hops and end-to-end paths of one signal.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignalFlow {
+    /// Qualified name of the directed feature carrying the signal, in the
+    /// definition of the producing port.
+    pub signal: String,
+    /// Every hop, in file and line order.
+    pub hops: Vec<FlowHop>,
+    /// Ports from each first producer to each last consumer.
+    pub paths: Vec<Vec<String>>,
+}
+
+/// An inconsistent connection or flow.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "issue")]
+pub enum FlowIssue {
+    /// Both ends of a connection produce, or both consume, a feature.
+    Direction {
+        via: String,
+        feature: String,
+        source: String,
+        source_direction: PortDirection,
+        target: String,
+        target_direction: PortDirection,
+        file: String,
+        line: u32,
+    },
+    /// A flow whose source produces nothing it could carry.
+    NoProducer {
+        via: String,
+        source: String,
+        file: String,
+        line: u32,
+    },
+    /// A flow whose target consumes nothing its source produces.
+    NoConsumer {
+        via: String,
+        target: String,
+        file: String,
+        line: u32,
+    },
+}
+
+impl fmt::Display for FlowIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowIssue::Direction {
+                via,
+                feature,
+                source,
+                source_direction,
+                target,
+                target_direction,
+                file,
+                line,
+            } => write!(
+                f,
+                "{}:{}: {}: '{}' is {} at {} and {} at {}",
+                file, line, via, feature, source_direction, source, target_direction, target
+            ),
+            FlowIssue::NoProducer {
+                via,
+                source,
+                file,
+                line,
+            } => write!(
+                f,
+                "{}:{}: {}: no producer, {} produces nothing the flow carries",
+                file, line, via, source
+            ),
+            FlowIssue::NoConsumer {
+                via,
+                target,
+                file,
+                line,
+            } => write!(
+                f,
+                "{}:{}: {}: no consumer, {} consumes nothing the flow carries",
+                file, line, via, target
+            ),
+        }
+    }
+}
+
+/// The signals of a model and the issues of its connections and flows.
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowReport {
+    /// Number of connections and flows checked.
+    pub connections: usize,
+    /// Signals, by qualified name.
+    pub signals: Vec<SignalFlow>,
+    /// Issues, in file and line order.
+    pub issues: Vec<FlowIssue>,
+}
+
+impl FlowReport {
+    /// Whether no issue was found.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Analyze `input` and check the directions of its connections and flows.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `options` - Verbosity and standard library to load
+pub fn check_flows(input: &Path, options: &AnalysisOptions) -> Result<FlowReport, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let library: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    super::load_input(&mut host, input, options.verbose)?;
+    let _analysis = host.analysis();
+
+    let mut paths: Vec<_> = host
+        .files()
+        .keys()
+        .filter(|path| !library.contains(&*path.to_string_lossy()))
+        .collect();
+    paths.sort();
+    let mut links = Vec::new();
+    let mut sources = HashMap::new();
+    for path in paths {
+        let file = &host.files()[path];
+        links.extend(file_links(&path.to_string_lossy(), file));
+        if let Some(id) = host.get_file_id_for_path(path) {
+            sources.insert(id, file.source_text());
+        }
+    }
+    let ports = Ports {
+        index: host.symbol_index(),
+        sources,
+    };
+
+    let mut hops: BTreeMap<String, Vec<FlowHop>> = BTreeMap::new();
+    let mut issues = Vec::new();
+    for link in &links {
+        let (Some(source), Some(target)) = (
+            ports.end(&link.scope, &link.source),
+            ports.end(&link.scope, &link.target),
+        ) else {
+            continue;
+        };
+        let hop = |producer: &End, consumer: &End| FlowHop {
+            producer: producer.port.clone(),
+            consumer: consumer.port.clone(),
+            via: link.via.clone(),
+            file: link.file.clone(),
+            line: link.line,
+        };
+
+        if !link.flow {
+            for (name, s) in &source.signals {
+                let Some(t) = target.signals.get(name) else {
+                    continue;
+                };
+                if s.direction.produces() && t.direction.consumes() {
+                    hops.entry(s.feature.clone())
+                        .or_default()
+                        .push(hop(&source, &target));
+                } else if t.direction.produces() && s.direction.consumes() {
+                    hops.entry(t.feature.clone())
+                        .or_default()
+                        .push(hop(&target, &source));
+                } else {
+                    issues.push(FlowIssue::Direction {
+                        via: link.via.clone(),
+                        feature: name.clone(),
+                        source: link.source.clone(),
+                        source_direction: s.direction,
+                        target: link.target.clone(),
+                        target_direction: t.direction,
+                        file: link.file.clone(),
+                        line: link.line,
+                    });
+                }
+            }
+            continue;
+        }
+
+        // Ends without directed features (e.g. parts) can't be checked
+        if source.signals.is_empty() {
+            continue;
+        }
+        let item = link
+            .item
+            .as_deref()
+            .and_then(|item| ports.item(&link.scope, item));
+        let produced: Vec<(&String, &Signal)> = source
+            .signals
+            .iter()
+            .filter(|(_, s)| s.direction.produces())
+            .filter(|(_, s)| item.is_none() || s.item == item)
+            .collect();
+        if produced.is_empty() {
+            issues.push(FlowIssue::NoProducer {
+                via: link.via.clone(),
+                source: link.source.clone(),
+                file: link.file.clone(),
+                line: link.line,
+            });
+            continue;
+        }
+        if target.signals.is_empty() {
+            continue;
+        }
+        let consumed: Vec<&Signal> = produced
+            .iter()
+            .filter(|(name, _)| {
+                target
+                    .signals
+                    .get(*name)
+                    .is_some_and(|t| t.direction.consumes())
+            })
+            .map(|(_, s)| *s)
+            .collect();
+        if consumed.is_empty() {
+            issues.push(FlowIssue::NoConsumer {
+                via: link.via.clone(),
+                target: link.target.clone(),
+                file: link.file.clone(),
+                line: link.line,
+            });
+        }
+        for signal in consumed {
+            hops.entry(signal.feature.clone())
+                .or_default()
+                .push(hop(&source, &target));
+        }
+    }
+
+    let signals: Vec<SignalFlow> = hops
+        .into_iter()
+        .map(|(signal, hops)| SignalFlow {
+            paths: end_to_end(&hops),
+            signal,
+            hops,
+        })
+        .collect();
+    if options.verbose {
+        tracing::info!(
+            "Checked {} connections and flows: {} signals, {} issues",
+            links.len(),
+            signals.len(),
+            issues.len()
+        );
+    }
+    Ok(FlowReport {
+        connections: links.len(),
+        signals,
+        issues,
+    })
+}
+
+/// A connection or flow as written.
+struct Link {
+    via: String,
+    flow: bool,
+    item: Option<String>,
+    scope: String,
+    source: String,
+    target: String,
+    file: String,
+    line: u32,
+}
+
+/// A directed feature at one end of a link.
+struct Signal {
+    feature: String,
+    item: Option<String>,
+    direction: PortDirection,
+}
+
+/// The port at (or nearest to) one end of a link and its directed features
+/// by name, with the directions seen from that end.
+struct End {
+    port: String,
+    signals: BTreeMap<String, Signal>,
+}
+
+/// The connections and flows declared in one file, with their scopes.
+fn file_links(path: &str, file: &SyntaxFile) -> Vec<Link> {
+    let Some(source) = file.source_file() else {
+        return Vec::new();
+    };
+    let lines = file.line_index();
+    let mut links = Vec::new();
+    let mut pending: Vec<(String, NormalizedElement)> = RowanNormalizedIter::new(&source)
+        .map(|element| (String::new(), element))
+        .collect();
+    pending.reverse();
+    while let Some((scope, element)) = pending.pop() {
+        let (name, children) = match element {
+            NormalizedElement::Package(p) => (p.name, p.children),
+            NormalizedElement::Definition(d) => (d.name, d.children),
+            NormalizedElement::Usage(u) => {
+                let end = |kind: NormalizedRelKind| {
+                    u.relationships
+                        .iter()
+                        .find(|r| r.kind == kind)
+                        .map(|r| r.target.as_str().into_owned())
+                };
+                let ends = match (
+                    end(NormalizedRelKind::ConnectSource),
+                    end(NormalizedRelKind::ConnectTarget),
+                ) {
+                    (Some(source), Some(target)) => Some((source, target, false)),
+                    _ => end(NormalizedRelKind::FlowSource)
+                        .zip(end(NormalizedRelKind::FlowTarget))
+                        .map(|(source, target)| (source, target, true)),
+                };
+                if let Some((source, target, flow)) = ends {
+                    let via = match &u.name {
+                        Some(name) => qualify(&scope, name),
+                        None if flow => format!("flow from {} to {}", source, target),
+                        None => format!("connect {} to {}", source, target),
+                    };
+                    links.push(Link {
+                        via,
+                        flow,
+                        item: flow.then(|| end(NormalizedRelKind::TypedBy)).flatten(),
+                        scope: scope.clone(),
+                        source,
+                        target,
+                        file: path.to_string(),
+                        line: u.range.map_or(0, |r| lines.line_col(r.start()).line) + 1,
+                    });
+                }
+                (u.name, u.children)
+            }
+            _ => continue,
+        };
+        let scope = match name {
+            Some(name) => qualify(&scope, &name),
+            None => scope,
+        };
+        pending.extend(
+            children
+                .into_iter()
+                .rev()
+                .map(|child| (scope.clone(), child)),
+        );
+    }
+    links
+}
+
+/// Resolves link ends to ports and their directed features.
+struct Ports<'a> {
+    index: &'a SymbolIndex,
+    /// Source of the model files, to tell `: ~Def` from `: Def`.
+    sources: HashMap<FileId, String>,
+}
+
+impl<'a> Ports<'a> {
+    /// The port an end written in `scope` resolves to, if it does.
+    fn end(&self, scope: &str, written: &str) -> Option<End> {
+        let parts: Vec<Arc<str>> = written.split('.').map(Arc::from).collect();
+        let first = Resolver::new(self.index)
+            .with_scope(scope)
+            .resolve(&parts[0])
+            .symbol()?
+            .qualified_name
+            .clone();
+        let mut chain = vec![self.index.lookup_qualified(&first)?];
+        for i in 1..parts.len() {
+            let member = self.index.resolve_feature_chain_member(scope, &parts, i)?;
+            chain.push(self.index.lookup_qualified(&member)?);
+        }
+
+        // Every conjugated port on the way reverses the directions below it,
+        // and an end starting at a port rather than a part is a port of the
+        // declaring element, seen from inside
+        let conjugated = chain
+            .iter()
+            .filter(|s| s.kind == SymbolKind::PortUsage && self.is_conjugated(s))
+            .count();
+        let inside = chain[0].kind == SymbolKind::PortUsage;
+        let reversed = (conjugated + usize::from(inside)) % 2 == 1;
+        let seen = |direction: Direction| {
+            let direction = PortDirection::from(direction);
+            if reversed {
+                direction.reversed()
+            } else {
+                direction
+            }
+        };
+
+        let last = chain[chain.len() - 1];
+        let features = if last.kind == SymbolKind::PortUsage {
+            let mut features = BTreeMap::new();
+            self.directed_features(last, &mut features, &mut HashSet::new());
+            features
+        } else if last.direction.is_some() {
+            BTreeMap::from([(last.name.to_string(), last)])
+        } else {
+            BTreeMap::new()
+        };
+        let signals = features
+            .into_iter()
+            .filter_map(|(name, feature)| {
+                let signal = Signal {
+                    feature: feature.qualified_name.to_string(),
+                    item: self
+                        .types(feature, RefKind::TypedBy)
+                        .first()
+                        .map(|t| t.qualified_name.to_string()),
+                    direction: seen(feature.direction?),
+                };
+                Some((name, signal))
+            })
+            .collect();
+        let port = chain
+            .iter()
+            .rfind(|s| s.kind == SymbolKind::PortUsage)
+            .unwrap_or(&last);
+        Some(End {
+            port: port.qualified_name.to_string(),
+            signals,
+        })
+    }
+
+    /// Directed features of `symbol` and of its types and supertypes; the
+    /// nearest declaration of a name wins.
+    fn directed_features(
+        &self,
+        symbol: &'a HirSymbol,
+        features: &mut BTreeMap<String, &'a HirSymbol>,
+        visited: &mut HashSet<String>,
+    ) {
+        if !visited.insert(symbol.qualified_name.to_string()) {
+            return;
+        }
+        if let Some(scope) = self.index.visibility_for_scope(&symbol.qualified_name) {
+            for (name, member) in scope.direct_defs() {
+                let Some(member) = self.index.lookup_qualified(member) else {
+                    continue;
+                };
+                if member.direction.is_some() {
+                    features.entry(name.to_string()).or_insert(member);
+                }
+            }
+        }
+        for kind in [RefKind::TypedBy, RefKind::Specializes] {
+            for general in self.types(symbol, kind) {
+                self.directed_features(general, features, visited);
+            }
+        }
+    }
+
+    /// The elements `symbol` refers to with references of `kind`.
+    fn types(&self, symbol: &HirSymbol, kind: RefKind) -> Vec<&'a HirSymbol> {
+        let scope = symbol
+            .qualified_name
+            .rsplit_once("::")
+            .map_or("", |(owner, _)| owner);
+        symbol
+            .type_refs
+            .iter()
+            .flat_map(|r| r.as_refs())
+            .filter(|r| r.kind == kind)
+            .filter_map(|r| {
+                let name = match &r.resolved_target {
+                    Some(target) => target.clone(),
+                    None => Resolver::new(self.index)
+                        .with_scope(scope)
+                        .resolve(&r.target)
+                        .symbol()?
+                        .qualified_name
+                        .clone(),
+                };
+                self.index.lookup_qualified(&name)
+            })
+            .collect()
+    }
+
+    /// Whether `port` is typed by a conjugated port definition (`: ~Def`).
+    fn is_conjugated(&self, port: &HirSymbol) -> bool {
+        let Some(text) = self.sources.get(&port.file) else {
+            return false;
+        };
+        let typing = port
+            .type_refs
+            .iter()
+            .flat_map(|r| r.as_refs())
+            .find(|r| r.kind == RefKind::TypedBy);
+        typing.is_some_and(|r| {
+            text.lines()
+                .nth(r.start_line as usize)
+                .and_then(|line| line.get(..r.start_col as usize))
+                .is_some_and(|before| before.trim_end().ends_with('~'))
+        })
+    }
+
+    /// The item a `flow of Item` carries, if `item` names one rather than a
+    /// connection or interface definition typing the flow.
+    fn item(&self, scope: &str, item: &str) -> Option<String> {
+        let result = Resolver::new(self.index).with_scope(scope).resolve(item);
+        let symbol = result.symbol()?;
+        let typing_flow = matches!(
+            symbol.kind,
+            SymbolKind::ConnectionDefinition
+                | SymbolKind::InterfaceDefinition
+                | SymbolKind::Association
+        );
+        (!typing_flow).then(|| symbol.qualified_name.to_string())
+    }
+}
+
+/// Join hops into paths from ports consuming nothing of the signal to
+/// ports producing nothing of it.
+fn end_to_end(hops: &[FlowHop]) -> Vec<Vec<String>> {
+    let mut next: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for hop in hops {
+        next.entry(&hop.producer).or_default().insert(&hop.consumer);
+    }
+    let consumers: HashSet<&str> = hops.iter().map(|hop| hop.consumer.as_str()).collect();
+    let mut pending: Vec<Vec<&str>> = next
+        .keys()
+        .rev()
+        .filter(|port| !consumers.contains(*port))
+        .map(|port| vec![*port])
+        .collect();
+
+    let mut paths = Vec::new();
+    while let Some(path) = pending.pop() {
+        let last = path[path.len() - 1];
+        // A port already on the path closes a loop; stop there
+        let follow: Vec<&str> = next
+            .get(last)
+            .into_iter()
+            .flatten()
+            .filter(|port| !path.contains(port))
+            .copied()
+            .collect();
+        if follow.is_empty() {
+            paths.push(path.iter().map(|port| port.to_string()).collect());
+            continue;
+        }
+        for port in follow.into_iter().rev() {
+            let mut longer = path.clone();
+            longer.push(port);
+            pending.push(longer);
+        }
+    }
+    paths
+}
@@ -0,0 +1,143 @@
+//! Glossary of the model's definitions.
+//!
+//! Every named definition is listed with its documentation under the
+//! package that owns it, optionally restricted to definitions carrying a
+//! given metadata annotation (e.g. `#Term`). Rendered with the code
+//! generation templates in `src/templates/`.
+
+use super::AnalysisOptions;
+use super::annotations::matches_metadata;
+use super::codegen::{CodegenElement, CodegenModel, DocumentFormat, render_template};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Glossary data, as passed to the templates.
+#[derive(Debug, Serialize)]
+pub struct Glossary {
+    /// Packages with at least one term, in qualified-name order.
+    pub packages: Vec<GlossaryPackage>,
+}
+
+/// The terms defined in one package.
+#[derive(Debug, Serialize)]
+pub struct GlossaryPackage {
+    /// Qualified name of the package, empty for top-level definitions.
+    pub qualified_name: String,
+    /// Terms sorted by name.
+    pub terms: Vec<GlossaryTerm>,
+}
+
+/// A single definition.
+#[derive(Debug, Serialize)]
+pub struct GlossaryTerm {
+    /// Simple name.
+    pub name: String,
+    /// Fully qualified name.
+    pub qualified_name: String,
+    /// Human-readable kind, e.g. `Part def`.
+    pub kind: String,
+    /// Documentation on a single line, empty if none.
+    pub doc: String,
+}
+
+/// Collect the glossary from a code generation model.
+///
+/// With `metadata`, only definitions annotated with that metadata (by simple
+/// or qualified name) are included.
+pub fn collect_glossary(model: &CodegenModel, metadata: Option<&str>) -> Glossary {
+    let mut packages: BTreeMap<String, Vec<GlossaryTerm>> = BTreeMap::new();
+    for element in &model.elements {
+        if !element.is_definition || element.name.is_empty() {
+            continue;
+        }
+        if let Some(wanted) = metadata {
+            let annotated = element.metadata.iter().any(|m| matches_metadata(m, wanted));
+            if !annotated {
+                continue;
+            }
+        }
+        packages
+            .entry(owning_package(model, element))
+            .or_default()
+            .push(GlossaryTerm {
+                name: element.name.clone(),
+                qualified_name: element.qualified_name.clone(),
+                kind: element.kind_label.clone(),
+                doc: element
+                    .doc
+                    .as_deref()
+                    .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" "))
+                    .unwrap_or_default(),
+            });
+    }
+
+    Glossary {
+        packages: packages
+            .into_iter()
+            .map(|(qualified_name, mut terms)| {
+                terms.sort_by(|a, b| {
+                    a.name
+                        .to_lowercase()
+                        .cmp(&b.name.to_lowercase())
+                        .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+                });
+                GlossaryPackage {
+                    qualified_name,
+                    terms,
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Qualified name of the innermost package enclosing `element`.
+fn owning_package(model: &CodegenModel, element: &CodegenElement) -> String {
+    let mut owner = element.owner.as_deref();
+    while let Some(name) = owner {
+        match model.by_name.get(name) {
+            Some(e) if e.kind == "Package" => return name.to_string(),
+            Some(e) => owner = e.owner.as_deref(),
+            None => owner = name.rsplit_once("::").map(|(parent, _)| parent),
+        }
+    }
+    String::new()
+}
+
+/// Render the glossary in `format`.
+pub fn render_glossary(glossary: &Glossary, format: DocumentFormat) -> Result<String, String> {
+    match format {
+        DocumentFormat::Markdown => render_template(
+            glossary,
+            "glossary.md",
+            include_str!("templates/glossary.md.tera"),
+        ),
+        DocumentFormat::Html => render_template(
+            glossary,
+            "glossary.html",
+            include_str!("templates/glossary.html.tera"),
+        ),
+        DocumentFormat::Csv => render_template(
+            glossary,
+            "glossary.csv",
+            include_str!("templates/glossary.csv.tera"),
+        ),
+    }
+}
+
+/// Analyze `input` and render its glossary.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `metadata` - Only include definitions carrying this metadata
+/// * `format` - Output format
+/// * `options` - Verbosity and standard library to load
+pub fn generate_glossary(
+    input: &Path,
+    metadata: Option<&str>,
+    format: DocumentFormat,
+    options: &AnalysisOptions,
+) -> Result<String, String> {
+    let model = CodegenModel::load(input, options)?;
+    render_glossary(&collect_glossary(&model, metadata), format)
+}
@@ -0,0 +1,292 @@
+//! Model health across git history.
+//!
+//! Every tagged revision is analyzed straight from the git object database,
+//! without touching the working tree: the SysML/KerML blobs of the tag are
+//! read with `git cat-file` and loaded into a fresh analysis host. The
+//! result is a time series of model size, diagnostic counts and
+//! documentation coverage.
+
+use super::coverage::doc_coverage;
+use super::{AnalysisOptions, collect_diagnostics};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use syster::hir::Severity;
+use syster::ide::AnalysisHost;
+
+/// A measurement taken at each revision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryMetric {
+    /// Number of SysML/KerML files.
+    Files,
+    /// Number of symbols.
+    Symbols,
+    /// Number of errors.
+    Errors,
+    /// Number of warnings.
+    Warnings,
+    /// Documented definitions, in percent.
+    Docs,
+}
+
+impl HistoryMetric {
+    /// Every metric, in column order.
+    pub const ALL: [HistoryMetric; 5] = [
+        Self::Files,
+        Self::Symbols,
+        Self::Errors,
+        Self::Warnings,
+        Self::Docs,
+    ];
+
+    /// Column (CSV) or field (JSON) name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Files => "files",
+            Self::Symbols => "symbols",
+            Self::Errors => "errors",
+            Self::Warnings => "warnings",
+            Self::Docs => "docs",
+        }
+    }
+
+    fn value(self, point: &HistoryPoint) -> Value {
+        match self {
+            Self::Files => point.file_count.into(),
+            Self::Symbols => point.symbol_count.into(),
+            Self::Errors => point.error_count.into(),
+            Self::Warnings => point.warning_count.into(),
+            Self::Docs => ((point.doc_coverage * 10.0).round() / 10.0).into(),
+        }
+    }
+}
+
+/// Analysis results for one tagged revision.
+#[derive(Debug, Clone)]
+pub struct HistoryPoint {
+    /// Tag name.
+    pub revision: String,
+    /// Abbreviated commit hash.
+    pub commit: String,
+    /// Commit date (`YYYY-MM-DD`).
+    pub date: String,
+    /// Number of SysML/KerML files at the revision.
+    pub file_count: usize,
+    /// Number of symbols, standard library included.
+    pub symbol_count: usize,
+    /// Number of errors.
+    pub error_count: usize,
+    /// Number of warnings.
+    pub warning_count: usize,
+    /// Share of the model's definitions with documentation, in percent.
+    pub doc_coverage: f64,
+}
+
+/// Analyze every tag of the repository containing `src`, oldest first.
+///
+/// Only files under `src` are analyzed. The standard library is loaded from
+/// the current location, not from history.
+///
+/// # Arguments
+/// * `src` - Model directory inside a git work tree
+/// * `since` - First tag to analyze; all tags if `None`
+/// * `options` - Verbosity and standard library to load; other settings
+///   don't apply
+pub fn analyze_history(
+    src: &Path,
+    since: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<Vec<HistoryPoint>, String> {
+    let verbose = options.verbose;
+    if !src.is_dir() {
+        return Err(format!("Not a directory: {}", src.display()));
+    }
+
+    let tags = git(src, &["tag", "--list", "--sort=creatordate"])?;
+    let mut tags: Vec<&str> = tags.lines().filter(|t| !t.is_empty()).collect();
+    if let Some(since) = since {
+        let start = tags
+            .iter()
+            .position(|t| *t == since)
+            .ok_or_else(|| format!("Unknown tag: {}", since))?;
+        tags.drain(..start);
+    }
+
+    let mut points = Vec::new();
+    for tag in tags {
+        if verbose {
+            tracing::info!("Analyzing {}", tag);
+        }
+        let mut host = AnalysisHost::new();
+        if options.load_stdlib {
+            super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), verbose)?;
+        }
+
+        let files = model_blobs(src, tag)?;
+        for (path, content) in &files {
+            host.set_file_content(path, content);
+        }
+        let _analysis = host.analysis();
+
+        let diagnostics = collect_diagnostics(&host);
+        let count = |severity: Severity| {
+            diagnostics
+                .iter()
+                .filter(|d| d.severity == severity)
+                .count()
+        };
+        let paths: HashSet<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+        let (coverage, _) = doc_coverage(&host, &paths, &[]);
+        let info = git(src, &["log", "-1", "--format=%h %cs", tag])?;
+        let (commit, date) = info.trim().split_once(' ').unwrap_or((info.trim(), ""));
+
+        points.push(HistoryPoint {
+            revision: tag.to_string(),
+            commit: commit.to_string(),
+            date: date.to_string(),
+            file_count: files.len(),
+            symbol_count: host.symbol_index().all_symbols().count(),
+            error_count: count(Severity::Error),
+            warning_count: count(Severity::Warning),
+            doc_coverage: coverage.percent,
+        });
+    }
+    Ok(points)
+}
+
+/// SysML/KerML files under `src` at `revision`, as `(path, content)`.
+fn model_blobs(src: &Path, revision: &str) -> Result<Vec<(String, String)>, String> {
+    // Paths are listed relative to `src`, restricted to it
+    let listing = git(src, &["ls-tree", "-r", "-z", revision, "."])?;
+    let blobs: Vec<(&str, &str)> = listing
+        .split('\0')
+        .filter_map(|line| {
+            let (meta, path) = line.split_once('\t')?;
+            let mut meta = meta.split_whitespace();
+            let (kind, oid) = (meta.nth(1)?, meta.next()?);
+            let is_model = path.ends_with(".sysml") || path.ends_with(".kerml");
+            (kind == "blob" && is_model).then_some((oid, path))
+        })
+        .collect();
+    let files = read_blobs(src, &blobs)?;
+    Ok(files
+        .into_iter()
+        .map(|(path, content)| (format!("{}/{}", revision, path), content))
+        .collect())
+}
+
+/// Read blobs given as `(object id, path)` with a single `git cat-file`,
+/// returning `(path, content)` in the same order.
+pub(crate) fn read_blobs(
+    dir: &Path,
+    blobs: &[(&str, &str)],
+) -> Result<Vec<(String, String)>, String> {
+    if blobs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["cat-file", "--batch"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+
+    // Request every blob up front; git answers in order
+    let request: String = blobs.iter().map(|(oid, _)| format!("{}\n", oid)).collect();
+    let mut stdin = child.stdin.take().ok_or("Failed to open git stdin")?;
+    let writer = std::thread::spawn(move || stdin.write_all(request.as_bytes()));
+
+    let mut reader = BufReader::new(child.stdout.take().ok_or("Failed to open git stdout")?);
+    let mut files = Vec::new();
+    for (oid, path) in blobs {
+        // Header: "<oid> blob <size>", then the content and a newline
+        let mut header = String::new();
+        reader
+            .read_line(&mut header)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        let size: usize = header
+            .split_whitespace()
+            .nth(2)
+            .and_then(|s| s.parse().ok())
+            .ok_or_else(|| format!("Unexpected git output for {}: {}", oid, header.trim()))?;
+        let mut content = vec![0; size + 1];
+        reader
+            .read_exact(&mut content)
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+        content.truncate(size);
+        files.push((
+            path.to_string(),
+            String::from_utf8_lossy(&content).into_owned(),
+        ));
+    }
+
+    writer
+        .join()
+        .map_err(|_| "Failed to write to git".to_string())?
+        .map_err(|e| format!("Failed to write to git: {}", e))?;
+    child
+        .wait()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    Ok(files)
+}
+
+/// Run git in `dir` and return its standard output.
+pub(crate) fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Render the time series as CSV: revision, commit, date and one column per
+/// metric.
+pub fn history_csv(points: &[HistoryPoint], metrics: &[HistoryMetric]) -> String {
+    let mut header = vec!["revision", "commit", "date"];
+    header.extend(metrics.iter().map(|m| m.name()));
+    let mut csv = header.join(",");
+    csv.push('\n');
+    for point in points {
+        let mut row = vec![
+            point.revision.clone(),
+            point.commit.clone(),
+            point.date.clone(),
+        ];
+        row.extend(metrics.iter().map(|m| m.value(point).to_string()));
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Render the time series as a JSON array with one object per revision.
+pub fn history_json(points: &[HistoryPoint], metrics: &[HistoryMetric]) -> Result<String, String> {
+    let series: Vec<Value> = points
+        .iter()
+        .map(|point| {
+            let mut object = Map::new();
+            object.insert("revision".into(), point.revision.clone().into());
+            object.insert("commit".into(), point.commit.clone().into());
+            object.insert("date".into(), point.date.clone().into());
+            for metric in metrics {
+                object.insert(metric.name().into(), metric.value(point));
+            }
+            Value::Object(object)
+        })
+        .collect();
+    serde_json::to_string_pretty(&series).map_err(|e| format!("Failed to serialize history: {}", e))
+}
@@ -0,0 +1,189 @@
+//! Hover information for editors.
+//!
+//! [`hover`] answers "what is at this position?" the way an editor tooltip
+//! does: the element's signature and doc comment as Markdown, followed by
+//! what it specializes and what its type resolves to. Hovering a type
+//! reference describes the referenced element. Positions are 1-based, like
+//! diagnostics.
+
+use super::AnalysisOptions;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use syster::hir::{HirSymbol, RefKind};
+use syster::ide::AnalysisHost;
+
+/// A `FILE:LINE:COL` position (1-based).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourcePosition {
+    /// Source file.
+    pub file: PathBuf,
+    /// Line (1-indexed).
+    pub line: u32,
+    /// Column (1-indexed).
+    pub col: u32,
+}
+
+impl FromStr for SourcePosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid position '{}': expected FILE:LINE:COL", s);
+        // Split from the right so Windows drive letters stay in the path
+        let mut parts = s.rsplitn(3, ':');
+        let col = parts.next().and_then(|c| c.parse().ok());
+        let line = parts.next().and_then(|l| l.parse().ok());
+        let file = parts.next().filter(|f| !f.is_empty());
+        match (file, line, col) {
+            (Some(file), Some(line), Some(col)) if line > 0 && col > 0 => Ok(Self {
+                file: PathBuf::from(file),
+                line,
+                col,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// What an editor shows when hovering a position.
+#[derive(Debug, Clone, Serialize)]
+pub struct HoverInfo {
+    /// Qualified name of the described element (`None` for an unresolved
+    /// reference).
+    pub qualified_name: Option<String>,
+    /// Signature, documentation, supertypes and type, as Markdown.
+    pub contents: String,
+    /// Qualified names of the elements it specializes or subsets.
+    pub supertypes: Vec<String>,
+    /// Qualified names of its types, resolved.
+    pub types: Vec<String>,
+    /// Start line of the hovered range (1-indexed).
+    pub start_line: u32,
+    /// Start column (1-indexed).
+    pub start_col: u32,
+    /// End line (1-indexed).
+    pub end_line: u32,
+    /// End column (1-indexed).
+    pub end_col: u32,
+}
+
+/// Describe the element at `position`, analyzing it in the context of
+/// `src` (the file's directory if `None`).
+///
+/// Returns `None` if there is nothing at the position.
+///
+/// # Arguments
+/// * `src` - Model file or directory the file belongs to
+/// * `position` - File and 1-based line and column
+/// * `options` - Verbosity and standard library to load
+pub fn hover(
+    src: Option<&Path>,
+    position: &SourcePosition,
+    options: &AnalysisOptions,
+) -> Result<Option<HoverInfo>, String> {
+    let (mut host, path) = load_file(src, &position.file, options)?;
+    let analysis = host.analysis();
+    let file_id = analysis
+        .get_file_id(&path.to_string_lossy())
+        .ok_or_else(|| format!("{} is not a SysML or KerML file", path.display()))?;
+    let Some(result) = analysis.hover(
+        file_id,
+        position.line.saturating_sub(1),
+        position.col.saturating_sub(1),
+    ) else {
+        return Ok(None);
+    };
+
+    let index = analysis.symbol_index();
+    let symbol = result
+        .qualified_name
+        .as_ref()
+        .and_then(|name| index.lookup_qualified(name));
+    let (supertypes, types) = symbol.map(relations).unwrap_or_default();
+
+    let mut contents = result.contents.trim_end().to_string();
+    if !supertypes.is_empty() {
+        let _ = write!(contents, "\n\n**Specializes:** {}", code_list(&supertypes));
+    }
+    if !types.is_empty() {
+        let _ = write!(contents, "\n\n**Type:** {}", code_list(&types));
+    }
+    contents.push('\n');
+
+    Ok(Some(HoverInfo {
+        qualified_name: result.qualified_name.map(|name| name.to_string()),
+        contents,
+        supertypes,
+        types,
+        start_line: result.start_line + 1,
+        start_col: result.start_col + 1,
+        end_line: result.end_line + 1,
+        end_col: result.end_col + 1,
+    }))
+}
+
+/// Load `file` with the model in `src` (the file's directory if `None`),
+/// returning the host and the path the file was loaded under.
+pub(crate) fn load_file(
+    src: Option<&Path>,
+    file: &Path,
+    options: &AnalysisOptions,
+) -> Result<(AnalysisHost, PathBuf), String> {
+    let file = std::fs::canonicalize(file)
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    match src {
+        Some(src) => super::load_input(&mut host, src, options.verbose)?,
+        None => super::load_input(
+            &mut host,
+            file.parent().unwrap_or(Path::new(".")),
+            options.verbose,
+        )?,
+    }
+    let loaded = host
+        .files()
+        .keys()
+        .find(|path| std::fs::canonicalize(path).is_ok_and(|p| p == file))
+        .cloned();
+    match loaded {
+        Some(path) => Ok((host, path)),
+        None => {
+            super::load_input(&mut host, &file, options.verbose)?;
+            Ok((host, file))
+        }
+    }
+}
+
+/// Resolved supertypes and types of a symbol.
+fn relations(symbol: &HirSymbol) -> (Vec<String>, Vec<String>) {
+    let (mut supertypes, mut types) = (Vec::new(), Vec::new());
+    for type_ref in symbol.type_refs.iter().flat_map(|r| r.as_refs()) {
+        let target = type_ref
+            .resolved_target
+            .as_ref()
+            .unwrap_or(&type_ref.target)
+            .to_string();
+        let list = match type_ref.kind {
+            RefKind::Specializes | RefKind::Subsets => &mut supertypes,
+            RefKind::TypedBy => &mut types,
+            _ => continue,
+        };
+        if !list.contains(&target) {
+            list.push(target);
+        }
+    }
+    (supertypes, types)
+}
+
+/// `` `A`, `B` ``.
+fn code_list(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| format!("`{}`", name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,148 @@
+//! JSON requests to web APIs (Jira, SysML v2 API repositories, the remote
+//! result cache).
+//!
+//! Requests are sent with `curl`, like the Pushgateway push. Credentials are
+//! passed to curl as a config file on its stdin, so they don't show up in
+//! the process list; bodies, which can be large, are uploaded from a
+//! temporary file.
+
+use serde_json::Value;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How a request is authenticated.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Auth<'a> {
+    /// HTTP Basic with `user:password`.
+    #[cfg(feature = "jira")]
+    Basic(&'a str),
+    /// A bearer token.
+    #[cfg(feature = "interchange")]
+    Bearer(&'a str),
+}
+
+/// Send a request and parse its JSON response (`null` if empty).
+pub(crate) fn request(
+    method: &str,
+    url: &str,
+    auth: Option<Auth>,
+    body: Option<&Value>,
+) -> Result<Value, String> {
+    let mut config = format!(
+        "url = {}\nrequest = {}\nheader = \"Accept: application/json\"\n",
+        curl_quote(url),
+        method
+    );
+    match auth {
+        #[cfg(feature = "jira")]
+        Some(Auth::Basic(credentials)) => {
+            config.push_str(&format!("user = {}\n", curl_quote(credentials)));
+        }
+        #[cfg(feature = "interchange")]
+        Some(Auth::Bearer(token)) => {
+            let header = format!("Authorization: Bearer {}", token);
+            config.push_str(&format!("header = {}\n", curl_quote(&header)));
+        }
+        None => {}
+    }
+    let body = body.map(BodyFile::write).transpose()?;
+    if body.is_some() {
+        config.push_str("header = \"Content-Type: application/json\"\n");
+    }
+
+    let mut command = Command::new("curl");
+    command.args(["--fail", "--silent", "--show-error", "--config", "-"]);
+    if let Some(body) = &body {
+        command
+            .arg("--data-binary")
+            .arg(format!("@{}", body.0.display()));
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.as_bytes())
+            .map_err(|e| format!("Failed to send request to curl: {}", e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} {} failed: {}",
+            method,
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    if output.stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Value::Null);
+    }
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Invalid response from {}: {}", url, e))
+}
+
+/// A request body written to a temporary file, removed when dropped.
+struct BodyFile(PathBuf);
+
+impl BodyFile {
+    fn write(body: &Value) -> Result<Self, String> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "syster-body-{}-{}.json",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, body.to_string())
+            .map_err(|e| format!("Failed to write request body {}: {}", path.display(), e))?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for BodyFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Send a `GET` request and parse its JSON response, or `None` if there is
+/// nothing at `url` (404).
+#[cfg(feature = "interchange")]
+pub(crate) fn get_if_found(url: &str, auth: Option<Auth>) -> Result<Option<Value>, String> {
+    match request("GET", url, auth, None) {
+        Ok(value) => Ok(Some(value)),
+        // curl --fail reports "The requested URL returned error: 404"
+        Err(e) if e.contains("error: 404") => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Percent-encode a query parameter value.
+pub(crate) fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Quote a value for a curl config file.
+fn curl_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t");
+    format!("\"{}\"", escaped)
+}
@@ -0,0 +1,234 @@
+//! Localized report text (`--lang`).
+//!
+//! The text of analysis reports — severities, summaries and the messages
+//! of common diagnostics — comes from [Fluent](https://projectfluent.org)
+//! message catalogs in `locales/`, one per language, built into the binary.
+//! Diagnostic codes, file locations and machine-readable output stay the
+//! same in every language.
+//!
+//! Diagnostics are produced in English. A catalog translates one by
+//! matching its message against the English pattern for its code
+//! (`diag-E0001`, ...) to recover the pattern's variables, and formatting
+//! the translation with them; messages without a pattern, or that do not
+//! match it, stay English. So does any text a catalog lacks.
+
+use super::DiagnosticInfo;
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, PatternElement};
+use regex::Regex;
+use std::fmt;
+use std::str::FromStr;
+use syster::hir::Severity;
+use unic_langid::LanguageIdentifier;
+
+/// Prefix of the catalog entries for diagnostic messages, followed by the
+/// diagnostic's code.
+const DIAGNOSTIC_PREFIX: &str = "diag-";
+
+/// A language reports can be written in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    En,
+    De,
+    Fr,
+    Ja,
+}
+
+impl Lang {
+    /// Every supported language.
+    pub const ALL: [Lang; 4] = [Lang::En, Lang::De, Lang::Fr, Lang::Ja];
+
+    /// ISO 639-1 code.
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::De => "de",
+            Lang::Fr => "fr",
+            Lang::Ja => "ja",
+        }
+    }
+
+    /// The language's catalog, in Fluent syntax.
+    fn source(self) -> &'static str {
+        match self {
+            Lang::En => include_str!("../locales/en.ftl"),
+            Lang::De => include_str!("../locales/de.ftl"),
+            Lang::Fr => include_str!("../locales/fr.ftl"),
+            Lang::Ja => include_str!("../locales/ja.ftl"),
+        }
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lang::ALL
+            .into_iter()
+            .find(|lang| lang.code().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown language '{}': expected one of: en, de, fr, ja", s))
+    }
+}
+
+/// The report text of one language, falling back to English.
+pub struct Catalog {
+    lang: Lang,
+    bundle: FluentBundle<FluentResource>,
+    english: FluentBundle<FluentResource>,
+    /// English diagnostic patterns: code, pattern and variable names.
+    patterns: Vec<(String, Regex, Vec<String>)>,
+}
+
+impl Catalog {
+    /// Load the catalog of `lang`.
+    pub fn new(lang: Lang) -> Result<Self, String> {
+        Ok(Self {
+            lang,
+            bundle: bundle(lang)?,
+            english: bundle(Lang::En)?,
+            patterns: diagnostic_patterns()?,
+        })
+    }
+
+    /// The English catalog.
+    pub fn english() -> Self {
+        Self::new(Lang::En).expect("the English catalog is valid")
+    }
+
+    /// The catalog's language.
+    pub fn lang(&self) -> Lang {
+        self.lang
+    }
+
+    /// Format the message `id` with `args`; the English text if the
+    /// language lacks it, and `id` itself if English does too.
+    pub fn text(&self, id: &str, args: &[(&str, String)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        [&self.bundle, &self.english]
+            .into_iter()
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+                Some(text.into_owned())
+            })
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// Name of a severity, as diagnostics are prefixed with.
+    pub fn severity(&self, severity: Severity) -> String {
+        let id = match severity {
+            Severity::Error => "severity-error",
+            Severity::Warning => "severity-warning",
+            Severity::Info => "severity-info",
+            Severity::Hint => "severity-hint",
+        };
+        self.text(id, &[])
+    }
+
+    /// The message of `diag`, translated if its code has a pattern the
+    /// English message matches.
+    pub fn diagnostic_message(&self, diag: &DiagnosticInfo) -> String {
+        if self.lang == Lang::En {
+            return diag.message.clone();
+        }
+        let Some(code) = diag.code.as_deref() else {
+            return diag.message.clone();
+        };
+        let Some((_, regex, names)) = self.patterns.iter().find(|(c, _, _)| c == code) else {
+            return diag.message.clone();
+        };
+        let Some(captures) = regex.captures(&diag.message) else {
+            return diag.message.clone();
+        };
+        let args: Vec<(&str, String)> = names
+            .iter()
+            .zip(captures.iter().skip(1))
+            .map(|(name, value)| {
+                (
+                    name.as_str(),
+                    value.map_or(String::new(), |v| v.as_str().to_string()),
+                )
+            })
+            .collect();
+        self.text(&format!("{}{}", DIAGNOSTIC_PREFIX, code), &args)
+    }
+}
+
+impl fmt::Debug for Catalog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Catalog").field("lang", &self.lang).finish()
+    }
+}
+
+fn parse(lang: Lang) -> Result<FluentResource, String> {
+    FluentResource::try_new(lang.source().to_string())
+        .map_err(|(_, errors)| format!("Invalid {} catalog: {:?}", lang, errors))
+}
+
+fn bundle(lang: Lang) -> Result<FluentBundle<FluentResource>, String> {
+    let id: LanguageIdentifier = lang
+        .code()
+        .parse()
+        .map_err(|e| format!("Invalid language {}: {}", lang, e))?;
+    let mut bundle = FluentBundle::new(vec![id]);
+    // Unicode isolation marks around placeables would end up in terminals
+    // and log files
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(parse(lang)?)
+        .map_err(|errors| format!("Invalid {} catalog: {:?}", lang, errors))?;
+    Ok(bundle)
+}
+
+/// Compile the English `diag-*` patterns into regular expressions with a
+/// group per variable.
+fn diagnostic_patterns() -> Result<Vec<(String, Regex, Vec<String>)>, String> {
+    let resource = parse(Lang::En)?;
+    let mut patterns = Vec::new();
+    for entry in resource.entries() {
+        let Entry::Message(message) = entry else {
+            continue;
+        };
+        let Some(code) = message.id.name.strip_prefix(DIAGNOSTIC_PREFIX) else {
+            continue;
+        };
+        let Some(value) = &message.value else {
+            continue;
+        };
+        let mut regex = String::from("^");
+        let mut names = Vec::new();
+        for element in &value.elements {
+            match element {
+                PatternElement::TextElement { value } => regex.push_str(&regex::escape(value)),
+                PatternElement::Placeable {
+                    expression: Expression::Inline(InlineExpression::VariableReference { id }),
+                } => {
+                    regex.push_str("(.+?)");
+                    names.push(id.name.to_string());
+                }
+                PatternElement::Placeable { .. } => {
+                    return Err(format!(
+                        "{}{}: only variables can be matched",
+                        DIAGNOSTIC_PREFIX, code
+                    ));
+                }
+            }
+        }
+        regex.push('$');
+        let regex =
+            Regex::new(&regex).map_err(|e| format!("{}{}: {}", DIAGNOSTIC_PREFIX, code, e))?;
+        patterns.push((code.to_string(), regex, names));
+    }
+    Ok(patterns)
+}
@@ -0,0 +1,169 @@
+//! Built-in interface control document (ICD) generator.
+//!
+//! Every port and interface definition is listed with its features
+//! (attributes, items, nested ports) including direction, type and
+//! multiplicity, followed by the connections, interfaces and flows that use
+//! it. The document is rendered with the code generation templates in
+//! `src/templates/`.
+
+use super::codegen::{CodegenElement, CodegenModel, DocumentFormat, render_template};
+use super::{AnalysisOptions, single_line};
+use serde::Serialize;
+use std::path::Path;
+
+/// Interface control document data, as passed to the templates.
+#[derive(Debug, Serialize)]
+pub struct IcdReport {
+    /// Port and interface definitions, in qualified-name order.
+    pub interfaces: Vec<IcdInterface>,
+}
+
+/// A port or interface definition.
+#[derive(Debug, Serialize)]
+pub struct IcdInterface {
+    /// Simple name.
+    pub name: String,
+    /// Fully qualified name.
+    pub qualified_name: String,
+    /// `port` or `interface`.
+    pub kind: String,
+    /// Documentation, empty if none.
+    pub doc: String,
+    /// Owned features in declaration order.
+    pub features: Vec<IcdFeature>,
+    /// Connections, interfaces and flows using the definition.
+    pub connections: Vec<IcdConnection>,
+}
+
+/// A feature of an interface. Missing values are empty strings.
+#[derive(Debug, Serialize)]
+pub struct IcdFeature {
+    /// Feature name.
+    pub name: String,
+    /// Kind, e.g. `attribute`, `item` or `port`.
+    pub kind: String,
+    /// `in`, `out` or `inout`.
+    pub direction: String,
+    /// Type name (qualified if resolved).
+    pub type_name: String,
+    /// Multiplicity, e.g. `0..*`.
+    pub multiplicity: String,
+    /// Documentation on a single line.
+    pub doc: String,
+}
+
+/// A connection, interface or flow using an interface definition.
+#[derive(Debug, Serialize)]
+pub struct IcdConnection {
+    /// Qualified name of the connection.
+    pub qualified_name: String,
+    /// `connection`, `interface` or `flow`.
+    pub kind: String,
+    /// Linked features.
+    pub ends: Vec<String>,
+}
+
+/// Collect the ICD data from a code generation model.
+pub fn interface_control_document(model: &CodegenModel) -> IcdReport {
+    let connections: Vec<&CodegenElement> = model
+        .elements
+        .iter()
+        .filter(|e| !e.ends.is_empty() || e.kind == "InterfaceUsage")
+        .collect();
+
+    let interfaces = model
+        .elements
+        .iter()
+        .filter(|e| matches!(e.kind.as_str(), "PortDefinition" | "InterfaceDefinition"))
+        .map(|def| {
+            let features = def
+                .members
+                .iter()
+                .filter_map(|name| model.by_name.get(name))
+                .filter(|m| m.kind.ends_with("Usage"))
+                .map(|m| IcdFeature {
+                    name: m.name.clone(),
+                    kind: short_kind(&m.kind),
+                    direction: m.direction.clone().unwrap_or_default(),
+                    type_name: m.types.join(", "),
+                    multiplicity: m.multiplicity.clone().unwrap_or_default(),
+                    doc: single_line(m.doc.as_deref()),
+                })
+                .collect();
+
+            // A connection uses the definition if it is typed by it or links
+            // a feature typed by it
+            let uses = |c: &CodegenElement| {
+                c.types.contains(&def.qualified_name)
+                    || c.ends.iter().any(|end| {
+                        model
+                            .by_name
+                            .get(end)
+                            .is_some_and(|e| e.types.contains(&def.qualified_name))
+                    })
+            };
+            let connections = connections
+                .iter()
+                .filter(|c| uses(c))
+                .map(|c| IcdConnection {
+                    qualified_name: c.qualified_name.clone(),
+                    kind: short_kind(&c.kind),
+                    ends: c.ends.clone(),
+                })
+                .collect();
+
+            IcdInterface {
+                name: def.name.clone(),
+                qualified_name: def.qualified_name.clone(),
+                kind: short_kind(&def.kind),
+                doc: def.doc.clone().unwrap_or_default(),
+                features,
+                connections,
+            }
+        })
+        .collect();
+
+    IcdReport { interfaces }
+}
+
+/// Render the ICD in `format`.
+pub fn render_icd(report: &IcdReport, format: DocumentFormat) -> Result<String, String> {
+    match format {
+        DocumentFormat::Markdown => {
+            render_template(report, "icd.md", include_str!("templates/icd.md.tera"))
+        }
+        DocumentFormat::Html => {
+            render_template(report, "icd.html", include_str!("templates/icd.html.tera"))
+        }
+        DocumentFormat::Csv => {
+            render_template(report, "icd.csv", include_str!("templates/icd.csv.tera"))
+        }
+    }
+}
+
+/// Analyze `input` and render its ICD.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `format` - Output format
+/// * `options` - Verbosity and standard library to load
+pub fn generate_icd(
+    input: &Path,
+    format: DocumentFormat,
+    options: &AnalysisOptions,
+) -> Result<String, String> {
+    let model = CodegenModel::load(input, options)?;
+    render_icd(&interface_control_document(&model), format)
+}
+
+/// `PortDefinition` becomes `port`, `FlowConnectionUsage` becomes `flow`.
+fn short_kind(kind: &str) -> String {
+    let kind = kind
+        .strip_suffix("Definition")
+        .or_else(|| kind.strip_suffix("Usage"))
+        .unwrap_or(kind);
+    match kind {
+        "FlowConnection" => "flow".to_string(),
+        other => other.to_lowercase(),
+    }
+}
@@ -0,0 +1,133 @@
+//! Element ID maps (`syster ids`).
+//!
+//! Lists every declaration of a model with the element ID an export gives it
+//! and where it is declared, so test management or PLM tools can refer to
+//! model elements by an ID that survives edits and round trips. IDs come
+//! from the same companion metadata that [`export_model`](super::export_model)
+//! restores them from. Declarations without one are listed with a freshly
+//! generated ID, which changes from run to run, and marked as not restored.
+//!
+//! Exports can also derive IDs from qualified names instead, see
+//! [`IdPolicy`].
+
+use super::{AnalysisOptions, csv_field, load_with_metadata, to_hex};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use syster::hir::{HirSymbol, SymbolKind};
+use syster::ide::AnalysisHost;
+use uuid::Uuid;
+
+/// Namespace of [`IdPolicy::UuidV5Namespace`] IDs when none is given: the
+/// name-based UUID of `https://github.com/jade-codes/syster-cli` in the
+/// RFC 4122 URL namespace.
+pub const DEFAULT_ID_NAMESPACE: &str = "532784a1-493a-56ae-9415-5e7c6d82eb73";
+
+/// How an export assigns element IDs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdPolicy {
+    /// Restore IDs from companion metadata, generating random ones for the
+    /// other elements.
+    #[default]
+    Preserve,
+    /// A random UUID (version 4) for every element, ignoring metadata.
+    UuidV4,
+    /// A name-based UUID (version 5) of the qualified name in a namespace,
+    /// so the same element always gets the same ID.
+    UuidV5Namespace,
+    /// The first 128 bits of the SHA-256 of the qualified name, in hex.
+    QualifiedNameHash,
+}
+
+/// Replace the element IDs of `symbols` as `policy` says. `namespace` is
+/// the UUID namespace of [`IdPolicy::UuidV5Namespace`] (default:
+/// [`DEFAULT_ID_NAMESPACE`]).
+pub(crate) fn apply_id_policy(
+    symbols: &mut [HirSymbol],
+    policy: IdPolicy,
+    namespace: Option<&str>,
+) -> Result<(), String> {
+    let namespace = Uuid::parse_str(namespace.unwrap_or(DEFAULT_ID_NAMESPACE))
+        .map_err(|e| format!("Invalid ID namespace: {}", e))?;
+    for sym in symbols {
+        let qualified_name = sym.qualified_name.as_bytes();
+        sym.element_id = match policy {
+            IdPolicy::Preserve => continue,
+            IdPolicy::UuidV4 => Uuid::new_v4().to_string(),
+            IdPolicy::UuidV5Namespace => Uuid::new_v5(&namespace, qualified_name).to_string(),
+            IdPolicy::QualifiedNameHash => to_hex(&Sha256::digest(qualified_name)[..16]),
+        }
+        .into();
+    }
+    Ok(())
+}
+
+/// A model element and its ID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ElementIdEntry {
+    /// Fully qualified name.
+    pub qualified_name: String,
+    /// Element ID used in exports.
+    pub element_id: String,
+    /// Display kind, e.g. "Part def".
+    pub kind: String,
+    /// File the element is declared in.
+    pub file: String,
+    /// Declaration line (1-indexed).
+    pub line: u32,
+    /// Declaration column (1-indexed).
+    pub col: u32,
+    /// Whether the ID came from a metadata file; generated IDs aren't stable.
+    pub restored: bool,
+}
+
+/// The element IDs of the declarations in `input`, ordered by file and
+/// position. Standard library elements and imports are left out.
+pub fn element_ids(input: &Path, options: &AnalysisOptions) -> Result<Vec<ElementIdEntry>, String> {
+    let _span = tracing::info_span!("ids").entered();
+    let mut host = AnalysisHost::new();
+    let stats = load_with_metadata(&mut host, input, options)?;
+
+    let analysis = host.analysis();
+    let mut entries: Vec<ElementIdEntry> = analysis
+        .symbol_index()
+        .all_symbols()
+        .filter(|sym| sym.kind != SymbolKind::Import)
+        .filter_map(|sym| {
+            let file = analysis.get_file_path(sym.file)?;
+            if file.contains("sysml.library") {
+                return None;
+            }
+            Some(ElementIdEntry {
+                qualified_name: sym.qualified_name.to_string(),
+                element_id: sym.element_id.to_string(),
+                kind: sym.kind.display().to_string(),
+                file: file.to_string(),
+                line: sym.start_line + 1,
+                col: sym.start_col + 1,
+                restored: stats.restored_names.contains(sym.qualified_name.as_ref()),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| (a.file.as_str(), a.line, a.col).cmp(&(b.file.as_str(), b.line, b.col)));
+    Ok(entries)
+}
+
+/// Render the map as CSV with a header row.
+pub fn element_ids_csv(entries: &[ElementIdEntry]) -> String {
+    let mut csv = "qualified_name,element_id,kind,file,line,col,restored\n".to_string();
+    for entry in entries {
+        let row = [
+            csv_field(&entry.qualified_name),
+            csv_field(&entry.element_id),
+            csv_field(&entry.kind),
+            csv_field(&entry.file),
+            entry.line.to_string(),
+            entry.col.to_string(),
+            entry.restored.to_string(),
+        ];
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
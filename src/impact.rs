@@ -0,0 +1,407 @@
+//! Change impact analysis (`syster impact`).
+//!
+//! Starting from changed elements, everything that depends on them is
+//! collected transitively, each with the number of steps from a change:
+//!
+//! - elements typed by, specializing, subsetting, redefining or otherwise
+//!   referencing an affected element;
+//! - the definition or usage owning an affected member (a changed signal
+//!   changes its port definition); packages are listed but not followed,
+//!   so an import does not make a whole package affected;
+//! - connections, interfaces, flows and allocations linking an affected
+//!   element, and the elements at their other ends.
+//!
+//! Changes are named explicitly, or found by comparing two versions of the
+//! sources: elements added, removed, or changed in kind, direction,
+//! multiplicity, abstractness or references. Anonymous elements are only
+//! compared through their named owners.
+
+use super::AnalysisOptions;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::path::Path;
+use syster::hir::{HirSymbol, RefKind, SymbolIndex, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// How an element changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// Named as changed, or present in both versions but different.
+    Changed,
+    /// Only in the new version.
+    Added,
+    /// Only in the old version.
+    Removed,
+}
+
+/// An element a change starts from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChangedElement {
+    /// Qualified name of the element.
+    pub qualified_name: String,
+    /// How it changed.
+    pub change: ChangeKind,
+}
+
+/// An element affected by a change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImpactedElement {
+    /// Qualified name of the element.
+    pub qualified_name: String,
+    /// Kind of the element, e.g. `Part def`.
+    pub kind: String,
+    /// Steps from the nearest change (1: depends on a changed element).
+    pub depth: usize,
+    /// How the element depends on `from`, e.g. `typed by` or `owns`.
+    pub via: String,
+    /// The element one step closer to the change.
+    pub from: String,
+    /// File declaring the element.
+    pub file: Option<String>,
+    /// Declaration line (1-based).
+    pub line: u32,
+}
+
+/// A package containing changed or affected elements.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImpactedPackage {
+    /// Qualified name of the package.
+    pub package: String,
+    /// Number of affected elements in the package.
+    pub elements: usize,
+    /// Smallest depth of its elements (0: it contains a change).
+    pub depth: usize,
+}
+
+/// What a set of changes affects.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactReport {
+    /// The changes the analysis starts from.
+    pub changes: Vec<ChangedElement>,
+    /// Affected elements, by depth and then qualified name.
+    pub elements: Vec<ImpactedElement>,
+    /// Packages of the changed and affected elements, by qualified name.
+    pub packages: Vec<ImpactedPackage>,
+}
+
+/// Analyze `input` and collect what the `changed` elements affect.
+///
+/// Fails if a changed element is not in the model.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `changed` - Qualified names of the changed elements
+/// * `max_depth` - Stop this many steps from a change
+/// * `options` - Verbosity and standard library to load
+pub fn change_impact(
+    input: &Path,
+    changed: &[String],
+    max_depth: Option<usize>,
+    options: &AnalysisOptions,
+) -> Result<ImpactReport, String> {
+    let graph = Graph::load(input, options)?;
+    for name in changed {
+        if !graph.elements.contains_key(name) {
+            return Err(format!("Unknown element: {}", name));
+        }
+    }
+    let changes = changed
+        .iter()
+        .map(|name| ChangedElement {
+            qualified_name: name.clone(),
+            change: ChangeKind::Changed,
+        })
+        .collect();
+    Ok(graph.impact(changes, max_depth, options.verbose))
+}
+
+/// Compare two versions of the sources and collect what their differences
+/// affect, following dependencies of either version.
+///
+/// # Arguments
+/// * `old` - Source file or directory of the old version
+/// * `new` - Source file or directory of the new version
+/// * `max_depth` - Stop this many steps from a change
+/// * `options` - Verbosity and standard library to load
+pub fn diff_impact(
+    old: &Path,
+    new: &Path,
+    max_depth: Option<usize>,
+    options: &AnalysisOptions,
+) -> Result<ImpactReport, String> {
+    let old = Graph::load(old, options)?;
+    let mut graph = Graph::load(new, options)?;
+
+    let mut changes = Vec::new();
+    for (name, before) in &old.elements {
+        let change = match graph.elements.get(name) {
+            None => ChangeKind::Removed,
+            Some(after) if after.signature != before.signature => ChangeKind::Changed,
+            Some(_) => continue,
+        };
+        changes.push(ChangedElement {
+            qualified_name: name.clone(),
+            change,
+        });
+    }
+    for name in graph.elements.keys() {
+        if !old.elements.contains_key(name) {
+            changes.push(ChangedElement {
+                qualified_name: name.clone(),
+                change: ChangeKind::Added,
+            });
+        }
+    }
+    changes.retain(|c| !c.qualified_name.contains('<'));
+    changes.sort_by(|a, b| a.qualified_name.cmp(&b.qualified_name));
+
+    graph.merge(old);
+    Ok(graph.impact(changes, max_depth, options.verbose))
+}
+
+/// A model element as far as impact is concerned.
+struct Element {
+    kind: String,
+    package: Option<String>,
+    file: Option<String>,
+    line: u32,
+    /// What a change to the element would alter, for comparing versions.
+    signature: String,
+}
+
+/// Elements of the user model and, for each, the elements depending on it
+/// with how they depend on it.
+struct Graph {
+    elements: BTreeMap<String, Element>,
+    dependents: BTreeMap<String, BTreeSet<(String, &'static str)>>,
+}
+
+impl Graph {
+    /// Analyze `input` and build its dependency graph.
+    fn load(input: &Path, options: &AnalysisOptions) -> Result<Self, String> {
+        let mut host = AnalysisHost::new();
+        if options.load_stdlib {
+            super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+        }
+        let library: HashSet<String> = host
+            .files()
+            .keys()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        super::load_input(&mut host, input, options.verbose)?;
+        let _analysis = host.analysis();
+        let index = host.symbol_index();
+
+        let mut graph = Graph {
+            elements: BTreeMap::new(),
+            dependents: BTreeMap::new(),
+        };
+        let symbols = index.all_symbols().filter(|s| {
+            s.kind != SymbolKind::Import
+                && host
+                    .get_file_path(s.file)
+                    .is_some_and(|path| !library.contains(path))
+        });
+        for symbol in symbols {
+            let name = symbol.qualified_name.to_string();
+            let references = references(index, symbol);
+            let mut signature: Vec<String> = references
+                .iter()
+                .map(|(target, via)| format!("{} {}", via, target))
+                .collect();
+            signature.sort();
+            graph.elements.insert(
+                name.clone(),
+                Element {
+                    kind: symbol.kind.display().to_string(),
+                    package: enclosing_package(index, &name),
+                    file: host.get_file_path(symbol.file).map(str::to_string),
+                    line: symbol.start_line + 1,
+                    signature: format!(
+                        "{:?} {:?} {:?} {} [{}]",
+                        symbol.kind,
+                        symbol.direction,
+                        symbol.multiplicity,
+                        symbol.is_abstract,
+                        signature.join(", ")
+                    ),
+                },
+            );
+
+            if symbol.kind == SymbolKind::Package {
+                continue;
+            }
+            for (target, via) in references {
+                graph.depends(&name, &target, via);
+                // A link affects the elements at its ends in turn
+                if is_link(symbol.kind) {
+                    graph.depends(&target, &name, "connected by");
+                }
+            }
+            let owner = name
+                .rsplit_once("::")
+                .and_then(|(owner, _)| index.lookup_qualified(owner))
+                .filter(|owner| owner.kind != SymbolKind::Package);
+            if let Some(owner) = owner {
+                graph.depends(&owner.qualified_name, &name, "owns");
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Record that `dependent` depends on `target`.
+    fn depends(&mut self, dependent: &str, target: &str, via: &'static str) {
+        if dependent != target {
+            self.dependents
+                .entry(target.to_string())
+                .or_default()
+                .insert((dependent.to_string(), via));
+        }
+    }
+
+    /// Add the elements and dependencies of another version.
+    fn merge(&mut self, other: Graph) {
+        for (name, element) in other.elements {
+            self.elements.entry(name).or_insert(element);
+        }
+        for (target, dependents) in other.dependents {
+            self.dependents
+                .entry(target)
+                .or_default()
+                .extend(dependents);
+        }
+    }
+
+    /// Collect what `changes` affect, breadth first.
+    fn impact(
+        &self,
+        changes: Vec<ChangedElement>,
+        max_depth: Option<usize>,
+        verbose: bool,
+    ) -> ImpactReport {
+        let mut reached: BTreeMap<&str, usize> = changes
+            .iter()
+            .map(|c| (c.qualified_name.as_str(), 0))
+            .collect();
+        let mut queue: VecDeque<(&str, usize)> = reached.iter().map(|(n, d)| (*n, *d)).collect();
+        let mut elements = Vec::new();
+        while let Some((name, depth)) = queue.pop_front() {
+            if max_depth.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            for (dependent, via) in self.dependents.get(name).into_iter().flatten() {
+                if reached.contains_key(dependent.as_str()) {
+                    continue;
+                }
+                // Only elements of the user model are reported
+                let Some(element) = self.elements.get(dependent) else {
+                    continue;
+                };
+                reached.insert(dependent, depth + 1);
+                queue.push_back((dependent, depth + 1));
+                elements.push(ImpactedElement {
+                    qualified_name: dependent.clone(),
+                    kind: element.kind.clone(),
+                    depth: depth + 1,
+                    via: via.to_string(),
+                    from: name.to_string(),
+                    file: element.file.clone(),
+                    line: element.line,
+                });
+            }
+        }
+        elements.sort_by(|a, b| (a.depth, &a.qualified_name).cmp(&(b.depth, &b.qualified_name)));
+
+        let mut packages: BTreeMap<&str, ImpactedPackage> = BTreeMap::new();
+        for (name, depth) in &reached {
+            let Some(package) = self.elements.get(*name).and_then(|e| e.package.as_deref()) else {
+                continue;
+            };
+            let entry = packages.entry(package).or_insert(ImpactedPackage {
+                package: package.to_string(),
+                elements: 0,
+                depth: *depth,
+            });
+            entry.depth = entry.depth.min(*depth);
+            if *depth > 0 {
+                entry.elements += 1;
+            }
+        }
+
+        if verbose {
+            tracing::info!(
+                "{} changes affect {} elements in {} packages",
+                changes.len(),
+                elements.len(),
+                packages.len()
+            );
+        }
+        ImpactReport {
+            changes,
+            elements,
+            packages: packages.into_values().collect(),
+        }
+    }
+}
+
+/// Qualified names of the elements `symbol` refers to, with how.
+///
+/// Uses the target resolved during analysis when there is one and resolves
+/// from the symbol's scope otherwise; unresolvable references are skipped.
+fn references(index: &SymbolIndex, symbol: &HirSymbol) -> Vec<(String, &'static str)> {
+    let resolver = index.resolver_for_scope(&symbol.qualified_name);
+    let link = is_link(symbol.kind);
+    symbol
+        .type_refs
+        .iter()
+        .flat_map(|r| r.as_refs())
+        .filter_map(|r| {
+            let target = match &r.resolved_target {
+                Some(resolved) => resolved.to_string(),
+                None => resolver
+                    .resolve(&r.target)
+                    .symbol()?
+                    .qualified_name
+                    .to_string(),
+            };
+            let via = match r.kind {
+                RefKind::TypedBy => "typed by",
+                RefKind::Specializes => "specializes",
+                RefKind::Redefines => "redefines",
+                RefKind::Subsets => "subsets",
+                RefKind::References => "references",
+                RefKind::Expression => "uses",
+                RefKind::Other if link => "connects",
+                RefKind::Other => "refers to",
+            };
+            Some((target, via))
+        })
+        .collect()
+}
+
+/// Whether `kind` links the elements at its ends.
+fn is_link(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::ConnectionUsage
+            | SymbolKind::InterfaceUsage
+            | SymbolKind::FlowConnectionUsage
+            | SymbolKind::AllocationUsage
+    )
+}
+
+/// Qualified name of the innermost package enclosing `name`, or `name`
+/// itself if it is a package.
+fn enclosing_package(index: &SymbolIndex, name: &str) -> Option<String> {
+    let mut current = name;
+    loop {
+        if index
+            .lookup_qualified(current)
+            .is_some_and(|s| s.kind == SymbolKind::Package)
+        {
+            return Some(current.to_string());
+        }
+        current = current.rsplit_once("::")?.0;
+    }
+}
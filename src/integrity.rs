@@ -0,0 +1,372 @@
+//! Checksums and signatures for exported artifacts.
+//!
+//! A KPAR gets a `META-INF/checksums.sha256` entry listing the SHA-256 of
+//! every other entry, and optionally `META-INF/signature.ed25519` holding an
+//! Ed25519 signature over that list. A single XMI file has no room for extra
+//! entries, so its checksum (and signature) go into a trailing XML comment
+//! covering everything before it.
+
+use super::kpar::{read_entries, write_entries};
+use super::to_hex;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Checksum list inside a KPAR archive.
+pub const CHECKSUMS_PATH: &str = "META-INF/checksums.sha256";
+
+/// Signature over the checksum list inside a KPAR archive.
+pub const SIGNATURE_PATH: &str = "META-INF/signature.ed25519";
+
+/// Marker that starts the integrity comment at the end of an XMI file.
+const XMI_MARKER: &str = "<!-- syster-integrity:";
+
+/// Outcome of checking a signature.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum SignatureStatus {
+    /// The artifact is not signed.
+    Absent,
+    /// The signature is valid for `public_key`. `trusted` is set when the
+    /// key matched the one supplied by the caller rather than the embedded one.
+    Valid { public_key: String, trusted: bool },
+    /// The signature does not match the content or the trusted key.
+    Invalid { reason: String },
+}
+
+/// Result of verifying an artifact's checksums and signature.
+#[derive(Debug, Serialize)]
+pub struct IntegrityReport {
+    /// Number of entries whose checksum matched.
+    pub verified_entries: usize,
+    /// Checksum problems (missing, mismatched or unlisted entries).
+    pub problems: Vec<String>,
+    /// Signature check outcome.
+    pub signature: SignatureStatus,
+}
+
+impl IntegrityReport {
+    /// Whether every checksum matched and any signature is valid.
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+            && matches!(
+                self.signature,
+                SignatureStatus::Absent | SignatureStatus::Valid { .. }
+            )
+    }
+}
+
+/// Load an Ed25519 private key from a PKCS#8 PEM file.
+///
+/// Such a key can be created with `openssl genpkey -algorithm ed25519`.
+pub fn load_signing_key(path: &Path) -> Result<SigningKey, String> {
+    use ed25519_dalek::pkcs8::DecodePrivateKey;
+
+    let pem = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    SigningKey::from_pkcs8_pem(&pem)
+        .map_err(|e| format!("{} is not an Ed25519 private key: {}", path.display(), e))
+}
+
+/// Load an Ed25519 public key from a PEM file.
+///
+/// Such a key can be created with `openssl pkey -in key.pem -pubout`.
+pub fn load_verifying_key(path: &Path) -> Result<VerifyingKey, String> {
+    use ed25519_dalek::pkcs8::DecodePublicKey;
+
+    let pem = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    VerifyingKey::from_public_key_pem(&pem)
+        .map_err(|e| format!("{} is not an Ed25519 public key: {}", path.display(), e))
+}
+
+/// Add a checksum list (and optionally a signature) to a KPAR archive.
+pub fn seal_kpar(kpar: &[u8], key: Option<&SigningKey>) -> Result<Vec<u8>, String> {
+    let mut entries = read_entries(kpar)?;
+    let checksums = checksum_list(&entries);
+
+    entries.retain(|(name, _)| !is_integrity_entry(name));
+    entries.push((CHECKSUMS_PATH.to_string(), checksums.clone().into_bytes()));
+    if let Some(key) = key {
+        let signature = key.sign(checksums.as_bytes());
+        let entry = format!(
+            "public-key: {}\nsignature: {}\n",
+            to_hex(key.verifying_key().as_bytes()),
+            to_hex(&signature.to_bytes())
+        );
+        entries.push((SIGNATURE_PATH.to_string(), entry.into_bytes()));
+    }
+
+    write_entries(&entries)
+}
+
+/// Append the integrity comment (and optionally a signature) to an XMI file.
+pub fn seal_xmi(xmi: &[u8], key: Option<&SigningKey>) -> Vec<u8> {
+    let mut fields = format!("sha256={}", to_hex(&Sha256::digest(xmi)));
+    if let Some(key) = key {
+        let signature = key.sign(xmi);
+        fields.push_str(&format!(
+            " ed25519-key={} ed25519-signature={}",
+            to_hex(key.verifying_key().as_bytes()),
+            to_hex(&signature.to_bytes())
+        ));
+    }
+    xmi_with_comment(xmi, &fields)
+}
+
+/// Verify the checksums and signature of a sealed KPAR or XMI file.
+///
+/// When `trusted_key` is given the signature must be made with that key;
+/// otherwise it is checked against the key embedded in the artifact, which
+/// proves integrity but not origin.
+pub fn verify_integrity(
+    path: &Path,
+    trusted_key: Option<&Path>,
+) -> Result<IntegrityReport, String> {
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let is_kpar = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("kpar"));
+
+    let (report, signed) = if is_kpar {
+        verify_kpar(&bytes)?
+    } else {
+        verify_xmi(&bytes)?
+    };
+
+    let signature = match signed {
+        None => SignatureStatus::Absent,
+        Some(signed) => check_signature(&signed, trusted_key)?,
+    };
+    if trusted_key.is_some() && signature == SignatureStatus::Absent {
+        return Ok(IntegrityReport {
+            signature: SignatureStatus::Invalid {
+                reason: "artifact is not signed".to_string(),
+            },
+            ..report
+        });
+    }
+
+    Ok(IntegrityReport {
+        signature,
+        ..report
+    })
+}
+
+/// Signed content with its embedded key and signature, all as found in the file.
+struct Signed {
+    message: Vec<u8>,
+    public_key: String,
+    signature: String,
+}
+
+/// Check a KPAR's checksum list, returning any signed content found.
+fn verify_kpar(bytes: &[u8]) -> Result<(IntegrityReport, Option<Signed>), String> {
+    let entries = read_entries(bytes)?;
+    let find = |name: &str| {
+        entries
+            .iter()
+            .find(|(entry, _)| entry == name)
+            .map(|(_, bytes)| bytes)
+    };
+
+    let Some(checksums) = find(CHECKSUMS_PATH) else {
+        return Ok((
+            IntegrityReport {
+                verified_entries: 0,
+                problems: vec![format!("missing {}", CHECKSUMS_PATH)],
+                signature: SignatureStatus::Absent,
+            },
+            None,
+        ));
+    };
+    let checksums = String::from_utf8_lossy(checksums).into_owned();
+
+    let mut problems = Vec::new();
+    let mut verified_entries = 0;
+    let mut listed = Vec::new();
+    for line in checksums.lines().filter(|l| !l.trim().is_empty()) {
+        let Some((expected, name)) = line.split_once("  ") else {
+            problems.push(format!("malformed checksum line: {}", line));
+            continue;
+        };
+        listed.push(name);
+        match find(name) {
+            Some(bytes) if to_hex(&Sha256::digest(bytes)) == expected => verified_entries += 1,
+            Some(_) => problems.push(format!("checksum mismatch: {}", name)),
+            None => problems.push(format!("listed entry missing: {}", name)),
+        }
+    }
+    for (name, _) in &entries {
+        if !is_integrity_entry(name) && !listed.contains(&name.as_str()) {
+            problems.push(format!("entry not covered by checksums: {}", name));
+        }
+    }
+
+    let signed = match find(SIGNATURE_PATH) {
+        Some(signature) => {
+            let text = String::from_utf8_lossy(signature);
+            let field = |key: &str| {
+                text.lines()
+                    .find_map(|l| l.strip_prefix(key))
+                    .map(|v| v.trim().to_string())
+                    .unwrap_or_default()
+            };
+            Some(Signed {
+                message: checksums.into_bytes(),
+                public_key: field("public-key:"),
+                signature: field("signature:"),
+            })
+        }
+        None => None,
+    };
+
+    Ok((
+        IntegrityReport {
+            verified_entries,
+            problems,
+            signature: SignatureStatus::Absent,
+        },
+        signed,
+    ))
+}
+
+/// Check an XMI file's integrity comment, returning any signed content found.
+fn verify_xmi(bytes: &[u8]) -> Result<(IntegrityReport, Option<Signed>), String> {
+    let marker = XMI_MARKER.as_bytes();
+    let Some(start) = bytes.windows(marker.len()).rposition(|w| w == marker) else {
+        return Ok((
+            IntegrityReport {
+                verified_entries: 0,
+                problems: vec!["missing integrity comment".to_string()],
+                signature: SignatureStatus::Absent,
+            },
+            None,
+        ));
+    };
+
+    let content = &bytes[..start];
+    let comment = String::from_utf8_lossy(&bytes[start + marker.len()..]);
+    let mut problems = Vec::new();
+    // The comment seals the end of the file: nothing but whitespace may follow
+    let fields = match comment.split_once("-->") {
+        Some((fields, rest)) => {
+            if !rest.trim().is_empty() {
+                problems.push("content after the integrity comment".to_string());
+            }
+            fields
+        }
+        None => {
+            problems.push("unterminated integrity comment".to_string());
+            &comment[..]
+        }
+    };
+    let fields: Vec<(&str, &str)> = fields
+        .split_whitespace()
+        .filter_map(|field| field.split_once('='))
+        .collect();
+    let field = |key: &str| {
+        fields
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.to_string())
+    };
+
+    let mut verified_entries = 0;
+    match field("sha256") {
+        Some(expected) if to_hex(&Sha256::digest(content)) == expected => verified_entries = 1,
+        Some(_) => problems.push("checksum mismatch".to_string()),
+        None => problems.push("integrity comment has no sha256".to_string()),
+    }
+
+    let signed = field("ed25519-signature").map(|signature| Signed {
+        message: content.to_vec(),
+        public_key: field("ed25519-key").unwrap_or_default(),
+        signature,
+    });
+
+    Ok((
+        IntegrityReport {
+            verified_entries,
+            problems,
+            signature: SignatureStatus::Absent,
+        },
+        signed,
+    ))
+}
+
+/// Check a signature against the trusted key, or the embedded key if none.
+fn check_signature(signed: &Signed, trusted_key: Option<&Path>) -> Result<SignatureStatus, String> {
+    let invalid = |reason: &str| {
+        Ok(SignatureStatus::Invalid {
+            reason: reason.to_string(),
+        })
+    };
+
+    let Some(embedded) = from_hex(&signed.public_key)
+        .and_then(|b| <[u8; 32]>::try_from(b).ok())
+        .and_then(|b| VerifyingKey::from_bytes(&b).ok())
+    else {
+        return invalid("malformed embedded public key");
+    };
+    let Some(signature) = from_hex(&signed.signature)
+        .and_then(|b| <[u8; 64]>::try_from(b).ok())
+        .map(|b| Signature::from_bytes(&b))
+    else {
+        return invalid("malformed signature");
+    };
+
+    let (key, trusted) = match trusted_key {
+        Some(path) => (load_verifying_key(path)?, true),
+        None => (embedded, false),
+    };
+    if trusted && key != embedded {
+        return invalid("signed with a different key");
+    }
+
+    match key.verify(&signed.message, &signature) {
+        Ok(()) => Ok(SignatureStatus::Valid {
+            public_key: to_hex(key.as_bytes()),
+            trusted,
+        }),
+        Err(_) => invalid("signature does not match content"),
+    }
+}
+
+/// `sha256sum`-style list of every non-integrity entry, sorted by name.
+fn checksum_list(entries: &[(String, Vec<u8>)]) -> String {
+    let mut covered: Vec<_> = entries
+        .iter()
+        .filter(|(name, _)| !is_integrity_entry(name))
+        .collect();
+    covered.sort_by(|a, b| a.0.cmp(&b.0));
+    covered
+        .into_iter()
+        .map(|(name, bytes)| format!("{}  {}\n", to_hex(&Sha256::digest(bytes)), name))
+        .collect()
+}
+
+/// Entries written by sealing, which the checksum list does not cover.
+fn is_integrity_entry(name: &str) -> bool {
+    name == CHECKSUMS_PATH || name == SIGNATURE_PATH
+}
+
+/// Append an integrity comment with `fields` to XMI content.
+fn xmi_with_comment(xmi: &[u8], fields: &str) -> Vec<u8> {
+    let mut sealed = xmi.to_vec();
+    sealed.extend_from_slice(format!("{} {} -->\n", XMI_MARKER, fields).as_bytes());
+    sealed
+}
+
+/// Decode lowercase or uppercase hex.
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,234 @@
+//! Jira issues from diagnostics (`syster publish jira`).
+//!
+//! Every diagnostic whose severity has a Jira priority becomes an issue.
+//! Issues carry a fingerprint label derived from the diagnostic's code,
+//! file and message (not its position, which shifts with unrelated edits),
+//! so publishing again updates the still open issue instead of filing a
+//! duplicate. Settings come from the `[publish.jira]` table of
+//! `syster.toml`:
+//!
+//! ```toml
+//! [publish.jira]
+//! url = "https://example.atlassian.net"
+//! project = "SYS"
+//! issue_type = "Bug"        # default
+//!
+//! [publish.jira.priority]   # default: error = "High"
+//! error = "Highest"
+//! warning = "Low"
+//! ```
+//!
+//! Requests go through the Jira REST API (v2), authenticated with a user and
+//! API token.
+
+use super::http::{self, Auth, encode};
+use super::project::{find_project_file, load_table};
+use super::{AnalysisResult, DiagnosticInfo, severity_name, to_hex};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Label every published issue has, besides its fingerprint.
+pub const ISSUE_LABEL: &str = "syster";
+
+/// Longest summary Jira accepts.
+const MAX_SUMMARY: usize = 255;
+
+/// Where and how issues are published.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JiraConfig {
+    /// Base URL of the Jira site.
+    #[serde(default)]
+    pub url: String,
+    /// Project key.
+    #[serde(default)]
+    pub project: String,
+    /// Issue type of new issues.
+    #[serde(default = "default_issue_type")]
+    pub issue_type: String,
+    /// Priority name by severity (`error`, `warning`, `info`, `hint`);
+    /// diagnostics of other severities are not published.
+    #[serde(default = "default_priorities", rename = "priority")]
+    pub priorities: BTreeMap<String, String>,
+}
+
+impl Default for JiraConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            project: String::new(),
+            issue_type: default_issue_type(),
+            priorities: default_priorities(),
+        }
+    }
+}
+
+fn default_issue_type() -> String {
+    "Bug".to_string()
+}
+
+fn default_priorities() -> BTreeMap<String, String> {
+    BTreeMap::from([("error".to_string(), "High".to_string())])
+}
+
+impl JiraConfig {
+    /// Load the `[publish.jira]` table of a project file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Ok(load_table(path, "publish.jira")?.unwrap_or_default())
+    }
+
+    /// Load the nearest `syster.toml` in `input` (or its directory) and its
+    /// ancestors, or the defaults if there is none.
+    pub fn discover(input: &Path) -> Result<Self, String> {
+        match find_project_file(input) {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// An issue to create or update.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JiraIssue {
+    /// Label identifying the diagnostic across runs.
+    pub fingerprint: String,
+    /// `[code] message`.
+    pub summary: String,
+    /// Location and message of the diagnostic.
+    pub description: String,
+    /// Jira priority name.
+    pub priority: String,
+}
+
+/// What publishing did.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PublishSummary {
+    /// Keys of newly created issues.
+    pub created: Vec<String>,
+    /// Keys of existing issues that were updated.
+    pub updated: Vec<String>,
+}
+
+/// The issues for the diagnostics of `result`, one per fingerprint. Paths
+/// are made relative to `root` so fingerprints don't depend on where the
+/// models are checked out.
+pub fn plan_issues(result: &AnalysisResult, root: &Path, config: &JiraConfig) -> Vec<JiraIssue> {
+    let mut issues: Vec<JiraIssue> = Vec::new();
+    for diag in &result.diagnostics {
+        let Some(priority) = config.priorities.get(severity_name(diag.severity)) else {
+            continue;
+        };
+        let file = Path::new(&diag.file)
+            .strip_prefix(root)
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| diag.file.clone());
+        let fingerprint = fingerprint(diag, &file);
+        if issues.iter().any(|i| i.fingerprint == fingerprint) {
+            continue;
+        }
+        let code = diag.code.as_deref().unwrap_or(severity_name(diag.severity));
+        let mut summary = format!("[{}] {}", code, diag.message);
+        if summary.len() > MAX_SUMMARY {
+            let mut end = MAX_SUMMARY - 3;
+            while !summary.is_char_boundary(end) {
+                end -= 1;
+            }
+            summary.truncate(end);
+            summary.push_str("...");
+        }
+        issues.push(JiraIssue {
+            description: format!(
+                "{}:{}:{}: {}\n\nReported by syster; fingerprint {}.",
+                file, diag.line, diag.col, diag.message, fingerprint
+            ),
+            fingerprint,
+            summary,
+            priority: priority.clone(),
+        });
+    }
+    issues
+}
+
+/// Create an issue for each of `issues` in the configured project, or update
+/// the open issue with the same fingerprint. `auth` is `user:api-token`.
+pub fn publish_issues(
+    issues: &[JiraIssue],
+    config: &JiraConfig,
+    auth: &str,
+) -> Result<PublishSummary, String> {
+    if config.url.is_empty() {
+        return Err("No Jira URL (set url in [publish.jira] or pass --url)".to_string());
+    }
+    if config.project.is_empty() {
+        return Err(
+            "No Jira project (set project in [publish.jira] or pass --project)".to_string(),
+        );
+    }
+    let api = format!("{}/rest/api/2", config.url.trim_end_matches('/'));
+
+    let mut summary = PublishSummary::default();
+    for issue in issues {
+        let jql = format!(
+            "project = \"{}\" AND labels = \"{}\" AND statusCategory != Done",
+            config.project, issue.fingerprint
+        );
+        let found = http::request(
+            "GET",
+            &format!(
+                "{}/search?fields=key&maxResults=1&jql={}",
+                api,
+                encode(&jql)
+            ),
+            Some(Auth::Basic(auth)),
+            None,
+        )?;
+        let existing = found["issues"][0]["key"].as_str().map(str::to_string);
+
+        let mut fields = json!({
+            "summary": issue.summary,
+            "description": issue.description,
+            "priority": { "name": issue.priority },
+        });
+        match existing {
+            Some(key) => {
+                http::request(
+                    "PUT",
+                    &format!("{}/issue/{}", api, key),
+                    Some(Auth::Basic(auth)),
+                    Some(&json!({ "fields": fields })),
+                )?;
+                summary.updated.push(key);
+            }
+            None => {
+                fields["project"] = json!({ "key": config.project });
+                fields["issuetype"] = json!({ "name": config.issue_type });
+                fields["labels"] = json!([ISSUE_LABEL, issue.fingerprint]);
+                let created = http::request(
+                    "POST",
+                    &format!("{}/issue", api),
+                    Some(Auth::Basic(auth)),
+                    Some(&json!({ "fields": fields })),
+                )?;
+                let key = created["key"]
+                    .as_str()
+                    .ok_or_else(|| "Jira did not return the new issue's key".to_string())?;
+                summary.created.push(key.to_string());
+            }
+        }
+    }
+    Ok(summary)
+}
+
+/// `syster-` and 16 hex digits of the SHA-256 of code, file and message.
+fn fingerprint(diag: &DiagnosticInfo, file: &str) -> String {
+    let key = format!(
+        "{}\n{}\n{}",
+        diag.code.as_deref().unwrap_or_default(),
+        file,
+        diag.message
+    );
+    format!("{}-{}", ISSUE_LABEL, &to_hex(&Sha256::digest(key))[..16])
+}
@@ -0,0 +1,639 @@
+//! Schema validation of standard JSON payloads (`syster validate-interchange`).
+//!
+//! Deliveries in the SysML v2 standard JSON format (see [`sysml_json`]) are
+//! checked against the JSON schemas the OMG publishes with the
+//! specification before they are converted, so a malformed delivery is
+//! reported as schema violations with JSON pointers into the payload instead
+//! of surfacing as a confusing error halfway through the converter.
+//!
+//! A bundle is a directory of schema files. Each element of the payload is
+//! validated against the schema named after its `@type`: a file of that
+//! name, or a definition under `$defs` (or `definitions`) of that name in
+//! any file. `$ref`s are resolved against `$id`s across the bundle; nothing
+//! is fetched over the network. The keywords that describe the shape of a
+//! payload are checked — types, required and additional properties, items,
+//! enumerations, bounds, patterns and the combinators — while annotations
+//! and formats are ignored.
+//!
+//! [`sysml_json`]: super::sysml_json
+
+use super::compress;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Nesting of schemas, through `$ref`s and combinators, beyond which a
+/// schema is taken to be recursive without consuming the payload.
+const MAX_DEPTH: usize = 256;
+
+/// A payload value that breaks the schema.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaViolation {
+    /// JSON pointer to the value in the payload.
+    pub pointer: String,
+    /// What is wrong with it.
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "(root)"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{}: {}", pointer, self.message)
+    }
+}
+
+/// Result of validating a payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaReport {
+    /// Number of elements in the payload.
+    pub elements: usize,
+    /// Violations, in payload order.
+    pub violations: Vec<SchemaViolation>,
+}
+
+/// The schema files of a bundle.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaBundle {
+    /// Schema documents by `$id` (or file path, if they have none).
+    documents: HashMap<String, Value>,
+    /// URIs of the schemas for each `@type`.
+    types: HashMap<String, String>,
+    /// Document URIs by file stem, for references by file name.
+    stems: HashMap<String, String>,
+}
+
+impl SchemaBundle {
+    /// Read every `.json` schema under `dir`.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        if !dir.is_dir() {
+            return Err(format!(
+                "Schema bundle {} is not a directory",
+                dir.display()
+            ));
+        }
+        let mut paths: Vec<_> = WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.into_path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "json"))
+            .collect();
+        paths.sort();
+
+        let mut bundle = Self::default();
+        let mut definitions = Vec::new();
+        for path in &paths {
+            let text = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let schema: Value = serde_json::from_str(&text)
+                .map_err(|e| format!("Invalid schema {}: {}", path.display(), e))?;
+            let uri = match schema.get("$id").and_then(Value::as_str) {
+                Some(id) => id.trim_end_matches('#').to_string(),
+                None => path.display().to_string(),
+            };
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                let stem = stem.trim_end_matches(".schema").to_string();
+                bundle
+                    .types
+                    .entry(stem.clone())
+                    .or_insert_with(|| uri.clone());
+                bundle.stems.entry(stem).or_insert_with(|| uri.clone());
+            }
+            for keyword in ["$defs", "definitions"] {
+                if let Some(defs) = schema.get(keyword).and_then(Value::as_object) {
+                    for name in defs.keys() {
+                        let pointer = format!("{}#/{}/{}", uri, keyword, escape(name));
+                        definitions.push((name.clone(), pointer));
+                    }
+                }
+            }
+            bundle.documents.insert(uri, schema);
+        }
+        // Files named after a type take precedence over definitions
+        for (name, pointer) in definitions {
+            bundle.types.entry(name).or_insert(pointer);
+        }
+
+        if bundle.documents.is_empty() {
+            return Err(format!("No JSON schemas in {}", dir.display()));
+        }
+        Ok(bundle)
+    }
+
+    /// Number of schema files in the bundle.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Whether the bundle has no schema files.
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Validate a payload: an array of elements, or a single element.
+    pub fn validate(&self, payload: &Value) -> SchemaReport {
+        let elements: Vec<(String, &Value)> = match payload {
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| (format!("/{}", i), item))
+                .collect(),
+            item => vec![(String::new(), item)],
+        };
+
+        let validator = Validator {
+            bundle: self,
+            patterns: RefCell::new(HashMap::new()),
+        };
+        let mut violations = Vec::new();
+        for (pointer, element) in &elements {
+            let Some(kind) = element.get("@type").and_then(Value::as_str) else {
+                violations.push(violation(pointer, "element has no @type"));
+                continue;
+            };
+            let Some(uri) = self.types.get(kind) else {
+                violations.push(violation(
+                    pointer,
+                    &format!("no schema for @type '{}' in the bundle", kind),
+                ));
+                continue;
+            };
+            violations.extend(validator.check_ref(uri, "", element, pointer, 0));
+        }
+        SchemaReport {
+            elements: elements.len(),
+            violations,
+        }
+    }
+
+    /// The schema a URI points to, and the URI of its document.
+    fn resolve(&self, uri: &str) -> Option<(&Value, String)> {
+        let (document, fragment) = uri.split_once('#').unwrap_or((uri, ""));
+        let document_uri = if self.documents.contains_key(document) {
+            document.to_string()
+        } else {
+            // A reference by file name, with or without the extension
+            let name = document.rsplit('/').next().unwrap_or(document);
+            let name = name.trim_end_matches(".json").trim_end_matches(".schema");
+            self.stems.get(name)?.clone()
+        };
+        let schema = self.documents.get(&document_uri)?;
+        let fragment = fragment.replace("%24", "$").replace("%25", "%");
+        let schema = if fragment.is_empty() {
+            schema
+        } else if fragment.starts_with('/') {
+            schema.pointer(&fragment)?
+        } else {
+            find_anchor(schema, &fragment)?
+        };
+        Some((schema, document_uri))
+    }
+}
+
+/// Validate the standard JSON payload in `input` against the schemas in
+/// `bundle_dir`. The file may be gzip or zstd compressed.
+pub fn validate_interchange(input: &Path, bundle_dir: &Path) -> Result<SchemaReport, String> {
+    let bundle = SchemaBundle::load(bundle_dir)?;
+    let bytes =
+        std::fs::read(input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+    let bytes = compress::decompress(bytes)?;
+    let payload: Value = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("{} is not JSON: {}", input.display(), e))?;
+    Ok(bundle.validate(&payload))
+}
+
+struct Validator<'a> {
+    bundle: &'a SchemaBundle,
+    /// Compiled `pattern`s; `None` if the pattern is not a valid regex.
+    patterns: RefCell<HashMap<String, Option<Regex>>>,
+}
+
+impl Validator<'_> {
+    fn check_ref(
+        &self,
+        reference: &str,
+        base: &str,
+        instance: &Value,
+        pointer: &str,
+        depth: usize,
+    ) -> Vec<SchemaViolation> {
+        let uri = resolve_uri(base, reference);
+        match self.bundle.resolve(&uri) {
+            Some((schema, document)) => self.check(schema, &document, instance, pointer, depth + 1),
+            None => vec![violation(
+                pointer,
+                &format!("schema reference {} cannot be resolved", reference),
+            )],
+        }
+    }
+
+    /// Violations of `schema`, whose references resolve against `base`.
+    fn check(
+        &self,
+        schema: &Value,
+        base: &str,
+        instance: &Value,
+        pointer: &str,
+        depth: usize,
+    ) -> Vec<SchemaViolation> {
+        if depth > MAX_DEPTH {
+            return vec![violation(pointer, "schema nesting too deep")];
+        }
+        let schema = match schema {
+            Value::Bool(true) => return Vec::new(),
+            Value::Bool(false) => return vec![violation(pointer, "no value is allowed here")],
+            Value::Object(schema) => schema,
+            _ => return Vec::new(),
+        };
+        let base = match schema.get("$id").and_then(Value::as_str) {
+            Some(id) => resolve_uri(base, id.trim_end_matches('#')),
+            None => base.to_string(),
+        };
+
+        let mut violations = Vec::new();
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            violations.extend(self.check_ref(reference, &base, instance, pointer, depth));
+        }
+        self.check_type(schema, instance, pointer, &mut violations);
+        self.check_combinators(schema, &base, instance, pointer, depth, &mut violations);
+        match instance {
+            Value::Object(object) => {
+                self.check_object(schema, &base, object, pointer, depth, &mut violations)
+            }
+            Value::Array(items) => {
+                self.check_array(schema, &base, items, pointer, depth, &mut violations)
+            }
+            Value::String(text) => self.check_string(schema, text, pointer, &mut violations),
+            Value::Number(_) => check_number(schema, instance, pointer, &mut violations),
+            _ => {}
+        }
+        violations
+    }
+
+    fn check_type(
+        &self,
+        schema: &Map<String, Value>,
+        instance: &Value,
+        pointer: &str,
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        if let Some(expected) = schema.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::String(kind) => vec![kind.as_str()],
+                Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|kind| has_type(instance, kind)) {
+                violations.push(violation(
+                    pointer,
+                    &format!(
+                        "expected {}, found {}",
+                        allowed.join(" or "),
+                        type_name(instance)
+                    ),
+                ));
+            }
+        }
+        if let Some(value) = schema.get("const") {
+            if value != instance {
+                violations.push(violation(pointer, &format!("must be {}", value)));
+            }
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            if !values.contains(instance) {
+                let values: Vec<String> = values.iter().map(ToString::to_string).collect();
+                violations.push(violation(
+                    pointer,
+                    &format!("must be one of {}", values.join(", ")),
+                ));
+            }
+        }
+    }
+
+    fn check_combinators(
+        &self,
+        schema: &Map<String, Value>,
+        base: &str,
+        instance: &Value,
+        pointer: &str,
+        depth: usize,
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        let matches = |sub: &Value| {
+            self.check(sub, base, instance, pointer, depth + 1)
+                .is_empty()
+        };
+
+        if let Some(Value::Array(all)) = schema.get("allOf") {
+            for sub in all {
+                violations.extend(self.check(sub, base, instance, pointer, depth + 1));
+            }
+        }
+        if let Some(Value::Array(any)) = schema.get("anyOf") {
+            if !any.iter().any(matches) {
+                violations.push(violation(
+                    pointer,
+                    &format!("does not match any of {} allowed schemas", any.len()),
+                ));
+            }
+        }
+        if let Some(Value::Array(one)) = schema.get("oneOf") {
+            let matched = one.iter().filter(|sub| matches(sub)).count();
+            if matched != 1 {
+                violations.push(violation(
+                    pointer,
+                    &format!(
+                        "matches {} of {} schemas where exactly one is allowed",
+                        matched,
+                        one.len()
+                    ),
+                ));
+            }
+        }
+        if let Some(not) = schema.get("not") {
+            if matches(not) {
+                violations.push(violation(pointer, "matches a schema it must not match"));
+            }
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if matches(condition) { "then" } else { "else" };
+            if let Some(sub) = schema.get(branch) {
+                violations.extend(self.check(sub, base, instance, pointer, depth + 1));
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &Map<String, Value>,
+        base: &str,
+        object: &Map<String, Value>,
+        pointer: &str,
+        depth: usize,
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(key) {
+                    violations.push(violation(
+                        pointer,
+                        &format!("missing required property '{}'", key),
+                    ));
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let patterns = schema.get("patternProperties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+        for (key, value) in object {
+            let at = format!("{}/{}", pointer, escape(key));
+            let mut described = false;
+            if let Some(sub) = properties.and_then(|p| p.get(key)) {
+                described = true;
+                violations.extend(self.check(sub, base, value, &at, depth + 1));
+            }
+            for (pattern, sub) in patterns.into_iter().flatten() {
+                if self.is_match(pattern, key) {
+                    described = true;
+                    violations.extend(self.check(sub, base, value, &at, depth + 1));
+                }
+            }
+            match additional {
+                Some(Value::Bool(false)) if !described => {
+                    violations.push(violation(&at, &format!("unexpected property '{}'", key)));
+                }
+                Some(sub) if !described => {
+                    violations.extend(self.check(sub, base, value, &at, depth + 1));
+                }
+                _ => {}
+            }
+        }
+
+        let count = object.len();
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if (count as u64) < min {
+                violations.push(violation(
+                    pointer,
+                    &format!("has {} properties, at least {} required", count, min),
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if count as u64 > max {
+                violations.push(violation(
+                    pointer,
+                    &format!("has {} properties, at most {} allowed", count, max),
+                ));
+            }
+        }
+    }
+
+    fn check_array(
+        &self,
+        schema: &Map<String, Value>,
+        base: &str,
+        items: &[Value],
+        pointer: &str,
+        depth: usize,
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        // prefixItems (2020-12), or items as an array (earlier drafts)
+        let prefix = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix)), _) | (None, Some(Value::Array(prefix))) => {
+                prefix.as_slice()
+            }
+            _ => &[],
+        };
+        let rest = match schema.get("items") {
+            Some(Value::Array(_)) => schema.get("additionalItems"),
+            items => items,
+        };
+        for (i, item) in items.iter().enumerate() {
+            let at = format!("{}/{}", pointer, i);
+            if let Some(sub) = prefix.get(i).or(rest) {
+                violations.extend(self.check(sub, base, item, &at, depth + 1));
+            }
+        }
+
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min {
+                violations.push(violation(
+                    pointer,
+                    &format!("has {} items, at least {} required", items.len(), min),
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if items.len() as u64 > max {
+                violations.push(violation(
+                    pointer,
+                    &format!("has {} items, at most {} allowed", items.len(), max),
+                ));
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            for (i, item) in items.iter().enumerate() {
+                if items[..i].contains(item) {
+                    violations.push(violation(
+                        &format!("{}/{}", pointer, i),
+                        "duplicates an earlier item",
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_string(
+        &self,
+        schema: &Map<String, Value>,
+        text: &str,
+        pointer: &str,
+        violations: &mut Vec<SchemaViolation>,
+    ) {
+        let length = text.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                violations.push(violation(
+                    pointer,
+                    &format!("is {} characters long, at least {} required", length, min),
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                violations.push(violation(
+                    pointer,
+                    &format!("is {} characters long, at most {} allowed", length, max),
+                ));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            if !self.is_match(pattern, text) {
+                violations.push(violation(
+                    pointer,
+                    &format!("does not match pattern {}", pattern),
+                ));
+            }
+        }
+    }
+
+    /// Whether `text` matches `pattern`; an invalid pattern matches
+    /// anything.
+    fn is_match(&self, pattern: &str, text: &str) -> bool {
+        let mut patterns = self.patterns.borrow_mut();
+        let regex = patterns
+            .entry(pattern.to_string())
+            .or_insert_with(|| Regex::new(pattern).ok());
+        regex.as_ref().is_none_or(|regex| regex.is_match(text))
+    }
+}
+
+fn check_number(
+    schema: &Map<String, Value>,
+    instance: &Value,
+    pointer: &str,
+    violations: &mut Vec<SchemaViolation>,
+) {
+    let Some(number) = instance.as_f64() else {
+        return;
+    };
+    let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+    let checks = [
+        (bound("minimum").filter(|min| number < *min), "at least"),
+        (bound("maximum").filter(|max| number > *max), "at most"),
+        (
+            bound("exclusiveMinimum").filter(|min| number <= *min),
+            "more than",
+        ),
+        (
+            bound("exclusiveMaximum").filter(|max| number >= *max),
+            "less than",
+        ),
+    ];
+    for (failed, relation) in checks {
+        if let Some(limit) = failed {
+            violations.push(violation(
+                pointer,
+                &format!("must be {} {}", relation, limit),
+            ));
+        }
+    }
+}
+
+fn violation(pointer: &str, message: &str) -> SchemaViolation {
+    SchemaViolation {
+        pointer: pointer.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn has_type(instance: &Value, kind: &str) -> bool {
+    match kind {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => {
+            instance.is_i64()
+                || instance.is_u64()
+                || instance.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escape a key for a JSON pointer.
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Resolve a reference against the URI of the schema it appears in.
+fn resolve_uri(base: &str, reference: &str) -> String {
+    let base_document = base.split('#').next().unwrap_or(base);
+    if let Some(fragment) = reference.strip_prefix('#') {
+        return format!("{}#{}", base_document, fragment);
+    }
+    if reference.contains("://") || reference.starts_with("urn:") || base_document.is_empty() {
+        return reference.to_string();
+    }
+    match base_document.rfind('/') {
+        Some(i) => format!("{}/{}", &base_document[..i], reference),
+        None => reference.to_string(),
+    }
+}
+
+/// The subschema declaring `$anchor: anchor`.
+fn find_anchor<'a>(schema: &'a Value, anchor: &str) -> Option<&'a Value> {
+    match schema {
+        Value::Object(object) => {
+            if object.get("$anchor").and_then(Value::as_str) == Some(anchor) {
+                return Some(schema);
+            }
+            object.values().find_map(|value| find_anchor(value, anchor))
+        }
+        Value::Array(items) => items.iter().find_map(|item| find_anchor(item, anchor)),
+        _ => None,
+    }
+}
@@ -0,0 +1,458 @@
+//! KPAR archive inspection, extraction and packing.
+//!
+//! The inspection helpers look inside a `.kpar` delivery without importing it
+//! into a workspace: they list the archive entries, read the manifest, parse
+//! each model file on its own to count elements, and report structural problems.
+//! [`pack_kpar`] goes the other way and builds an archive from a source tree.
+
+use super::provenance::{PROVENANCE_PATH, Provenance};
+use super::{AnalysisOptions, escape_xml};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+use syster::interchange::{ModelFormat, Xmi};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Manifest location inside a KPAR archive.
+const MANIFEST_PATH: &str = "META-INF/manifest.xml";
+
+/// Directory holding the model files inside a KPAR archive.
+const MODEL_DIR: &str = "model/";
+
+/// Directory holding the original source files inside a packed KPAR archive.
+const SOURCE_DIR: &str = "src/";
+
+/// A single entry in a KPAR archive.
+#[derive(Debug, Serialize)]
+pub struct KparEntry {
+    /// Path of the entry inside the archive.
+    pub name: String,
+    /// Uncompressed size in bytes.
+    pub size: u64,
+    /// Compressed size in bytes.
+    pub compressed_size: u64,
+    /// Number of elements, for model files that parsed successfully.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub element_count: Option<usize>,
+}
+
+/// Result of inspecting a KPAR archive.
+#[derive(Debug, Serialize)]
+pub struct KparInspection {
+    /// All entries in archive order.
+    pub entries: Vec<KparEntry>,
+    /// Raw manifest XML, if present.
+    pub manifest: Option<String>,
+    /// Files listed in the manifest.
+    pub manifest_files: Vec<String>,
+    /// Provenance record, if the archive has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    /// Structural problems found while inspecting.
+    pub problems: Vec<String>,
+}
+
+impl KparInspection {
+    /// Whether the archive passed every check.
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// Total number of elements across all model files.
+    pub fn element_count(&self) -> usize {
+        self.entries.iter().filter_map(|e| e.element_count).sum()
+    }
+}
+
+/// Inspect a KPAR archive without importing it.
+///
+/// Checks that the archive has a manifest, contains at least one model file,
+/// that every model file parses, and that every file the manifest lists is
+/// actually present.
+pub fn inspect_kpar(path: &Path, verbose: bool) -> Result<KparInspection, String> {
+    let mut archive = open_archive(path)?;
+
+    let mut entries = Vec::new();
+    let mut manifest = None;
+    let mut provenance = None;
+    let mut problems = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read entry {}: {}", i, e))?;
+        let name = file.name().to_string();
+
+        if verbose {
+            tracing::info!("Inspecting: {}", name);
+        }
+
+        let mut element_count = None;
+        if name == MANIFEST_PATH {
+            let mut content = String::new();
+            match file.read_to_string(&mut content) {
+                Ok(_) => manifest = Some(content),
+                Err(e) => problems.push(format!("{}: unreadable manifest: {}", name, e)),
+            }
+        } else if name == PROVENANCE_PATH {
+            let mut content = String::new();
+            match file.read_to_string(&mut content) {
+                Ok(_) => match serde_json::from_str(&content) {
+                    Ok(record) => provenance = Some(record),
+                    Err(e) => problems.push(format!("{}: invalid provenance: {}", name, e)),
+                },
+                Err(e) => problems.push(format!("{}: {}", name, e)),
+            }
+        } else if is_model_file(&name) {
+            let mut bytes = Vec::new();
+            match file.read_to_end(&mut bytes) {
+                Ok(_) => match Xmi.read(&bytes) {
+                    Ok(model) => element_count = Some(model.elements.len()),
+                    Err(e) => problems.push(format!("{}: {}", name, e)),
+                },
+                Err(e) => problems.push(format!("{}: {}", name, e)),
+            }
+        }
+
+        entries.push(KparEntry {
+            name,
+            size: file.size(),
+            compressed_size: file.compressed_size(),
+            element_count,
+        });
+    }
+
+    let manifest_files = manifest
+        .as_deref()
+        .map(manifest_model_files)
+        .unwrap_or_default();
+
+    if manifest.is_none() {
+        problems.push(format!("missing {}", MANIFEST_PATH));
+    }
+    if !entries.iter().any(|e| is_model_file(&e.name)) {
+        problems.push(format!("no model files under {}", MODEL_DIR));
+    }
+    for listed in &manifest_files {
+        if !entries.iter().any(|e| &e.name == listed) {
+            problems.push(format!("manifest lists missing file {}", listed));
+        }
+    }
+
+    Ok(KparInspection {
+        entries,
+        manifest,
+        manifest_files,
+        provenance,
+        problems,
+    })
+}
+
+/// Extract every entry of a KPAR archive into `dest`.
+///
+/// Entries whose paths would escape `dest` are rejected.
+///
+/// # Returns
+/// The number of files written.
+pub fn extract_kpar(path: &Path, dest: &Path, verbose: bool) -> Result<usize, String> {
+    let mut archive = open_archive(path)?;
+    let mut written = 0;
+
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read entry {}: {}", i, e))?;
+        let relative = file
+            .enclosed_name()
+            .ok_or_else(|| format!("Refusing to extract unsafe path: {}", file.name()))?;
+        let target = dest.join(relative);
+
+        if file.is_dir() {
+            std::fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut out = File::create(&target)
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        std::io::copy(&mut file, &mut out)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+
+        if verbose {
+            tracing::info!("Extracted: {}", target.display());
+        }
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+/// Package metadata written to the manifest by [`pack_kpar`].
+#[derive(Debug, Clone, Default)]
+pub struct PackManifest {
+    /// Package name.
+    pub name: String,
+    /// Package version.
+    pub version: Option<String>,
+    /// Package description.
+    pub description: Option<String>,
+    /// Packages this one depends on.
+    pub dependencies: Vec<PackDependency>,
+}
+
+/// A dependency recorded in a packed manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackDependency {
+    /// Dependency name.
+    pub name: String,
+    /// Required version.
+    pub version: Option<String>,
+}
+
+/// Build a KPAR archive from a directory of SysML/KerML sources.
+///
+/// The archive contains the exported model as `model/main.xmi`, the original
+/// source files under `src/`, a manifest generated from `manifest`, and a
+/// provenance record.
+///
+/// # Returns
+/// The archive as bytes.
+pub fn pack_kpar(
+    dir: &Path,
+    manifest: &PackManifest,
+    options: &AnalysisOptions,
+) -> Result<Vec<u8>, String> {
+    if !dir.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+
+    let xmi = super::export_model(
+        dir,
+        "xmi",
+        options.verbose,
+        options.load_stdlib,
+        options.stdlib_path.as_deref(),
+        false,
+    )?;
+    let provenance = Provenance::collect(dir, options.load_stdlib, options.stdlib_path.as_deref())?;
+    let provenance = serde_json::to_vec_pretty(&provenance)
+        .map_err(|e| format!("Failed to serialize provenance: {}", e))?;
+
+    let mut sources: Vec<_> = WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file() && super::is_sysml_file(e.path()))
+        .map(|e| e.into_path())
+        .collect();
+    sources.sort();
+
+    let model_file = format!("{}main.xmi", MODEL_DIR);
+
+    let mut source_files = Vec::new();
+    for source in &sources {
+        let relative = source.strip_prefix(dir).unwrap_or(source);
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        source_files.push(format!("{}{}", SOURCE_DIR, name));
+    }
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let file_options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut add = |name: &str, bytes: &[u8]| -> Result<(), String> {
+        if options.verbose {
+            tracing::info!("Packing: {}", name);
+        }
+        zip.start_file(name, file_options)
+            .map_err(|e| format!("Failed to create {}: {}", name, e))?;
+        zip.write_all(bytes)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))
+    };
+
+    add(
+        MANIFEST_PATH,
+        manifest_xml(manifest, &model_file, &source_files).as_bytes(),
+    )?;
+    add(PROVENANCE_PATH, &provenance)?;
+    add(&model_file, &xmi)?;
+    for (source, name) in sources.iter().zip(&source_files) {
+        let bytes = std::fs::read(source)
+            .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        add(name, &bytes)?;
+    }
+
+    let buffer = zip
+        .finish()
+        .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+    Ok(buffer.into_inner())
+}
+
+/// Parse a `--dependency` value of the form `NAME` or `NAME@VERSION`.
+pub fn parse_dependency(value: &str) -> Result<PackDependency, String> {
+    let (name, version) = match value.split_once('@') {
+        Some((name, version)) => (name, Some(version.to_string())),
+        None => (value, None),
+    };
+    if name.is_empty() || version.as_deref() == Some("") {
+        return Err(format!(
+            "invalid dependency '{}': expected NAME or NAME@VERSION",
+            value
+        ));
+    }
+    Ok(PackDependency {
+        name: name.to_string(),
+        version,
+    })
+}
+
+/// Render a manifest as XML.
+fn manifest_xml(manifest: &PackManifest, model_file: &str, source_files: &[String]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <manifest xmlns=\"http://www.omg.org/spec/SysML/20230201/kpar\">\n",
+    );
+
+    xml.push_str(&format!(
+        "  <package name=\"{}\"",
+        escape_xml(&manifest.name)
+    ));
+    if let Some(version) = &manifest.version {
+        xml.push_str(&format!(" version=\"{}\"", escape_xml(version)));
+    }
+    xml.push_str(">\n");
+
+    if let Some(description) = &manifest.description {
+        xml.push_str(&format!(
+            "    <description>{}</description>\n",
+            escape_xml(description)
+        ));
+    }
+
+    xml.push_str("    <model-files>\n");
+    xml.push_str(&format!("      <file>{}</file>\n", escape_xml(model_file)));
+    xml.push_str("    </model-files>\n");
+
+    if !source_files.is_empty() {
+        xml.push_str("    <source-files>\n");
+        for file in source_files {
+            xml.push_str(&format!("      <file>{}</file>\n", escape_xml(file)));
+        }
+        xml.push_str("    </source-files>\n");
+    }
+
+    if !manifest.dependencies.is_empty() {
+        xml.push_str("    <dependencies>\n");
+        for dep in &manifest.dependencies {
+            xml.push_str(&format!(
+                "      <dependency name=\"{}\"",
+                escape_xml(&dep.name)
+            ));
+            if let Some(version) = &dep.version {
+                xml.push_str(&format!(" version=\"{}\"", escape_xml(version)));
+            }
+            xml.push_str("/>\n");
+        }
+        xml.push_str("    </dependencies>\n");
+    }
+
+    xml.push_str("  </package>\n</manifest>\n");
+    xml
+}
+
+/// Open a KPAR file as a ZIP archive.
+fn open_archive(path: &Path) -> Result<ZipArchive<File>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    ZipArchive::new(file)
+        .map_err(|e| format!("{} is not a valid KPAR archive: {}", path.display(), e))
+}
+
+/// Check if an archive entry is a model file.
+fn is_model_file(name: &str) -> bool {
+    name.starts_with(MODEL_DIR) && name.ends_with(".xmi")
+}
+
+/// The package version declared in the manifest of a KPAR archive held in
+/// memory, if any.
+pub(crate) fn manifest_version(bytes: &[u8]) -> Result<Option<String>, String> {
+    let manifest = read_entries(bytes)?
+        .into_iter()
+        .find(|(name, _)| name == MANIFEST_PATH)
+        .map(|(_, content)| String::from_utf8_lossy(&content).into_owned());
+    Ok(manifest.and_then(|manifest| {
+        let package = manifest.split("<package").nth(1)?.split('>').next()?;
+        let version = package.split(" version=\"").nth(1)?.split('"').next()?;
+        Some(
+            version
+                .replace("&quot;", "\"")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&"),
+        )
+    }))
+}
+
+/// Collect the `<file>` entries listed in a manifest.
+fn manifest_model_files(manifest: &str) -> Vec<String> {
+    manifest
+        .split("<file>")
+        .skip(1)
+        .filter_map(|rest| rest.split_once("</file>"))
+        .map(|(name, _)| name.trim().to_string())
+        .collect()
+}
+
+/// Read every file entry of a KPAR archive held in memory, in archive order.
+pub(crate) fn read_entries(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>, String> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))
+        .map_err(|e| format!("Not a valid KPAR archive: {}", e))?;
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read entry {}: {}", i, e))?;
+        if file.is_dir() {
+            continue;
+        }
+        let mut content = Vec::new();
+        file.read_to_end(&mut content)
+            .map_err(|e| format!("Failed to read {}: {}", file.name(), e))?;
+        entries.push((file.name().to_string(), content));
+    }
+    Ok(entries)
+}
+
+/// Write entries to a new KPAR archive, in the given order.
+pub(crate) fn write_entries(entries: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    write_entries_with(entries, CompressionMethod::Deflated)
+}
+
+/// Write entries to a new KPAR archive compressed with `method`.
+pub(crate) fn write_entries_with(
+    entries: &[(String, Vec<u8>)],
+    method: CompressionMethod,
+) -> Result<Vec<u8>, String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(method);
+
+    for (name, bytes) in entries {
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to create {}: {}", name, e))?;
+        zip.write_all(bytes)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    }
+
+    let buffer = zip
+        .finish()
+        .map_err(|e| format!("Failed to finalize archive: {}", e))?;
+    Ok(buffer.into_inner())
+}
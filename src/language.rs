@@ -0,0 +1,159 @@
+//! SysML v2 language versions.
+//!
+//! The parser accepts the union of every supported release, so a model can
+//! use a keyword its reviewers' older tool rejects without anyone noticing.
+//! Selecting a version with `--sysml-version` (or `sysml_version` in
+//! `syster.toml`) reports each keyword that version does not have:
+//!
+//! ```toml
+//! sysml_version = "2024-09"
+//! ```
+
+use super::DiagnosticInfo;
+use super::lint::codes;
+use super::project::{find_project_file, load_table};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use syster::hir::Severity;
+use syster::ide::AnalysisHost;
+use syster::parser::Lexer;
+
+/// A release of the SysML v2 specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SysmlVersion {
+    /// September 2024 release.
+    V2024_09,
+    /// April 2025 release.
+    V2025_04,
+}
+
+impl SysmlVersion {
+    /// Every supported release, oldest first.
+    pub const ALL: &'static [Self] = &[Self::V2024_09, Self::V2025_04];
+
+    /// The version set in the nearest `syster.toml` at or above `input`, if
+    /// any.
+    pub fn discover(input: &Path) -> Result<Option<Self>, String> {
+        let Some(path) = find_project_file(input) else {
+            return Ok(None);
+        };
+        load_table::<String>(&path, "sysml_version")?
+            .map(|v| v.parse())
+            .transpose()
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))
+    }
+}
+
+impl fmt::Display for SysmlVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::V2024_09 => "2024-09",
+            Self::V2025_04 => "2025-04",
+        })
+    }
+}
+
+impl FromStr for SysmlVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|v| v.to_string() == s.trim())
+            .ok_or_else(|| {
+                let known: Vec<String> = Self::ALL.iter().map(|v| v.to_string()).collect();
+                format!(
+                    "Unknown SysML version '{}' (expected one of: {})",
+                    s,
+                    known.join(", ")
+                )
+            })
+    }
+}
+
+/// A keyword that only some releases have.
+struct VersionedKeyword {
+    keyword: &'static str,
+    /// First release with the keyword (`None`: every supported release).
+    introduced: Option<SysmlVersion>,
+    /// First release without it.
+    removed: Option<SysmlVersion>,
+    /// What to write instead in older or newer releases.
+    hint: &'static str,
+}
+
+/// Keyword differences between the supported releases.
+const KEYWORDS: &[VersionedKeyword] = &[
+    VersionedKeyword {
+        keyword: "constant",
+        introduced: Some(SysmlVersion::V2025_04),
+        removed: None,
+        hint: "use `readonly`",
+    },
+    VersionedKeyword {
+        keyword: "readonly",
+        introduced: None,
+        removed: Some(SysmlVersion::V2025_04),
+        hint: "use `constant`",
+    },
+];
+
+/// Report every keyword in `files` that `version` does not have.
+pub(crate) fn version_diagnostics(
+    host: &AnalysisHost,
+    files: &HashSet<&str>,
+    version: SysmlVersion,
+) -> Vec<DiagnosticInfo> {
+    let mut diagnostics = Vec::new();
+    for path in host.files().keys() {
+        let file = path.to_string_lossy().to_string();
+        if !files.contains(file.as_str()) {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        let (mut line, mut line_start) = (1, 0);
+        for token in Lexer::new(&text) {
+            let offset = u32::from(token.offset) as usize;
+            if token.kind.is_keyword() {
+                let unavailable = KEYWORDS.iter().find(|k| {
+                    k.keyword == token.text
+                        && (k.introduced.is_some_and(|v| version < v)
+                            || k.removed.is_some_and(|v| version >= v))
+                });
+                if let Some(k) = unavailable {
+                    let col = (offset - line_start) as u32 + 1;
+                    let change = match k.introduced {
+                        Some(v) if version < v => format!("introduced in {}", v),
+                        _ => format!("removed in {}", k.removed.unwrap_or(version)),
+                    };
+                    diagnostics.push(DiagnosticInfo {
+                        file: file.clone(),
+                        line,
+                        col,
+                        end_line: line,
+                        end_col: col + token.text.len() as u32,
+                        message: format!(
+                            "`{}` is not available in SysML {} ({}; {})",
+                            k.keyword, version, change, k.hint
+                        ),
+                        severity: Severity::Error,
+                        code: Some(codes::LANGUAGE_VERSION.to_string()),
+                        blame: None,
+                        owners: Vec::new(),
+                        related: Vec::new(),
+                    });
+                }
+            }
+            for (i, _) in token.text.match_indices('\n') {
+                line += 1;
+                line_start = offset + i + 1;
+            }
+        }
+    }
+    diagnostics
+}
@@ -0,0 +1,238 @@
+//! Allowed dependencies between layers (`[layers]` in `syster.toml`).
+//!
+//! Each key of the table is a package glob naming a layer, and its value the
+//! globs of the packages that layer may depend on:
+//!
+//! ```toml
+//! [layers]
+//! "App::*" = ["Domain::*", "Std::*"]
+//! "Domain::*" = ["Std::*"]
+//! ```
+//!
+//! In a glob, `*` matches within one name segment and `**` across segments;
+//! a glob matching a package also matches the packages nested in it. A
+//! package may always depend on packages of its own layer. Every import,
+//! typing, specialization or other reference from a member of a layer to a
+//! package it is not allowed to depend on is an error, pointing at both
+//! ends. Packages no layer matches are unconstrained, and references into
+//! the standard library and other libraries are always allowed.
+
+use super::arch::{enclosing_package, package_of};
+use super::lint::codes;
+use super::project::{find_project_file, load_table};
+use super::{DiagnosticInfo, RelatedInformation};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+use syster::hir::{Severity, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// Allowed dependencies between layers.
+#[derive(Debug, Clone)]
+pub struct LayerRules {
+    layers: Vec<Layer>,
+}
+
+#[derive(Debug, Clone)]
+struct Layer {
+    glob: Glob,
+    allowed: Vec<Glob>,
+}
+
+/// A package glob and the pattern it compiles to.
+#[derive(Debug, Clone)]
+pub(crate) struct Glob {
+    text: String,
+    regex: Regex,
+}
+
+impl LayerRules {
+    /// Rules from layer globs and the globs each may depend on.
+    pub fn new(layers: BTreeMap<String, Vec<String>>) -> Result<Self, String> {
+        let layers = layers
+            .into_iter()
+            .map(|(layer, allowed)| {
+                Ok(Layer {
+                    glob: Glob::new(&layer)?,
+                    allowed: allowed
+                        .iter()
+                        .map(|glob| Glob::new(glob))
+                        .collect::<Result<_, String>>()?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { layers })
+    }
+
+    /// Load the `[layers]` table of a project file, if it has one.
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        load_table(path, "layers")?
+            .map(Self::new)
+            .transpose()
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))
+    }
+
+    /// Load the `[layers]` table of the nearest `syster.toml` in `input`
+    /// (or its directory) and its ancestors, if there is one.
+    pub fn discover(input: &Path) -> Result<Option<Self>, String> {
+        match find_project_file(input) {
+            Some(path) => Self::load(&path),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether the package `from` may depend on the package `to`.
+    pub fn allows(&self, from: &str, to: &str) -> bool {
+        self.violated(from, to).is_empty()
+    }
+
+    /// Each layer glob with the globs it may depend on, as written.
+    pub(crate) fn globs(&self) -> Vec<(&str, Vec<&str>)> {
+        self.layers
+            .iter()
+            .map(|layer| {
+                let allowed = layer.allowed.iter().map(Glob::as_str).collect();
+                (layer.glob.as_str(), allowed)
+            })
+            .collect()
+    }
+
+    /// The layers of `from` that do not allow depending on `to`; empty if
+    /// it is allowed.
+    fn violated(&self, from: &str, to: &str) -> Vec<&Layer> {
+        let layers: Vec<&Layer> = self
+            .layers
+            .iter()
+            .filter(|layer| layer.glob.matches(from))
+            .collect();
+        if layers.iter().any(|layer| {
+            layer.glob.matches(to) || layer.allowed.iter().any(|glob| glob.matches(to))
+        }) {
+            return Vec::new();
+        }
+        layers
+    }
+}
+
+impl Glob {
+    pub(crate) fn new(text: &str) -> Result<Self, String> {
+        if text.is_empty() {
+            return Err("empty package glob".to_string());
+        }
+        let mut pattern = String::from("^");
+        let mut rest = text;
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix("**") {
+                pattern.push_str(".*");
+                rest = after;
+            } else if let Some(after) = rest.strip_prefix('*') {
+                pattern.push_str("[^:]*");
+                rest = after;
+            } else {
+                let end = rest.find('*').unwrap_or(rest.len());
+                pattern.push_str(&regex::escape(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+        pattern.push_str("(::.*)?$");
+        let regex =
+            Regex::new(&pattern).map_err(|e| format!("invalid package glob '{}': {}", text, e))?;
+        Ok(Self {
+            text: text.to_string(),
+            regex,
+        })
+    }
+
+    pub(crate) fn matches(&self, package: &str) -> bool {
+        self.regex.is_match(package)
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Report references from members of the packages in `files` to packages
+/// their layers may not depend on; references into `library_files` are
+/// skipped.
+pub(crate) fn layer_diagnostics(
+    host: &AnalysisHost,
+    files: &HashSet<&str>,
+    library_files: &HashSet<String>,
+    rules: &LayerRules,
+) -> Vec<DiagnosticInfo> {
+    let index = host.symbol_index();
+    let packages: BTreeSet<String> = index
+        .all_symbols()
+        .filter(|s| s.kind == SymbolKind::Package)
+        .map(|s| s.qualified_name.to_string())
+        .collect();
+
+    let mut diagnostics = Vec::new();
+    for symbol in index.all_symbols() {
+        let Some(file) = host.get_file_path(symbol.file) else {
+            continue;
+        };
+        if !files.contains(file) {
+            continue;
+        }
+        let Some(from) = enclosing_package(&packages, &symbol.qualified_name) else {
+            continue;
+        };
+        let mut reported = HashSet::new();
+        for target in super::closure::dependencies(index, symbol) {
+            let Some(target_symbol) = index.lookup_qualified(&target) else {
+                continue;
+            };
+            let target_file = host.get_file_path(target_symbol.file).unwrap_or_default();
+            if library_files.contains(target_file) {
+                continue;
+            }
+            let Some(to) = package_of(&packages, &target) else {
+                continue;
+            };
+            let violated = rules.violated(from, to);
+            if violated.is_empty() || !reported.insert(target.clone()) {
+                continue;
+            }
+
+            let allowed: Vec<&str> = violated
+                .iter()
+                .flat_map(|layer| layer.allowed.iter().map(|glob| glob.text.as_str()))
+                .collect();
+            let layers: Vec<&str> = violated
+                .iter()
+                .map(|layer| layer.glob.text.as_str())
+                .collect();
+            diagnostics.push(DiagnosticInfo {
+                file: file.to_string(),
+                line: symbol.start_line + 1,
+                col: symbol.start_col + 1,
+                end_line: symbol.end_line + 1,
+                end_col: symbol.end_col + 1,
+                message: format!(
+                    "`{}` depends on `{}`, but layer {} may only depend on {}",
+                    symbol.qualified_name,
+                    target,
+                    layers.join(", "),
+                    if allowed.is_empty() {
+                        "itself".to_string()
+                    } else {
+                        allowed.join(", ")
+                    }
+                ),
+                severity: Severity::Error,
+                code: Some(codes::LAYER_DEPENDENCY.to_string()),
+                blame: None,
+                owners: Vec::new(),
+                related: vec![RelatedInformation {
+                    file: target_file.to_string(),
+                    line: target_symbol.start_line + 1,
+                    col: target_symbol.start_col + 1,
+                    message: format!("`{}` declared here, in package {}", target, to),
+                }],
+            });
+        }
+    }
+    diagnostics
+}
@@ -3,14 +3,271 @@
 //! This module provides the `run_analysis` function for parsing and analyzing
 //! SysML v2 and KerML files using the syster-base library.
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use syster::hir::{Severity, check_file};
+use std::str::FromStr;
+use syster::hir::{Severity, SymbolKind, check_file};
 use syster::ide::AnalysisHost;
+use systerignore::IgnoreRules;
 use walkdir::WalkDir;
 
+pub mod aliases;
+pub mod allocations;
+pub mod annotations;
+pub mod arch;
+#[cfg(feature = "interchange")]
+pub mod attestation;
+pub mod badge;
+#[cfg(feature = "interchange")]
+pub mod baseline;
+pub mod blame;
+pub mod budgets;
+#[cfg(feature = "interchange")]
+pub mod cache;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod clones;
+pub mod closure;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+#[cfg(feature = "interchange")]
+pub mod compress;
+#[cfg(feature = "interchange")]
+pub mod conflicts;
+pub mod coverage;
+#[cfg(feature = "interchange")]
+pub mod crash;
+#[cfg(feature = "codegen")]
+pub mod dashboard;
+pub mod deps;
+pub mod diagdiff;
+mod duplicates;
+mod extents;
+pub mod flows;
+#[cfg(feature = "codegen")]
+pub mod glossary;
+pub mod history;
+pub mod hover;
+#[cfg(any(feature = "interchange", feature = "jira"))]
+mod http;
+pub mod i18n;
+#[cfg(feature = "codegen")]
+pub mod icd;
+#[cfg(feature = "interchange")]
+pub mod ids;
+pub mod impact;
+#[cfg(feature = "signing")]
+pub mod integrity;
+#[cfg(feature = "jira")]
+pub mod jira;
+#[cfg(feature = "interchange")]
+pub mod json_schema;
+#[cfg(feature = "interchange")]
+pub mod kpar;
+pub mod language;
+pub mod layers;
+pub mod lint;
+pub mod logging;
+pub mod metrics;
+#[cfg(feature = "interchange")]
+pub mod migrate;
+pub mod minimize;
+mod names;
+pub mod notebook;
+pub mod origin;
+pub mod owners;
+#[cfg(feature = "interchange")]
+pub mod paging;
+pub mod parametrics;
+pub mod precommit;
+pub mod project;
+pub mod projects;
+#[cfg(feature = "interchange")]
+pub mod provenance;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "interchange")]
+pub mod recovery;
+#[cfg(feature = "interchange")]
+pub mod redact;
+#[cfg(feature = "interchange")]
+pub mod references;
+#[cfg(feature = "interchange")]
+pub mod repository;
+pub mod requirements;
+pub mod review;
+#[cfg(all(feature = "codegen", feature = "interchange"))]
+pub mod safety;
+pub mod sample;
+#[cfg(feature = "codegen")]
+pub mod scaffold;
+pub mod scip;
+#[cfg(feature = "interchange")]
+pub mod semver;
+pub mod sequence;
+mod shadowing;
+pub mod sidx;
+#[cfg(feature = "interchange")]
+pub mod split;
+pub mod stdlib;
+#[cfg(feature = "interchange")]
+pub mod subtree;
+pub mod summary;
+#[cfg(feature = "interchange")]
+pub mod sysml_json;
+mod systerignore;
+pub mod tags;
+#[cfg(feature = "otel")]
+pub mod telemetry;
+pub mod tokens;
+#[cfg(feature = "trends")]
+pub mod trends;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod variants;
+#[cfg(feature = "codegen")]
+pub mod verification;
+#[cfg(feature = "interchange")]
+pub mod verify;
+#[cfg(feature = "interchange")]
+pub mod visibility;
+pub mod workspace;
+#[cfg(feature = "interchange")]
+pub mod xmi_stream;
+
+pub use aliases::{
+    AliasChain, AliasReport, MatchKind, Reexport, ReexportedMember, SymbolMatch, find_symbol,
+    resolve_aliases,
+};
+pub use allocations::{
+    Allocation, AllocationConfig, AllocationIssue, AllocationReport, check_allocations,
+};
+pub use annotations::{is_annotated, matches_metadata, select_annotated};
+pub use arch::{ArchConfig, ArchReport, ArchSmell, LayerRule, PackageMetrics, arch_check};
+#[cfg(feature = "interchange")]
+pub use attestation::{Attestation, ResolvedSource};
+pub use badge::{Badge, BadgeMetric, badge};
+#[cfg(feature = "interchange")]
+pub use baseline::{Baseline, BaselineComparison, compare_baseline, create_baseline};
+pub use blame::{Blame, annotate_blame};
+pub use budgets::Budgets;
+#[cfg(feature = "interchange")]
+pub use cache::{RemoteCache, cache_key};
+pub use clones::{CloneGroup, CloneOptions, CloneReport, CloneSite, NearClone, find_clones};
+pub use closure::{ClosureElement, ClosureReport, dependency_closure, package_closure};
+#[cfg(feature = "codegen")]
+pub use codegen::{CodegenElement, CodegenModel, DocumentFormat, generate};
+#[cfg(feature = "interchange")]
+pub use compress::{Compression, compress_export};
+#[cfg(feature = "interchange")]
+pub use conflicts::{IdConflictPolicy, IdResolution};
+pub use coverage::{DocCoverage, parse_doc_kind};
+#[cfg(feature = "interchange")]
+pub use crash::{
+    CrashRedaction, CrashReport, crash_report, install_panic_hook, redact_source,
+    write_crash_report,
+};
+#[cfg(feature = "codegen")]
+pub use dashboard::export_html;
+pub use deps::{Dependency, DependencySource, FetchStatus, Manifest, fetch};
+pub use diagdiff::{DiagnosticDiff, diff_diagnostics, render_diagnostic_diff};
+pub use flows::{FlowHop, FlowIssue, FlowReport, PortDirection, SignalFlow, check_flows};
+#[cfg(feature = "codegen")]
+pub use glossary::{Glossary, collect_glossary, generate_glossary, render_glossary};
+pub use history::{HistoryMetric, HistoryPoint, analyze_history, history_csv, history_json};
+pub use hover::{HoverInfo, SourcePosition, hover};
+pub use i18n::{Catalog, Lang};
+#[cfg(feature = "codegen")]
+pub use icd::{IcdReport, generate_icd, interface_control_document, render_icd};
+#[cfg(feature = "interchange")]
+pub use ids::{DEFAULT_ID_NAMESPACE, ElementIdEntry, IdPolicy, element_ids, element_ids_csv};
+pub use impact::{
+    ChangeKind, ChangedElement, ImpactReport, ImpactedElement, ImpactedPackage, change_impact,
+    diff_impact,
+};
+#[cfg(feature = "signing")]
+pub use integrity::{IntegrityReport, SignatureStatus, seal_kpar, seal_xmi, verify_integrity};
+#[cfg(feature = "jira")]
+pub use jira::{JiraConfig, JiraIssue, PublishSummary, plan_issues, publish_issues};
+#[cfg(feature = "interchange")]
+pub use json_schema::{SchemaBundle, SchemaReport, SchemaViolation, validate_interchange};
+#[cfg(feature = "interchange")]
+pub use kpar::{
+    KparEntry, KparInspection, PackDependency, PackManifest, extract_kpar, inspect_kpar, pack_kpar,
+};
+pub use language::SysmlVersion;
+pub use layers::LayerRules;
+pub use lint::{LintReport, NamingConfig, Rename, apply_renames, lint};
+pub use logging::{LogFormat, LogLevel, LogOptions, Logging, init_logging};
+pub use metrics::{prometheus_metrics, push_metrics};
+#[cfg(feature = "interchange")]
+pub use migrate::{AppliedMigration, MigrationReport, migrate_file};
+pub use minimize::{Reproducer, ReproducerFile, minimize};
+pub use notebook::{NotebookOutput, export_notebook, notebook_analysis};
+pub use origin::{FileOrigin, FileOrigins};
+pub use owners::ModelOwners;
+#[cfg(feature = "interchange")]
+pub use paging::{PageEntry, PageIndex, paginate_jsonld, write_jsonld_pages};
+pub use parametrics::{
+    Access, ParametricCluster, ParametricEdge, ParametricGraph, ParametricKind, ParametricNode,
+    parametric_graph,
+};
+pub use precommit::{install_pre_commit_hook, run_analysis_staged};
+#[cfg(feature = "interchange")]
+pub use projects::{ProjectExport, ProjectsExport, export_projects};
+pub use projects::{ProjectResult, ProjectsResult, analyze_projects, find_projects};
+#[cfg(feature = "interchange")]
+pub use provenance::{Provenance, SCHEMA_VERSION, embed_provenance};
+#[cfg(feature = "interchange")]
+pub use recovery::SkippedContent;
+#[cfg(feature = "interchange")]
+pub use redact::{RedactionMap, redact_model, unredact_model};
+#[cfg(feature = "interchange")]
+pub use references::{ExternalReference, ReferenceStatus};
+#[cfg(feature = "interchange")]
+pub use repository::{PulledModel, PushReport, Repository, commit_changes, pull_model, push_model};
+pub use requirements::{DEFAULT_VAGUE_TERMS, RequirementRules};
+pub use review::{export_gerrit, export_rdjson};
+#[cfg(all(feature = "codegen", feature = "interchange"))]
+pub use safety::{FmeaRow, FmeaTable, fmea, fmea_table};
+pub use sample::{Sample, SampleFile, sample};
+#[cfg(feature = "codegen")]
+pub use scaffold::{TestFramework, TestScaffold, TestStub, generate_tests, test_stubs};
+pub use scip::build_scip_index;
+#[cfg(feature = "interchange")]
+pub use semver::{Change, ChangeLevel, SemverReport, classify_changes, semver_check};
+pub use sequence::{SequenceDiagram, SequenceStep, render_plantuml_sequence, sequence_diagrams};
+pub use sidx::{IndexedSymbol, ModelIndex, build_index};
+#[cfg(feature = "interchange")]
+pub use split::{DecompiledFile, decompile_model_split};
+pub use stdlib::{
+    KnownIssue, KnownIssues, StdlibCheck, StdlibSubset, check_stdlib, needed_stdlib_files,
+};
+pub use summary::{Breakdown, SummaryBy, render_summary, render_summary_in, top_offenders};
+pub use tags::{TagsFormat, build_tags};
+#[cfg(feature = "otel")]
+pub use telemetry::traces_endpoint;
+pub use tokens::{SemanticToken, TokenKind, semantic_tokens};
+#[cfg(feature = "trends")]
+pub use trends::{MetricsDb, MetricsRun, git_revision, trend_chart};
+pub use variants::{
+    MatrixFormat, Resolution, VariantConfig, VariationPoint, list_variation_points,
+    render_variant_matrix, resolve_variants, variation_points,
+};
+#[cfg(feature = "codegen")]
+pub use verification::{
+    VerificationCase, VerificationPlan, VerificationStatus, VerificationSubject,
+    VerifiedRequirement, render_verification_plan, verification_plan,
+};
+#[cfg(feature = "interchange")]
+pub use verify::{ModelDiff, diff_models, verify_against};
+pub use workspace::{SavedRelationship, SavedSymbol, Workspace, index_symbols};
+#[cfg(feature = "interchange")]
+pub use xmi_stream::write_xmi;
+
 /// Result of analyzing SysML/KerML files.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResult {
     /// Number of files analyzed.
     pub file_count: usize,
@@ -22,10 +279,55 @@ pub struct AnalysisResult {
     pub warning_count: usize,
     /// All diagnostics collected.
     pub diagnostics: Vec<DiagnosticInfo>,
+    /// Diagnostics inside the standard library, neither reported nor
+    /// counted unless [`AnalysisOptions::include_stdlib_diagnostics`] moves
+    /// them to `diagnostics`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stdlib_diagnostics: Vec<DiagnosticInfo>,
+    /// Documentation coverage of the model's own elements.
+    #[serde(default)]
+    pub doc_coverage: DocCoverage,
+    /// Files with syntax errors and the symbols recovered from them.
+    #[serde(default)]
+    pub syntax_errors: ParseRecovery,
+    /// Symbols, errors and warnings per top-level package.
+    #[serde(default)]
+    pub packages: Vec<Breakdown>,
+    /// Symbols, errors and warnings per file.
+    #[serde(default)]
+    pub files: Vec<Breakdown>,
+    /// Files with the most errors and warnings, worst first, when requested
+    /// (see [`top_offenders`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_offenders: Vec<Breakdown>,
+}
+
+/// Files of the model with syntax errors, and what analysis kept of them.
+///
+/// The parser recovers from syntax errors, so a broken file still declares
+/// the elements before and around the error, and references into it keep
+/// resolving. With [`AnalysisOptions::strict_parse`] such files are left out
+/// instead and no symbols are recovered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseRecovery {
+    /// Files with at least one syntax error.
+    pub files: usize,
+    /// Symbols recovered from them.
+    pub symbols: usize,
+}
+
+impl ParseRecovery {
+    /// Combine the recovery of disjoint sets of files.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            files: self.files + other.files,
+            symbols: self.symbols + other.symbols,
+        }
+    }
 }
 
 /// A diagnostic message with location information.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiagnosticInfo {
     /// File path containing the diagnostic.
     pub file: String,
@@ -40,10 +342,46 @@ pub struct DiagnosticInfo {
     /// The diagnostic message.
     pub message: String,
     /// Severity level.
-    #[serde(serialize_with = "serialize_severity")]
+    #[serde(
+        serialize_with = "serialize_severity",
+        deserialize_with = "deserialize_severity"
+    )]
     pub severity: Severity,
     /// Optional error code.
     pub code: Option<String>,
+    /// Last change to the offending line, with `--blame`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blame: Option<Blame>,
+    /// Teams owning the package the diagnostic is in, from `MODELOWNERS`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+    /// Other locations involved in the diagnostic.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<RelatedInformation>,
+}
+
+/// A secondary location of a diagnostic, e.g. the other definition of a
+/// duplicated name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedInformation {
+    /// File path of the location.
+    pub file: String,
+    /// Line (1-indexed).
+    pub line: u32,
+    /// Column (1-indexed).
+    pub col: u32,
+    /// What the location is.
+    pub message: String,
+}
+
+/// Name of a severity, as serialized.
+pub(crate) fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+        Severity::Hint => "hint",
+    }
 }
 
 /// Serialize Severity as a string
@@ -51,17 +389,97 @@ fn serialize_severity<S>(severity: &Severity, serializer: S) -> Result<S::Ok, S:
 where
     S: serde::Serializer,
 {
-    let s = match severity {
-        Severity::Error => "error",
-        Severity::Warning => "warning",
-        Severity::Info => "info",
-        Severity::Hint => "hint",
-    };
-    serializer.serialize_str(s)
+    serializer.serialize_str(severity_name(*severity))
+}
+
+/// Deserialize Severity from its string form
+fn deserialize_severity<'de, D>(deserializer: D) -> Result<Severity, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    match s.as_str() {
+        "error" => Ok(Severity::Error),
+        "warning" => Ok(Severity::Warning),
+        "info" => Ok(Severity::Info),
+        "hint" => Ok(Severity::Hint),
+        other => Err(serde::de::Error::custom(format!(
+            "unknown severity: {}",
+            other
+        ))),
+    }
+}
+
+/// A deterministic partition of the loaded files, selected with `--shard i/N`.
+///
+/// Every shard loads the full workspace (so cross-file references still
+/// resolve) but only reports files assigned to it. Files are sorted by path
+/// and dealt out round-robin, so the same tree always produces the same
+/// partition and the shards together cover every file exactly once.
+///
+/// Separate `--shard` runs each parse the standard library, as syster-base
+/// can't serialize parsed files or the index for another process to reuse;
+/// [`analyze_shards`] runs all shards in one process against a single
+/// parsed standard library and workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shard {
+    /// 1-based shard index.
+    pub index: usize,
+    /// Total number of shards.
+    pub count: usize,
+}
+
+impl Shard {
+    /// Create a shard, validating that `1 <= index <= count`.
+    pub fn new(index: usize, count: usize) -> Result<Self, String> {
+        if count == 0 {
+            return Err("Shard count must be at least 1".to_string());
+        }
+        if index == 0 || index > count {
+            return Err(format!(
+                "Shard index must be between 1 and {}, got {}",
+                count, index
+            ));
+        }
+        Ok(Self { index, count })
+    }
+
+    /// Select the paths belonging to this shard.
+    pub fn select<'a>(&self, paths: &'a [String]) -> Vec<&'a String> {
+        let mut sorted: Vec<&String> = paths.iter().collect();
+        sorted.sort();
+        sorted
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| i % self.count == self.index - 1)
+            .map(|(_, p)| p)
+            .collect()
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, count) = s
+            .split_once('/')
+            .ok_or_else(|| format!("Invalid shard '{}': expected I/N (e.g. 2/4)", s))?;
+        let index = index
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid shard index: {}", index))?;
+        let count = count
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid shard count: {}", count))?;
+        Shard::new(index, count)
+    }
 }
 
 /// Run analysis on input file or directory.
 ///
+/// Shorthand for [`analyze`] with only the standard library options set.
+///
 /// # Arguments
 /// * `input` - Path to a file or directory to analyze
 /// * `verbose` - Enable verbose output
@@ -76,23 +494,809 @@ pub fn run_analysis(
     load_stdlib: bool,
     stdlib_path: Option<&Path>,
 ) -> Result<AnalysisResult, String> {
+    let options = AnalysisOptions {
+        verbose,
+        load_stdlib,
+        stdlib_path: stdlib_path.map(Path::to_path_buf),
+        ..Default::default()
+    };
+    analyze(input, &options)
+}
+
+/// Run analysis, reporting only the files that belong to `shard`.
+#[deprecated(note = "use `analyze` with `AnalysisOptions::shard`")]
+pub fn run_analysis_shard(
+    input: &Path,
+    verbose: bool,
+    load_stdlib: bool,
+    stdlib_path: Option<&Path>,
+    shard: Option<Shard>,
+) -> Result<AnalysisResult, String> {
+    let options = AnalysisOptions {
+        verbose,
+        load_stdlib,
+        stdlib_path: stdlib_path.map(Path::to_path_buf),
+        shard,
+        ..Default::default()
+    };
+    analyze(input, &options)
+}
+
+/// Run analysis on the configuration selected by `config`.
+#[deprecated(note = "use `analyze` with `AnalysisOptions::config`")]
+pub fn run_analysis_with_config(
+    input: &Path,
+    verbose: bool,
+    load_stdlib: bool,
+    stdlib_path: Option<&Path>,
+    shard: Option<Shard>,
+    config: Option<&VariantConfig>,
+) -> Result<AnalysisResult, String> {
+    let options = AnalysisOptions {
+        verbose,
+        load_stdlib,
+        stdlib_path: stdlib_path.map(Path::to_path_buf),
+        shard,
+        config,
+        ..Default::default()
+    };
+    analyze(input, &options)
+}
+
+/// Run analysis with the given options, overriding their standard library
+/// and verbosity settings.
+#[deprecated(
+    note = "use `analyze`, setting `verbose`, `load_stdlib` and `stdlib_path` in the options"
+)]
+pub fn run_analysis_with_options(
+    input: &Path,
+    verbose: bool,
+    load_stdlib: bool,
+    stdlib_path: Option<&Path>,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult, String> {
+    let options = AnalysisOptions {
+        verbose,
+        load_stdlib,
+        stdlib_path: stdlib_path.map(Path::to_path_buf),
+        ..options.clone()
+    };
+    analyze(input, &options)
+}
+
+/// What [`analyze`] loads, checks and reports.
+///
+/// The default analyzes the input alone, without the standard library, at
+/// the [`Profile::Standard`] severities. Set fields directly or chain the
+/// setters of the same name, e.g.
+/// `AnalysisOptions::new().load_stdlib(true).profile(Profile::Strict)`.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisOptions<'a> {
+    /// Report progress as `tracing` events (see [`logging`]).
+    pub verbose: bool,
+    /// Load the standard library, so references into it resolve.
+    pub load_stdlib: bool,
+    /// Standard library directory, instead of the default locations.
+    pub stdlib_path: Option<PathBuf>,
+    /// How much of the standard library to load (see [`StdlibSubset`]).
+    pub stdlib_subset: StdlibSubset,
+    /// Report and count the diagnostics inside the standard library, which
+    /// are otherwise set aside in [`AnalysisResult::stdlib_diagnostics`].
+    pub include_stdlib_diagnostics: bool,
+    /// Only report the files of this shard.
+    pub shard: Option<Shard>,
+    /// Analyze the configuration selected by this variant selection.
+    pub config: Option<&'a VariantConfig>,
+    /// Report definitions whose qualified name is defined in more than one
+    /// file at this severity, each pointing at the other definitions through
+    /// [`DiagnosticInfo::related`].
+    pub duplicates: Option<Severity>,
+    /// Warn about undocumented elements of these kinds, and measure
+    /// documentation coverage over them (over all definitions if empty).
+    pub require_docs: Vec<SymbolKind>,
+    /// Pre-built indexes of dependencies to resolve references against,
+    /// instead of loading their sources.
+    pub indexes: &'a [ModelIndex],
+    /// Files or directories loaded as libraries: references resolve into
+    /// them, but they are not reported on (see [`Manifest::library_paths`]).
+    pub libraries: Vec<PathBuf>,
+    /// Bundle of checks and severities to apply (see [`Profile`]).
+    pub profile: Profile,
+    /// Report keywords this SysML release does not have.
+    pub sysml_version: Option<SysmlVersion>,
+    /// Leave files with syntax errors out of the analysis instead of using
+    /// the symbols recovered from them (see [`ParseRecovery`]).
+    pub strict_parse: bool,
+    /// How input directories are walked.
+    pub walk: WalkOptions,
+    /// Drop diagnostics less severe than this.
+    pub min_severity: Option<Severity>,
+    /// Report references to packages a layer may not depend on (see
+    /// [`LayerRules`]).
+    pub layers: Option<LayerRules>,
+    /// Annotate diagnostics and package breakdowns with the teams owning
+    /// them (see [`ModelOwners`]).
+    pub owners: Option<ModelOwners>,
+    /// Only report the diagnostics and packages of these teams (with
+    /// `owners`).
+    pub only_owners: Vec<String>,
+    /// Check the text of requirements (see [`RequirementRules`]).
+    pub requirements: Option<RequirementRules>,
+    /// Report where the model outgrows these size budgets (see
+    /// [`Budgets`]).
+    pub budgets: Option<Budgets>,
+}
+
+impl<'a> AnalysisOptions<'a> {
+    /// The default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report progress as `tracing` events.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Load the standard library, or not.
+    pub fn load_stdlib(mut self, load_stdlib: bool) -> Self {
+        self.load_stdlib = load_stdlib;
+        self
+    }
+
+    /// Load the standard library from `path`.
+    pub fn stdlib_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.stdlib_path = Some(path.into());
+        self
+    }
+
+    /// Load only part of the standard library.
+    pub fn stdlib_subset(mut self, subset: StdlibSubset) -> Self {
+        self.stdlib_subset = subset;
+        self
+    }
+
+    /// Report the diagnostics inside the standard library too.
+    pub fn include_stdlib_diagnostics(mut self, include: bool) -> Self {
+        self.include_stdlib_diagnostics = include;
+        self
+    }
+
+    /// Only report the files of `shard`.
+    pub fn shard(mut self, shard: Shard) -> Self {
+        self.shard = Some(shard);
+        self
+    }
+
+    /// Analyze the configuration `config` selects.
+    pub fn config(mut self, config: &'a VariantConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Report definitions repeated across files at `severity`.
+    pub fn duplicates(mut self, severity: Severity) -> Self {
+        self.duplicates = Some(severity);
+        self
+    }
+
+    /// Warn about undocumented elements of `kinds`.
+    pub fn require_docs(mut self, kinds: Vec<SymbolKind>) -> Self {
+        self.require_docs = kinds;
+        self
+    }
+
+    /// Resolve references against pre-built dependency indexes.
+    pub fn indexes(mut self, indexes: &'a [ModelIndex]) -> Self {
+        self.indexes = indexes;
+        self
+    }
+
+    /// Load `libraries` without reporting on them.
+    pub fn libraries(mut self, libraries: Vec<PathBuf>) -> Self {
+        self.libraries = libraries;
+        self
+    }
+
+    /// Apply the checks and severities of `profile`.
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Report keywords SysML release `version` does not have.
+    pub fn sysml_version(mut self, version: SysmlVersion) -> Self {
+        self.sysml_version = Some(version);
+        self
+    }
+
+    /// Leave files with syntax errors out.
+    pub fn strict_parse(mut self, strict_parse: bool) -> Self {
+        self.strict_parse = strict_parse;
+        self
+    }
+
+    /// Walk input directories as `walk` says.
+    pub fn walk(mut self, walk: WalkOptions) -> Self {
+        self.walk = walk;
+        self
+    }
+
+    /// Drop diagnostics less severe than `severity`.
+    pub fn min_severity(mut self, severity: Severity) -> Self {
+        self.min_severity = Some(severity);
+        self
+    }
+
+    /// Check dependencies between layers against `rules`.
+    pub fn layers(mut self, rules: LayerRules) -> Self {
+        self.layers = Some(rules);
+        self
+    }
+
+    /// Annotate diagnostics with the teams in `owners`.
+    pub fn owners(mut self, owners: ModelOwners) -> Self {
+        self.owners = Some(owners);
+        self
+    }
+
+    /// Only report what `teams` own.
+    pub fn only_owners(mut self, teams: Vec<String>) -> Self {
+        self.only_owners = teams;
+        self
+    }
+
+    /// Check requirement text against `rules`.
+    pub fn requirements(mut self, rules: RequirementRules) -> Self {
+        self.requirements = Some(rules);
+        self
+    }
+
+    /// Report where the model exceeds `budgets`.
+    pub fn budgets(mut self, budgets: Budgets) -> Self {
+        self.budgets = Some(budgets);
+        self
+    }
+}
+
+/// How input directories are walked for SysML and KerML files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WalkOptions {
+    /// Follow symbolic links to files and directories. Links back into an
+    /// ancestor directory are skipped with a warning.
+    pub follow_symlinks: bool,
+    /// Only load files at most this many levels below the input directory
+    /// (`Some(1)`: its own files, not those of its subdirectories).
+    pub max_depth: Option<usize>,
+    /// Skip, with a warning, files larger than this many bytes (typically
+    /// generated models nobody means to analyze).
+    pub max_file_size: Option<u64>,
+    /// Fail before loading anything if a directory holds more model files
+    /// than this.
+    pub max_files: Option<usize>,
+    /// Also skip what `.gitignore` files ignore (`.systerignore` files are
+    /// always read).
+    pub respect_gitignore: bool,
+    /// Skip subdirectories holding their own `syster.toml`, which are
+    /// analyzed as projects of their own (see [`projects`]).
+    pub skip_nested_projects: bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            follow_symlinks: true,
+            max_depth: None,
+            max_file_size: None,
+            max_files: None,
+            respect_gitignore: false,
+            skip_nested_projects: false,
+        }
+    }
+}
+
+/// Parse a file size in bytes, with an optional `K`, `M` or `G` suffix
+/// (powers of 1024), e.g. `512K` or `10M`.
+pub fn parse_file_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (digits, unit) = match s.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&s[..i], c.to_ascii_uppercase()),
+        _ => (s, 'B'),
+    };
+    let multiplier: u64 = match unit {
+        'B' => 1,
+        'K' => 1 << 10,
+        'M' => 1 << 20,
+        'G' => 1 << 30,
+        _ => return Err(format!("Invalid size '{}': expected e.g. 512K or 10M", s)),
+    };
+    digits
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("Invalid size '{}': expected e.g. 512K or 10M", s))
+}
+
+/// How demanding an analysis is, for models at different maturity levels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Profile {
+    /// Run every validation pass (duplicate definitions, missing docs on
+    /// definitions unless `require_docs` names other kinds) and treat
+    /// warnings as errors.
+    Strict,
+    /// Report diagnostics at their own severity.
+    #[default]
+    Standard,
+    /// Report unresolved, ambiguous and invalid imported names as warnings,
+    /// for models that are still incomplete.
+    Permissive,
+}
+
+/// Codes of name-resolution issues (undefined and ambiguous references,
+/// invalid imports), downgraded by [`Profile::Permissive`].
+const RESOLUTION_CODES: &[&str] = &["E0001", "E0002", "E0014"];
+
+impl Profile {
+    /// Adjust the severities of `diagnostics` to this profile.
+    pub fn apply(self, diagnostics: &mut [DiagnosticInfo]) {
+        for diag in diagnostics {
+            match self {
+                Self::Strict if matches!(diag.severity, Severity::Warning) => {
+                    diag.severity = Severity::Error;
+                }
+                Self::Permissive
+                    if matches!(diag.severity, Severity::Error)
+                        && diag
+                            .code
+                            .as_deref()
+                            .is_some_and(|code| RESOLUTION_CODES.contains(&code)) =>
+                {
+                    diag.severity = Severity::Warning;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Analyze an input file or directory as `options` say.
+///
+/// The partial results of sharded runs can be combined with
+/// [`merge_results`].
+pub fn analyze(input: &Path, options: &AnalysisOptions) -> Result<AnalysisResult, String> {
+    let _span = tracing::info_span!("analyze", input = %input.display()).entered();
+    analyze_into(&mut AnalysisHost::new(), input, options)
+}
+
+/// Load `input` as [`analyze`] does into `host`, which keeps the loaded
+/// files, and analyze it.
+pub(crate) fn analyze_into(
+    host: &mut AnalysisHost,
+    input: &Path,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult, String> {
+    let (stdlib_files, dependency_files) = load_into(host, input, options)?;
+    Ok(analyze_host(
+        host,
+        &stdlib_files,
+        &dependency_files,
+        options,
+    ))
+}
+
+/// Analyze every shard of `input` in this process, returning the result of
+/// shard 1 to `count` in order, as `--shard I/N` runs would.
+///
+/// The standard library and the workspace are parsed and indexed once and
+/// shared by all shards; `options.shard` is ignored.
+pub fn analyze_shards(
+    input: &Path,
+    options: &AnalysisOptions,
+    count: usize,
+) -> Result<Vec<AnalysisResult>, String> {
+    Shard::new(1, count)?;
+    let _span = tracing::info_span!("analyze", input = %input.display(), shards = count).entered();
     let mut host = AnalysisHost::new();
+    let (stdlib_files, dependency_files) = load_into(&mut host, input, options)?;
+    host.rebuild_index();
+    (1..=count)
+        .map(|index| {
+            let options = AnalysisOptions {
+                shard: Some(Shard::new(index, count)?),
+                ..options.clone()
+            };
+            Ok(analyze_host(
+                &mut host.clone(),
+                &stdlib_files,
+                &dependency_files,
+                &options,
+            ))
+        })
+        .collect()
+}
 
-    // 1. Load stdlib if requested
-    if load_stdlib {
-        load_stdlib_files(&mut host, stdlib_path, verbose)?;
+/// Load the standard library, dependencies and `input` into `host` as
+/// `options` say, returning the paths of the stdlib (with dependencies) and
+/// of the dependencies alone.
+fn load_into(
+    host: &mut AnalysisHost,
+    input: &Path,
+    options: &AnalysisOptions,
+) -> Result<(HashSet<String>, HashSet<String>), String> {
+    let verbose = options.verbose;
+
+    // 1. Load stdlib if requested, or just the part the input needs
+    if options.load_stdlib {
+        match options.stdlib_subset {
+            StdlibSubset::Full => {
+                load_stdlib_files(host, options.stdlib_path.as_deref(), verbose)?;
+            }
+            StdlibSubset::Auto => {
+                let mut inputs = vec![input];
+                inputs.extend(options.libraries.iter().map(PathBuf::as_path));
+                stdlib::load_stdlib_subset(
+                    host,
+                    options.stdlib_path.as_deref(),
+                    &inputs,
+                    &options.walk,
+                    verbose,
+                )?;
+            }
+        }
+    }
+
+    // 1.5. Load dependencies (indexes and libraries); like the stdlib,
+    // they are never reported on
+    let mut dependency_files: HashSet<String> = options
+        .indexes
+        .iter()
+        .map(|index| index.load(host, verbose))
+        .collect();
+    for library in &options.libraries {
+        let before: HashSet<PathBuf> = host.files().keys().cloned().collect();
+        load_input(host, library, verbose)?;
+        dependency_files.extend(
+            host.files()
+                .keys()
+                .filter(|p| !before.contains(*p))
+                .map(|p| p.to_string_lossy().to_string()),
+        );
     }
+    let stdlib_files: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
 
     // 2. Load input file(s)
-    load_input(&mut host, input, verbose)?;
+    load_input_with(host, input, verbose, &options.walk)?;
+
+    Ok((stdlib_files, dependency_files))
+}
+
+/// A standard library parsed once and reused for many analyses, as by the
+/// Node.js bindings and other long-running embedders.
+///
+/// Each call works on a copy, so the library is never reported on and
+/// never parsed again.
+#[derive(Clone)]
+pub struct LoadedStdlib {
+    host: AnalysisHost,
+}
+
+impl LoadedStdlib {
+    /// Load the whole standard library from `options.stdlib_path` or the
+    /// default locations, or nothing if `options.load_stdlib` is off.
+    pub fn load(options: &AnalysisOptions) -> Result<Self, String> {
+        let mut host = AnalysisHost::new();
+        if options.load_stdlib {
+            load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+        }
+        Ok(Self { host })
+    }
+
+    /// Analyze `input` against the loaded library, as [`analyze`] does;
+    /// the stdlib options in `options` are ignored.
+    pub fn analyze(
+        &self,
+        input: &Path,
+        options: &AnalysisOptions,
+    ) -> Result<AnalysisResult, String> {
+        let options = AnalysisOptions {
+            load_stdlib: false,
+            ..options.clone()
+        };
+        analyze_into(&mut self.host.clone(), input, &options)
+    }
+
+    /// Export `input` with the loaded library to an interchange format;
+    /// only the model's own elements are exported.
+    #[cfg(feature = "interchange")]
+    pub fn export(
+        &self,
+        input: &Path,
+        format: &str,
+        options: &AnalysisOptions,
+    ) -> Result<Vec<u8>, String> {
+        let mut host = self.host.clone();
+        load_input_with(&mut host, input, options.verbose, &options.walk)?;
+        export_from_host(&mut host, format, options.verbose, false)
+    }
+}
+
+/// Analyze in-memory sources, without touching the filesystem.
+///
+/// `files` are `(path, content)` pairs; the paths only name the files in
+/// diagnostics. The standard library is not loaded and `options.walk` and
+/// `options.libraries` don't apply, as they name files; dependency indexes
+/// in `options.indexes` are used. Handy for unit tests and for services
+/// that already hold the model text.
+pub fn run_analysis_from_sources(
+    files: &[(String, String)],
+    options: &AnalysisOptions,
+) -> AnalysisResult {
+    let _span = tracing::info_span!("analyze", files = files.len()).entered();
+    let mut host = AnalysisHost::new();
+    let dependency_files: HashSet<String> = options
+        .indexes
+        .iter()
+        .map(|index| index.load(&mut host, options.verbose))
+        .collect();
+    let stdlib_files: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    for (path, content) in files {
+        add_source(&mut host, path, content);
+    }
+    analyze_host(&mut host, &stdlib_files, &dependency_files, options)
+}
+
+/// Analyze the files loaded into `host`, reporting on all but the
+/// `dependency_files` (steps 2.5 to 5 of [`analyze`]).
+fn analyze_host(
+    host: &mut AnalysisHost,
+    stdlib_files: &HashSet<String>,
+    dependency_files: &HashSet<String>,
+    options: &AnalysisOptions,
+) -> AnalysisResult {
+    let AnalysisOptions {
+        verbose,
+        shard,
+        config,
+        duplicates,
+        profile,
+        ..
+    } = *options;
+    let strict = profile == Profile::Strict;
+
+    // The files to report on, listed before strict parsing drops any so
+    // a broken file belongs to the same shard either way
+    let all_paths: Vec<String> = host
+        .files()
+        .keys()
+        .map(|p| p.to_string_lossy().to_string())
+        .filter(|p| !dependency_files.contains(p))
+        .collect();
+
+    // 2.5. Files with syntax errors contribute what the parser recovered,
+    // unless parsing is strict
+    let mut broken_files: Vec<String> = host
+        .files()
+        .iter()
+        .filter(|(_, file)| !file.errors().is_empty())
+        .map(|(path, _)| path.to_string_lossy().to_string())
+        .filter(|path| !stdlib_files.contains(path))
+        .collect();
+    if options.strict_parse {
+        for path in &broken_files {
+            host.remove_file(path);
+        }
+    }
 
     // 3. Trigger index rebuild and get analysis
-    let _analysis = host.analysis();
+    let analysis = {
+        let _span = tracing::info_span!("index").entered();
+        host.analysis()
+    };
+
+    // 3.5. Resolve variation points to the configured variants
+    let resolution = config.map(|config| resolve_variants(&analysis, config));
+    if verbose {
+        if let Some(resolution) = &resolution {
+            tracing::info!(
+                "Resolved {} variation point(s), removed {} variant(s)",
+                resolution.selected.len(),
+                resolution.excluded.len()
+            );
+        }
+    }
+
+    // 4. Collect diagnostics from all files (or only this shard's files)
+    let mut selected: HashSet<&str> = match shard {
+        None => all_paths.iter().map(|p| p.as_str()).collect(),
+        Some(shard) => {
+            let selected: HashSet<&str> = shard
+                .select(&all_paths)
+                .into_iter()
+                .map(|p| p.as_str())
+                .collect();
+            if verbose {
+                tracing::info!(
+                    "Shard {}/{}: reporting {} of {} files",
+                    shard.index,
+                    shard.count,
+                    selected.len(),
+                    all_paths.len()
+                );
+            }
+            selected
+        }
+    };
+    broken_files.retain(|p| selected.contains(p.as_str()));
+    selected.retain(|p| host.has_file(p));
+
+    let mut breakdowns = summary::Breakdowns::default();
+    for path in &selected {
+        let Some(file_id) = host.get_file_id(path) else {
+            continue;
+        };
+        for sym in host.symbol_index().symbols_in_file(file_id) {
+            if resolution
+                .as_ref()
+                .is_none_or(|r| !r.is_excluded(&sym.qualified_name))
+            {
+                breakdowns.add_symbol(path, sym);
+            }
+        }
+    }
+    let symbol_count = breakdowns.symbol_count();
+    let mut diagnostics: Vec<DiagnosticInfo> = collect_diagnostics(host)
+        .into_iter()
+        .filter(|d| selected.contains(d.file.as_str()))
+        .collect();
+
+    // 4.1. Warn about ambiguous and shadowed imports in the model's own files
+    let own_files: HashSet<&str> = selected
+        .iter()
+        .copied()
+        .filter(|p| !stdlib_files.contains(*p))
+        .collect();
+    diagnostics.extend(shadowing::import_diagnostics(host, &own_files));
+    sort_diagnostics(&mut diagnostics);
+
+    // 4.2. Report definitions duplicated across files
+    if let Some(severity) = duplicates.or(strict.then_some(Severity::Warning)) {
+        diagnostics.extend(
+            duplicates::duplicate_definitions(host, severity)
+                .into_iter()
+                .filter(|d| selected.contains(d.file.as_str())),
+        );
+        sort_diagnostics(&mut diagnostics);
+    }
 
-    // 4. Collect diagnostics from all files
-    let diagnostics = collect_diagnostics(&host);
+    // 4.3. Measure documentation coverage and report missing required docs
+    let required_docs = if strict && options.require_docs.is_empty() {
+        coverage::definition_kinds()
+    } else {
+        options.require_docs.clone()
+    };
+    let (doc_coverage, missing_docs) = coverage::doc_coverage(host, &own_files, &required_docs);
+    diagnostics.extend(missing_docs);
+    sort_diagnostics(&mut diagnostics);
+
+    // 4.4. Report keywords the selected language version does not have
+    if let Some(version) = options.sysml_version {
+        diagnostics.extend(language::version_diagnostics(host, &own_files, version));
+        sort_diagnostics(&mut diagnostics);
+    }
+
+    // 4.5. Report dependencies the layer rules do not allow
+    if let Some(rules) = &options.layers {
+        diagnostics.extend(layers::layer_diagnostics(
+            host,
+            &own_files,
+            stdlib_files,
+            rules,
+        ));
+        sort_diagnostics(&mut diagnostics);
+    }
+
+    // 4.55. Check the text of requirements
+    if let Some(rules) = &options.requirements {
+        diagnostics.extend(requirements::requirement_diagnostics(
+            host, &own_files, rules,
+        ));
+        sort_diagnostics(&mut diagnostics);
+    }
+
+    // 4.56. Report where the model outgrows its size budgets
+    if let Some(budgets) = &options.budgets {
+        diagnostics.extend(budgets::budget_diagnostics(host, &own_files, budgets));
+        sort_diagnostics(&mut diagnostics);
+    }
+
+    // 4.6. Drop diagnostics in removed variants and add configuration issues
+    if let Some(resolution) = resolution {
+        diagnostics = resolution.retain_diagnostics(diagnostics);
+        diagnostics.extend(
+            resolution
+                .issues
+                .into_iter()
+                .filter(|d| shard.is_none() || selected.contains(d.file.as_str())),
+        );
+        sort_diagnostics(&mut diagnostics);
+    }
+
+    // 4.7. Apply the profile's severities
+    profile.apply(&mut diagnostics);
+
+    // 4.8. Drop diagnostics below the threshold
+    if let Some(min) = options.min_severity {
+        diagnostics.retain(|d| severity_rank(d.severity) >= severity_rank(min));
+    }
+
+    // 4.9. Annotate owners and keep the selected teams' diagnostics
+    if let Some(owners) = &options.owners {
+        owners::annotate_owners(host, owners, &mut diagnostics);
+        diagnostics.retain(|d| owners::owned_by_any(&d.owners, &options.only_owners));
+    }
+
+    // 4.95. Set aside the diagnostics inside the standard library, which
+    // are not the model's to fix
+    let mut stdlib_diagnostics = Vec::new();
+    if !options.include_stdlib_diagnostics {
+        let origins = FileOrigins::new(host, stdlib_files, dependency_files);
+        (stdlib_diagnostics, diagnostics) = diagnostics.into_iter().partition(|d| {
+            host.get_file_id(&d.file)
+                .is_some_and(|id| origins.get(id) == FileOrigin::Stdlib)
+        });
+        if verbose && !stdlib_diagnostics.is_empty() {
+            tracing::info!(
+                "Set aside {} diagnostic(s) inside the standard library",
+                stdlib_diagnostics.len()
+            );
+        }
+    }
 
     // 5. Build result
+    let recovered_symbols = if options.strict_parse {
+        0
+    } else {
+        broken_files
+            .iter()
+            .filter_map(|p| host.get_file_id(p))
+            .map(|id| host.symbol_index().symbols_in_file(id).len())
+            .sum()
+    };
+    let (mut packages, files) = breakdowns.finish(&diagnostics);
+    if let Some(owners) = &options.owners {
+        for package in &mut packages {
+            package.owners = owners.owners_of(&package.name).to_vec();
+        }
+        packages.retain(|p| owners::owned_by_any(&p.owners, &options.only_owners));
+    }
+    let mut result = build_result(selected.len(), symbol_count, diagnostics);
+    result.stdlib_diagnostics = stdlib_diagnostics;
+    result.doc_coverage = doc_coverage;
+    result.packages = packages;
+    result.files = files;
+    result.syntax_errors = ParseRecovery {
+        files: broken_files.len(),
+        symbols: recovered_symbols,
+    };
+    result
+}
+
+/// Build an `AnalysisResult`, deriving the error/warning counts from the diagnostics.
+fn build_result(
+    file_count: usize,
+    symbol_count: usize,
+    diagnostics: Vec<DiagnosticInfo>,
+) -> AnalysisResult {
     let error_count = diagnostics
         .iter()
         .filter(|d| matches!(d.severity, Severity::Error))
@@ -102,21 +1306,81 @@ pub fn run_analysis(
         .filter(|d| matches!(d.severity, Severity::Warning))
         .count();
 
-    Ok(AnalysisResult {
-        file_count: host.file_count(),
-        symbol_count: host.symbol_index().all_symbols().count(),
+    AnalysisResult {
+        file_count,
+        symbol_count,
         error_count,
         warning_count,
         diagnostics,
-    })
+        stdlib_diagnostics: Vec::new(),
+        doc_coverage: DocCoverage::default(),
+        syntax_errors: ParseRecovery::default(),
+        packages: Vec::new(),
+        files: Vec::new(),
+        top_offenders: Vec::new(),
+    }
+}
+
+/// Merge partial results (e.g. from `--shard` runs) into a single result.
+///
+/// File and symbol counts are summed, diagnostics are concatenated and
+/// re-sorted, the error/warning counts and documentation coverage are
+/// recomputed, and the package and file breakdowns are combined.
+pub fn merge_results(results: Vec<AnalysisResult>) -> AnalysisResult {
+    let mut file_count = 0;
+    let mut symbol_count = 0;
+    let mut diagnostics = Vec::new();
+    let mut stdlib_diagnostics = Vec::new();
+    let mut doc_coverage = DocCoverage::default();
+    let mut syntax_errors = ParseRecovery::default();
+    let mut packages = Vec::new();
+    let mut files = Vec::new();
+
+    for result in results {
+        file_count += result.file_count;
+        symbol_count += result.symbol_count;
+        diagnostics.extend(result.diagnostics);
+        stdlib_diagnostics.extend(result.stdlib_diagnostics);
+        doc_coverage = doc_coverage.merge(result.doc_coverage);
+        syntax_errors = syntax_errors.merge(result.syntax_errors);
+        packages = summary::merge(packages, result.packages);
+        files = summary::merge(files, result.files);
+    }
+
+    sort_diagnostics(&mut diagnostics);
+    sort_diagnostics(&mut stdlib_diagnostics);
+    let mut result = build_result(file_count, symbol_count, diagnostics);
+    result.stdlib_diagnostics = stdlib_diagnostics;
+    result.doc_coverage = doc_coverage;
+    result.syntax_errors = syntax_errors;
+    result.packages = packages;
+    result.files = files;
+    result
+}
+
+/// Load an `AnalysisResult` previously written with `--json`.
+pub fn load_json_result(path: &Path) -> Result<AnalysisResult, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
 }
 
 /// Load input file or directory.
 fn load_input(host: &mut AnalysisHost, input: &Path, verbose: bool) -> Result<(), String> {
+    load_input_with(host, input, verbose, &WalkOptions::default())
+}
+
+/// Load input file or directory, walking directories as `walk` says.
+fn load_input_with(
+    host: &mut AnalysisHost,
+    input: &Path,
+    verbose: bool,
+    walk: &WalkOptions,
+) -> Result<(), String> {
     if input.is_file() {
         load_file(host, input, verbose)
     } else if input.is_dir() {
-        load_directory(host, input, verbose)
+        load_directory(host, input, verbose, walk)
     } else {
         Err(format!("Path does not exist: {}", input.display()))
     }
@@ -124,46 +1388,121 @@ fn load_input(host: &mut AnalysisHost, input: &Path, verbose: bool) -> Result<()
 
 /// Load a single file into the analysis host.
 fn load_file(host: &mut AnalysisHost, path: &Path, verbose: bool) -> Result<(), String> {
+    let _span = tracing::info_span!("load", file = %path.display()).entered();
     if verbose {
-        println!("  Loading: {}", path.display());
+        tracing::info!("Loading: {}", path.display());
     }
 
     let content = std::fs::read_to_string(path)
         .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
 
-    let path_str = path.to_string_lossy();
-    let parse_errors = host.set_file_content(&path_str, &content);
+    add_source(host, &path.to_string_lossy(), &content);
+    Ok(())
+}
+
+/// Parse `content` into the analysis host as the file `path`.
+fn add_source(host: &mut AnalysisHost, path: &str, content: &str) {
+    let parse_errors = tracing::info_span!("parse", file = %path)
+        .in_scope(|| host.set_file_content(path, content));
 
     // Parse errors are reported but don't fail the load
     for err in parse_errors {
-        eprintln!(
+        tracing::warn!(
             "parse error: {}:{}:{}: {}",
-            path.display(),
+            path,
             err.position.line,
             err.position.column,
             err.message
         );
     }
+}
+
+/// Load all SysML/KerML files from a directory.
+///
+/// A file reachable through several paths (symbolic links, or a link back
+/// into an ancestor directory) is loaded once, under the first path found.
+/// Files and directories matched by a `.systerignore` are skipped.
+fn load_directory(
+    host: &mut AnalysisHost,
+    dir: &Path,
+    verbose: bool,
+    walk: &WalkOptions,
+) -> Result<(), String> {
+    for path in &model_files(dir, verbose, walk)? {
+        load_file(host, path, verbose)?;
+    }
 
     Ok(())
 }
 
-/// Load all SysML/KerML files from a directory.
-fn load_directory(host: &mut AnalysisHost, dir: &Path, verbose: bool) -> Result<(), String> {
+/// The SysML/KerML files [`load_directory`] loads from `dir`, in walk order.
+fn model_files(dir: &Path, verbose: bool, walk: &WalkOptions) -> Result<Vec<PathBuf>, String> {
     if verbose {
-        println!("Scanning directory: {}", dir.display());
+        tracing::info!("Scanning directory: {}", dir.display());
     }
 
-    for entry in WalkDir::new(dir).follow_links(true) {
-        let entry = entry.map_err(|e| format!("Walk error: {}", e))?;
+    // Fetched dependencies are libraries, not part of the model
+    let mut walker = WalkDir::new(dir).follow_links(walk.follow_symlinks);
+    if let Some(depth) = walk.max_depth {
+        walker = walker.max_depth(depth);
+    }
+    let mut ignored = IgnoreRules::new(dir, walk.respect_gitignore);
+    let mut seen = HashSet::new();
+    let mut paths = Vec::new();
+    for entry in walker.into_iter().filter_entry(|e| {
+        e.depth() == 0
+            || (e.file_name() != ".syster"
+                && !ignored.is_ignored(e.path(), e.file_type().is_dir())
+                && !(walk.skip_nested_projects
+                    && e.file_type().is_dir()
+                    && e.path().join(project::PROJECT_FILE).is_file()))
+    }) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) if e.loop_ancestor().is_some() => {
+                tracing::warn!("skipping symlink loop: {}", e);
+                continue;
+            }
+            Err(e) => return Err(format!("Walk error: {}", e)),
+        };
         let path = entry.path();
+        if entry.path_is_symlink() && !walk.follow_symlinks {
+            continue;
+        }
 
         if is_sysml_file(path) {
-            load_file(host, path, verbose)?;
+            let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+            if !seen.insert(canonical) {
+                if verbose {
+                    tracing::info!("Skipping (already loaded): {}", path.display());
+                }
+                continue;
+            }
+            if let Some(max) = walk.max_file_size {
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                if size > max {
+                    tracing::warn!(
+                        "skipping {}: {} bytes exceeds the maximum file size of {} bytes",
+                        path.display(),
+                        size,
+                        max
+                    );
+                    continue;
+                }
+            }
+            paths.push(path.to_path_buf());
         }
     }
 
-    Ok(())
+    if let Some(max) = walk.max_files.filter(|max| paths.len() > *max) {
+        return Err(format!(
+            "{} contains {} SysML/KerML files, more than the maximum of {}",
+            dir.display(),
+            paths.len(),
+            max
+        ));
+    }
+    Ok(paths)
 }
 
 /// Check if a path is a SysML or KerML file.
@@ -182,13 +1521,29 @@ fn load_stdlib_files(
     verbose: bool,
 ) -> Result<(), String> {
     if verbose {
-        println!("Loading standard library...");
+        tracing::info!("Loading standard library...");
     }
 
+    match find_stdlib_dir(custom_path)? {
+        Some(path) => load_directory(host, &path, verbose, &WalkOptions::default()),
+        None => {
+            if verbose {
+                tracing::warn!("Standard library not found");
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Locate the standard library directory.
+///
+/// A custom path must exist; otherwise the default locations are tried and
+/// `None` is returned if none of them exists.
+fn find_stdlib_dir(custom_path: Option<&Path>) -> Result<Option<PathBuf>, String> {
     // Try custom path first
     if let Some(path) = custom_path {
         if path.exists() {
-            return load_directory(host, path, verbose);
+            return Ok(Some(path.to_path_buf()));
         } else {
             return Err(format!("Stdlib path does not exist: {}", path.display()));
         }
@@ -201,17 +1556,7 @@ fn load_stdlib_files(
         PathBuf::from("../base/sysml.library"),
     ];
 
-    for path in &default_paths {
-        if path.exists() {
-            return load_directory(host, path, verbose);
-        }
-    }
-
-    if verbose {
-        println!("  Warning: Standard library not found");
-    }
-
-    Ok(())
+    Ok(default_paths.into_iter().find(|path| path.exists()))
 }
 
 /// Collect diagnostics from all files in the host.
@@ -221,6 +1566,7 @@ fn collect_diagnostics(host: &AnalysisHost) -> Vec<DiagnosticInfo> {
     for path in host.files().keys() {
         if let Some(file_id) = host.get_file_id_for_path(path) {
             let file_path = path.to_string_lossy().to_string();
+            let _span = tracing::info_span!("check", file = %file_path).entered();
             let diagnostics = check_file(host.symbol_index(), file_id);
 
             for diag in diagnostics {
@@ -233,17 +1579,34 @@ fn collect_diagnostics(host: &AnalysisHost) -> Vec<DiagnosticInfo> {
                     message: diag.message.to_string(),
                     severity: diag.severity,
                     code: diag.code.map(|c| c.to_string()),
+                    blame: None,
+                    owners: Vec::new(),
+                    related: Vec::new(),
                 });
             }
         }
     }
 
-    // Sort by file, then line, then column
-    all_diagnostics.sort_by(|a, b| (&a.file, a.line, a.col).cmp(&(&b.file, b.line, b.col)));
+    sort_diagnostics(&mut all_diagnostics);
 
     all_diagnostics
 }
 
+/// Sort by file, then line, then column
+fn sort_diagnostics(diagnostics: &mut [DiagnosticInfo]) {
+    diagnostics.sort_by(|a, b| (&a.file, a.line, a.col).cmp(&(&b.file, b.line, b.col)));
+}
+
+/// Order of severities, hints lowest.
+fn severity_rank(severity: Severity) -> u8 {
+    match severity {
+        Severity::Hint => 0,
+        Severity::Info => 1,
+        Severity::Warning => 2,
+        Severity::Error => 3,
+    }
+}
+
 // ============================================================================
 // EXPORT FUNCTIONS
 // ============================================================================
@@ -263,6 +1626,9 @@ pub struct ExportSymbol {
     pub doc: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub supertypes: Vec<String>,
+    /// Metadata applied to the symbol, as written (e.g. `Safety`).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub metadata: Vec<String>,
 }
 
 /// AST export result.
@@ -285,30 +1651,96 @@ pub fn export_ast(
     load_stdlib: bool,
     stdlib_path: Option<&Path>,
 ) -> Result<String, String> {
+    let options = AnalysisOptions {
+        verbose,
+        load_stdlib,
+        stdlib_path: stdlib_path.map(Path::to_path_buf),
+        ..Default::default()
+    };
+    export_ast_with_options(input, &options, &[])
+}
+
+/// Export AST (symbols), keeping only elements annotated with one of
+/// `with_metadata` (see [`select_annotated`]); everything if empty.
+#[deprecated(note = "use `export_ast_with_options`")]
+pub fn export_ast_with_metadata(
+    input: &Path,
+    verbose: bool,
+    load_stdlib: bool,
+    stdlib_path: Option<&Path>,
+    with_metadata: &[String],
+) -> Result<String, String> {
+    let options = AnalysisOptions {
+        verbose,
+        load_stdlib,
+        stdlib_path: stdlib_path.map(Path::to_path_buf),
+        ..Default::default()
+    };
+    export_ast_with_options(input, &options, with_metadata)
+}
+
+/// Export AST (symbols), loading the standard library and walking and
+/// parsing the input as `options` say, and keeping only elements annotated
+/// with one of `with_metadata` (see [`select_annotated`]); everything if
+/// empty.
+pub fn export_ast_with_options(
+    input: &Path,
+    options: &AnalysisOptions,
+    with_metadata: &[String],
+) -> Result<String, String> {
+    let export = collect_ast(input, options, with_metadata)?;
+    serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize AST: {}", e))
+}
+
+/// Collect the symbols of every user file, as exported by
+/// [`export_ast_with_options`].
+pub(crate) fn collect_ast(
+    input: &Path,
+    options: &AnalysisOptions,
+    with_metadata: &[String],
+) -> Result<AstExport, String> {
+    let verbose = options.verbose;
     let mut host = AnalysisHost::new();
 
-    if load_stdlib {
-        load_stdlib_files(&mut host, stdlib_path, verbose)?;
+    if options.load_stdlib {
+        load_stdlib_files(&mut host, options.stdlib_path.as_deref(), verbose)?;
+    }
+    let stdlib_files: HashSet<PathBuf> = host.files().keys().cloned().collect();
+
+    load_input_with(&mut host, input, verbose, &options.walk)?;
+    if options.strict_parse {
+        let broken: Vec<String> = host
+            .files()
+            .iter()
+            .filter(|(path, file)| !file.errors().is_empty() && !stdlib_files.contains(*path))
+            .map(|(path, _)| path.to_string_lossy().to_string())
+            .collect();
+        for path in &broken {
+            host.remove_file(path);
+        }
     }
-
-    load_input(&mut host, input, verbose)?;
     let _analysis = host.analysis();
 
     let mut files = Vec::new();
 
     // Only export user files, not stdlib
     for path in host.files().keys() {
-        let path_str = path.to_string_lossy().to_string();
-
-        // Skip stdlib files
-        if path_str.contains("sysml.library") {
+        if stdlib_files.contains(path) {
             continue;
         }
+        let path_str = path.to_string_lossy().to_string();
 
         if let Some(file_id) = host.get_file_id_for_path(path) {
-            let symbols: Vec<ExportSymbol> = host
+            let mut symbols: Vec<_> = host
                 .symbol_index()
                 .symbols_in_file(file_id)
+                .into_iter()
+                .cloned()
+                .collect();
+            if !with_metadata.is_empty() {
+                symbols = select_annotated(symbols, with_metadata);
+            }
+            let symbols: Vec<ExportSymbol> = symbols
                 .into_iter()
                 .map(|sym| ExportSymbol {
                     name: sym.name.to_string(),
@@ -321,6 +1753,11 @@ pub fn export_ast(
                     end_col: sym.end_col + 1,
                     doc: sym.doc.as_ref().map(|d| d.to_string()),
                     supertypes: sym.supertypes.iter().map(|s| s.to_string()).collect(),
+                    metadata: sym
+                        .metadata_annotations
+                        .iter()
+                        .map(|m| m.to_string())
+                        .collect(),
                 })
                 .collect();
 
@@ -334,12 +1771,12 @@ pub fn export_ast(
     // Sort files by path for consistent output
     files.sort_by(|a, b| a.path.cmp(&b.path));
 
-    let export = AstExport { files };
-    serde_json::to_string_pretty(&export).map_err(|e| format!("Failed to serialize AST: {}", e))
+    Ok(AstExport { files })
 }
 
 /// Export analysis result as JSON.
 pub fn export_json(result: &AnalysisResult) -> Result<String, String> {
+    let _span = tracing::info_span!("export", format = "json").entered();
     serde_json::to_string_pretty(result).map_err(|e| format!("Failed to serialize result: {}", e))
 }
 
@@ -354,6 +1791,8 @@ pub fn export_json(result: &AnalysisResult) -> Result<String, String> {
 /// - `kpar` - Kernel Package Archive (ZIP)
 /// - `jsonld` - JSON-LD
 ///
+/// XMI, KPAR and JSON-LD output carries a [`Provenance`] record.
+///
 /// # Arguments
 /// * `input` - Path to a file or directory to analyze
 /// * `format` - Output format (xmi, kpar, jsonld)
@@ -372,53 +1811,122 @@ pub fn export_model(
     stdlib_path: Option<&Path>,
     self_contained: bool,
 ) -> Result<Vec<u8>, String> {
-    use syster::interchange::{
-        JsonLd, Kpar, ModelFormat, Xmi, Yaml, model_from_symbols, restore_ids_from_symbols,
+    let options = AnalysisOptions {
+        verbose,
+        load_stdlib,
+        stdlib_path: stdlib_path.map(Path::to_path_buf),
+        ..Default::default()
     };
+    export_model_with_options(
+        input,
+        format,
+        &options,
+        self_contained,
+        &ExportFilter::default(),
+    )
+    .map(|(bytes, _)| bytes)
+}
 
-    let mut host = AnalysisHost::new();
+/// Which parts of the loaded model an export includes, and how they appear.
+#[cfg(feature = "interchange")]
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    /// Only export these packages and their transitive dependencies (see
+    /// [`package_closure`]); everything if empty.
+    pub packages: Vec<String>,
+    /// Remove `private` members, failing if a remaining element references
+    /// one (see [`visibility::strip_private`]).
+    pub strip_private: bool,
+    /// Only export elements annotated with one of these metadata definitions,
+    /// with their contents and enclosing namespaces (see
+    /// [`select_annotated`]); everything if empty.
+    pub with_metadata: Vec<String>,
+    /// Replace names and documentation with pseudonyms, keeping the mapping
+    /// in this file (see [`redact::redact_model`]).
+    pub redaction_map: Option<PathBuf>,
+    /// Only export this configuration of the product line (see
+    /// [`resolve_variants`]); fails if the selection is incomplete or
+    /// inconsistent.
+    pub config: Option<VariantConfig>,
+    /// How element IDs are assigned.
+    pub id_policy: IdPolicy,
+    /// UUID namespace for [`IdPolicy::UuidV5Namespace`] (default:
+    /// [`DEFAULT_ID_NAMESPACE`]).
+    pub id_namespace: Option<String>,
+    /// Only export the element with this qualified name and its contents,
+    /// with stubs of what they reference outside it (see [`subtree`]).
+    pub root: Option<String>,
+}
 
-    // 1. Load stdlib if requested
-    if load_stdlib {
-        load_stdlib_files(&mut host, stdlib_path, verbose)?;
-    }
+/// Export a model, also reporting how many element IDs were restored.
+#[cfg(feature = "interchange")]
+#[deprecated(note = "use `export_model_with_options`")]
+pub fn export_model_with_stats(
+    input: &Path,
+    format: &str,
+    verbose: bool,
+    load_stdlib: bool,
+    stdlib_path: Option<&Path>,
+    self_contained: bool,
+    filter: &ExportFilter,
+) -> Result<(Vec<u8>, IdRestoreStats), String> {
+    let options = AnalysisOptions {
+        verbose,
+        load_stdlib,
+        stdlib_path: stdlib_path.map(Path::to_path_buf),
+        ..Default::default()
+    };
+    export_model_with_options(input, format, &options, self_contained, filter)
+}
 
-    // 2. Load input file(s)
-    load_input(&mut host, input, verbose)?;
+/// Export a model, also reporting how many element IDs were restored.
+///
+/// See [`export_model`]. The standard library is loaded and input
+/// directories are walked as `options` say; only the elements selected by
+/// `filter` are exported, with IDs assigned by its [`IdPolicy`]. The
+/// returned [`IdRestoreStats`] lists, per source file, how many symbols have
+/// an ID in a companion metadata file and how many don't.
+#[cfg(feature = "interchange")]
+pub fn export_model_with_options(
+    input: &Path,
+    format: &str,
+    options: &AnalysisOptions,
+    self_contained: bool,
+    filter: &ExportFilter,
+) -> Result<(Vec<u8>, IdRestoreStats), String> {
+    let mut bytes = Vec::new();
+    let stats = export_model_to(input, format, options, self_contained, filter, &mut bytes)?;
+    Ok((bytes, stats))
+}
 
-    // 2.5. Load metadata if present (for ID preservation on round-trip)
-    #[cfg(feature = "interchange")]
-    {
-        use syster::project::WorkspaceLoader;
-        let loader = WorkspaceLoader::new();
-
-        // If input is a file, check for companion metadata
-        if input.is_file() {
-            let parent_dir = input.parent().unwrap_or(input);
-            if let Err(e) = loader.load_metadata_from_directory(parent_dir, &mut host) {
-                if verbose {
-                    eprintln!("Note: Could not load metadata: {}", e);
-                }
-            } else if verbose {
-                println!("Loaded metadata from {}", parent_dir.display());
-            }
-        } else if input.is_dir() {
-            // For directories, load metadata from that directory
-            if let Err(e) = loader.load_metadata_from_directory(input, &mut host) {
-                if verbose {
-                    eprintln!("Note: Could not load metadata: {}", e);
-                }
-            } else if verbose {
-                println!("Loaded metadata from {}", input.display());
-            }
-        }
-    }
+/// Export a model into `out`, also reporting how many element IDs were
+/// restored.
+///
+/// See [`export_model_with_options`]. XMI is written to `out` as it is
+/// serialized (see [`xmi_stream`]), so the exported file never has to fit
+/// in memory; other formats are serialized in memory first.
+#[cfg(feature = "interchange")]
+pub fn export_model_to(
+    input: &Path,
+    format: &str,
+    options: &AnalysisOptions,
+    self_contained: bool,
+    filter: &ExportFilter,
+    out: impl std::io::Write,
+) -> Result<IdRestoreStats, String> {
+    use syster::interchange::{model_from_symbols, restore_ids_from_symbols};
+
+    let verbose = options.verbose;
+    let mut host = AnalysisHost::new();
+
+    // 1.-2.5. Load stdlib, input file(s) and companion metadata
+    let stats = load_with_metadata(&mut host, input, options)?;
 
     // 3. Trigger index rebuild
     let analysis = host.analysis();
 
     // 4. Get symbols from the index (filtered unless self_contained)
-    let symbols: Vec<_> = if self_contained {
+    let mut symbols: Vec<_> = if self_contained {
         // Include all symbols (user model + stdlib)
         analysis.symbol_index().all_symbols().cloned().collect()
     } else {
@@ -438,42 +1946,304 @@ pub fn export_model(
             .collect()
     };
 
-    if verbose {
-        println!(
-            "Collecting {} symbols (self_contained={})",
-            symbols.len(),
-            self_contained
-        );
+    // 4.5. Keep only the configured variants, failing on an invalid selection
+    if let Some(config) = &filter.config {
+        let resolution = resolve_variants(&analysis, config);
+        resolution.check()?;
+        symbols = resolution.select_symbols(symbols);
+    }
+
+    // 4.6. Narrow to the selected packages and what they depend on
+    if !filter.packages.is_empty() {
+        let required = package_closure(analysis.symbol_index(), &filter.packages)?;
+        symbols.retain(|sym| required.contains(sym.qualified_name.as_ref()));
+    }
+
+    // 4.65. Narrow to the subtree under the root, with stubs of the
+    // elements it references
+    let mut external = HashSet::new();
+    if let Some(root) = &filter.root {
+        (symbols, external) = subtree::select_subtree(analysis.symbol_index(), symbols, root)?;
+    }
+
+    // 4.7. Narrow to elements tagged with the requested metadata
+    if !filter.with_metadata.is_empty() {
+        symbols = select_annotated(symbols, &filter.with_metadata);
+    }
+
+    // 4.8. Drop private members
+    if filter.strip_private {
+        symbols = visibility::strip_private(&mut host, symbols)?;
+    }
+    let analysis = host.analysis();
+
+    if verbose {
+        tracing::info!(
+            "Collecting {} symbols (self_contained={})",
+            symbols.len(),
+            self_contained
+        );
+    }
+
+    // 4.9. Assign IDs by policy instead of restoring them
+    ids::apply_id_policy(
+        &mut symbols,
+        filter.id_policy,
+        filter.id_namespace.as_deref(),
+    )?;
+
+    // 5. Convert to interchange model
+    let mut model = model_from_symbols(&symbols);
+
+    // 6. Restore original element IDs from symbols (if they exist)
+    if filter.id_policy == IdPolicy::Preserve {
+        model = restore_ids_from_symbols(model, analysis.symbol_index());
+        if verbose {
+            tracing::info!("Restored element IDs from symbol database");
+        }
+    }
+
+    // 6.5. Mark the stubs
+    subtree::mark_external(&mut model, &external);
+
+    // 7. Redact names and documentation, leaving the standard library as is
+    if let Some(map_path) = &filter.redaction_map {
+        let library: HashSet<String> = symbols
+            .iter()
+            .filter(|sym| {
+                analysis
+                    .get_file_path(sym.file)
+                    .is_some_and(|path| path.contains("sysml.library"))
+            })
+            .map(|sym| sym.qualified_name.to_string())
+            .collect();
+        let mut map = redact::RedactionMap::load(map_path)?;
+        redact::redact_model(&mut model, &mut map, &library);
+        map.save(map_path)?;
+        if verbose {
+            tracing::info!("Redacted model, mapping kept in {}", map_path.display());
+        }
+    }
+
+    if verbose {
+        tracing::info!(
+            "Exported model: {} elements, {} relationships",
+            model.elements.len(),
+            model.relationships.len()
+        );
+    }
+
+    // 8. Record where the artifact came from
+    let provenance = provenance::Provenance::collect(
+        input,
+        options.load_stdlib,
+        options.stdlib_path.as_deref(),
+    )?;
+
+    // 9. Serialize to requested format
+    write_model(&model, format, Some(&provenance), out)?;
+
+    Ok(stats)
+}
+
+/// Serialize `model` in `format` into `out`, embedding `provenance` if given.
+///
+/// XMI is streamed; the other formats are serialized in memory, then
+/// written.
+#[cfg(feature = "interchange")]
+fn write_model(
+    model: &syster::interchange::Model,
+    format: &str,
+    provenance: Option<&provenance::Provenance>,
+    mut out: impl std::io::Write,
+) -> Result<(), String> {
+    use syster::interchange::{JsonLd, Kpar, ModelFormat, Yaml};
+
+    let bytes = match format.to_lowercase().as_str() {
+        "xmi" => return xmi_stream::write_xmi(model, provenance, out),
+        "kpar" => Kpar.write(model).map_err(|e| e.to_string()),
+        "jsonld" | "json-ld" => JsonLd.write(model).map_err(|e| e.to_string()),
+        "json" => sysml_json::write(model),
+        "yaml" | "yml" => Yaml.write(model).map_err(|e| e.to_string()),
+        _ => Err(format!(
+            "Unsupported format: {}. Use xmi, kpar, jsonld, json, or yaml.",
+            format
+        )),
+    }?;
+    let bytes = match provenance {
+        Some(provenance) => provenance::embed_provenance(bytes, format, provenance)?,
+        None => bytes,
+    };
+    out.write_all(&bytes)
+        .map_err(|e| format!("Failed to write export: {}", e))
+}
+
+/// Element ID restoration statistics for an export.
+#[cfg(feature = "interchange")]
+#[derive(Debug, Default, Serialize)]
+pub struct IdRestoreStats {
+    /// Metadata files that were loaded.
+    pub metadata_files: Vec<String>,
+    /// Per-source-file counts, sorted by path.
+    pub files: Vec<FileIdStats>,
+    /// Qualified names with an ID in a metadata file.
+    #[serde(skip)]
+    pub(crate) restored_names: HashSet<String>,
+}
+
+#[cfg(feature = "interchange")]
+impl IdRestoreStats {
+    /// Total number of restored IDs.
+    pub fn restored(&self) -> usize {
+        self.files.iter().map(|f| f.restored).sum()
+    }
+
+    /// Total number of newly generated IDs.
+    pub fn generated(&self) -> usize {
+        self.files.iter().map(|f| f.generated).sum()
+    }
+}
+
+/// Element ID restoration counts for a single source file.
+#[cfg(feature = "interchange")]
+#[derive(Debug, Serialize)]
+pub struct FileIdStats {
+    /// Source file path.
+    pub file: String,
+    /// Symbols whose ID came from a metadata file.
+    pub restored: usize,
+    /// Symbols that were given a new ID.
+    pub generated: usize,
+}
+
+/// Load the standard library (if requested), `input` and its companion
+/// metadata into `host`, so symbols carry the element IDs an export gets.
+#[cfg(feature = "interchange")]
+pub(crate) fn load_with_metadata(
+    host: &mut AnalysisHost,
+    input: &Path,
+    options: &AnalysisOptions,
+) -> Result<IdRestoreStats, String> {
+    let verbose = options.verbose;
+
+    // 1. Load stdlib if requested
+    if options.load_stdlib {
+        load_stdlib_files(host, options.stdlib_path.as_deref(), verbose)?;
+    }
+
+    // 2. Load input file(s)
+    load_input_with(host, input, verbose, &options.walk)?;
+
+    // 2.5. Load metadata if present (for ID preservation on round-trip)
+    Ok(load_companion_metadata(host, input, verbose))
+}
+
+/// Load every companion metadata file under the input's project directory.
+///
+/// For a directory input the whole tree is searched; for a file input, the
+/// tree rooted at its parent directory. Metadata files are `*.metadata.json`,
+/// `*.metadata` and `meta.json`, applied in path order.
+#[cfg(feature = "interchange")]
+fn load_companion_metadata(host: &mut AnalysisHost, input: &Path, verbose: bool) -> IdRestoreStats {
+    use syster::hir::SymbolKind;
+    use syster::interchange::{ImportMetadata, apply_metadata_to_host};
+
+    let root = if input.is_dir() {
+        input
+    } else {
+        input.parent().unwrap_or(input)
+    };
+
+    let mut metadata_paths: Vec<PathBuf> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_file() && is_metadata_file(e.path()))
+        .map(|e| e.into_path())
+        .collect();
+    metadata_paths.sort();
+
+    let mut stats = IdRestoreStats::default();
+    let mut known_ids = HashSet::new();
+    for path in &metadata_paths {
+        match ImportMetadata::read_from_file(path) {
+            Ok(metadata) => {
+                apply_metadata_to_host(host, &metadata);
+                known_ids.extend(
+                    metadata
+                        .elements
+                        .into_iter()
+                        .filter(|(_, meta)| meta.original_id.is_some())
+                        .map(|(qualified_name, _)| qualified_name),
+                );
+                stats
+                    .metadata_files
+                    .push(path.to_string_lossy().to_string());
+                if verbose {
+                    tracing::info!("Loaded metadata from {}", path.display());
+                }
+            }
+            Err(e) => {
+                if verbose {
+                    tracing::warn!("Could not load metadata {}: {}", path.display(), e);
+                }
+            }
+        }
     }
 
-    // 5. Convert to interchange model
-    let mut model = model_from_symbols(&symbols);
+    // Make sure the index is current even when no metadata was applied
+    let _ = host.analysis();
 
-    // 6. Restore original element IDs from symbols (if they exist)
-    model = restore_ids_from_symbols(model, analysis.symbol_index());
-    if verbose {
-        println!("Restored element IDs from symbol database");
+    for path in host.files().keys() {
+        let file = path.to_string_lossy().to_string();
+        if file.contains("sysml.library") {
+            continue;
+        }
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        let (restored, generated) = host
+            .symbol_index()
+            .symbols_in_file(file_id)
+            .into_iter()
+            .filter(|sym| sym.kind != SymbolKind::Import)
+            .fold((0, 0), |(restored, generated), sym| {
+                if known_ids.contains(sym.qualified_name.as_ref()) {
+                    (restored + 1, generated)
+                } else {
+                    (restored, generated + 1)
+                }
+            });
+        stats.files.push(FileIdStats {
+            file,
+            restored,
+            generated,
+        });
     }
+    stats.files.sort_by(|a, b| a.file.cmp(&b.file));
+    stats.restored_names = known_ids;
 
     if verbose {
-        println!(
-            "Exported model: {} elements, {} relationships",
-            model.elements.len(),
-            model.relationships.len()
-        );
+        for file in &stats.files {
+            tracing::info!(
+                "{}: {} IDs restored, {} generated",
+                file.file,
+                file.restored,
+                file.generated
+            );
+        }
     }
 
-    // 8. Serialize to requested format
-    match format.to_lowercase().as_str() {
-        "xmi" => Xmi.write(&model).map_err(|e| e.to_string()),
-        "kpar" => Kpar.write(&model).map_err(|e| e.to_string()),
-        "jsonld" | "json-ld" => JsonLd.write(&model).map_err(|e| e.to_string()),
-        "yaml" | "yml" => Yaml.write(&model).map_err(|e| e.to_string()),
-        _ => Err(format!(
-            "Unsupported format: {}. Use xmi, kpar, jsonld, or yaml.",
-            format
-        )),
-    }
+    stats
+}
+
+/// Check if a path is a companion metadata file.
+#[cfg(feature = "interchange")]
+fn is_metadata_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| {
+            name.ends_with(".metadata.json") || name.ends_with(".metadata") || name == "meta.json"
+        })
 }
 
 /// Export model from an existing AnalysisHost to an interchange format.
@@ -490,9 +2260,23 @@ pub fn export_from_host(
     verbose: bool,
     self_contained: bool,
 ) -> Result<Vec<u8>, String> {
-    use syster::interchange::{
-        JsonLd, Kpar, ModelFormat, Xmi, Yaml, model_from_symbols, restore_ids_from_symbols,
-    };
+    let mut bytes = Vec::new();
+    export_from_host_to(host, format, verbose, self_contained, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Export model from an existing AnalysisHost into `out`.
+///
+/// See [`export_from_host`]; XMI is streamed as in [`export_model_to`].
+#[cfg(feature = "interchange")]
+pub fn export_from_host_to(
+    host: &mut AnalysisHost,
+    format: &str,
+    verbose: bool,
+    self_contained: bool,
+    out: impl std::io::Write,
+) -> Result<(), String> {
+    use syster::interchange::{model_from_symbols, restore_ids_from_symbols};
 
     let analysis = host.analysis();
     let symbols: Vec<_> = if self_contained {
@@ -513,7 +2297,7 @@ pub fn export_from_host(
     };
 
     if verbose {
-        println!(
+        tracing::info!(
             "Collecting {} symbols (self_contained={})",
             symbols.len(),
             self_contained
@@ -524,23 +2308,14 @@ pub fn export_from_host(
     model = restore_ids_from_symbols(model, analysis.symbol_index());
 
     if verbose {
-        println!(
+        tracing::info!(
             "Exported model: {} elements, {} relationships",
             model.elements.len(),
             model.relationships.len()
         );
     }
 
-    match format.to_lowercase().as_str() {
-        "xmi" => Xmi.write(&model).map_err(|e| e.to_string()),
-        "kpar" => Kpar.write(&model).map_err(|e| e.to_string()),
-        "jsonld" | "json-ld" => JsonLd.write(&model).map_err(|e| e.to_string()),
-        "yaml" | "yml" => Yaml.write(&model).map_err(|e| e.to_string()),
-        _ => Err(format!(
-            "Unsupported format: {}. Use xmi, kpar, jsonld, or yaml.",
-            format
-        )),
-    }
+    write_model(&model, format, None, out)
 }
 
 /// Result of importing a model from an interchange format.
@@ -553,8 +2328,34 @@ pub struct ImportResult {
     pub relationship_count: usize,
     /// Number of validation errors.
     pub error_count: usize,
-    /// Validation messages.
+    /// Validation messages, followed by one per skipped element.
     pub messages: Vec<String>,
+    /// Content left out with [`ImportOptions::continue_on_error`].
+    pub skipped: Vec<SkippedContent>,
+    /// Element ID conflicts resolved as [`ImportOptions::on_id_conflict`]
+    /// says; only when importing into a workspace.
+    pub id_resolutions: Vec<IdResolution>,
+    /// Referenced elements the file does not contain, classified (see
+    /// [`references`]); unresolved ones count as errors.
+    pub references: Vec<ExternalReference>,
+}
+
+/// How an interchange file is imported.
+#[cfg(feature = "interchange")]
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// Report progress as `tracing` events (see [`logging`]).
+    pub verbose: bool,
+    /// Skip XMI elements (and KPAR entries) that cannot be read instead of
+    /// failing, listing them in [`ImportResult::skipped`] (see [`recovery`]).
+    pub continue_on_error: bool,
+    /// What to do with imported elements whose IDs workspace elements
+    /// already have (see [`conflicts`]); only when importing into a
+    /// workspace.
+    pub on_id_conflict: IdConflictPolicy,
+    /// Directories, interchange files and symbol indexes to look up
+    /// referenced elements the file does not contain in.
+    pub resolve_refs: Vec<PathBuf>,
 }
 
 /// Import a model from an interchange format file (validation only).
@@ -565,7 +2366,9 @@ pub struct ImportResult {
 /// Supported formats are detected from file extension:
 /// - `.xmi` - XML Model Interchange
 /// - `.kpar` - Kernel Package Archive (ZIP)
-/// - `.jsonld`, `.json` - JSON-LD
+/// - `.jsonld` - JSON-LD
+/// - `.json` - SysML v2 standard JSON (see [`sysml_json`]), or JSON-LD
+/// - `.yaml`, `.yml` - YAML, as written by `--export yaml`
 ///
 /// # Arguments
 /// * `input` - Path to the interchange file
@@ -581,54 +2384,39 @@ pub fn import_model_into_host(
     format: Option<&str>,
     verbose: bool,
 ) -> Result<ImportResult, String> {
-    use syster::interchange::{JsonLd, Kpar, ModelFormat, Xmi, detect_format};
-
-    // Read the input file
-    let bytes =
-        std::fs::read(input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
-
-    // Determine format
-    let format_str = format.map(String::from).unwrap_or_else(|| {
-        input
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("xmi")
-            .to_string()
-    });
+    let options = ImportOptions {
+        verbose,
+        ..ImportOptions::default()
+    };
+    import_model_into_host_with_options(host, input, format, &options)
+}
 
-    if verbose {
-        println!(
-            "Importing {} as {} into workspace",
-            input.display(),
-            format_str
-        );
+/// Import a model into a workspace as [`import_model_into_host`] does, as
+/// `options` say.
+#[cfg(feature = "interchange")]
+pub fn import_model_into_host_with_options(
+    host: &mut AnalysisHost,
+    input: &Path,
+    format: Option<&str>,
+    options: &ImportOptions,
+) -> Result<ImportResult, String> {
+    let verbose = options.verbose;
+    let (mut model, skipped) = read_import_model(input, format, options)?;
+    let id_resolutions = conflicts::resolve_id_conflicts(host, &mut model, options.on_id_conflict)?;
+    if verbose && !id_resolutions.is_empty() {
+        tracing::info!("Resolved {} element ID conflicts", id_resolutions.len());
     }
 
-    // Parse the model
-    let model = match format_str.to_lowercase().as_str() {
-        "xmi" | "sysmlx" | "kermlx" => Xmi.read(&bytes).map_err(|e| e.to_string())?,
-        "kpar" => Kpar.read(&bytes).map_err(|e| e.to_string())?,
-        "jsonld" | "json-ld" | "json" => JsonLd.read(&bytes).map_err(|e| e.to_string())?,
-        _ => {
-            // Try to detect from file extension
-            if let Some(format_impl) = detect_format(input) {
-                format_impl.read(&bytes).map_err(|e| e.to_string())?
-            } else {
-                return Err(format!(
-                    "Unknown format: {}. Use xmi, sysmlx, kermlx, kpar, or jsonld.",
-                    format_str
-                ));
-            }
-        }
-    };
+    let references = references::classify_references(&model, &options.resolve_refs, verbose)?;
 
     let element_count = model.elements.len();
     let relationship_count = model.relationships.len();
 
     if verbose {
-        println!(
+        tracing::info!(
             "Parsed {} elements and {} relationships",
-            element_count, relationship_count
+            element_count,
+            relationship_count
         );
     }
 
@@ -639,17 +2427,29 @@ pub fn import_model_into_host(
 
     if verbose {
         if errors.is_empty() {
-            println!("Loaded model into workspace with preserved element IDs");
+            tracing::info!("Loaded model into workspace with preserved element IDs");
         } else {
-            println!("Loaded model with {} parse warnings", errors.len());
+            tracing::info!("Loaded model with {} parse warnings", errors.len());
         }
     }
 
+    let unresolved: Vec<String> = references
+        .iter()
+        .filter(|r| r.status == ReferenceStatus::Unresolved)
+        .map(ToString::to_string)
+        .collect();
+    let mut messages = vec![format!("Successfully imported {} elements", element_count)];
+    messages.extend(unresolved.iter().cloned());
+    messages.extend(skipped.iter().map(ToString::to_string));
+    messages.extend(id_resolutions.iter().map(ToString::to_string));
     Ok(ImportResult {
         element_count,
         relationship_count,
-        error_count: errors.len(),
-        messages: vec![format!("Successfully imported {} elements", element_count)],
+        error_count: errors.len() + unresolved.len(),
+        messages,
+        skipped,
+        id_resolutions,
+        references,
     })
 }
 
@@ -660,7 +2460,7 @@ pub fn import_model_into_host(
 ///
 /// # Arguments
 /// * `input` - Path to the model file
-/// * `format` - Optional format override (xmi, kpar, jsonld)
+/// * `format` - Optional format override (xmi, kpar, jsonld, json, yaml)
 /// * `verbose` - Enable verbose output
 ///
 /// # Returns
@@ -671,15 +2471,83 @@ pub fn import_model(
     format: Option<&str>,
     verbose: bool,
 ) -> Result<ImportResult, String> {
-    use syster::interchange::{JsonLd, Kpar, ModelFormat, Xmi, detect_format};
+    let options = ImportOptions {
+        verbose,
+        ..ImportOptions::default()
+    };
+    import_model_with_options(input, format, &options)
+}
+
+/// Import and validate a model as [`import_model`] does, as `options` say.
+#[cfg(feature = "interchange")]
+pub fn import_model_with_options(
+    input: &Path,
+    format: Option<&str>,
+    options: &ImportOptions,
+) -> Result<ImportResult, String> {
+    let verbose = options.verbose;
+    let (model, skipped) = read_import_model(input, format, options)?;
+
+    // Basic validation
+    let mut messages = Vec::new();
+    let mut error_count = 0;
+
+    // Check for references to elements that are neither in the file, nor
+    // in the supplementary sources, nor in another document
+    let references = references::classify_references(&model, &options.resolve_refs, verbose)?;
+    for reference in &references {
+        if reference.status == ReferenceStatus::Unresolved {
+            messages.push(reference.to_string());
+            error_count += 1;
+        }
+    }
+
+    if verbose {
+        tracing::info!(
+            "Imported: {} elements, {} relationships, {} validation issues",
+            model.elements.len(),
+            model.relationships.len(),
+            error_count
+        );
+        for msg in &messages {
+            tracing::info!("{}", msg);
+        }
+    }
+
+    messages.extend(skipped.iter().map(ToString::to_string));
+    Ok(ImportResult {
+        element_count: model.elements.len(),
+        relationship_count: model.relationships.len(),
+        error_count,
+        messages,
+        skipped,
+        id_resolutions: Vec::new(),
+        references,
+    })
+}
+
+/// Read an interchange file for import, recovering what can be read if
+/// `options` say so.
+#[cfg(feature = "interchange")]
+fn read_import_model(
+    input: &Path,
+    format: Option<&str>,
+    options: &ImportOptions,
+) -> Result<(syster::interchange::Model, Vec<SkippedContent>), String> {
+    use syster::interchange::{JsonLd, ModelFormat, Xmi, Yaml, detect_format};
+
+    let verbose = options.verbose;
+    let source = input.display().to_string();
 
-    // Read the input file
+    // Read the input file, decompressing it if needed
     let bytes =
         std::fs::read(input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+    let bytes = compress::decompress(bytes)?;
+    let format_path = compress::uncompressed_path(input);
 
     // Determine format
     let format_str = format.map(String::from).unwrap_or_else(|| {
-        input
+        format_path
             .extension()
             .and_then(|e| e.to_str())
             .unwrap_or("xmi")
@@ -687,67 +2555,61 @@ pub fn import_model(
     });
 
     if verbose {
-        println!("Importing {} as {}", input.display(), format_str);
+        tracing::info!(
+            "Importing {} as {} ({} bytes)",
+            input.display(),
+            format_str,
+            bytes.len()
+        );
     }
 
-    // Parse the model
+    // Parse the model; KPAR entries are read one by one
+    let mut skipped = Vec::new();
     let model = match format_str.to_lowercase().as_str() {
+        "xmi" | "sysmlx" | "kermlx" if options.continue_on_error => {
+            let (bytes, cut) = recovery::recover_xmi(bytes, &source, verbose)?;
+            skipped = cut;
+            Xmi.read(&bytes).map_err(|e| e.to_string())?
+        }
         "xmi" | "sysmlx" | "kermlx" => Xmi.read(&bytes).map_err(|e| e.to_string())?,
-        "kpar" => Kpar.read(&bytes).map_err(|e| e.to_string())?,
-        "jsonld" | "json-ld" | "json" => JsonLd.read(&bytes).map_err(|e| e.to_string())?,
+        "kpar" => {
+            let (model, cut) = recovery::read_kpar(&bytes, &source, options)?;
+            skipped = cut;
+            model
+        }
+        "jsonld" | "json-ld" => JsonLd.read(&bytes).map_err(|e| e.to_string())?,
+        "json" if sysml_json::is_standard_json(&bytes) => sysml_json::read(&bytes)?,
+        "json" => JsonLd.read(&bytes).map_err(|e| e.to_string())?,
+        "yaml" | "yml" => Yaml.read(&bytes).map_err(|e| e.to_string())?,
         _ => {
             // Try to detect from file extension
-            if let Some(format_impl) = detect_format(input) {
+            if let Some(format_impl) = detect_format(&format_path) {
                 format_impl.read(&bytes).map_err(|e| e.to_string())?
             } else {
                 return Err(format!(
-                    "Unknown format: {}. Use xmi, sysmlx, kermlx, kpar, or jsonld.",
+                    "Unknown format: {}. Use xmi, sysmlx, kermlx, kpar, jsonld, json, or yaml.",
                     format_str
                 ));
             }
         }
     };
 
-    // Basic validation
-    let mut messages = Vec::new();
-    let mut error_count = 0;
-
-    // Check for orphan relationships (references to non-existent elements)
-    for rel in &model.relationships {
-        if model.elements.get(&rel.source).is_none() {
-            messages.push(format!(
-                "Warning: Relationship source '{}' not found",
-                rel.source
-            ));
-            error_count += 1;
-        }
-        if model.elements.get(&rel.target).is_none() {
-            messages.push(format!(
-                "Warning: Relationship target '{}' not found",
-                rel.target
-            ));
-            error_count += 1;
-        }
+    if verbose && !skipped.is_empty() {
+        tracing::info!("Skipped {} unreadable elements", skipped.len());
     }
 
-    if verbose {
-        println!(
-            "Imported: {} elements, {} relationships, {} validation issues",
-            model.elements.len(),
-            model.relationships.len(),
-            error_count
-        );
-        for msg in &messages {
-            println!("  {}", msg);
-        }
-    }
+    Ok((model, skipped))
+}
 
-    Ok(ImportResult {
-        element_count: model.elements.len(),
-        relationship_count: model.relationships.len(),
-        error_count,
-        messages,
-    })
+/// Order of members within each namespace in decompiled text.
+#[cfg(feature = "interchange")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MemberOrder {
+    /// Keep the element order from the interchange file.
+    #[default]
+    Source,
+    /// Sort members by name.
+    Alphabetical,
 }
 
 /// Result of decompiling a model to SysML files.
@@ -783,17 +2645,156 @@ pub fn decompile_model(
     format: Option<&str>,
     verbose: bool,
 ) -> Result<DecompileResult, String> {
-    use syster::interchange::{
-        JsonLd, Kpar, ModelFormat, SourceInfo, Xmi, decompile_with_source, detect_format,
+    decompile_model_ordered(input, format, verbose, MemberOrder::Source)
+}
+
+/// Decompile an interchange file, ordering members as requested.
+///
+/// See [`decompile_model`]. Output is deterministic for either order: the
+/// same input always produces byte-identical SysML text and metadata.
+#[cfg(feature = "interchange")]
+pub fn decompile_model_ordered(
+    input: &Path,
+    format: Option<&str>,
+    verbose: bool,
+    order: MemberOrder,
+) -> Result<DecompileResult, String> {
+    use syster::interchange::SourceInfo;
+
+    let (model, format_str) = read_decompile_input(input, format, verbose)?;
+
+    let element_count = model.elements.len();
+
+    // Create source info
+    let source = SourceInfo::from_path(input.to_string_lossy()).with_format(&format_str);
+
+    // Decompile to SysML
+    let (text, metadata) = decompile_in_order(&model, source, order);
+
+    if verbose {
+        tracing::info!(
+            "Decompiled: {} elements -> {} chars of SysML, {} metadata entries",
+            element_count,
+            text.len(),
+            metadata.elements.len()
+        );
+    }
+
+    // Serialize metadata to JSON
+    let metadata_json = metadata_to_json(&metadata)?;
+
+    Ok(DecompileResult {
+        sysml_text: text,
+        metadata_json,
+        element_count,
+        source_path: input.to_string_lossy().to_string(),
+    })
+}
+
+/// Decompile a model, ordering members as requested.
+///
+/// Metadata always comes from a source-order pass so the recorded sibling
+/// order reflects the interchange file, not the printed order.
+#[cfg(feature = "interchange")]
+pub(crate) fn decompile_in_order(
+    model: &syster::interchange::Model,
+    source: syster::interchange::SourceInfo,
+    order: MemberOrder,
+) -> (String, syster::interchange::ImportMetadata) {
+    use syster::interchange::decompile_with_source;
+
+    let result = decompile_with_source(model, source);
+    match order {
+        MemberOrder::Source => (result.text, result.metadata),
+        MemberOrder::Alphabetical => {
+            let mut sorted = model.clone();
+            sort_members(&mut sorted);
+            let text = decompile_with_source(&sorted, Default::default()).text;
+            (text, result.metadata)
+        }
+    }
+}
+
+/// Sort the roots and the owned elements of every element by name.
+///
+/// Unnamed wrappers (memberships, namespaces) sort by the name of the element
+/// they wrap; the element ID breaks ties so the order is total.
+#[cfg(feature = "interchange")]
+fn sort_members(model: &mut syster::interchange::Model) {
+    use std::collections::HashMap;
+    use syster::interchange::ElementId;
+
+    let keys: HashMap<ElementId, (String, String)> = model
+        .elements
+        .keys()
+        .map(|id| (id.clone(), member_sort_key(model, id)))
+        .collect();
+    let key = |id: &ElementId| {
+        keys.get(id)
+            .cloned()
+            .unwrap_or_else(|| (String::new(), id.as_str().to_string()))
     };
 
-    // Read the input file
+    for element in model.elements.values_mut() {
+        element.owned_elements.sort_by_cached_key(&key);
+    }
+    model.roots.sort_by_cached_key(&key);
+}
+
+/// Sort key for a member: its display name, then its ID.
+#[cfg(feature = "interchange")]
+fn member_sort_key(
+    model: &syster::interchange::Model,
+    id: &syster::interchange::ElementId,
+) -> (String, String) {
+    let mut current = id;
+    while let Some(element) = model.get(current) {
+        if let Some(name) = &element.name {
+            return (name.to_string(), id.as_str().to_string());
+        }
+        match element.owned_elements.first() {
+            Some(child) if element.owned_elements.len() == 1 => current = child,
+            _ => break,
+        }
+    }
+    (String::new(), id.as_str().to_string())
+}
+
+/// Serialize import metadata as pretty JSON with sorted keys.
+///
+/// `ImportMetadata` keeps its elements in a `HashMap`; going through
+/// `serde_json::Value` (whose maps are ordered) keeps the output stable
+/// across runs so metadata files diff cleanly.
+#[cfg(feature = "interchange")]
+pub(crate) fn metadata_to_json(
+    metadata: &syster::interchange::ImportMetadata,
+) -> Result<String, String> {
+    serde_json::to_value(metadata)
+        .and_then(|value| serde_json::to_string_pretty(&value))
+        .map_err(|e| format!("Failed to serialize metadata: {}", e))
+}
+
+/// Read an interchange file for decompilation.
+///
+/// # Returns
+/// The parsed model and the format name it was read as.
+#[cfg(feature = "interchange")]
+pub(crate) fn read_decompile_input(
+    input: &Path,
+    format: Option<&str>,
+    verbose: bool,
+) -> Result<(syster::interchange::Model, String), String> {
+    use syster::interchange::{JsonLd, Kpar, ModelFormat, Xmi, Yaml, detect_format};
+
+    // Read the input file, decompressing it if needed
     let bytes =
         std::fs::read(input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+    let bytes = compress::decompress(bytes)?;
+    let format_path = compress::uncompressed_path(input);
 
     // Determine format
     let format_str = format.map(String::from).unwrap_or_else(|| {
-        input
+        format_path
             .extension()
             .and_then(|e| e.to_str())
             .unwrap_or("xmi")
@@ -801,51 +2802,88 @@ pub fn decompile_model(
     });
 
     if verbose {
-        println!("Decompiling {} as {}", input.display(), format_str);
+        tracing::info!("Decompiling {} as {}", input.display(), format_str);
     }
 
     // Parse the model
     let model = match format_str.to_lowercase().as_str() {
         "xmi" | "sysmlx" | "kermlx" => Xmi.read(&bytes).map_err(|e| e.to_string())?,
-        "kpar" => Kpar.read(&bytes).map_err(|e| e.to_string())?,
-        "jsonld" | "json-ld" | "json" => JsonLd.read(&bytes).map_err(|e| e.to_string())?,
+        "kpar" => Kpar
+            .read(&compress::deflate_kpar(bytes)?)
+            .map_err(|e| e.to_string())?,
+        "jsonld" | "json-ld" => JsonLd.read(&bytes).map_err(|e| e.to_string())?,
+        "json" if sysml_json::is_standard_json(&bytes) => sysml_json::read(&bytes)?,
+        "json" => JsonLd.read(&bytes).map_err(|e| e.to_string())?,
+        "yaml" | "yml" => Yaml.read(&bytes).map_err(|e| e.to_string())?,
         _ => {
-            if let Some(format_impl) = detect_format(input) {
+            if let Some(format_impl) = detect_format(&format_path) {
                 format_impl.read(&bytes).map_err(|e| e.to_string())?
             } else {
                 return Err(format!(
-                    "Unknown format: {}. Use xmi, sysmlx, kermlx, kpar, or jsonld.",
+                    "Unknown format: {}. Use xmi, sysmlx, kermlx, kpar, jsonld, json, or yaml.",
                     format_str
                 ));
             }
         }
     };
 
-    let element_count = model.elements.len();
-
-    // Create source info
-    let source = SourceInfo::from_path(input.to_string_lossy()).with_format(&format_str);
+    Ok((model, format_str))
+}
 
-    // Decompile to SysML
-    let result = decompile_with_source(&model, source);
+/// Format seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`.
+pub(crate) fn format_rfc3339(seconds: u64) -> String {
+    let days = (seconds / 86_400) as i64;
+    let time = seconds % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
 
-    if verbose {
-        println!(
-            "Decompiled: {} elements -> {} chars of SysML, {} metadata entries",
-            element_count,
-            result.text.len(),
-            result.metadata.elements.len()
-        );
+/// Quote a CSV field if it contains a separator, quote or line break.
+pub(crate) fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
     }
+}
 
-    // Serialize metadata to JSON
-    let metadata_json = serde_json::to_string_pretty(&result.metadata)
-        .map_err(|e| format!("Failed to serialize metadata: {}", e))?;
+/// Escape the XML (and HTML) special characters in text and attribute
+/// values.
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
 
-    Ok(DecompileResult {
-        sysml_text: result.text,
-        metadata_json,
-        element_count,
-        source_path: input.to_string_lossy().to_string(),
-    })
+/// Collapse documentation onto one line for table cells.
+#[cfg(feature = "codegen")]
+pub(crate) fn single_line(doc: Option<&str>) -> String {
+    doc.map(|d| d.split_whitespace().collect::<Vec<_>>().join(" "))
+        .unwrap_or_default()
+}
+
+/// Lowercase hex encoding.
+#[cfg(any(feature = "interchange", feature = "jira"))]
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
 }
@@ -0,0 +1,523 @@
+//! Naming-convention lint rules.
+//!
+//! `syster lint` checks declared names against per-kind patterns and an
+//! acronym policy, configured in the `[lint.naming]` table of a
+//! `syster.toml` next to (or above) the models:
+//!
+//! ```toml
+//! [lint.naming]
+//! definition = "^[A-Z][A-Za-z0-9]*$"   # PascalCase (default)
+//! usage = "^[a-z][A-Za-z0-9]*$"        # camelCase (default)
+//! package = "^[A-Z][A-Za-z0-9]*$"      # PascalCase (default)
+//! acronyms = ["ECU", "CAN"]
+//! acronym_style = "upper"              # VehicleECU; "capitalized": VehicleEcu
+//! ```
+//!
+//! Every violation is a warning with its own code (see [`codes`]). Where a
+//! violation can be fixed by a case conversion that satisfies every rule
+//! and does not clash with a sibling, a [`Rename`] is proposed; `--fix`
+//! applies it to the declaration and every resolved reference.
+
+use super::names::parent_scope;
+use super::project::{find_project_file, load_table};
+use super::{AnalysisOptions, DiagnosticInfo, load_input, load_stdlib_files};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use syster::hir::{HirSymbol, RefKind, Severity, SymbolIndex, SymbolKind, TypeRefKind};
+use syster::ide::AnalysisHost;
+
+/// Diagnostic codes of the lint rules.
+pub mod codes {
+    /// Definition name does not match the definition pattern.
+    pub const DEFINITION_NAME: &str = "L0001";
+    /// Usage name does not match the usage pattern.
+    pub const USAGE_NAME: &str = "L0002";
+    /// Package name does not match the package pattern.
+    pub const PACKAGE_NAME: &str = "L0003";
+    /// Acronym not written in the configured style.
+    pub const ACRONYM: &str = "L0004";
+    /// Element of a kind given to `--require-docs` has no documentation.
+    pub const MISSING_DOC: &str = "L0005";
+    /// Keyword not available in the SysML version selected with
+    /// `--sysml-version`.
+    pub const LANGUAGE_VERSION: &str = "L0006";
+    /// Reference to a package its layer may not depend on (`[layers]` in
+    /// `syster.toml`).
+    pub const LAYER_DEPENDENCY: &str = "L0007";
+    /// Requirement text without a "shall" (`[requirements]` in
+    /// `syster.toml`).
+    pub const REQUIREMENT_SHALL: &str = "L0008";
+    /// Requirement text with a vague term.
+    pub const REQUIREMENT_VAGUE: &str = "L0009";
+    /// Requirement text of more than one sentence.
+    pub const REQUIREMENT_SENTENCES: &str = "L0010";
+    /// Requirement text without a measurable criterion.
+    pub const REQUIREMENT_MEASURABLE: &str = "L0011";
+    /// Package owning more elements than its budget (`[budgets]` in
+    /// `syster.toml`).
+    pub const PACKAGE_ELEMENTS: &str = "L0012";
+    /// File longer than its budget.
+    pub const FILE_LINES: &str = "L0013";
+    /// Element nested deeper than the budget.
+    pub const NESTING_DEPTH: &str = "L0014";
+}
+
+const PASCAL_CASE: &str = "^[A-Z][A-Za-z0-9]*$";
+const CAMEL_CASE: &str = "^[a-z][A-Za-z0-9]*$";
+
+/// How configured acronyms are written inside names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AcronymStyle {
+    /// All capitals: `VehicleECU`.
+    #[default]
+    Upper,
+    /// Like a word: `VehicleEcu`.
+    Capitalized,
+}
+
+/// Naming rules, from `syster.toml` or the defaults.
+#[derive(Debug, Clone)]
+pub struct NamingConfig {
+    /// Pattern for definition names.
+    pub definition: Regex,
+    /// Pattern for usage (including attribute) names.
+    pub usage: Regex,
+    /// Pattern for package names.
+    pub package: Regex,
+    /// Acronyms subject to the acronym policy.
+    pub acronyms: Vec<String>,
+    /// How acronyms are written.
+    pub acronym_style: AcronymStyle,
+}
+
+/// The `[lint.naming]` table of `syster.toml`.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct NamingSection {
+    definition: Option<String>,
+    usage: Option<String>,
+    package: Option<String>,
+    #[serde(default)]
+    acronyms: Vec<String>,
+    #[serde(default)]
+    acronym_style: AcronymStyle,
+}
+
+impl Default for NamingConfig {
+    fn default() -> Self {
+        Self::from_section(NamingSection::default()).expect("default patterns are valid")
+    }
+}
+
+impl NamingConfig {
+    /// Load the `[lint.naming]` table of a project file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let section = load_table(path, "lint.naming")?.unwrap_or_default();
+        Self::from_section(section)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    /// Load the nearest `syster.toml` in `input` (or its directory) and its
+    /// ancestors, or the defaults if there is none.
+    pub fn discover(input: &Path) -> Result<Self, String> {
+        match find_project_file(input) {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    fn from_section(section: NamingSection) -> Result<Self, String> {
+        let pattern = |value: Option<String>, default: &str| {
+            let value = value.unwrap_or_else(|| default.to_string());
+            Regex::new(&value).map_err(|e| format!("Invalid pattern '{}': {}", value, e))
+        };
+        Ok(Self {
+            definition: pattern(section.definition, PASCAL_CASE)?,
+            usage: pattern(section.usage, CAMEL_CASE)?,
+            package: pattern(section.package, PASCAL_CASE)?,
+            acronyms: section.acronyms,
+            acronym_style: section.acronym_style,
+        })
+    }
+}
+
+/// A proposed fix: rename a declaration and its references.
+#[derive(Debug, Clone, Serialize)]
+pub struct Rename {
+    /// Qualified name of the declaration.
+    pub qualified_name: String,
+    /// Current simple name.
+    pub from: String,
+    /// New simple name.
+    pub to: String,
+    /// Declaration and reference spans to rewrite.
+    #[serde(skip)]
+    locations: Vec<Location>,
+}
+
+/// A single-line span naming the renamed element.
+#[derive(Debug, Clone)]
+struct Location {
+    file: String,
+    /// Line (0-indexed).
+    line: u32,
+    /// Start and end byte column (0-indexed).
+    start: u32,
+    end: u32,
+    /// Number of trailing `::` segments after the renamed name
+    /// (`Pkg::Old::Member` renaming `Pkg::Old`: 1).
+    depth: usize,
+}
+
+/// Lint results.
+#[derive(Debug, Serialize)]
+pub struct LintReport {
+    /// Number of files linted.
+    pub file_count: usize,
+    /// Naming violations, as warnings.
+    pub diagnostics: Vec<DiagnosticInfo>,
+    /// Renames fixing violations, for `--fix`.
+    pub renames: Vec<Rename>,
+}
+
+/// Lint the names declared in `input` (the standard library is loaded for
+/// resolution only).
+///
+/// # Arguments
+/// * `input` - Path to a file or directory to lint
+/// * `options` - Verbosity and standard library to load
+/// * `config` - Naming rules
+pub fn lint(
+    input: &Path,
+    options: &AnalysisOptions,
+    config: &NamingConfig,
+) -> Result<LintReport, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let stdlib_files: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    load_input(&mut host, input, options.verbose)?;
+    let _analysis = host.analysis();
+
+    let index = host.symbol_index();
+    let mut diagnostics = Vec::new();
+    let mut renames = Vec::new();
+    let mut file_count = 0;
+    for path in host.files().keys() {
+        let file = path.to_string_lossy().to_string();
+        if stdlib_files.contains(&file) {
+            continue;
+        }
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        file_count += 1;
+        for symbol in index.symbols_in_file(file_id) {
+            let Some((issues, fixed)) = check_name(symbol, config) else {
+                continue;
+            };
+            let fix = fixed.filter(|to| {
+                let scope = parent_scope(&symbol.qualified_name);
+                index
+                    .visibility_for_scope(scope)
+                    .is_none_or(|vis| vis.lookup_direct(to).is_none())
+            });
+            let suffix = fix
+                .as_ref()
+                .map(|to| format!(" (rename to '{}')", to))
+                .unwrap_or_default();
+            for (code, message) in issues {
+                diagnostics.push(DiagnosticInfo {
+                    file: file.clone(),
+                    line: symbol.start_line + 1,
+                    col: symbol.start_col + 1,
+                    end_line: symbol.end_line + 1,
+                    end_col: symbol.end_col + 1,
+                    message: format!("{}{}", message, suffix),
+                    severity: Severity::Warning,
+                    code: Some(code.to_string()),
+                    blame: None,
+                    owners: Vec::new(),
+                    related: Vec::new(),
+                });
+            }
+            if let Some(to) = fix {
+                renames.push(rename(&host, index, &file, symbol, to));
+            }
+        }
+    }
+
+    super::sort_diagnostics(&mut diagnostics);
+    renames.sort_by(|a, b| a.qualified_name.cmp(&b.qualified_name));
+    Ok(LintReport {
+        file_count,
+        diagnostics,
+        renames,
+    })
+}
+
+/// Check one declaration, returning its violations and, if a case
+/// conversion fixes all of them, the new name. `None` if the symbol is not
+/// subject to naming rules.
+fn check_name(
+    symbol: &HirSymbol,
+    config: &NamingConfig,
+) -> Option<(Vec<(&'static str, String)>, Option<String>)> {
+    let name = symbol.name.as_ref();
+    let (label, pattern, code, convert): (_, _, _, fn(&str) -> String) = match symbol.kind {
+        SymbolKind::Package => (
+            "package",
+            &config.package,
+            codes::PACKAGE_NAME,
+            to_pascal_case,
+        ),
+        kind if kind.is_definition() => (
+            "definition",
+            &config.definition,
+            codes::DEFINITION_NAME,
+            to_pascal_case,
+        ),
+        kind if kind.is_usage() => ("usage", &config.usage, codes::USAGE_NAME, to_camel_case),
+        _ => return None,
+    };
+    // Anonymous and redefining usages do not choose their own name
+    let redefines = symbol.type_refs.iter().any(|r| {
+        matches!(r, TypeRefKind::Simple(r) if r.kind == RefKind::Redefines && *r.target == *name)
+    });
+    if name.is_empty() || name.starts_with('<') || redefines {
+        return None;
+    }
+
+    let mut issues = Vec::new();
+    let styled = apply_acronym_style(name, config, symbol.kind.is_usage());
+    if styled != name {
+        issues.push((
+            codes::ACRONYM,
+            format!(
+                "{} name '{}' does not follow the acronym style '{}'",
+                label, name, styled
+            ),
+        ));
+    }
+    if !pattern.is_match(name) {
+        issues.push((
+            code,
+            format!(
+                "{} name '{}' does not match '{}'",
+                label,
+                name,
+                pattern.as_str()
+            ),
+        ));
+    }
+    if issues.is_empty() {
+        return None;
+    }
+
+    let fixed = if pattern.is_match(&styled) {
+        styled
+    } else {
+        apply_acronym_style(&convert(&styled), config, symbol.kind.is_usage())
+    };
+    let fixable = fixed != name
+        && pattern.is_match(&fixed)
+        && apply_acronym_style(&fixed, config, symbol.kind.is_usage()) == fixed
+        && fixed.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    Some((issues, fixable.then_some(fixed)))
+}
+
+/// Collect the spans to rewrite when renaming `symbol`: its declaration and
+/// every reference resolved to it or, through a qualified name, to one of
+/// its members.
+fn rename(
+    host: &AnalysisHost,
+    index: &SymbolIndex,
+    file: &str,
+    symbol: &HirSymbol,
+    to: String,
+) -> Rename {
+    let mut locations = vec![Location {
+        file: file.to_string(),
+        line: symbol.start_line,
+        start: symbol.start_col,
+        end: symbol.end_col,
+        depth: 0,
+    }];
+    let member_prefix = format!("{}::", symbol.qualified_name);
+    for other in index.all_symbols() {
+        let Some(other_file) = host.get_file_path(other.file) else {
+            continue;
+        };
+        for type_ref in &other.type_refs {
+            for r in type_ref.as_refs() {
+                let Some(target) = r.resolved_target.as_deref() else {
+                    continue;
+                };
+                let depth = if target == &*symbol.qualified_name {
+                    0
+                } else if let Some(member) = target.strip_prefix(&member_prefix) {
+                    member.split("::").count()
+                } else {
+                    continue;
+                };
+                if r.start_line == r.end_line {
+                    locations.push(Location {
+                        file: other_file.to_string(),
+                        line: r.start_line,
+                        start: r.start_col,
+                        end: r.end_col,
+                        depth,
+                    });
+                }
+            }
+        }
+    }
+    Rename {
+        qualified_name: symbol.qualified_name.to_string(),
+        from: symbol.name.to_string(),
+        to,
+        locations,
+    }
+}
+
+/// Apply renames to the files on disk, returning the number of replaced
+/// occurrences.
+///
+/// In a qualified reference (`Pkg::Old::Member`) only the segment naming
+/// the renamed element is rewritten, and only if it still reads the old
+/// name; references reaching a member through an import are left alone.
+pub fn apply_renames(renames: &[Rename]) -> Result<usize, String> {
+    let mut by_file: BTreeMap<&str, Vec<(&Location, &Rename)>> = BTreeMap::new();
+    for rename in renames {
+        for location in &rename.locations {
+            by_file
+                .entry(&location.file)
+                .or_default()
+                .push((location, rename));
+        }
+    }
+
+    let mut count = 0;
+    for (file, edits) in by_file {
+        let mut text =
+            std::fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        let mut replacements: Vec<(usize, usize, &str)> = edits
+            .into_iter()
+            .filter_map(|(location, rename)| {
+                let base = *line_starts.get(location.line as usize)?;
+                let start = base + location.start as usize;
+                let span = text.get(start..base + location.end as usize)?;
+                let offset = segment_offset(span, location.depth, &rename.from)?;
+                let start = start + offset;
+                Some((start, start + rename.from.len(), rename.to.as_str()))
+            })
+            .collect();
+        replacements.sort();
+        replacements.dedup();
+
+        // Back to front so earlier offsets stay valid
+        for (start, end, to) in replacements.iter().rev() {
+            text.replace_range(*start..*end, to);
+        }
+        count += replacements.len();
+        std::fs::write(file, text).map_err(|e| format!("Failed to write {}: {}", file, e))?;
+    }
+    Ok(count)
+}
+
+/// Byte offset in `span` of the `depth`-th `::` segment from the end, if
+/// it reads `name`.
+fn segment_offset(span: &str, depth: usize, name: &str) -> Option<usize> {
+    let segments: Vec<&str> = span.split("::").collect();
+    let index = segments.len().checked_sub(depth + 1)?;
+    if segments[index].trim() != name {
+        return None;
+    }
+    let before: usize = segments[..index].iter().map(|s| s.len() + 2).sum();
+    let leading = segments[index].len() - segments[index].trim_start().len();
+    Some(before + leading)
+}
+
+/// Rewrite the configured acronyms of `name` in the configured style.
+///
+/// At the start of a usage name (camelCase) acronyms are left alone.
+fn apply_acronym_style(name: &str, config: &NamingConfig, is_usage: bool) -> String {
+    let mut name = name.to_string();
+    for acronym in &config.acronyms {
+        let upper = acronym.to_uppercase();
+        let capitalized = to_pascal_case(&acronym.to_lowercase());
+        let (wrong, right) = match config.acronym_style {
+            AcronymStyle::Upper => (capitalized, upper),
+            AcronymStyle::Capitalized => (upper, capitalized),
+        };
+        if wrong == right {
+            continue;
+        }
+        let matches: Vec<usize> = name
+            .match_indices(&wrong)
+            .map(|(i, _)| i)
+            .filter(|&i| {
+                let before = name[..i].chars().next_back();
+                let after = name[i + wrong.len()..].chars().next();
+                let starts_word = match before {
+                    None => !is_usage,
+                    Some(c) => c.is_lowercase() || c.is_ascii_digit() || c == '_',
+                };
+                starts_word && !after.is_some_and(|c| c.is_lowercase())
+            })
+            .collect();
+        for i in matches.into_iter().rev() {
+            name.replace_range(i..i + wrong.len(), &right);
+        }
+    }
+    name
+}
+
+/// `engine_block` / `engineBlock` → `EngineBlock`.
+fn to_pascal_case(name: &str) -> String {
+    name.split(['_', '-'])
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `engine_block` / `EngineBlock` → `engineBlock`; a leading acronym is
+/// lowercased (`ECUController` → `ecuController`).
+fn to_camel_case(name: &str) -> String {
+    let pascal = to_pascal_case(name);
+    let chars: Vec<char> = pascal.chars().collect();
+    let run = chars.iter().take_while(|c| c.is_uppercase()).count();
+    let lower = if run == chars.len() || run <= 1 {
+        run
+    } else {
+        run - 1
+    };
+    chars
+        .iter()
+        .enumerate()
+        .flat_map(|(i, c)| {
+            if i < lower {
+                c.to_lowercase().collect::<Vec<_>>()
+            } else {
+                vec![*c]
+            }
+        })
+        .collect()
+}
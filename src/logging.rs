@@ -0,0 +1,147 @@
+//! Log output of the library and the CLI.
+//!
+//! Progress messages (the `verbose` output) and warnings are emitted as
+//! `tracing` events, so embedders can capture or silence them with their
+//! own subscriber. [`init_logging`] installs the CLI's: events go to stderr,
+//! leaving stdout to the results, either as plain text in the CLI's usual
+//! `warning: ...` style or as one JSON object per line:
+//!
+//! ```text
+//! syster ./models --log-level debug --log-format json 2> log.jsonl
+//! ```
+
+#[cfg(feature = "otel")]
+use super::telemetry::{self, Telemetry};
+use std::fmt;
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{Layer, fmt as fmt_layer};
+
+/// Most detailed events logged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogLevel {
+    /// Errors only.
+    Error,
+    /// Warnings and errors only.
+    #[default]
+    Warn,
+    /// Also progress messages (what `--verbose` prints).
+    Info,
+    /// Also debugging detail.
+    Debug,
+    /// Everything.
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// How log events are written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// `warning: ...`, `error: ...`; progress messages as they are.
+    #[default]
+    Text,
+    /// One JSON object per event, with timestamp, level, fields and spans.
+    Json,
+}
+
+/// Options for [`init_logging`].
+#[derive(Debug, Clone, Default)]
+pub struct LogOptions {
+    pub level: LogLevel,
+    pub format: LogFormat,
+    /// OTLP/HTTP collector to export spans to (see [`telemetry`]).
+    #[cfg(feature = "otel")]
+    pub otlp_endpoint: Option<String>,
+}
+
+/// Installed logging; call [`Logging::shutdown`] before exiting.
+pub struct Logging {
+    #[cfg(feature = "otel")]
+    telemetry: Option<Telemetry>,
+}
+
+impl Logging {
+    /// Flush pending spans, if any are exported.
+    pub fn shutdown(self) -> Result<(), String> {
+        #[cfg(feature = "otel")]
+        if let Some(telemetry) = self.telemetry {
+            return telemetry.shutdown();
+        }
+        Ok(())
+    }
+}
+
+/// Log events of this process to stderr as configured by `options`.
+pub fn init_logging(options: &LogOptions) -> Result<Logging, String> {
+    let output = match options.format {
+        LogFormat::Text => fmt_layer::layer()
+            .event_format(TextFormat)
+            .with_writer(std::io::stderr)
+            .boxed(),
+        LogFormat::Json => fmt_layer::layer()
+            .json()
+            .with_writer(std::io::stderr)
+            .boxed(),
+    };
+    let subscriber =
+        tracing_subscriber::registry().with(output.with_filter(LevelFilter::from(options.level)));
+
+    #[cfg(feature = "otel")]
+    let (subscriber, telemetry) = match &options.otlp_endpoint {
+        Some(endpoint) => {
+            let (telemetry, spans) = telemetry::layer(endpoint)?;
+            (subscriber.with(Some(spans)), Some(telemetry))
+        }
+        None => (subscriber.with(None), None),
+    };
+
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|e| format!("Failed to install logging: {}", e))?;
+    Ok(Logging {
+        #[cfg(feature = "otel")]
+        telemetry,
+    })
+}
+
+/// The CLI's message style: a severity prefix for errors and warnings,
+/// none for progress messages.
+struct TextFormat;
+
+impl<S, N> FormatEvent<S, N> for TextFormat
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+    N: for<'writer> FormatFields<'writer> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let prefix = match *event.metadata().level() {
+            Level::ERROR => "error: ",
+            Level::WARN => "warning: ",
+            Level::INFO => "",
+            Level::DEBUG => "debug: ",
+            _ => "trace: ",
+        };
+        write!(writer, "{}", prefix)?;
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
@@ -1,12 +1,23 @@
 //! syster CLI - Command-line interface for SysML v2 and KerML analysis
 
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 use std::process::ExitCode;
-use syster::hir::Severity;
-use syster_cli::{DiagnosticInfo, export_ast, export_json, run_analysis};
+use syster::hir::{Severity, SymbolKind};
+use syster_cli::{
+    AnalysisOptions, Budgets, Catalog, DiagnosticInfo, FetchStatus, KnownIssues, LayerRules,
+    Manifest, ModelIndex, ModelOwners, RequirementRules, Shard, SourcePosition, SysmlVersion,
+    TagsFormat, VariantConfig, WalkOptions, Workspace, analyze, analyze_projects, analyze_shards,
+    annotate_blame, check_stdlib, diff_diagnostics, export_ast_with_options, export_gerrit,
+    export_json, export_rdjson, load_json_result, merge_results, render_diagnostic_diff,
+    run_analysis_staged,
+};
 #[cfg(feature = "interchange")]
-use syster_cli::{decompile_model, export_model, import_model, import_model_into_host};
+use syster_cli::{
+    Compression, ExportFilter, IdConflictPolicy, IdPolicy, ImportOptions, ImportResult,
+    MemberOrder, ReferenceStatus, compress_export, decompile_model_ordered, export_model_to,
+    export_model_with_options, import_model_into_host_with_options, import_model_with_options,
+};
 
 /// Output format for export commands
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -17,6 +28,33 @@ enum OutputFormat {
     Json,
 }
 
+/// Diagnostics format for code-review tools and reports
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ReviewFormat {
+    /// reviewdog Diagnostic Result JSON
+    Rdjson,
+    /// Gerrit ReviewInput with robot comments
+    Gerrit,
+    /// Standalone HTML dashboard with charts and source snippets
+    #[cfg(feature = "codegen")]
+    Html,
+    /// Jupyter display data: an HTML rendering and the --json result
+    NotebookJson,
+}
+
+/// Format of `syster index build`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum IndexFormat {
+    /// Dependency index for --index
+    Sidx,
+    /// SCIP code-navigation index (Sourcegraph)
+    Scip,
+    /// Vim tags file
+    Ctags,
+    /// Emacs TAGS file
+    Etags,
+}
+
 /// Interchange format for model export
 #[cfg(feature = "interchange")]
 #[derive(Clone, Copy, Debug, ValueEnum)]
@@ -27,45 +65,470 @@ enum InterchangeFormat {
     Kpar,
     /// JSON-LD
     JsonLd,
+    /// SysML v2 standard JSON (API element payloads)
+    Json,
     /// YAML
     Yaml,
+    /// PlantUML sequence diagrams, one per behavior
+    PlantumlSequence,
+}
+
+/// Document format for `syster icd`, `syster glossary` and
+/// `syster verification-report`
+#[cfg(feature = "codegen")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum DocFormat {
+    /// Markdown tables
+    Markdown,
+    /// Standalone HTML page
+    Html,
+    /// One CSV row per entry
+    Csv,
+}
+
+#[cfg(feature = "codegen")]
+impl From<DocFormat> for syster_cli::DocumentFormat {
+    fn from(format: DocFormat) -> Self {
+        match format {
+            DocFormat::Markdown => Self::Markdown,
+            DocFormat::Html => Self::Html,
+            DocFormat::Csv => Self::Csv,
+        }
+    }
+}
+
+/// Test framework for `syster generate-tests`
+#[cfg(feature = "codegen")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Framework {
+    /// Python test modules for pytest
+    Pytest,
+    /// C++ test files for GoogleTest
+    Gtest,
+}
+
+#[cfg(feature = "codegen")]
+impl From<Framework> for syster_cli::TestFramework {
+    fn from(framework: Framework) -> Self {
+        match framework {
+            Framework::Pytest => Self::Pytest,
+            Framework::Gtest => Self::Gtest,
+        }
+    }
+}
+
+/// Selection matrix format for `syster variants list`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SelectionFormat {
+    /// TOML configuration to fill in
+    Text,
+    /// One row per variant
+    Csv,
+    /// One object per variation point
+    Json,
+}
+
+impl From<SelectionFormat> for syster_cli::MatrixFormat {
+    fn from(format: SelectionFormat) -> Self {
+        match format {
+            SelectionFormat::Text => Self::Text,
+            SelectionFormat::Csv => Self::Csv,
+            SelectionFormat::Json => Self::Json,
+        }
+    }
+}
+
+/// Metric reported by `syster history` and charted by `syster metrics chart`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Metric {
+    /// Number of SysML/KerML files
+    Files,
+    /// Number of symbols
+    Symbols,
+    /// Number of errors
+    Errors,
+    /// Number of warnings
+    Warnings,
+    /// Documented definitions, in percent
+    Docs,
+}
+
+impl From<Metric> for syster_cli::HistoryMetric {
+    fn from(metric: Metric) -> Self {
+        match metric {
+            Metric::Files => Self::Files,
+            Metric::Symbols => Self::Symbols,
+            Metric::Errors => Self::Errors,
+            Metric::Warnings => Self::Warnings,
+            Metric::Docs => Self::Docs,
+        }
+    }
+}
+
+/// Severity of `--duplicates` diagnostics
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum DuplicateSeverity {
+    /// Report duplicates as warnings
+    Warning,
+    /// Report duplicates as errors (fails the check)
+    Error,
+}
+
+impl From<DuplicateSeverity> for Severity {
+    fn from(severity: DuplicateSeverity) -> Self {
+        match severity {
+            DuplicateSeverity::Warning => Self::Warning,
+            DuplicateSeverity::Error => Self::Error,
+        }
+    }
+}
+
+/// Analysis profile selected with `--profile`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CheckProfile {
+    /// Every validation pass; warnings are errors
+    Strict,
+    /// Diagnostics at their own severity
+    Standard,
+    /// Unresolved names are warnings (for incomplete models)
+    Permissive,
+}
+
+impl From<CheckProfile> for syster_cli::Profile {
+    fn from(profile: CheckProfile) -> Self {
+        match profile {
+            CheckProfile::Strict => Self::Strict,
+            CheckProfile::Standard => Self::Standard,
+            CheckProfile::Permissive => Self::Permissive,
+        }
+    }
+}
+
+/// Report language selected with `--lang`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ReportLang {
+    /// English
+    En,
+    /// German
+    De,
+    /// French
+    Fr,
+    /// Japanese
+    Ja,
+}
+
+impl From<ReportLang> for syster_cli::Lang {
+    fn from(lang: ReportLang) -> Self {
+        match lang {
+            ReportLang::En => Self::En,
+            ReportLang::De => Self::De,
+            ReportLang::Fr => Self::Fr,
+            ReportLang::Ja => Self::Ja,
+        }
+    }
+}
+
+/// How much of the offending file `--crash-report` includes
+#[cfg(feature = "interchange")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CrashRedactArg {
+    /// The file as it is
+    None,
+    /// Names, strings and comments replaced
+    Names,
+    /// Only the file's path and size
+    Content,
+}
+
+#[cfg(feature = "interchange")]
+impl From<CrashRedactArg> for syster_cli::CrashRedaction {
+    fn from(redact: CrashRedactArg) -> Self {
+        match redact {
+            CrashRedactArg::None => Self::None,
+            CrashRedactArg::Names => Self::Names,
+            CrashRedactArg::Content => Self::Content,
+        }
+    }
+}
+
+/// Metric shown by `syster badge`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum BadgeMetric {
+    /// Number of errors
+    Errors,
+    /// Number of warnings
+    Warnings,
+    /// Documented definitions, in percent
+    DocCoverage,
+}
+
+impl From<BadgeMetric> for syster_cli::BadgeMetric {
+    fn from(metric: BadgeMetric) -> Self {
+        match metric {
+            BadgeMetric::Errors => Self::Errors,
+            BadgeMetric::Warnings => Self::Warnings,
+            BadgeMetric::DocCoverage => Self::DocCoverage,
+        }
+    }
+}
+
+/// Grouping selected with `--summary-by`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SummaryGrouping {
+    /// Symbols, errors and warnings per top-level package
+    Package,
+    /// Symbols, errors and warnings per file
+    File,
+    /// Diagnostics per severity
+    Severity,
+}
+
+impl From<SummaryGrouping> for syster_cli::SummaryBy {
+    fn from(grouping: SummaryGrouping) -> Self {
+        match grouping {
+            SummaryGrouping::Package => Self::Package,
+            SummaryGrouping::File => Self::File,
+            SummaryGrouping::Severity => Self::Severity,
+        }
+    }
+}
+
+/// Level selected with `--log-level`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum LogLevelArg {
+    /// Errors only
+    Error,
+    /// Warnings and errors
+    Warn,
+    /// Also progress messages (as with --verbose)
+    Info,
+    /// Also debugging detail
+    Debug,
+    /// Everything
+    Trace,
+}
+
+impl From<LogLevelArg> for syster_cli::LogLevel {
+    fn from(level: LogLevelArg) -> Self {
+        match level {
+            LogLevelArg::Error => Self::Error,
+            LogLevelArg::Warn => Self::Warn,
+            LogLevelArg::Info => Self::Info,
+            LogLevelArg::Debug => Self::Debug,
+            LogLevelArg::Trace => Self::Trace,
+        }
+    }
+}
+
+/// Format selected with `--log-format`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum LogFormatArg {
+    /// Plain text
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+impl From<LogFormatArg> for syster_cli::LogFormat {
+    fn from(format: LogFormatArg) -> Self {
+        match format {
+            LogFormatArg::Text => Self::Text,
+            LogFormatArg::Json => Self::Json,
+        }
+    }
+}
+
+/// Part of the standard library selected with `--stdlib-subset`
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum StdlibSubsetArg {
+    /// The whole library
+    Full,
+    /// Only the files the model needs, found from its imports and references
+    Auto,
+}
+
+impl From<StdlibSubsetArg> for syster_cli::StdlibSubset {
+    fn from(subset: StdlibSubsetArg) -> Self {
+        match subset {
+            StdlibSubsetArg::Full => Self::Full,
+            StdlibSubsetArg::Auto => Self::Auto,
+        }
+    }
+}
+
+/// Table format for `syster safety fmea`
+#[cfg(all(feature = "codegen", feature = "interchange"))]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum TableFormat {
+    /// Comma-separated values
+    Csv,
+    /// Excel workbook
+    Xlsx,
+}
+
+/// Member order for decompiled SysML text
+#[cfg(feature = "interchange")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SortOrder {
+    /// Keep the element order from the interchange file
+    None,
+    /// Sort members by name
+    Alphabetical,
+}
+
+#[cfg(feature = "interchange")]
+impl From<SortOrder> for MemberOrder {
+    fn from(order: SortOrder) -> Self {
+        match order {
+            SortOrder::None => MemberOrder::Source,
+            SortOrder::Alphabetical => MemberOrder::Alphabetical,
+        }
+    }
+}
+
+/// Element ID assignment for exports
+#[cfg(feature = "interchange")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum IdPolicyArg {
+    /// Restore IDs from companion metadata, random UUIDs for the rest
+    Preserve,
+    /// Random UUIDs for every element
+    UuidV4,
+    /// UUIDv5 of the qualified name in --id-namespace
+    UuidV5Namespace,
+    /// Hex hash of the qualified name
+    QualifiedNameHash,
+}
+
+#[cfg(feature = "interchange")]
+impl From<IdPolicyArg> for IdPolicy {
+    fn from(policy: IdPolicyArg) -> Self {
+        match policy {
+            IdPolicyArg::Preserve => IdPolicy::Preserve,
+            IdPolicyArg::UuidV4 => IdPolicy::UuidV4,
+            IdPolicyArg::UuidV5Namespace => IdPolicy::UuidV5Namespace,
+            IdPolicyArg::QualifiedNameHash => IdPolicy::QualifiedNameHash,
+        }
+    }
+}
+
+/// Compression for exports
+#[cfg(feature = "interchange")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CompressionArg {
+    /// Uncompressed
+    None,
+    /// gzip the whole file
+    Gzip,
+    /// zstd the whole file; for kpar, compress its entries with zstd
+    Zstd,
+}
+
+#[cfg(feature = "interchange")]
+impl From<CompressionArg> for Compression {
+    fn from(compression: CompressionArg) -> Self {
+        match compression {
+            CompressionArg::None => Compression::None,
+            CompressionArg::Gzip => Compression::Gzip,
+            CompressionArg::Zstd => Compression::Zstd,
+        }
+    }
+}
+
+/// What --import-workspace does with imported elements whose IDs are taken
+#[cfg(feature = "interchange")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum IdConflictArg {
+    /// Fail the import
+    Error,
+    /// Give the imported element a new ID
+    Rename,
+    /// Leave the imported element and its members out
+    Skip,
+    /// Keep the imported element's ID and give the workspace element a new one
+    Overwrite,
+}
+
+#[cfg(feature = "interchange")]
+impl From<IdConflictArg> for IdConflictPolicy {
+    fn from(policy: IdConflictArg) -> Self {
+        match policy {
+            IdConflictArg::Error => IdConflictPolicy::Error,
+            IdConflictArg::Rename => IdConflictPolicy::Rename,
+            IdConflictArg::Skip => IdConflictPolicy::Skip,
+            IdConflictArg::Overwrite => IdConflictPolicy::Overwrite,
+        }
+    }
 }
 
 #[derive(Parser)]
 #[command(name = "syster")]
 #[command(about = "SysML v2 parser and semantic analyzer", long_about = None)]
 #[command(version)]
+#[command(subcommand_negates_reqs = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Input file or directory to analyze
-    #[arg(value_name = "FILE")]
-    input: PathBuf,
+    #[arg(value_name = "FILE", required = true)]
+    input: Option<PathBuf>,
 
-    /// Enable verbose output
-    #[arg(short, long)]
+    /// Enable verbose output (same as --log-level info)
+    #[arg(short, long, global = true)]
     verbose: bool,
 
+    /// Most detailed log messages to print on stderr (default: warn, or info with --verbose)
+    #[arg(long, value_name = "LEVEL", global = true)]
+    log_level: Option<LogLevelArg>,
+
+    /// Format of log messages on stderr
+    #[arg(long, value_name = "FORMAT", global = true, default_value = "text")]
+    log_format: LogFormatArg,
+
     /// Skip loading standard library
-    #[arg(long)]
+    #[arg(long, global = true)]
     no_stdlib: bool,
 
     /// Path to custom standard library (default: sysml.library)
-    #[arg(long, value_name = "PATH")]
+    #[arg(long, value_name = "PATH", global = true)]
     stdlib_path: Option<PathBuf>,
 
+    /// Part of the standard library to load when analyzing
+    #[arg(long, value_name = "SUBSET", global = true, default_value = "full")]
+    stdlib_subset: StdlibSubsetArg,
+
+    /// Report diagnostics inside the standard library, and fail on its errors
+    #[arg(long, global = true)]
+    include_stdlib_diagnostics: bool,
+
     /// Export AST (abstract syntax tree) for all files
     #[arg(long)]
     export_ast: bool,
 
+    /// With --export or --export-ast, only include elements annotated with this metadata (repeatable)
+    #[arg(long, value_name = "METADEF")]
+    with_metadata: Vec<String>,
+
     /// Export analysis results as JSON
-    #[arg(long)]
+    #[arg(long, global = true)]
     json: bool,
 
+    /// Write diagnostics for a code-review tool (rdjson for reviewdog, gerrit for robot comments) or as an HTML dashboard
+    #[arg(long, value_name = "FORMAT", conflicts_with = "json")]
+    format: Option<ReviewFormat>,
+
+    /// Machine mode for wrappers such as the Jupyter magic: stdout carries only the --json
+    /// result (or the --format output), never progress or summary lines
+    #[arg(long)]
+    machine: bool,
+
     /// Export model to interchange format (xmi, kpar, jsonld)
     #[cfg(feature = "interchange")]
     #[arg(long, value_name = "FORMAT")]
     export: Option<InterchangeFormat>,
 
-    /// Import and validate an interchange file (xmi, kpar, jsonld)
+    /// Import and validate an interchange file (xmi, kpar, jsonld, json, yaml)
     #[cfg(feature = "interchange")]
     #[arg(long)]
     import: bool,
@@ -75,187 +538,4281 @@ struct Cli {
     #[arg(long)]
     import_workspace: bool,
 
+    /// With --import or --import-workspace, skip XMI elements (and KPAR entries) that cannot be read, listing them
+    #[cfg(feature = "interchange")]
+    #[arg(long)]
+    continue_on_error: bool,
+
+    /// With --import-workspace, also import these interchange files into the workspace, in order (repeatable)
+    #[cfg(feature = "interchange")]
+    #[arg(long, value_name = "FILE", requires = "import_workspace")]
+    merge: Vec<PathBuf>,
+
+    /// With --import-workspace, what to do with imported elements whose IDs workspace elements already have
+    #[cfg(feature = "interchange")]
+    #[arg(
+        long,
+        value_enum,
+        value_name = "POLICY",
+        default_value = "error",
+        requires = "import_workspace"
+    )]
+    on_id_conflict: IdConflictArg,
+
+    /// With --import or --import-workspace, look up elements the file references but does not contain in this directory, interchange file or symbol index (repeatable)
+    #[cfg(feature = "interchange")]
+    #[arg(long, value_name = "PATH")]
+    resolve_refs: Vec<PathBuf>,
+
     /// Decompile interchange file to SysML text + metadata
     #[cfg(feature = "interchange")]
     #[arg(long)]
     decompile: bool,
 
+    /// With --decompile, write one .sysml file per package into a directory
+    #[cfg(feature = "interchange")]
+    #[arg(long, requires = "decompile")]
+    split_by_package: bool,
+
+    /// Member order in decompiled SysML text
+    #[cfg(feature = "interchange")]
+    #[arg(
+        long,
+        value_name = "ORDER",
+        default_value = "none",
+        requires = "decompile"
+    )]
+    sort: SortOrder,
+
     /// Include standard library in export (self-contained output)
     #[cfg(feature = "interchange")]
     #[arg(long)]
     self_contained: bool,
 
+    /// Only export this package and its dependencies (repeatable)
+    #[cfg(feature = "interchange")]
+    #[arg(long = "package", value_name = "QUALIFIED::NAME", requires = "export")]
+    packages: Vec<String>,
+
+    /// Only export this element and its contents, with stubs (marked isExternal) of what they reference
+    #[cfg(feature = "interchange")]
+    #[arg(long, value_name = "QUALIFIED::NAME", requires = "export")]
+    root: Option<String>,
+
+    /// Compress the export: gzip or zstd for the whole file, zstd entries for kpar
+    #[cfg(feature = "interchange")]
+    #[arg(
+        long,
+        value_name = "CODEC",
+        default_value = "none",
+        requires = "export"
+    )]
+    compress: CompressionArg,
+
+    /// With --export jsonld, write pages of at most N objects and an index.jsonld into the -o directory
+    #[cfg(feature = "interchange")]
+    #[arg(long, value_name = "N", requires = "export")]
+    page_size: Option<usize>,
+
+    /// Remove private members from the export; fail if public elements reference them
+    #[cfg(feature = "interchange")]
+    #[arg(long, requires = "export")]
+    strip_private: bool,
+
+    /// Replace element names and documentation with stable pseudonyms
+    #[cfg(feature = "interchange")]
+    #[arg(long, requires = "export")]
+    redact: bool,
+
+    /// Mapping file used to keep pseudonyms stable and reverse --redact
+    #[cfg(feature = "interchange")]
+    #[arg(
+        long,
+        value_name = "FILE",
+        default_value = "redaction-map.json",
+        requires = "redact"
+    )]
+    redact_map: PathBuf,
+
+    /// How exported element IDs are assigned
+    #[cfg(feature = "interchange")]
+    #[arg(
+        long,
+        value_name = "POLICY",
+        default_value = "preserve",
+        requires = "export"
+    )]
+    id_policy: IdPolicyArg,
+
+    /// UUID namespace for --id-policy uuid-v5-namespace
+    #[cfg(feature = "interchange")]
+    #[arg(long, value_name = "UUID")]
+    id_namespace: Option<String>,
+
+    /// With --export xmi or kpar, embed SHA-256 checksums of the exported content
+    #[cfg(feature = "signing")]
+    #[arg(long, requires = "export")]
+    checksum: bool,
+
+    /// With --export xmi or kpar, sign the checksums with an Ed25519 key (PKCS#8 PEM)
+    #[cfg(feature = "signing")]
+    #[arg(long, value_name = "KEY", requires = "export")]
+    sign: Option<PathBuf>,
+
+    /// Analyze or export only the variants selected in this TOML file
+    #[arg(long, value_name = "FILE", conflicts_with = "export_ast")]
+    config: Option<PathBuf>,
+
+    /// Only check files staged in git, as staged (for pre-commit hooks)
+    #[arg(long, conflicts_with_all = ["export_ast", "config", "shard"])]
+    staged: bool,
+
+    /// Report definitions with the same qualified name in different files
+    #[arg(long, value_name = "SEVERITY", conflicts_with = "staged")]
+    duplicates: Option<DuplicateSeverity>,
+
+    /// Warn about elements of these kinds without a doc comment (e.g. partdef,requirementdef)
+    #[arg(
+        long,
+        value_name = "KINDS",
+        value_delimiter = ',',
+        value_parser = syster_cli::parse_doc_kind,
+        conflicts_with = "staged"
+    )]
+    require_docs: Vec<SymbolKind>,
+
+    /// Fail if less than PERCENT of the measured elements are documented
+    #[arg(long, value_name = "PERCENT", conflicts_with = "staged")]
+    min_doc_coverage: Option<f64>,
+
+    /// Bundle of checks: strict (all passes, warnings fail), standard, or permissive (unresolved names warn)
+    #[arg(
+        long,
+        value_name = "PROFILE",
+        default_value = "standard",
+        conflicts_with = "staged"
+    )]
+    profile: CheckProfile,
+
+    /// Report keywords not available in this SysML release (2024-09, 2025-04; default: syster.toml's sysml_version)
+    #[arg(long, value_name = "VERSION", conflicts_with = "staged")]
+    sysml_version: Option<SysmlVersion>,
+
+    /// Leave files with syntax errors out of the analysis instead of using the symbols recovered from them
+    #[arg(long, conflicts_with = "staged")]
+    strict_parse: bool,
+
+    /// Do not follow symbolic links when scanning directories
+    #[arg(long, conflicts_with = "staged")]
+    no_follow_symlinks: bool,
+
+    /// Only load files at most this many directory levels below the input (1: its own files)
+    #[arg(long, value_name = "N", conflicts_with = "staged")]
+    max_depth: Option<usize>,
+
+    /// Skip files larger than this, with a warning (bytes, or with a K/M/G suffix)
+    #[arg(
+        long,
+        value_name = "SIZE",
+        value_parser = syster_cli::parse_file_size,
+        conflicts_with = "staged"
+    )]
+    max_file_size: Option<u64>,
+
+    /// Fail if the input directory holds more SysML/KerML files than this
+    #[arg(long, value_name = "N", conflicts_with = "staged")]
+    max_files: Option<usize>,
+
+    /// Also skip files ignored by .gitignore files (.systerignore files are always honored)
+    #[arg(long, conflicts_with = "staged")]
+    respect_gitignore: bool,
+
+    /// Break the summary down per top-level package, file or severity
+    #[arg(long, value_name = "GROUPING")]
+    summary_by: Option<SummaryGrouping>,
+
+    /// List the N files with the most errors and warnings
+    #[arg(long, value_name = "N")]
+    top_offenders: Option<usize>,
+
+    /// Push run metrics (duration, files, diagnostics by severity) to a Prometheus Pushgateway
+    #[arg(long, value_name = "URL")]
+    push_gateway: Option<String>,
+
+    /// Export tracing spans of the load, parse, index, check and export phases to an OTLP/HTTP collector
+    #[cfg(feature = "otel")]
+    #[arg(long, value_name = "URL", global = true)]
+    otlp_endpoint: Option<String>,
+
+    /// Resolve references against a pre-built dependency index (repeatable; see `syster index build`)
+    #[arg(long = "index", value_name = "FILE", conflicts_with = "staged")]
+    indexes: Vec<PathBuf>,
+
+    /// Annotate diagnostics with the author and date of the last change to their line (git blame)
+    #[arg(long)]
+    blame: bool,
+
+    /// Language of the diagnostics and summary text (codes and --json output stay the same)
+    #[arg(long, value_name = "LANG", default_value = "en")]
+    lang: ReportLang,
+
+    /// Only report the diagnostics and packages this team owns in MODELOWNERS (repeatable)
+    #[arg(long = "owner", value_name = "TEAM", conflicts_with = "staged")]
+    owners: Vec<String>,
+
+    /// Only report the I-th of N deterministic file partitions (e.g. 2/4)
+    #[arg(long, value_name = "I/N")]
+    shard: Option<Shard>,
+
+    /// Analyze all N partitions in this process, parsing the standard library once, and write DIR/shard-I.json for each (with --json -o DIR)
+    #[arg(
+        long,
+        value_name = "N",
+        requires_all = ["json", "output"],
+        conflicts_with_all = ["shard", "staged", "per_project", "export_ast", "format", "blame", "top_offenders", "summary_by", "min_doc_coverage"]
+    )]
+    shards: Option<usize>,
+
+    /// Analyze each directory holding a syster.toml as a project of its own, reporting every project and their aggregate
+    #[arg(
+        long,
+        conflicts_with_all = ["staged", "shard", "export_ast", "format", "blame", "top_offenders", "summary_by", "min_doc_coverage"]
+    )]
+    per_project: bool,
+
+    /// Read analysis results from, and store them in, this HTTP cache (token: SYSTER_CACHE_TOKEN)
+    #[cfg(feature = "interchange")]
+    #[arg(long, value_name = "URL", conflicts_with_all = ["staged", "per_project", "shards"])]
+    cache_url: Option<String>,
+
+    /// Write an in-toto attestation binding the result to the sources, tool version and arguments to this file
+    #[cfg(feature = "interchange")]
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["staged", "per_project", "shards", "export", "export_ast"])]
+    attest: Option<PathBuf>,
+
+    /// Sign the --attest attestation with an Ed25519 key (PKCS#8 PEM), as a DSSE envelope
+    #[cfg(feature = "signing")]
+    #[arg(long, value_name = "KEY", requires = "attest")]
+    attest_key: Option<PathBuf>,
+
     /// Write output to file instead of stdout
-    #[arg(short, long, value_name = "FILE")]
+    #[arg(short, long, value_name = "FILE", global = true)]
     output: Option<PathBuf>,
+
+    /// On a panic, write the panic, a backtrace and the offending file to this zip archive
+    #[cfg(feature = "interchange")]
+    #[arg(long, value_name = "FILE", global = true)]
+    crash_report: Option<PathBuf>,
+
+    /// How much of the offending file the crash report includes
+    #[cfg(feature = "interchange")]
+    #[arg(long, value_name = "MODE", default_value = "names", global = true)]
+    crash_redact: CrashRedactArg,
 }
 
-fn main() -> ExitCode {
-    let cli = Cli::parse();
+#[derive(Subcommand)]
+enum Command {
+    /// Combine partial JSON results (e.g. from --shard runs) into one
+    MergeReports {
+        /// JSON result files written with --json
+        #[arg(value_name = "REPORT", required = true)]
+        reports: Vec<PathBuf>,
+    },
 
-    if cli.verbose {
-        println!("Analyzing: {}", cli.input.display());
-    }
+    /// Compare two saved JSON results: new, fixed and persisting diagnostics (fails on new ones)
+    DiagDiff {
+        /// JSON result of the earlier run
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
 
-    // Handle decompile (convert XMI to SysML text)
-    #[cfg(feature = "interchange")]
-    if cli.decompile {
-        match decompile_model(&cli.input, None, cli.verbose) {
-            Ok(result) => {
-                println!(
-                    "✓ Decompiled {} elements from {}",
-                    result.element_count, result.source_path
-                );
+        /// JSON result of the later run
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
 
-                // Write SysML file
-                let sysml_path = cli
-                    .output
-                    .clone()
-                    .unwrap_or_else(|| cli.input.with_extension("sysml"));
-                if let Err(e) = std::fs::write(&sysml_path, &result.sysml_text) {
-                    eprintln!("error: failed to write {}: {}", sysml_path.display(), e);
-                    return ExitCode::FAILURE;
-                }
-                println!("  Wrote: {}", sysml_path.display());
+        /// Lines a diagnostic may move between the runs and still match
+        #[arg(long, value_name = "N", default_value_t = syster_cli::diagdiff::DEFAULT_LINE_FUZZ)]
+        line_fuzz: u32,
+    },
+
+    /// Write a shields.io endpoint badge (errors, warnings or doc coverage) for a README
+    Badge {
+        /// Metric to show
+        #[arg(long, value_name = "METRIC")]
+        metric: BadgeMetric,
+
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Badge label (default: the metric's name)
+        #[arg(long, value_name = "TEXT")]
+        label: Option<String>,
+    },
+
+    /// List everything the selected packages transitively depend on
+    Closure {
+        /// Qualified names of the selected packages
+        #[arg(value_name = "QUALIFIED::PACKAGE", required = true)]
+        packages: Vec<String>,
+
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Write a minimal self-contained export of the closure instead
+        #[cfg(feature = "interchange")]
+        #[arg(long, value_name = "FORMAT")]
+        export: Option<InterchangeFormat>,
+    },
+
+    /// Find definitions of identical or near-identical structure, candidates for consolidation
+    Clones {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Only compare definitions with at least this many members
+        #[arg(long, value_name = "N", default_value_t = 2)]
+        min_members: usize,
+
+        /// Report definitions sharing at least this fraction of their features as near clones
+        #[arg(long, value_name = "FRACTION", default_value_t = 0.8)]
+        similarity: f64,
+    },
+
+    /// Compute package coupling metrics and flag cycles, god packages and layer violations
+    ArchCheck {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Flag packages with more fan-in plus fan-out than this (default: from syster.toml, or 10)
+        #[arg(long, value_name = "N")]
+        max_coupling: Option<usize>,
+    },
+
+    /// List allocations and check them for dangling ends, abstract targets and cardinality
+    Allocations {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Allocations each logical function needs, e.g. 1 or 1..* (default: from syster.toml, or 1)
+        #[arg(long, value_name = "MULTIPLICITY")]
+        cardinality: Option<String>,
+    },
+
+    /// Check port directions across connections and flows, and trace each signal end to end
+    Flows {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+    },
+
+    /// List the elements and packages a change affects through typings, specializations and connections
+    Impact {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Qualified name of a changed element (repeatable)
+        #[arg(long, value_name = "QNAME", required_unless_present = "from_diff")]
+        changed: Vec<String>,
+
+        /// Start from every element that differs between two versions of the sources
+        #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with = "changed")]
+        from_diff: Vec<PathBuf>,
+
+        /// Stop this many steps from a change
+        #[arg(long, value_name = "N")]
+        max_depth: Option<usize>,
+    },
+
+    /// Export the graph of calculations and constraints and the attributes they read and write (DOT, or --json)
+    Parametrics {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+    },
+
+    /// Extract a reproducible random sample of the models, closed over its dependencies
+    Sample {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Number of elements to sample
+        #[arg(long, value_name = "N", default_value_t = 500)]
+        elements: usize,
+
+        /// Seed of the random drawing; the same seed gives the same sample
+        #[arg(long, value_name = "SEED", default_value_t = 0)]
+        seed: u64,
+    },
+
+    /// Cut the models down to a minimal reproducer of a diagnostic
+    Minimize {
+        /// Source file or directory
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+
+        /// Code of the diagnostic to keep, e.g. E0001
+        #[arg(long, value_name = "CODE")]
+        preserve_error: String,
+
+        /// Only keep a diagnostic whose message contains this text
+        #[arg(long, value_name = "TEXT")]
+        message: Option<String>,
+    },
+
+    /// Report every alias and re-export chain with the elements they finally resolve to
+    ResolveAliases {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Only report aliases and re-exports declared in this namespace
+        #[arg(long, value_name = "QUALIFIED::NAME")]
+        scope: Option<String>,
+
+        /// List each re-exported member with its final target
+        #[arg(long)]
+        members: bool,
+
+        /// Instead, list where this name is declared, aliased or re-exported, and what it denotes
+        #[arg(long, value_name = "NAME", conflicts_with_all = ["scope", "members"])]
+        find: Option<String>,
+    },
+
+    /// Analyze every tagged git revision and report model size and diagnostics over time
+    History {
+        /// Model directory inside a git work tree
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// First tag to analyze (default: all tags)
+        #[arg(long, value_name = "TAG")]
+        since: Option<String>,
+
+        /// Metrics to report, comma-separated (default: all)
+        #[arg(long, value_name = "METRIC", value_delimiter = ',')]
+        metric: Vec<Metric>,
+    },
+
+    /// Record model metrics in a database and chart their trends
+    #[cfg(feature = "trends")]
+    Metrics {
+        #[command(subcommand)]
+        command: MetricsCommand,
+    },
+
+    /// Check declared names against the conventions configured in syster.toml
+    Lint {
+        /// SysML/KerML file or directory to lint
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Apply the proposed renames to the declarations and their references
+        #[arg(long)]
+        fix: bool,
+    },
+
+    /// Install a git pre-commit hook that runs `syster <dir> --staged`
+    InstallHooks {
+        /// Model directory inside the repository
+        #[arg(value_name = "DIR", default_value = ".")]
+        dir: PathBuf,
+
+        /// Replace an existing pre-commit hook
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Download the dependencies declared in syster.toml into .syster/deps
+    Fetch {
+        /// Model directory (syster.toml is looked up from here)
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Download again even if already cached
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// List qualified names with their export element IDs and locations, as CSV
+    #[cfg(feature = "interchange")]
+    Ids {
+        /// Source file or directory
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Describe the element at a position (signature, docs, supertypes, type) as Markdown
+    Hover {
+        /// Position to describe
+        #[arg(value_name = "FILE:LINE:COL")]
+        position: SourcePosition,
+
+        /// Model file or directory to analyze the file with (default: its directory)
+        #[arg(long, value_name = "PATH")]
+        src: Option<PathBuf>,
+    },
+
+    /// Build symbol indexes for --index or for code navigation (SCIP)
+    Index {
+        #[command(subcommand)]
+        command: IndexCommand,
+    },
+
+    /// Save an analyzed model to a single file, or reopen one
+    Workspace {
+        #[command(subcommand)]
+        command: WorkspaceCommand,
+    },
+
+    /// Classify the tokens of a file for semantic highlighting
+    Tokens {
+        /// File to classify
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Model file or directory to analyze the file with (default: its directory)
+        #[arg(long, value_name = "PATH")]
+        src: Option<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_name = "FORMAT", default_value = "text")]
+        format: OutputFormat,
+    },
+
+    /// Work with the variation points of a product-line model
+    Variants {
+        #[command(subcommand)]
+        command: VariantsCommand,
+    },
+
+    /// Render Tera templates against the model (e.g. C headers, ICD tables)
+    #[cfg(feature = "codegen")]
+    Generate {
+        /// Template file (repeatable); `name.ext.tera` renders to `name.ext`
+        #[arg(long, value_name = "FILE", required = true)]
+        template: Vec<PathBuf>,
+
+        /// Directory to write the generated files to
+        #[arg(long, value_name = "DIR")]
+        out: PathBuf,
+
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+    },
+
+    /// Write an interface control document for all port and interface defs
+    #[cfg(feature = "codegen")]
+    Icd {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Document format
+        #[arg(long, value_name = "FORMAT", default_value = "markdown")]
+        format: DocFormat,
+    },
+
+    /// List every named definition with its documentation, grouped by package
+    #[cfg(feature = "codegen")]
+    Glossary {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Only include definitions annotated with this metadata
+        #[arg(long, value_name = "NAME")]
+        metadata: Option<String>,
+
+        /// Document format
+        #[arg(long, value_name = "FORMAT", default_value = "markdown")]
+        format: DocFormat,
+    },
+
+    /// Write a verification plan of the verification cases, the requirements
+    /// they verify and their subjects
+    #[cfg(feature = "codegen")]
+    VerificationReport {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Document format
+        #[arg(long, value_name = "FORMAT", default_value = "markdown")]
+        format: DocFormat,
+    },
+
+    /// Write a test stub for each verification case, with one skipped test
+    /// per verified requirement
+    #[cfg(feature = "codegen")]
+    GenerateTests {
+        /// Test framework to write stubs for
+        #[arg(long, value_name = "FRAMEWORK")]
+        framework: Framework,
+
+        /// Directory to write the stubs to
+        #[arg(long, value_name = "DIR")]
+        out: PathBuf,
+
+        /// Overwrite stubs that already exist
+        #[arg(long)]
+        force: bool,
+
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+    },
+
+    /// Standard library maintenance
+    Stdlib {
+        #[command(subcommand)]
+        command: StdlibCommand,
+    },
+
+    /// Safety-analysis worksheets derived from the model
+    #[cfg(all(feature = "codegen", feature = "interchange"))]
+    Safety {
+        #[command(subcommand)]
+        command: SafetyCommand,
+    },
+
+    /// Check that an interchange file matches the textual sources
+    #[cfg(feature = "interchange")]
+    Verify {
+        /// Source file or directory
+        #[arg(value_name = "SRC")]
+        src: PathBuf,
+
+        /// Interchange file the sources must match (xmi, kpar, jsonld, json, yaml)
+        #[arg(long, value_name = "FILE")]
+        against: PathBuf,
+    },
+
+    /// Classify the changes between two releases and check the version bump
+    #[cfg(feature = "interchange")]
+    SemverCheck {
+        /// Previous release (kpar, xmi, jsonld)
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// New release (kpar, xmi, jsonld)
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+
+        /// Version of the previous release (default: from its KPAR manifest)
+        #[arg(long, value_name = "VERSION")]
+        old_version: Option<String>,
+
+        /// Declared version of the new release (default: from its KPAR manifest)
+        #[arg(long, value_name = "VERSION")]
+        new_version: Option<String>,
+    },
+
+    /// Check a standard JSON payload against the SysML v2 JSON schemas before converting it
+    #[cfg(feature = "interchange")]
+    ValidateInterchange {
+        /// Standard JSON file to check
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Directory of the published JSON schema files
+        #[arg(long, value_name = "DIR")]
+        schema_bundle: PathBuf,
+    },
+
+    /// Upgrade an XMI, JSON-LD or KPAR export of an older syster to the current schema
+    #[cfg(feature = "interchange")]
+    Migrate {
+        /// Export to migrate
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Store model snapshots under .syster/baselines and diff against them
+    #[cfg(feature = "interchange")]
+    Baseline {
+        #[command(subcommand)]
+        command: BaselineCommand,
+    },
+
+    /// Check the checksums and signature of an exported KPAR or XMI file
+    #[cfg(feature = "signing")]
+    VerifySignature {
+        /// Sealed KPAR or XMI file
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Public key (PEM) the signature must be made with
+        #[arg(long, value_name = "KEY")]
+        key: Option<PathBuf>,
+    },
+
+    /// Browse packages, symbols and diagnostics in an interactive terminal UI
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Source file or directory
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+    },
+
+    /// Inspect or unpack KPAR archives
+    #[cfg(feature = "interchange")]
+    Kpar {
+        #[command(subcommand)]
+        command: KparCommand,
+    },
+
+    /// Commit the exported model to a SysML v2 API repository (token: SYSML_API_TOKEN)
+    #[cfg(feature = "interchange")]
+    Push {
+        /// Base URL of the repository's API
+        #[arg(long, value_name = "URL")]
+        repo: String,
+
+        /// Project name or ID
+        #[arg(long, value_name = "PROJECT")]
+        project: String,
+
+        /// Branch name or ID
+        #[arg(long, value_name = "BRANCH", default_value = "main")]
+        branch: String,
+
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+    },
+
+    /// Analyze a commit of a SysML v2 API repository, or decompile it to SysML text (token: SYSML_API_TOKEN)
+    #[cfg(feature = "interchange")]
+    Pull {
+        /// Base URL of the repository's API
+        #[arg(long, value_name = "URL")]
+        repo: String,
+
+        /// Project name or ID
+        #[arg(long, value_name = "PROJECT")]
+        project: String,
+
+        /// Commit ID
+        #[arg(long, value_name = "ID")]
+        commit: String,
+
+        /// Write SysML text (and ID metadata next to --output) instead of analyzing
+        #[arg(long)]
+        decompile: bool,
+    },
+
+    /// Track diagnostics in an issue tracker
+    #[cfg(feature = "jira")]
+    Publish {
+        #[command(subcommand)]
+        command: PublishCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum VariantsCommand {
+    /// List variation points and their variants as a selection matrix for --config
+    List {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Matrix format
+        #[arg(long, value_name = "FORMAT", default_value = "text")]
+        format: SelectionFormat,
+    },
+}
+
+#[cfg(feature = "trends")]
+#[derive(Subcommand)]
+enum MetricsCommand {
+    /// Analyze a model and append its metrics, timestamp and git revision to the database
+    Record {
+        /// SQLite database of recorded runs (created if missing)
+        #[arg(long, value_name = "FILE")]
+        db: PathBuf,
+
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+    },
+    /// Render the recorded runs as HTML trend charts
+    Chart {
+        /// SQLite database of recorded runs
+        #[arg(long, value_name = "FILE")]
+        db: PathBuf,
+
+        /// HTML file to write (default: --output or stdout)
+        #[arg(long, value_name = "FILE")]
+        out: Option<PathBuf>,
+
+        /// Metrics to chart, comma-separated (default: all)
+        #[arg(long, value_name = "METRIC", value_delimiter = ',')]
+        metric: Vec<Metric>,
+    },
+}
+
+#[derive(Subcommand)]
+enum IndexCommand {
+    /// Write an index of a model's declarations (use with -o)
+    Build {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Index format
+        #[arg(long, value_name = "FORMAT", default_value = "sidx")]
+        format: IndexFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkspaceCommand {
+    /// Analyze a model and save its files and diagnostics (use with -o)
+    Save {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+    },
+    /// Report the diagnostics of a saved workspace without re-analyzing it
+    Load {
+        /// Workspace written by `workspace save`
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Browse the workspace in the terminal UI instead
+        #[cfg(feature = "tui")]
+        #[arg(long)]
+        tui: bool,
+    },
+}
+
+#[cfg(feature = "interchange")]
+#[derive(Subcommand)]
+enum BaselineCommand {
+    /// Snapshot the current model as an XMI export with its hash
+    Create {
+        /// Baseline name (e.g. R1)
+        #[arg(long, value_name = "TAG")]
+        tag: String,
+
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Replace an existing baseline with the same tag
+        #[arg(long)]
+        force: bool,
+    },
+    /// Report the changes between a baseline and the current model
+    Compare {
+        /// Baseline name
+        #[arg(value_name = "TAG")]
+        tag: String,
+
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+    },
+}
+
+#[cfg(all(feature = "codegen", feature = "interchange"))]
+#[derive(Subcommand)]
+enum SafetyCommand {
+    /// FMEA skeleton for the elements annotated with failure-mode metadata
+    Fmea {
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// Metadata marking the elements to analyze
+        #[arg(long, value_name = "NAME", default_value = "FailureMode")]
+        metadata: String,
+
+        /// Table format
+        #[arg(long, value_name = "FORMAT", default_value = "csv")]
+        format: TableFormat,
+    },
+}
+
+#[derive(Subcommand)]
+enum StdlibCommand {
+    /// Analyze the standard library by itself, separating genuine issues from resolver false positives and known issues
+    Check {
+        /// Library directory (default: --stdlib-path, or sysml.library)
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+
+        /// Project file listing known issues in [[stdlib.known_issues]] (default: the nearest syster.toml)
+        #[arg(long, value_name = "FILE")]
+        known_issues: Option<PathBuf>,
+    },
+}
+
+#[cfg(feature = "jira")]
+#[derive(Subcommand)]
+enum PublishCommand {
+    /// Create or update a Jira issue per error (JIRA_USER, JIRA_API_TOKEN)
+    Jira {
+        /// Project key (default: project in [publish.jira] of syster.toml)
+        #[arg(long, value_name = "KEY")]
+        project: Option<String>,
+
+        /// Jira site (default: url in [publish.jira] of syster.toml)
+        #[arg(long, value_name = "URL")]
+        url: Option<String>,
+
+        /// Source file or directory
+        #[arg(long, value_name = "PATH", default_value = ".")]
+        src: PathBuf,
+
+        /// List the issues without contacting Jira
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[cfg(feature = "interchange")]
+#[derive(Subcommand)]
+enum KparCommand {
+    /// List entries, manifest, element counts and validation status
+    Inspect {
+        /// KPAR archive to inspect
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Unpack a KPAR archive into a directory
+    Extract {
+        /// KPAR archive to extract
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Destination directory (default: archive name without extension)
+        #[arg(value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+
+    /// Build a KPAR from a source directory (sources, exported XMI, manifest)
+    Pack {
+        /// Directory of SysML/KerML sources
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// Package name recorded in the manifest (default: directory name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Package version recorded in the manifest
+        #[arg(long)]
+        version: Option<String>,
+
+        /// Package description recorded in the manifest
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Package dependency as NAME or NAME@VERSION (repeatable)
+        #[arg(
+            long = "dependency",
+            value_name = "NAME[@VERSION]",
+            value_parser = syster_cli::kpar::parse_dependency
+        )]
+        dependencies: Vec<syster_cli::PackDependency>,
+    },
+}
+
+fn main() -> ExitCode {
+    let mut cli = Cli::parse();
+
+    let level = cli.log_level.unwrap_or(if cli.verbose {
+        LogLevelArg::Info
+    } else {
+        LogLevelArg::Warn
+    });
+    // Progress messages are only produced when verbose
+    cli.verbose = matches!(
+        level,
+        LogLevelArg::Info | LogLevelArg::Debug | LogLevelArg::Trace
+    );
+    let log_options = syster_cli::LogOptions {
+        level: level.into(),
+        format: cli.log_format.into(),
+        #[cfg(feature = "otel")]
+        otlp_endpoint: cli.otlp_endpoint.clone(),
+    };
+    let logging = match syster_cli::init_logging(&log_options) {
+        Ok(logging) => logging,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    #[cfg(feature = "interchange")]
+    let code = match cli.crash_report.clone() {
+        Some(path) => run_reporting_crashes(cli, &path),
+        None => run(cli),
+    };
+    #[cfg(not(feature = "interchange"))]
+    let code = run(cli);
+
+    if let Err(e) = logging.shutdown() {
+        eprintln!("warning: {}", e);
+    }
+    code
+}
+
+/// Run `cli`, writing a crash report to `path` if it panics.
+#[cfg(feature = "interchange")]
+fn run_reporting_crashes(cli: Cli, path: &std::path::Path) -> ExitCode {
+    let input = cli.input.clone();
+    let redaction = cli.crash_redact.into();
+    syster_cli::install_panic_hook();
+    if let Ok(code) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| run(cli))) {
+        return code;
+    }
+
+    let args: Vec<String> = std::env::args().collect();
+    let report = syster_cli::crash_report(input.as_deref(), &args, redaction);
+    match syster_cli::write_crash_report(&report, path) {
+        Ok(()) => eprintln!(
+            "error: syster crashed ({}); crash report written to {}",
+            report.message,
+            path.display()
+        ),
+        Err(e) => eprintln!(
+            "error: syster crashed ({}), and the crash report could not be written: {}",
+            report.message, e
+        ),
+    }
+    ExitCode::FAILURE
+}
+
+/// Run the subcommand or analysis `cli` asks for.
+fn run(mut cli: Cli) -> ExitCode {
+    if cli.machine {
+        if let Err(e) = machine_mode(&mut cli) {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+    if let Some(command) = &cli.command {
+        return run_command(command, &cli);
+    }
+
+    // `input` is required whenever no subcommand is given
+    let input = cli.input.clone().expect("input is required");
+
+    tracing::info!("Analyzing: {}", input.display());
+
+    // Handle decompile (convert XMI to SysML text)
+    #[cfg(feature = "interchange")]
+    if cli.decompile && cli.split_by_package {
+        use std::collections::HashSet;
+        use syster_cli::decompile_model_split;
+
+        let out_dir = cli
+            .output
+            .clone()
+            .unwrap_or_else(|| input.with_extension(""));
+        match decompile_model_split(&input, None, cli.verbose, cli.sort.into()) {
+            Ok(files) => {
+                let element_count: usize = files.iter().map(|f| f.element_count).sum();
+                println!(
+                    "✓ Decompiled {} elements from {} into {} files",
+                    element_count,
+                    input.display(),
+                    files.len()
+                );
+
+                let mut written = HashSet::new();
+                for file in &files {
+                    let sysml_path = out_dir.join(&file.path);
+                    let metadata_path = out_dir.join(file.metadata_path());
+                    if !written.insert(sysml_path.to_string_lossy().to_lowercase()) {
+                        eprintln!(
+                            "error: two packages map to {}; not overwriting it",
+                            sysml_path.display()
+                        );
+                        return ExitCode::FAILURE;
+                    }
+                    let parent = sysml_path.parent().unwrap_or(&out_dir);
+                    if let Err(e) = std::fs::create_dir_all(parent) {
+                        eprintln!("error: failed to create {}: {}", parent.display(), e);
+                        return ExitCode::FAILURE;
+                    }
+                    for (path, content) in [
+                        (&sysml_path, &file.sysml_text),
+                        (&metadata_path, &file.metadata_json),
+                    ] {
+                        if let Err(e) = std::fs::write(path, content) {
+                            eprintln!("error: failed to write {}: {}", path.display(), e);
+                            return ExitCode::FAILURE;
+                        }
+                        println!("  Wrote: {}", path.display());
+                    }
+                }
+
+                return ExitCode::SUCCESS;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    #[cfg(feature = "interchange")]
+    if cli.decompile {
+        match decompile_model_ordered(&input, None, cli.verbose, cli.sort.into()) {
+            Ok(result) => {
+                println!(
+                    "✓ Decompiled {} elements from {}",
+                    result.element_count, result.source_path
+                );
+
+                // Write SysML file
+                let sysml_path = cli
+                    .output
+                    .clone()
+                    .unwrap_or_else(|| input.with_extension("sysml"));
+                if let Err(e) = std::fs::write(&sysml_path, &result.sysml_text) {
+                    eprintln!("error: failed to write {}: {}", sysml_path.display(), e);
+                    return ExitCode::FAILURE;
+                }
+                println!("  Wrote: {}", sysml_path.display());
+
+                // Write metadata file
+                let metadata_path = sysml_path.with_extension("metadata.json");
+                if let Err(e) = std::fs::write(&metadata_path, &result.metadata_json) {
+                    eprintln!("error: failed to write {}: {}", metadata_path.display(), e);
+                    return ExitCode::FAILURE;
+                }
+                println!("  Wrote: {}", metadata_path.display());
+
+                return ExitCode::SUCCESS;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    // Handle interchange import (validate only)
+    #[cfg(feature = "interchange")]
+    if cli.import {
+        match import_model_with_options(&input, None, &import_options(&cli)) {
+            Ok(result) => {
+                println!(
+                    "✓ Imported {} elements, {} relationships",
+                    result.element_count, result.relationship_count
+                );
+                report_skipped(&result);
+                report_references(&result, false);
+                if result.error_count > 0 {
+                    eprintln!("  {} validation issues:", result.error_count);
+                    let validation = result.messages.len() - result.skipped.len();
+                    for msg in &result.messages[..validation] {
+                        eprintln!("    {}", msg);
+                    }
+                    return ExitCode::FAILURE;
+                }
+                return ExitCode::SUCCESS;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    // Handle import into workspace (for analysis) + optional export
+    #[cfg(feature = "interchange")]
+    if cli.import_workspace {
+        use syster::ide::AnalysisHost;
+        use syster::project::StdLibLoader;
+        use syster_cli::{export_from_host, export_from_host_to};
+
+        let mut host = AnalysisHost::new();
+
+        // Load stdlib if enabled
+        if !cli.no_stdlib {
+            let mut loader = StdLibLoader::new();
+            if let Err(e) = loader.ensure_loaded_into_host(&mut host) {
+                tracing::warn!("failed to load stdlib: {}", e);
+            }
+        }
+
+        // Import the XMI/KPAR model into workspace, then the files merged into it
+        let options = import_options(&cli);
+        let imported =
+            std::iter::once(&input)
+                .chain(&cli.merge)
+                .try_fold(0, |element_count, path| {
+                    let result =
+                        import_model_into_host_with_options(&mut host, path, None, &options)?;
+                    report_skipped(&result);
+                    report_id_resolutions(&result);
+                    report_references(&result, true);
+                    Ok::<_, String>(element_count + result.element_count)
+                });
+        match imported {
+            Ok(element_count) => {
+                // If --export is also specified, export from the imported workspace
+                if let Some(format) = &cli.export {
+                    // Use stderr for status when exporting (stdout is for data)
+                    eprintln!(
+                        "✓ Imported {} elements ({} symbols) into workspace",
+                        element_count, element_count
+                    );
+
+                    let analysis = host.analysis();
+                    let all_symbols: Vec<_> = analysis.symbol_index().all_symbols().collect();
+                    eprintln!("  Total symbols in workspace: {}", all_symbols.len());
+                    eprintln!("  Element IDs preserved: ✓");
+
+                    let format_str = match format {
+                        InterchangeFormat::Xmi => "xmi",
+                        InterchangeFormat::Kpar => "kpar",
+                        InterchangeFormat::JsonLd => "jsonld",
+                        InterchangeFormat::Json => "json",
+                        InterchangeFormat::Yaml => "yaml",
+                        InterchangeFormat::PlantumlSequence => {
+                            eprintln!(
+                                "error: plantuml-sequence is rendered from sources, not imported models"
+                            );
+                            return ExitCode::FAILURE;
+                        }
+                    };
+
+                    let exported = if streams_export(*format, &cli) {
+                        stream_export(cli.output.as_ref(), |out| {
+                            export_from_host_to(
+                                &mut host,
+                                format_str,
+                                cli.verbose,
+                                cli.self_contained,
+                                out,
+                            )
+                        })
+                    } else {
+                        export_from_host(&mut host, format_str, cli.verbose, cli.self_contained)
+                            .and_then(|bytes| finish_export(bytes, *format, format_str, &cli))
+                    };
+                    match exported {
+                        Ok(()) => return ExitCode::SUCCESS,
+                        Err(e) => {
+                            eprintln!("error: {}", e);
+                            return ExitCode::FAILURE;
+                        }
+                    }
+                }
+
+                // No export - use stdout for status
+                println!(
+                    "✓ Imported {} elements ({} symbols) into workspace",
+                    element_count, element_count
+                );
+
+                // Run analysis on imported symbols
+                let analysis = host.analysis();
+                let all_symbols: Vec<_> = analysis.symbol_index().all_symbols().collect();
+
+                println!("  Total symbols in workspace: {}", all_symbols.len());
+                println!("  Element IDs preserved: ✓");
+
+                return ExitCode::SUCCESS;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    // Load the variant configuration, if any
+    let config = match cli.config.as_deref().map(VariantConfig::load).transpose() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let indexes = match cli
+        .indexes
+        .iter()
+        .map(|path| ModelIndex::read(path))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(indexes) => indexes,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let libraries = match Manifest::discover(&input)
+        .and_then(|manifest| manifest.map_or(Ok(Vec::new()), |m| m.library_paths()))
+    {
+        Ok(libraries) => libraries,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let sysml_version = match cli.sysml_version {
+        Some(version) => Some(version),
+        None => match SysmlVersion::discover(&input) {
+            Ok(version) => version,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+
+    let layers = match LayerRules::discover(&input) {
+        Ok(layers) => layers,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let requirements = match RequirementRules::discover(&input) {
+        Ok(requirements) => requirements,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let budgets = match Budgets::discover(&input) {
+        Ok(budgets) => budgets,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let owners = match ModelOwners::discover(&input) {
+        Ok(None) if !cli.owners.is_empty() => {
+            eprintln!("error: --owner needs a MODELOWNERS file at or above the models");
+            return ExitCode::FAILURE;
+        }
+        Ok(owners) => owners,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Handle interchange export
+    #[cfg(feature = "interchange")]
+    if let Some(format) = &cli.export {
+        let format_str = match format {
+            InterchangeFormat::Xmi => "xmi",
+            InterchangeFormat::Kpar => "kpar",
+            InterchangeFormat::JsonLd => "jsonld",
+            InterchangeFormat::Json => "json",
+            InterchangeFormat::Yaml => "yaml",
+            InterchangeFormat::PlantumlSequence => return run_sequence_export(&input, &cli),
+        };
+
+        let filter = ExportFilter {
+            packages: cli.packages.clone(),
+            strip_private: cli.strip_private,
+            with_metadata: cli.with_metadata.clone(),
+            redaction_map: cli.redact.then(|| cli.redact_map.clone()),
+            config: config.clone(),
+            id_policy: cli.id_policy.into(),
+            id_namespace: cli.id_namespace.clone(),
+            root: cli.root.clone(),
+        };
+        let options = input_options(&cli);
+        if cli.per_project {
+            return run_per_project_export(&input, *format, format_str, &options, &filter, &cli);
+        }
+        let exported = if streams_export(*format, &cli) {
+            stream_export(cli.output.as_ref(), |out| {
+                export_model_to(
+                    &input,
+                    format_str,
+                    &options,
+                    cli.self_contained,
+                    &filter,
+                    out,
+                )
+            })
+        } else {
+            export_model_with_options(&input, format_str, &options, cli.self_contained, &filter)
+                .and_then(|(bytes, stats)| {
+                    finish_export(bytes, *format, format_str, &cli)?;
+                    Ok(stats)
+                })
+        };
+
+        match exported {
+            Ok(stats) => {
+                if cli.redact {
+                    eprintln!(
+                        "Redaction map: {} (keep it private)",
+                        cli.redact_map.display()
+                    );
+                }
+
+                // With --json, report ID restoration stats (on stderr if
+                // stdout already carries the exported model)
+                if cli.json {
+                    match serde_json::to_string_pretty(&stats) {
+                        Ok(json) if cli.output.is_some() => println!("{}", json),
+                        Ok(json) => eprintln!("{}", json),
+                        Err(e) => {
+                            eprintln!("error: Failed to serialize stats: {}", e);
+                            return ExitCode::FAILURE;
+                        }
+                    }
+                } else if cli.verbose {
+                    tracing::info!(
+                        "IDs: {} restored, {} generated",
+                        stats.restored(),
+                        stats.generated()
+                    );
+                }
+                return ExitCode::SUCCESS;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    // Handle AST export
+    if cli.export_ast {
+        match export_ast_with_options(&input, &input_options(&cli), &cli.with_metadata) {
+            Ok(ast_output) => {
+                write_output(&ast_output, cli.output.as_ref());
+                return ExitCode::SUCCESS;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let options = AnalysisOptions {
+        shard: cli.shard,
+        config: config.as_ref(),
+        duplicates: cli.duplicates.map(Severity::from),
+        require_docs: cli.require_docs.clone(),
+        indexes: &indexes,
+        libraries,
+        profile: cli.profile.into(),
+        sysml_version,
+        layers,
+        owners,
+        requirements,
+        budgets,
+        only_owners: cli.owners.clone(),
+        ..input_options(&cli)
+    };
+    if cli.per_project {
+        // Each project follows the SysML release of its own syster.toml
+        let options = AnalysisOptions {
+            sysml_version: cli.sysml_version,
+            ..options
+        };
+        return run_per_project(&input, &options, &cli);
+    }
+    if let Some(count) = cli.shards {
+        return run_all_shards(&input, &options, count, &cli);
+    }
+
+    let started = std::time::Instant::now();
+    let result = if cli.staged {
+        run_analysis_staged(&input, &options)
+    } else {
+        analyze_through_cache(&input, &options, &cli)
+    };
+    match result {
+        Ok(mut result) => {
+            if cli.blame {
+                annotate_blame(&mut result.diagnostics);
+            }
+            if let Some(n) = cli.top_offenders {
+                result.top_offenders = syster_cli::top_offenders(&result, n);
+            }
+            #[cfg(feature = "interchange")]
+            if let Some(path) = &cli.attest {
+                if let Err(e) = write_attestation(path, &input, &options, &result, &cli) {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+            if let Some(gateway) = &cli.push_gateway {
+                let metrics = syster_cli::prometheus_metrics(&result, started.elapsed());
+                if let Err(e) = syster_cli::push_metrics(gateway, &metrics) {
+                    tracing::warn!("{}", e);
+                }
+            }
+            let coverage = &result.doc_coverage;
+            let below_min_coverage = cli
+                .min_doc_coverage
+                .is_some_and(|min| coverage.percent < min);
+
+            // Handle JSON export
+            if cli.json {
+                match export_json(&result) {
+                    Ok(json) => {
+                        write_output(&json, cli.output.as_ref());
+                        return if result.error_count == 0 && !below_min_coverage {
+                            ExitCode::SUCCESS
+                        } else {
+                            ExitCode::FAILURE
+                        };
+                    }
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+
+            // Handle code-review formats
+            if let Some(format) = cli.format {
+                let report = match format {
+                    ReviewFormat::Rdjson => export_rdjson(&result),
+                    ReviewFormat::Gerrit => export_gerrit(&result),
+                    #[cfg(feature = "codegen")]
+                    ReviewFormat::Html => syster_cli::export_html(&result),
+                    ReviewFormat::NotebookJson => syster_cli::export_notebook(&result),
+                };
+                match report {
+                    Ok(report) => {
+                        write_output(&report, cli.output.as_ref());
+                        return if result.error_count == 0 && !below_min_coverage {
+                            ExitCode::SUCCESS
+                        } else {
+                            ExitCode::FAILURE
+                        };
+                    }
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+
+            // Print diagnostics (normal mode)
+            let catalog = match Catalog::new(cli.lang.into()) {
+                Ok(catalog) => catalog,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            for diag in &result.diagnostics {
+                print_localized_diagnostic(diag, &catalog);
+            }
+
+            if !cli.require_docs.is_empty() || cli.min_doc_coverage.is_some() {
+                println!(
+                    "  {}",
+                    catalog.text(
+                        "doc-coverage",
+                        &[
+                            ("documented", coverage.documented.to_string()),
+                            ("total", coverage.total.to_string()),
+                            ("percent", format!("{:.1}", coverage.percent)),
+                        ]
+                    )
+                );
+            }
+            if let Some(min) = cli.min_doc_coverage.filter(|_| below_min_coverage) {
+                eprintln!(
+                    "✗ Documentation coverage {:.1}% is below the minimum of {}%",
+                    coverage.percent, min
+                );
+                return ExitCode::FAILURE;
+            }
+
+            let broken = result.syntax_errors;
+            if broken.files > 0 {
+                let files = ("files", broken.files.to_string());
+                if cli.strict_parse {
+                    println!("  {}", catalog.text("syntax-errors-excluded", &[files]));
+                } else {
+                    println!(
+                        "  {}",
+                        catalog.text(
+                            "syntax-errors-recovered",
+                            &[files, ("symbols", broken.symbols.to_string())]
+                        )
+                    );
+                }
+            }
+            if !result.stdlib_diagnostics.is_empty() {
+                println!(
+                    "  {}",
+                    catalog.text(
+                        "stdlib-diagnostics-hidden",
+                        &[("count", result.stdlib_diagnostics.len().to_string())]
+                    )
+                );
+            }
+
+            if let Some(grouping) = cli.summary_by {
+                print!(
+                    "{}",
+                    syster_cli::render_summary_in(&result, grouping.into(), &catalog)
+                );
+            }
+            if let Some(n) = cli.top_offenders {
+                println!("Top {} files:", n);
+                for file in &result.top_offenders {
+                    println!(
+                        "  {}: {} errors, {} warnings",
+                        file.name, file.errors, file.warnings
+                    );
+                }
+            }
+
+            // Print summary
+            let counts = [
+                ("files", result.file_count.to_string()),
+                ("symbols", result.symbol_count.to_string()),
+                ("errors", result.error_count.to_string()),
+                ("warnings", result.warning_count.to_string()),
+            ];
+            if result.error_count == 0 {
+                println!("{}", catalog.text("analysis-ok", &counts));
+                ExitCode::SUCCESS
+            } else {
+                eprintln!("{}", catalog.text("analysis-failed", &counts));
+                ExitCode::FAILURE
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Analyze `input`, through the remote cache if `--cache-url` is set.
+fn analyze_through_cache(
+    input: &std::path::Path,
+    options: &AnalysisOptions,
+    cli: &Cli,
+) -> Result<syster_cli::AnalysisResult, String> {
+    #[cfg(feature = "interchange")]
+    if let Some(url) = &cli.cache_url {
+        let cache = syster_cli::RemoteCache::new(url, std::env::var("SYSTER_CACHE_TOKEN").ok());
+        return cache.analyze(input, options);
+    }
+    #[cfg(not(feature = "interchange"))]
+    let _ = cli;
+    analyze(input, options)
+}
+
+/// Write the `--attest` attestation of `result` to `path`, signed with
+/// `--attest-key` if given.
+///
+/// The subject is the result as `--json` writes it, named after the `-o`
+/// file when there is one.
+#[cfg(feature = "interchange")]
+fn write_attestation(
+    path: &std::path::Path,
+    input: &std::path::Path,
+    options: &AnalysisOptions,
+    result: &syster_cli::AnalysisResult,
+    cli: &Cli,
+) -> Result<(), String> {
+    let report = export_json(result)?;
+    let name = cli
+        .output
+        .as_ref()
+        .filter(|_| cli.json)
+        .and_then(|output| output.file_name())
+        .map_or_else(
+            || "analysis.json".to_string(),
+            |name| name.to_string_lossy().to_string(),
+        );
+    let arguments = std::env::args().skip(1).collect();
+    let attestation =
+        syster_cli::Attestation::collect(input, options, arguments, &name, report.as_bytes())?;
+    #[cfg(feature = "signing")]
+    let document = match &cli.attest_key {
+        Some(key) => attestation.sign(&syster_cli::integrity::load_signing_key(key)?),
+        None => attestation.statement(),
+    };
+    #[cfg(not(feature = "signing"))]
+    let document = attestation.statement();
+    let json = serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("Failed to serialize attestation: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Analyze every shard against one parsed standard library and write
+/// `shard-I.json` for each into the `-o` directory.
+fn run_all_shards(
+    input: &std::path::Path,
+    options: &AnalysisOptions,
+    count: usize,
+    cli: &Cli,
+) -> ExitCode {
+    let results = match analyze_shards(input, options, count) {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(out_dir) = cli.output.as_ref() else {
+        eprintln!("error: --shards needs an output directory (-o DIR)");
+        return ExitCode::FAILURE;
+    };
+    if let Err(e) = std::fs::create_dir_all(out_dir) {
+        eprintln!("error: failed to create {}: {}", out_dir.display(), e);
+        return ExitCode::FAILURE;
+    }
+    for (i, result) in results.iter().enumerate() {
+        let path = out_dir.join(format!("shard-{}.json", i + 1));
+        let json = match export_json(result) {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        if let Err(e) = std::fs::write(&path, json) {
+            eprintln!("error: failed to write {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+        if cli.verbose {
+            println!("  Wrote: {}", path.display());
+        }
+    }
+    if results.iter().all(|r| r.error_count == 0) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Analyze every project under `input` on its own (`--per-project`).
+///
+/// With `--json`, `-o` names a directory receiving each project's result as
+/// `<project>/analysis.json` and the aggregate as `aggregate.json`.
+fn run_per_project(input: &std::path::Path, options: &AnalysisOptions, cli: &Cli) -> ExitCode {
+    let report = match analyze_projects(input, options) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let status = if report.aggregate.error_count == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    };
+
+    if cli.json {
+        let Some(out_dir) = cli.output.as_ref() else {
+            match serde_json::to_string_pretty(&report) {
+                Ok(json) => write_output(&json, None),
+                Err(e) => {
+                    eprintln!("error: Failed to serialize results: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+            return status;
+        };
+        let mut results: Vec<(PathBuf, &syster_cli::AnalysisResult)> = report
+            .projects
+            .iter()
+            .map(|project| {
+                let dir = match project.name.as_str() {
+                    "." => out_dir.clone(),
+                    name => out_dir.join(name),
+                };
+                (dir.join("analysis.json"), &project.result)
+            })
+            .collect();
+        results.push((out_dir.join("aggregate.json"), &report.aggregate));
+        for (path, result) in results {
+            let json = match export_json(result) {
+                Ok(json) => json,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let parent = path.parent().unwrap_or(out_dir);
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                eprintln!("error: failed to create {}: {}", parent.display(), e);
+                return ExitCode::FAILURE;
+            }
+            if let Err(e) = std::fs::write(&path, json) {
+                eprintln!("error: failed to write {}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+            if cli.verbose {
+                println!("  Wrote: {}", path.display());
+            }
+        }
+        return status;
+    }
+
+    let catalog = match Catalog::new(cli.lang.into()) {
+        Ok(catalog) => catalog,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    for project in &report.projects {
+        let result = &project.result;
+        for diag in &result.diagnostics {
+            print_localized_diagnostic(diag, &catalog);
+        }
+        println!(
+            "  {}",
+            catalog.text(
+                "project-summary",
+                &[
+                    ("project", project.name.clone()),
+                    ("files", result.file_count.to_string()),
+                    ("errors", result.error_count.to_string()),
+                    ("warnings", result.warning_count.to_string()),
+                ]
+            )
+        );
+    }
+
+    let result = &report.aggregate;
+    let counts = [
+        ("files", result.file_count.to_string()),
+        ("symbols", result.symbol_count.to_string()),
+        ("errors", result.error_count.to_string()),
+        ("warnings", result.warning_count.to_string()),
+    ];
+    if result.error_count == 0 {
+        println!("{}", catalog.text("analysis-ok", &counts));
+    } else {
+        eprintln!("{}", catalog.text("analysis-failed", &counts));
+    }
+    status
+}
+
+/// Check that `--machine` applies and default its output to `--json`.
+///
+/// Only plain analysis guarantees that stdout is a single document; the
+/// subcommands and the interchange modes report progress there.
+fn machine_mode(cli: &mut Cli) -> Result<(), String> {
+    #[cfg(feature = "interchange")]
+    let interchange_mode = cli.decompile || cli.import || cli.import_workspace;
+    #[cfg(not(feature = "interchange"))]
+    let interchange_mode = false;
+    if cli.command.is_some() || interchange_mode {
+        return Err("--machine only applies to analysis".to_string());
+    }
+    if cli.format.is_none() {
+        cli.json = true;
+    }
+    Ok(())
+}
+
+/// Dispatch a subcommand.
+fn run_command(command: &Command, cli: &Cli) -> ExitCode {
+    match command {
+        Command::MergeReports { reports } => run_merge_reports(reports, cli),
+        Command::Badge { metric, src, label } => run_badge(*metric, src, label.as_deref(), cli),
+        Command::DiagDiff {
+            old,
+            new,
+            line_fuzz,
+        } => run_diag_diff(old, new, *line_fuzz, cli),
+        #[cfg(feature = "interchange")]
+        Command::Closure {
+            packages,
+            src,
+            export: Some(format),
+        } => run_closure_export(packages, src, *format, cli),
+        Command::Closure { packages, src, .. } => run_closure(packages, src, cli),
+        Command::Clones {
+            src,
+            min_members,
+            similarity,
+        } => run_clones(src, *min_members, *similarity, cli),
+        Command::ArchCheck { src, max_coupling } => run_arch_check(src, *max_coupling, cli),
+        Command::Allocations { src, cardinality } => {
+            run_allocations(src, cardinality.as_deref(), cli)
+        }
+        Command::Flows { src } => run_flows(src, cli),
+        Command::Impact {
+            src,
+            changed,
+            from_diff,
+            max_depth,
+        } => run_impact(src, changed, from_diff, *max_depth, cli),
+        Command::Parametrics { src } => run_parametrics(src, cli),
+        Command::Sample {
+            src,
+            elements,
+            seed,
+        } => run_sample(src, *elements, *seed, cli),
+        Command::Minimize {
+            path,
+            preserve_error,
+            message,
+        } => run_minimize(path, preserve_error, message.as_deref(), cli),
+        Command::ResolveAliases {
+            src,
+            find: Some(name),
+            ..
+        } => run_find_symbol(src, name, cli),
+        Command::ResolveAliases {
+            src,
+            scope,
+            members,
+            find: None,
+        } => run_resolve_aliases(src, scope.as_deref(), *members, cli),
+        Command::History { src, since, metric } => run_history(src, since.as_deref(), metric, cli),
+        #[cfg(feature = "trends")]
+        Command::Metrics { command } => run_metrics(command, cli),
+        Command::Fetch { src, force } => run_fetch(src, *force, cli),
+        #[cfg(feature = "interchange")]
+        Command::Ids { path } => run_ids(path, cli),
+        Command::Hover { position, src } => run_hover(position, src.as_deref(), cli),
+        Command::Index { command } => run_index(command, cli),
+        Command::Workspace { command } => run_workspace(command, cli),
+        Command::Tokens { file, src, format } => run_tokens(file, src.as_deref(), *format, cli),
+        Command::Variants { command } => run_variants(command, cli),
+        Command::Lint { src, fix } => run_lint(src, *fix, cli),
+        Command::InstallHooks { dir, force } => run_install_hooks(dir, *force),
+        #[cfg(feature = "codegen")]
+        Command::Generate { template, out, src } => run_generate(template, out, src, cli),
+        #[cfg(feature = "codegen")]
+        Command::Icd { src, format } => run_icd(src, *format, cli),
+        #[cfg(feature = "codegen")]
+        Command::Glossary {
+            src,
+            metadata,
+            format,
+        } => run_glossary(src, metadata.as_deref(), *format, cli),
+        #[cfg(feature = "codegen")]
+        Command::VerificationReport { src, format } => run_verification_report(src, *format, cli),
+        #[cfg(feature = "codegen")]
+        Command::GenerateTests {
+            framework,
+            out,
+            force,
+            src,
+        } => run_generate_tests(*framework, out, *force, src, cli),
+        Command::Stdlib { command } => run_stdlib(command, cli),
+        #[cfg(all(feature = "codegen", feature = "interchange"))]
+        Command::Safety { command } => run_safety(command, cli),
+        #[cfg(feature = "interchange")]
+        Command::Verify { src, against } => run_verify(src, against, cli),
+        #[cfg(feature = "interchange")]
+        Command::Migrate { file } => run_migrate(file, cli),
+        #[cfg(feature = "interchange")]
+        Command::ValidateInterchange {
+            file,
+            schema_bundle,
+        } => run_validate_interchange(file, schema_bundle, cli),
+        #[cfg(feature = "interchange")]
+        Command::SemverCheck {
+            old,
+            new,
+            old_version,
+            new_version,
+        } => run_semver_check(
+            old,
+            new,
+            old_version.as_deref(),
+            new_version.as_deref(),
+            cli,
+        ),
+        #[cfg(feature = "interchange")]
+        Command::Baseline { command } => run_baseline(command, cli),
+        #[cfg(feature = "signing")]
+        Command::VerifySignature { file, key } => run_verify_signature(file, key.as_deref(), cli),
+        #[cfg(feature = "tui")]
+        Command::Tui { path } => run_tui(path, cli),
+        #[cfg(feature = "interchange")]
+        Command::Kpar { command } => run_kpar(command, cli),
+        #[cfg(feature = "interchange")]
+        Command::Push {
+            repo,
+            project,
+            branch,
+            src,
+        } => run_push(repo, project, branch, src, cli),
+        #[cfg(feature = "interchange")]
+        Command::Pull {
+            repo,
+            project,
+            commit,
+            decompile,
+        } => run_pull(repo, project, commit, *decompile, cli),
+        #[cfg(feature = "jira")]
+        Command::Publish { command } => run_publish(command, cli),
+    }
+}
+
+/// Write the model health time series as CSV, or JSON with `--json`.
+fn run_history(
+    src: &std::path::Path,
+    since: Option<&str>,
+    metrics: &[Metric],
+    cli: &Cli,
+) -> ExitCode {
+    use syster_cli::HistoryMetric;
+
+    let metrics: Vec<HistoryMetric> = if metrics.is_empty() {
+        HistoryMetric::ALL.to_vec()
+    } else {
+        metrics.iter().map(|&m| m.into()).collect()
+    };
+    let series = syster_cli::analyze_history(src, since, &stdlib_options(cli)).and_then(|points| {
+        if cli.json {
+            syster_cli::history_json(&points, &metrics)
+        } else {
+            Ok(syster_cli::history_csv(&points, &metrics))
+        }
+    });
+    match series {
+        Ok(series) => {
+            write_output(&series, cli.output.as_ref());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Record a run's metrics in the database, or chart the recorded runs.
+#[cfg(feature = "trends")]
+fn run_metrics(command: &MetricsCommand, cli: &Cli) -> ExitCode {
+    use syster_cli::{HistoryMetric, MetricsDb, MetricsRun};
+
+    let done = match command {
+        MetricsCommand::Record { db, src } => MetricsDb::open(db).and_then(|database| {
+            let started = std::time::Instant::now();
+            let result = analyze(src, &stdlib_options(cli))?;
+            let run = MetricsRun::new(&result, started.elapsed(), syster_cli::git_revision(src));
+            database.record(&run)?;
+            println!(
+                "✓ Recorded {} files, {} errors, {} warnings at {} into {}",
+                run.file_count,
+                run.error_count,
+                run.warning_count,
+                run.revision.as_deref().unwrap_or("no revision"),
+                db.display()
+            );
+            Ok(())
+        }),
+        MetricsCommand::Chart { db, out, metric } => {
+            let metrics: Vec<HistoryMetric> = if metric.is_empty() {
+                HistoryMetric::ALL.to_vec()
+            } else {
+                metric.iter().map(|&m| m.into()).collect()
+            };
+            MetricsDb::open(db).and_then(|db| db.runs()).map(|runs| {
+                let html = syster_cli::trend_chart(&runs, &metrics);
+                write_output(&html, out.as_ref().or(cli.output.as_ref()));
+            })
+        }
+    };
+    match done {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Lint names, optionally applying the proposed renames first.
+fn run_lint(src: &std::path::Path, fix: bool, cli: &Cli) -> ExitCode {
+    let lint =
+        |config: &syster_cli::NamingConfig| syster_cli::lint(src, &stdlib_options(cli), config);
+    let result = syster_cli::NamingConfig::discover(src).and_then(|config| {
+        let report = lint(&config)?;
+        if !fix || report.renames.is_empty() {
+            return Ok((report, Vec::new()));
+        }
+        syster_cli::apply_renames(&report.renames)?;
+        Ok((lint(&config)?, report.renames))
+    });
+    let (report, applied) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize lint report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        for rename in &applied {
+            println!("Renamed {} to {}", rename.qualified_name, rename.to);
+        }
+        for diag in &report.diagnostics {
+            print_diagnostic(diag);
+        }
+        if report.diagnostics.is_empty() {
+            println!("✓ Linted {} files: no naming issues", report.file_count);
+        } else {
+            eprintln!(
+                "✗ Linted {} files: {} naming issues ({} fixable with --fix)",
+                report.file_count,
+                report.diagnostics.len(),
+                report.renames.len()
+            );
+        }
+    }
+
+    if report.diagnostics.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Install the pre-commit hook.
+fn run_install_hooks(dir: &std::path::Path, force: bool) -> ExitCode {
+    match syster_cli::install_pre_commit_hook(dir, force) {
+        Ok(hook) => {
+            println!("✓ Installed pre-commit hook: {}", hook.display());
+            println!("  Staged SysML/KerML files are checked before each commit;");
+            println!("  bypass once with `git commit --no-verify`.");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Download the project's dependencies.
+fn run_fetch(src: &std::path::Path, force: bool, cli: &Cli) -> ExitCode {
+    let manifest = match Manifest::discover(src) {
+        Ok(Some(manifest)) => manifest,
+        Ok(None) => {
+            eprintln!(
+                "error: No {} found in {} or its parents",
+                syster_cli::project::PROJECT_FILE,
+                src.display()
+            );
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match syster_cli::fetch(&manifest, force, cli.verbose) {
+        Ok(statuses) => {
+            for (name, status) in &statuses {
+                let label = match status {
+                    FetchStatus::Fetched => "Fetched",
+                    FetchStatus::Cached => "Cached",
+                    FetchStatus::Local => "Local",
+                };
+                println!("  {} {}", label, name);
+            }
+            println!("✓ {} dependencies ready", statuses.len());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Write the element ID map as CSV, or JSON with `--json`.
+#[cfg(feature = "interchange")]
+fn run_ids(path: &std::path::Path, cli: &Cli) -> ExitCode {
+    let map = syster_cli::element_ids(path, &stdlib_options(cli)).and_then(|entries| {
+        if cli.json {
+            serde_json::to_string_pretty(&entries)
+                .map_err(|e| format!("Failed to serialize element IDs: {}", e))
+        } else {
+            Ok(syster_cli::element_ids_csv(&entries))
+        }
+    });
+    match map {
+        Ok(map) => {
+            write_output(&map, cli.output.as_ref());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Print hover information for a position, as Markdown or with `--json`.
+fn run_hover(position: &SourcePosition, src: Option<&std::path::Path>, cli: &Cli) -> ExitCode {
+    let info = match syster_cli::hover(src, position, &stdlib_options(cli)) {
+        Ok(info) => info,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&info) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize hover: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        match &info {
+            Some(info) => write_output(info.contents.trim_end(), cli.output.as_ref()),
+            None => eprintln!(
+                "No element at {}:{}:{}",
+                position.file.display(),
+                position.line,
+                position.col
+            ),
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+/// Print the semantic tokens of a file, one per line or as JSON.
+fn run_tokens(
+    file: &std::path::Path,
+    src: Option<&std::path::Path>,
+    format: OutputFormat,
+    cli: &Cli,
+) -> ExitCode {
+    let tokens = match syster_cli::semantic_tokens(src, file, &stdlib_options(cli)) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json || matches!(format, OutputFormat::Json) {
+        match serde_json::to_string_pretty(&tokens) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize tokens: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let lines: Vec<String> = tokens
+            .iter()
+            .map(|token| {
+                let range = format!(
+                    "{}:{}-{}:{}",
+                    token.line, token.col, token.end_line, token.end_col
+                );
+                match &token.target {
+                    Some(target) => format!("{} {} {}", range, token.kind, target),
+                    None => format!("{} {}", range, token.kind),
+                }
+            })
+            .collect();
+        write_output(&lines.join("\n"), cli.output.as_ref());
+    }
+    ExitCode::SUCCESS
+}
+
+/// Build a symbol index.
+fn run_index(command: &IndexCommand, cli: &Cli) -> ExitCode {
+    let IndexCommand::Build { src, format } = command;
+    let options = input_options(cli);
+    let built = match format {
+        IndexFormat::Sidx => syster_cli::build_index(src, &options)
+            .map(|index| (index.to_bytes(), format!("{} symbols", index.symbols.len()))),
+        IndexFormat::Scip => syster_cli::build_scip_index(src, &options)
+            .map(|bytes| (bytes, src.display().to_string())),
+        IndexFormat::Ctags | IndexFormat::Etags => {
+            let format = match format {
+                IndexFormat::Etags => TagsFormat::Etags,
+                _ => TagsFormat::Ctags,
+            };
+            syster_cli::build_tags(src, format, &options)
+                .map(|tags| (tags.into_bytes(), src.display().to_string()))
+        }
+    };
+    match built {
+        Ok((bytes, summary)) => {
+            write_bytes_output(&bytes, cli.output.as_ref());
+            if let Some(path) = &cli.output {
+                println!("✓ Indexed {} into {}", summary, path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Save a workspace, or report (or browse) a saved one.
+fn run_workspace(command: &WorkspaceCommand, cli: &Cli) -> ExitCode {
+    match command {
+        WorkspaceCommand::Save { src } => {
+            let saved = Workspace::analyze(src, &input_options(cli))
+                .and_then(|workspace| Ok((workspace.to_bytes()?, workspace)));
+            match saved {
+                Ok((bytes, workspace)) => {
+                    write_bytes_output(&bytes, cli.output.as_ref());
+                    if let Some(path) = &cli.output {
+                        println!(
+                            "✓ Saved {} files, {} diagnostics into {}",
+                            workspace.files.len(),
+                            workspace.result.diagnostics.len(),
+                            path.display()
+                        );
+                    }
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        WorkspaceCommand::Load {
+            file,
+            #[cfg(feature = "tui")]
+            tui,
+        } => {
+            let workspace = match Workspace::read(file) {
+                Ok(workspace) => workspace,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+            #[cfg(feature = "tui")]
+            if *tui {
+                return match syster_cli::tui::run_workspace(&workspace) {
+                    Ok(()) => ExitCode::SUCCESS,
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        ExitCode::FAILURE
+                    }
+                };
+            }
+
+            let result = &workspace.result;
+            if cli.json {
+                match export_json(result) {
+                    Ok(json) => write_output(&json, cli.output.as_ref()),
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            } else {
+                for diag in &result.diagnostics {
+                    print_diagnostic(diag);
+                }
+                if result.error_count == 0 {
+                    println!(
+                        "✓ Loaded {} files: {} symbols, {} warnings",
+                        result.file_count, result.symbol_count, result.warning_count
+                    );
+                } else {
+                    eprintln!(
+                        "✗ Loaded {} files: {} errors, {} warnings",
+                        result.file_count, result.error_count, result.warning_count
+                    );
+                }
+            }
+            if result.error_count == 0 {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+/// Write the variant selection matrix.
+fn run_variants(command: &VariantsCommand, cli: &Cli) -> ExitCode {
+    let VariantsCommand::List { src, format } = command;
+    let matrix = syster_cli::list_variation_points(src, &stdlib_options(cli))
+        .and_then(|points| syster_cli::render_variant_matrix(&points, (*format).into()));
+    match matrix {
+        Ok(matrix) => {
+            write_output(&matrix, cli.output.as_ref());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Render templates into the output directory.
+#[cfg(feature = "codegen")]
+fn run_generate(
+    templates: &[PathBuf],
+    out: &std::path::Path,
+    src: &std::path::Path,
+    cli: &Cli,
+) -> ExitCode {
+    match syster_cli::generate(src, templates, out, &stdlib_options(cli)) {
+        Ok(files) => {
+            for file in files {
+                println!("Generated {}", file.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Write the interface control document.
+#[cfg(feature = "codegen")]
+fn run_icd(src: &std::path::Path, format: DocFormat, cli: &Cli) -> ExitCode {
+    match syster_cli::generate_icd(src, format.into(), &stdlib_options(cli)) {
+        Ok(document) => {
+            write_output(&document, cli.output.as_ref());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Write the glossary.
+#[cfg(feature = "codegen")]
+fn run_glossary(
+    src: &std::path::Path,
+    metadata: Option<&str>,
+    format: DocFormat,
+    cli: &Cli,
+) -> ExitCode {
+    match syster_cli::generate_glossary(src, metadata, format.into(), &stdlib_options(cli)) {
+        Ok(document) => {
+            write_output(&document, cli.output.as_ref());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Write the verification plan, reporting incomplete cases on stderr.
+#[cfg(feature = "codegen")]
+fn run_verification_report(src: &std::path::Path, format: DocFormat, cli: &Cli) -> ExitCode {
+    let plan = match syster_cli::verification_plan(src, &stdlib_options(cli)) {
+        Ok(plan) => plan,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    match syster_cli::render_verification_plan(&plan, format.into()) {
+        Ok(document) => write_output(&document, cli.output.as_ref()),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    for case in plan.incomplete() {
+        let missing = match (case.requirements.is_empty(), case.subjects.is_empty()) {
+            (true, true) => "no requirement and no subject",
+            (true, false) => "no requirement",
+            _ => "no subject",
+        };
+        eprintln!(
+            "warning: {} is incomplete: {}",
+            case.qualified_name, missing
+        );
+    }
+    let requirements: std::collections::BTreeSet<&str> = plan
+        .cases
+        .iter()
+        .flat_map(|c| c.requirements.iter().map(|r| r.qualified_name.as_str()))
+        .collect();
+    eprintln!(
+        "✓ {} verification cases verifying {} requirements ({} incomplete)",
+        plan.cases.len(),
+        requirements.len(),
+        plan.incomplete().count()
+    );
+    ExitCode::SUCCESS
+}
+
+/// Write test stubs for the verification cases.
+#[cfg(feature = "codegen")]
+fn run_generate_tests(
+    framework: Framework,
+    out: &std::path::Path,
+    force: bool,
+    src: &std::path::Path,
+    cli: &Cli,
+) -> ExitCode {
+    match syster_cli::generate_tests(src, framework.into(), out, force, &stdlib_options(cli)) {
+        Ok(scaffold) => {
+            for file in &scaffold.written {
+                println!("Generated {}", file.display());
+            }
+            for file in &scaffold.skipped {
+                println!("Kept {} (use --force to overwrite)", file.display());
+            }
+            eprintln!(
+                "✓ {} test stubs written, {} kept",
+                scaffold.written.len(),
+                scaffold.skipped.len()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Check the standard library by itself.
+fn run_stdlib(command: &StdlibCommand, cli: &Cli) -> ExitCode {
+    let StdlibCommand::Check { path, known_issues } = command;
+    let known = match known_issues {
+        Some(file) => KnownIssues::load(file),
+        None => KnownIssues::discover(std::path::Path::new(".")),
+    };
+    let library = path.as_deref().or(cli.stdlib_path.as_deref());
+    let check = match known.and_then(|known| check_stdlib(library, &known, cli.verbose)) {
+        Ok(check) => check,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&check) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize stdlib check: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        for diag in &check.issues {
+            print_diagnostic(diag);
+        }
+        if cli.verbose {
+            for diag in &check.false_positives {
+                print_diagnostic_as(diag, "false positive", &diag.message);
+            }
+        }
+    }
+    for issue in &check.stale {
+        let fields: Vec<String> = [
+            ("code", &issue.code),
+            ("file", &issue.file),
+            ("message", &issue.message),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_ref().map(|v| format!("{} = {:?}", key, v)))
+        .collect();
+        eprintln!(
+            "warning: known issue matches no diagnostic: {}",
+            fields.join(", ")
+        );
+    }
+
+    let summary = format!(
+        "{}: {} files, {} issues ({} resolver false positives, {} known)",
+        check.library,
+        check.files,
+        check.issues.len(),
+        check.false_positives.len(),
+        check.known.len()
+    );
+    if check.error_count() > 0 {
+        eprintln!("✗ {}", summary);
+        ExitCode::FAILURE
+    } else {
+        eprintln!("✓ {}", summary);
+        ExitCode::SUCCESS
+    }
+}
+
+/// Write a safety-analysis worksheet.
+#[cfg(all(feature = "codegen", feature = "interchange"))]
+fn run_safety(command: &SafetyCommand, cli: &Cli) -> ExitCode {
+    let SafetyCommand::Fmea {
+        src,
+        metadata,
+        format,
+    } = command;
+    let table = match syster_cli::fmea(src, metadata, &stdlib_options(cli)) {
+        Ok(table) => table,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if table.rows.is_empty() {
+        tracing::warn!("no elements annotated with {}", metadata);
+    }
+
+    match format {
+        TableFormat::Csv => write_output(&table.to_csv(), cli.output.as_ref()),
+        TableFormat::Xlsx => match table.to_xlsx() {
+            Ok(bytes) => write_bytes_output(&bytes, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+    }
+    ExitCode::SUCCESS
+}
+
+/// Open the interactive browser.
+#[cfg(feature = "tui")]
+fn run_tui(path: &std::path::Path, cli: &Cli) -> ExitCode {
+    match syster_cli::tui::run(path, &stdlib_options(cli)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Import options from the command line.
+#[cfg(feature = "interchange")]
+fn import_options(cli: &Cli) -> ImportOptions {
+    ImportOptions {
+        verbose: cli.verbose,
+        continue_on_error: cli.continue_on_error,
+        on_id_conflict: cli.on_id_conflict.into(),
+        resolve_refs: cli.resolve_refs.clone(),
+    }
+}
+
+/// Summarize what --continue-on-error left out of an import, on stderr.
+#[cfg(feature = "interchange")]
+fn report_skipped(result: &ImportResult) {
+    if result.skipped.is_empty() {
+        return;
+    }
+    let nested: usize = result.skipped.iter().map(|s| s.nested).sum();
+    eprintln!(
+        "warning: skipped {} unreadable elements and {} elements nested in them; the import is incomplete",
+        result.skipped.len(),
+        nested
+    );
+    for skipped in &result.skipped {
+        eprintln!("    {}", skipped);
+    }
+}
+
+/// List the element ID conflicts --on-id-conflict resolved, on stderr.
+#[cfg(feature = "interchange")]
+fn report_id_resolutions(result: &ImportResult) {
+    if result.id_resolutions.is_empty() {
+        return;
+    }
+    eprintln!(
+        "note: resolved {} element ID conflicts",
+        result.id_resolutions.len()
+    );
+    for resolution in &result.id_resolutions {
+        eprintln!("    {}", resolution);
+    }
+}
+
+/// Summarize the references to elements outside an imported file, on
+/// stderr, listing the unresolved ones if `list_unresolved`.
+#[cfg(feature = "interchange")]
+fn report_references(result: &ImportResult, list_unresolved: bool) {
+    if result.references.is_empty() {
+        return;
+    }
+    let count = |status: ReferenceStatus| {
+        result
+            .references
+            .iter()
+            .filter(|r| r.status == status)
+            .count()
+    };
+    let unresolved = count(ReferenceStatus::Unresolved);
+    eprintln!(
+        "  {} references to elements outside the file: {} resolved, {} external, {} unresolved",
+        result.references.len(),
+        count(ReferenceStatus::Resolved),
+        count(ReferenceStatus::External),
+        unresolved
+    );
+    if list_unresolved && unresolved > 0 {
+        for reference in &result.references {
+            if reference.status == ReferenceStatus::Unresolved {
+                eprintln!("    {}", reference);
+            }
+        }
+    }
+}
+
+/// Whether an export is written to the output as it is serialized: XMI
+/// that is neither sealed nor compressed, which both need the whole file
+/// (nor paged, which only JSON-LD can be).
+#[cfg(feature = "interchange")]
+fn streams_export(format: InterchangeFormat, cli: &Cli) -> bool {
+    #[cfg(feature = "signing")]
+    if cli.checksum || cli.sign.is_some() {
+        return false;
+    }
+    matches!(format, InterchangeFormat::Xmi)
+        && matches!(cli.compress, CompressionArg::None)
+        && cli.page_size.is_none()
+}
+
+/// Run `export` against the output file (or stdout), removing a partly
+/// written file if it fails.
+#[cfg(feature = "interchange")]
+fn stream_export<T>(
+    output_path: Option<&PathBuf>,
+    export: impl FnOnce(&mut dyn std::io::Write) -> Result<T, String>,
+) -> Result<T, String> {
+    use std::io::BufWriter;
+
+    let Some(path) = output_path else {
+        return export(&mut BufWriter::new(std::io::stdout().lock()));
+    };
+    let file = std::fs::File::create(path)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let result = export(&mut BufWriter::new(file));
+    if result.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+    result
+}
+
+/// Seal and compress exported bytes as the flags say, then write them out
+/// (or page them with --page-size).
+#[cfg(feature = "interchange")]
+fn finish_export(
+    bytes: Vec<u8>,
+    format: InterchangeFormat,
+    format_str: &str,
+    cli: &Cli,
+) -> Result<(), String> {
+    if let Some(page_size) = cli.page_size {
+        return write_pages(&bytes, format, page_size, cli);
+    }
+    let bytes = seal_and_compress(bytes, format, format_str, cli)?;
+    write_bytes_output(&bytes, cli.output.as_ref());
+    Ok(())
+}
+
+/// Seal and compress exported bytes as the flags say.
+#[cfg(feature = "interchange")]
+fn seal_and_compress(
+    bytes: Vec<u8>,
+    format: InterchangeFormat,
+    format_str: &str,
+    cli: &Cli,
+) -> Result<Vec<u8>, String> {
+    #[cfg(feature = "signing")]
+    let bytes = seal_export(bytes, format, cli)?;
+    #[cfg(not(feature = "signing"))]
+    let _ = format;
+    compress_export(bytes, format_str, cli.compress.into())
+}
+
+/// Export every project under `input` on its own (`--per-project
+/// --export`) into the -o directory, as `<project>/model.<format>`, and the
+/// whole input as `aggregate.<format>`.
+#[cfg(feature = "interchange")]
+fn run_per_project_export(
+    input: &std::path::Path,
+    format: InterchangeFormat,
+    format_str: &str,
+    options: &AnalysisOptions,
+    filter: &ExportFilter,
+    cli: &Cli,
+) -> ExitCode {
+    let Some(out_dir) = cli.output.as_ref() else {
+        eprintln!("error: --per-project --export needs -o DIR to write the projects' models into");
+        return ExitCode::FAILURE;
+    };
+    if cli.page_size.is_some() {
+        eprintln!("error: --page-size cannot be combined with --per-project");
+        return ExitCode::FAILURE;
+    }
+    let exports =
+        match syster_cli::export_projects(input, format_str, options, cli.self_contained, filter) {
+            Ok(exports) => exports,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+
+    let file_name = |stem: &str| match cli.compress {
+        CompressionArg::Gzip => format!("{}.{}.gz", stem, format_str),
+        CompressionArg::Zstd if format_str != "kpar" => format!("{}.{}.zst", stem, format_str),
+        _ => format!("{}.{}", stem, format_str),
+    };
+    let count = exports.projects.len();
+    let mut files: Vec<(PathBuf, Vec<u8>)> = exports
+        .projects
+        .into_iter()
+        .map(|project| {
+            let dir = match project.name.as_str() {
+                "." => out_dir.clone(),
+                name => out_dir.join(name),
+            };
+            (dir.join(file_name("model")), project.bytes)
+        })
+        .collect();
+    files.push((out_dir.join(file_name("aggregate")), exports.aggregate));
+    for (path, bytes) in files {
+        let bytes = match seal_and_compress(bytes, format, format_str, cli) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        };
+        let parent = path.parent().unwrap_or(out_dir);
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            eprintln!("error: failed to create {}: {}", parent.display(), e);
+            return ExitCode::FAILURE;
+        }
+        if let Err(e) = std::fs::write(&path, bytes) {
+            eprintln!("error: failed to write {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+        if cli.verbose {
+            println!("  Wrote: {}", path.display());
+        }
+    }
+    println!(
+        "✓ Exported {} project(s) and their aggregate into {}",
+        count,
+        out_dir.display()
+    );
+    ExitCode::SUCCESS
+}
+
+/// Write a JSON-LD export as pages into the --output directory.
+#[cfg(feature = "interchange")]
+fn write_pages(
+    bytes: &[u8],
+    format: InterchangeFormat,
+    page_size: usize,
+    cli: &Cli,
+) -> Result<(), String> {
+    if !matches!(format, InterchangeFormat::JsonLd) {
+        return Err("--page-size is only supported for jsonld exports".to_string());
+    }
+    if !matches!(cli.compress, CompressionArg::None) {
+        return Err("--page-size cannot be combined with --compress".to_string());
+    }
+    #[cfg(feature = "signing")]
+    if cli.checksum || cli.sign.is_some() {
+        return Err(
+            "--checksum and --sign are only supported for xmi and kpar exports".to_string(),
+        );
+    }
+    let dir = cli
+        .output
+        .as_ref()
+        .ok_or("--page-size needs -o DIR to write the pages into")?;
+    let index = syster_cli::write_jsonld_pages(bytes, page_size, dir)?;
+    eprintln!(
+        "✓ Wrote {} objects in {} pages to {}",
+        index.item_count,
+        index.pages.len(),
+        dir.display()
+    );
+    Ok(())
+}
+
+/// Apply `--checksum` / `--sign` to exported bytes.
+#[cfg(feature = "signing")]
+fn seal_export(bytes: Vec<u8>, format: InterchangeFormat, cli: &Cli) -> Result<Vec<u8>, String> {
+    use syster_cli::integrity::load_signing_key;
+    use syster_cli::{seal_kpar, seal_xmi};
+
+    if !cli.checksum && cli.sign.is_none() {
+        return Ok(bytes);
+    }
+    let key = cli.sign.as_deref().map(load_signing_key).transpose()?;
+
+    match format {
+        InterchangeFormat::Kpar => seal_kpar(&bytes, key.as_ref()),
+        InterchangeFormat::Xmi => Ok(seal_xmi(&bytes, key.as_ref())),
+        InterchangeFormat::JsonLd
+        | InterchangeFormat::Json
+        | InterchangeFormat::Yaml
+        | InterchangeFormat::PlantumlSequence => {
+            Err("--checksum and --sign are only supported for xmi and kpar exports".to_string())
+        }
+    }
+}
+
+/// Check the checksums and signature of a sealed export.
+#[cfg(feature = "signing")]
+fn run_verify_signature(
+    file: &std::path::Path,
+    key: Option<&std::path::Path>,
+    cli: &Cli,
+) -> ExitCode {
+    use syster_cli::{SignatureStatus, verify_integrity};
+
+    let report = match verify_integrity(file, key) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let mut text = String::new();
+        for problem in &report.problems {
+            text.push_str(&format!("  - {}\n", problem));
+        }
+        match &report.signature {
+            SignatureStatus::Absent => text.push_str("  signature: none\n"),
+            SignatureStatus::Valid {
+                public_key,
+                trusted,
+            } => {
+                text.push_str(&format!("  signature: valid (key {})\n", public_key));
+                if !trusted {
+                    eprintln!(
+                        "warning: signature checked against the key embedded in the file; \
+                         pass --key to check who signed it"
+                    );
+                }
+            }
+            SignatureStatus::Invalid { reason } => {
+                text.push_str(&format!("  signature: invalid ({})\n", reason));
+            }
+        }
+        if report.is_valid() {
+            text.push_str(&format!(
+                "✓ {}: {} checksums verified",
+                file.display(),
+                report.verified_entries
+            ));
+        } else {
+            text.push_str(&format!("✗ {}: integrity check failed", file.display()));
+        }
+        write_output(&text, cli.output.as_ref());
+    }
+
+    if report.is_valid() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// List the dependency closure of a package selection.
+fn run_closure(packages: &[String], src: &std::path::Path, cli: &Cli) -> ExitCode {
+    use syster_cli::dependency_closure;
+
+    let report = match dependency_closure(src, packages, &stdlib_options(cli)) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize closure: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut text = format!(
+        "Closure of {}: {} elements in {} packages ({} from stdlib)\n\nPackages:\n",
+        report.selection.join(", "),
+        report.elements.len(),
+        report.packages.len(),
+        report.library_count()
+    );
+    for package in &report.packages {
+        text.push_str(&format!("  {}\n", package));
+    }
+    text.push_str("\nElements:\n");
+    for element in &report.elements {
+        text.push_str(&format!(
+            "  {:<20} {}{}\n",
+            element.kind,
+            element.qualified_name,
+            if element.library { "  (stdlib)" } else { "" }
+        ));
+    }
+    write_output(text.trim_end(), cli.output.as_ref());
+    ExitCode::SUCCESS
+}
+
+/// Write a random sample of the models to the `-o` directory.
+fn run_sample(src: &std::path::Path, elements: usize, seed: u64, cli: &Cli) -> ExitCode {
+    let Some(out_dir) = cli.output.as_ref() else {
+        eprintln!("error: sample needs an output directory (-o DIR)");
+        return ExitCode::FAILURE;
+    };
+    let sample = match syster_cli::sample(src, elements, seed, &stdlib_options(cli)) {
+        Ok(sample) => sample,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for file in &sample.files {
+        let path = out_dir.join(&file.path);
+        let parent = path.parent().unwrap_or(out_dir);
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            eprintln!("error: failed to create {}: {}", parent.display(), e);
+            return ExitCode::FAILURE;
+        }
+        if let Err(e) = std::fs::write(&path, &file.text) {
+            eprintln!("error: failed to write {}: {}", path.display(), e);
+            return ExitCode::FAILURE;
+        }
+        if cli.verbose {
+            println!("  Wrote: {}", path.display());
+        }
+    }
+    println!(
+        "✓ Sampled {} elements ({} drawn, seed {}) into {} files in {}",
+        sample.elements.len(),
+        sample.drawn.len(),
+        sample.seed,
+        sample.files.len(),
+        out_dir.display()
+    );
+    ExitCode::SUCCESS
+}
+
+/// Minimize the models to a reproducer, written to the `-o` directory or
+/// printed file by file.
+fn run_minimize(path: &std::path::Path, code: &str, message: Option<&str>, cli: &Cli) -> ExitCode {
+    let reproducer = match syster_cli::minimize(path, code, message, &stdlib_options(cli)) {
+        Ok(reproducer) => reproducer,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match cli.output.as_ref() {
+        Some(out_dir) => {
+            for file in &reproducer.files {
+                let path = out_dir.join(&file.path);
+                let parent = path.parent().unwrap_or(out_dir);
+                if let Err(e) = std::fs::create_dir_all(parent) {
+                    eprintln!("error: failed to create {}: {}", parent.display(), e);
+                    return ExitCode::FAILURE;
+                }
+                if let Err(e) = std::fs::write(&path, &file.text) {
+                    eprintln!("error: failed to write {}: {}", path.display(), e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        None => {
+            for file in &reproducer.files {
+                println!("// {}\n{}", file.path.display(), file.text);
+            }
+        }
+    }
+    eprintln!(
+        "✓ Minimized {} files ({} lines) to {} files ({} lines) reporting {} in {} analyses",
+        reproducer.original_files,
+        reproducer.original_lines,
+        reproducer.files.len(),
+        reproducer.lines(),
+        code,
+        reproducer.analyses
+    );
+    ExitCode::SUCCESS
+}
+
+/// Report package metrics and architecture smells; fails if there are any.
+fn run_clones(src: &std::path::Path, min_members: usize, similarity: f64, cli: &Cli) -> ExitCode {
+    let options = syster_cli::CloneOptions {
+        min_members,
+        similarity,
+    };
+    let report = match syster_cli::find_clones(src, &options, &stdlib_options(cli)) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize clone report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let mut text = String::new();
+        for group in &report.groups {
+            text.push_str(&format!(
+                "{} definitions of identical structure ({}, {} members):\n",
+                group.definitions.len(),
+                group.kind,
+                group.members
+            ));
+            for site in &group.definitions {
+                text.push_str(&format!(
+                    "  {}  ({}:{})\n",
+                    site.qualified_name, site.file, site.line
+                ));
+            }
+        }
+        if !report.near.is_empty() {
+            text.push_str("Near clones:\n");
+        }
+        for near in &report.near {
+            text.push_str(&format!(
+                "  {} ~ {}  ({:.0}% shared, {})\n",
+                near.first.qualified_name,
+                near.second.qualified_name,
+                near.similarity * 100.0,
+                near.kind
+            ));
+        }
+        write_output(text.trim_end(), cli.output.as_ref());
+    }
+    eprintln!(
+        "✓ Compared {} definitions: {} clone groups ({} redundant definitions), {} near clones",
+        report.definitions,
+        report.groups.len(),
+        report.redundant_count(),
+        report.near.len()
+    );
+    ExitCode::SUCCESS
+}
+
+fn run_arch_check(src: &std::path::Path, max_coupling: Option<usize>, cli: &Cli) -> ExitCode {
+    let result = syster_cli::ArchConfig::discover(src).and_then(|mut config| {
+        if let Some(max_coupling) = max_coupling {
+            config.max_coupling = max_coupling;
+        }
+        syster_cli::arch_check(src, &config, &stdlib_options(cli))
+    });
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize architecture report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let mut text = format!(
+            "{:<40} {:>8} {:>6} {:>7} {:>11} {:>8}\n",
+            "Package", "Elements", "Fan-in", "Fan-out", "Instability", "Cohesion"
+        );
+        for package in &report.packages {
+            let cohesion = package
+                .cohesion
+                .map(|c| format!("{:.2}", c))
+                .unwrap_or_else(|| "-".to_string());
+            text.push_str(&format!(
+                "{:<40} {:>8} {:>6} {:>7} {:>11.2} {:>8}\n",
+                package.package,
+                package.elements,
+                package.fan_in,
+                package.fan_out,
+                package.instability,
+                cohesion
+            ));
+        }
+        write_output(text.trim_end(), cli.output.as_ref());
+        for smell in &report.smells {
+            eprintln!("✗ {}", smell);
+        }
+        if report.is_clean() {
+            eprintln!(
+                "✓ Checked {} packages: no architecture smells",
+                report.packages.len()
+            );
+        } else {
+            eprintln!(
+                "✗ Checked {} packages: {} architecture smells",
+                report.packages.len(),
+                report.smells.len()
+            );
+        }
+    }
+
+    if report.is_clean() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// List allocations and report their issues.
+fn run_allocations(src: &std::path::Path, cardinality: Option<&str>, cli: &Cli) -> ExitCode {
+    let result = syster_cli::AllocationConfig::discover(src).and_then(|mut config| {
+        if let Some(cardinality) = cardinality {
+            config.set_cardinality(cardinality)?;
+        }
+        syster_cli::check_allocations(src, &config, &stdlib_options(cli))
+    });
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize allocation report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let mut text = format!(
+            "{:<40} {:<40} {:<30} {}\n",
+            "Source", "Target", "Allocation", "Location"
+        );
+        for allocation in &report.allocations {
+            text.push_str(&format!(
+                "{:<40} {:<40} {:<30} {}:{}\n",
+                allocation
+                    .source_element
+                    .as_deref()
+                    .unwrap_or(&allocation.source),
+                allocation
+                    .target_element
+                    .as_deref()
+                    .unwrap_or(&allocation.target),
+                allocation.name.as_deref().unwrap_or("-"),
+                allocation.file,
+                allocation.line
+            ));
+        }
+        write_output(text.trim_end(), cli.output.as_ref());
+        for issue in &report.issues {
+            eprintln!("✗ {}", issue);
+        }
+        if report.is_clean() {
+            eprintln!(
+                "✓ Checked {} allocations: no issues",
+                report.allocations.len()
+            );
+        } else {
+            eprintln!(
+                "✗ Checked {} allocations: {} issues",
+                report.allocations.len(),
+                report.issues.len()
+            );
+        }
+    }
+
+    if report.is_clean() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Report signal paths and inconsistent port directions; fails if there are any.
+fn run_flows(src: &std::path::Path, cli: &Cli) -> ExitCode {
+    let report = match syster_cli::check_flows(src, &stdlib_options(cli)) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize flow report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let mut text = String::new();
+        for signal in &report.signals {
+            text.push_str(&format!("{}\n", signal.signal));
+            for path in &signal.paths {
+                text.push_str(&format!("  {}\n", path.join(" → ")));
+            }
+        }
+        write_output(text.trim_end(), cli.output.as_ref());
+        for issue in &report.issues {
+            eprintln!("✗ {}", issue);
+        }
+        if report.is_clean() {
+            eprintln!(
+                "✓ Checked {} connections and flows: {} signals, no issues",
+                report.connections,
+                report.signals.len()
+            );
+        } else {
+            eprintln!(
+                "✗ Checked {} connections and flows: {} issues",
+                report.connections,
+                report.issues.len()
+            );
+        }
+    }
+
+    if report.is_clean() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Report what a change affects.
+fn run_impact(
+    src: &std::path::Path,
+    changed: &[String],
+    from_diff: &[PathBuf],
+    max_depth: Option<usize>,
+    cli: &Cli,
+) -> ExitCode {
+    let result = match from_diff {
+        [old, new] => syster_cli::diff_impact(old, new, max_depth, &stdlib_options(cli)),
+        _ => syster_cli::change_impact(src, changed, max_depth, &stdlib_options(cli)),
+    };
+    let report = match result {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize impact report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let mut text = format!(
+            "{:>5}  {:<50} {:<14} {}\n",
+            "Depth", "Element", "Via", "From"
+        );
+        for element in &report.elements {
+            text.push_str(&format!(
+                "{:>5}  {:<50} {:<14} {}\n",
+                element.depth, element.qualified_name, element.via, element.from
+            ));
+        }
+        text.push_str("\nPackages:\n");
+        for package in &report.packages {
+            text.push_str(&format!(
+                "{:>5}  {} ({} elements)\n",
+                package.depth, package.package, package.elements
+            ));
+        }
+        write_output(text.trim_end(), cli.output.as_ref());
+        eprintln!(
+            "✓ {} changes affect {} elements in {} packages",
+            report.changes.len(),
+            report.elements.len(),
+            report.packages.len()
+        );
+    }
+    ExitCode::SUCCESS
+}
+
+/// Export the parametric graph and report over-constrained clusters.
+fn run_parametrics(src: &std::path::Path, cli: &Cli) -> ExitCode {
+    let graph = match syster_cli::parametric_graph(src, &stdlib_options(cli)) {
+        Ok(graph) => graph,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&graph) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize parametric graph: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        write_output(graph.to_dot().trim_end(), cli.output.as_ref());
+    }
+
+    let over_constrained: Vec<_> = graph.over_constrained().collect();
+    for cluster in &over_constrained {
+        eprintln!(
+            "✗ Over-constrained: {} equalities on {} free attributes among {}",
+            cluster.fixed,
+            cluster.free,
+            cluster.members.join(", ")
+        );
+    }
+    let calculations = graph
+        .nodes
+        .iter()
+        .filter(|n| n.kind != syster_cli::ParametricKind::Attribute)
+        .count();
+    let summary = format!(
+        "{} calculations and constraints on {} attributes in {} clusters",
+        calculations,
+        graph.nodes.len() - calculations,
+        graph.clusters.len()
+    );
+    if over_constrained.is_empty() {
+        eprintln!("✓ {}", summary);
+        ExitCode::SUCCESS
+    } else {
+        eprintln!("✗ {}: {} over-constrained", summary, over_constrained.len());
+        ExitCode::FAILURE
+    }
+}
+
+/// Report aliases and re-export chains with their final targets.
+fn run_resolve_aliases(
+    src: &std::path::Path,
+    scope: Option<&str>,
+    members: bool,
+    cli: &Cli,
+) -> ExitCode {
+    let report = match syster_cli::resolve_aliases(src, scope, &stdlib_options(cli)) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize alias report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut text = format!("Aliases ({}):\n", report.aliases.len());
+    for alias in &report.aliases {
+        let status = match &alias.target {
+            Some(_) => "",
+            None if alias.is_circular() => "  (circular)",
+            None => "  (unresolved)",
+        };
+        text.push_str(&format!(
+            "  {} -> {}{}  ({}:{})\n",
+            alias.alias,
+            alias.chain.join(" -> "),
+            status,
+            alias.file,
+            alias.line
+        ));
+    }
+    text.push_str(&format!("\nRe-exports ({}):\n", report.reexports.len()));
+    for reexport in &report.reexports {
+        text.push_str(&format!(
+            "  {} -> {}  ({} members)\n",
+            reexport.scope,
+            reexport.chain.join(" -> "),
+            reexport.members.len()
+        ));
+        if members {
+            for member in &reexport.members {
+                text.push_str(&format!(
+                    "      {} -> {}\n",
+                    member.name,
+                    member.target.as_deref().unwrap_or("(unresolved)")
+                ));
+            }
+        }
+    }
+    write_output(text.trim_end(), cli.output.as_ref());
+    ExitCode::SUCCESS
+}
+
+fn run_find_symbol(src: &std::path::Path, name: &str, cli: &Cli) -> ExitCode {
+    let matches = match syster_cli::find_symbol(src, name, &stdlib_options(cli)) {
+        Ok(matches) => matches,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&matches) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize symbol matches: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let mut text = String::new();
+        for found in &matches {
+            let status = if found.target.is_some() {
+                ""
+            } else {
+                "  (unresolved)"
+            };
+            text.push_str(&format!(
+                "{}{}  ({}:{})\n",
+                found.path(),
+                status,
+                found.file,
+                found.line
+            ));
+        }
+        write_output(text.trim_end(), cli.output.as_ref());
+    }
+    if matches.is_empty() {
+        eprintln!("✗ No declaration, alias or re-export named {}", name);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+/// Render the behaviors of `input` as PlantUML sequence diagrams, keeping
+/// those in the `--package` selection if there is one.
+#[cfg(feature = "interchange")]
+fn run_sequence_export(input: &std::path::Path, cli: &Cli) -> ExitCode {
+    let mut diagrams = match syster_cli::sequence_diagrams(input, &stdlib_options(cli)) {
+        Ok(diagrams) => diagrams,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if !cli.packages.is_empty() {
+        diagrams.retain(|diagram| {
+            cli.packages.iter().any(|package| {
+                diagram.behavior == *package
+                    || diagram.behavior.starts_with(&format!("{}::", package))
+            })
+        });
+    }
+
+    let rendered = syster_cli::render_plantuml_sequence(&diagrams);
+    write_output(rendered.trim_end(), cli.output.as_ref());
+    eprintln!("✓ Rendered {} sequence diagrams", diagrams.len());
+    ExitCode::SUCCESS
+}
+
+/// Export only the dependency closure of a package selection, stdlib included.
+#[cfg(feature = "interchange")]
+fn run_closure_export(
+    packages: &[String],
+    src: &std::path::Path,
+    format: InterchangeFormat,
+    cli: &Cli,
+) -> ExitCode {
+    let format_str = match format {
+        InterchangeFormat::Xmi => "xmi",
+        InterchangeFormat::Kpar => "kpar",
+        InterchangeFormat::JsonLd => "jsonld",
+        InterchangeFormat::Json => "json",
+        InterchangeFormat::Yaml => "yaml",
+        InterchangeFormat::PlantumlSequence => {
+            eprintln!("error: plantuml-sequence is not supported for closure exports");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match export_model_with_options(
+        src,
+        format_str,
+        &stdlib_options(cli),
+        true,
+        &ExportFilter {
+            packages: packages.to_vec(),
+            ..ExportFilter::default()
+        },
+    ) {
+        Ok((bytes, _)) => {
+            write_bytes_output(&bytes, cli.output.as_ref());
+            eprintln!(
+                "✓ Exported closure of {} as {}",
+                packages.join(", "),
+                format_str
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Classify the changes between two releases; fails if the declared
+/// version bump is too small.
+#[cfg(feature = "interchange")]
+fn run_semver_check(
+    old: &std::path::Path,
+    new: &std::path::Path,
+    old_version: Option<&str>,
+    new_version: Option<&str>,
+    cli: &Cli,
+) -> ExitCode {
+    let report = match syster_cli::semver_check(old, new, old_version, new_version, cli.verbose) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        let mut text = String::new();
+        for change in &report.changes {
+            text.push_str(&format!("{}: {}\n", change.level, change.description));
+        }
+        match report.level {
+            Some(level) => text.push_str(&format!("Change level: {}\n", level)),
+            None => text.push_str("No changes\n"),
+        }
+        if let (Some(old), Some(suggested)) = (&report.old_version, &report.suggested_version) {
+            text.push_str(&format!(
+                "Suggested version: {} (from {})\n",
+                suggested, old
+            ));
+        }
+        match (report.sufficient, &report.new_version) {
+            (Some(true), Some(declared)) => {
+                text.push_str(&format!("✓ Declared version {} is sufficient", declared))
+            }
+            (Some(false), Some(declared)) => text.push_str(&format!(
+                "✗ Declared version {} is insufficient: {} changes need at least {}",
+                declared,
+                report.level.map(|l| l.to_string()).unwrap_or_default(),
+                report.suggested_version.as_deref().unwrap_or_default()
+            )),
+            _ => text.push_str("Declared version unknown: pass --old-version and --new-version"),
+        }
+        write_output(&text, cli.output.as_ref());
+    }
+
+    if report.sufficient == Some(false) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Create a baseline, or report the changes since one.
+#[cfg(feature = "interchange")]
+fn run_baseline(command: &BaselineCommand, cli: &Cli) -> ExitCode {
+    let options = input_options(cli);
+    match command {
+        BaselineCommand::Create { tag, src, force } => {
+            match syster_cli::create_baseline(src, tag, *force, &options) {
+                Ok(baseline) => {
+                    println!(
+                        "✓ Created baseline {} ({} elements, {})",
+                        baseline.tag, baseline.elements, baseline.hash
+                    );
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        BaselineCommand::Compare { tag, src } => {
+            let comparison = match syster_cli::compare_baseline(src, tag, &options) {
+                Ok(comparison) => comparison,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            if cli.json {
+                match serde_json::to_string_pretty(&comparison) {
+                    Ok(json) => write_output(&json, cli.output.as_ref()),
+                    Err(e) => {
+                        eprintln!("error: Failed to serialize report: {}", e);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            } else {
+                let baseline = &comparison.baseline;
+                let mut text = format!(
+                    "Baseline {} ({}, {})\n",
+                    baseline.tag, baseline.provenance.timestamp, baseline.hash
+                );
+                for change in &comparison.changes {
+                    text.push_str(&format!("{}: {}\n", change.level, change.description));
+                }
+                match comparison.level {
+                    Some(_) => text.push_str(&format!(
+                        "{} changes since baseline {}",
+                        comparison.changes.len(),
+                        baseline.tag
+                    )),
+                    None => text.push_str(&format!("✓ No changes since baseline {}", baseline.tag)),
+                }
+                write_output(&text, cli.output.as_ref());
+            }
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+/// Migrate an export to the current schema and report what was applied on
+/// stderr (stdout may carry the migrated file).
+#[cfg(feature = "interchange")]
+fn run_migrate(file: &std::path::Path, cli: &Cli) -> ExitCode {
+    let (bytes, report) = match syster_cli::migrate_file(file) {
+        Ok(migrated) => migrated,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if report.applied.is_empty() {
+        eprintln!(
+            "✓ {} is already at schema {}",
+            file.display(),
+            report.to_version
+        );
+    } else {
+        eprintln!(
+            "✓ Migrated {} from schema {} to {}",
+            file.display(),
+            report.from_version,
+            report.to_version
+        );
+        for migration in &report.applied {
+            eprintln!(
+                "  {} ({} changes)",
+                migration.description, migration.changes
+            );
+        }
+    }
+    write_bytes_output(&bytes, cli.output.as_ref());
+    ExitCode::SUCCESS
+}
+
+/// List the schema violations of a standard JSON payload.
+#[cfg(feature = "interchange")]
+fn run_validate_interchange(
+    file: &std::path::Path,
+    schema_bundle: &std::path::Path,
+    cli: &Cli,
+) -> ExitCode {
+    let report = match syster_cli::validate_interchange(file, schema_bundle) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if cli.json {
+        match serde_json::to_string_pretty(&report) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize report: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else if report.violations.is_empty() {
+        println!(
+            "✓ {} matches the schemas ({} elements)",
+            file.display(),
+            report.elements
+        );
+    } else {
+        let mut text = String::new();
+        for violation in &report.violations {
+            text.push_str(&format!("{}\n", violation));
+        }
+        text.push_str(&format!(
+            "✗ {} schema violations in {} ({} elements)",
+            report.violations.len(),
+            file.display(),
+            report.elements
+        ));
+        write_output(&text, cli.output.as_ref());
+    }
+
+    if report.violations.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Diff the model from `src` against an interchange file.
+#[cfg(feature = "interchange")]
+fn run_verify(src: &std::path::Path, against: &std::path::Path, cli: &Cli) -> ExitCode {
+    use syster_cli::verify_against;
 
-                // Write metadata file
-                let metadata_path = sysml_path.with_extension("metadata.json");
-                if let Err(e) = std::fs::write(&metadata_path, &result.metadata_json) {
-                    eprintln!("error: failed to write {}: {}", metadata_path.display(), e);
-                    return ExitCode::FAILURE;
-                }
-                println!("  Wrote: {}", metadata_path.display());
+    let diff = match verify_against(src, against, &stdlib_options(cli)) {
+        Ok(diff) => diff,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
 
-                return ExitCode::SUCCESS;
+    if cli.json {
+        match serde_json::to_string_pretty(&diff) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
+            Err(e) => {
+                eprintln!("error: Failed to serialize diff: {}", e);
+                return ExitCode::FAILURE;
             }
+        }
+    } else if diff.is_empty() {
+        println!("✓ {} matches {}", against.display(), src.display());
+    } else {
+        let mut report = String::new();
+        for entry in &diff.only_in_source {
+            report.push_str(&format!("- only in sources: {}\n", entry));
+        }
+        for entry in &diff.only_in_interchange {
+            report.push_str(&format!("+ only in {}: {}\n", against.display(), entry));
+        }
+        for entry in &diff.kind_mismatches {
+            report.push_str(&format!("~ kind differs: {}\n", entry));
+        }
+        report.push_str(&format!(
+            "✗ {} diverges from {}: {} differences",
+            against.display(),
+            src.display(),
+            diff.only_in_source.len() + diff.only_in_interchange.len() + diff.kind_mismatches.len()
+        ));
+        write_output(&report, cli.output.as_ref());
+    }
+
+    if diff.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Commit the model to a SysML v2 API repository.
+#[cfg(feature = "interchange")]
+fn run_push(repo: &str, project: &str, branch: &str, src: &std::path::Path, cli: &Cli) -> ExitCode {
+    let repository = syster_cli::Repository::new(repo, std::env::var("SYSML_API_TOKEN").ok());
+    match syster_cli::push_model(
+        src,
+        &stdlib_options(cli),
+        &ExportFilter::default(),
+        &repository,
+        project,
+        branch,
+    ) {
+        Ok(report) => {
+            println!(
+                "✓ Pushed {} elements to {} ({}) as commit {}",
+                report.changes, project, branch, report.commit_id
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Analyze or decompile a commit pulled from a SysML v2 API repository.
+#[cfg(feature = "interchange")]
+fn run_pull(repo: &str, project: &str, commit: &str, decompile: bool, cli: &Cli) -> ExitCode {
+    let repository = syster_cli::Repository::new(repo, std::env::var("SYSML_API_TOKEN").ok());
+    let pulled = match syster_cli::pull_model(&repository, project, commit) {
+        Ok(pulled) => pulled,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if decompile {
+        let result = match pulled.decompile(MemberOrder::Source) {
+            Ok(result) => result,
             Err(e) => {
                 eprintln!("error: {}", e);
                 return ExitCode::FAILURE;
             }
+        };
+        write_output(&result.sysml_text, cli.output.as_ref());
+        if let Some(path) = &cli.output {
+            let metadata_path = path.with_extension("metadata.json");
+            if let Err(e) = std::fs::write(&metadata_path, &result.metadata_json) {
+                eprintln!("error: failed to write {}: {}", metadata_path.display(), e);
+                return ExitCode::FAILURE;
+            }
+            println!(
+                "✓ Decompiled {} elements from commit {} into {}",
+                result.element_count,
+                commit,
+                path.display()
+            );
         }
+        return ExitCode::SUCCESS;
     }
 
-    // Handle interchange import (validate only)
-    #[cfg(feature = "interchange")]
-    if cli.import {
-        match import_model(&cli.input, None, cli.verbose) {
-            Ok(result) => {
-                println!(
-                    "✓ Imported {} elements, {} relationships",
-                    result.element_count, result.relationship_count
-                );
-                if result.error_count > 0 {
-                    eprintln!("  {} validation issues:", result.error_count);
-                    for msg in &result.messages {
-                        eprintln!("    {}", msg);
-                    }
-                    return ExitCode::FAILURE;
-                }
-                return ExitCode::SUCCESS;
-            }
+    let result = match pulled.analyze(&stdlib_options(cli)) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if cli.json {
+        match export_json(&result) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
             Err(e) => {
                 eprintln!("error: {}", e);
                 return ExitCode::FAILURE;
             }
         }
+    } else {
+        for diag in &result.diagnostics {
+            print_diagnostic(diag);
+        }
+        if result.error_count == 0 {
+            println!(
+                "✓ Pulled {} elements from commit {}: {} symbols, {} warnings",
+                pulled.model.elements.len(),
+                commit,
+                result.symbol_count,
+                result.warning_count
+            );
+        } else {
+            eprintln!(
+                "✗ Pulled {} elements from commit {}: {} errors, {} warnings",
+                pulled.model.elements.len(),
+                commit,
+                result.error_count,
+                result.warning_count
+            );
+        }
     }
+    if result.error_count == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
 
-    // Handle import into workspace (for analysis) + optional export
-    #[cfg(feature = "interchange")]
-    if cli.import_workspace {
-        use syster::ide::AnalysisHost;
-        use syster::project::StdLibLoader;
-        use syster_cli::export_from_host;
+/// Publish diagnostics to Jira, or list what would be published.
+#[cfg(feature = "jira")]
+fn run_publish(command: &PublishCommand, cli: &Cli) -> ExitCode {
+    let PublishCommand::Jira {
+        project,
+        url,
+        src,
+        dry_run,
+    } = command;
+    let mut config = match syster_cli::JiraConfig::discover(src) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(project) = project {
+        config.project = project.clone();
+    }
+    if let Some(url) = url {
+        config.url = url.clone();
+    }
 
-        let mut host = AnalysisHost::new();
+    let result = match analyze(src, &stdlib_options(cli)) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let root = if src.is_dir() {
+        src.as_path()
+    } else {
+        src.parent().unwrap_or(src)
+    };
+    let issues = syster_cli::plan_issues(&result, root, &config);
 
-        // Load stdlib if enabled
-        if !cli.no_stdlib {
-            let mut loader = StdLibLoader::new();
-            if let Err(e) = loader.ensure_loaded_into_host(&mut host) {
-                eprintln!("warning: failed to load stdlib: {}", e);
+    if *dry_run {
+        if cli.json {
+            match serde_json::to_string_pretty(&issues) {
+                Ok(json) => write_output(&json, cli.output.as_ref()),
+                Err(e) => {
+                    eprintln!("error: failed to serialize issues: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            }
+        } else {
+            for issue in &issues {
+                println!(
+                    "{} [{}] {}",
+                    issue.fingerprint, issue.priority, issue.summary
+                );
             }
+            println!("Would publish {} issues", issues.len());
         }
+        return ExitCode::SUCCESS;
+    }
 
-        // Import the XMI/KPAR model into workspace
-        match import_model_into_host(&mut host, &cli.input, None, cli.verbose) {
-            Ok(result) => {
-                // If --export is also specified, export from the imported workspace
-                if let Some(format) = &cli.export {
-                    // Use stderr for status when exporting (stdout is for data)
-                    eprintln!(
-                        "✓ Imported {} elements ({} symbols) into workspace",
-                        result.element_count, result.element_count
-                    );
+    let auth = match (std::env::var("JIRA_USER"), std::env::var("JIRA_API_TOKEN")) {
+        (Ok(user), Ok(token)) => format!("{}:{}", user, token),
+        _ => {
+            eprintln!("error: set JIRA_USER and JIRA_API_TOKEN to publish to Jira");
+            return ExitCode::FAILURE;
+        }
+    };
+    match syster_cli::publish_issues(&issues, &config, &auth) {
+        Ok(summary) => {
+            println!(
+                "✓ Published {} issues to {}: {} created, {} updated",
+                issues.len(),
+                config.project,
+                summary.created.len(),
+                summary.updated.len()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
-                    let analysis = host.analysis();
-                    let all_symbols: Vec<_> = analysis.symbol_index().all_symbols().collect();
-                    eprintln!("  Total symbols in workspace: {}", all_symbols.len());
-                    eprintln!("  Element IDs preserved: ✓");
+/// Run a `kpar` subcommand.
+#[cfg(feature = "interchange")]
+fn run_kpar(command: &KparCommand, cli: &Cli) -> ExitCode {
+    use syster_cli::{PackManifest, extract_kpar, inspect_kpar, pack_kpar};
 
-                    let format_str = match format {
-                        InterchangeFormat::Xmi => "xmi",
-                        InterchangeFormat::Kpar => "kpar",
-                        InterchangeFormat::JsonLd => "jsonld",
-                        InterchangeFormat::Yaml => "yaml",
-                    };
+    match command {
+        KparCommand::Inspect { file } => {
+            let inspection = match inspect_kpar(file, cli.verbose) {
+                Ok(inspection) => inspection,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
 
-                    match export_from_host(&mut host, format_str, cli.verbose, cli.self_contained) {
-                        Ok(bytes) => {
-                            write_bytes_output(&bytes, cli.output.as_ref());
-                            return ExitCode::SUCCESS;
-                        }
-                        Err(e) => {
-                            eprintln!("error: {}", e);
-                            return ExitCode::FAILURE;
-                        }
+            if cli.json {
+                match serde_json::to_string_pretty(&inspection) {
+                    Ok(json) => write_output(&json, cli.output.as_ref()),
+                    Err(e) => {
+                        eprintln!("error: Failed to serialize inspection: {}", e);
+                        return ExitCode::FAILURE;
                     }
                 }
+            } else {
+                let mut report = format!("{}\n\nEntries:\n", file.display());
+                for entry in &inspection.entries {
+                    report.push_str(&format!("  {:>10}  {}", entry.size, entry.name));
+                    if let Some(count) = entry.element_count {
+                        report.push_str(&format!("  ({} elements)", count));
+                    }
+                    report.push('\n');
+                }
+                if let Some(manifest) = &inspection.manifest {
+                    report.push_str(&format!("\nManifest:\n{}\n", manifest.trim_end()));
+                }
+                if let Some(provenance) = &inspection.provenance {
+                    report.push_str("\nProvenance:\n");
+                    report.push_str(&format!(
+                        "  Tool:       {} {}\n  Exported:   {}\n  Input hash: {}\n",
+                        provenance.tool,
+                        provenance.version,
+                        provenance.timestamp,
+                        provenance.input_hash
+                    ));
+                    if let Some(stdlib) = &provenance.stdlib_version {
+                        report.push_str(&format!("  Stdlib:     {}\n", stdlib));
+                    }
+                }
+                if inspection.is_valid() {
+                    report.push_str(&format!(
+                        "\n✓ Valid KPAR: {} entries, {} elements",
+                        inspection.entries.len(),
+                        inspection.element_count()
+                    ));
+                } else {
+                    report.push_str("\n✗ Invalid KPAR:");
+                    for problem in &inspection.problems {
+                        report.push_str(&format!("\n  - {}", problem));
+                    }
+                }
+                write_output(&report, cli.output.as_ref());
+            }
 
-                // No export - use stdout for status
-                println!(
-                    "✓ Imported {} elements ({} symbols) into workspace",
-                    result.element_count, result.element_count
-                );
-
-                // Run analysis on imported symbols
-                let analysis = host.analysis();
-                let all_symbols: Vec<_> = analysis.symbol_index().all_symbols().collect();
-
-                println!("  Total symbols in workspace: {}", all_symbols.len());
-                println!("  Element IDs preserved: ✓");
-
-                return ExitCode::SUCCESS;
+            if inspection.is_valid() {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
             }
-            Err(e) => {
-                eprintln!("error: {}", e);
-                return ExitCode::FAILURE;
+        }
+        KparCommand::Extract { file, dir } => {
+            let dest = dir.clone().unwrap_or_else(|| file.with_extension(""));
+            match extract_kpar(file, &dest, cli.verbose) {
+                Ok(count) => {
+                    println!("✓ Extracted {} files to {}", count, dest.display());
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    ExitCode::FAILURE
+                }
             }
         }
-    }
+        KparCommand::Pack {
+            dir,
+            name,
+            version,
+            description,
+            dependencies,
+        } => {
+            let name = name.clone().unwrap_or_else(|| {
+                dir.canonicalize()
+                    .ok()
+                    .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+                    .unwrap_or_else(|| "unnamed".to_string())
+            });
+            let manifest = PackManifest {
+                name: name.clone(),
+                version: version.clone(),
+                description: description.clone(),
+                dependencies: dependencies.clone(),
+            };
 
-    // Handle interchange export
-    #[cfg(feature = "interchange")]
-    if let Some(format) = &cli.export {
-        let format_str = match format {
-            InterchangeFormat::Xmi => "xmi",
-            InterchangeFormat::Kpar => "kpar",
-            InterchangeFormat::JsonLd => "jsonld",
-            InterchangeFormat::Yaml => "yaml",
-        };
+            let output = cli
+                .output
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(format!("{}.kpar", name)));
 
-        match export_model(
-            &cli.input,
-            format_str,
-            cli.verbose,
-            !cli.no_stdlib,
-            cli.stdlib_path.as_deref(),
-            cli.self_contained,
-        ) {
-            Ok(bytes) => {
-                write_bytes_output(&bytes, cli.output.as_ref());
-                return ExitCode::SUCCESS;
+            match pack_kpar(dir, &manifest, &stdlib_options(cli)) {
+                Ok(bytes) => {
+                    if let Err(e) = std::fs::write(&output, bytes) {
+                        eprintln!("error: failed to write output: {}", e);
+                        return ExitCode::FAILURE;
+                    }
+                    println!("✓ Packed {} into {}", dir.display(), output.display());
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    ExitCode::FAILURE
+                }
             }
+        }
+    }
+}
+
+/// Merge partial analysis results into a single JSON result.
+fn run_merge_reports(reports: &[PathBuf], cli: &Cli) -> ExitCode {
+    let mut results = Vec::new();
+    for path in reports {
+        tracing::info!("Reading: {}", path.display());
+        match load_json_result(path) {
+            Ok(result) => results.push(result),
             Err(e) => {
                 eprintln!("error: {}", e);
                 return ExitCode::FAILURE;
@@ -263,77 +4820,129 @@ fn main() -> ExitCode {
         }
     }
 
-    // Handle AST export
-    if cli.export_ast {
-        match export_ast(
-            &cli.input,
-            cli.verbose,
-            !cli.no_stdlib,
-            cli.stdlib_path.as_deref(),
-        ) {
-            Ok(ast_output) => {
-                write_output(&ast_output, cli.output.as_ref());
-                return ExitCode::SUCCESS;
+    let merged = merge_results(results);
+    match export_json(&merged) {
+        Ok(json) => {
+            write_output(&json, cli.output.as_ref());
+            if merged.error_count == 0 {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
             }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Compare the diagnostics of two saved JSON results.
+fn run_diag_diff(
+    old: &std::path::Path,
+    new: &std::path::Path,
+    line_fuzz: u32,
+    cli: &Cli,
+) -> ExitCode {
+    let (old, new) = match (load_json_result(old), load_json_result(new)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let diff = diff_diagnostics(&old, &new, line_fuzz);
+    if cli.json {
+        match serde_json::to_string_pretty(&diff) {
+            Ok(json) => write_output(&json, cli.output.as_ref()),
             Err(e) => {
-                eprintln!("error: {}", e);
+                eprintln!("error: failed to serialize diff: {}", e);
                 return ExitCode::FAILURE;
             }
         }
+    } else {
+        write_output(
+            render_diagnostic_diff(&diff).trim_end(),
+            cli.output.as_ref(),
+        );
     }
 
-    match run_analysis(
-        &cli.input,
-        cli.verbose,
-        !cli.no_stdlib,
-        cli.stdlib_path.as_deref(),
-    ) {
-        Ok(result) => {
-            // Handle JSON export
-            if cli.json {
-                match export_json(&result) {
-                    Ok(json) => {
-                        write_output(&json, cli.output.as_ref());
-                        return if result.error_count == 0 {
-                            ExitCode::SUCCESS
-                        } else {
-                            ExitCode::FAILURE
-                        };
-                    }
-                    Err(e) => {
-                        eprintln!("error: {}", e);
-                        return ExitCode::FAILURE;
-                    }
-                }
-            }
+    if diff.new.is_empty() {
+        if !cli.json {
+            println!(
+                "✓ No new diagnostics ({} fixed, {} persisting)",
+                diff.fixed.len(),
+                diff.persisting.len()
+            );
+        }
+        ExitCode::SUCCESS
+    } else {
+        if !cli.json {
+            eprintln!("✗ {} new diagnostics", diff.new.len());
+        }
+        ExitCode::FAILURE
+    }
+}
 
-            // Print diagnostics (normal mode)
-            for diag in &result.diagnostics {
-                print_diagnostic(diag);
-            }
+/// Analyze the model and write a shields.io endpoint badge for `metric`.
+fn run_badge(
+    metric: BadgeMetric,
+    src: &std::path::Path,
+    label: Option<&str>,
+    cli: &Cli,
+) -> ExitCode {
+    let result = match analyze(src, &stdlib_options(cli)) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
 
-            // Print summary
-            if result.error_count == 0 {
-                println!(
-                    "✓ Analyzed {} files: {} symbols, {} warnings",
-                    result.file_count, result.symbol_count, result.warning_count
-                );
-                ExitCode::SUCCESS
-            } else {
-                eprintln!(
-                    "✗ Analyzed {} files: {} errors, {} warnings",
-                    result.file_count, result.error_count, result.warning_count
-                );
-                ExitCode::FAILURE
-            }
+    let badge = syster_cli::badge(&result, metric.into(), label);
+    match serde_json::to_string_pretty(&badge) {
+        Ok(json) => {
+            write_output(&json, cli.output.as_ref());
+            ExitCode::SUCCESS
         }
         Err(e) => {
-            eprintln!("error: {}", e);
+            eprintln!("error: failed to serialize badge: {}", e);
             ExitCode::FAILURE
         }
     }
 }
 
+/// Analysis options with the standard library and verbosity of the global
+/// flags
+fn stdlib_options(cli: &Cli) -> AnalysisOptions<'static> {
+    AnalysisOptions {
+        verbose: cli.verbose,
+        load_stdlib: !cli.no_stdlib,
+        stdlib_path: cli.stdlib_path.clone(),
+        stdlib_subset: cli.stdlib_subset.into(),
+        include_stdlib_diagnostics: cli.include_stdlib_diagnostics,
+        ..Default::default()
+    }
+}
+
+/// Analysis options with the standard library and verbosity of the global
+/// flags, and the input walked and parsed as they say
+fn input_options(cli: &Cli) -> AnalysisOptions<'static> {
+    AnalysisOptions {
+        strict_parse: cli.strict_parse,
+        walk: WalkOptions {
+            follow_symlinks: !cli.no_follow_symlinks,
+            max_depth: cli.max_depth,
+            max_file_size: cli.max_file_size,
+            max_files: cli.max_files,
+            respect_gitignore: cli.respect_gitignore,
+            skip_nested_projects: false,
+        },
+        ..stdlib_options(cli)
+    }
+}
+
 /// Write output to file or stdout
 fn write_output(content: &str, output_path: Option<&PathBuf>) {
     match output_path {
@@ -379,15 +4988,53 @@ fn print_diagnostic(diag: &DiagnosticInfo) {
         Severity::Info => "info",
         Severity::Hint => "hint",
     };
+    print_diagnostic_as(diag, prefix, &diag.message);
+}
+
+/// Print a diagnostic in the language of `catalog`.
+fn print_localized_diagnostic(diag: &DiagnosticInfo, catalog: &Catalog) {
+    print_diagnostic_as(
+        diag,
+        &catalog.severity(diag.severity),
+        &catalog.diagnostic_message(diag),
+    );
+}
 
+/// Print a diagnostic with the given severity name and message.
+fn print_diagnostic_as(diag: &DiagnosticInfo, prefix: &str, message: &str) {
     let code_suffix = diag
         .code
         .as_ref()
         .map(|c| format!("[{}]", c))
         .unwrap_or_default();
 
+    let blame_suffix = diag
+        .blame
+        .as_ref()
+        .map(|b| {
+            format!(
+                " ({}, {}, {})",
+                b.author,
+                &b.date[..b.date.len().min(10)],
+                b.commit
+            )
+        })
+        .unwrap_or_default();
+
+    let owner_suffix = if diag.owners.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", diag.owners.join(" "))
+    };
+
     eprintln!(
-        "{}{}: {}:{}:{}: {}",
-        prefix, code_suffix, diag.file, diag.line, diag.col, diag.message
+        "{}{}: {}:{}:{}: {}{}{}",
+        prefix, code_suffix, diag.file, diag.line, diag.col, message, blame_suffix, owner_suffix
     );
+    for related in &diag.related {
+        eprintln!(
+            "  note: {}:{}:{}: {}",
+            related.file, related.line, related.col, related.message
+        );
+    }
 }
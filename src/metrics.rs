@@ -0,0 +1,119 @@
+//! Prometheus metrics of an analysis run.
+//!
+//! [`prometheus_metrics`] renders a run in the Prometheus text exposition
+//! format and [`push_metrics`] sends it to a Pushgateway, which is how batch
+//! jobs such as CI checks report to Prometheus:
+//!
+//! ```text
+//! syster ./models --push-gateway http://pushgateway:9091
+//! ```
+
+use super::AnalysisResult;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use syster::hir::Severity;
+
+/// Job the metrics are pushed under.
+pub const PUSH_JOB: &str = "syster";
+
+/// Render `result`, analyzed in `duration`, in the Prometheus text
+/// exposition format.
+pub fn prometheus_metrics(result: &AnalysisResult, duration: Duration) -> String {
+    let mut out = String::new();
+    let mut gauge = |name: &str, help: &str, samples: &[(&str, String)]| {
+        out.push_str(&format!(
+            "# HELP {} {}\n# TYPE {} gauge\n",
+            name, help, name
+        ));
+        for (labels, value) in samples {
+            out.push_str(&format!("{}{} {}\n", name, labels, value));
+        }
+    };
+
+    gauge(
+        "syster_analysis_duration_seconds",
+        "Wall-clock time of the analysis.",
+        &[("", format!("{:.3}", duration.as_secs_f64()))],
+    );
+    gauge(
+        "syster_files",
+        "Files analyzed.",
+        &[("", result.file_count.to_string())],
+    );
+    gauge(
+        "syster_symbols",
+        "Symbols declared in the analyzed files.",
+        &[("", result.symbol_count.to_string())],
+    );
+    let severities: Vec<(&str, String)> = [
+        ("{severity=\"error\"}", Severity::Error),
+        ("{severity=\"warning\"}", Severity::Warning),
+        ("{severity=\"info\"}", Severity::Info),
+        ("{severity=\"hint\"}", Severity::Hint),
+    ]
+    .into_iter()
+    .map(|(labels, severity)| {
+        let count = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count();
+        (labels, count.to_string())
+    })
+    .collect();
+    gauge(
+        "syster_diagnostics",
+        "Diagnostics reported, by severity.",
+        &severities,
+    );
+    gauge(
+        "syster_files_with_syntax_errors",
+        "Analyzed files with syntax errors.",
+        &[("", result.syntax_errors.files.to_string())],
+    );
+    gauge(
+        "syster_doc_coverage_ratio",
+        "Documented definitions out of those measured.",
+        &[("", format!("{:.4}", result.doc_coverage.percent / 100.0))],
+    );
+    out
+}
+
+/// Push `metrics` to the Pushgateway at `gateway` under the job
+/// [`PUSH_JOB`], replacing the job's previous metrics.
+pub fn push_metrics(gateway: &str, metrics: &str) -> Result<(), String> {
+    let url = format!("{}/metrics/job/{}", gateway.trim_end_matches('/'), PUSH_JOB);
+    let mut child = Command::new("curl")
+        .args([
+            "--fail",
+            "--silent",
+            "--show-error",
+            "--request",
+            "PUT",
+            "--data-binary",
+            "@-",
+        ])
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(metrics.as_bytes())
+            .map_err(|e| format!("Failed to send metrics to curl: {}", e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Pushing metrics to {} failed: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,271 @@
+//! Migration of exports written by older syster versions (`syster migrate`).
+//!
+//! Every XMI, JSON-LD and KPAR export carries the interchange schema
+//! version it was written in, in its provenance record (see
+//! [`SCHEMA_VERSION`]). Exports from before the version was stamped are
+//! schema 1. A migration upgrades a file from one schema version to the
+//! next; migrating applies every migration from the file's version up to
+//! the current one and stamps the result with the current version.
+//!
+//! Schema 2 reads older kind names and relationship encodings that current
+//! readers would drop: kinds renamed by later SysML v2 releases, and
+//! relationship ends written as one-element lists.
+
+use super::kpar::{read_entries, write_entries};
+use super::provenance::{PROVENANCE_PATH, Provenance, SCHEMA_VERSION, replace_provenance};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// Kinds renamed by later SysML v2 releases, old name first.
+const RENAMED_KINDS: &[(&str, &str)] = &[
+    ("Superclassing", "Subclassification"),
+    ("Generalization", "Specialization"),
+    ("ItemFlow", "Flow"),
+];
+
+/// One step of the schema's history.
+struct Migration {
+    /// Schema version the migration upgrades from.
+    from: u32,
+    /// What it changes, as reported.
+    description: &'static str,
+    /// Apply to an XMI document; returns the number of changes.
+    xmi: fn(&mut String) -> usize,
+    /// Apply to the objects of a JSON-LD document; returns the number of
+    /// changes.
+    jsonld: fn(&mut [Value]) -> usize,
+}
+
+/// Every migration, oldest first.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        description: "Rename kinds superseded by later SysML v2 releases",
+        xmi: rename_xmi_kinds,
+        jsonld: rename_jsonld_kinds,
+    },
+    Migration {
+        from: 1,
+        description: "Write one-element relationship source and target lists as references",
+        xmi: |_| 0,
+        jsonld: unwrap_relationship_ends,
+    },
+];
+
+/// What a migration did.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    /// Schema version of the original file.
+    pub from_version: u32,
+    /// Schema version of the migrated file.
+    pub to_version: u32,
+    /// Migrations applied, in order.
+    pub applied: Vec<AppliedMigration>,
+}
+
+/// A migration applied to a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AppliedMigration {
+    /// Schema version the migration upgrades from.
+    pub from_version: u32,
+    /// What it changes.
+    pub description: String,
+    /// Number of changes it made to the file.
+    pub changes: usize,
+}
+
+/// Migrate an XMI, JSON-LD or KPAR export (format from its extension) to
+/// the current schema version.
+///
+/// # Returns
+/// The migrated file's bytes, unchanged if it already is current, and what
+/// was applied.
+pub fn migrate_file(input: &Path) -> Result<(Vec<u8>, MigrationReport), String> {
+    let bytes =
+        std::fs::read(input).map_err(|e| format!("Failed to read {}: {}", input.display(), e))?;
+    let extension = input
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    let format = match extension.as_str() {
+        "xmi" | "sysmlx" | "kermlx" => "xmi",
+        "jsonld" | "json" if super::sysml_json::is_standard_json(&bytes) => {
+            return Err(format!(
+                "{} is standard JSON, which carries no schema version",
+                input.display()
+            ));
+        }
+        "jsonld" | "json" => "jsonld",
+        "kpar" => "kpar",
+        _ => {
+            return Err(format!(
+                "Cannot migrate {}: use an .xmi, .jsonld or .kpar file",
+                input.display()
+            ));
+        }
+    };
+    migrate_bytes(bytes, format)
+}
+
+/// Migrate exported bytes of the given format (`xmi`, `jsonld`, `kpar`).
+pub fn migrate_bytes(bytes: Vec<u8>, format: &str) -> Result<(Vec<u8>, MigrationReport), String> {
+    let provenance = match format {
+        "kpar" => read_entries(&bytes)?
+            .into_iter()
+            .find(|(name, _)| name == PROVENANCE_PATH)
+            .and_then(|(_, record)| serde_json::from_slice::<Provenance>(&record).ok()),
+        _ => Provenance::from_export(&bytes),
+    };
+    let version = provenance.as_ref().map_or(1, |p| p.schema_version);
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "Schema version {} is newer than this syster supports ({}); upgrade syster",
+            version, SCHEMA_VERSION
+        ));
+    }
+
+    let mut report = MigrationReport {
+        from_version: version,
+        to_version: SCHEMA_VERSION,
+        applied: Vec::new(),
+    };
+    if version == SCHEMA_VERSION {
+        return Ok((bytes, report));
+    }
+
+    let pending: Vec<&Migration> = MIGRATIONS.iter().filter(|m| m.from >= version).collect();
+    let mut changes = vec![0; pending.len()];
+    let migrated = match format {
+        "xmi" => migrate_xmi(&bytes, &pending, &mut changes)?,
+        "jsonld" => migrate_jsonld(&bytes, &pending, &mut changes)?,
+        "kpar" => {
+            let mut entries = read_entries(&bytes)?;
+            for (name, content) in &mut entries {
+                if name.ends_with(".xmi") {
+                    *content = migrate_xmi(content, &pending, &mut changes)?;
+                }
+            }
+            write_entries(&entries)?
+        }
+        _ => return Err(format!("Cannot migrate format: {}", format)),
+    };
+
+    let provenance = match provenance {
+        Some(provenance) => Provenance {
+            schema_version: SCHEMA_VERSION,
+            ..provenance
+        },
+        None => Provenance::for_file(&bytes)?,
+    };
+    let migrated = replace_provenance(migrated, format, &provenance)?;
+
+    report.applied = pending
+        .iter()
+        .zip(changes)
+        .map(|(migration, changes)| AppliedMigration {
+            from_version: migration.from,
+            description: migration.description.to_string(),
+            changes,
+        })
+        .collect();
+    Ok((migrated, report))
+}
+
+fn migrate_xmi(
+    bytes: &[u8],
+    migrations: &[&Migration],
+    changes: &mut [usize],
+) -> Result<Vec<u8>, String> {
+    let mut text = String::from_utf8(bytes.to_vec()).map_err(|_| "XMI is not UTF-8".to_string())?;
+    for (migration, count) in migrations.iter().zip(changes.iter_mut()) {
+        *count += (migration.xmi)(&mut text);
+    }
+    Ok(text.into_bytes())
+}
+
+fn migrate_jsonld(
+    bytes: &[u8],
+    migrations: &[&Migration],
+    changes: &mut [usize],
+) -> Result<Vec<u8>, String> {
+    let value: Value =
+        serde_json::from_slice(bytes).map_err(|e| format!("Invalid JSON-LD: {}", e))?;
+    let single = !value.is_array();
+    let mut items = match value {
+        Value::Array(items) => items,
+        item => vec![item],
+    };
+    for (migration, count) in migrations.iter().zip(changes.iter_mut()) {
+        *count += (migration.jsonld)(&mut items);
+    }
+    let value = if single {
+        items.remove(0)
+    } else {
+        Value::Array(items)
+    };
+    serde_json::to_vec_pretty(&value).map_err(|e| format!("Failed to serialize JSON-LD: {}", e))
+}
+
+fn renamed_kind(kind: &str) -> Option<&'static str> {
+    RENAMED_KINDS
+        .iter()
+        .find(|(old, _)| *old == kind)
+        .map(|(_, new)| *new)
+}
+
+/// Rename old kinds in `xsi:type`/`xmi:type` attributes and element tags.
+fn rename_xmi_kinds(text: &mut String) -> usize {
+    let old: Vec<&str> = RENAMED_KINDS.iter().map(|(old, _)| *old).collect();
+    let pattern = Regex::new(&format!(
+        r#"((?:xsi|xmi):type="(?:\w+:)?|</?\w+:)({})\b"#,
+        old.join("|")
+    ))
+    .expect("valid kind pattern");
+
+    let mut count = 0;
+    let renamed = pattern.replace_all(text, |caps: &regex::Captures| {
+        count += 1;
+        format!("{}{}", &caps[1], renamed_kind(&caps[2]).unwrap_or(&caps[2]))
+    });
+    if count > 0 {
+        *text = renamed.into_owned();
+    }
+    count
+}
+
+fn rename_jsonld_kinds(items: &mut [Value]) -> usize {
+    let mut count = 0;
+    for item in items {
+        let new = item
+            .get("@type")
+            .and_then(Value::as_str)
+            .and_then(renamed_kind);
+        if let Some(new) = new {
+            item["@type"] = Value::String(new.to_string());
+            count += 1;
+        }
+    }
+    count
+}
+
+/// `"source": [{"@id": ...}]` becomes `"source": {"@id": ...}`, and so for
+/// `target`.
+fn unwrap_relationship_ends(items: &mut [Value]) -> usize {
+    let mut count = 0;
+    for item in items {
+        for end in ["source", "target"] {
+            let Some(Value::Array(list)) = item.get_mut(end) else {
+                continue;
+            };
+            if list.len() == 1 && list[0].get("@id").is_some() {
+                let reference = list.remove(0);
+                item[end] = reference;
+                count += 1;
+            }
+        }
+    }
+    count
+}
@@ -0,0 +1,293 @@
+//! Minimal reproducers for diagnostics (`syster minimize`).
+//!
+//! Delta debugging over the model: first whole files, then declarations,
+//! are removed in ever smaller chunks for as long as the target diagnostic
+//! is still reported. A removal that loses it, or introduces syntax errors,
+//! is undone. The target is a diagnostic with the given code whose message
+//! contains the given text or, without one, the whole message of the first
+//! such diagnostic, so the reproducer cannot slip to another diagnostic
+//! with the same code. Removing declarations is repeated until a round
+//! removes nothing, since each round exposes the declarations nested in
+//! what the previous one kept.
+//!
+//! Only the models are cut down; the standard library is loaded once and
+//! kept. The reproducer is the remaining text of the files still needed.
+
+use super::extents::{Extents, remove_ranges};
+use super::sample::relative_path;
+use super::{AnalysisOptions, AnalysisResult};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use syster::ide::AnalysisHost;
+
+/// A minimal model still reporting the target diagnostic.
+#[derive(Debug)]
+pub struct Reproducer {
+    /// Files of the reproducer, in path order.
+    pub files: Vec<ReproducerFile>,
+    /// Number of files in the original model.
+    pub original_files: usize,
+    /// Number of lines in the original model.
+    pub original_lines: usize,
+    /// Number of analyses it took.
+    pub analyses: usize,
+}
+
+/// A file of a reproducer.
+#[derive(Debug)]
+pub struct ReproducerFile {
+    /// Path relative to the minimized directory.
+    pub path: PathBuf,
+    /// What is left of the file's text.
+    pub text: String,
+}
+
+impl Reproducer {
+    /// Number of lines in the reproducer.
+    pub fn lines(&self) -> usize {
+        self.files.iter().map(|f| f.text.lines().count()).sum()
+    }
+}
+
+/// Analyze `input` and cut it down to a minimal model that still reports
+/// a diagnostic with `code` whose message contains `message`, or the first
+/// such diagnostic without one.
+///
+/// Fails if `input` does not report such a diagnostic to begin with.
+pub fn minimize(
+    input: &Path,
+    code: &str,
+    message: Option<&str>,
+    options: &AnalysisOptions,
+) -> Result<Reproducer, String> {
+    let verbose = options.verbose;
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), verbose)?;
+    }
+    let library: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    super::load_input_with(&mut host, input, verbose, &options.walk)?;
+    let mut current: BTreeMap<String, String> = host
+        .files()
+        .iter()
+        .map(|(path, file)| (path.to_string_lossy().to_string(), file.source_text()))
+        .filter(|(path, _)| !library.contains(path))
+        .collect();
+    let original_files = current.len();
+    let original_lines = current.values().map(|text| text.lines().count()).sum();
+
+    let mut minimizer = Minimizer {
+        host,
+        library,
+        loaded: current.clone(),
+        options,
+        code,
+        message: message.unwrap_or_default().to_string(),
+        syntax_errors: 0,
+        analyses: 0,
+    };
+    let result = minimizer.analyze(&current);
+    let first = result
+        .diagnostics
+        .iter()
+        .find(|d| d.code.as_deref() == Some(code) && d.message.contains(&minimizer.message));
+    let Some(first) = first else {
+        return Err(match message {
+            Some(message) => format!(
+                "No {} diagnostic matching '{}' in {}",
+                code,
+                message,
+                input.display()
+            ),
+            None => format!("No {} diagnostic in {}", code, input.display()),
+        });
+    };
+    if message.is_none() {
+        minimizer.message = first.message.clone();
+    }
+    minimizer.syntax_errors = result.syntax_errors.files;
+
+    // 1. Files
+    let paths: Vec<String> = current.keys().cloned().collect();
+    let removed = ddmin(paths.len(), |removed| {
+        let candidate = current
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !removed.contains(i))
+            .map(|(_, (path, text))| (path.clone(), text.clone()))
+            .collect();
+        minimizer.holds(&candidate)
+    });
+    for i in removed {
+        current.remove(&paths[i]);
+    }
+    if verbose {
+        tracing::info!("{} of {} files needed", current.len(), original_files);
+    }
+
+    // 2. Declarations, until a round removes none
+    loop {
+        minimizer.sync(&current);
+        let _analysis = minimizer.host.analysis();
+        let units = declarations(&minimizer.host, &current);
+        let removed = ddmin(units.len(), |removed| {
+            minimizer.holds(&without(&current, &units, removed))
+        });
+        if removed.is_empty() {
+            break;
+        }
+        current = without(&current, &units, &removed);
+        if verbose {
+            tracing::info!("Removed {} of {} declarations", removed.len(), units.len());
+        }
+    }
+
+    let mut files: Vec<ReproducerFile> = current
+        .into_iter()
+        .map(|(path, text)| ReproducerFile {
+            path: relative_path(input, Path::new(&path)),
+            text,
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(Reproducer {
+        files,
+        original_files,
+        original_lines,
+        analyses: minimizer.analyses,
+    })
+}
+
+/// The analysis host and what the candidates are tested for.
+struct Minimizer<'a> {
+    host: AnalysisHost,
+    library: HashSet<String>,
+    /// Model files as loaded into the host.
+    loaded: BTreeMap<String, String>,
+    options: &'a AnalysisOptions<'a>,
+    code: &'a str,
+    /// Text the message of the target diagnostic contains.
+    message: String,
+    /// Files with syntax errors in the original model.
+    syntax_errors: usize,
+    analyses: usize,
+}
+
+impl Minimizer<'_> {
+    /// Whether the model `files` still reports the diagnostic, without
+    /// more syntax errors than the original.
+    fn holds(&mut self, files: &BTreeMap<String, String>) -> bool {
+        let result = self.analyze(files);
+        self.reports(&result) && result.syntax_errors.files <= self.syntax_errors
+    }
+
+    fn reports(&self, result: &AnalysisResult) -> bool {
+        result
+            .diagnostics
+            .iter()
+            .any(|d| d.code.as_deref() == Some(self.code) && d.message.contains(&self.message))
+    }
+
+    fn analyze(&mut self, files: &BTreeMap<String, String>) -> AnalysisResult {
+        self.sync(files);
+        self.analyses += 1;
+        super::analyze_host(&mut self.host, &self.library, &HashSet::new(), self.options)
+    }
+
+    /// Load `files` into the host in place of the loaded ones.
+    fn sync(&mut self, files: &BTreeMap<String, String>) {
+        for path in self.loaded.keys() {
+            if !files.contains_key(path) {
+                self.host.remove_file(path);
+            }
+        }
+        for (path, text) in files {
+            if self.loaded.get(path) != Some(text) {
+                self.host.set_file_content(path, text);
+            }
+        }
+        self.loaded = files.clone();
+    }
+}
+
+/// Every declaration in `files` with an extent, outermost first.
+fn declarations(
+    host: &AnalysisHost,
+    files: &BTreeMap<String, String>,
+) -> Vec<(String, Range<usize>)> {
+    let mut units = Vec::new();
+    for path in files.keys() {
+        let Some(file_id) = host.get_file_id(path) else {
+            continue;
+        };
+        let Some(extents) = Extents::of(host, file_id) else {
+            continue;
+        };
+        let ranges: BTreeSet<(usize, std::cmp::Reverse<usize>)> = host
+            .symbol_index()
+            .symbols_in_file(file_id)
+            .into_iter()
+            .filter_map(|s| extents.range(s))
+            .map(|range| (range.start, std::cmp::Reverse(range.end)))
+            .collect();
+        units.extend(
+            ranges
+                .into_iter()
+                .map(|(start, end)| (path.clone(), start..end.0)),
+        );
+    }
+    units
+}
+
+/// `files` without the declarations of `units` numbered in `removed`.
+fn without(
+    files: &BTreeMap<String, String>,
+    units: &[(String, Range<usize>)],
+    removed: &BTreeSet<usize>,
+) -> BTreeMap<String, String> {
+    files
+        .iter()
+        .map(|(path, text)| {
+            let ranges = removed
+                .iter()
+                .map(|&i| &units[i])
+                .filter(|(file, _)| file == path)
+                .map(|(_, range)| range.clone())
+                .collect();
+            (path.clone(), remove_ranges(text, ranges))
+        })
+        .collect()
+}
+
+/// Delta debugging over `count` units: the largest set of units found
+/// whose removal `holds`, trying chunks from halves down to single units.
+fn ddmin(count: usize, mut holds: impl FnMut(&BTreeSet<usize>) -> bool) -> BTreeSet<usize> {
+    let mut removed = BTreeSet::new();
+    let mut granularity = 2;
+    loop {
+        let remaining: Vec<usize> = (0..count).filter(|i| !removed.contains(i)).collect();
+        if remaining.is_empty() {
+            break;
+        }
+        let chunk_size = remaining.len().div_ceil(granularity);
+        let reduced = remaining.chunks(chunk_size).find_map(|chunk| {
+            let mut candidate = removed.clone();
+            candidate.extend(chunk.iter().copied());
+            holds(&candidate).then_some(candidate)
+        });
+        match reduced {
+            Some(candidate) => {
+                removed = candidate;
+                granularity = (granularity - 1).max(2);
+            }
+            None if chunk_size == 1 => break,
+            None => granularity = (granularity * 2).min(remaining.len()),
+        }
+    }
+    removed
+}
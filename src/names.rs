@@ -0,0 +1,19 @@
+//! Qualified-name helpers shared by the passes that walk the symbol index.
+
+/// Enclosing scope of a qualified name (`""` at the top level).
+pub(crate) fn parent_scope(qualified_name: &str) -> &str {
+    qualified_name
+        .rsplit_once("::")
+        .map(|(parent, _)| parent)
+        .unwrap_or("")
+}
+
+/// `name` declared in `scope`, as a qualified name.
+pub(crate) fn qualify(scope: &str, name: &str) -> String {
+    let name = name.trim_matches('\'');
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", scope, name)
+    }
+}
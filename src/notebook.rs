@@ -0,0 +1,114 @@
+//! Output for Jupyter notebooks (`--format notebook-json`).
+//!
+//! The companion kernel and `%syster` magic run the CLI with `--machine`
+//! and display what it prints, so the output is a single Jupyter
+//! display-data bundle: an HTML rendering, a plain-text fallback and the
+//! structured result, with the command that produced it in the metadata:
+//!
+//! ```json
+//! {
+//!   "data": {
+//!     "text/html": "<div class=\"syster\">...</div>",
+//!     "text/plain": "✓ Analyzed 3 files: 12 symbols, 0 warnings",
+//!     "application/json": { "file_count": 3, ... }
+//!   },
+//!   "metadata": { "syster": { "command": "analyze", "version": "..." } }
+//! }
+//! ```
+//!
+//! The `application/json` data is what `--json` writes for the command.
+
+use super::{AnalysisResult, escape_xml, severity_name};
+use serde_json::{Value, json};
+
+/// What a command displays in a notebook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotebookOutput {
+    /// The command, e.g. `analyze`.
+    pub command: String,
+    /// HTML snippet, without a surrounding page.
+    pub html: String,
+    /// Plain-text rendering for frontends without HTML.
+    pub text: String,
+    /// Structured result.
+    pub data: Value,
+}
+
+impl NotebookOutput {
+    /// The output as a Jupyter display-data bundle.
+    pub fn to_json(&self) -> Result<String, String> {
+        let bundle = json!({
+            "data": {
+                "text/html": self.html,
+                "text/plain": self.text,
+                "application/json": self.data,
+            },
+            "metadata": {
+                "syster": {
+                    "command": self.command,
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            },
+        });
+        serde_json::to_string_pretty(&bundle)
+            .map_err(|e| format!("Failed to serialize notebook output: {}", e))
+    }
+}
+
+/// The notebook output of an analysis: a summary and a table of the
+/// diagnostics.
+pub fn notebook_analysis(result: &AnalysisResult) -> Result<NotebookOutput, String> {
+    let text = if result.error_count == 0 {
+        format!(
+            "✓ Analyzed {} files: {} symbols, {} warnings",
+            result.file_count, result.symbol_count, result.warning_count
+        )
+    } else {
+        format!(
+            "✗ Analyzed {} files: {} errors, {} warnings",
+            result.file_count, result.error_count, result.warning_count
+        )
+    };
+
+    let mut html = format!(
+        "<div class=\"syster\">\n<p class=\"syster-summary\">{}</p>\n",
+        escape_xml(&text)
+    );
+    if !result.diagnostics.is_empty() {
+        html.push_str(
+            "<table class=\"syster-diagnostics\">\n\
+             <thead><tr><th>Severity</th><th>Location</th><th>Code</th><th>Message</th></tr></thead>\n\
+             <tbody>\n",
+        );
+        for diag in &result.diagnostics {
+            let severity = severity_name(diag.severity);
+            html.push_str(&format!(
+                "<tr class=\"syster-{}\"><td>{}</td><td>{}:{}:{}</td><td>{}</td><td>{}</td></tr>\n",
+                severity,
+                severity,
+                escape_xml(&diag.file),
+                diag.line,
+                diag.col,
+                escape_xml(diag.code.as_deref().unwrap_or_default()),
+                escape_xml(&diag.message)
+            ));
+        }
+        html.push_str("</tbody>\n</table>\n");
+    }
+    html.push_str("</div>\n");
+
+    let data =
+        serde_json::to_value(result).map_err(|e| format!("Failed to serialize result: {}", e))?;
+    Ok(NotebookOutput {
+        command: "analyze".to_string(),
+        html,
+        text,
+        data,
+    })
+}
+
+/// Export an analysis as a Jupyter display-data bundle.
+pub fn export_notebook(result: &AnalysisResult) -> Result<String, String> {
+    let _span = tracing::info_span!("export", format = "notebook-json").entered();
+    notebook_analysis(result)?.to_json()
+}
@@ -0,0 +1,70 @@
+//! Where each loaded file comes from.
+//!
+//! Analysis reports on the model's own files. The standard library and
+//! dependencies are loaded so references into them resolve, and their
+//! diagnostics are not the user's to fix: those of dependencies are never
+//! reported, those of the standard library only on request (see
+//! [`AnalysisOptions::include_stdlib_diagnostics`](super::AnalysisOptions)).
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use syster::FileId;
+use syster::ide::AnalysisHost;
+
+/// Where a loaded file comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileOrigin {
+    /// A file of the analyzed model.
+    #[default]
+    User,
+    /// A file of the standard library.
+    Stdlib,
+    /// A dependency: a library, an index or an imported model.
+    Imported,
+}
+
+/// The origin of every file loaded into a host.
+#[derive(Debug, Default)]
+pub struct FileOrigins {
+    origins: HashMap<FileId, FileOrigin>,
+}
+
+impl FileOrigins {
+    /// Origins of the files of `host`: the `dependency_files` are imported,
+    /// the other `library_files` (loaded before the model) are the standard
+    /// library, and the rest belong to the model.
+    pub fn new(
+        host: &AnalysisHost,
+        library_files: &HashSet<String>,
+        dependency_files: &HashSet<String>,
+    ) -> Self {
+        let origins = host
+            .files()
+            .keys()
+            .filter_map(|path| {
+                let id = host.get_file_id_for_path(path)?;
+                let path = path.to_string_lossy();
+                let origin = if dependency_files.contains(path.as_ref()) {
+                    FileOrigin::Imported
+                } else if library_files.contains(path.as_ref()) {
+                    FileOrigin::Stdlib
+                } else {
+                    FileOrigin::User
+                };
+                Some((id, origin))
+            })
+            .collect();
+        Self { origins }
+    }
+
+    /// Origin of `file`; files the host did not hold are the model's.
+    pub fn get(&self, file: FileId) -> FileOrigin {
+        self.origins.get(&file).copied().unwrap_or_default()
+    }
+
+    /// Number of files of `origin`.
+    pub fn count(&self, origin: FileOrigin) -> usize {
+        self.origins.values().filter(|o| **o == origin).count()
+    }
+}
@@ -0,0 +1,166 @@
+//! Package ownership (`MODELOWNERS`).
+//!
+//! Like a `CODEOWNERS` file, a `MODELOWNERS` file next to the models (or in
+//! a directory above them) maps package globs to the teams owning them, one
+//! rule per line; the last matching rule wins:
+//!
+//! ```text
+//! # package glob      owners
+//! **                  @systems
+//! Vehicle::*          @vehicle-team
+//! Vehicle::Power      @power-team @vehicle-team
+//! ```
+//!
+//! Globs are those of [layer rules](super::layers): `*` matches within one
+//! name segment, `**` across segments, and a glob also covers the packages
+//! nested in what it matches. Diagnostics are owned by the teams owning the
+//! innermost package enclosing them, and top-level package breakdowns by the
+//! teams owning the package.
+
+use super::DiagnosticInfo;
+use super::extents::Extents;
+use super::layers::Glob;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use syster::hir::SymbolKind;
+use syster::ide::AnalysisHost;
+
+/// Name of the ownership file.
+pub const OWNERS_FILE: &str = "MODELOWNERS";
+
+/// Teams owning the packages of a model.
+#[derive(Debug, Clone, Default)]
+pub struct ModelOwners {
+    rules: Vec<(Glob, Vec<String>)>,
+}
+
+impl ModelOwners {
+    /// Parse the rules of a `MODELOWNERS` file.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let mut fields = line.split_whitespace();
+            let Some(glob) = fields.next() else {
+                continue;
+            };
+            let owners: Vec<String> = fields.map(String::from).collect();
+            if owners.is_empty() {
+                return Err(format!("line {}: no owners for {}", number + 1, glob));
+            }
+            let glob = Glob::new(glob).map_err(|e| format!("line {}: {}", number + 1, e))?;
+            rules.push((glob, owners));
+        }
+        Ok(Self { rules })
+    }
+
+    /// Load a `MODELOWNERS` file.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&text).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+    }
+
+    /// Load the nearest `MODELOWNERS` in `input` (or its directory) and its
+    /// ancestors, if there is one.
+    pub fn discover(input: &Path) -> Result<Option<Self>, String> {
+        find_owners_file(input)
+            .map(|path| Self::load(&path))
+            .transpose()
+    }
+
+    /// Teams owning `package`: those of the last rule matching it.
+    pub fn owners_of(&self, package: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(glob, _)| glob.matches(package))
+            .map(|(_, owners)| owners.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Each package glob with its owners, in file order.
+    pub(crate) fn rules(&self) -> Vec<(&str, &[String])> {
+        self.rules
+            .iter()
+            .map(|(glob, owners)| (glob.as_str(), owners.as_slice()))
+            .collect()
+    }
+}
+
+/// Whether `owners` include one of `teams`, or `teams` is empty; a leading
+/// `@` is optional on either.
+pub(crate) fn owned_by_any(owners: &[String], teams: &[String]) -> bool {
+    teams.is_empty()
+        || teams.iter().any(|team| {
+            let team = team.trim_start_matches('@');
+            owners.iter().any(|o| o.trim_start_matches('@') == team)
+        })
+}
+
+/// Find the nearest `MODELOWNERS` file in `input` (or its directory) and its
+/// ancestors.
+pub(crate) fn find_owners_file(input: &Path) -> Option<PathBuf> {
+    let dir = if input.is_dir() {
+        input
+    } else {
+        input
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+    };
+    let dir = std::path::absolute(dir).ok()?;
+    dir.ancestors()
+        .map(|d| d.join(OWNERS_FILE))
+        .find(|p| p.is_file())
+}
+
+/// Set the owners of each diagnostic from the innermost package enclosing
+/// its line.
+pub(crate) fn annotate_owners(
+    host: &AnalysisHost,
+    owners: &ModelOwners,
+    diagnostics: &mut [DiagnosticInfo],
+) {
+    // Line ranges (0-based, inclusive) of the packages per file
+    let mut packages: HashMap<&str, Vec<(u32, u32, &str)>> = HashMap::new();
+    for diag in diagnostics.iter() {
+        if packages.contains_key(diag.file.as_str()) {
+            continue;
+        }
+        let ranges = host
+            .get_file_id(&diag.file)
+            .and_then(|file_id| {
+                let extents = Extents::of(host, file_id)?;
+                let ranges = host
+                    .symbol_index()
+                    .symbols_in_file(file_id)
+                    .into_iter()
+                    .filter(|s| s.kind == SymbolKind::Package)
+                    .map(|s| {
+                        let (start, end) = extents.lines(s);
+                        (start, end, &*s.qualified_name)
+                    })
+                    .collect();
+                Some(ranges)
+            })
+            .unwrap_or_default();
+        packages.insert(diag.file.as_str(), ranges);
+    }
+
+    let owned: Vec<Vec<String>> = diagnostics
+        .iter()
+        .map(|diag| {
+            let line = diag.line.saturating_sub(1);
+            packages[diag.file.as_str()]
+                .iter()
+                .filter(|(start, end, _)| (*start..=*end).contains(&line))
+                .max_by_key(|(_, _, name)| name.len())
+                .map(|(_, _, name)| owners.owners_of(name).to_vec())
+                .unwrap_or_default()
+        })
+        .collect();
+    for (diag, owned) in diagnostics.iter_mut().zip(owned) {
+        diag.owners = owned;
+    }
+}
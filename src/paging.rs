@@ -0,0 +1,137 @@
+//! Paged JSON-LD exports (`--page-size`).
+//!
+//! A JSON-LD export of a large model is one array that a consumer has to
+//! parse whole before it can ingest anything. A paged export splits the
+//! array into numbered page files of at most `page_size` objects each, every
+//! one a JSON-LD document of its own, next to an index document listing the
+//! pages in order. Triple stores and message queues can then load a model
+//! page by page. The provenance record moves from the array into the index.
+
+use super::to_hex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// File name of the index document in a paged export.
+pub const INDEX_FILE: &str = "index.jsonld";
+
+/// `@type` of the index document.
+const INDEX_TYPE: &str = "syster:PageIndex";
+
+/// `@type` of the provenance object moved into the index.
+const PROVENANCE_TYPE: &str = "syster:Provenance";
+
+/// The index document of a paged export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageIndex {
+    /// Always `syster:PageIndex`.
+    #[serde(rename = "@type")]
+    pub kind: String,
+    /// Maximum number of objects per page.
+    pub page_size: usize,
+    /// Number of objects over all pages.
+    pub item_count: usize,
+    /// The pages, in order.
+    pub pages: Vec<PageEntry>,
+    /// Provenance record of the export, if it had one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Value>,
+}
+
+/// One page of a paged export.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageEntry {
+    /// File name, relative to the index.
+    pub file: String,
+    /// Number of objects on the page.
+    pub item_count: usize,
+    /// SHA-256 of the page file, for checking a transfer.
+    pub sha256: String,
+}
+
+/// Split a JSON-LD export into pages of at most `page_size` objects.
+///
+/// # Returns
+/// The index and the pages' contents, in the index's order.
+pub fn paginate_jsonld(
+    bytes: &[u8],
+    page_size: usize,
+) -> Result<(PageIndex, Vec<Vec<u8>>), String> {
+    if page_size == 0 {
+        return Err("Page size must be at least 1".to_string());
+    }
+    let value: Value =
+        serde_json::from_slice(bytes).map_err(|e| format!("Invalid JSON-LD: {}", e))?;
+    let items = match value {
+        Value::Array(items) => items,
+        item => vec![item],
+    };
+    let (provenance, items): (Vec<Value>, Vec<Value>) = items
+        .into_iter()
+        .partition(|item| item.get("@type").and_then(Value::as_str) == Some(PROVENANCE_TYPE));
+
+    let mut index = PageIndex {
+        kind: INDEX_TYPE.to_string(),
+        page_size,
+        item_count: items.len(),
+        pages: Vec::new(),
+        provenance: provenance.into_iter().next(),
+    };
+    let mut pages = Vec::new();
+    for (number, chunk) in items.chunks(page_size).enumerate() {
+        let page = serde_json::to_vec_pretty(chunk)
+            .map_err(|e| format!("Failed to serialize page: {}", e))?;
+        index.pages.push(PageEntry {
+            file: page_file(number + 1),
+            item_count: chunk.len(),
+            sha256: to_hex(&Sha256::digest(&page)),
+        });
+        pages.push(page);
+    }
+    Ok((index, pages))
+}
+
+/// Write a JSON-LD export as pages of at most `page_size` objects, plus
+/// [`INDEX_FILE`], into `dir`.
+///
+/// The directory is created if needed; pages left in it by an earlier,
+/// larger export are removed.
+pub fn write_jsonld_pages(bytes: &[u8], page_size: usize, dir: &Path) -> Result<PageIndex, String> {
+    let (index, pages) = paginate_jsonld(bytes, page_size)?;
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let existing =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in existing.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_page_file(&name) {
+            std::fs::remove_file(entry.path())
+                .map_err(|e| format!("Failed to remove stale page {}: {}", name, e))?;
+        }
+    }
+
+    for (entry, page) in index.pages.iter().zip(&pages) {
+        let path = dir.join(&entry.file);
+        std::fs::write(&path, page)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    let json = serde_json::to_vec_pretty(&index)
+        .map_err(|e| format!("Failed to serialize page index: {}", e))?;
+    let path = dir.join(INDEX_FILE);
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(index)
+}
+
+fn page_file(number: usize) -> String {
+    format!("page-{:05}.jsonld", number)
+}
+
+fn is_page_file(name: &str) -> bool {
+    name.strip_prefix("page-")
+        .and_then(|rest| rest.strip_suffix(".jsonld"))
+        .is_some_and(|number| !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()))
+}
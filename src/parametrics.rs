@@ -0,0 +1,511 @@
+//! Calculation and constraint dependency graph (`syster parametrics`).
+//!
+//! Calculation and constraint usages are linked to the attributes they
+//! couple:
+//!
+//! - a calculation or constraint *reads* every attribute its expressions
+//!   (including the bindings of its parameters) refer to;
+//! - a calculation *writes* every attribute whose value refers to it or to
+//!   its result.
+//!
+//! Attributes declared inside a calculation or constraint are its own
+//! parameters and are not part of the graph.
+//!
+//! The graph splits into clusters of coupled elements. A cluster is
+//! over-constrained when it fixes more values than it leaves free: each
+//! equality constraint (`==`) fixes a value, and each attribute without a
+//! value (`= ...`, which includes being computed by a calculation) is
+//! free. Inequalities only narrow the values and fix nothing.
+
+use super::AnalysisOptions;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use syster::FileId;
+use syster::hir::{HirSymbol, RefKind, SymbolIndex, SymbolKind, TypeRefKind};
+use syster::ide::AnalysisHost;
+
+/// Kind of a node of the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParametricKind {
+    Calculation,
+    Constraint,
+    Attribute,
+}
+
+/// How a calculation or constraint uses an attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Access {
+    Reads,
+    Writes,
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Access::Reads => "reads",
+            Access::Writes => "writes",
+        })
+    }
+}
+
+/// A calculation, constraint or attribute.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParametricNode {
+    /// Qualified name of the element.
+    pub name: String,
+    pub kind: ParametricKind,
+    /// For attributes, whether the value is given (`= ...`); for
+    /// constraints, whether it is an equality (`==`).
+    pub fixed: bool,
+    /// File declaring the element.
+    pub file: String,
+    /// Declaration line (1-based).
+    pub line: u32,
+}
+
+/// An attribute read or written by a calculation or constraint.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ParametricEdge {
+    /// Qualified name of the calculation or constraint.
+    pub calculation: String,
+    /// Qualified name of the attribute.
+    pub attribute: String,
+    pub access: Access,
+}
+
+/// Coupled calculations, constraints and attributes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParametricCluster {
+    /// Qualified names of the members, sorted.
+    pub members: Vec<String>,
+    /// Values fixed: equality constraints.
+    pub fixed: usize,
+    /// Attributes without a value of their own.
+    pub free: usize,
+}
+
+impl ParametricCluster {
+    /// Whether the cluster fixes more values than it leaves free.
+    pub fn is_over_constrained(&self) -> bool {
+        self.fixed > self.free
+    }
+}
+
+/// The dependency graph of a model's calculations and constraints.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParametricGraph {
+    /// Nodes by qualified name.
+    pub nodes: Vec<ParametricNode>,
+    /// Edges, sorted.
+    pub edges: Vec<ParametricEdge>,
+    /// Clusters of coupled nodes, largest first.
+    pub clusters: Vec<ParametricCluster>,
+}
+
+impl ParametricGraph {
+    /// Clusters that fix more values than they leave free.
+    pub fn over_constrained(&self) -> impl Iterator<Item = &ParametricCluster> {
+        self.clusters.iter().filter(|c| c.is_over_constrained())
+    }
+
+    /// Render the graph in Graphviz DOT, one subgraph per cluster with
+    /// over-constrained ones in red. Edges follow the data: attribute to
+    /// the reading element, calculation to the written attribute.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph parametrics {\n    rankdir=LR;\n");
+        let node = |node: &ParametricNode| {
+            let shape = match node.kind {
+                ParametricKind::Calculation => "box",
+                ParametricKind::Constraint => "hexagon",
+                ParametricKind::Attribute => "ellipse",
+            };
+            let style = if node.fixed && node.kind == ParametricKind::Attribute {
+                ", style=filled, fillcolor=lightgrey"
+            } else {
+                ""
+            };
+            format!("{} [shape={}{}];\n", quote(&node.name), shape, style)
+        };
+        let nodes: HashMap<&str, &ParametricNode> =
+            self.nodes.iter().map(|n| (n.name.as_str(), n)).collect();
+        let mut clustered = HashSet::new();
+        for (i, cluster) in self.clusters.iter().enumerate() {
+            out.push_str(&format!("    subgraph cluster_{} {{\n", i));
+            out.push_str(&format!(
+                "        label={};\n",
+                quote(&format!(
+                    "{} fixed, {} free{}",
+                    cluster.fixed,
+                    cluster.free,
+                    if cluster.is_over_constrained() {
+                        " (over-constrained)"
+                    } else {
+                        ""
+                    }
+                ))
+            ));
+            if cluster.is_over_constrained() {
+                out.push_str("        color=red;\n");
+            }
+            for member in &cluster.members {
+                if let Some(n) = nodes.get(member.as_str()) {
+                    out.push_str(&format!("        {}", node(n)));
+                }
+                clustered.insert(member.as_str());
+            }
+            out.push_str("    }\n");
+        }
+        for n in self
+            .nodes
+            .iter()
+            .filter(|n| !clustered.contains(n.name.as_str()))
+        {
+            out.push_str(&format!("    {}", node(n)));
+        }
+        for edge in &self.edges {
+            let (from, to) = match edge.access {
+                Access::Reads => (&edge.attribute, &edge.calculation),
+                Access::Writes => (&edge.calculation, &edge.attribute),
+            };
+            out.push_str(&format!(
+                "    {} -> {} [label={}];\n",
+                quote(from),
+                quote(to),
+                edge.access
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Analyze `input` and build the dependency graph of its calculations and
+/// constraints.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `options` - Verbosity and standard library to load
+pub fn parametric_graph(
+    input: &Path,
+    options: &AnalysisOptions,
+) -> Result<ParametricGraph, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let library: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    super::load_input(&mut host, input, options.verbose)?;
+    let _analysis = host.analysis();
+    let index = host.symbol_index();
+
+    let mut sources = HashMap::new();
+    for path in host.files().keys() {
+        if let Some(id) = host.get_file_id_for_path(path) {
+            sources.insert(id, host.files()[path].source_text());
+        }
+    }
+    let model = Model { index, sources };
+
+    let symbols: Vec<&HirSymbol> = index
+        .all_symbols()
+        .filter(|s| {
+            host.get_file_path(s.file)
+                .is_some_and(|path| !library.contains(path))
+        })
+        .collect();
+    let node = |symbol: &HirSymbol, kind: ParametricKind| ParametricNode {
+        name: symbol.qualified_name.to_string(),
+        kind,
+        fixed: match kind {
+            ParametricKind::Attribute => model.has_value(symbol),
+            _ => model.is_equation(symbol),
+        },
+        file: host
+            .get_file_path(symbol.file)
+            .unwrap_or_default()
+            .to_string(),
+        line: symbol.start_line + 1,
+    };
+
+    let mut nodes: BTreeMap<String, ParametricNode> = BTreeMap::new();
+    let mut edges = BTreeSet::new();
+    for symbol in &symbols {
+        let name = symbol.qualified_name.as_ref();
+        let kind = match symbol.kind {
+            SymbolKind::CalculationUsage => ParametricKind::Calculation,
+            SymbolKind::ConstraintUsage => ParametricKind::Constraint,
+            SymbolKind::AttributeUsage => ParametricKind::Attribute,
+            _ => continue,
+        };
+        // Nested in a calculation or constraint: a parameter or part of
+        // the enclosing expression
+        if model.enclosing(owner(name)).is_some() {
+            continue;
+        }
+
+        if kind == ParametricKind::Attribute {
+            for target in model.references(symbol) {
+                let calculation = model
+                    .enclosing(&target)
+                    .filter(|s| s.kind == SymbolKind::CalculationUsage);
+                if let Some(calculation) = calculation {
+                    nodes.insert(name.to_string(), node(symbol, kind));
+                    edges.insert(ParametricEdge {
+                        calculation: calculation.qualified_name.to_string(),
+                        attribute: name.to_string(),
+                        access: Access::Writes,
+                    });
+                }
+            }
+            continue;
+        }
+
+        nodes.insert(name.to_string(), node(symbol, kind));
+        let prefix = format!("{}::", name);
+        let body = symbols
+            .iter()
+            .filter(|s| s.qualified_name.starts_with(&prefix));
+        for s in std::iter::once(symbol).chain(body) {
+            for target in model.references(s) {
+                let attribute = index
+                    .lookup_qualified(&target)
+                    .filter(|t| t.kind == SymbolKind::AttributeUsage)
+                    .filter(|t| model.enclosing(owner(&t.qualified_name)).is_none());
+                let Some(attribute) = attribute else {
+                    continue;
+                };
+                if !library.contains(host.get_file_path(attribute.file).unwrap_or_default()) {
+                    nodes.insert(target.clone(), node(attribute, ParametricKind::Attribute));
+                    edges.insert(ParametricEdge {
+                        calculation: name.to_string(),
+                        attribute: target,
+                        access: Access::Reads,
+                    });
+                }
+            }
+        }
+    }
+
+    let edges: Vec<ParametricEdge> = edges.into_iter().collect();
+    let clusters = clusters(&nodes, &edges);
+    if options.verbose {
+        tracing::info!(
+            "Parametric graph: {} nodes, {} edges, {} clusters",
+            nodes.len(),
+            edges.len(),
+            clusters.len()
+        );
+    }
+    Ok(ParametricGraph {
+        nodes: nodes.into_values().collect(),
+        edges,
+        clusters,
+    })
+}
+
+/// Connected components of the edges, with their fixed and free values.
+fn clusters(
+    nodes: &BTreeMap<String, ParametricNode>,
+    edges: &[ParametricEdge],
+) -> Vec<ParametricCluster> {
+    let mut neighbours: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for edge in edges {
+        neighbours
+            .entry(&edge.calculation)
+            .or_default()
+            .push(&edge.attribute);
+        neighbours
+            .entry(&edge.attribute)
+            .or_default()
+            .push(&edge.calculation);
+    }
+    let mut seen = HashSet::new();
+    let mut clusters = Vec::new();
+    for start in neighbours.keys() {
+        if !seen.insert(*start) {
+            continue;
+        }
+        let mut members = vec![*start];
+        let mut stack = vec![*start];
+        while let Some(name) = stack.pop() {
+            for next in &neighbours[name] {
+                if seen.insert(*next) {
+                    members.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+        members.sort();
+
+        let mut fixed = 0;
+        let mut free = 0;
+        for member in &members {
+            let Some(node) = nodes.get(*member) else {
+                continue;
+            };
+            match node.kind {
+                ParametricKind::Attribute if !node.fixed => free += 1,
+                ParametricKind::Constraint if node.fixed => fixed += 1,
+                _ => {}
+            }
+        }
+        clusters.push(ParametricCluster {
+            members: members.into_iter().map(str::to_string).collect(),
+            fixed,
+            free,
+        });
+    }
+    clusters.sort_by(|a, b| {
+        b.members
+            .len()
+            .cmp(&a.members.len())
+            .then_with(|| a.members.cmp(&b.members))
+    });
+    clusters
+}
+
+/// Looks up what the elements of the model refer to and how they are
+/// written.
+struct Model<'a> {
+    index: &'a SymbolIndex,
+    /// Source of the model files, to find values and equalities.
+    sources: HashMap<FileId, String>,
+}
+
+impl Model<'_> {
+    /// Qualified names of what the expressions of `symbol` refer to; for
+    /// a feature chain (`tank.mass`), the feature at its end.
+    fn references(&self, symbol: &HirSymbol) -> Vec<String> {
+        let scope = symbol.qualified_name.as_ref();
+        symbol
+            .type_refs
+            .iter()
+            .filter(|r| r.as_refs().iter().all(|r| r.kind == RefKind::Expression))
+            .filter_map(|r| match r {
+                TypeRefKind::Simple(r) => match &r.resolved_target {
+                    Some(target) => Some(target.to_string()),
+                    None => self
+                        .index
+                        .resolver_for_scope(scope)
+                        .resolve(&r.target)
+                        .symbol()
+                        .map(|s| s.qualified_name.to_string()),
+                },
+                TypeRefKind::Chain(chain) => {
+                    let last = chain.parts.last()?;
+                    if let Some(target) = &last.resolved_target {
+                        return Some(target.to_string());
+                    }
+                    let parts: Vec<Arc<str>> =
+                        chain.parts.iter().map(|p| p.target.clone()).collect();
+                    self.index
+                        .resolve_feature_chain_member(scope, &parts, parts.len() - 1)
+                        .map(|member| member.to_string())
+                }
+            })
+            .collect()
+    }
+
+    /// The outermost calculation or constraint usage `name` is, or is
+    /// nested in.
+    fn enclosing(&self, name: &str) -> Option<&HirSymbol> {
+        let mut outermost = None;
+        let mut current = name;
+        loop {
+            let symbol = self.index.lookup_qualified(current).filter(|s| {
+                matches!(
+                    s.kind,
+                    SymbolKind::CalculationUsage | SymbolKind::ConstraintUsage
+                )
+            });
+            if symbol.is_some() {
+                outermost = symbol;
+            }
+            match current.rsplit_once("::") {
+                Some((owner, _)) => current = owner,
+                None => return outermost,
+            }
+        }
+    }
+
+    /// Whether the attribute `symbol` is given a value (`= ...`, `:= ...`).
+    fn has_value(&self, symbol: &HirSymbol) -> bool {
+        let declaration = self.declaration(symbol);
+        let head = declaration.split(['{', ';']).next().unwrap_or_default();
+        let chars: Vec<char> = head.chars().collect();
+        chars.iter().enumerate().any(|(i, c)| {
+            *c == '='
+                && chars.get(i + 1) != Some(&'=')
+                && !matches!(
+                    i.checked_sub(1).map(|p| chars[p]),
+                    Some('=' | '<' | '>' | '!')
+                )
+        })
+    }
+
+    /// Whether the constraint `symbol`, or a definition typing it, states
+    /// an equality.
+    fn is_equation(&self, symbol: &HirSymbol) -> bool {
+        if self.declaration(symbol).contains("==") {
+            return true;
+        }
+        let resolver = self.index.resolver_for_scope(&symbol.qualified_name);
+        symbol
+            .type_refs
+            .iter()
+            .flat_map(|r| r.as_refs())
+            .filter(|r| r.kind == RefKind::TypedBy)
+            .filter_map(|r| match &r.resolved_target {
+                Some(target) => self.index.lookup_qualified(target),
+                None => resolver.resolve(&r.target).symbol(),
+            })
+            .any(|definition| self.declaration(definition).contains("=="))
+    }
+
+    /// The source of `symbol` from its name to the end of its declaration:
+    /// the `;` ending it, or the brace closing its body.
+    fn declaration(&self, symbol: &HirSymbol) -> &str {
+        let Some(text) = self.sources.get(&symbol.file) else {
+            return "";
+        };
+        let start: usize = text
+            .split_inclusive('\n')
+            .take(symbol.start_line as usize)
+            .map(str::len)
+            .sum::<usize>()
+            + symbol.start_col as usize;
+        let Some(rest) = text.get(start..) else {
+            return "";
+        };
+        let mut depth = 0usize;
+        for (i, c) in rest.char_indices() {
+            match c {
+                ';' if depth == 0 => return &rest[..=i],
+                '{' => depth += 1,
+                '}' if depth <= 1 => return &rest[..=i],
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+        rest
+    }
+}
+
+/// The owner of a qualified name.
+fn owner(name: &str) -> &str {
+    name.rsplit_once("::").map_or("", |(owner, _)| owner)
+}
+
+/// `text` as a quoted DOT identifier.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
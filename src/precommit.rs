@@ -0,0 +1,153 @@
+//! Pre-commit integration.
+//!
+//! `--staged` analyzes what is about to be committed: the models are loaded
+//! from the git index rather than the working tree, so unstaged edits do not
+//! mask or cause failures. Every model file in the index is loaded so
+//! references resolve, but only diagnostics in staged files are reported.
+
+use super::history::{git, read_blobs};
+use super::shadowing::import_diagnostics;
+use super::{AnalysisOptions, AnalysisResult, build_result, collect_diagnostics, sort_diagnostics};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use syster::ide::AnalysisHost;
+
+/// Marks hooks written by [`install_pre_commit_hook`].
+const HOOK_MARKER: &str = "# syster pre-commit hook";
+
+/// Analyze the staged version of the models under `input`, reporting only
+/// files with staged changes.
+///
+/// Of `options`, only verbosity and the standard library settings apply.
+///
+/// # Arguments
+/// * `input` - Model directory inside a git work tree
+/// * `options` - Verbosity and standard library to load
+pub fn run_analysis_staged(
+    input: &Path,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResult, String> {
+    let verbose = options.verbose;
+    let dir = if input.is_dir() {
+        input
+    } else {
+        input.parent().unwrap_or(Path::new("."))
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+
+    // Added, copied, modified or renamed model files, relative to `dir`
+    let staged = git(
+        dir,
+        &[
+            "diff",
+            "--cached",
+            "--name-only",
+            "--relative",
+            "--diff-filter=ACMR",
+            "-z",
+        ],
+    )?;
+    let staged: HashSet<&str> = staged.split('\0').filter(|p| is_model_path(p)).collect();
+    if verbose {
+        tracing::info!("{} staged model file(s)", staged.len());
+    }
+    if staged.is_empty() {
+        return Ok(build_result(0, 0, Vec::new()));
+    }
+
+    // Index entries: "<mode> <oid> <stage>\t<path>"
+    let index = git(dir, &["ls-files", "--stage", "-z", "."])?;
+    let blobs: Vec<(&str, &str)> = index
+        .split('\0')
+        .filter_map(|entry| {
+            let (meta, path) = entry.split_once('\t')?;
+            let oid = meta.split_whitespace().nth(1)?;
+            is_model_path(path).then_some((oid, path))
+        })
+        .collect();
+
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), verbose)?;
+    }
+    let mut reported = Vec::new();
+    for (path, content) in read_blobs(dir, &blobs)? {
+        let full_path = dir.join(&path).to_string_lossy().to_string();
+        if verbose {
+            tracing::info!("Loading (staged): {}", full_path);
+        }
+        host.set_file_content(&full_path, &content);
+        if staged.contains(path.as_str()) {
+            reported.push(full_path);
+        }
+    }
+    let _analysis = host.analysis();
+
+    let symbol_count = reported
+        .iter()
+        .filter_map(|p| host.get_file_id(p))
+        .map(|id| host.symbol_index().symbols_in_file(id).len())
+        .sum();
+    let mut diagnostics: Vec<_> = collect_diagnostics(&host)
+        .into_iter()
+        .filter(|d| reported.contains(&d.file))
+        .collect();
+    let staged_files: HashSet<&str> = reported.iter().map(|p| p.as_str()).collect();
+    diagnostics.extend(import_diagnostics(&host, &staged_files));
+    sort_diagnostics(&mut diagnostics);
+    Ok(build_result(reported.len(), symbol_count, diagnostics))
+}
+
+/// Whether `path` names a SysML or KerML file.
+fn is_model_path(path: &str) -> bool {
+    path.ends_with(".sysml") || path.ends_with(".kerml")
+}
+
+/// Install a git pre-commit hook running `syster <dir> --staged` in the
+/// repository containing `dir`.
+///
+/// An existing hook not written by syster is only replaced with `force`.
+/// Returns the path of the hook.
+pub fn install_pre_commit_hook(dir: &Path, force: bool) -> Result<PathBuf, String> {
+    // The hooks path is relative to `dir` unless core.hooksPath is absolute
+    let prefix = git(dir, &["rev-parse", "--show-prefix"])?;
+    let hooks = git(dir, &["rev-parse", "--git-path", "hooks"])?;
+    let hooks = dir.join(hooks.trim());
+
+    let hook = hooks.join("pre-commit");
+    if let Ok(existing) = std::fs::read_to_string(&hook) {
+        if !force && !existing.contains(HOOK_MARKER) {
+            return Err(format!(
+                "{} already exists; use --force to replace it",
+                hook.display()
+            ));
+        }
+    }
+
+    // Hooks run from the top of the work tree
+    let models = match prefix.trim().trim_end_matches('/') {
+        "" => ".",
+        prefix => prefix,
+    };
+    let script = format!(
+        "#!/bin/sh\n{}\n# Checks the staged SysML/KerML files before each commit.\nexec syster '{}' --staged\n",
+        HOOK_MARKER, models
+    );
+    std::fs::create_dir_all(&hooks)
+        .map_err(|e| format!("Failed to create {}: {}", hooks.display(), e))?;
+    std::fs::write(&hook, script)
+        .map_err(|e| format!("Failed to write {}: {}", hook.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", hook.display(), e))?;
+    }
+
+    Ok(hook)
+}
@@ -0,0 +1,96 @@
+//! The project file, `syster.toml`.
+//!
+//! A project's settings live in one `syster.toml` next to its models or in
+//! a directory above them, one table per feature:
+//!
+//! ```toml
+//! sysml_version = "2025-04"
+//!
+//! [lint.naming]
+//! acronyms = ["ECU"]
+//!
+//! [budgets]
+//! max_file_lines = 500
+//! ```
+//!
+//! Each module reads its own table with [`load_table`]. Keys no module
+//! reads, at the top level or inside a table, are errors rather than
+//! silently ignored, so a misspelled setting is reported wherever it is.
+
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use toml::Table;
+
+/// Name of the project configuration file.
+pub const PROJECT_FILE: &str = "syster.toml";
+
+/// Keys a project file may set, each read by one module.
+const KEYS: &[&str] = &[
+    "allocations",
+    "arch",
+    "budgets",
+    "dependencies",
+    "layers",
+    "lint.naming",
+    "publish.jira",
+    "requirements",
+    "stdlib",
+    "sysml_version",
+];
+
+/// The nearest `syster.toml` at or above `input`.
+pub fn find_project_file(input: &Path) -> Option<PathBuf> {
+    let dir = if input.is_dir() {
+        input
+    } else {
+        input
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+    };
+    let dir = std::path::absolute(dir).ok()?;
+    dir.ancestors()
+        .map(|d| d.join(PROJECT_FILE))
+        .find(|p| p.is_file())
+}
+
+/// Read the value of `key` (dotted for nested tables, e.g. `lint.naming`)
+/// from the project file at `path`, if the file sets it.
+pub(crate) fn load_table<T: DeserializeOwned>(path: &Path, key: &str) -> Result<Option<T>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut table: Table =
+        toml::from_str(&text).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    check_keys(&table, "").map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+    let (parents, name) = key.rsplit_once('.').unwrap_or(("", key));
+    for parent in parents.split('.').filter(|p| !p.is_empty()) {
+        match table.remove(parent) {
+            Some(toml::Value::Table(inner)) => table = inner,
+            _ => return Ok(None),
+        }
+    }
+    table
+        .remove(name)
+        .map(|value| value.try_into())
+        .transpose()
+        .map_err(|e| format!("Failed to parse {}: [{}]: {}", path.display(), key, e))
+}
+
+/// Fail on a key of `table`, found under `prefix`, that no module reads.
+fn check_keys(table: &Table, prefix: &str) -> Result<(), String> {
+    for (name, value) in table {
+        let key = format!("{}{}", prefix, name);
+        if KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let nested = format!("{}.", key);
+        match value {
+            toml::Value::Table(inner) if KEYS.iter().any(|k| k.starts_with(&nested)) => {
+                check_keys(inner, &nested)?
+            }
+            _ => return Err(format!("unknown key `{}`", key)),
+        }
+    }
+    Ok(())
+}
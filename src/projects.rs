@@ -0,0 +1,214 @@
+//! Per-project analysis of monorepos (`--per-project`).
+//!
+//! A repository holding the models of several teams gives each its own
+//! `syster.toml`. Analyzed as one, their diagnostics are mixed and the
+//! settings of the outermost project file apply to all of them.
+//! [`analyze_projects`] instead analyzes every directory under the input
+//! holding a `syster.toml` on its own: without the files of the projects
+//! nested in it, and with the budgets, rules, libraries, SysML release and
+//! `MODELOWNERS` governing that directory. The results are kept per project
+//! and merged into an aggregate, as [`merge_results`](super::merge_results)
+//! merges shards.
+//!
+//! The input itself is a project too, named `.`, if it holds a `syster.toml`
+//! or model files outside every nested project.
+//!
+//! [`export_projects`] exports the same projects, each to a model of its
+//! own, with the whole input exported as one model for the aggregate.
+
+#[cfg(feature = "interchange")]
+use super::ExportFilter;
+use super::budgets::Budgets;
+use super::deps::Manifest;
+use super::language::SysmlVersion;
+use super::layers::LayerRules;
+use super::owners::ModelOwners;
+use super::project::PROJECT_FILE;
+use super::requirements::RequirementRules;
+use super::systerignore::IgnoreRules;
+use super::{AnalysisOptions, AnalysisResult, WalkOptions};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// The analysis of one project.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectResult {
+    /// Project directory relative to the input, `.` for the input itself.
+    pub name: String,
+    /// Project directory.
+    pub path: String,
+    /// Analysis of the project's own files.
+    pub result: AnalysisResult,
+}
+
+/// The analyses of the projects under an input.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectsResult {
+    /// Projects, by name.
+    pub projects: Vec<ProjectResult>,
+    /// All projects' results merged.
+    pub aggregate: AnalysisResult,
+}
+
+/// The export of one project.
+#[cfg(feature = "interchange")]
+#[derive(Debug, Clone)]
+pub struct ProjectExport {
+    /// Project directory relative to the input, `.` for the input itself.
+    pub name: String,
+    /// The exported model.
+    pub bytes: Vec<u8>,
+}
+
+/// The exports of the projects under an input.
+#[cfg(feature = "interchange")]
+#[derive(Debug, Clone)]
+pub struct ProjectsExport {
+    /// Projects, by name.
+    pub projects: Vec<ProjectExport>,
+    /// The whole input exported as one model.
+    pub aggregate: Vec<u8>,
+}
+
+/// Directories below `root` holding a `syster.toml`, sorted, walking as
+/// `walk` says.
+pub fn find_projects(root: &Path, walk: &WalkOptions) -> Result<Vec<PathBuf>, String> {
+    let mut walker = WalkDir::new(root).follow_links(walk.follow_symlinks);
+    if let Some(depth) = walk.max_depth {
+        walker = walker.max_depth(depth);
+    }
+    let mut ignored = IgnoreRules::new(root, walk.respect_gitignore);
+    let mut projects = Vec::new();
+    for entry in walker.into_iter().filter_entry(|e| {
+        e.depth() == 0
+            || (e.file_type().is_dir()
+                && e.file_name() != ".syster"
+                && !ignored.is_ignored(e.path(), true))
+    }) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) if e.loop_ancestor().is_some() => continue,
+            Err(e) => return Err(format!("Walk error: {}", e)),
+        };
+        if entry.depth() > 0 && entry.path().join(PROJECT_FILE).is_file() {
+            projects.push(entry.into_path());
+        }
+    }
+    projects.sort();
+    Ok(projects)
+}
+
+/// Analyze each project under the directory `input` on its own.
+///
+/// Every project gets `options`, with the settings of its own project file
+/// and `MODELOWNERS` in place of those passed; a SysML release set in
+/// `options` still wins over the project files'.
+pub fn analyze_projects(input: &Path, options: &AnalysisOptions) -> Result<ProjectsResult, String> {
+    let mut projects = Vec::new();
+    for (name, dir) in project_dirs(input, options)? {
+        let _span = tracing::info_span!("project", name = %name).entered();
+        if options.verbose {
+            tracing::info!("Analyzing project {}", name);
+        }
+        let result = super::analyze(&dir, &project_options(&dir, options)?)
+            .map_err(|e| format!("{}: {}", name, e))?;
+        projects.push(ProjectResult {
+            name,
+            path: dir.to_string_lossy().to_string(),
+            result,
+        });
+    }
+
+    let aggregate = super::merge_results(projects.iter().map(|p| p.result.clone()).collect());
+    Ok(ProjectsResult {
+        projects,
+        aggregate,
+    })
+}
+
+/// Export each project under the directory `input` on its own, with the
+/// settings [`analyze_projects`] gives it, and the whole input as one model
+/// for the aggregate.
+#[cfg(feature = "interchange")]
+pub fn export_projects(
+    input: &Path,
+    format: &str,
+    options: &AnalysisOptions,
+    self_contained: bool,
+    filter: &ExportFilter,
+) -> Result<ProjectsExport, String> {
+    let mut projects = Vec::new();
+    for (name, dir) in project_dirs(input, options)? {
+        let _span = tracing::info_span!("project", name = %name).entered();
+        if options.verbose {
+            tracing::info!("Exporting project {}", name);
+        }
+        let options = project_options(&dir, options)?;
+        let (bytes, _) =
+            super::export_model_with_options(&dir, format, &options, self_contained, filter)
+                .map_err(|e| format!("{}: {}", name, e))?;
+        projects.push(ProjectExport { name, bytes });
+    }
+
+    let (aggregate, _) =
+        super::export_model_with_options(input, format, options, self_contained, filter)?;
+    Ok(ProjectsExport {
+        projects,
+        aggregate,
+    })
+}
+
+/// The projects under the directory `input`, named by their directory
+/// relative to it.
+fn project_dirs(input: &Path, options: &AnalysisOptions) -> Result<Vec<(String, PathBuf)>, String> {
+    if !input.is_dir() {
+        return Err(format!(
+            "Per-project analysis needs a directory: {}",
+            input.display()
+        ));
+    }
+    let walk = WalkOptions {
+        skip_nested_projects: true,
+        ..options.walk
+    };
+    let mut dirs = Vec::new();
+    if input.join(PROJECT_FILE).is_file() || !super::model_files(input, false, &walk)?.is_empty() {
+        dirs.push(input.to_path_buf());
+    }
+    dirs.extend(find_projects(input, &options.walk)?);
+
+    Ok(dirs
+        .into_iter()
+        .map(|dir| {
+            let name = match dir.strip_prefix(input) {
+                Ok(relative) if !relative.as_os_str().is_empty() => {
+                    relative.to_string_lossy().replace('\\', "/")
+                }
+                _ => ".".to_string(),
+            };
+            (name, dir)
+        })
+        .collect())
+}
+
+/// `options` with the settings governing the project at `dir`.
+fn project_options<'a>(
+    dir: &Path,
+    options: &AnalysisOptions<'a>,
+) -> Result<AnalysisOptions<'a>, String> {
+    let mut options = options.clone();
+    options.walk.skip_nested_projects = true;
+    options.layers = LayerRules::discover(dir)?;
+    options.requirements = RequirementRules::discover(dir)?;
+    options.budgets = Budgets::discover(dir)?;
+    options.owners = ModelOwners::discover(dir)?;
+    options.libraries = match Manifest::discover(dir)? {
+        Some(manifest) => manifest.library_paths()?,
+        None => Vec::new(),
+    };
+    if options.sysml_version.is_none() {
+        options.sysml_version = SysmlVersion::discover(dir)?;
+    }
+    Ok(options)
+}
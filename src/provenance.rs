@@ -0,0 +1,335 @@
+//! Provenance records for exported artifacts.
+//!
+//! Every XMI, JSON-LD and KPAR export says which tool produced it, when, from
+//! which sources and against which standard library, so a delivered file can
+//! be traced back to its origin. It also stamps the interchange schema
+//! version, which `syster migrate` upgrades older files from. The record is
+//! stored where each format's readers ignore it: a comment after the XML
+//! declaration, an extra object without `@id` in JSON-LD, and a
+//! `META-INF/provenance.json` archive entry.
+
+use super::kpar::{read_entries, write_entries};
+use super::to_hex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use walkdir::WalkDir;
+
+/// Provenance entry inside a KPAR archive.
+pub const PROVENANCE_PATH: &str = "META-INF/provenance.json";
+
+/// Marker that starts the provenance comment in an XMI file.
+const XMI_MARKER: &str = "<!-- syster-provenance ";
+
+/// `@type` of the provenance object in JSON-LD output.
+const JSONLD_TYPE: &str = "syster:Provenance";
+
+/// Version of the interchange schema exports are written in. Bump it, with
+/// a migration in [`migrate`](super::migrate), whenever what an export
+/// contains changes in a way older files need upgrading for.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Where an exported artifact came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Name of the exporting tool.
+    pub tool: String,
+    /// Version of the exporting tool.
+    pub version: String,
+    /// Export time as RFC 3339 UTC (from `SOURCE_DATE_EPOCH` when set).
+    pub timestamp: String,
+    /// SHA-256 over the input's SysML/KerML files and their relative paths.
+    pub input_hash: String,
+    /// Version of the standard library the model was analyzed against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdlib_version: Option<String>,
+    /// Interchange schema version of the export (see [`SCHEMA_VERSION`]).
+    /// Records from before it was stamped read as 1.
+    #[serde(default = "unversioned")]
+    pub schema_version: u32,
+}
+
+fn unversioned() -> u32 {
+    1
+}
+
+impl Provenance {
+    /// Collect provenance for an export of `input`.
+    ///
+    /// # Arguments
+    /// * `input` - File or directory that was exported
+    /// * `load_stdlib` - Whether the standard library was loaded
+    /// * `stdlib_path` - Optional custom path to the standard library
+    pub fn collect(
+        input: &Path,
+        load_stdlib: bool,
+        stdlib_path: Option<&Path>,
+    ) -> Result<Self, String> {
+        let stdlib_version = if load_stdlib {
+            super::find_stdlib_dir(stdlib_path)?
+                .map(|dir| stdlib_version(&dir))
+                .transpose()?
+        } else {
+            None
+        };
+
+        Ok(Self {
+            tool: "syster".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: timestamp()?,
+            input_hash: hash_sources(input)?,
+            stdlib_version,
+            schema_version: SCHEMA_VERSION,
+        })
+    }
+
+    /// Provenance for a file syster rewrote without its sources, such as a
+    /// migrated export: the input hash covers the original file's bytes.
+    pub(crate) fn for_file(bytes: &[u8]) -> Result<Self, String> {
+        Ok(Self {
+            tool: "syster".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: timestamp()?,
+            input_hash: format!("sha256:{}", to_hex(&Sha256::digest(bytes))),
+            stdlib_version: None,
+            schema_version: SCHEMA_VERSION,
+        })
+    }
+
+    /// Read the provenance record embedded in an exported XMI or JSON-LD file.
+    pub fn from_export(bytes: &[u8]) -> Option<Self> {
+        let text = std::str::from_utf8(bytes).ok()?;
+        if let Some(start) = text.find(XMI_MARKER) {
+            let rest = &text[start + XMI_MARKER.len()..];
+            let end = rest.find("-->")?;
+            return serde_json::from_str(rest[..end].trim()).ok();
+        }
+
+        let value: Value = serde_json::from_str(text).ok()?;
+        let items = match value {
+            Value::Array(items) => items,
+            other => vec![other],
+        };
+        items
+            .into_iter()
+            .find(|item| item.get("@type").and_then(Value::as_str) == Some(JSONLD_TYPE))
+            .and_then(|item| serde_json::from_value(item).ok())
+    }
+}
+
+/// Embed `provenance` into exported bytes of the given format.
+///
+/// YAML and standard JSON output are returned unchanged.
+pub fn embed_provenance(
+    bytes: Vec<u8>,
+    format: &str,
+    provenance: &Provenance,
+) -> Result<Vec<u8>, String> {
+    match format.to_lowercase().as_str() {
+        "xmi" => Ok(embed_xmi(bytes, provenance)),
+        "kpar" => embed_kpar(&bytes, provenance),
+        "jsonld" | "json-ld" => embed_jsonld(&bytes, provenance),
+        _ => Ok(bytes),
+    }
+}
+
+/// Replace the provenance record of exported bytes of the given format, or
+/// embed one if they have none.
+pub(crate) fn replace_provenance(
+    bytes: Vec<u8>,
+    format: &str,
+    provenance: &Provenance,
+) -> Result<Vec<u8>, String> {
+    let bytes = match format.to_lowercase().as_str() {
+        "xmi" => strip_xmi(bytes),
+        "jsonld" | "json-ld" => strip_jsonld(bytes)?,
+        // The KPAR entry is replaced anyway
+        _ => bytes,
+    };
+    embed_provenance(bytes, format, provenance)
+}
+
+/// Remove the provenance comment (and the line break before it) from XMI.
+fn strip_xmi(bytes: Vec<u8>) -> Vec<u8> {
+    let Some(start) = bytes
+        .windows(XMI_MARKER.len())
+        .position(|w| w == XMI_MARKER.as_bytes())
+    else {
+        return bytes;
+    };
+    let Some(end) = bytes[start..]
+        .windows(3)
+        .position(|w| w == b"-->")
+        .map(|i| start + i + 3)
+    else {
+        return bytes;
+    };
+    let start = if start > 0 && bytes[start - 1] == b'\n' {
+        start - 1
+    } else {
+        start
+    };
+    let end = if start == 0 && bytes.get(end) == Some(&b'\n') {
+        end + 1
+    } else {
+        end
+    };
+    let mut stripped = bytes[..start].to_vec();
+    stripped.extend_from_slice(&bytes[end..]);
+    stripped
+}
+
+/// Remove the provenance object from JSON-LD.
+fn strip_jsonld(bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    let value: Value =
+        serde_json::from_slice(&bytes).map_err(|e| format!("Invalid JSON-LD: {}", e))?;
+    let Value::Array(mut items) = value else {
+        return Ok(bytes);
+    };
+    items.retain(|item| item.get("@type").and_then(Value::as_str) != Some(JSONLD_TYPE));
+    let value = match items.len() {
+        1 => items.remove(0),
+        _ => Value::Array(items),
+    };
+    serde_json::to_vec_pretty(&value).map_err(|e| format!("Failed to serialize JSON-LD: {}", e))
+}
+
+/// The XML comment carrying `provenance` in an XMI file.
+pub(crate) fn xmi_comment(provenance: &Provenance) -> String {
+    // "--" may not appear inside an XML comment; escape it within JSON strings
+    let json = serde_json::to_string(provenance)
+        .unwrap_or_default()
+        .replace("--", "-\\u002d");
+    format!("{}{} -->", XMI_MARKER, json)
+}
+
+/// Insert the provenance comment after the XML declaration.
+fn embed_xmi(bytes: Vec<u8>, provenance: &Provenance) -> Vec<u8> {
+    let comment = xmi_comment(provenance);
+
+    let declaration_end = bytes
+        .starts_with(b"<?xml")
+        .then(|| bytes.windows(2).position(|w| w == b"?>"))
+        .flatten()
+        .map(|i| i + 2);
+
+    let mut embedded = Vec::with_capacity(bytes.len() + comment.len() + 1);
+    match declaration_end {
+        Some(at) => {
+            embedded.extend_from_slice(&bytes[..at]);
+            embedded.push(b'\n');
+            embedded.extend_from_slice(comment.as_bytes());
+            embedded.extend_from_slice(&bytes[at..]);
+        }
+        None => {
+            embedded.extend_from_slice(comment.as_bytes());
+            embedded.push(b'\n');
+            embedded.extend_from_slice(&bytes);
+        }
+    }
+    embedded
+}
+
+/// Add a provenance entry to a KPAR archive, replacing any existing one.
+fn embed_kpar(bytes: &[u8], provenance: &Provenance) -> Result<Vec<u8>, String> {
+    let json = serde_json::to_vec_pretty(provenance)
+        .map_err(|e| format!("Failed to serialize provenance: {}", e))?;
+
+    let mut entries = read_entries(bytes)?;
+    entries.retain(|(name, _)| name != PROVENANCE_PATH);
+    entries.push((PROVENANCE_PATH.to_string(), json));
+    write_entries(&entries)
+}
+
+/// Append a provenance object to JSON-LD output.
+///
+/// The object has no `@id`, so readers skip it when building the model.
+fn embed_jsonld(bytes: &[u8], provenance: &Provenance) -> Result<Vec<u8>, String> {
+    let value: Value =
+        serde_json::from_slice(bytes).map_err(|e| format!("Invalid JSON-LD output: {}", e))?;
+    let mut record = serde_json::to_value(provenance)
+        .map_err(|e| format!("Failed to serialize provenance: {}", e))?;
+    if let Value::Object(fields) = &mut record {
+        fields.insert("@type".to_string(), Value::String(JSONLD_TYPE.to_string()));
+    }
+
+    let mut items = match value {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+    items.push(record);
+
+    serde_json::to_vec_pretty(&Value::Array(items))
+        .map_err(|e| format!("Failed to serialize JSON-LD: {}", e))
+}
+
+/// Export time, honouring `SOURCE_DATE_EPOCH` for reproducible builds.
+pub(crate) fn timestamp() -> Result<String, String> {
+    let seconds = match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(value) => value
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid SOURCE_DATE_EPOCH: {}", value))?,
+        Err(_) => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+    Ok(super::format_rfc3339(seconds))
+}
+
+/// Hash the SysML/KerML files under `input` in path order.
+pub(crate) fn hash_sources(input: &Path) -> Result<String, String> {
+    let mut files: Vec<PathBuf> = if input.is_dir() {
+        WalkDir::new(input)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.into_path())
+            .filter(|p| super::is_sysml_file(p))
+            .collect()
+    } else {
+        vec![input.to_path_buf()]
+    };
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in &files {
+        let relative = file.strip_prefix(input).unwrap_or(file);
+        let name = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let content =
+            std::fs::read(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+
+        hasher.update(name.as_bytes());
+        hasher.update([0]);
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    Ok(format!("sha256:{}", to_hex(&hasher.finalize())))
+}
+
+/// Version of a standard library directory.
+///
+/// Uses the `version` from the library's `.project.json` when present, and
+/// a content hash otherwise.
+fn stdlib_version(dir: &Path) -> Result<String, String> {
+    let declared = std::fs::read_to_string(dir.join(".project.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .and_then(|project| {
+            project
+                .get("version")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        });
+    match declared {
+        Some(version) => Ok(version),
+        None => hash_sources(dir),
+    }
+}
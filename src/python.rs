@@ -0,0 +1,111 @@
+//! Python bindings (feature `python`).
+//!
+//! Builds the `syster_cli` extension module with PyO3, e.g. with
+//! `maturin develop` (see `pyproject.toml`). Results are the dicts and
+//! lists of their `--json` output, ready for pandas:
+//!
+//! ```python
+//! import pandas, syster_cli
+//!
+//! result = syster_cli.analyze("models/", stdlib=False)
+//! pandas.DataFrame(result["diagnostics"])
+//! ```
+//!
+//! `analyze` takes the keyword options `stdlib` (default `True`, as on the
+//! command line), `stdlib_path`, `strict_parse` and `verbose`; `export`
+//! takes the same and returns the exported file as `bytes`.
+
+use super::{
+    AnalysisOptions, ExportFilter, analyze as analyze_input, export_model_with_options,
+    semver_check,
+};
+use pyo3::exceptions::{PyRuntimeError, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Analyze a file or directory; returns the `--json` result as a dict.
+#[pyfunction]
+#[pyo3(signature = (path, **options))]
+fn analyze(
+    py: Python<'_>,
+    path: PathBuf,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyObject> {
+    let options = analysis_options("analyze", options)?;
+    let result = analyze_input(&path, &options).map_err(PyRuntimeError::new_err)?;
+    to_python(py, &result)
+}
+
+/// Export a file or directory to an interchange format (`xmi`, `kpar`,
+/// `jsonld`, `json`, `yaml`); returns the exported file's bytes.
+#[pyfunction]
+#[pyo3(signature = (path, format, **options))]
+fn export(
+    py: Python<'_>,
+    path: PathBuf,
+    format: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyObject> {
+    let options = analysis_options("export", options)?;
+    let (bytes, _) =
+        export_model_with_options(&path, format, &options, false, &ExportFilter::default())
+            .map_err(PyRuntimeError::new_err)?;
+    Ok(PyBytes::new_bound(py, &bytes).into_any().unbind())
+}
+
+/// Compare two releases of a model; returns the `syster semver` report
+/// as a dict.
+#[pyfunction]
+fn diff(py: Python<'_>, a: PathBuf, b: PathBuf) -> PyResult<PyObject> {
+    let report = semver_check(&a, &b, None, None, false).map_err(PyRuntimeError::new_err)?;
+    to_python(py, &report)
+}
+
+/// The `syster_cli` Python module.
+#[pymodule]
+fn syster_cli(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add_function(wrap_pyfunction!(analyze, m)?)?;
+    m.add_function(wrap_pyfunction!(export, m)?)?;
+    m.add_function(wrap_pyfunction!(diff, m)?)?;
+    Ok(())
+}
+
+/// Analysis options from the keyword arguments of `function`.
+fn analysis_options(
+    function: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<AnalysisOptions<'static>> {
+    let mut result = AnalysisOptions {
+        load_stdlib: true,
+        ..AnalysisOptions::default()
+    };
+    for (key, value) in options.into_iter().flat_map(|options| options.iter()) {
+        let key: String = key.extract()?;
+        match key.as_str() {
+            "stdlib" => result.load_stdlib = value.extract()?,
+            "stdlib_path" => result.stdlib_path = value.extract()?,
+            "strict_parse" => result.strict_parse = value.extract()?,
+            "verbose" => result.verbose = value.extract()?,
+            _ => {
+                return Err(PyTypeError::new_err(format!(
+                    "{}() got an unexpected keyword argument '{}'",
+                    function, key
+                )));
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Convert through JSON, so Python sees exactly what `--json` writes.
+fn to_python<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value)
+        .map_err(|e| PyRuntimeError::new_err(format!("Failed to serialize result: {}", e)))?;
+    Ok(py
+        .import_bound("json")?
+        .call_method1("loads", (json,))?
+        .unbind())
+}
@@ -0,0 +1,309 @@
+//! Partial-failure recovery for large imports (`--continue-on-error`).
+//!
+//! The XMI reader gives up on the first element it cannot read, so one bad
+//! attribute in a multi-gigabyte file aborts the whole import. Recovery
+//! scans the document first and cuts out every element that would fail,
+//! with everything nested in it, recording where it was and why. Errors
+//! that leave the document's structure unknown, such as a mismatched end
+//! tag, still fail the file; in a KPAR they skip the entry instead.
+
+use super::ImportOptions;
+use super::kpar::read_entries;
+use quick_xml::Reader;
+use quick_xml::events::{BytesStart, Event};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+use syster::interchange::{Model, ModelFormat, Xmi};
+
+/// Directory of the model entries in a KPAR archive.
+const MODEL_DIR: &str = "model/";
+
+/// Content left out of an import because it could not be read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedContent {
+    /// Where it starts, as `file:line:column`; a KPAR entry is named as
+    /// `archive!entry`.
+    pub location: String,
+    /// The element's tag and type, or the KPAR entry's name.
+    pub element: String,
+    /// The element's `xmi:id`, if it could be read.
+    pub id: Option<String>,
+    /// Why it was skipped.
+    pub reason: String,
+    /// Number of elements nested in it, skipped with it.
+    pub nested: usize,
+}
+
+impl fmt::Display for SkippedContent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Skipped {}", self.element)?;
+        if let Some(id) = &self.id {
+            write!(f, " '{}'", id)?;
+        }
+        write!(f, " at {}: {}", self.location, self.reason)?;
+        if self.nested > 0 {
+            write!(f, " (with {} nested elements)", self.nested)?;
+        }
+        Ok(())
+    }
+}
+
+/// Cut the elements that cannot be read out of an XMI document, with
+/// everything nested in them.
+///
+/// `source` names the document in locations. Fails on errors that leave
+/// the document's structure unknown.
+///
+/// # Returns
+/// The readable document (`bytes` unchanged if nothing was skipped) and
+/// what was skipped, in document order.
+pub(crate) fn recover_xmi(
+    bytes: Vec<u8>,
+    source: &str,
+    verbose: bool,
+) -> Result<(Vec<u8>, Vec<SkippedContent>), String> {
+    let mut lines = LineCounter::default();
+    let mut progress = Progress::new(source, bytes.len(), verbose);
+    let mut seen_ids = HashSet::new();
+    let mut skipped = Vec::new();
+    let mut cuts = Vec::new();
+
+    let mut reader = Reader::from_reader(bytes.as_slice());
+    loop {
+        let start = reader.buffer_position() as usize;
+        let event = reader.read_event().map_err(|e| {
+            let at = lines.position(&bytes, reader.error_position() as usize);
+            format!("{}:{}: {}", source, at, e)
+        })?;
+        progress.update(reader.buffer_position() as usize);
+
+        let (tag, has_content) = match &event {
+            Event::Start(tag) => (tag, true),
+            Event::Empty(tag) => (tag, false),
+            Event::Eof => break,
+            _ => continue,
+        };
+        let Err(reason) = check_element(tag, &mut seen_ids) else {
+            continue;
+        };
+
+        let location = format!("{}:{}", source, lines.position(&bytes, start));
+        let nested = if has_content {
+            skip_content(&mut reader).map_err(|e| format!("{}: {}", location, e))?
+        } else {
+            0
+        };
+        cuts.push((start, reader.buffer_position() as usize));
+        skipped.push(SkippedContent {
+            location,
+            element: describe(tag),
+            id: attribute(tag, b"xmi:id"),
+            reason,
+            nested,
+        });
+    }
+
+    if cuts.is_empty() {
+        return Ok((bytes, skipped));
+    }
+    let mut recovered = Vec::with_capacity(bytes.len());
+    let mut from = 0;
+    for (start, end) in cuts {
+        recovered.extend_from_slice(&bytes[from..start]);
+        from = end;
+    }
+    recovered.extend_from_slice(&bytes[from..]);
+    Ok((recovered, skipped))
+}
+
+/// Read the model entries of a KPAR one at a time, reporting each when
+/// verbose.
+///
+/// With `continue_on_error`, each entry is recovered (see [`recover_xmi`])
+/// and an entry that cannot be read at all is skipped; otherwise the first
+/// error fails the import. `archive` names the archive in locations.
+pub(crate) fn read_kpar(
+    bytes: &[u8],
+    archive: &str,
+    options: &ImportOptions,
+) -> Result<(Model, Vec<SkippedContent>), String> {
+    let entries: Vec<(String, Vec<u8>)> = read_entries(bytes)?
+        .into_iter()
+        .filter(|(name, _)| name.starts_with(MODEL_DIR) && name.ends_with(".xmi"))
+        .collect();
+
+    let mut model = Model::new();
+    let mut skipped = Vec::new();
+    let total = entries.len();
+    for (i, (name, content)) in entries.into_iter().enumerate() {
+        if options.verbose {
+            tracing::info!("Reading entry {} of {}: {}", i + 1, total, name);
+        }
+        let source = format!("{}!{}", archive, name);
+        let entry = if options.continue_on_error {
+            let read = recover_xmi(content, &source, options.verbose).and_then(|(content, cut)| {
+                let entry = Xmi
+                    .read(&content)
+                    .map_err(|e| format!("{}: {}", source, e))?;
+                Ok((entry, cut))
+            });
+            match read {
+                Ok((entry, cut)) => {
+                    skipped.extend(cut);
+                    entry
+                }
+                Err(reason) => {
+                    skipped.push(SkippedContent {
+                        location: source,
+                        element: format!("entry {}", name),
+                        id: None,
+                        reason,
+                        nested: 0,
+                    });
+                    continue;
+                }
+            }
+        } else {
+            Xmi.read(&content)
+                .map_err(|e| format!("{}: {}", source, e))?
+        };
+        merge(&mut model, entry);
+    }
+    Ok((model, skipped))
+}
+
+/// Add an entry's model to the archive's, as the KPAR reader does: the
+/// first element with an ID wins.
+fn merge(model: &mut Model, entry: Model) {
+    for (id, element) in entry.elements {
+        model.elements.entry(id).or_insert(element);
+    }
+    model.relationships.extend(entry.relationships);
+    for root in entry.roots {
+        if !model.roots.contains(&root) {
+            model.roots.push(root);
+        }
+    }
+}
+
+/// Why the XMI reader would fail on this element, if it would; also fails
+/// an element reusing an `xmi:id`.
+fn check_element(tag: &BytesStart<'_>, seen_ids: &mut HashSet<String>) -> Result<(), String> {
+    std::str::from_utf8(tag.name().as_ref())
+        .map_err(|e| format!("tag name is not UTF-8: {}", e))?;
+
+    let mut id = None;
+    for attr in tag.attributes() {
+        let attr = attr.map_err(|e| format!("malformed attribute: {}", e))?;
+        let key = std::str::from_utf8(attr.key.as_ref())
+            .map_err(|e| format!("attribute name is not UTF-8: {}", e))?;
+        let value = attr
+            .unescape_value()
+            .map_err(|e| format!("invalid {} value: {}", key, e))?;
+        match key {
+            "xmi:id" | "id" => id = Some(value.into_owned()),
+            "elementId" if id.is_none() => id = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+
+    match id {
+        Some(id) if !seen_ids.insert(id.clone()) => Err(format!("duplicate xmi:id {}", id)),
+        _ => Ok(()),
+    }
+}
+
+/// Skip to the end tag of the element just started.
+///
+/// # Returns
+/// The number of elements nested in it.
+fn skip_content(reader: &mut Reader<&[u8]>) -> Result<usize, String> {
+    let mut depth = 1;
+    let mut nested = 0;
+    while depth > 0 {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(_) => {
+                depth += 1;
+                nested += 1;
+            }
+            Event::Empty(_) => nested += 1,
+            Event::End(_) => depth -= 1,
+            Event::Eof => return Err("unexpected end of file in skipped element".to_string()),
+            _ => {}
+        }
+    }
+    Ok(nested)
+}
+
+/// The tag, with the element's type if it has one.
+fn describe(tag: &BytesStart<'_>) -> String {
+    let name = String::from_utf8_lossy(tag.name().as_ref()).to_string();
+    match attribute(tag, b"xsi:type").or_else(|| attribute(tag, b"xmi:type")) {
+        Some(kind) => format!("{} ({})", name, kind),
+        None => name,
+    }
+}
+
+/// An attribute's value, if it can be read.
+fn attribute(tag: &BytesStart<'_>, key: &[u8]) -> Option<String> {
+    tag.attributes()
+        .filter_map(|attr| attr.ok())
+        .find(|attr| attr.key.as_ref() == key)
+        .and_then(|attr| attr.unescape_value().ok().map(|value| value.into_owned()))
+}
+
+/// Line and column of byte offsets, counted incrementally as offsets grow.
+#[derive(Default)]
+struct LineCounter {
+    offset: usize,
+    line: usize,
+    line_start: usize,
+}
+
+impl LineCounter {
+    fn position(&mut self, bytes: &[u8], offset: usize) -> String {
+        let offset = offset.min(bytes.len());
+        if offset < self.offset {
+            *self = Self::default();
+        }
+        for (i, byte) in bytes[self.offset..offset].iter().enumerate() {
+            if *byte == b'\n' {
+                self.line += 1;
+                self.line_start = self.offset + i + 1;
+            }
+        }
+        self.offset = offset;
+        format!("{}:{}", self.line + 1, offset - self.line_start + 1)
+    }
+}
+
+/// Reports how much of a document was scanned, every 10%.
+struct Progress<'a> {
+    source: &'a str,
+    total: usize,
+    next_percent: usize,
+    verbose: bool,
+}
+
+impl<'a> Progress<'a> {
+    fn new(source: &'a str, total: usize, verbose: bool) -> Self {
+        Self {
+            source,
+            total,
+            next_percent: 10,
+            verbose,
+        }
+    }
+
+    fn update(&mut self, offset: usize) {
+        if !self.verbose || self.total == 0 {
+            return;
+        }
+        let percent = offset.saturating_mul(100) / self.total;
+        if percent >= self.next_percent {
+            tracing::info!("Scanned {}% of {}", percent, self.source);
+            self.next_percent = (percent / 10 + 1) * 10;
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Redaction of exported models.
+//!
+//! Element names and documentation are replaced with pseudonyms while IDs,
+//! kinds, ownership and relationships stay as they are, so a model can be
+//! shared with a tool vendor to reproduce a problem without exposing its
+//! content. The mapping back to the original text is kept in a local JSON
+//! file and reused on later runs, so the same name always gets the same
+//! pseudonym.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use syster::interchange::{Element, ElementId, Model};
+
+/// Original text to pseudonym mapping.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RedactionMap {
+    /// Element names and short names.
+    #[serde(default)]
+    pub names: BTreeMap<String, String>,
+    /// Documentation bodies.
+    #[serde(default)]
+    pub documentation: BTreeMap<String, String>,
+}
+
+impl RedactionMap {
+    /// Load a mapping file, or start an empty mapping if it does not exist.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+
+    /// Write the mapping file.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize redaction map: {}", e))?;
+        std::fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Pseudonym for a name, allocating a new one if needed.
+    fn name(&mut self, original: &str) -> String {
+        let next = self.names.len() + 1;
+        self.names
+            .entry(original.to_string())
+            .or_insert_with(|| format!("N{}", next))
+            .clone()
+    }
+
+    /// Placeholder for a documentation body, allocating a new one if needed.
+    fn doc(&mut self, original: &str) -> String {
+        let next = self.documentation.len() + 1;
+        self.documentation
+            .entry(original.to_string())
+            .or_insert_with(|| format!("Redacted documentation D{}", next))
+            .clone()
+    }
+}
+
+/// Replace names and documentation in `model` with pseudonyms from `map`.
+///
+/// Elements whose qualified name is in `keep` (e.g. standard library
+/// elements in a self-contained export) are left untouched. New names are
+/// numbered in sorted order so the result does not depend on element order.
+pub fn redact_model(model: &mut Model, map: &mut RedactionMap, keep: &HashSet<String>) {
+    let redacted = |e: &Element| {
+        e.qualified_name
+            .as_deref()
+            .is_none_or(|q| !keep.contains(q))
+    };
+
+    let mut names = BTreeSet::new();
+    let mut docs = BTreeSet::new();
+    for element in model.elements.values().filter(|e| redacted(e)) {
+        names.extend(element.name.iter().map(|n| n.to_string()));
+        names.extend(element.short_name.iter().map(|n| n.to_string()));
+        docs.extend(element.documentation.iter().map(|d| d.to_string()));
+    }
+    for name in &names {
+        map.name(name);
+    }
+    for doc in &docs {
+        map.doc(doc);
+    }
+
+    let names = &map.names;
+    let rename = |text: &str| -> Arc<str> {
+        text.split("::")
+            .map(|segment| names.get(segment).map_or(segment, String::as_str))
+            .collect::<Vec<_>>()
+            .join("::")
+            .into()
+    };
+
+    for element in model.elements.values_mut().filter(|e| redacted(e)) {
+        element.name = element.name.as_deref().map(&rename);
+        element.short_name = element.short_name.as_deref().map(&rename);
+        element.qualified_name = element.qualified_name.as_deref().map(&rename);
+        element.documentation = element
+            .documentation
+            .as_deref()
+            .map(|d| Arc::from(map.documentation[d].as_str()));
+    }
+
+    // Targets outside the exported model are kept by qualified name
+    for relationship in &mut model.relationships {
+        if !model.elements.contains_key(&relationship.target) {
+            relationship.target = ElementId::new(rename(relationship.target.as_str()));
+        }
+    }
+}
+
+/// Undo [`redact_model`] using the same mapping.
+pub fn unredact_model(model: &mut Model, map: &RedactionMap) {
+    let names: BTreeMap<&str, &str> = map
+        .names
+        .iter()
+        .map(|(original, pseudonym)| (pseudonym.as_str(), original.as_str()))
+        .collect();
+    let docs: BTreeMap<&str, &str> = map
+        .documentation
+        .iter()
+        .map(|(original, placeholder)| (placeholder.as_str(), original.as_str()))
+        .collect();
+    let rename = |text: &str| -> Arc<str> {
+        text.split("::")
+            .map(|segment| names.get(segment).copied().unwrap_or(segment))
+            .collect::<Vec<_>>()
+            .join("::")
+            .into()
+    };
+
+    for element in model.elements.values_mut() {
+        element.name = element.name.as_deref().map(&rename);
+        element.short_name = element.short_name.as_deref().map(&rename);
+        element.qualified_name = element.qualified_name.as_deref().map(&rename);
+        element.documentation = element
+            .documentation
+            .as_deref()
+            .map(|d| Arc::from(docs.get(d).copied().unwrap_or(d)));
+    }
+    for relationship in &mut model.relationships {
+        if !model.elements.contains_key(&relationship.target) {
+            relationship.target = ElementId::new(rename(relationship.target.as_str()));
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! References out of an imported file (`--resolve-refs`).
+//!
+//! An XMI file exported from a larger model points at elements it does not
+//! contain, through `href`s and relationship ends. Some of those are meant
+//! to be external — an `href` naming another document, such as a library
+//! file — and some are simply broken. Import lists every reference to an
+//! element the file does not have, looks the element up in supplementary
+//! sources if any are given, and classifies what is left: references into
+//! another document are external, the rest are errors.
+//!
+//! A source is a directory of interchange files (searched recursively), a
+//! single interchange file such as a KPAR, or a symbol index built by
+//! `syster index build`. Interchange files are matched by element ID;
+//! indexes, which record no IDs, by the qualified name the reference
+//! carries, if any.
+
+use super::sidx::ModelIndex;
+use super::{ImportOptions, compress, is_metadata_file, read_import_model};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use syster::interchange::model::PropertyValue;
+use syster::interchange::{ElementId, Model};
+use walkdir::WalkDir;
+
+/// Extensions of the files a source directory is searched for.
+const INTERCHANGE_EXTENSIONS: &[&str] = &[
+    "xmi", "sysmlx", "kermlx", "kpar", "jsonld", "json", "yaml", "yml",
+];
+
+/// How a reference to an element outside the imported file was classified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceStatus {
+    /// Found in a supplementary source.
+    Resolved,
+    /// Not found, but it names another document, so it is meant to point
+    /// outside the file.
+    External,
+    /// Not found, and meant to be in the file: an error.
+    Unresolved,
+}
+
+/// A referenced element the imported file does not contain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExternalReference {
+    /// ID of the referenced element.
+    pub id: String,
+    /// The `href` it was referenced by, if any.
+    pub href: Option<String>,
+    /// Qualified name of the referenced element, if the reference gives it.
+    pub name: Option<String>,
+    /// Qualified names (or IDs) of the elements referring to it.
+    pub referenced_by: Vec<String>,
+    /// How it was classified.
+    pub status: ReferenceStatus,
+    /// The source it was found in, if resolved.
+    pub resolved_in: Option<String>,
+}
+
+impl ExternalReference {
+    /// The document an `href` names before its `#`, if any.
+    pub fn document(&self) -> Option<&str> {
+        self.href
+            .as_deref()
+            .and_then(|href| href.rsplit_once('#'))
+            .map(|(document, _)| document)
+            .filter(|document| !document.is_empty())
+    }
+}
+
+impl fmt::Display for ExternalReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            ReferenceStatus::Resolved => write!(
+                f,
+                "Reference to '{}' resolved in {}",
+                self.id,
+                self.resolved_in.as_deref().unwrap_or_default()
+            ),
+            ReferenceStatus::External => write!(
+                f,
+                "External reference to '{}' in {}",
+                self.id,
+                self.document().unwrap_or_default()
+            ),
+            ReferenceStatus::Unresolved => write!(
+                f,
+                "Warning: Reference to '{}' not found (from {})",
+                self.id,
+                self.referenced_by.join(", ")
+            ),
+        }
+    }
+}
+
+/// Elements of one supplementary source.
+struct Source {
+    label: String,
+    ids: HashSet<String>,
+    names: HashSet<String>,
+}
+
+/// Find the references of `model` to elements it does not contain and
+/// classify them, looking them up in `sources` first.
+///
+/// # Returns
+/// One entry per referenced element, ordered by ID.
+pub(crate) fn classify_references(
+    model: &Model,
+    sources: &[PathBuf],
+    verbose: bool,
+) -> Result<Vec<ExternalReference>, String> {
+    let mut references = missing_references(model);
+    if references.is_empty() {
+        return Ok(references);
+    }
+
+    let sources = sources
+        .iter()
+        .map(|path| load_source(path, verbose))
+        .collect::<Result<Vec<_>, _>>()?;
+    for reference in &mut references {
+        let found = sources.iter().find(|source| {
+            source.ids.contains(&reference.id)
+                || reference
+                    .name
+                    .as_ref()
+                    .is_some_and(|name| source.names.contains(name))
+        });
+        if let Some(source) = found {
+            reference.status = ReferenceStatus::Resolved;
+            reference.resolved_in = Some(source.label.clone());
+        } else if reference.document().is_some() {
+            reference.status = ReferenceStatus::External;
+        }
+    }
+
+    if verbose {
+        let count =
+            |status: ReferenceStatus| references.iter().filter(|r| r.status == status).count();
+        tracing::info!(
+            "{} references out of the file: {} resolved, {} external, {} unresolved",
+            references.len(),
+            count(ReferenceStatus::Resolved),
+            count(ReferenceStatus::External),
+            count(ReferenceStatus::Unresolved)
+        );
+    }
+    Ok(references)
+}
+
+/// References through `href`s and relationship ends to elements the model
+/// does not contain, all unresolved.
+fn missing_references(model: &Model) -> Vec<ExternalReference> {
+    // Anonymous elements, such as relationships, are named by their owner
+    let name_of = |id: &str| {
+        let element = model.get(&ElementId::new(id));
+        let owner = element.and_then(|e| e.owner.as_ref().and_then(|o| model.get(o)));
+        [element, owner]
+            .into_iter()
+            .flatten()
+            .find_map(|e| e.qualified_name.as_ref().or(e.name.as_ref()))
+            .map(ToString::to_string)
+            .unwrap_or_else(|| id.to_string())
+    };
+    let mut references: BTreeMap<String, ExternalReference> = BTreeMap::new();
+    let mut add = |id: &str, from: &str, href: Option<&str>, name: Option<&str>| {
+        if id.is_empty() || model.elements.contains_key(&ElementId::new(id)) {
+            return;
+        }
+        let reference = references
+            .entry(id.to_string())
+            .or_insert_with(|| ExternalReference {
+                id: id.to_string(),
+                href: None,
+                name: None,
+                referenced_by: Vec::new(),
+                status: ReferenceStatus::Unresolved,
+                resolved_in: None,
+            });
+        if reference.href.is_none() {
+            reference.href = href.map(String::from);
+        }
+        if reference.name.is_none() {
+            reference.name = name.map(String::from);
+        }
+        let from = name_of(from);
+        if !reference.referenced_by.contains(&from) {
+            reference.referenced_by.push(from);
+        }
+    };
+
+    for element in model.iter_elements() {
+        let Some(PropertyValue::String(href)) = element.properties.get("href") else {
+            continue;
+        };
+        let name = match element.properties.get("href_target_name") {
+            Some(PropertyValue::String(name)) => Some(name.as_ref()),
+            _ => None,
+        };
+        let id = href.rsplit('#').next().unwrap_or_default();
+        add(id, element.id.as_str(), Some(href.as_ref()), name);
+    }
+    for rel in &model.relationships {
+        let from = rel.owner.as_ref().unwrap_or(&rel.id);
+        add(rel.source.as_str(), from.as_str(), None, None);
+        add(rel.target.as_str(), from.as_str(), None, None);
+    }
+    references.into_values().collect()
+}
+
+/// Read the element IDs and names of a source.
+fn load_source(path: &Path, verbose: bool) -> Result<Source, String> {
+    let mut source = Source {
+        label: path.display().to_string(),
+        ids: HashSet::new(),
+        names: HashSet::new(),
+    };
+
+    if path.is_dir() {
+        let mut files: Vec<PathBuf> = WalkDir::new(path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .map(|e| e.into_path())
+            .filter(|p| p.is_file() && is_interchange_file(p))
+            .collect();
+        files.sort();
+        for file in files {
+            add_model(&mut source, &read_model(&file)?);
+        }
+    } else if path.extension().is_some_and(|e| e == "sidx") {
+        let index = ModelIndex::read(path)?;
+        source
+            .names
+            .extend(index.symbols.into_iter().map(|s| s.qualified_name));
+    } else {
+        add_model(&mut source, &read_model(path)?);
+    }
+
+    if verbose {
+        tracing::info!(
+            "Loaded {} element IDs and {} names from {}",
+            source.ids.len(),
+            source.names.len(),
+            source.label
+        );
+    }
+    Ok(source)
+}
+
+fn read_model(path: &Path) -> Result<Model, String> {
+    read_import_model(path, None, &ImportOptions::default())
+        .map(|(model, _)| model)
+        .map_err(|e| format!("Failed to read reference source {}: {}", path.display(), e))
+}
+
+fn add_model(source: &mut Source, model: &Model) {
+    for element in model.iter_elements() {
+        source.ids.insert(element.id.as_str().to_string());
+        if let Some(name) = &element.qualified_name {
+            source.names.insert(name.to_string());
+        }
+    }
+}
+
+fn is_interchange_file(path: &Path) -> bool {
+    !is_metadata_file(path)
+        && compress::uncompressed_path(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|ext| {
+                INTERCHANGE_EXTENSIONS
+                    .iter()
+                    .any(|known| ext.eq_ignore_ascii_case(known))
+            })
+}
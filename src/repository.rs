@@ -0,0 +1,257 @@
+//! SysML v2 API repositories (`syster push`, `syster pull`).
+//!
+//! Talks to a model repository implementing the OMG Systems Modeling API
+//! and Services (REST/HTTP), such as Teamwork Cloud or the pilot
+//! implementation. A push exports the model as JSON-LD and records every
+//! element and relationship as a change of one new commit on a branch; a
+//! pull reads the elements of a commit back into an interchange model, to
+//! analyze or decompile like an imported file:
+//!
+//! ```text
+//! SYSML_API_TOKEN=... syster push --repo https://host/api --project X --branch main
+//! SYSML_API_TOKEN=... syster pull --repo https://host/api --project X --commit <id>
+//! ```
+
+use super::http::{self, Auth, encode};
+use super::{
+    AnalysisOptions, AnalysisResult, DecompileResult, ExportFilter, MemberOrder,
+    export_model_with_options,
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::path::Path;
+use syster::ide::AnalysisHost;
+use syster::interchange::{JsonLd, Model, ModelFormat, SourceInfo};
+
+/// A SysML v2 API server.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    /// Base URL of the API, e.g. `https://host/api`.
+    pub url: String,
+    /// Bearer token sent with every request, if any.
+    pub token: Option<String>,
+}
+
+/// Where a push went.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PushReport {
+    /// ID of the project pushed to.
+    pub project_id: String,
+    /// ID of the branch pushed to.
+    pub branch_id: String,
+    /// ID of the new commit.
+    pub commit_id: String,
+    /// Elements and relationships in the commit.
+    pub changes: usize,
+}
+
+impl Repository {
+    /// A repository at `url`, authenticated with `token` if given.
+    pub fn new(url: &str, token: Option<String>) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    /// The ID of the project with this ID or name.
+    pub fn project_id(&self, project: &str) -> Result<String, String> {
+        let projects = self.get("/projects")?;
+        find_id(&projects, project)
+            .ok_or_else(|| format!("No project '{}' in {}", project, self.url))
+    }
+
+    /// The ID of the branch of a project with this ID or name.
+    pub fn branch_id(&self, project_id: &str, branch: &str) -> Result<String, String> {
+        let branches = self.get(&format!("/projects/{}/branches", encode(project_id)))?;
+        find_id(&branches, branch)
+            .ok_or_else(|| format!("No branch '{}' in project {}", branch, project_id))
+    }
+
+    /// Commit `changes` (`DataVersion`s, see [`commit_changes`]) to a branch
+    /// and return the new commit's ID.
+    pub fn commit(
+        &self,
+        project_id: &str,
+        branch_id: &str,
+        changes: Vec<Value>,
+    ) -> Result<String, String> {
+        let commit = json!({ "@type": "Commit", "change": changes });
+        let created = self.send(
+            "POST",
+            &format!(
+                "/projects/{}/commits?branchId={}",
+                encode(project_id),
+                encode(branch_id)
+            ),
+            Some(&commit),
+        )?;
+        created["@id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "The repository did not return the commit's ID".to_string())
+    }
+
+    /// The elements and relationships of a project at a commit.
+    pub fn elements(&self, project_id: &str, commit_id: &str) -> Result<Vec<Value>, String> {
+        let elements = self.get(&format!(
+            "/projects/{}/commits/{}/elements",
+            encode(project_id),
+            encode(commit_id)
+        ))?;
+        match elements {
+            Value::Array(elements) => Ok(elements),
+            _ => Err(format!(
+                "The repository did not list the elements of commit {}",
+                commit_id
+            )),
+        }
+    }
+
+    fn get(&self, path: &str) -> Result<Value, String> {
+        self.send("GET", path, None)
+    }
+
+    fn send(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value, String> {
+        let auth = self.token.as_deref().map(Auth::Bearer);
+        http::request(method, &format!("{}{}", self.url, path), auth, body)
+    }
+}
+
+/// Export `input` and commit it to `branch` of `project` in `repository`.
+/// The model is exported as JSON-LD with `filter` applied (see
+/// [`export_model_with_options`]).
+pub fn push_model(
+    input: &Path,
+    options: &AnalysisOptions,
+    filter: &ExportFilter,
+    repository: &Repository,
+    project: &str,
+    branch: &str,
+) -> Result<PushReport, String> {
+    let _span = tracing::info_span!("push").entered();
+    let (jsonld, _) = export_model_with_options(input, "jsonld", options, false, filter)?;
+    let changes = commit_changes(&jsonld)?;
+
+    let project_id = repository.project_id(project)?;
+    let branch_id = repository.branch_id(&project_id, branch)?;
+    let count = changes.len();
+    let commit_id = repository.commit(&project_id, &branch_id, changes)?;
+    if options.verbose {
+        tracing::info!("Committed {} changes as {}", count, commit_id);
+    }
+    Ok(PushReport {
+        project_id,
+        branch_id,
+        commit_id,
+        changes: count,
+    })
+}
+
+/// A model read from a commit of a repository.
+#[derive(Debug, Clone)]
+pub struct PulledModel {
+    /// ID of the project pulled from.
+    pub project_id: String,
+    /// ID of the commit pulled.
+    pub commit_id: String,
+    /// The commit's elements and relationships.
+    pub model: Model,
+}
+
+impl PulledModel {
+    /// Name the decompiled model has in diagnostics and metadata.
+    pub fn path(&self) -> String {
+        format!("{}@{}.sysml", self.project_id, self.commit_id)
+    }
+
+    /// Decompile the model to SysML text and ID metadata, as
+    /// [`decompile_model_ordered`](super::decompile_model_ordered) does for
+    /// an interchange file.
+    pub fn decompile(&self, order: MemberOrder) -> Result<DecompileResult, String> {
+        let source = SourceInfo::from_path(self.path()).with_format("jsonld");
+        let (sysml_text, metadata) = super::decompile_in_order(&self.model, source, order);
+        Ok(DecompileResult {
+            sysml_text,
+            metadata_json: super::metadata_to_json(&metadata)?,
+            element_count: self.model.elements.len(),
+            source_path: self.path(),
+        })
+    }
+
+    /// Load the model into a workspace (with the standard library if
+    /// `options` say so) and analyze it. Element IDs are kept.
+    pub fn analyze(&self, options: &AnalysisOptions) -> Result<AnalysisResult, String> {
+        let mut host = AnalysisHost::new();
+        if options.load_stdlib {
+            super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+        }
+        let stdlib_files: HashSet<String> = host
+            .files()
+            .keys()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        host.add_model(&self.model, &self.path());
+        Ok(super::analyze_host(
+            &mut host,
+            &stdlib_files,
+            &HashSet::new(),
+            options,
+        ))
+    }
+}
+
+/// Read commit `commit` of `project` (an ID or name) from `repository`.
+pub fn pull_model(
+    repository: &Repository,
+    project: &str,
+    commit: &str,
+) -> Result<PulledModel, String> {
+    let _span = tracing::info_span!("pull").entered();
+    let project_id = repository.project_id(project)?;
+    let elements = repository.elements(&project_id, commit)?;
+    let bytes = serde_json::to_vec(&elements)
+        .map_err(|e| format!("Failed to serialize elements: {}", e))?;
+    let model = JsonLd.read(&bytes).map_err(|e| e.to_string())?;
+    Ok(PulledModel {
+        project_id,
+        commit_id: commit.to_string(),
+        model,
+    })
+}
+
+/// One `DataVersion` per element and relationship of a JSON-LD export,
+/// with the element as its payload. Objects without an `@id` (such as the
+/// provenance record) are left out.
+pub fn commit_changes(jsonld: &[u8]) -> Result<Vec<Value>, String> {
+    let value: Value =
+        serde_json::from_slice(jsonld).map_err(|e| format!("Invalid JSON-LD: {}", e))?;
+    let items = match value {
+        Value::Array(items) => items,
+        item => vec![item],
+    };
+    Ok(items
+        .into_iter()
+        .filter_map(|mut item| {
+            let id = item.get("@id")?.as_str()?.to_string();
+            if let Value::Object(object) = &mut item {
+                object.remove("@context");
+            }
+            Some(json!({
+                "@type": "DataVersion",
+                "identity": { "@id": id },
+                "payload": item,
+            }))
+        })
+        .collect())
+}
+
+/// The `@id` of the listed record whose `@id` or `name` is `key`.
+fn find_id(records: &Value, key: &str) -> Option<String> {
+    records.as_array()?.iter().find_map(|record| {
+        let id = record.get("@id")?.as_str()?;
+        let name = record.get("name").and_then(Value::as_str);
+        (id == key || name == Some(key)).then(|| id.to_string())
+    })
+}
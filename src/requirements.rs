@@ -0,0 +1,237 @@
+//! Quality of requirement text (`[requirements]` in `syster.toml`).
+//!
+//! The documentation of every requirement definition and usage is checked
+//! against common requirement-writing rules, each of which can be switched
+//! off:
+//!
+//! ```toml
+//! [requirements]
+//! shall = true            # L0008: states an obligation with "shall"
+//! vague_terms = true      # L0009: no vague terms such as "etc."
+//! single_sentence = true  # L0010: one sentence per requirement
+//! measurable = true       # L0011: states a number to verify against
+//! vague = ["as appropriate", "etc."]  # replaces the default terms
+//! ```
+//!
+//! An empty table turns every rule on. Violations are warnings at the
+//! requirement. Requirements without documentation are not checked; see
+//! `--require-docs requirementdef` for those.
+
+use super::DiagnosticInfo;
+use super::lint::codes;
+use super::project::{find_project_file, load_table};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use syster::hir::{HirSymbol, Severity, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// Terms that make a requirement ambiguous or impossible to verify.
+pub const DEFAULT_VAGUE_TERMS: &[&str] = &[
+    "as appropriate",
+    "as applicable",
+    "as required",
+    "as needed",
+    "if possible",
+    "if practical",
+    "etc.",
+    "and/or",
+    "adequate",
+    "sufficient",
+    "user-friendly",
+    "easy",
+    "fast",
+    "quickly",
+    "approximately",
+    "minimize",
+    "maximize",
+];
+
+/// Which checks run on requirement text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RequirementRules {
+    /// The text says what "shall" be done.
+    pub shall: bool,
+    /// The text uses none of the `vague` terms.
+    pub vague_terms: bool,
+    /// The text is a single sentence.
+    pub single_sentence: bool,
+    /// The text contains a number, such as a limit or tolerance.
+    pub measurable: bool,
+    /// Terms reported by `vague_terms`, matched as whole words ignoring case.
+    pub vague: Vec<String>,
+}
+
+impl Default for RequirementRules {
+    fn default() -> Self {
+        Self {
+            shall: true,
+            vague_terms: true,
+            single_sentence: true,
+            measurable: true,
+            vague: DEFAULT_VAGUE_TERMS.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+}
+
+/// The `[requirements]` table of `syster.toml`.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct RequirementsSection {
+    shall: Option<bool>,
+    vague_terms: Option<bool>,
+    single_sentence: Option<bool>,
+    measurable: Option<bool>,
+    vague: Option<Vec<String>>,
+}
+
+impl RequirementRules {
+    /// Load the `[requirements]` table of a project file, if it has one.
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        let section: Option<RequirementsSection> = load_table(path, "requirements")?;
+        Ok(section.map(|section| {
+            let default = Self::default();
+            Self {
+                shall: section.shall.unwrap_or(default.shall),
+                vague_terms: section.vague_terms.unwrap_or(default.vague_terms),
+                single_sentence: section.single_sentence.unwrap_or(default.single_sentence),
+                measurable: section.measurable.unwrap_or(default.measurable),
+                vague: section.vague.unwrap_or(default.vague),
+            }
+        }))
+    }
+
+    /// Load the `[requirements]` table of the nearest `syster.toml` in
+    /// `input` (or its directory) and its ancestors, if there is one.
+    pub fn discover(input: &Path) -> Result<Option<Self>, String> {
+        match find_project_file(input) {
+            Some(path) => Self::load(&path),
+            None => Ok(None),
+        }
+    }
+
+    /// What is wrong with requirement `text`, as `(code, problem)`.
+    pub fn check(&self, text: &str) -> Vec<(&'static str, String)> {
+        let lower = text.to_lowercase();
+        let mut problems = Vec::new();
+        if self.shall && find_word(&lower, "shall").is_none() {
+            problems.push((
+                codes::REQUIREMENT_SHALL,
+                "does not state what shall be done".to_string(),
+            ));
+        }
+        if self.vague_terms {
+            for term in &self.vague {
+                if find_word(&lower, &term.to_lowercase()).is_some() {
+                    problems.push((
+                        codes::REQUIREMENT_VAGUE,
+                        format!("uses the vague term \"{}\"", term),
+                    ));
+                }
+            }
+        }
+        if self.single_sentence {
+            let sentences = count_sentences(text);
+            if sentences > 1 {
+                problems.push((
+                    codes::REQUIREMENT_SENTENCES,
+                    format!("has {} sentences; state one requirement each", sentences),
+                ));
+            }
+        }
+        if self.measurable && !text.chars().any(|c| c.is_ascii_digit()) {
+            problems.push((
+                codes::REQUIREMENT_MEASURABLE,
+                "states no measurable criterion (no number)".to_string(),
+            ));
+        }
+        problems
+    }
+}
+
+/// Check the documentation of the requirements in `files`.
+pub(crate) fn requirement_diagnostics(
+    host: &AnalysisHost,
+    files: &HashSet<&str>,
+    rules: &RequirementRules,
+) -> Vec<DiagnosticInfo> {
+    let mut diagnostics = Vec::new();
+    for path in host.files().keys() {
+        let file = path.to_string_lossy().to_string();
+        if !files.contains(file.as_str()) {
+            continue;
+        }
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        for symbol in host.symbol_index().symbols_in_file(file_id) {
+            let Some(text) = requirement_text(symbol) else {
+                continue;
+            };
+            for (code, problem) in rules.check(text) {
+                diagnostics.push(DiagnosticInfo {
+                    file: file.clone(),
+                    line: symbol.start_line + 1,
+                    col: symbol.start_col + 1,
+                    end_line: symbol.end_line + 1,
+                    end_col: symbol.end_col + 1,
+                    message: format!(
+                        "{} '{}' {}",
+                        symbol.kind.display().to_lowercase(),
+                        symbol.qualified_name,
+                        problem
+                    ),
+                    severity: Severity::Warning,
+                    code: Some(code.to_string()),
+                    blame: None,
+                    owners: Vec::new(),
+                    related: Vec::new(),
+                });
+            }
+        }
+    }
+    diagnostics
+}
+
+/// The documentation of a requirement, if `symbol` is a documented one.
+fn requirement_text(symbol: &HirSymbol) -> Option<&str> {
+    if !matches!(
+        symbol.kind,
+        SymbolKind::RequirementDefinition | SymbolKind::RequirementUsage
+    ) {
+        return None;
+    }
+    symbol.doc.as_deref().filter(|doc| !doc.trim().is_empty())
+}
+
+/// Byte offset of `word` in `text` where it is not part of a longer word.
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).map(|(i, _)| i).find(|&i| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + word.len()..].chars().next();
+        // Only letters and digits at the ends of `word` need a boundary
+        let starts_word = word.chars().next().is_some_and(is_word);
+        let ends_word = word.chars().next_back().is_some_and(is_word);
+        !(starts_word && before.is_some_and(is_word)) && !(ends_word && after.is_some_and(is_word))
+    })
+}
+
+/// Number of sentences in `text`: a `.`, `!` or `?` followed by a space and
+/// a capital letter starts a new one, so decimals and most abbreviations
+/// don't.
+fn count_sentences(text: &str) -> usize {
+    let chars: Vec<char> = text.trim().chars().collect();
+    let mut sentences = 1;
+    for (i, &c) in chars.iter().enumerate() {
+        if !matches!(c, '.' | '!' | '?') {
+            continue;
+        }
+        let rest = &chars[i + 1..];
+        let spaces = rest.iter().take_while(|c| c.is_whitespace()).count();
+        if spaces > 0 && rest.get(spaces).is_some_and(|c| c.is_uppercase()) {
+            sentences += 1;
+        }
+    }
+    sentences
+}
@@ -0,0 +1,152 @@
+//! Diagnostics for code-review tools.
+//!
+//! `--format rdjson` writes reviewdog's Diagnostic Result JSON, to pipe into
+//! `reviewdog -f=rdjson`. `--format gerrit` writes a Gerrit `ReviewInput`
+//! carrying robot comments, to post to
+//! `/changes/{change}/revisions/{revision}/review`.
+//!
+//! Paths are reported as analyzed, with absolute paths under the current
+//! directory made relative, so run the check from the repository root.
+
+use super::{AnalysisResult, DiagnosticInfo, severity_name};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+use std::time::{SystemTime, UNIX_EPOCH};
+use syster::hir::Severity;
+
+/// Robot ID of Gerrit robot comments.
+const ROBOT_ID: &str = "syster";
+
+/// Export diagnostics as reviewdog Diagnostic Result JSON (rdjson).
+pub fn export_rdjson(result: &AnalysisResult) -> Result<String, String> {
+    let _span = tracing::info_span!("export", format = "rdjson").entered();
+    let diagnostics: Vec<Value> = result
+        .diagnostics
+        .iter()
+        .map(|diag| {
+            let mut entry = json!({
+                "message": diag.message,
+                "location": {
+                    "path": review_path(&diag.file),
+                    "range": {
+                        "start": { "line": diag.line, "column": diag.col },
+                        "end": { "line": diag.end_line, "column": diag.end_col },
+                    },
+                },
+                "severity": match diag.severity {
+                    Severity::Error => "ERROR",
+                    Severity::Warning => "WARNING",
+                    Severity::Info | Severity::Hint => "INFO",
+                },
+            });
+            if let Some(code) = &diag.code {
+                entry["code"] = json!({ "value": code });
+            }
+            if !diag.related.is_empty() {
+                entry["related_locations"] = diag
+                    .related
+                    .iter()
+                    .map(|related| {
+                        json!({
+                            "message": related.message,
+                            "location": {
+                                "path": review_path(&related.file),
+                                "range": {
+                                    "start": { "line": related.line, "column": related.col },
+                                },
+                            },
+                        })
+                    })
+                    .collect();
+            }
+            entry
+        })
+        .collect();
+
+    let report = json!({
+        "source": { "name": "syster" },
+        "diagnostics": diagnostics,
+    });
+    serde_json::to_string_pretty(&report).map_err(|e| format!("Failed to serialize rdjson: {}", e))
+}
+
+/// Export diagnostics as a Gerrit `ReviewInput` with robot comments.
+///
+/// Gerrit ranges count characters from 0, so columns are shifted by one, and
+/// a comment's line must be the last line of its range.
+pub fn export_gerrit(result: &AnalysisResult) -> Result<String, String> {
+    let _span = tracing::info_span!("export", format = "gerrit").entered();
+    let run_id = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| super::format_rfc3339(d.as_secs()))
+        .unwrap_or_default();
+
+    let mut comments: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+    for diag in &result.diagnostics {
+        let mut comment = json!({
+            "robot_id": ROBOT_ID,
+            "robot_run_id": run_id,
+            "line": diag.end_line,
+            "range": {
+                "start_line": diag.line,
+                "start_character": diag.col.saturating_sub(1),
+                "end_line": diag.end_line,
+                "end_character": diag.end_col.saturating_sub(1),
+            },
+            "message": gerrit_message(diag),
+        });
+        if let Some(code) = &diag.code {
+            comment["properties"] = json!({ "code": code });
+        }
+        comments
+            .entry(review_path(&diag.file))
+            .or_default()
+            .push(comment);
+    }
+
+    let review = json!({
+        "message": format!(
+            "syster: {} errors, {} warnings",
+            result.error_count, result.warning_count
+        ),
+        "robot_comments": comments,
+    });
+    serde_json::to_string_pretty(&review)
+        .map_err(|e| format!("Failed to serialize Gerrit review: {}", e))
+}
+
+/// `error[code]: message`, followed by the related locations.
+fn gerrit_message(diag: &DiagnosticInfo) -> String {
+    let severity = severity_name(diag.severity);
+    let mut message = match &diag.code {
+        Some(code) => format!("{}[{}]: {}", severity, code, diag.message),
+        None => format!("{}: {}", severity, diag.message),
+    };
+    for related in &diag.related {
+        message.push_str(&format!(
+            "\nnote: {}:{}:{}: {}",
+            review_path(&related.file),
+            related.line,
+            related.col,
+            related.message
+        ));
+    }
+    message
+}
+
+/// `file` relative to the current directory if it lies below it, with `/`
+/// separators and without a leading `./`.
+fn review_path(file: &str) -> String {
+    let path = Path::new(file);
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf());
+    relative
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
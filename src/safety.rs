@@ -0,0 +1,246 @@
+//! Safety-analysis support: FMEA table skeletons.
+//!
+//! Parts annotated with failure-mode metadata (e.g. `#FailureMode`) become
+//! rows of a Failure Mode and Effects Analysis table. The element, its
+//! functions (owned actions) and the elements it is connected to are filled
+//! in from the model; failure modes, ratings and actions are left blank for
+//! the safety engineer.
+
+use super::AnalysisOptions;
+use super::annotations::matches_metadata;
+use super::codegen::{CodegenElement, CodegenModel};
+use super::{csv_field, escape_xml};
+use std::path::Path;
+
+/// Column headings of the FMEA table, in output order.
+pub const FMEA_COLUMNS: [&str; 12] = [
+    "Element",
+    "Kind",
+    "Function",
+    "Failure Mode",
+    "Failure Cause",
+    "Local Effect",
+    "Connected Effects",
+    "Severity",
+    "Occurrence",
+    "Detection",
+    "RPN",
+    "Recommended Action",
+];
+
+/// An FMEA table skeleton.
+#[derive(Debug, Default)]
+pub struct FmeaTable {
+    /// One row per annotated element, in qualified-name order.
+    pub rows: Vec<FmeaRow>,
+}
+
+/// The model-derived columns of one FMEA row.
+#[derive(Debug)]
+pub struct FmeaRow {
+    /// Qualified name of the element.
+    pub element: String,
+    /// Human-readable kind, e.g. `Part def`.
+    pub kind: String,
+    /// Names of the actions the element performs.
+    pub functions: Vec<String>,
+    /// Elements connected to this one, as `other (via connection)`.
+    pub connected_effects: Vec<String>,
+}
+
+/// Collect an FMEA skeleton for the elements annotated with `metadata`.
+pub fn fmea_table(model: &CodegenModel, metadata: &str) -> FmeaTable {
+    let connections: Vec<&CodegenElement> = model
+        .elements
+        .iter()
+        .filter(|e| !e.ends.is_empty())
+        .collect();
+
+    let rows = model
+        .elements
+        .iter()
+        .filter(|e| e.metadata.iter().any(|m| matches_metadata(m, metadata)))
+        .map(|element| {
+            let connected_effects = connections
+                .iter()
+                .filter(|c| c.ends.iter().any(|end| links(model, end, element)))
+                .flat_map(|c| {
+                    c.ends
+                        .iter()
+                        .filter(|end| !links(model, end, element))
+                        .map(|end| format!("{} (via {})", end, c.qualified_name))
+                })
+                .collect();
+
+            FmeaRow {
+                element: element.qualified_name.clone(),
+                kind: element.kind_label.clone(),
+                functions: functions(model, element),
+                connected_effects,
+            }
+        })
+        .collect();
+
+    FmeaTable { rows }
+}
+
+/// Names of the actions owned by `element` or, for a usage, by its types.
+fn functions(model: &CodegenModel, element: &CodegenElement) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let owners =
+        std::iter::once(element).chain(element.types.iter().filter_map(|t| model.by_name.get(t)));
+    for owner in owners {
+        for member in owner.members.iter().filter_map(|m| model.by_name.get(m)) {
+            if member.kind == "ActionUsage" && !names.contains(&member.name) {
+                names.push(member.name.clone());
+            }
+        }
+    }
+    names
+}
+
+/// Whether the connection end `end` refers to `element`, to a usage typed by
+/// it, to its type, or to a feature nested in any of these.
+fn links(model: &CodegenModel, end: &str, element: &CodegenElement) -> bool {
+    let mut current = Some(end);
+    while let Some(name) = current {
+        let matches = name == element.qualified_name
+            || element.types.iter().any(|t| t == name)
+            || model
+                .by_name
+                .get(name)
+                .is_some_and(|e| e.types.contains(&element.qualified_name));
+        if matches {
+            return true;
+        }
+        current = parent_of(name);
+    }
+    false
+}
+
+/// `a::b` for `a::b::c` and `a` for a feature chain `a.b`.
+fn parent_of(name: &str) -> Option<&str> {
+    let scope = name.rfind("::");
+    let chain = name.rfind('.');
+    match (scope, chain) {
+        (Some(s), Some(c)) if c > s => Some(&name[..c]),
+        (Some(s), _) => Some(&name[..s]),
+        (None, Some(c)) => Some(&name[..c]),
+        (None, None) => None,
+    }
+}
+
+impl FmeaTable {
+    /// Cells of every row, padded with blank columns for the analyst.
+    fn cells(&self) -> Vec<Vec<String>> {
+        let mut rows = vec![FMEA_COLUMNS.iter().map(|c| c.to_string()).collect()];
+        for row in &self.rows {
+            let mut cells = vec![
+                row.element.clone(),
+                row.kind.clone(),
+                row.functions.join("; "),
+                String::new(),
+                String::new(),
+                String::new(),
+                row.connected_effects.join("; "),
+            ];
+            cells.resize(FMEA_COLUMNS.len(), String::new());
+            rows.push(cells);
+        }
+        rows
+    }
+
+    /// Render the table as CSV with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for row in self.cells() {
+            let fields: Vec<String> = row.iter().map(|cell| csv_field(cell)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Render the table as a single-sheet XLSX workbook.
+    pub fn to_xlsx(&self) -> Result<Vec<u8>, String> {
+        let mut sheet = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\"><sheetData>",
+        );
+        for (r, row) in self.cells().iter().enumerate() {
+            sheet.push_str(&format!("<row r=\"{}\">", r + 1));
+            for (c, cell) in row.iter().enumerate() {
+                if cell.is_empty() {
+                    continue;
+                }
+                sheet.push_str(&format!(
+                    "<c r=\"{}{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+                    column_letter(c),
+                    r + 1,
+                    escape_xml(cell)
+                ));
+            }
+            sheet.push_str("</row>");
+        }
+        sheet.push_str("</sheetData></worksheet>\n");
+
+        let entries = [
+            ("[Content_Types].xml", CONTENT_TYPES.to_string()),
+            ("_rels/.rels", ROOT_RELS.to_string()),
+            ("xl/workbook.xml", WORKBOOK.to_string()),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.to_string()),
+            ("xl/worksheets/sheet1.xml", sheet),
+        ]
+        .map(|(name, xml)| (name.to_string(), xml.into_bytes()));
+        super::kpar::write_entries(&entries)
+    }
+}
+
+/// Spreadsheet column letter for a zero-based index (`A`..`Z`, `AA`..).
+fn column_letter(mut index: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap_or_default()
+}
+
+const CONTENT_TYPES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+<Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+<Override PartName=\"/xl/worksheets/sheet1.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>\
+</Types>\n";
+
+const ROOT_RELS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+</Relationships>\n";
+
+const WORKBOOK: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+<sheets><sheet name=\"FMEA\" sheetId=\"1\" r:id=\"rId1\"/></sheets>\
+</workbook>\n";
+
+const WORKBOOK_RELS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet1.xml\"/>\
+</Relationships>\n";
+
+/// Analyze `input` and collect the FMEA skeleton.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `metadata` - Metadata marking elements to analyze (e.g. `FailureMode`)
+/// * `options` - Verbosity and standard library to load
+pub fn fmea(input: &Path, metadata: &str, options: &AnalysisOptions) -> Result<FmeaTable, String> {
+    let model = CodegenModel::load(input, options)?;
+    Ok(fmea_table(&model, metadata))
+}
@@ -0,0 +1,233 @@
+//! Random self-contained samples of a workspace (`syster sample`).
+//!
+//! Elements are drawn in an order shuffled by a seeded generator until the
+//! sample holds the requested number of elements. Each drawn element brings
+//! along what it needs to resolve the same way: every element it references,
+//! transitively, the namespaces enclosing them and the imports of those
+//! namespaces. Library elements are referenced, never copied.
+//!
+//! The sample is the workspace's own files with every other declaration cut
+//! out, so what remains is the original text. The same seed on the same
+//! workspace always gives the same sample.
+
+use super::AnalysisOptions;
+use super::closure::dependencies;
+use super::extents::{Extents, remove_ranges};
+use super::shadowing::import_scope;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syster::base::FileId;
+use syster::hir::{HirSymbol, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// A sample of a workspace.
+#[derive(Debug)]
+pub struct Sample {
+    /// Seed the sample was drawn with.
+    pub seed: u64,
+    /// Qualified names of the drawn elements, in drawing order.
+    pub drawn: Vec<String>,
+    /// Qualified names of every element in the sample, drawn or required.
+    pub elements: BTreeSet<String>,
+    /// Files of the sample, in path order.
+    pub files: Vec<SampleFile>,
+}
+
+/// A file of a sample.
+#[derive(Debug)]
+pub struct SampleFile {
+    /// Path relative to the sampled directory.
+    pub path: PathBuf,
+    /// What is left of the file's text.
+    pub text: String,
+}
+
+/// Analyze `input` and draw a sample of at least `elements` elements, or
+/// all of them if there are fewer.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `elements` - Number of elements to sample
+/// * `seed` - Seed of the drawing order
+/// * `options` - Verbosity and standard library to load
+pub fn sample(
+    input: &Path,
+    elements: usize,
+    seed: u64,
+    options: &AnalysisOptions,
+) -> Result<Sample, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let library: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    super::load_input(&mut host, input, options.verbose)?;
+    let _analysis = host.analysis();
+    let index = host.symbol_index();
+
+    let in_workspace = |symbol: &HirSymbol| {
+        host.get_file_path(symbol.file)
+            .is_some_and(|path| !library.contains(path))
+    };
+    let by_name: BTreeMap<&str, &HirSymbol> = index
+        .all_symbols()
+        .filter(|s| in_workspace(s))
+        .map(|s| (&*s.qualified_name, s))
+        .collect();
+    let mut imports: HashMap<&str, Vec<&HirSymbol>> = HashMap::new();
+    for symbol in by_name.values() {
+        if symbol.kind == SymbolKind::Import {
+            imports
+                .entry(import_scope(&symbol.qualified_name))
+                .or_default()
+                .push(symbol);
+        }
+    }
+
+    // Candidates in name order, so only the seed decides the drawing order
+    let mut candidates: Vec<&str> = by_name
+        .values()
+        .filter(|s| counts(s) && s.kind != SymbolKind::Package)
+        .map(|s| &*s.qualified_name)
+        .collect();
+    shuffle(&mut candidates, seed);
+
+    let mut required: HashSet<&str> = HashSet::new();
+    let mut touched: HashSet<FileId> = HashSet::new();
+    let mut count = 0;
+    let mut drawn = Vec::new();
+    for candidate in candidates {
+        if count >= elements {
+            break;
+        }
+        if required.contains(candidate) {
+            continue;
+        }
+        drawn.push(candidate.to_string());
+        let mut queue = vec![candidate.to_string()];
+        while let Some(name) = queue.pop() {
+            let Some((&name, symbol)) = by_name.get_key_value(name.as_str()) else {
+                continue;
+            };
+            if !required.insert(name) {
+                continue;
+            }
+            if counts(symbol) {
+                count += 1;
+            }
+            queue.extend(dependencies(index, symbol));
+            let scope = match symbol.kind {
+                SymbolKind::Import => import_scope(name),
+                _ => parent(&by_name, name).unwrap_or(""),
+            };
+            if !scope.is_empty() {
+                queue.push(scope.to_string());
+            }
+            // The namespace's imports, and the file's top-level ones
+            let mut needed: Vec<&HirSymbol> = imports.get(name).cloned().unwrap_or_default();
+            if touched.insert(symbol.file) {
+                needed.extend(
+                    imports
+                        .get("")
+                        .into_iter()
+                        .flatten()
+                        .filter(|import| import.file == symbol.file),
+                );
+            }
+            queue.extend(
+                needed
+                    .iter()
+                    .map(|import| import.qualified_name.to_string()),
+            );
+        }
+    }
+
+    let mut files = Vec::new();
+    for file_id in touched {
+        let Some(path) = host.get_file_path_buf(file_id) else {
+            continue;
+        };
+        let Some(syntax) = host.files().get(&path) else {
+            continue;
+        };
+        let extents = Extents::of_file(syntax);
+        let removed: Vec<_> = index
+            .symbols_in_file(file_id)
+            .into_iter()
+            .filter(|s| !required.contains(&*s.qualified_name))
+            .filter_map(|s| extents.range(s))
+            .collect();
+        files.push(SampleFile {
+            path: relative_path(input, &path),
+            text: remove_ranges(&syntax.source_text(), removed),
+        });
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if options.verbose {
+        tracing::info!(
+            "Sampled {} elements ({} drawn) into {} files",
+            count,
+            drawn.len(),
+            files.len()
+        );
+    }
+
+    Ok(Sample {
+        seed,
+        drawn,
+        elements: required
+            .into_iter()
+            .filter(|name| counts(by_name[name]))
+            .map(str::to_string)
+            .collect(),
+        files,
+    })
+}
+
+/// Path of a model file relative to the `input` directory, or its file
+/// name if `input` is a file.
+pub(crate) fn relative_path(input: &Path, path: &Path) -> PathBuf {
+    match path.strip_prefix(input) {
+        Ok(relative) if input.is_dir() => relative.to_path_buf(),
+        _ => PathBuf::from(path.file_name().unwrap_or_default()),
+    }
+}
+
+/// Whether `symbol` counts as an element of a sample.
+fn counts(symbol: &HirSymbol) -> bool {
+    !matches!(symbol.kind, SymbolKind::Import | SymbolKind::Comment)
+}
+
+/// The innermost symbol enclosing `name`.
+fn parent<'a>(by_name: &BTreeMap<&'a str, &HirSymbol>, name: &str) -> Option<&'a str> {
+    let mut current = name;
+    while let Some((prefix, _)) = current.rsplit_once("::") {
+        if let Some((&parent, _)) = by_name.get_key_value(prefix) {
+            return Some(parent);
+        }
+        current = prefix;
+    }
+    None
+}
+
+/// Shuffle `items` in an order only `seed` decides (Fisher–Yates over
+/// SplitMix64, which is stable across platforms and releases).
+fn shuffle<T>(items: &mut [T], seed: u64) {
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    for i in (1..items.len()).rev() {
+        let j = (next() % (i as u64 + 1)) as usize;
+        items.swap(i, j);
+    }
+}
@@ -0,0 +1,266 @@
+//! Test stubs for verification cases.
+//!
+//! Each case of the [verification plan](super::verification) becomes a test
+//! file named after its qualified name, with one skipped test per verified
+//! requirement documented with the requirement's text (or a single test if
+//! it verifies none). A verification definition typing usages is left to
+//! them. Files that already exist are kept unless forced, so the stubs can
+//! be regenerated as cases are added without losing the tests written so
+//! far.
+
+use super::AnalysisOptions;
+use super::codegen::snake_case;
+use super::verification::{VerificationCase, VerificationPlan, verification_plan};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Test framework the stubs are written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestFramework {
+    /// Python `test_*.py` modules for pytest.
+    Pytest,
+    /// C++ `*_test.cc` files for GoogleTest.
+    Gtest,
+}
+
+/// A generated test file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestStub {
+    /// Qualified name of the verification case.
+    pub case: String,
+    /// File name, e.g. `test_tests_mass_test.py`.
+    pub file_name: String,
+    /// File content.
+    pub content: String,
+}
+
+/// Files written by [`generate_tests`].
+#[derive(Debug, Default)]
+pub struct TestScaffold {
+    /// Stubs written, in case order.
+    pub written: Vec<PathBuf>,
+    /// Stubs not written because the file already exists.
+    pub skipped: Vec<PathBuf>,
+}
+
+/// Build a test stub for every case of `plan` except definitions typing
+/// one of its usages.
+///
+/// Fails if two cases would be written to the same file.
+pub fn test_stubs(
+    plan: &VerificationPlan,
+    framework: TestFramework,
+) -> Result<Vec<TestStub>, String> {
+    let used: HashSet<&str> = plan.cases.iter().map(|c| c.definition.as_str()).collect();
+    let mut files: HashMap<String, &str> = HashMap::new();
+    let mut stubs = Vec::new();
+    for case in &plan.cases {
+        if case.is_definition && used.contains(case.qualified_name.as_str()) {
+            continue;
+        }
+        let stem = snake(&case.qualified_name);
+        let file_name = match framework {
+            TestFramework::Pytest => format!("test_{}.py", stem),
+            TestFramework::Gtest => format!("{}_test.cc", stem),
+        };
+        if let Some(other) = files.insert(file_name.clone(), &case.qualified_name) {
+            return Err(format!(
+                "Test stubs of {} and {} would both be written to {}",
+                other, case.qualified_name, file_name
+            ));
+        }
+        let content = match framework {
+            TestFramework::Pytest => pytest_stub(case),
+            TestFramework::Gtest => gtest_stub(case),
+        };
+        stubs.push(TestStub {
+            case: case.qualified_name.clone(),
+            file_name,
+            content,
+        });
+    }
+    Ok(stubs)
+}
+
+/// Analyze `input` and write a test stub for each verification case to
+/// `out_dir`.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `framework` - Test framework to write stubs for
+/// * `out_dir` - Directory to write the stubs to
+/// * `force` - Overwrite existing files
+/// * `options` - Verbosity and standard library to load
+pub fn generate_tests(
+    input: &Path,
+    framework: TestFramework,
+    out_dir: &Path,
+    force: bool,
+    options: &AnalysisOptions,
+) -> Result<TestScaffold, String> {
+    let plan = verification_plan(input, options)?;
+    let stubs = test_stubs(&plan, framework)?;
+    std::fs::create_dir_all(out_dir)
+        .map_err(|e| format!("Failed to create {}: {}", out_dir.display(), e))?;
+
+    let mut scaffold = TestScaffold::default();
+    for stub in stubs {
+        let path = out_dir.join(&stub.file_name);
+        if path.exists() && !force {
+            scaffold.skipped.push(path);
+            continue;
+        }
+        std::fs::write(&path, stub.content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        if options.verbose {
+            tracing::info!("Wrote test stub for {} to {}", stub.case, path.display());
+        }
+        scaffold.written.push(path);
+    }
+    Ok(scaffold)
+}
+
+/// A pytest module with one skipped test per verified requirement.
+fn pytest_stub(case: &VerificationCase) -> String {
+    let mut out = format!(
+        "\"\"\"Verification case {}.\n",
+        py_text(&case.qualified_name)
+    );
+    for paragraph in header(case) {
+        out.push_str(&format!("\n{}\n", py_text(&paragraph)));
+    }
+    out.push_str("\"\"\"\n\nimport pytest\n");
+
+    for (name, summary, verifies) in tests(case) {
+        out.push_str(&format!(
+            "\n\ndef test_{}():\n    \"\"\"{}",
+            snake(&name),
+            py_text(&summary)
+        ));
+        if let Some(verifies) = verifies {
+            out.push_str(&format!("\n\n    {}\n    ", py_text(&verifies)));
+        }
+        out.push_str("\"\"\"\n    pytest.skip(\"not implemented\")\n");
+    }
+    out
+}
+
+/// A GoogleTest file with one skipped test per verified requirement.
+fn gtest_stub(case: &VerificationCase) -> String {
+    let mut out = format!("// Verification case {}.\n", case.qualified_name);
+    for paragraph in header(case) {
+        out.push_str(&format!("//\n// {}\n", paragraph));
+    }
+    out.push_str("\n#include <gtest/gtest.h>\n");
+
+    let suite = camel(&case.qualified_name);
+    for (name, summary, verifies) in tests(case) {
+        out.push_str(&format!("\n// {}\n", summary));
+        if let Some(verifies) = verifies {
+            out.push_str(&format!("// {}\n", verifies));
+        }
+        out.push_str(&format!(
+            "TEST({}, {}) {{\n    GTEST_SKIP() << \"not implemented\";\n}}\n",
+            suite,
+            camel(&name)
+        ));
+    }
+    out
+}
+
+/// The paragraphs describing a case: its documentation, its subjects and
+/// where it is declared.
+fn header(case: &VerificationCase) -> Vec<String> {
+    let mut paragraphs = Vec::new();
+    if !case.doc.is_empty() {
+        paragraphs.push(case.doc.clone());
+    }
+    if !case.subjects.is_empty() {
+        let subjects: Vec<String> = case
+            .subjects
+            .iter()
+            .map(|s| match s.type_name.as_str() {
+                "" => s.name.clone(),
+                type_name => format!("{} : {}", s.name, type_name),
+            })
+            .collect();
+        paragraphs.push(format!("Subject: {}", subjects.join(", ")));
+    }
+    let file = Path::new(&case.file)
+        .file_name()
+        .map_or_else(|| case.file.clone(), |n| n.to_string_lossy().to_string());
+    paragraphs.push(format!(
+        "Declared at {}:{}; generated by `syster generate-tests`.",
+        file, case.line
+    ));
+    paragraphs
+}
+
+/// The tests of a case as `(name, summary, verifies)`: one per verified
+/// requirement, named after it and summarized by its text, or one named
+/// after the case if it verifies none. Names are unique within the case.
+fn tests(case: &VerificationCase) -> Vec<(String, String, Option<String>)> {
+    if case.requirements.is_empty() {
+        let summary = if case.doc.is_empty() {
+            format!("Verification case {}.", case.qualified_name)
+        } else {
+            case.doc.clone()
+        };
+        return vec![(case.name.clone(), summary, None)];
+    }
+
+    let mut names = HashSet::new();
+    case.requirements
+        .iter()
+        .map(|requirement| {
+            let simple = requirement
+                .qualified_name
+                .rsplit("::")
+                .next()
+                .unwrap_or_default();
+            let mut name = simple.to_string();
+            let mut suffix = 1;
+            while !names.insert(snake(&name)) {
+                suffix += 1;
+                name = format!("{}_{}", simple, suffix);
+            }
+            let verifies = match requirement.id.as_str() {
+                "" => format!("Verifies {}.", requirement.qualified_name),
+                id => format!("Verifies {} ({}).", id, requirement.qualified_name),
+            };
+            if requirement.text.is_empty() {
+                (name, verifies, None)
+            } else {
+                (name, requirement.text.clone(), Some(verifies))
+            }
+        })
+        .collect()
+}
+
+/// `name` as a `snake_case` identifier.
+fn snake(name: &str) -> String {
+    match snake_case(name) {
+        snake if snake.is_empty() => "case".to_string(),
+        snake => snake,
+    }
+}
+
+/// A `CamelCase` GoogleTest suite or test name (GoogleTest reserves
+/// underscores).
+fn camel(name: &str) -> String {
+    snake(name)
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// `text` escaped for a Python docstring.
+fn py_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
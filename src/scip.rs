@@ -0,0 +1,296 @@
+//! SCIP code-intelligence indexes.
+//!
+//! `syster index build --format scip` writes an `index.scip` for
+//! Sourcegraph and other SCIP consumers: every SysML/KerML file of the model
+//! becomes a document with an occurrence for each declared name and each
+//! resolved reference, and every declaration carries its signature and doc
+//! comment as hover documentation.
+//!
+//! Symbols are named `scip-syster . . . <descriptors>`, one descriptor per
+//! segment of the qualified name: `Pkg/` for packages, `Def#` for
+//! definitions, `usage.` for everything else. References into the standard
+//! library use the same scheme but have no defining document.
+//!
+//! The index is a Protocol Buffers message; the few fields used are
+//! encoded by hand rather than through generated bindings.
+
+use super::AnalysisOptions;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use syster::hir::{HirSymbol, RefKind, SymbolIndex, SymbolKind};
+use syster::ide::AnalysisHost;
+
+/// Scheme prefix of every symbol.
+const SCHEME: &str = "scip-syster . . . ";
+
+/// `SymbolRole.Definition`.
+const ROLE_DEFINITION: u64 = 1;
+
+/// `TextEncoding.UTF8`.
+const UTF8: u64 = 1;
+
+/// Build the SCIP index of the model at `input` (stdlib files are not
+/// indexed as documents).
+///
+/// # Arguments
+/// * `input` - Source file or directory; also the project root
+/// * `options` - Verbosity, standard library to load and directory walk
+pub fn build_scip_index(input: &Path, options: &AnalysisOptions) -> Result<Vec<u8>, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let stdlib_files: HashSet<PathBuf> = host.files().keys().cloned().collect();
+    super::load_input_with(&mut host, input, options.verbose, &options.walk)?;
+    let _analysis = host.analysis();
+
+    let root = if input.is_dir() {
+        input
+    } else {
+        input.parent().unwrap_or(Path::new("."))
+    };
+    let root = std::path::absolute(root)
+        .map_err(|e| format!("Failed to resolve {}: {}", root.display(), e))?;
+
+    let mut paths: Vec<&PathBuf> = host
+        .files()
+        .keys()
+        .filter(|path| !stdlib_files.contains(*path))
+        .collect();
+    paths.sort();
+
+    let mut index = Message::default();
+    index.message(1, metadata(&root));
+    for path in paths {
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        let mut symbols = host.symbol_index().symbols_in_file(file_id);
+        symbols.sort_by_key(|s| (s.start_line, s.start_col));
+        let relative = relative_path(path, &root);
+        if options.verbose {
+            tracing::info!("Indexing {} ({} symbols)", relative, symbols.len());
+        }
+        index.message(2, document(&relative, &symbols, host.symbol_index()));
+    }
+    Ok(index.0)
+}
+
+/// `Metadata` naming the tool and project root.
+fn metadata(root: &Path) -> Message {
+    let mut tool = Message::default();
+    tool.string(1, "syster");
+    tool.string(2, env!("CARGO_PKG_VERSION"));
+
+    let mut metadata = Message::default();
+    metadata.message(2, tool);
+    metadata.string(3, &format!("file://{}", root.to_string_lossy()));
+    metadata.varint(4, UTF8);
+    metadata
+}
+
+/// `Document` with the occurrences and declarations of one file.
+fn document(relative_path: &str, symbols: &[&HirSymbol], index: &SymbolIndex) -> Message {
+    let mut document = Message::default();
+    document.string(1, relative_path);
+    let mut information = Vec::new();
+    for symbol in symbols {
+        // References are recorded for anonymous elements too
+        for type_ref in symbol.type_refs.iter().flat_map(|r| r.as_refs()) {
+            let Some(target) = &type_ref.resolved_target else {
+                continue;
+            };
+            document.message(
+                2,
+                occurrence(
+                    [
+                        type_ref.start_line,
+                        type_ref.start_col,
+                        type_ref.end_line,
+                        type_ref.end_col,
+                    ],
+                    &scip_symbol(target, index),
+                    0,
+                ),
+            );
+        }
+        if !is_declaration(symbol) {
+            continue;
+        }
+        let name = scip_symbol(&symbol.qualified_name, index);
+        document.message(
+            2,
+            occurrence(
+                [
+                    symbol.start_line,
+                    symbol.start_col,
+                    symbol.end_line,
+                    symbol.end_col,
+                ],
+                &name,
+                ROLE_DEFINITION,
+            ),
+        );
+        information.push(symbol_information(symbol, &name, index));
+    }
+    for info in information {
+        document.message(3, info);
+    }
+    let language = if relative_path.ends_with(".kerml") {
+        "KerML"
+    } else {
+        "SysML"
+    };
+    document.string(4, language);
+    document
+}
+
+/// `Occurrence` of `symbol` at a 0-based `[start line, start column, end
+/// line, end column]` range.
+fn occurrence(range: [u32; 4], symbol: &str, roles: u64) -> Message {
+    let [start_line, start_col, end_line, end_col] = range;
+    let range = if start_line == end_line {
+        vec![start_line, start_col, end_col]
+    } else {
+        vec![start_line, start_col, end_line, end_col]
+    };
+    let mut occurrence = Message::default();
+    occurrence.packed(1, &range);
+    occurrence.string(2, symbol);
+    if roles != 0 {
+        occurrence.varint(3, roles);
+    }
+    occurrence
+}
+
+/// `SymbolInformation`: hover documentation and the types a declaration is
+/// typed by or specializes.
+fn symbol_information(symbol: &HirSymbol, name: &str, index: &SymbolIndex) -> Message {
+    let mut info = Message::default();
+    info.string(1, name);
+    info.string(
+        3,
+        &format!(
+            "```sysml\n{} {}\n```",
+            symbol.kind.display().to_lowercase(),
+            symbol.qualified_name
+        ),
+    );
+    if let Some(doc) = symbol.doc.as_ref().filter(|d| !d.trim().is_empty()) {
+        info.string(3, doc.trim());
+    }
+    for type_ref in symbol.type_refs.iter().flat_map(|r| r.as_refs()) {
+        let Some(target) = &type_ref.resolved_target else {
+            continue;
+        };
+        // is_type_definition (4) or is_implementation (3)
+        let field = match type_ref.kind {
+            RefKind::TypedBy => 4,
+            RefKind::Specializes | RefKind::Subsets => 3,
+            _ => continue,
+        };
+        let mut relationship = Message::default();
+        relationship.string(1, &scip_symbol(target, index));
+        relationship.varint(field, 1);
+        info.message(4, relationship);
+    }
+    info.string(6, &symbol.name);
+    info
+}
+
+/// Whether `symbol` is a named declaration (not an import or anonymous
+/// element).
+fn is_declaration(symbol: &HirSymbol) -> bool {
+    symbol.kind != SymbolKind::Import && !symbol.name.is_empty() && !symbol.name.starts_with('<')
+}
+
+/// SCIP symbol for a qualified name, with descriptors chosen from the kind
+/// of each enclosing element.
+fn scip_symbol(qualified_name: &str, index: &SymbolIndex) -> String {
+    let mut symbol = SCHEME.to_string();
+    let mut prefix = String::new();
+    for segment in qualified_name.split("::") {
+        if !prefix.is_empty() {
+            prefix.push_str("::");
+        }
+        prefix.push_str(segment);
+        let suffix = match index.lookup_qualified(&prefix).map(|s| s.kind) {
+            Some(SymbolKind::Package) | None => '/',
+            Some(kind) if kind.is_definition() => '#',
+            Some(_) => '.',
+        };
+        symbol.push_str(&escape(segment.trim_matches('\'')));
+        symbol.push(suffix);
+    }
+    symbol
+}
+
+/// Backtick-quote names that are not plain SCIP identifiers.
+fn escape(name: &str) -> String {
+    let simple = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '+' | '-' | '$'));
+    if simple {
+        name.to_string()
+    } else {
+        format!("`{}`", name.replace('`', "``"))
+    }
+}
+
+/// `path` relative to `root`, with `/` separators.
+fn relative_path(path: &Path, root: &Path) -> String {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let relative = absolute.strip_prefix(root).unwrap_or(&absolute);
+    relative
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// A Protocol Buffers message being encoded.
+#[derive(Default)]
+struct Message(Vec<u8>);
+
+impl Message {
+    fn tag(&mut self, field: u32, wire_type: u8) {
+        self.raw_varint((u64::from(field) << 3) | u64::from(wire_type));
+    }
+
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn varint(&mut self, field: u32, value: u64) {
+        self.tag(field, 0);
+        self.raw_varint(value);
+    }
+
+    fn bytes(&mut self, field: u32, bytes: &[u8]) {
+        self.tag(field, 2);
+        self.raw_varint(bytes.len() as u64);
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn string(&mut self, field: u32, value: &str) {
+        self.bytes(field, value.as_bytes());
+    }
+
+    fn message(&mut self, field: u32, message: Message) {
+        self.bytes(field, &message.0);
+    }
+
+    fn packed(&mut self, field: u32, values: &[u32]) {
+        let mut packed = Message::default();
+        for value in values {
+            packed.raw_varint(u64::from(*value));
+        }
+        self.bytes(field, &packed.0);
+    }
+}
@@ -0,0 +1,342 @@
+//! Semantic-versioning check between two releases of a model.
+//!
+//! Both releases are read from interchange files and compared by qualified
+//! name, like [`verify`](super::verify). Each difference is classified:
+//!
+//! - **major** (breaking): a public element removed or made private, an
+//!   element's kind or type changed, a specialization removed;
+//! - **minor** (additive): a public element or specialization added;
+//! - **patch**: changes to private elements and documentation.
+//!
+//! The largest change determines the smallest acceptable next version.
+//! Below 1.0.0, breaking changes bump the minor version and additive ones
+//! the patch version.
+
+use super::verify::{is_compared, kinds_differ, qualified_name};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::Path;
+use syster::interchange::model::Visibility;
+use syster::interchange::{ElementKind, Model, RelationshipKind};
+
+/// How much a change affects users of the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeLevel {
+    /// No effect on users.
+    Patch,
+    /// Backwards-compatible addition.
+    Minor,
+    /// Breaking change.
+    Major,
+}
+
+impl fmt::Display for ChangeLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+        })
+    }
+}
+
+/// A classified difference between two releases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Change {
+    /// How much the change affects users.
+    pub level: ChangeLevel,
+    /// What changed.
+    pub description: String,
+}
+
+/// Result of comparing two releases.
+#[derive(Debug, Serialize)]
+pub struct SemverReport {
+    /// Version of the old release, if known.
+    pub old_version: Option<String>,
+    /// Declared version of the new release, if known.
+    pub new_version: Option<String>,
+    /// Largest change, `None` if the releases are equivalent.
+    pub level: Option<ChangeLevel>,
+    /// Smallest acceptable version for the new release, if the old version
+    /// is known.
+    pub suggested_version: Option<String>,
+    /// Whether the declared version is at least the suggested one, if both
+    /// versions are known.
+    pub sufficient: Option<bool>,
+    /// Every change, most severe first.
+    pub changes: Vec<Change>,
+}
+
+/// Compare two releases read from interchange files (format detected from
+/// the extension).
+///
+/// Versions default to those declared in the manifests of KPAR archives.
+pub fn semver_check(
+    old: &Path,
+    new: &Path,
+    old_version: Option<&str>,
+    new_version: Option<&str>,
+    verbose: bool,
+) -> Result<SemverReport, String> {
+    let old_version = match old_version {
+        Some(version) => Some(version.to_string()),
+        None => declared_version(old)?,
+    };
+    let new_version = match new_version {
+        Some(version) => Some(version.to_string()),
+        None => declared_version(new)?,
+    };
+    let old_parsed = old_version.as_deref().map(Version::parse).transpose()?;
+    let new_parsed = new_version.as_deref().map(Version::parse).transpose()?;
+
+    let (old_model, _) = super::read_decompile_input(old, None, verbose)?;
+    let (new_model, _) = super::read_decompile_input(new, None, verbose)?;
+    let changes = classify_changes(&old_model, &new_model);
+    let level = changes.iter().map(|c| c.level).max();
+
+    let suggested = old_parsed.map(|old| old.bump(level));
+    let sufficient = suggested.zip(new_parsed).map(|(min, new)| new >= min);
+    Ok(SemverReport {
+        old_version,
+        new_version,
+        level,
+        suggested_version: suggested.map(|v| v.to_string()),
+        sufficient,
+        changes,
+    })
+}
+
+/// Classify the differences between two releases, most severe first.
+pub fn classify_changes(old: &Model, new: &Model) -> Vec<Change> {
+    let old_elements = elements(old);
+    let new_elements = elements(new);
+    let mut changes = Vec::new();
+    let mut change = |level, description: String| changes.push(Change { level, description });
+
+    for (name, before) in &old_elements {
+        let Some(after) = new_elements.get(name) else {
+            if before.public {
+                change(
+                    ChangeLevel::Major,
+                    format!("removed public element {}", name),
+                );
+            } else {
+                change(
+                    ChangeLevel::Patch,
+                    format!("removed private element {}", name),
+                );
+            }
+            continue;
+        };
+        let exposed = if before.public {
+            ChangeLevel::Major
+        } else {
+            ChangeLevel::Patch
+        };
+        if kinds_differ(before.kind, after.kind) {
+            change(
+                exposed,
+                format!(
+                    "changed kind of {} from {:?} to {:?}",
+                    name, before.kind, after.kind
+                ),
+            );
+        }
+        match (before.public, after.public) {
+            (true, false) => change(ChangeLevel::Major, format!("made {} private", name)),
+            (false, true) => change(ChangeLevel::Minor, format!("made {} public", name)),
+            _ => {}
+        }
+        if before.documentation != after.documentation {
+            change(
+                ChangeLevel::Patch,
+                format!("changed documentation of {}", name),
+            );
+        }
+    }
+    for (name, after) in &new_elements {
+        if !old_elements.contains_key(name) {
+            if after.public {
+                change(ChangeLevel::Minor, format!("added public element {}", name));
+            } else {
+                change(
+                    ChangeLevel::Patch,
+                    format!("added private element {}", name),
+                );
+            }
+        }
+    }
+
+    // Relationships of elements present in both releases
+    let old_relationships = relationships(old, &old_elements);
+    let new_relationships = relationships(new, &new_elements);
+    let mut keys: Vec<_> = old_relationships
+        .keys()
+        .chain(new_relationships.keys())
+        .filter(|(source, _)| {
+            new_elements.contains_key(source) && old_elements.contains_key(source)
+        })
+        .collect();
+    keys.sort_by(|a, b| (&a.0, format!("{:?}", a.1)).cmp(&(&b.0, format!("{:?}", b.1))));
+    keys.dedup();
+    let empty = BTreeSet::new();
+    for key @ (source, kind) in keys {
+        let before = old_relationships.get(key).unwrap_or(&empty);
+        let after = new_relationships.get(key).unwrap_or(&empty);
+        if before == after {
+            continue;
+        }
+        let public = old_elements[source].public;
+        let exposed = |level| if public { level } else { ChangeLevel::Patch };
+        match kind {
+            RelationshipKind::FeatureTyping => change(
+                exposed(ChangeLevel::Major),
+                format!(
+                    "changed type of {} from {} to {}",
+                    source,
+                    list(before),
+                    list(after)
+                ),
+            ),
+            RelationshipKind::Specialization | RelationshipKind::Subsetting => {
+                for target in before.difference(after) {
+                    change(
+                        exposed(ChangeLevel::Major),
+                        format!("{} no longer specializes {}", source, target),
+                    );
+                }
+                for target in after.difference(before) {
+                    change(
+                        exposed(ChangeLevel::Minor),
+                        format!("{} now specializes {}", source, target),
+                    );
+                }
+            }
+            _ => change(
+                ChangeLevel::Patch,
+                format!("changed {:?} of {}", kind, source),
+            ),
+        }
+    }
+
+    changes.sort_by(|a, b| b.level.cmp(&a.level));
+    changes
+}
+
+/// What is compared of an element.
+struct ElementInfo {
+    kind: ElementKind,
+    public: bool,
+    documentation: Option<String>,
+}
+
+/// Named, comparable elements keyed by qualified name.
+fn elements(model: &Model) -> BTreeMap<String, ElementInfo> {
+    model
+        .elements
+        .values()
+        .filter(|e| e.name.is_some() && is_compared(e.kind))
+        .map(|e| {
+            // Public only if visible all the way from the root
+            let mut public = true;
+            let mut current = Some(e);
+            while let Some(element) = current {
+                public &= element.visibility == Visibility::Public;
+                current = element.owner.as_ref().and_then(|owner| model.get(owner));
+            }
+            let info = ElementInfo {
+                kind: e.kind,
+                public,
+                documentation: e.documentation.as_ref().map(|d| d.trim().to_string()),
+            };
+            (qualified_name(model, &e.id), info)
+        })
+        .collect()
+}
+
+/// Relationship targets by source element and relationship kind.
+fn relationships(
+    model: &Model,
+    elements: &BTreeMap<String, ElementInfo>,
+) -> HashMap<(String, RelationshipKind), BTreeSet<String>> {
+    let mut relationships: HashMap<_, BTreeSet<String>> = HashMap::new();
+    for rel in &model.relationships {
+        let source = qualified_name(model, &rel.source);
+        if elements.contains_key(&source) {
+            relationships
+                .entry((source, rel.kind))
+                .or_default()
+                .insert(qualified_name(model, &rel.target));
+        }
+    }
+    relationships
+}
+
+/// `A, B`, or `nothing`.
+fn list(names: &BTreeSet<String>) -> String {
+    if names.is_empty() {
+        "nothing".to_string()
+    } else {
+        names.iter().cloned().collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// The version in the manifest of a KPAR archive (`None` for other formats).
+fn declared_version(path: &Path) -> Result<Option<String>, String> {
+    let is_kpar = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("kpar"));
+    if !is_kpar {
+        return Ok(None);
+    }
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    super::kpar::manifest_version(&bytes)
+}
+
+/// A `MAJOR.MINOR.PATCH` version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Version(u64, u64, u64);
+
+impl Version {
+    /// Parse `1.2.3` (or `v1.2.3`).
+    fn parse(text: &str) -> Result<Self, String> {
+        let parts = text
+            .strip_prefix('v')
+            .unwrap_or(text)
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()
+            .filter(|parts| parts.len() == 3)
+            .ok_or_else(|| {
+                format!(
+                    "Unsupported version '{}' (expected MAJOR.MINOR.PATCH)",
+                    text
+                )
+            })?;
+        Ok(Self(parts[0], parts[1], parts[2]))
+    }
+
+    /// The smallest version allowed after changes up to `level`.
+    fn bump(self, level: Option<ChangeLevel>) -> Self {
+        let Self(major, minor, patch) = self;
+        match (level, major) {
+            (None, _) => self,
+            (Some(ChangeLevel::Major), 0) => Self(0, minor + 1, 0),
+            (Some(ChangeLevel::Major), _) => Self(major + 1, 0, 0),
+            (Some(ChangeLevel::Minor), 0) => Self(0, minor, patch + 1),
+            (Some(ChangeLevel::Minor), _) => Self(major, minor + 1, 0),
+            (Some(ChangeLevel::Patch), _) => Self(major, minor, patch + 1),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
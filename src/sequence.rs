@@ -0,0 +1,401 @@
+//! Sequence diagrams of behaviors (`--export plantuml-sequence`).
+//!
+//! Each behavior — an action, state or use case not nested in another, or
+//! any other element declaring messages or flows between its parts — gets
+//! one diagram with its steps in source order:
+//!
+//! - a named action occurrence, on the lifeline of the part performing it
+//!   (`part engine { perform drive.start; }`), or on the behavior's own
+//!   lifeline if no part does;
+//! - a message or flow, as an arrow from the part at its source end to the
+//!   part at its target end (`driver.ignition` is on `driver`), labelled
+//!   with its name and payload.
+//!
+//! Nested actions and states contribute their own steps after themselves.
+
+use super::AnalysisOptions;
+use super::names::qualify;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use syster::hir::{Resolver, SymbolIndex};
+use syster::ide::AnalysisHost;
+use syster::syntax::{
+    NormalizedDefKind, NormalizedElement, NormalizedRelKind, NormalizedUsage, NormalizedUsageKind,
+    RowanNormalizedIter, SyntaxFile,
+};
+
+/// One step of a sequence diagram.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum SequenceStep {
+    /// An action occurrence on the lifeline performing it.
+    Action {
+        action: String,
+        lifeline: String,
+        line: u32,
+    },
+    /// A message or flow from one lifeline to another.
+    Message {
+        from: String,
+        to: String,
+        label: String,
+        line: u32,
+    },
+}
+
+/// The sequence of one behavior.
+#[derive(Debug, Clone, Serialize)]
+pub struct SequenceDiagram {
+    /// Qualified name of the behavior.
+    pub behavior: String,
+    /// File declaring the behavior.
+    pub file: String,
+    /// Declaration line (1-based).
+    pub line: u32,
+    /// Lifelines in order of first appearance.
+    pub participants: Vec<String>,
+    /// Steps in source order.
+    pub steps: Vec<SequenceStep>,
+}
+
+/// Analyze `input` and collect a sequence diagram per behavior with at
+/// least one action occurrence or message, by file and line.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `options` - Verbosity and standard library to load
+pub fn sequence_diagrams(
+    input: &Path,
+    options: &AnalysisOptions,
+) -> Result<Vec<SequenceDiagram>, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let library: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    super::load_input(&mut host, input, options.verbose)?;
+    let _analysis = host.analysis();
+    let index = host.symbol_index();
+
+    let mut paths: Vec<_> = host
+        .files()
+        .keys()
+        .filter(|path| !library.contains(&*path.to_string_lossy()))
+        .collect();
+    paths.sort();
+    let mut behaviors = Vec::new();
+    let mut performs = Vec::new();
+    for path in paths {
+        file_behaviors(
+            &path.to_string_lossy(),
+            &host.files()[path],
+            &mut behaviors,
+            &mut performs,
+        );
+    }
+
+    // The first part found performing an action is its lifeline
+    let mut performers: HashMap<String, String> = HashMap::new();
+    for perform in performs {
+        if let Some(action) = resolve(index, &perform.scope, &perform.action) {
+            performers.entry(action).or_insert(perform.part);
+        }
+    }
+
+    let diagrams: Vec<SequenceDiagram> = behaviors
+        .into_iter()
+        .filter(|behavior| !behavior.steps.is_empty())
+        .map(|behavior| {
+            let own = simple_name(&behavior.behavior);
+            let steps: Vec<SequenceStep> = draft
+                .steps
+                .into_iter()
+                .map(|step| match step {
+                    Draft::Action {
+                        qualified_name,
+                        line,
+                    } => SequenceStep::Action {
+                        action: simple_name(&qualified_name),
+                        lifeline: performers
+                            .get(&qualified_name)
+                            .cloned()
+                            .unwrap_or_else(|| own.clone()),
+                        line,
+                    },
+                    Draft::Message(step) => step,
+                })
+                .collect();
+            let mut participants: Vec<String> = Vec::new();
+            for step in &steps {
+                let lifelines = match step {
+                    SequenceStep::Action { lifeline, .. } => vec![lifeline],
+                    SequenceStep::Message { from, to, .. } => vec![from, to],
+                };
+                for lifeline in lifelines {
+                    if !participants.contains(lifeline) {
+                        participants.push(lifeline.clone());
+                    }
+                }
+            }
+            SequenceDiagram {
+                behavior: behavior.behavior,
+                file: behavior.file,
+                line: behavior.line,
+                participants,
+                steps,
+            }
+        })
+        .collect();
+
+    if options.verbose {
+        tracing::info!("Found {} behaviors with a sequence", diagrams.len());
+    }
+    Ok(diagrams)
+}
+
+/// Render `diagrams` as PlantUML, one `@startuml` block per behavior named
+/// after it.
+pub fn render_plantuml_sequence(diagrams: &[SequenceDiagram]) -> String {
+    let mut out = String::new();
+    for (i, diagram) in diagrams.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            "@startuml {}\ntitle {}\n",
+            alias(&diagram.behavior),
+            diagram.behavior
+        ));
+        for participant in &diagram.participants {
+            if alias(participant) == *participant {
+                out.push_str(&format!("participant {}\n", participant));
+            } else {
+                out.push_str(&format!(
+                    "participant \"{}\" as {}\n",
+                    participant,
+                    alias(participant)
+                ));
+            }
+        }
+        for step in &diagram.steps {
+            match step {
+                SequenceStep::Action {
+                    action, lifeline, ..
+                } => {
+                    out.push_str(&format!("hnote over {} : {}\n", alias(lifeline), action));
+                }
+                SequenceStep::Message {
+                    from, to, label, ..
+                } if label.is_empty() => {
+                    out.push_str(&format!("{} -> {}\n", alias(from), alias(to)));
+                }
+                SequenceStep::Message {
+                    from, to, label, ..
+                } => {
+                    out.push_str(&format!("{} -> {} : {}\n", alias(from), alias(to), label));
+                }
+            }
+        }
+        out.push_str("@enduml\n");
+    }
+    out
+}
+
+/// A behavior as written, before its actions are given lifelines.
+struct Behavior {
+    behavior: String,
+    file: String,
+    line: u32,
+    steps: Vec<Draft>,
+}
+
+/// A step as written.
+enum Draft {
+    Action { qualified_name: String, line: u32 },
+    Message(SequenceStep),
+}
+
+/// A `perform` in the body of a part usage.
+struct Perform {
+    scope: String,
+    action: String,
+    part: String,
+}
+
+/// The behaviors and performed actions declared in one file.
+fn file_behaviors(
+    path: &str,
+    file: &SyntaxFile,
+    behaviors: &mut Vec<Behavior>,
+    performs: &mut Vec<Perform>,
+) {
+    let Some(source) = file.source_file() else {
+        return;
+    };
+    let lines = file.line_index();
+    // (scope, element, behavior whose steps it adds to, part usage owning it)
+    let mut pending: Vec<(String, NormalizedElement, Option<usize>, Option<String>)> =
+        RowanNormalizedIter::new(&source)
+            .map(|element| (String::new(), element, None, None))
+            .collect();
+    pending.reverse();
+    while let Some((scope, element, current, part)) = pending.pop() {
+        // `behavior` is None for elements that never get a diagram
+        let (name, behavior, range, children, performer) = match element {
+            NormalizedElement::Package(p) => (p.name, None, None, p.children, None),
+            NormalizedElement::Definition(d) => {
+                let behavior = matches!(
+                    d.kind,
+                    NormalizedDefKind::Action
+                        | NormalizedDefKind::State
+                        | NormalizedDefKind::UseCase
+                );
+                (d.name, Some(behavior), d.range, d.children, None)
+            }
+            NormalizedElement::Usage(u) => {
+                let line = u.range.map_or(0, |r| lines.line_col(r.start()).line) + 1;
+                let performed = u
+                    .relationships
+                    .iter()
+                    .find(|r| r.kind == NormalizedRelKind::Performs);
+                if let (Some(performed), Some(part)) = (performed, &part) {
+                    performs.push(Perform {
+                        scope: scope.clone(),
+                        action: performed.target.as_str().into_owned(),
+                        part: part.clone(),
+                    });
+                }
+                if let Some(index) = current {
+                    if let Some(step) = message(&u, line) {
+                        behaviors[index].steps.push(Draft::Message(step));
+                    } else if let Some(name) = u.name.as_ref().filter(|_| is_action(&u)) {
+                        behaviors[index].steps.push(Draft::Action {
+                            qualified_name: qualify(&scope, name),
+                            line,
+                        });
+                    }
+                }
+                let behavior = is_action(&u)
+                    || matches!(
+                        u.kind,
+                        NormalizedUsageKind::State | NormalizedUsageKind::Occurrence
+                    );
+                let performer = u
+                    .name
+                    .as_deref()
+                    .filter(|_| u.kind == NormalizedUsageKind::Part)
+                    .map(simple_name);
+                (u.name, Some(behavior), u.range, u.children, performer)
+            }
+            _ => continue,
+        };
+
+        let scope = match &name {
+            Some(name) => qualify(&scope, name),
+            None => scope,
+        };
+        let mut inner = current;
+        let mut whole = true;
+        if current.is_none()
+            && name.is_some()
+            && behavior.is_some_and(|b| b || children.iter().any(is_message))
+        {
+            behaviors.push(Behavior {
+                behavior: scope.clone(),
+                file: path.to_string(),
+                line: range.map_or(0, |r| lines.line_col(r.start()).line) + 1,
+                steps: Vec::new(),
+            });
+            inner = Some(behaviors.len() - 1);
+            whole = behavior == Some(true);
+        }
+        // A behavior's whole body is its sequence; other elements only add
+        // the messages they declare, and nested behaviors get their own
+        pending.extend(children.into_iter().rev().map(|child| {
+            let current = inner.filter(|_| whole || is_message(&child));
+            (scope.clone(), child, current, performer.clone())
+        }));
+    }
+}
+
+/// Whether `usage` is an action (not a control node or accept).
+fn is_action(usage: &NormalizedUsage) -> bool {
+    usage.kind == NormalizedUsageKind::Action
+}
+
+/// Whether `element` is a message or flow with both ends.
+fn is_message(element: &NormalizedElement) -> bool {
+    matches!(element, NormalizedElement::Usage(u) if message(u, 0).is_some())
+}
+
+/// `usage` as a message step, if it is a message or flow with both ends.
+fn message(usage: &NormalizedUsage, line: u32) -> Option<SequenceStep> {
+    if usage.kind != NormalizedUsageKind::Flow {
+        return None;
+    }
+    let target = |kind: NormalizedRelKind| {
+        usage
+            .relationships
+            .iter()
+            .find(|r| r.kind == kind)
+            .map(|r| r.target.as_str().into_owned())
+    };
+    let from = target(NormalizedRelKind::FlowSource)?;
+    let to = target(NormalizedRelKind::FlowTarget)?;
+    let name = usage.name.as_deref().map(|n| n.trim_matches('\''));
+    let label = match (name, target(NormalizedRelKind::TypedBy)) {
+        (Some(name), Some(payload)) => format!("{} : {}", name, payload),
+        (Some(name), None) => name.to_string(),
+        (None, Some(payload)) => payload,
+        (None, None) => String::new(),
+    };
+    Some(SequenceStep::Message {
+        from: lifeline(&from),
+        to: lifeline(&to),
+        label,
+        line,
+    })
+}
+
+/// The lifeline of a message end: the part it starts at.
+fn lifeline(end: &str) -> String {
+    let first = end.split('.').next().unwrap_or(end);
+    simple_name(first)
+}
+
+/// The last segment of a qualified name, unquoted.
+fn simple_name(name: &str) -> String {
+    name.rsplit("::")
+        .next()
+        .unwrap_or(name)
+        .trim_matches('\'')
+        .to_string()
+}
+
+/// The element a (possibly dotted) name written in `scope` refers to.
+fn resolve(index: &SymbolIndex, scope: &str, written: &str) -> Option<String> {
+    let parts: Vec<Arc<str>> = written.split('.').map(Arc::from).collect();
+    if parts.len() > 1 {
+        return index
+            .resolve_feature_chain_member(scope, &parts, parts.len() - 1)
+            .map(|member| member.to_string());
+    }
+    Resolver::new(index)
+        .with_scope(scope)
+        .resolve(&parts[0])
+        .symbol()
+        .map(|symbol| symbol.qualified_name.to_string())
+}
+
+/// A PlantUML identifier for `name`.
+fn alias(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
@@ -0,0 +1,278 @@
+//! Ambiguous-import and shadowing warnings.
+//!
+//! When two wildcard imports bring in the same simple name, the resolver
+//! silently picks one of them, and a local declaration silently hides an
+//! imported element of the same name. Both are legal, but rarely intended,
+//! so this pass warns about:
+//!
+//! - type references to a name that two or more wildcard imports of the
+//!   resolving scope provide with different targets, and
+//! - declarations whose name is also provided by an import of the
+//!   enclosing scope.
+//!
+//! Each warning lists the candidates and points at the imports through
+//! related information. Wildcard overlaps that are never referenced are
+//! not reported.
+
+use super::names::parent_scope;
+use super::{DiagnosticInfo, RelatedInformation};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use syster::hir::{HirSymbol, Severity, SymbolIndex, SymbolKind, TypeRefKind};
+use syster::ide::AnalysisHost;
+
+/// An import statement and the file declaring it.
+struct Import<'a> {
+    symbol: &'a HirSymbol,
+    file: String,
+    /// Whether this is `P::*` or `P::**` rather than `P::Name`.
+    wildcard: bool,
+}
+
+/// Names visible in a scope through its imports:
+/// simple name → qualified target → importing statements.
+type Candidates<'a> = BTreeMap<String, BTreeMap<String, Vec<&'a Import<'a>>>>;
+
+/// Warn about ambiguous wildcard imports and shadowed imported names,
+/// reporting only diagnostics in `files`.
+pub(crate) fn import_diagnostics(
+    host: &AnalysisHost,
+    files: &HashSet<&str>,
+) -> Vec<DiagnosticInfo> {
+    let index = host.symbol_index();
+
+    let mut imports_by_scope: HashMap<&str, Vec<Import>> = HashMap::new();
+    for symbol in index.all_symbols() {
+        if symbol.kind != SymbolKind::Import {
+            continue;
+        }
+        let Some(file) = host.get_file_path(symbol.file) else {
+            continue;
+        };
+        imports_by_scope
+            .entry(import_scope(&symbol.qualified_name))
+            .or_default()
+            .push(Import {
+                symbol,
+                file: file.to_string(),
+                wildcard: symbol.name.ends_with("::*") || symbol.name.ends_with("::**"),
+            });
+    }
+    let candidates: HashMap<&str, Candidates> = imports_by_scope
+        .iter()
+        .map(|(scope, imports)| (*scope, scope_candidates(index, scope, imports)))
+        .collect();
+
+    let mut diagnostics = Vec::new();
+    let mut reported = HashSet::new();
+    for path in host.files().keys() {
+        let file = path.to_string_lossy().to_string();
+        if !files.contains(file.as_str()) {
+            continue;
+        }
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        for symbol in index.symbols_in_file(file_id) {
+            if symbol.kind == SymbolKind::Import {
+                continue;
+            }
+            let scope = parent_scope(&symbol.qualified_name);
+
+            // Declarations hiding an imported name
+            if !symbol.name.starts_with('<') {
+                let hidden = candidates
+                    .get(scope)
+                    .and_then(|c| c.get(symbol.name.as_ref()))
+                    .map(|targets| {
+                        targets
+                            .iter()
+                            .filter(|(target, _)| target.as_str() != &*symbol.qualified_name)
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                if !hidden.is_empty() {
+                    diagnostics.push(warning(
+                        &file,
+                        (
+                            symbol.start_line,
+                            symbol.start_col,
+                            symbol.end_line,
+                            symbol.end_col,
+                        ),
+                        format!("'{}' shadows imported {}", symbol.name, describe(&hidden)),
+                        &hidden,
+                    ));
+                }
+            }
+
+            // References resolved through ambiguous wildcard imports
+            for type_ref in &symbol.type_refs {
+                // Feature chains and redefinitions resolve through types, not imports
+                let TypeRefKind::Simple(r) = type_ref else {
+                    continue;
+                };
+                if !r.kind.is_type_reference() || r.target.contains("::") {
+                    continue;
+                }
+                let Some(ambiguous) = ambiguous_in(index, &candidates, scope, &r.target) else {
+                    continue;
+                };
+                if !reported.insert((file.clone(), r.start_line, r.start_col)) {
+                    continue;
+                }
+                diagnostics.push(warning(
+                    &file,
+                    (r.start_line, r.start_col, r.end_line, r.end_col),
+                    format!(
+                        "ambiguous name '{}': could be {}",
+                        r.target,
+                        describe(&ambiguous)
+                    ),
+                    &ambiguous,
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Resolve `name` from `scope` outwards the way the resolver does, and
+/// return the candidates if the first scope providing it only does so
+/// through several conflicting wildcard imports.
+fn ambiguous_in<'c, 'a>(
+    index: &SymbolIndex,
+    candidates: &'c HashMap<&str, Candidates<'a>>,
+    scope: &str,
+    name: &str,
+) -> Option<Vec<(&'c String, &'c Vec<&'a Import<'a>>)>> {
+    let mut scope = scope;
+    loop {
+        let defined = index
+            .visibility_for_scope(scope)
+            .is_some_and(|vis| vis.lookup_direct(name).is_some());
+        if defined {
+            return None;
+        }
+        if let Some(targets) = candidates.get(scope).and_then(|c| c.get(name)) {
+            let explicit = targets.values().flatten().any(|import| !import.wildcard);
+            return (!explicit && targets.len() > 1).then(|| targets.iter().collect());
+        }
+        if scope.is_empty() {
+            return None;
+        }
+        scope = parent_scope(scope);
+    }
+}
+
+/// Collect the names made visible by the imports of one scope.
+fn scope_candidates<'a>(
+    index: &SymbolIndex,
+    scope: &str,
+    imports: &'a [Import<'a>],
+) -> Candidates<'a> {
+    let mut candidates: Candidates = BTreeMap::new();
+    for import in imports {
+        let path = &import.symbol.name;
+        let target = path
+            .strip_suffix("::**")
+            .or_else(|| path.strip_suffix("::*"))
+            .unwrap_or(path);
+        let target = resolve_target(index, scope, target);
+
+        if import.wildcard {
+            let Some(vis) = index.visibility_for_scope(&target) else {
+                continue;
+            };
+            for (name, qualified_name) in vis.direct_defs() {
+                candidates
+                    .entry(name.to_string())
+                    .or_default()
+                    .entry(qualified_name.to_string())
+                    .or_default()
+                    .push(import);
+            }
+        } else {
+            let name = target.rsplit("::").next().unwrap_or(&target).to_string();
+            candidates
+                .entry(name)
+                .or_default()
+                .entry(target.clone())
+                .or_default()
+                .push(import);
+        }
+    }
+    candidates
+}
+
+/// Resolve an import target as written in `scope`: a fully qualified name,
+/// or a name relative to `scope` or one of its parents.
+fn resolve_target(index: &SymbolIndex, scope: &str, target: &str) -> String {
+    if index.lookup_qualified(target).is_some() {
+        return target.to_string();
+    }
+    let mut scope = scope;
+    while !scope.is_empty() {
+        let qualified = format!("{}::{}", scope, target);
+        if index.lookup_qualified(&qualified).is_some() {
+            return qualified;
+        }
+        scope = parent_scope(scope);
+    }
+    target.to_string()
+}
+
+/// The scope declaring an import symbol (`P::import:Q::*` → `P`).
+pub(crate) fn import_scope(qualified_name: &str) -> &str {
+    match qualified_name.find("import:") {
+        Some(pos) => qualified_name[..pos].trim_end_matches("::"),
+        None => parent_scope(qualified_name),
+    }
+}
+
+/// "A::X (import A::*) or B::X (import B::*)".
+fn describe(targets: &[(&String, &Vec<&Import>)]) -> String {
+    targets
+        .iter()
+        .map(|(target, imports)| {
+            let via: Vec<String> = imports
+                .iter()
+                .map(|i| format!("import {}", i.symbol.name))
+                .collect();
+            format!("{} ({})", target, via.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+/// A warning at `span` (0-indexed) pointing at the imports of `targets`.
+fn warning(
+    file: &str,
+    span: (u32, u32, u32, u32),
+    message: String,
+    targets: &[(&String, &Vec<&Import>)],
+) -> DiagnosticInfo {
+    let related = targets
+        .iter()
+        .flat_map(|(target, imports)| {
+            imports.iter().map(move |import| RelatedInformation {
+                file: import.file.clone(),
+                line: import.symbol.start_line + 1,
+                col: import.symbol.start_col + 1,
+                message: format!("'{}' imported here", target),
+            })
+        })
+        .collect();
+    DiagnosticInfo {
+        file: file.to_string(),
+        line: span.0 + 1,
+        col: span.1 + 1,
+        end_line: span.2 + 1,
+        end_col: span.3 + 1,
+        message,
+        severity: Severity::Warning,
+        code: None,
+        blame: None,
+        owners: Vec::new(),
+        related,
+    }
+}
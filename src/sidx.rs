@@ -0,0 +1,457 @@
+//! Pre-built symbol indexes (`.sidx`) for cross-repository models.
+//!
+//! `syster index build` records the declarations of a model repository —
+//! packages, definitions, usages, public imports, and the types they are
+//! typed by or specialize — in a compact binary file. Other repositories
+//! pass it to `--index` to resolve references into the dependency without
+//! loading its sources: the index is expanded into a declaration-only SysML
+//! skeleton and loaded next to the standard library.
+//!
+//! Bodies, expressions, redefinitions and KerML-only elements are not
+//! recorded, so diagnostics are only ever reported for the model's own
+//! files, never for indexed dependencies.
+//!
+//! # Format
+//!
+//! All integers are little-endian `u32` unless noted:
+//!
+//! ```text
+//! "SIDX" version
+//! string-count (length utf8-bytes)*
+//! symbol-count (qualified-name kind flags:u8 typed-by-count typed-by* specializes-count specializes*)*
+//! ```
+//!
+//! Names and kinds are indexes into the string table; kinds are stored as
+//! their SysML keyword (`part def`, `attribute`, `import`, ...).
+
+use super::AnalysisOptions;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syster::hir::{RefKind, SymbolKind, TypeRefKind};
+use syster::ide::AnalysisHost;
+
+/// File signature.
+const MAGIC: &[u8; 4] = b"SIDX";
+
+/// Format version, bumped on incompatible changes.
+const VERSION: u32 = 1;
+
+/// Flag bit: the element is abstract.
+const ABSTRACT: u8 = 1;
+
+/// A declaration recorded in a [`ModelIndex`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedSymbol {
+    /// Fully qualified name (`Scope::import:Path` for imports).
+    pub qualified_name: String,
+    /// Element kind.
+    pub kind: SymbolKind,
+    /// Whether the element is abstract.
+    pub is_abstract: bool,
+    /// Qualified names of the types of a usage (`:`).
+    pub typed_by: Vec<String>,
+    /// Qualified names of specialized or subsetted elements (`:>`).
+    pub specializes: Vec<String>,
+}
+
+/// Declarations of a model repository, as written by `syster index build`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelIndex {
+    /// Declarations, ordered by qualified name.
+    pub symbols: Vec<IndexedSymbol>,
+    /// Where the index was read from, used to name its skeleton file.
+    path: PathBuf,
+}
+
+/// Build the index of the model at `input` (stdlib elements are not indexed).
+pub fn build_index(input: &Path, options: &AnalysisOptions) -> Result<ModelIndex, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let stdlib_files: HashSet<PathBuf> = host.files().keys().cloned().collect();
+    super::load_input_with(&mut host, input, options.verbose, &options.walk)?;
+    let _analysis = host.analysis();
+
+    let mut symbols = BTreeMap::new();
+    for path in host.files().keys() {
+        if stdlib_files.contains(path) {
+            continue;
+        }
+        let Some(file_id) = host.get_file_id_for_path(path) else {
+            continue;
+        };
+        for symbol in host.symbol_index().symbols_in_file(file_id) {
+            let indexed = match symbol.kind {
+                SymbolKind::Import => symbol.is_public,
+                kind => keyword(kind).is_some() && !symbol.name.starts_with('<'),
+            };
+            if !indexed || symbols.contains_key(symbol.qualified_name.as_ref()) {
+                continue;
+            }
+            let (mut typed_by, mut specializes) = (Vec::new(), Vec::new());
+            for type_ref in &symbol.type_refs {
+                let TypeRefKind::Simple(r) = type_ref else {
+                    continue;
+                };
+                let target = r.resolved_target.as_ref().unwrap_or(&r.target).to_string();
+                match r.kind {
+                    RefKind::TypedBy => typed_by.push(target),
+                    RefKind::Specializes | RefKind::Subsets => specializes.push(target),
+                    _ => {}
+                }
+            }
+            symbols.insert(
+                symbol.qualified_name.to_string(),
+                IndexedSymbol {
+                    qualified_name: symbol.qualified_name.to_string(),
+                    kind: symbol.kind,
+                    is_abstract: symbol.is_abstract,
+                    typed_by,
+                    specializes,
+                },
+            );
+        }
+    }
+
+    Ok(ModelIndex {
+        symbols: symbols.into_values().collect(),
+        path: PathBuf::new(),
+    })
+}
+
+impl ModelIndex {
+    /// Read an index written by [`ModelIndex::write`].
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let mut index = Self::from_bytes(&bytes)
+            .map_err(|e| format!("Invalid symbol index {}: {}", path.display(), e))?;
+        index.path = path.to_path_buf();
+        Ok(index)
+    }
+
+    /// Write the index to `path`.
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Encode the index in the binary `.sidx` format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut strings = StringTable::default();
+
+        let mut body = Vec::new();
+        put_u32(&mut body, self.symbols.len() as u32);
+        for symbol in &self.symbols {
+            put_u32(&mut body, strings.id(&symbol.qualified_name));
+            put_u32(
+                &mut body,
+                strings.id(keyword(symbol.kind).unwrap_or(IMPORT)),
+            );
+            body.push(if symbol.is_abstract { ABSTRACT } else { 0 });
+            for targets in [&symbol.typed_by, &symbol.specializes] {
+                put_u32(&mut body, targets.len() as u32);
+                for target in targets {
+                    put_u32(&mut body, strings.id(target));
+                }
+            }
+        }
+
+        let mut bytes = MAGIC.to_vec();
+        put_u32(&mut bytes, VERSION);
+        put_u32(&mut bytes, strings.strings.len() as u32);
+        for s in &strings.strings {
+            put_u32(&mut bytes, s.len() as u32);
+            bytes.extend_from_slice(s.as_bytes());
+        }
+        bytes.extend(body);
+        bytes
+    }
+
+    /// Decode an index in the binary `.sidx` format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err("not a symbol index".to_string());
+        }
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(format!(
+                "unsupported version {} (expected {})",
+                version, VERSION
+            ));
+        }
+
+        let strings = (0..reader.u32()?)
+            .map(|_| {
+                let len = reader.u32()? as usize;
+                String::from_utf8(reader.take(len)?.to_vec())
+                    .map_err(|_| "invalid UTF-8 in string table".to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let string = |id: u32| {
+            strings
+                .get(id as usize)
+                .cloned()
+                .ok_or_else(|| format!("string {} out of range", id))
+        };
+
+        let mut symbols = Vec::new();
+        for _ in 0..reader.u32()? {
+            let qualified_name = string(reader.u32()?)?;
+            let kind_name = string(reader.u32()?)?;
+            let kind = kind_from_keyword(&kind_name)
+                .ok_or_else(|| format!("unknown element kind '{}'", kind_name))?;
+            let flags = reader.take(1)?[0];
+            let mut targets = [Vec::new(), Vec::new()];
+            for list in &mut targets {
+                for _ in 0..reader.u32()? {
+                    list.push(string(reader.u32()?)?);
+                }
+            }
+            let [typed_by, specializes] = targets;
+            symbols.push(IndexedSymbol {
+                qualified_name,
+                kind,
+                is_abstract: flags & ABSTRACT != 0,
+                typed_by,
+                specializes,
+            });
+        }
+        if reader.pos != bytes.len() {
+            return Err("trailing data".to_string());
+        }
+
+        Ok(Self {
+            symbols,
+            path: PathBuf::new(),
+        })
+    }
+
+    /// Render the declarations as a SysML skeleton.
+    pub fn to_sysml(&self) -> String {
+        let mut children: BTreeMap<&str, Vec<&IndexedSymbol>> = BTreeMap::new();
+        for symbol in &self.symbols {
+            children
+                .entry(owner(&symbol.qualified_name))
+                .or_default()
+                .push(symbol);
+        }
+        let mut out = String::new();
+        render(&mut out, &children, "", None, 0);
+        out
+    }
+
+    /// Load the skeleton into `host`, returning the path it was loaded as.
+    pub(crate) fn load(&self, host: &mut AnalysisHost, verbose: bool) -> String {
+        let path = format!("{}.sysml", self.path.display());
+        if verbose {
+            tracing::info!(
+                "Loading index: {} ({} symbols)",
+                self.path.display(),
+                self.symbols.len()
+            );
+        }
+        for err in host.set_file_content(&path, &self.to_sysml()) {
+            tracing::warn!(
+                "parse error: {}:{}:{}: {}",
+                path,
+                err.position.line,
+                err.position.column,
+                err.message
+            );
+        }
+        path
+    }
+}
+
+/// Keyword recorded for imports.
+const IMPORT: &str = "import";
+
+/// SysML keyword declaring elements of `kind`, for the kinds an index records.
+fn keyword(kind: SymbolKind) -> Option<&'static str> {
+    KEYWORDS
+        .iter()
+        .find(|(k, _)| *k == kind)
+        .map(|(_, keyword)| *keyword)
+}
+
+fn kind_from_keyword(keyword: &str) -> Option<SymbolKind> {
+    if keyword == IMPORT {
+        return Some(SymbolKind::Import);
+    }
+    KEYWORDS
+        .iter()
+        .find(|(_, k)| *k == keyword)
+        .map(|(kind, _)| *kind)
+}
+
+const KEYWORDS: &[(SymbolKind, &str)] = &[
+    (SymbolKind::Package, "package"),
+    (SymbolKind::PartDefinition, "part def"),
+    (SymbolKind::ItemDefinition, "item def"),
+    (SymbolKind::ActionDefinition, "action def"),
+    (SymbolKind::PortDefinition, "port def"),
+    (SymbolKind::AttributeDefinition, "attribute def"),
+    (SymbolKind::ConnectionDefinition, "connection def"),
+    (SymbolKind::InterfaceDefinition, "interface def"),
+    (SymbolKind::AllocationDefinition, "allocation def"),
+    (SymbolKind::RequirementDefinition, "requirement def"),
+    (SymbolKind::ConstraintDefinition, "constraint def"),
+    (SymbolKind::StateDefinition, "state def"),
+    (SymbolKind::CalculationDefinition, "calc def"),
+    (SymbolKind::UseCaseDefinition, "use case def"),
+    (SymbolKind::AnalysisCaseDefinition, "analysis def"),
+    (SymbolKind::ConcernDefinition, "concern def"),
+    (SymbolKind::ViewDefinition, "view def"),
+    (SymbolKind::ViewpointDefinition, "viewpoint def"),
+    (SymbolKind::RenderingDefinition, "rendering def"),
+    (SymbolKind::EnumerationDefinition, "enum def"),
+    (SymbolKind::MetadataDefinition, "metadata def"),
+    (SymbolKind::PartUsage, "part"),
+    (SymbolKind::ItemUsage, "item"),
+    (SymbolKind::ActionUsage, "action"),
+    (SymbolKind::PortUsage, "port"),
+    (SymbolKind::AttributeUsage, "attribute"),
+    (SymbolKind::ConnectionUsage, "connection"),
+    (SymbolKind::InterfaceUsage, "interface"),
+    (SymbolKind::AllocationUsage, "allocation"),
+    (SymbolKind::RequirementUsage, "requirement"),
+    (SymbolKind::ConstraintUsage, "constraint"),
+    (SymbolKind::StateUsage, "state"),
+    (SymbolKind::CalculationUsage, "calc"),
+    (SymbolKind::ReferenceUsage, "ref"),
+    (SymbolKind::OccurrenceUsage, "occurrence"),
+    (SymbolKind::ViewUsage, "view"),
+];
+
+/// The qualified name of the scope declaring `qualified_name`
+/// (`P::import:Q::*` → `P`, `P::Q` → `P`, `Q` → `""`).
+fn owner(qualified_name: &str) -> &str {
+    if let Some(pos) = qualified_name.find("import:") {
+        return qualified_name[..pos].trim_end_matches("::");
+    }
+    qualified_name
+        .rsplit_once("::")
+        .map(|(owner, _)| owner)
+        .unwrap_or("")
+}
+
+/// Write the members of `scope` (of kind `scope_kind`) and their bodies.
+fn render(
+    out: &mut String,
+    children: &BTreeMap<&str, Vec<&IndexedSymbol>>,
+    scope: &str,
+    scope_kind: Option<SymbolKind>,
+    depth: usize,
+) {
+    let indent = "    ".repeat(depth);
+    for symbol in children.get(scope).into_iter().flatten() {
+        let qn = symbol.qualified_name.as_str();
+        if symbol.kind == SymbolKind::Import {
+            let path = &qn[qn.find("import:").unwrap_or(0) + "import:".len()..];
+            out.push_str(&format!("{}public import {};\n", indent, path));
+            continue;
+        }
+
+        let keyword = match scope_kind {
+            Some(SymbolKind::EnumerationDefinition) if !symbol.kind.is_definition() => "enum",
+            _ => keyword(symbol.kind).unwrap_or("part"),
+        };
+        let name = qn.rsplit("::").next().unwrap_or(qn);
+        out.push_str(&indent);
+        if symbol.is_abstract {
+            out.push_str("abstract ");
+        }
+        out.push_str(&format!("{} {}", keyword, quote(name)));
+        if !symbol.typed_by.is_empty() {
+            out.push_str(&format!(" : {}", qualified(&symbol.typed_by)));
+        }
+        if !symbol.specializes.is_empty() {
+            out.push_str(&format!(" :> {}", qualified(&symbol.specializes)));
+        }
+        if children.contains_key(qn) {
+            out.push_str(" {\n");
+            render(out, children, qn, Some(symbol.kind), depth + 1);
+            out.push_str(&format!("{}}}\n", indent));
+        } else {
+            out.push_str(";\n");
+        }
+    }
+}
+
+/// `A::B, C` with each segment quoted as needed.
+fn qualified(targets: &[String]) -> String {
+    targets
+        .iter()
+        .map(|target| target.split("::").map(quote).collect::<Vec<_>>().join("::"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A name as written in SysML: unrestricted names are quoted.
+fn quote(name: &str) -> String {
+    let basic = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if basic {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "\\'"))
+    }
+}
+
+/// Interned strings of an index being encoded.
+#[derive(Default)]
+struct StringTable<'a> {
+    strings: Vec<&'a str>,
+    ids: HashMap<&'a str, u32>,
+}
+
+impl<'a> StringTable<'a> {
+    fn id(&mut self, s: &'a str) -> u32 {
+        *self.ids.entry(s).or_insert_with(|| {
+            self.strings.push(s);
+            self.strings.len() as u32 - 1
+        })
+    }
+}
+
+pub(crate) fn put_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Put a count or length as a `u32`, failing rather than truncating one
+/// that doesn't fit.
+pub(crate) fn put_len(bytes: &mut Vec<u8>, len: usize) -> Result<(), String> {
+    let len = u32::try_from(len).map_err(|_| format!("{} is too large to encode", len))?;
+    put_u32(bytes, len);
+    Ok(())
+}
+
+/// Cursor over an encoded index (or other binary file).
+pub(crate) struct Reader<'a> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| "unexpected end of file".to_string())?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
@@ -0,0 +1,253 @@
+//! Decompile an interchange model into a multi-file project layout.
+//!
+//! Instead of one blob of SysML text, the model is split at package
+//! boundaries. A package that only groups other packages becomes a folder;
+//! any other package becomes a `.sysml` file inside the folder of its
+//! enclosing packages. Each file keeps the declarations of its enclosing
+//! packages so qualified names are unchanged, and gets its own companion
+//! `.metadata.json`. Packages whose names map to the same file name get a
+//! counter suffix, so no file overwrites another.
+
+use super::MemberOrder;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syster::interchange::{ElementId, ElementKind, Model, SourceInfo};
+
+/// One file of a decompiled project layout.
+#[derive(Debug)]
+pub struct DecompiledFile {
+    /// Path of the `.sysml` file, relative to the output directory.
+    pub path: PathBuf,
+    /// Generated SysML text.
+    pub sysml_text: String,
+    /// Metadata JSON for preserving element IDs.
+    pub metadata_json: String,
+    /// Number of elements in this file.
+    pub element_count: usize,
+}
+
+impl DecompiledFile {
+    /// Path of the companion metadata file, relative to the output directory.
+    pub fn metadata_path(&self) -> PathBuf {
+        self.path.with_extension("metadata.json")
+    }
+}
+
+/// Decompile an interchange file to one SysML file per package.
+///
+/// Top-level elements that are not packages are collected into a single
+/// file named after the input.
+///
+/// # Arguments
+/// * `input` - Path to the interchange file
+/// * `format` - Optional format override (otherwise detected from extension)
+/// * `verbose` - Enable verbose output
+/// * `order` - Order of members within each namespace
+///
+/// # Returns
+/// The files to write, with paths relative to the output directory.
+pub fn decompile_model_split(
+    input: &Path,
+    format: Option<&str>,
+    verbose: bool,
+    order: MemberOrder,
+) -> Result<Vec<DecompiledFile>, String> {
+    let (model, format_str) = super::read_decompile_input(input, format, verbose)?;
+
+    let mut units = Vec::new();
+    let mut loose = Vec::new();
+    for root in &model.roots {
+        collect_units(&model, root, Path::new(""), &mut units, &mut loose);
+    }
+    if !loose.is_empty() {
+        let stem = input
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "model".to_string());
+        units.push((loose, PathBuf::from(format!("{}.sysml", file_name(&stem)))));
+    }
+
+    disambiguate(&mut units);
+
+    let mut files = Vec::new();
+    for (roots, path) in units {
+        let unit = extract_unit(&model, &roots);
+        let source = SourceInfo::from_path(input.to_string_lossy()).with_format(&format_str);
+        let (text, metadata) = super::decompile_in_order(&unit, source, order);
+        let metadata_json = super::metadata_to_json(&metadata)?;
+
+        if verbose {
+            tracing::info!("{}: {} elements", path.display(), metadata.elements.len());
+        }
+
+        files.push(DecompiledFile {
+            path,
+            sysml_text: text,
+            metadata_json,
+            element_count: metadata.elements.len(),
+        });
+    }
+
+    Ok(files)
+}
+
+/// Assign `id` and its descendants to output files.
+///
+/// `units` receives one entry per file; top-level non-package elements are
+/// pushed to `loose`.
+fn collect_units(
+    model: &Model,
+    id: &ElementId,
+    dir: &Path,
+    units: &mut Vec<(Vec<ElementId>, PathBuf)>,
+    loose: &mut Vec<ElementId>,
+) {
+    let Some(element) = model.get(id) else {
+        return;
+    };
+
+    if is_transparent(element.kind) {
+        for child in &element.owned_elements {
+            collect_units(model, child, dir, units, loose);
+        }
+        return;
+    }
+
+    if !is_package(element.kind) {
+        loose.push(id.clone());
+        return;
+    }
+
+    let name = file_name(element.name.as_deref().unwrap_or(id.as_str()));
+    let children = content_children(model, id);
+    let is_grouping = !children.is_empty()
+        && children
+            .iter()
+            .all(|c| model.get(c).is_some_and(|e| is_package(e.kind)))
+        && element.documentation.is_none()
+        && !model
+            .relationships
+            .iter()
+            .any(|r| r.owner.as_ref() == Some(id));
+
+    if is_grouping {
+        let folder = dir.join(&name);
+        for child in &children {
+            collect_units(model, child, &folder, units, &mut Vec::new());
+        }
+    } else {
+        units.push((vec![id.clone()], dir.join(format!("{}.sysml", name))));
+    }
+}
+
+/// Children of `id`, looking through relationship and namespace wrappers.
+fn content_children(model: &Model, id: &ElementId) -> Vec<ElementId> {
+    let mut children = Vec::new();
+    let Some(element) = model.get(id) else {
+        return children;
+    };
+    for child in &element.owned_elements {
+        match model.get(child) {
+            Some(c) if is_transparent(c.kind) => children.extend(content_children(model, child)),
+            Some(_) => children.push(child.clone()),
+            None => {}
+        }
+    }
+    children
+}
+
+/// Build a model containing `roots`, their descendants, and their ancestors.
+///
+/// Ancestors keep only the children on the path to a root, so the
+/// decompiled text declares the enclosing packages without their siblings.
+fn extract_unit(model: &Model, roots: &[ElementId]) -> Model {
+    let mut keep = HashSet::new();
+    let mut stack: Vec<ElementId> = roots.to_vec();
+    while let Some(id) = stack.pop() {
+        if let Some(element) = model.get(&id) {
+            stack.extend(element.owned_elements.iter().cloned());
+        }
+        keep.insert(id);
+    }
+
+    let mut path_children: HashMap<ElementId, HashSet<ElementId>> = HashMap::new();
+    for root in roots {
+        let mut current = root.clone();
+        while let Some(owner) = model.get(&current).and_then(|e| e.owner.clone()) {
+            if !model.elements.contains_key(&owner) {
+                break;
+            }
+            path_children
+                .entry(owner.clone())
+                .or_default()
+                .insert(current);
+            current = owner;
+        }
+    }
+
+    let mut unit = Model::new();
+    unit.metadata = model.metadata.clone();
+    for (id, element) in &model.elements {
+        if keep.contains(id) {
+            unit.elements.insert(id.clone(), element.clone());
+        } else if let Some(children) = path_children.get(id) {
+            let mut element = element.clone();
+            element.owned_elements.retain(|c| children.contains(c));
+            unit.elements.insert(id.clone(), element);
+        }
+    }
+    unit.roots = model
+        .roots
+        .iter()
+        .filter(|r| unit.elements.contains_key(*r))
+        .cloned()
+        .collect();
+    unit.relationships = model
+        .relationships
+        .iter()
+        .filter(|r| match &r.owner {
+            Some(owner) => unit.elements.contains_key(owner),
+            None => keep.contains(&r.source),
+        })
+        .cloned()
+        .collect();
+    unit
+}
+
+/// Packages map to folders or files in the layout.
+fn is_package(kind: ElementKind) -> bool {
+    matches!(kind, ElementKind::Package | ElementKind::LibraryPackage)
+}
+
+/// Wrappers that produce no SysML text of their own.
+fn is_transparent(kind: ElementKind) -> bool {
+    kind == ElementKind::Namespace || kind.is_relationship()
+}
+
+/// Give every file a path of its own. Names that only differ in the
+/// characters [`file_name`] replaces (`A.B` and `A_B`), or in case, which
+/// some filesystems ignore, get a counter: `A_B.sysml`, `A_B_2.sysml`.
+fn disambiguate(units: &mut [(Vec<ElementId>, PathBuf)]) {
+    let mut taken = HashSet::new();
+    for (_, path) in units.iter_mut() {
+        let stem = path.with_extension("");
+        let mut n = 1;
+        while !taken.insert(path.to_string_lossy().to_lowercase()) {
+            n += 1;
+            *path = PathBuf::from(format!("{}_{}.sysml", stem.display(), n));
+        }
+    }
+}
+
+/// Make an element name safe to use as a file or folder name.
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
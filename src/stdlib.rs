@@ -0,0 +1,483 @@
+//! Loading a subset of the standard library, and checking the library.
+//!
+//! With [`StdlibSubset::Auto`] the library files declaring the packages a
+//! model refers to are loaded, along with the files those refer to,
+//! transitively; the rest of the library is never parsed. References are
+//! found in the text rather than by resolving names: the package an
+//! `import` names, the first segment of qualified names, and the packages
+//! holding the implicit supertypes of declarations (a `part` needs
+//! `Parts::Part`). Comments and string literals are skipped. Keeping a file
+//! too many costs a little time, dropping one leaves names unresolved, so
+//! the scan errs on the side of keeping files.
+//!
+//! `syster stdlib check` analyzes the library by itself and sorts its
+//! diagnostics into genuine issues, resolver false positives and known
+//! issues. A reference the resolver reports as undefined although the
+//! library declares every name in it, or as ambiguous between a
+//! declaration and itself reached through several re-exports (the `ISQ`
+//! packages), is a false positive: the library is consistent, the resolver
+//! just does not follow it yet. Known
+//! issues are listed in the `[stdlib]` table of `syster.toml`:
+//!
+//! ```toml
+//! [[stdlib.known_issues]]
+//! code = "E0001"                       # diagnostic code
+//! file = "ISQSpaceTime.sysml"          # path, or its trailing components
+//! message = "'cartesianPosition3dVector'"  # text the message contains
+//! reason = "Fixed in the next release"
+//! ```
+//!
+//! Every field is optional; an issue matches the diagnostics matching all
+//! the fields it has.
+
+use super::project::{find_project_file, load_table};
+use super::{DiagnosticInfo, WalkOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syster::hir::Severity;
+use syster::ide::AnalysisHost;
+
+/// How much of the standard library to load.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StdlibSubset {
+    /// Every file of the library.
+    #[default]
+    Full,
+    /// Only the files the model needs, and the files those need.
+    Auto,
+}
+
+/// Packages every model needs: everything specializes `Base::Anything`.
+const ROOT_PACKAGES: &[&str] = &["Base"];
+
+/// Packages holding the implicit supertypes of declarations, by keyword.
+const IMPLICIT_PACKAGES: &[(&str, &str)] = &[
+    ("accept", "Actions"),
+    ("action", "Actions"),
+    ("allocate", "Allocations"),
+    ("allocation", "Allocations"),
+    ("analysis", "AnalysisCases"),
+    ("assert", "Constraints"),
+    ("assoc", "Links"),
+    ("attribute", "Attributes"),
+    ("behavior", "Performances"),
+    ("binding", "Links"),
+    ("calc", "Calculations"),
+    ("case", "Cases"),
+    ("class", "Occurrences"),
+    ("concern", "Requirements"),
+    ("connect", "Connections"),
+    ("connection", "Connections"),
+    ("connector", "Links"),
+    ("constraint", "Constraints"),
+    ("enum", "Attributes"),
+    ("event", "Occurrences"),
+    ("exhibit", "States"),
+    ("expr", "Performances"),
+    ("flow", "Flows"),
+    ("function", "Performances"),
+    ("individual", "Occurrences"),
+    ("interface", "Interfaces"),
+    ("item", "Items"),
+    ("message", "Flows"),
+    ("metadata", "Metadata"),
+    ("occurrence", "Occurrences"),
+    ("part", "Parts"),
+    ("perform", "Actions"),
+    ("port", "Ports"),
+    ("predicate", "Performances"),
+    ("rendering", "Views"),
+    ("requirement", "Requirements"),
+    ("satisfy", "Requirements"),
+    ("send", "Actions"),
+    ("snapshot", "Occurrences"),
+    ("state", "States"),
+    ("step", "Performances"),
+    ("struct", "Objects"),
+    ("succession", "Links"),
+    ("timeslice", "Occurrences"),
+    ("transition", "States"),
+    ("use", "UseCases"),
+    ("verification", "VerificationCases"),
+    ("verify", "VerificationCases"),
+    ("view", "Views"),
+    ("viewpoint", "Views"),
+];
+
+/// The files of the standard library in `stdlib_dir` that models with the
+/// given `sources` need, in path order.
+pub fn needed_stdlib_files(stdlib_dir: &Path, sources: &[&str]) -> Result<Vec<PathBuf>, String> {
+    let library = read_library(stdlib_dir)?;
+    let texts: Vec<&str> = library.iter().map(|(_, text)| text.as_str()).collect();
+    Ok(needed_files(&texts, sources)
+        .into_iter()
+        .map(|i| library[i].0.clone())
+        .collect())
+}
+
+/// Load the files of the standard library (found as
+/// [`super::load_stdlib_files`] does) that the `inputs` need.
+pub(crate) fn load_stdlib_subset(
+    host: &mut AnalysisHost,
+    custom_path: Option<&Path>,
+    inputs: &[&Path],
+    walk: &WalkOptions,
+    verbose: bool,
+) -> Result<(), String> {
+    if verbose {
+        tracing::info!("Loading standard library subset...");
+    }
+    let Some(dir) = super::find_stdlib_dir(custom_path)? else {
+        if verbose {
+            tracing::warn!("Standard library not found");
+        }
+        return Ok(());
+    };
+
+    let mut sources = Vec::new();
+    for input in inputs {
+        sources.extend(read_sources(input, walk)?);
+    }
+    let sources: Vec<&str> = sources.iter().map(String::as_str).collect();
+    let library = read_library(&dir)?;
+    let texts: Vec<&str> = library.iter().map(|(_, text)| text.as_str()).collect();
+    let needed = needed_files(&texts, &sources);
+    if verbose {
+        tracing::info!(
+            "Loading {} of {} standard library files",
+            needed.len(),
+            library.len()
+        );
+    }
+    for i in needed {
+        let (path, text) = &library[i];
+        if verbose {
+            tracing::info!("Loading: {}", path.display());
+        }
+        super::add_source(host, &path.to_string_lossy(), text);
+    }
+    Ok(())
+}
+
+/// The files of the library in `dir` with their text, in path order.
+fn read_library(dir: &Path) -> Result<Vec<(PathBuf, String)>, String> {
+    let mut paths = super::model_files(dir, false, &WalkOptions::default())?;
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            std::fs::read_to_string(&path)
+                .map(|text| (path.clone(), text))
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        })
+        .collect()
+}
+
+/// The text of the model files under `input`, walked as `walk` says; none
+/// if it does not exist, which loading it reports.
+fn read_sources(input: &Path, walk: &WalkOptions) -> Result<Vec<String>, String> {
+    let paths = if input.is_dir() {
+        super::model_files(input, false, walk)?
+    } else if input.is_file() {
+        vec![input.to_path_buf()]
+    } else {
+        Vec::new()
+    };
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        })
+        .collect()
+}
+
+/// Indexes of the `library` files needed by `sources`, in order.
+fn needed_files(library: &[&str], sources: &[&str]) -> Vec<usize> {
+    let scans: Vec<Scan> = library.iter().map(|text| scan(text)).collect();
+    let mut declaring: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, scan) in scans.iter().enumerate() {
+        for package in &scan.packages {
+            declaring.entry(package).or_default().push(i);
+        }
+    }
+
+    let mut pending: Vec<String> = ROOT_PACKAGES.iter().map(|p| p.to_string()).collect();
+    for source in sources {
+        pending.extend(scan(source).needs);
+    }
+    let mut seen = HashSet::new();
+    let mut kept = BTreeSet::new();
+    while let Some(package) = pending.pop() {
+        if !seen.insert(package.clone()) {
+            continue;
+        }
+        for &i in declaring.get(package.as_str()).into_iter().flatten() {
+            if kept.insert(i) {
+                pending.extend(scans[i].needs.iter().cloned());
+            }
+        }
+    }
+    kept.into_iter().collect()
+}
+
+/// The packages a file declares and the packages it refers to.
+#[derive(Debug, Default)]
+struct Scan {
+    packages: Vec<String>,
+    needs: BTreeSet<String>,
+}
+
+/// Scan `text` for the packages it declares and needs.
+fn scan(text: &str) -> Scan {
+    let tokens = tokens(text);
+    let mut scan = Scan::default();
+    for (i, &token) in tokens.iter().enumerate() {
+        let previous = i.checked_sub(1).map(|i| tokens[i]);
+        let next = tokens.get(i + 1).copied();
+        match token {
+            "::" => {}
+            "package" => {
+                if let Some(name) = next.filter(|&n| n != "::") {
+                    scan.packages.push(name.to_string());
+                }
+            }
+            "import" => {
+                let name = tokens[i + 1..].iter().find(|&&t| t != "all");
+                if let Some(name) = name.filter(|&&n| n != "::") {
+                    scan.needs.insert(name.to_string());
+                }
+            }
+            name => {
+                if next == Some("::") && previous != Some("::") {
+                    scan.needs.insert(name.to_string());
+                }
+                if let Some((_, package)) = IMPLICIT_PACKAGES.iter().find(|(k, _)| *k == name) {
+                    scan.needs.insert(package.to_string());
+                }
+            }
+        }
+    }
+    scan
+}
+
+/// The names (unquoted) and `::` separators of `text`, leaving out
+/// comments, string literals and other punctuation.
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        if rest.starts_with("//") {
+            i += rest.find('\n').unwrap_or(rest.len());
+        } else if rest.starts_with("/*") {
+            i += rest[2..].find("*/").map_or(rest.len(), |end| end + 4);
+        } else if rest.starts_with("::") {
+            tokens.push("::");
+            i += 2;
+        } else if c == '"' || c == '\'' {
+            let end = closing_quote(&rest[1..], c).map_or(rest.len(), |end| end + 1);
+            if c == '\'' {
+                tokens.push(&rest[1..end]);
+            }
+            i += (end + 1).min(rest.len());
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(&rest[..end]);
+            i += end;
+        } else {
+            i += c.len_utf8();
+        }
+    }
+    tokens
+}
+
+/// Offset of the first unescaped `quote` in `text`.
+fn closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '\\' if !escaped => escaped = true,
+            c if c == quote && !escaped => return Some(i),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+/// A diagnostic of the standard library that `syster stdlib check` accepts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KnownIssue {
+    /// Diagnostic code, e.g. `E0001`.
+    pub code: Option<String>,
+    /// Path of the file, or its trailing components (`ISQ.sysml`).
+    pub file: Option<String>,
+    /// Text the message contains.
+    pub message: Option<String>,
+    /// Why the issue is accepted.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+}
+
+impl KnownIssue {
+    /// Whether `diag` is this issue.
+    pub fn matches(&self, diag: &DiagnosticInfo) -> bool {
+        self.code
+            .as_ref()
+            .is_none_or(|code| diag.code.as_ref() == Some(code))
+            && self
+                .file
+                .as_ref()
+                .is_none_or(|file| Path::new(&diag.file).ends_with(file))
+            && self
+                .message
+                .as_ref()
+                .is_none_or(|message| diag.message.contains(message.as_str()))
+    }
+}
+
+/// The known issues of the `[stdlib]` table of a project file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KnownIssues {
+    /// Accepted issues, in file order.
+    pub issues: Vec<KnownIssue>,
+}
+
+/// The `[stdlib]` table of `syster.toml`.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct StdlibSection {
+    #[serde(default)]
+    known_issues: Vec<KnownIssue>,
+}
+
+impl KnownIssues {
+    /// Load the `[stdlib]` table of a project file; none if it has none.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let section: StdlibSection = load_table(path, "stdlib")?.unwrap_or_default();
+        Ok(Self {
+            issues: section.known_issues,
+        })
+    }
+
+    /// Load the `[stdlib]` table of the nearest `syster.toml` in `dir` and
+    /// its ancestors; none if there is none.
+    pub fn discover(dir: &Path) -> Result<Self, String> {
+        match find_project_file(dir) {
+            Some(path) => Self::load(&path),
+            None => Ok(Self::default()),
+        }
+    }
+}
+
+/// Diagnostics of the standard library analyzed by itself.
+#[derive(Debug, Serialize)]
+pub struct StdlibCheck {
+    /// Library directory.
+    pub library: String,
+    /// Number of files in the library.
+    pub files: usize,
+    /// Errors and warnings that need fixing.
+    pub issues: Vec<DiagnosticInfo>,
+    /// References the library resolves but the resolver does not.
+    pub false_positives: Vec<DiagnosticInfo>,
+    /// Diagnostics matching a known issue.
+    pub known: Vec<DiagnosticInfo>,
+    /// Known issues matching no diagnostic, which can be removed.
+    pub stale: Vec<KnownIssue>,
+}
+
+impl StdlibCheck {
+    /// Number of genuine errors.
+    pub fn error_count(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|d| matches!(d.severity, Severity::Error))
+            .count()
+    }
+}
+
+/// Analyze the standard library in `path` (or found as
+/// [`super::load_stdlib_files`] does) by itself and sort its errors and
+/// warnings.
+pub fn check_stdlib(
+    path: Option<&Path>,
+    known: &KnownIssues,
+    verbose: bool,
+) -> Result<StdlibCheck, String> {
+    let dir = super::find_stdlib_dir(path)?
+        .ok_or("Standard library not found; pass its path or --stdlib-path")?;
+    let mut host = AnalysisHost::new();
+    super::load_directory(&mut host, &dir, verbose, &WalkOptions::default())?;
+    let _analysis = host.analysis();
+    let index = host.symbol_index();
+    let declared: HashSet<&str> = index
+        .all_symbols()
+        .flat_map(|s| std::iter::once(&*s.name).chain(s.short_name.as_deref()))
+        .collect();
+
+    let mut check = StdlibCheck {
+        library: dir.to_string_lossy().to_string(),
+        files: host.files().len(),
+        issues: Vec::new(),
+        false_positives: Vec::new(),
+        known: Vec::new(),
+        stale: Vec::new(),
+    };
+    let mut matched = vec![false; known.issues.len()];
+    for diag in super::collect_diagnostics(&host) {
+        if !matches!(diag.severity, Severity::Error | Severity::Warning) {
+            continue;
+        }
+        if let Some(i) = known.issues.iter().position(|issue| issue.matches(&diag)) {
+            matched[i] = true;
+            check.known.push(diag);
+        } else if is_false_positive(&diag, &declared) {
+            check.false_positives.push(diag);
+        } else {
+            check.issues.push(diag);
+        }
+    }
+    check.stale = known
+        .issues
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !matched)
+        .map(|(issue, _)| issue.clone())
+        .collect();
+    if verbose {
+        tracing::info!(
+            "Checked {} library files: {} issues, {} false positives, {} known",
+            check.files,
+            check.issues.len(),
+            check.false_positives.len(),
+            check.known.len()
+        );
+    }
+    Ok(check)
+}
+
+/// Whether `diag` reports an undefined reference every segment of which
+/// the library declares (names `declared`), or a reference ambiguous
+/// between a declaration and itself.
+fn is_false_positive(diag: &DiagnosticInfo, declared: &HashSet<&str>) -> bool {
+    match diag.code.as_deref() {
+        Some("E0001") => diag
+            .message
+            .split('\'')
+            .nth(1)
+            .is_some_and(|name| name.split("::").all(|segment| declared.contains(segment))),
+        Some("E0002") => diag
+            .message
+            .split_once(" could be: ")
+            .is_some_and(|(_, candidates)| {
+                candidates.split(", ").collect::<HashSet<_>>().len() <= 1
+            }),
+        _ => false,
+    }
+}
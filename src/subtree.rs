@@ -0,0 +1,85 @@
+//! Export of a single element subtree (`--root`).
+//!
+//! Keeps the element with the given qualified name and everything it
+//! contains. Elements outside the subtree that it references (types,
+//! specialized definitions, redefined features, ...) are kept as stubs —
+//! the element alone, without members or relationships — and marked with
+//! the `isExternal` property, so a fragment can be embedded elsewhere
+//! without dangling references.
+
+use std::collections::HashSet;
+use syster::hir::{HirSymbol, SymbolIndex};
+use syster::interchange::Model;
+use syster::interchange::model::PropertyValue;
+
+/// Property marking the stub of an element outside the subtree.
+pub const EXTERNAL_PROPERTY: &str = "isExternal";
+
+/// The symbols under `root`, and stubs of the symbols outside it they
+/// reference, whose qualified names are returned as well. Targets are
+/// resolved as for the dependency closure, and references to stubs are
+/// rewritten to their qualified names so they resolve in the exported
+/// model.
+pub(crate) fn select_subtree(
+    index: &SymbolIndex,
+    symbols: Vec<HirSymbol>,
+    root: &str,
+) -> Result<(Vec<HirSymbol>, HashSet<String>), String> {
+    let prefix = format!("{}::", root);
+    let in_subtree = |name: &str| name == root || name.starts_with(&prefix);
+
+    let mut selected: Vec<HirSymbol> = symbols
+        .into_iter()
+        .filter(|sym| in_subtree(&sym.qualified_name))
+        .collect();
+    if !selected
+        .iter()
+        .any(|sym| sym.qualified_name.as_ref() == root)
+    {
+        return Err(format!("No element '{}' to export", root));
+    }
+
+    let mut external = HashSet::new();
+    let mut stubs = Vec::new();
+    for sym in &mut selected {
+        let resolver = index.resolver_for_scope(&sym.qualified_name);
+        for rel in &mut sym.relationships {
+            let target = match &rel.resolved_target {
+                Some(resolved) => Some(resolved.clone()),
+                None => resolver
+                    .resolve(&rel.target)
+                    .symbol()
+                    .map(|s| s.qualified_name.clone()),
+            };
+            let Some(stub) = target
+                .and_then(|target| index.lookup_qualified(&target))
+                .filter(|stub| !in_subtree(&stub.qualified_name))
+            else {
+                continue;
+            };
+            rel.target = stub.qualified_name.clone();
+            if external.insert(stub.qualified_name.to_string()) {
+                let mut stub = stub.clone();
+                stub.relationships.clear();
+                stubs.push(stub);
+            }
+        }
+    }
+    selected.extend(stubs);
+    Ok((selected, external))
+}
+
+/// Mark the elements of `model` named in `external` as stubs.
+pub(crate) fn mark_external(model: &mut Model, external: &HashSet<String>) {
+    for element in model.elements.values_mut() {
+        if element
+            .qualified_name
+            .as_deref()
+            .is_some_and(|name| external.contains(name))
+        {
+            element
+                .properties
+                .insert(EXTERNAL_PROPERTY.into(), PropertyValue::Boolean(true));
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Per-package and per-file breakdowns of an analysis.
+//!
+//! Totals alone don't say where to start on a large workspace. Every
+//! [`AnalysisResult`] carries the symbols, errors and warnings of each
+//! top-level package and each file, and [`render_summary`] prints them as a
+//! table, worst first:
+//!
+//! ```text
+//! By package:
+//!   Vehicle  120 symbols, 3 errors, 1 warnings
+//!   Power     48 symbols, 0 errors, 2 warnings
+//! ```
+//!
+//! [`top_offenders`] picks the files to clean up first.
+
+use super::i18n::Catalog;
+use super::{AnalysisResult, DiagnosticInfo};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use syster::hir::{HirSymbol, Severity};
+
+/// Group name of diagnostics outside every top-level package.
+const ROOT: &str = "(root)";
+
+/// Symbols, errors and warnings of one package or file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Breakdown {
+    /// Top-level package name or file path.
+    pub name: String,
+    /// Symbols declared in it.
+    pub symbols: usize,
+    /// Errors reported in it.
+    pub errors: usize,
+    /// Warnings reported in it.
+    pub warnings: usize,
+    /// Teams owning the package, from `MODELOWNERS`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+}
+
+/// How [`render_summary`] groups the counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryBy {
+    /// One row per top-level package.
+    Package,
+    /// One row per file.
+    File,
+    /// One row per diagnostic severity.
+    Severity,
+}
+
+/// Accumulates the breakdowns while the analysis runs.
+#[derive(Default)]
+pub(crate) struct Breakdowns {
+    packages: BTreeMap<String, Breakdown>,
+    files: BTreeMap<String, Breakdown>,
+    /// Line ranges (0-based, inclusive) of the top-level elements per file.
+    top_level: HashMap<String, Vec<(u32, u32, String)>>,
+}
+
+impl Breakdowns {
+    /// Count a symbol declared in `file`.
+    pub(crate) fn add_symbol(&mut self, file: &str, symbol: &HirSymbol) {
+        let package = top_level_name(&symbol.qualified_name);
+        if package == &*symbol.qualified_name {
+            self.top_level.entry(file.to_string()).or_default().push((
+                symbol.start_line,
+                symbol.end_line,
+                package.to_string(),
+            ));
+        }
+        entry(&mut self.packages, package).symbols += 1;
+        entry(&mut self.files, file).symbols += 1;
+    }
+
+    /// Total number of symbols counted.
+    pub(crate) fn symbol_count(&self) -> usize {
+        self.files.values().map(|b| b.symbols).sum()
+    }
+
+    /// Count the errors and warnings of `diagnostics`, each in the file it
+    /// is in and the top-level package enclosing its line.
+    pub(crate) fn finish(
+        mut self,
+        diagnostics: &[DiagnosticInfo],
+    ) -> (Vec<Breakdown>, Vec<Breakdown>) {
+        for diag in diagnostics {
+            let (errors, warnings) = match diag.severity {
+                Severity::Error => (1, 0),
+                Severity::Warning => (0, 1),
+                _ => continue,
+            };
+            let line = diag.line.saturating_sub(1);
+            let package = self
+                .top_level
+                .get(&diag.file)
+                .and_then(|ranges| {
+                    ranges
+                        .iter()
+                        .find(|(start, end, _)| (*start..=*end).contains(&line))
+                })
+                .map_or(ROOT, |(_, _, name)| name.as_str())
+                .to_string();
+            for group in [
+                entry(&mut self.packages, &package),
+                entry(&mut self.files, &diag.file),
+            ] {
+                group.errors += errors;
+                group.warnings += warnings;
+            }
+        }
+        (
+            self.packages.into_values().collect(),
+            self.files.into_values().collect(),
+        )
+    }
+}
+
+/// Combine the breakdowns of two results, summing groups with the same
+/// name.
+pub(crate) fn merge(a: Vec<Breakdown>, b: Vec<Breakdown>) -> Vec<Breakdown> {
+    let mut merged: BTreeMap<String, Breakdown> = BTreeMap::new();
+    for breakdown in a.into_iter().chain(b) {
+        let group = entry(&mut merged, &breakdown.name);
+        group.symbols += breakdown.symbols;
+        group.errors += breakdown.errors;
+        group.warnings += breakdown.warnings;
+        if group.owners.is_empty() {
+            group.owners = breakdown.owners;
+        }
+    }
+    merged.into_values().collect()
+}
+
+/// Render the counts of `result` grouped by `by`, one row per group, groups
+/// with the most errors (then warnings) first.
+pub fn render_summary(result: &AnalysisResult, by: SummaryBy) -> String {
+    render_summary_in(result, by, &Catalog::english())
+}
+
+/// [`render_summary`] in the language of `catalog`.
+pub fn render_summary_in(result: &AnalysisResult, by: SummaryBy, catalog: &Catalog) -> String {
+    let (title, mut rows) = match by {
+        SummaryBy::Package => ("summary-by-package", result.packages.clone()),
+        SummaryBy::File => ("summary-by-file", result.files.clone()),
+        SummaryBy::Severity => {
+            let mut out = format!("{}\n", catalog.text("summary-by-severity", &[]));
+            let severities = [
+                Severity::Error,
+                Severity::Warning,
+                Severity::Info,
+                Severity::Hint,
+            ]
+            .map(|severity| (catalog.severity(severity), severity));
+            let width = severities
+                .iter()
+                .map(|(name, _)| name.chars().count())
+                .max()
+                .unwrap_or(0);
+            for (name, severity) in severities {
+                let count = result
+                    .diagnostics
+                    .iter()
+                    .filter(|d| d.severity == severity)
+                    .count();
+                out.push_str(&format!("  {:<width$}  {}\n", name, count));
+            }
+            return out;
+        }
+    };
+    rows.sort_by(worst_first);
+    let name_width = rows.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    let symbols_width = rows
+        .iter()
+        .map(|r| r.symbols.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let mut out = format!("{}\n", catalog.text(title, &[]));
+    for row in &rows {
+        let counts = catalog.text(
+            "summary-row",
+            &[
+                ("symbols", format!("{:>symbols_width$}", row.symbols)),
+                ("errors", row.errors.to_string()),
+                ("warnings", row.warnings.to_string()),
+            ],
+        );
+        out.push_str(&format!("  {:<name_width$}  {}", row.name, counts));
+        if !row.owners.is_empty() {
+            out.push_str(&format!("  ({})", row.owners.join(" ")));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// The `n` files with the most errors (then warnings), worst first; files
+/// without any are left out.
+pub fn top_offenders(result: &AnalysisResult, n: usize) -> Vec<Breakdown> {
+    let mut files: Vec<Breakdown> = result
+        .files
+        .iter()
+        .filter(|f| f.errors + f.warnings > 0)
+        .cloned()
+        .collect();
+    files.sort_by(worst_first);
+    files.truncate(n);
+    files
+}
+
+/// Order by errors, then warnings, descending, then by name.
+fn worst_first(a: &Breakdown, b: &Breakdown) -> Ordering {
+    (b.errors, b.warnings)
+        .cmp(&(a.errors, a.warnings))
+        .then_with(|| a.name.cmp(&b.name))
+}
+
+/// First segment of a qualified name.
+fn top_level_name(qualified_name: &str) -> &str {
+    qualified_name
+        .split_once("::")
+        .map_or(qualified_name, |(first, _)| first)
+}
+
+fn entry<'a>(groups: &'a mut BTreeMap<String, Breakdown>, name: &str) -> &'a mut Breakdown {
+    groups.entry(name.to_string()).or_insert_with(|| Breakdown {
+        name: name.to_string(),
+        ..Default::default()
+    })
+}
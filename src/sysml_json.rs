@@ -0,0 +1,390 @@
+//! SysML v2 standard JSON (`--export json`).
+//!
+//! The plain JSON element payloads of the Systems Modeling API, as the
+//! pilot implementation serves and accepts them: a flat array of records
+//! with `@id`, `@type` and `elementId`, references as `{"@id": ...}`, and
+//! ownership spelled out through relationships. Each owned element hangs
+//! off an `OwningMembership` (or a `FeatureMembership` for usages) listed in
+//! its owner's `ownedRelationship`, and documentation is a `Documentation`
+//! element annotating its owner. Unlike the JSON-LD export there is no
+//! `@context` and no derived `ownedMember` shortcut.
+//!
+//! The memberships and documentation elements the model does not have get
+//! name-based UUIDs of the element they belong to, so exports of the same
+//! model diff cleanly.
+
+use super::ids::DEFAULT_ID_NAMESPACE;
+use serde_json::{Map, Value, json};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use syster::interchange::model::{PropertyValue, Visibility};
+use syster::interchange::{Element, ElementId, ElementKind, Model, Relationship, RelationshipKind};
+use uuid::Uuid;
+
+/// Serialize `model` as standard JSON.
+pub fn write(model: &Model) -> Result<Vec<u8>, String> {
+    let namespace = Uuid::parse_str(DEFAULT_ID_NAMESPACE).map_err(|e| e.to_string())?;
+    let derived_id = |id: &ElementId, role: &str| {
+        Uuid::new_v5(&namespace, format!("{}/{}", id.as_str(), role).as_bytes()).to_string()
+    };
+
+    let mut owned_relationships: HashMap<&str, Vec<String>> = HashMap::new();
+    for element in model.iter_elements() {
+        if let Some(owner) = &element.owner {
+            owned_relationships
+                .entry(owner.as_str())
+                .or_default()
+                .push(derived_id(&element.id, "membership"));
+        }
+    }
+    for rel in &model.relationships {
+        let owner = rel.owner.as_ref().unwrap_or(&rel.source);
+        owned_relationships
+            .entry(owner.as_str())
+            .or_default()
+            .push(rel.id.as_str().to_string());
+    }
+
+    let mut records = Vec::new();
+    let mut memberships = Vec::new();
+    for element in model.iter_elements() {
+        let mut record = element_record(element);
+        if let Some(owned) = owned_relationships.get(element.id.as_str()) {
+            record.insert("ownedRelationship".into(), references(owned));
+        }
+        if let Some(owner) = &element.owner {
+            let membership = derived_id(&element.id, "membership");
+            record.insert("owner".into(), reference(owner.as_str()));
+            record.insert("owningRelationship".into(), reference(&membership));
+            memberships.push(membership_record(element, owner, &membership));
+        }
+        let documentation = element.documentation.as_ref().map(|body| {
+            let id = derived_id(&element.id, "documentation");
+            record.insert("documentation".into(), references([&id]));
+            json!({
+                "@id": id,
+                "@type": "Documentation",
+                "elementId": id,
+                "body": body.as_ref(),
+                "annotatedElement": [reference(element.id.as_str())],
+                "owner": reference(element.id.as_str()),
+            })
+        });
+        records.push(Value::Object(record));
+        records.extend(documentation);
+    }
+    records.extend(memberships);
+    records.extend(model.relationships.iter().map(relationship_record));
+
+    serde_json::to_vec_pretty(&records).map_err(|e| format!("Failed to serialize JSON: {}", e))
+}
+
+/// Whether `bytes` hold standard JSON rather than JSON-LD: some record
+/// carries an `elementId`.
+pub fn is_standard_json(bytes: &[u8]) -> bool {
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(Value::Array(records)) => records.iter().any(|r| r.get("elementId").is_some()),
+        Ok(record) => record.get("elementId").is_some(),
+        Err(_) => false,
+    }
+}
+
+/// Read standard JSON, as written by [`write`] or served by an API.
+pub fn read(bytes: &[u8]) -> Result<Model, String> {
+    let value: Value = serde_json::from_slice(bytes).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let records = match value {
+        Value::Array(records) => records,
+        record @ Value::Object(_) => vec![record],
+        _ => return Err("Expected a JSON object or array of elements".to_string()),
+    };
+
+    let mut elements = Vec::new();
+    let mut relationships = Vec::new();
+    // (owned element, owner, visibility) of every membership, in document
+    // order
+    let mut ownership: Vec<(String, String, Option<Visibility>)> = Vec::new();
+    // Owners of elements as their own records name them
+    let mut declared_owners: Vec<(String, String)> = Vec::new();
+    let mut documentation: HashMap<String, Arc<str>> = HashMap::new();
+
+    for record in &records {
+        let Some(object) = record.as_object() else {
+            continue;
+        };
+        let Some(id) = object.get("@id").and_then(Value::as_str) else {
+            continue;
+        };
+        let kind = object
+            .get("@type")
+            .and_then(Value::as_str)
+            .unwrap_or("Element");
+
+        if kind == "Documentation" {
+            let annotated = first_reference(object.get("annotatedElement"))
+                .or_else(|| first_reference(object.get("owner")));
+            if let (Some(annotated), Some(body)) =
+                (annotated, object.get("body").and_then(Value::as_str))
+            {
+                documentation.insert(annotated, Arc::from(body));
+            }
+        } else if kind.ends_with("Membership") && object.contains_key("ownedRelatedElement") {
+            let owner = first_reference(object.get("owningRelatedElement"))
+                .or_else(|| first_reference(object.get("membershipOwningNamespace")))
+                .or_else(|| first_reference(object.get("source")));
+            let visibility = object
+                .get("visibility")
+                .and_then(Value::as_str)
+                .and_then(parse_visibility);
+            if let Some(owner) = owner {
+                for owned in all_references(object.get("ownedRelatedElement")) {
+                    ownership.push((owned, owner.clone(), visibility));
+                }
+            }
+        } else if let Some(rel) = parse_relationship(id, kind, object) {
+            relationships.push(rel);
+        } else {
+            if let Some(owner) = first_reference(object.get("owner")) {
+                declared_owners.push((id.to_string(), owner));
+            }
+            elements.push(parse_element(id, kind, object));
+        }
+    }
+
+    // Payloads without memberships still name each element's owner
+    let membership_owned: HashSet<String> = ownership
+        .iter()
+        .map(|(owned, _, _)| owned.clone())
+        .collect();
+    for (child, owner) in declared_owners {
+        if !membership_owned.contains(&child) {
+            ownership.push((child, owner, None));
+        }
+    }
+
+    let mut owners: HashMap<String, (String, Option<Visibility>)> = HashMap::new();
+    let mut owned: HashMap<String, Vec<ElementId>> = HashMap::new();
+    for (child, owner, visibility) in ownership {
+        owned
+            .entry(owner.clone())
+            .or_default()
+            .push(ElementId::new(child.clone()));
+        owners.insert(child, (owner, visibility));
+    }
+
+    let mut model = Model::new();
+    for mut element in elements {
+        let id = element.id.as_str().to_string();
+        if let Some((owner, visibility)) = owners.remove(&id) {
+            element.owner = Some(ElementId::new(owner));
+            element.visibility = visibility.unwrap_or_default();
+        }
+        if let Some(children) = owned.remove(&id) {
+            element.owned_elements = children;
+        }
+        if let Some(body) = documentation.remove(&id) {
+            element.documentation = Some(body);
+        }
+        model.add_element(element);
+    }
+    model.relationships = relationships;
+    Ok(model)
+}
+
+/// The record of an element, without its ownership.
+fn element_record(element: &Element) -> Map<String, Value> {
+    let mut record = Map::new();
+    record.insert("@id".into(), json!(element.id.as_str()));
+    record.insert("@type".into(), json!(element.kind.jsonld_type()));
+    record.insert("elementId".into(), json!(element.id.as_str()));
+    if let Some(name) = &element.name {
+        record.insert("declaredName".into(), json!(name.as_ref()));
+        record.insert("name".into(), json!(name.as_ref()));
+    }
+    if let Some(short_name) = &element.short_name {
+        record.insert("declaredShortName".into(), json!(short_name.as_ref()));
+        record.insert("shortName".into(), json!(short_name.as_ref()));
+    }
+    if let Some(qualified_name) = &element.qualified_name {
+        record.insert("qualifiedName".into(), json!(qualified_name.as_ref()));
+    }
+    for (key, set) in [
+        ("isAbstract", element.is_abstract),
+        ("isVariation", element.is_variation),
+        ("isDerived", element.is_derived),
+        ("isReadOnly", element.is_readonly),
+        ("isParallel", element.is_parallel),
+    ] {
+        if set {
+            record.insert(key.into(), json!(true));
+        }
+    }
+    for (key, value) in &element.properties {
+        record.insert(key.to_string(), property_value(value));
+    }
+    record
+}
+
+/// The membership through which `owner` owns `element`.
+fn membership_record(element: &Element, owner: &ElementId, id: &str) -> Value {
+    let kind = if element.kind.is_usage() {
+        "FeatureMembership"
+    } else {
+        "OwningMembership"
+    };
+    let visibility = match element.visibility {
+        Visibility::Public => "public",
+        Visibility::Private => "private",
+        Visibility::Protected => "protected",
+    };
+    let mut record = json!({
+        "@id": id,
+        "@type": kind,
+        "elementId": id,
+        "visibility": visibility,
+        "owningRelatedElement": reference(owner.as_str()),
+        "ownedRelatedElement": [reference(element.id.as_str())],
+        "source": [reference(owner.as_str())],
+        "target": [reference(element.id.as_str())],
+        "memberElement": reference(element.id.as_str()),
+    });
+    if let Some(name) = &element.name {
+        record["memberName"] = json!(name.as_ref());
+    }
+    record
+}
+
+fn relationship_record(rel: &Relationship) -> Value {
+    let owner = rel.owner.as_ref().unwrap_or(&rel.source);
+    json!({
+        "@id": rel.id.as_str(),
+        "@type": rel.kind.xmi_type().rsplit(':').next().unwrap_or("Relationship"),
+        "elementId": rel.id.as_str(),
+        "owningRelatedElement": reference(owner.as_str()),
+        "source": [reference(rel.source.as_str())],
+        "target": [reference(rel.target.as_str())],
+    })
+}
+
+/// A relationship record with a source and target; relationship elements
+/// without them are read as elements.
+fn parse_relationship(id: &str, kind: &str, object: &Map<String, Value>) -> Option<Relationship> {
+    let kind = RelationshipKind::from_xmi_type(kind)?;
+    let source = first_reference(object.get("source"))?;
+    let target = first_reference(object.get("target"))?;
+    let mut rel = Relationship::new(id, kind, source, target);
+    rel.owner = first_reference(object.get("owningRelatedElement"))
+        .or_else(|| first_reference(object.get("owner")))
+        .map(ElementId::new);
+    Some(rel)
+}
+
+fn parse_element(id: &str, kind: &str, object: &Map<String, Value>) -> Element {
+    let mut element = Element::new(id, ElementKind::from_xmi_type(kind));
+    let text = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| object.get(*key).and_then(Value::as_str))
+            .map(Arc::from)
+    };
+    element.name = text(&["declaredName", "name"]);
+    element.short_name = text(&["declaredShortName", "shortName"]);
+    element.qualified_name = text(&["qualifiedName"]);
+    let flag = |key: &str| object.get(key).and_then(Value::as_bool).unwrap_or(false);
+    element.set_abstract(flag("isAbstract"));
+    element.set_variation(flag("isVariation"));
+    element.set_derived(flag("isDerived"));
+    element.set_readonly(flag("isReadOnly"));
+    element.set_parallel(flag("isParallel"));
+
+    for (key, value) in object {
+        if matches!(
+            key.as_str(),
+            "@id"
+                | "@type"
+                | "elementId"
+                | "name"
+                | "declaredName"
+                | "shortName"
+                | "declaredShortName"
+                | "qualifiedName"
+                | "isAbstract"
+                | "isVariation"
+                | "isDerived"
+                | "isReadOnly"
+                | "isParallel"
+                | "documentation"
+                | "owner"
+                | "owningRelationship"
+                | "owningNamespace"
+                | "ownedRelationship"
+                | "ownedElement"
+                | "ownedMember"
+        ) {
+            continue;
+        }
+        if let Some(value) = parse_property(value) {
+            element.properties.insert(Arc::from(key.as_str()), value);
+        }
+    }
+    element
+}
+
+fn property_value(value: &PropertyValue) -> Value {
+    match value {
+        PropertyValue::String(s) => json!(s.as_ref()),
+        PropertyValue::Integer(i) => json!(i),
+        PropertyValue::Real(f) => json!(f),
+        PropertyValue::Boolean(b) => json!(b),
+        PropertyValue::Reference(id) => reference(id.as_str()),
+        PropertyValue::List(items) => Value::Array(items.iter().map(property_value).collect()),
+    }
+}
+
+fn parse_property(value: &Value) -> Option<PropertyValue> {
+    match value {
+        Value::String(s) => Some(PropertyValue::String(Arc::from(s.as_str()))),
+        Value::Bool(b) => Some(PropertyValue::Boolean(*b)),
+        Value::Number(n) => n
+            .as_i64()
+            .map(PropertyValue::Integer)
+            .or_else(|| n.as_f64().map(PropertyValue::Real)),
+        Value::Object(object) => object
+            .get("@id")
+            .and_then(Value::as_str)
+            .map(|id| PropertyValue::Reference(ElementId::new(id))),
+        Value::Array(items) => Some(PropertyValue::List(
+            items.iter().filter_map(parse_property).collect(),
+        )),
+        Value::Null => None,
+    }
+}
+
+fn parse_visibility(visibility: &str) -> Option<Visibility> {
+    match visibility {
+        "public" => Some(Visibility::Public),
+        "private" => Some(Visibility::Private),
+        "protected" => Some(Visibility::Protected),
+        _ => None,
+    }
+}
+
+fn reference(id: &str) -> Value {
+    json!({ "@id": id })
+}
+
+fn references<S: AsRef<str>>(ids: impl IntoIterator<Item = S>) -> Value {
+    Value::Array(ids.into_iter().map(|id| reference(id.as_ref())).collect())
+}
+
+/// The ID of a reference, or of the first of a list of references.
+fn first_reference(value: Option<&Value>) -> Option<String> {
+    all_references(value).into_iter().next()
+}
+
+fn all_references(value: Option<&Value>) -> Vec<String> {
+    let id = |value: &Value| value.get("@id").and_then(Value::as_str).map(str::to_string);
+    match value {
+        Some(Value::Array(items)) => items.iter().filter_map(id).collect(),
+        Some(value) => id(value).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
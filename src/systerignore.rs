@@ -0,0 +1,102 @@
+//! `.systerignore` files.
+//!
+//! A `.systerignore` uses `.gitignore` syntax and applies to the directory
+//! holding it and everything below, so build output and scratch models can
+//! be left out of every run:
+//!
+//! ```text
+//! build/
+//! scratch/**/*.sysml
+//! !scratch/keep.sysml
+//! ```
+//!
+//! With [`WalkOptions::respect_gitignore`](super::WalkOptions) `.gitignore`
+//! files are read too; a `.systerignore` in the same directory takes
+//! precedence.
+
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Ignore file read in every walked directory.
+const IGNORE_FILE: &str = ".systerignore";
+
+/// Ignore rules of the directories below a walk's root, read the first time
+/// each directory is reached.
+pub(crate) struct IgnoreRules {
+    root: PathBuf,
+    /// Ignore files read per directory, later ones taking precedence.
+    names: &'static [&'static str],
+    by_dir: HashMap<PathBuf, Option<Gitignore>>,
+}
+
+impl IgnoreRules {
+    /// Rules for a walk of `root`.
+    pub(crate) fn new(root: &Path, respect_gitignore: bool) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            names: ignore_file_names(respect_gitignore),
+            by_dir: HashMap::new(),
+        }
+    }
+
+    /// Whether `path` (a file or directory below the root) is ignored: the
+    /// rules of the nearest directory with a matching pattern decide.
+    pub(crate) fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(&self.root) {
+                break;
+            }
+            let matched = self.rules(dir).map(|rules| rules.matched(path, is_dir));
+            match matched {
+                Some(Match::Ignore(_)) => return true,
+                Some(Match::Whitelist(_)) => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// The ignore files of `dir`, if it has any.
+    fn rules(&mut self, dir: &Path) -> Option<&Gitignore> {
+        let names = self.names;
+        self.by_dir
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| read_rules(dir, names))
+            .as_ref()
+    }
+}
+
+/// Ignore files read per directory, later ones taking precedence.
+pub(crate) fn ignore_file_names(respect_gitignore: bool) -> &'static [&'static str] {
+    if respect_gitignore {
+        &[".gitignore", IGNORE_FILE]
+    } else {
+        &[IGNORE_FILE]
+    }
+}
+
+fn read_rules(dir: &Path, names: &[&str]) -> Option<Gitignore> {
+    let mut builder = GitignoreBuilder::new(dir);
+    let mut found = false;
+    for name in names {
+        let path = dir.join(name);
+        if path.is_file() {
+            found = true;
+            if let Some(e) = builder.add(&path) {
+                tracing::warn!("{}: {}", path.display(), e);
+            }
+        }
+    }
+    if !found {
+        return None;
+    }
+    match builder.build() {
+        Ok(rules) => Some(rules),
+        Err(e) => {
+            tracing::warn!("ignore rules in {}: {}", dir.display(), e);
+            None
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! ctags and etags files.
+//!
+//! `syster index build --format ctags` writes a Vim-style `tags` file and
+//! `--format etags` an Emacs `TAGS` file, so editors can jump to
+//! declarations without a language server. Every named declaration is
+//! tagged under its simple name and, when different, its qualified name.
+//!
+//! ctags kinds are single letters, upper case for definitions and lower case
+//! for the matching usages (`P` part def, `p` part); the file's
+//! `!_TAG_KIND_DESCRIPTION` pseudo-tags list them.
+
+use super::{AnalysisOptions, AstExport, ExportSymbol};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Tags file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagsFormat {
+    /// Exuberant/Universal ctags (`tags`, Vim)
+    Ctags,
+    /// etags (`TAGS`, Emacs)
+    Etags,
+}
+
+/// Build the tags file of the model at `input` (stdlib elements are not
+/// tagged).
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `format` - Tags file format
+/// * `options` - Verbosity, standard library to load and directory walk
+pub fn build_tags(
+    input: &Path,
+    format: TagsFormat,
+    options: &AnalysisOptions,
+) -> Result<String, String> {
+    let ast = super::collect_ast(input, options, &[])?;
+    match format {
+        TagsFormat::Ctags => Ok(ctags(&ast)),
+        TagsFormat::Etags => etags(&ast),
+    }
+}
+
+/// Sorted ctags lines in extended format, with a line-number address.
+fn ctags(ast: &AstExport) -> String {
+    let mut used_kinds = BTreeSet::new();
+    let mut tags = BTreeSet::new();
+    for symbol in ast.files.iter().flat_map(|f| &f.symbols) {
+        let Some((letter, _, _)) = kind_entry(symbol) else {
+            continue;
+        };
+        used_kinds.insert(letter);
+        for name in tag_names(symbol) {
+            tags.insert(format!(
+                "{}\t{}\t{};\"\t{}\tline:{}",
+                name, symbol.file, symbol.start_line, letter, symbol.start_line
+            ));
+        }
+    }
+
+    let mut out = String::from(
+        "!_TAG_FILE_FORMAT\t2\t/extended format/\n\
+         !_TAG_FILE_SORTED\t1\t/0=unsorted, 1=sorted, 2=foldcase/\n",
+    );
+    for (letter, name, _) in KINDS.iter().filter(|(l, _, _)| used_kinds.contains(l)) {
+        out.push_str(&format!(
+            "!_TAG_KIND_DESCRIPTION!SysML\t{},{}\t/{}/\n",
+            letter,
+            name.replace(' ', ""),
+            name
+        ));
+    }
+    out.push_str(&format!(
+        "!_TAG_PROGRAM_NAME\tsyster\t//\n!_TAG_PROGRAM_VERSION\t{}\t//\n",
+        env!("CARGO_PKG_VERSION")
+    ));
+    for tag in tags {
+        out.push_str(&tag);
+        out.push('\n');
+    }
+    out
+}
+
+/// One etags section per file: `pattern DEL name SOH line,offset`.
+fn etags(ast: &AstExport) -> Result<String, String> {
+    let mut out = String::new();
+    for file in &ast.files {
+        let text = std::fs::read_to_string(&file.path)
+            .map_err(|e| format!("Failed to read {}: {}", file.path, e))?;
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        let mut section = String::new();
+        let mut symbols: Vec<&ExportSymbol> = file
+            .symbols
+            .iter()
+            .filter(|s| kind_entry(s).is_some())
+            .collect();
+        symbols.sort_by_key(|s| (s.start_line, s.start_col));
+        for symbol in symbols {
+            let Some(&start) = line_starts.get(symbol.start_line as usize - 1) else {
+                continue;
+            };
+            let line = text[start..].lines().next().unwrap_or_default();
+            // Pattern: the line up to and including the name
+            let pattern = line
+                .find(symbol.name.as_str())
+                .map(|at| &line[..at + symbol.name.len()])
+                .unwrap_or(line);
+            for name in tag_names(symbol) {
+                section.push_str(&format!(
+                    "{}\x7f{}\x01{},{}\n",
+                    pattern, name, symbol.start_line, start
+                ));
+            }
+        }
+        out.push_str(&format!(
+            "\x0c\n{},{}\n{}",
+            file.path,
+            section.len(),
+            section
+        ));
+    }
+    Ok(out)
+}
+
+/// Names to tag a symbol under: simple, then qualified if different.
+fn tag_names(symbol: &ExportSymbol) -> Vec<&str> {
+    let mut names = vec![symbol.name.as_str()];
+    if symbol.qualified_name != symbol.name {
+        names.push(symbol.qualified_name.as_str());
+    }
+    names
+}
+
+/// Kind table entry of a named declaration, `None` for imports, comments,
+/// anonymous elements and other untagged symbols.
+fn kind_entry(symbol: &ExportSymbol) -> Option<&'static (char, &'static str, &'static str)> {
+    if symbol.name.is_empty() || symbol.name.starts_with('<') {
+        return None;
+    }
+    KINDS.iter().find(|(_, _, kind)| *kind == symbol.kind)
+}
+
+/// ctags kind letter, kind name and `SymbolKind` (as exported) of each
+/// tagged element kind.
+const KINDS: &[(char, &str, &str)] = &[
+    ('K', "package", "Package"),
+    ('P', "part def", "PartDefinition"),
+    ('p', "part", "PartUsage"),
+    ('I', "item def", "ItemDefinition"),
+    ('i', "item", "ItemUsage"),
+    ('A', "action def", "ActionDefinition"),
+    ('a', "action", "ActionUsage"),
+    ('O', "port def", "PortDefinition"),
+    ('o', "port", "PortUsage"),
+    ('T', "attribute def", "AttributeDefinition"),
+    ('t', "attribute", "AttributeUsage"),
+    ('C', "connection def", "ConnectionDefinition"),
+    ('c', "connection", "ConnectionUsage"),
+    ('w', "flow", "FlowConnectionUsage"),
+    ('F', "interface def", "InterfaceDefinition"),
+    ('f', "interface", "InterfaceUsage"),
+    ('L', "allocation def", "AllocationDefinition"),
+    ('l', "allocation", "AllocationUsage"),
+    ('R', "requirement def", "RequirementDefinition"),
+    ('r', "requirement", "RequirementUsage"),
+    ('N', "constraint def", "ConstraintDefinition"),
+    ('n', "constraint", "ConstraintUsage"),
+    ('S', "state def", "StateDefinition"),
+    ('s', "state", "StateUsage"),
+    ('x', "transition", "TransitionUsage"),
+    ('U', "calc def", "CalculationDefinition"),
+    ('u', "calc", "CalculationUsage"),
+    ('E', "use case def", "UseCaseDefinition"),
+    ('Y', "analysis def", "AnalysisCaseDefinition"),
+    ('H', "concern def", "ConcernDefinition"),
+    ('V', "view def", "ViewDefinition"),
+    ('v', "view", "ViewUsage"),
+    ('W', "viewpoint def", "ViewpointDefinition"),
+    ('q', "viewpoint", "ViewpointUsage"),
+    ('G', "rendering def", "RenderingDefinition"),
+    ('g', "rendering", "RenderingUsage"),
+    ('M', "enum def", "EnumerationDefinition"),
+    ('D', "metadata def", "MetadataDefinition"),
+    ('Q', "interaction", "Interaction"),
+    ('e', "reference", "ReferenceUsage"),
+    ('z', "occurrence", "OccurrenceUsage"),
+    ('B', "datatype", "DataType"),
+    ('Z', "class", "Class"),
+    ('J', "struct", "Structure"),
+    ('b', "behavior", "Behavior"),
+    ('h', "function", "Function"),
+    ('X', "assoc", "Association"),
+    ('k', "alias", "Alias"),
+];
@@ -0,0 +1,70 @@
+//! OpenTelemetry export of analysis spans (`otel` feature).
+//!
+//! The analysis records `tracing` spans for its phases: `load` and `parse`
+//! per file, `index`, `check` per file, and `export`. With
+//! [`LogOptions::otlp_endpoint`](crate::LogOptions::otlp_endpoint) set,
+//! [`init_logging`](crate::init_logging) also sends them to an OTLP/HTTP
+//! collector, so a slow CI analysis can be looked at in the tracing backend
+//! already in use:
+//!
+//! ```text
+//! syster ./models --otlp-endpoint http://collector:4318
+//! ```
+//!
+//! Without a collector configured the spans cost next to nothing.
+
+use opentelemetry::KeyValue;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Service name spans are reported under.
+const SERVICE_NAME: &str = "syster";
+
+/// Installed span export; shut it down before exiting so no span is lost.
+pub(crate) struct Telemetry {
+    provider: TracerProvider,
+}
+
+impl Telemetry {
+    /// Flush pending spans and stop exporting.
+    pub(crate) fn shutdown(self) -> Result<(), String> {
+        self.provider
+            .shutdown()
+            .map_err(|e| format!("Failed to export spans: {}", e))
+    }
+}
+
+/// A layer exporting spans to the OTLP/HTTP collector at `endpoint` (see
+/// [`traces_endpoint`]).
+pub(crate) fn layer<S>(endpoint: &str) -> Result<(Telemetry, OpenTelemetryLayer<S, Tracer>), String>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(traces_endpoint(endpoint))
+        .build()
+        .map_err(|e| format!("Failed to create OTLP exporter: {}", e))?;
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exporter)
+        .with_resource(Resource::new([KeyValue::new("service.name", SERVICE_NAME)]))
+        .build();
+    let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(SERVICE_NAME));
+    Ok((Telemetry { provider }, layer))
+}
+
+/// The traces URL of a collector: `endpoint` as given if it already ends
+/// with `/v1/traces`, otherwise with that path appended.
+pub fn traces_endpoint(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    }
+}
@@ -0,0 +1,209 @@
+//! Semantic tokens for syntax highlighting.
+//!
+//! [`semantic_tokens`] classifies the ranges of a file the way a
+//! highlighter needs them: keywords, comments and literals come from the
+//! lexer, declared names and type references from name resolution. A
+//! reference that does not resolve is reported as such, so editors and web
+//! viewers can show broken references without running the checker.
+//!
+//! Ranges are 1-based with an exclusive end, like diagnostics.
+
+use super::AnalysisOptions;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+use syster::hir::SymbolKind;
+use syster::parser::{Lexer, SyntaxKind};
+
+/// Classification of a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TokenKind {
+    /// Reserved word (`part`, `def`, `import`, ...)
+    Keyword,
+    /// Line or block comment, including `doc` and `comment` bodies
+    Comment,
+    /// String literal
+    String,
+    /// Integer or decimal literal
+    Number,
+    /// Declared package name or import
+    Namespace,
+    /// Declared definition name
+    Type,
+    /// Declared attribute name
+    Attribute,
+    /// Declared usage name
+    Property,
+    /// Declared alias or other named element
+    Variable,
+    /// Reference that resolves to an element
+    Reference,
+    /// Reference that does not resolve
+    UnresolvedReference,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Keyword => "keyword",
+            Self::Comment => "comment",
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Namespace => "namespace",
+            Self::Type => "type",
+            Self::Attribute => "attribute",
+            Self::Property => "property",
+            Self::Variable => "variable",
+            Self::Reference => "reference",
+            Self::UnresolvedReference => "unresolved-reference",
+        })
+    }
+}
+
+/// A classified range of a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SemanticToken {
+    /// Start line (1-indexed).
+    pub line: u32,
+    /// Start column (1-indexed).
+    pub col: u32,
+    /// End line (1-indexed).
+    pub end_line: u32,
+    /// End column (1-indexed, exclusive).
+    pub end_col: u32,
+    /// Classification.
+    pub kind: TokenKind,
+    /// Qualified name of the declared or referenced element; the name as
+    /// written for an unresolved reference.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+/// Classified tokens of `file`, analyzed in the context of `src` (the
+/// file's directory if `None`), sorted by position.
+///
+/// # Arguments
+/// * `src` - Model file or directory the file belongs to
+/// * `file` - File to classify
+/// * `options` - Verbosity and standard library to load
+pub fn semantic_tokens(
+    src: Option<&Path>,
+    file: &Path,
+    options: &AnalysisOptions,
+) -> Result<Vec<SemanticToken>, String> {
+    let (mut host, path) = super::hover::load_file(src, file, options)?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let analysis = host.analysis();
+    let file_id = analysis
+        .get_file_id(&path.to_string_lossy())
+        .ok_or_else(|| format!("{} is not a SysML or KerML file", path.display()))?;
+
+    // Names and references first; lexical tokens only fill the gaps
+    let mut tokens = Vec::new();
+    for symbol in analysis.symbol_index().symbols_in_file(file_id) {
+        for type_ref in symbol.type_refs.iter().flat_map(|r| r.as_refs()) {
+            let range = [
+                type_ref.start_line,
+                type_ref.start_col,
+                type_ref.end_line,
+                type_ref.end_col,
+            ];
+            let (kind, target) = match &type_ref.resolved_target {
+                Some(target) => (TokenKind::Reference, target.to_string()),
+                None => (TokenKind::UnresolvedReference, type_ref.target.to_string()),
+            };
+            tokens.extend(token(range, kind, Some(target)));
+        }
+        if symbol.name.is_empty() || symbol.name.starts_with('<') {
+            continue;
+        }
+        let range = [
+            symbol.start_line,
+            symbol.start_col,
+            symbol.end_line,
+            symbol.end_col,
+        ];
+        let target = symbol.qualified_name.to_string();
+        tokens.extend(token(range, declaration_kind(symbol.kind), Some(target)));
+    }
+    tokens.sort_by_key(|t| (t.line, t.col));
+    tokens.dedup_by_key(|t| (t.line, t.col));
+
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let position = |offset: usize| {
+        let line = line_starts.partition_point(|&start| start <= offset) - 1;
+        (line as u32, (offset - line_starts[line]) as u32)
+    };
+    let mut lexical = Vec::new();
+    for lexed in Lexer::new(&text) {
+        let kind = match lexed.kind {
+            SyntaxKind::LINE_COMMENT | SyntaxKind::BLOCK_COMMENT => TokenKind::Comment,
+            SyntaxKind::STRING => TokenKind::String,
+            SyntaxKind::INTEGER | SyntaxKind::DECIMAL => TokenKind::Number,
+            kind if kind.is_keyword() => TokenKind::Keyword,
+            _ => continue,
+        };
+        let start = u32::from(lexed.offset) as usize;
+        let (line, col) = position(start);
+        let (end_line, end_col) = position(start + lexed.text.len());
+        let Some(classified) = token([line, col, end_line, end_col], kind, None) else {
+            continue;
+        };
+        // Quoted names lex as strings but are already classified
+        if !tokens.iter().any(|t| overlaps(t, &classified)) {
+            lexical.push(classified);
+        }
+    }
+
+    tokens.extend(lexical);
+    tokens.sort_by_key(|t| (t.line, t.col));
+    if options.verbose {
+        tracing::info!("{} tokens in {}", tokens.len(), path.display());
+    }
+    Ok(tokens)
+}
+
+/// Token for a 0-based `[start line, start column, end line, end column]`
+/// range, `None` for empty or synthetic ranges.
+fn token(range: [u32; 4], kind: TokenKind, target: Option<String>) -> Option<SemanticToken> {
+    let [line, col, end_line, end_col] = range;
+    // Synthetic elements are placed at the start of the file
+    if (line, col) == (0, 0) && target.is_some() {
+        return None;
+    }
+    if (end_line, end_col) <= (line, col) {
+        return None;
+    }
+    Some(SemanticToken {
+        line: line + 1,
+        col: col + 1,
+        end_line: end_line + 1,
+        end_col: end_col + 1,
+        kind,
+        target,
+    })
+}
+
+/// Whether two tokens share any character.
+fn overlaps(a: &SemanticToken, b: &SemanticToken) -> bool {
+    (a.line, a.col) < (b.end_line, b.end_col) && (b.line, b.col) < (a.end_line, a.end_col)
+}
+
+/// Token kind of a declared name.
+fn declaration_kind(kind: SymbolKind) -> TokenKind {
+    match kind {
+        SymbolKind::Package | SymbolKind::Import => TokenKind::Namespace,
+        SymbolKind::AttributeUsage => TokenKind::Attribute,
+        SymbolKind::Alias
+        | SymbolKind::Dependency
+        | SymbolKind::ExposeRelationship
+        | SymbolKind::Comment
+        | SymbolKind::Other => TokenKind::Variable,
+        kind if kind.is_definition() => TokenKind::Type,
+        _ => TokenKind::Property,
+    }
+}
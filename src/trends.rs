@@ -0,0 +1,272 @@
+//! Model statistics over time (`syster metrics`).
+//!
+//! Every run of `syster metrics record` appends one row to a SQLite
+//! database: when it ran, the git revision of the model, its size,
+//! diagnostic counts, documentation coverage and how long analysis took.
+//! `syster metrics chart` renders the rows as a self-contained HTML page
+//! with one trend line per metric.
+//!
+//! Unlike [`analyze_history`](super::analyze_history), which replays tags,
+//! the database records whatever is analyzed, so CI can record every build
+//! of a branch.
+
+use super::history::{HistoryMetric, git};
+use super::{AnalysisResult, escape_xml};
+use rusqlite::{Connection, params};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Metrics of one recorded run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsRun {
+    /// When the run was recorded (`YYYY-MM-DDTHH:MM:SSZ`).
+    pub recorded_at: String,
+    /// Abbreviated commit hash of the model, if it is in a git work tree.
+    pub revision: Option<String>,
+    /// Number of files analyzed.
+    pub file_count: usize,
+    /// Number of symbols, standard library included.
+    pub symbol_count: usize,
+    /// Number of errors.
+    pub error_count: usize,
+    /// Number of warnings.
+    pub warning_count: usize,
+    /// Share of the model's definitions with documentation, in percent.
+    pub doc_coverage: f64,
+    /// Wall-clock time of the analysis, in seconds.
+    pub duration: f64,
+}
+
+impl MetricsRun {
+    /// The metrics of `result`, analyzed in `duration` at `revision`,
+    /// recorded now.
+    pub fn new(result: &AnalysisResult, duration: Duration, revision: Option<String>) -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            recorded_at: super::format_rfc3339(seconds),
+            revision,
+            file_count: result.file_count,
+            symbol_count: result.symbol_count,
+            error_count: result.error_count,
+            warning_count: result.warning_count,
+            doc_coverage: result.doc_coverage.percent,
+            duration: duration.as_secs_f64(),
+        }
+    }
+
+    fn value(&self, metric: HistoryMetric) -> f64 {
+        match metric {
+            HistoryMetric::Files => self.file_count as f64,
+            HistoryMetric::Symbols => self.symbol_count as f64,
+            HistoryMetric::Errors => self.error_count as f64,
+            HistoryMetric::Warnings => self.warning_count as f64,
+            HistoryMetric::Docs => self.doc_coverage,
+        }
+    }
+}
+
+/// A SQLite database of recorded runs.
+pub struct MetricsDb {
+    connection: Connection,
+}
+
+impl MetricsDb {
+    /// Open the database at `path`, creating it if it does not exist.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let connection = Connection::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS runs (
+                    id INTEGER PRIMARY KEY,
+                    recorded_at TEXT NOT NULL,
+                    revision TEXT,
+                    files INTEGER NOT NULL,
+                    symbols INTEGER NOT NULL,
+                    errors INTEGER NOT NULL,
+                    warnings INTEGER NOT NULL,
+                    docs REAL NOT NULL,
+                    duration_seconds REAL NOT NULL
+                );",
+            )
+            .map_err(|e| {
+                format!(
+                    "Failed to create the runs table in {}: {}",
+                    path.display(),
+                    e
+                )
+            })?;
+        Ok(Self { connection })
+    }
+
+    /// Append `run`.
+    pub fn record(&self, run: &MetricsRun) -> Result<(), String> {
+        self.connection
+            .execute(
+                "INSERT INTO runs (recorded_at, revision, files, symbols, errors, warnings, docs, duration_seconds)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    run.recorded_at,
+                    run.revision,
+                    run.file_count as i64,
+                    run.symbol_count as i64,
+                    run.error_count as i64,
+                    run.warning_count as i64,
+                    run.doc_coverage,
+                    run.duration,
+                ],
+            )
+            .map_err(|e| format!("Failed to record run: {}", e))?;
+        Ok(())
+    }
+
+    /// Every recorded run, oldest first.
+    pub fn runs(&self) -> Result<Vec<MetricsRun>, String> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "SELECT recorded_at, revision, files, symbols, errors, warnings, docs, duration_seconds
+                 FROM runs ORDER BY id",
+            )
+            .map_err(|e| format!("Failed to read runs: {}", e))?;
+        let runs = statement
+            .query_map([], |row| {
+                Ok(MetricsRun {
+                    recorded_at: row.get(0)?,
+                    revision: row.get(1)?,
+                    file_count: row.get::<_, i64>(2)? as usize,
+                    symbol_count: row.get::<_, i64>(3)? as usize,
+                    error_count: row.get::<_, i64>(4)? as usize,
+                    warning_count: row.get::<_, i64>(5)? as usize,
+                    doc_coverage: row.get(6)?,
+                    duration: row.get(7)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("Failed to read runs: {}", e))?;
+        Ok(runs)
+    }
+}
+
+/// Abbreviated commit hash checked out where `input` lives, if it is in a
+/// git work tree.
+pub fn git_revision(input: &Path) -> Option<String> {
+    let dir = if input.is_dir() {
+        input
+    } else {
+        input.parent().filter(|p| !p.as_os_str().is_empty())?
+    };
+    let revision = git(dir, &["rev-parse", "--short", "HEAD"]).ok()?;
+    Some(revision.trim().to_string()).filter(|r| !r.is_empty())
+}
+
+/// Chart size and the margin around the plot, in pixels.
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 180.0;
+const MARGIN: f64 = 40.0;
+
+/// Render `runs` as an HTML page with a trend chart (inline SVG) per metric.
+pub fn trend_chart(runs: &[MetricsRun], metrics: &[HistoryMetric]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Model trends</title>\n\
+         <style>\nbody { font-family: sans-serif; margin: 2em; }\n\
+         svg { display: block; margin-bottom: 2em; }\n\
+         text { font-size: 11px; fill: #555; }\n\
+         polyline { fill: none; stroke: #1f77b4; stroke-width: 2; }\n\
+         circle { fill: #1f77b4; }\n</style>\n</head>\n<body>\n<h1>Model trends</h1>\n",
+    );
+    match (runs.first(), runs.last()) {
+        (Some(first), Some(last)) => html.push_str(&format!(
+            "<p>{} runs from {} to {}</p>\n",
+            runs.len(),
+            escape_xml(&first.recorded_at),
+            escape_xml(&last.recorded_at)
+        )),
+        _ => html.push_str("<p>No runs recorded yet.</p>\n"),
+    }
+    if !runs.is_empty() {
+        for &metric in metrics {
+            html.push_str(&format!("<h2>{}</h2>\n", metric.name()));
+            html.push_str(&chart(runs, metric));
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// One metric's trend line, scaled between its lowest and highest value.
+fn chart(runs: &[MetricsRun], metric: HistoryMetric) -> String {
+    let values: Vec<f64> = runs.iter().map(|run| run.value(metric)).collect();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let plot_width = WIDTH - 2.0 * MARGIN;
+    let plot_height = HEIGHT - 2.0 * MARGIN;
+    let x = |i: usize| match values.len() {
+        1 => MARGIN + plot_width / 2.0,
+        n => MARGIN + plot_width * i as f64 / (n - 1) as f64,
+    };
+    let y = |value: f64| {
+        if max > min {
+            HEIGHT - MARGIN - plot_height * (value - min) / (max - min)
+        } else {
+            HEIGHT / 2.0
+        }
+    };
+    let format = |value: f64| match metric {
+        HistoryMetric::Docs => format!("{:.1}%", value),
+        _ => format!("{}", value),
+    };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = WIDTH,
+        h = HEIGHT
+    );
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n\
+         <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>\n",
+        MARGIN - 6.0,
+        y(max) + 4.0,
+        format(max),
+        MARGIN - 6.0,
+        y(min) + 4.0,
+        format(min)
+    ));
+    let points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| format!("{:.1},{:.1}", x(i), y(value)))
+        .collect();
+    svg.push_str(&format!("<polyline points=\"{}\"/>\n", points.join(" ")));
+    for (i, (run, &value)) in runs.iter().zip(&values).enumerate() {
+        let revision = run
+            .revision
+            .as_deref()
+            .map(|r| format!(" ({})", r))
+            .unwrap_or_default();
+        svg.push_str(&format!(
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\"><title>{}{}: {}</title></circle>\n",
+            x(i),
+            y(value),
+            escape_xml(&run.recorded_at),
+            escape_xml(&revision),
+            format(value)
+        ));
+    }
+    let date = |run: &MetricsRun| escape_xml(run.recorded_at.get(..10).unwrap_or_default());
+    svg.push_str(&format!(
+        "<text x=\"{}\" y=\"{}\" text-anchor=\"start\">{}</text>\n\
+         <text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+        MARGIN,
+        HEIGHT - MARGIN + 20.0,
+        date(&runs[0]),
+        WIDTH - MARGIN,
+        HEIGHT - MARGIN + 20.0,
+        date(&runs[runs.len() - 1])
+    ));
+    svg.push_str("</svg>\n");
+    svg
+}
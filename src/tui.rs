@@ -0,0 +1,580 @@
+//! Interactive terminal browser (`syster tui`).
+//!
+//! The model is loaded once into an [`AnalysisHost`] that stays warm for the
+//! whole session, so browsing, jumping and reloading never re-read the
+//! standard library. A saved workspace is browsed from its saved symbols and
+//! sources, without parsing anything until the first reload. [`Browser`]
+//! holds the navigation state and is usable without a terminal; [`run`]
+//! draws it with ratatui and feeds it key events.
+
+use super::workspace::{SavedSymbol, index_symbols, tree_order};
+use super::{AnalysisOptions, DiagnosticInfo, Workspace};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use syster::hir::Severity;
+use syster::ide::AnalysisHost;
+
+/// Lines of source shown above and below a jump target.
+const EXCERPT_CONTEXT: u32 = 3;
+
+/// A symbol in the package tree.
+#[derive(Debug, Clone)]
+pub struct TreeNode {
+    /// Simple name.
+    pub name: String,
+    /// Fully qualified name.
+    pub qualified_name: String,
+    /// Display kind, e.g. "Part def".
+    pub kind: String,
+    /// Nesting depth (0 for top-level packages).
+    pub depth: usize,
+    /// Whether any symbol is nested inside this one.
+    pub has_children: bool,
+    /// File the symbol is declared in.
+    pub file: String,
+    /// Declaration line (1-indexed).
+    pub line: u32,
+    /// Declaration column (1-indexed).
+    pub col: u32,
+}
+
+/// Which pane receives navigation keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    /// The package tree.
+    Tree,
+    /// The diagnostics list.
+    Diagnostics,
+}
+
+/// Navigation state of the browser.
+pub struct Browser {
+    /// The loaded model; `None` for a saved workspace until it is reloaded.
+    host: Option<AnalysisHost>,
+    /// Sources of a saved workspace, by path, while `host` is `None`.
+    saved_files: HashMap<String, String>,
+    /// Every indexed symbol, in [`tree_order`].
+    symbols: Vec<SavedSymbol>,
+    nodes: Vec<TreeNode>,
+    names: HashSet<String>,
+    expanded: BTreeSet<String>,
+    diagnostics: Vec<DiagnosticInfo>,
+    focus: Pane,
+    tree_selected: usize,
+    diag_selected: usize,
+    jump: Option<(String, u32)>,
+}
+
+impl Browser {
+    /// Load `input` (and the standard library, if requested) and open the
+    /// tree with top-level packages expanded. Nothing is logged, whatever
+    /// `options.verbose`, as the browser owns the terminal.
+    pub fn load(input: &Path, options: &AnalysisOptions) -> Result<Self, String> {
+        let mut host = AnalysisHost::new();
+        if options.load_stdlib {
+            super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), false)?;
+        }
+        super::load_input(&mut host, input, false)?;
+        let mut browser = Self::open(Some(host), HashMap::new());
+        browser.refresh();
+        browser.expand_top_level();
+        Ok(browser)
+    }
+
+    /// Open a saved workspace from its saved symbols and diagnostics,
+    /// without parsing its sources or touching the files it was saved from.
+    pub fn from_workspace(workspace: &Workspace) -> Self {
+        let mut browser = Self::open(None, workspace.files.iter().cloned().collect());
+        browser.symbols = workspace.symbols.clone();
+        browser.diagnostics = workspace.result.diagnostics.clone();
+        browser.build_tree();
+        browser.expand_top_level();
+        browser
+    }
+
+    /// A browser on `host` or `saved_files`, with nothing indexed yet.
+    fn open(host: Option<AnalysisHost>, saved_files: HashMap<String, String>) -> Self {
+        Self {
+            host,
+            saved_files,
+            symbols: Vec::new(),
+            nodes: Vec::new(),
+            names: HashSet::new(),
+            expanded: BTreeSet::new(),
+            diagnostics: Vec::new(),
+            focus: Pane::Tree,
+            tree_selected: 0,
+            diag_selected: 0,
+            jump: None,
+        }
+    }
+
+    /// Expand the top-level packages.
+    fn expand_top_level(&mut self) {
+        self.expanded = self
+            .nodes
+            .iter()
+            .filter(|n| n.depth == 0)
+            .map(|n| n.qualified_name.clone())
+            .collect();
+    }
+
+    /// Re-read the loaded model files from disk and re-run analysis,
+    /// keeping the expanded packages and the selection where possible. A
+    /// saved workspace is parsed from its saved sources first.
+    pub fn reload(&mut self) -> Result<(), String> {
+        let host = self.host.get_or_insert_with(|| {
+            let mut host = AnalysisHost::new();
+            for (path, content) in &self.saved_files {
+                host.set_file_content(path, content);
+            }
+            host
+        });
+        self.saved_files.clear();
+        let paths: Vec<_> = host
+            .files()
+            .keys()
+            .filter(|p| !p.to_string_lossy().contains("sysml.library"))
+            .cloned()
+            .collect();
+        for path in paths {
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            host.set_file_content(&path.to_string_lossy(), &content);
+        }
+
+        let selected = self.selected().map(|n| n.qualified_name.clone());
+        self.refresh();
+        if let Some(name) = selected {
+            self.select(&name);
+        }
+        self.clamp();
+        Ok(())
+    }
+
+    /// Re-index the analysis host and rebuild the tree and diagnostics.
+    fn refresh(&mut self) {
+        let Some(host) = &mut self.host else {
+            return;
+        };
+        self.symbols = index_symbols(host);
+        self.diagnostics = super::collect_diagnostics(host);
+        self.build_tree();
+    }
+
+    /// Build the tree from the symbols of the model's own files.
+    fn build_tree(&mut self) {
+        let mut nodes: Vec<TreeNode> = self
+            .symbols
+            .iter()
+            .filter(|sym| !sym.file.contains("sysml.library"))
+            .map(|sym| TreeNode {
+                name: sym.name.clone(),
+                qualified_name: sym.qualified_name.clone(),
+                kind: sym.kind.clone(),
+                depth: sym.qualified_name.matches("::").count(),
+                has_children: false,
+                file: sym.file.clone(),
+                line: sym.line,
+                col: sym.col,
+            })
+            .collect();
+        for i in 1..nodes.len() {
+            let (before, after) = nodes.split_at_mut(i);
+            let parent = &mut before[i - 1];
+            parent.has_children = after[0]
+                .qualified_name
+                .strip_prefix(parent.qualified_name.as_str())
+                .is_some_and(|rest| rest.starts_with("::"));
+        }
+
+        self.names = nodes.iter().map(|n| n.qualified_name.clone()).collect();
+        self.nodes = nodes;
+    }
+
+    /// Tree rows currently shown, i.e. those whose ancestors are all expanded.
+    pub fn visible(&self) -> Vec<&TreeNode> {
+        self.nodes
+            .iter()
+            .filter(|node| {
+                node.qualified_name
+                    .match_indices("::")
+                    .map(|(i, _)| &node.qualified_name[..i])
+                    .all(|ancestor| {
+                        !self.names.contains(ancestor) || self.expanded.contains(ancestor)
+                    })
+            })
+            .collect()
+    }
+
+    /// The selected tree row.
+    pub fn selected(&self) -> Option<&TreeNode> {
+        self.visible().get(self.tree_selected).copied()
+    }
+
+    /// All diagnostics, sorted by location.
+    pub fn diagnostics(&self) -> &[DiagnosticInfo] {
+        &self.diagnostics
+    }
+
+    /// The pane receiving navigation keys.
+    pub fn focus(&self) -> Pane {
+        self.focus
+    }
+
+    /// Switch between the tree and the diagnostics list.
+    pub fn toggle_focus(&mut self) {
+        self.focus = match self.focus {
+            Pane::Tree => Pane::Diagnostics,
+            Pane::Diagnostics => Pane::Tree,
+        };
+    }
+
+    /// Move the selection of the focused pane by `delta` rows.
+    pub fn move_selection(&mut self, delta: isize) {
+        let len = match self.focus {
+            Pane::Tree => self.visible().len(),
+            Pane::Diagnostics => self.diagnostics.len(),
+        };
+        let selected = match self.focus {
+            Pane::Tree => {
+                self.jump = None;
+                &mut self.tree_selected
+            }
+            Pane::Diagnostics => &mut self.diag_selected,
+        };
+        *selected = selected
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+    }
+
+    /// Expand or collapse the selected tree row.
+    pub fn set_expanded(&mut self, expanded: bool) {
+        let Some(name) = self.selected().map(|n| n.qualified_name.clone()) else {
+            return;
+        };
+        if expanded {
+            self.expanded.insert(name);
+        } else {
+            self.expanded.remove(&name);
+        }
+    }
+
+    /// Toggle the selected tree row.
+    pub fn toggle_expanded(&mut self) {
+        let expanded = self
+            .selected()
+            .is_some_and(|n| self.expanded.contains(&n.qualified_name));
+        self.set_expanded(!expanded);
+    }
+
+    /// Jump to the selected diagnostic: select the closest declaration at or
+    /// before it in the tree and show the source around it.
+    pub fn jump_to_diagnostic(&mut self) {
+        let Some(diag) = self.diagnostics.get(self.diag_selected) else {
+            return;
+        };
+        let (file, line, col) = (diag.file.clone(), diag.line, diag.col);
+
+        let target = self
+            .owner_of(&file, line, col)
+            .map(|n| n.qualified_name.clone());
+        if let Some(name) = target {
+            self.select(&name);
+        }
+        self.focus = Pane::Tree;
+        self.jump = Some((file, line));
+    }
+
+    /// Select `qualified_name`, expanding its ancestors.
+    fn select(&mut self, qualified_name: &str) {
+        for (i, _) in qualified_name.match_indices("::") {
+            self.expanded.insert(qualified_name[..i].to_string());
+        }
+        if let Some(i) = self
+            .visible()
+            .iter()
+            .position(|n| n.qualified_name == qualified_name)
+        {
+            self.tree_selected = i;
+        }
+    }
+
+    /// Keep both selections inside their lists.
+    fn clamp(&mut self) {
+        let visible = self.visible().len();
+        self.tree_selected = self.tree_selected.min(visible.saturating_sub(1));
+        self.diag_selected = self
+            .diag_selected
+            .min(self.diagnostics.len().saturating_sub(1));
+    }
+
+    /// File and line (1-indexed) the details pane points at: the last
+    /// diagnostic jumped to, otherwise the selected declaration.
+    pub fn location(&self) -> Option<(String, u32)> {
+        self.jump
+            .clone()
+            .or_else(|| self.selected().map(|n| (n.file.clone(), n.line)))
+    }
+
+    /// Details of the selected symbol.
+    pub fn details(&self) -> Vec<String> {
+        let Some(node) = self.selected() else {
+            return vec!["No symbols loaded".to_string()];
+        };
+        let mut lines = vec![
+            format!("{} {}", node.kind, node.qualified_name),
+            format!("Defined in: {}:{}:{}", node.file, node.line, node.col),
+        ];
+
+        let symbol = self
+            .symbols
+            .binary_search_by(|sym| tree_order(&sym.qualified_name, &node.qualified_name))
+            .ok()
+            .map(|i| &self.symbols[i]);
+        if let Some(symbol) = symbol {
+            if let Some(doc) = &symbol.doc {
+                lines.push(String::new());
+                lines.extend(doc.lines().map(|l| l.trim().to_string()));
+            }
+            if !symbol.relationships.is_empty() {
+                lines.push(String::new());
+            }
+            for rel in &symbol.relationships {
+                lines.push(format!("{}: {}", rel.kind, rel.target));
+            }
+        }
+
+        let problems = self
+            .diagnostics
+            .iter()
+            .filter(|d| {
+                self.owner_of(&d.file, d.line, d.col)
+                    .is_some_and(|owner| owner.qualified_name == node.qualified_name)
+            })
+            .count();
+        if problems > 0 {
+            lines.push(String::new());
+            lines.push(format!("Diagnostics: {}", problems));
+        }
+        lines
+    }
+
+    /// The closest declaration at or before a location.
+    fn owner_of(&self, file: &str, line: u32, col: u32) -> Option<&TreeNode> {
+        self.nodes
+            .iter()
+            .filter(|n| n.file == file && (n.line, n.col) <= (line, col))
+            .max_by_key(|n| (n.line, n.col))
+    }
+
+    /// Numbered source lines around [`Browser::location`].
+    pub fn source_excerpt(&self) -> Vec<(u32, String)> {
+        let Some((file, line)) = self.location() else {
+            return Vec::new();
+        };
+        let source = match &self.host {
+            Some(host) => host.files().get(Path::new(&file)).map(|f| f.source_text()),
+            None => self.saved_files.get(&file).cloned(),
+        };
+        let Some(source) = source else {
+            return Vec::new();
+        };
+        let first = line.saturating_sub(EXCERPT_CONTEXT).max(1);
+        source
+            .lines()
+            .enumerate()
+            .map(|(i, text)| (i as u32 + 1, text.to_string()))
+            .skip_while(|(n, _)| *n < first)
+            .take_while(|(n, _)| *n <= line + EXCERPT_CONTEXT)
+            .collect()
+    }
+}
+
+/// Open the browser on `input` in the current terminal.
+pub fn run(input: &Path, options: &AnalysisOptions) -> Result<(), String> {
+    browse(Browser::load(input, options)?)
+}
+
+/// Open the browser on a saved workspace in the current terminal.
+pub fn run_workspace(workspace: &Workspace) -> Result<(), String> {
+    browse(Browser::from_workspace(workspace))
+}
+
+/// Run `browser` in the current terminal until the user quits.
+fn browse(mut browser: Browser) -> Result<(), String> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut browser);
+    ratatui::restore();
+    result
+}
+
+/// Draw and handle keys until the user quits.
+fn event_loop(terminal: &mut DefaultTerminal, browser: &mut Browser) -> Result<(), String> {
+    let mut status = String::new();
+    loop {
+        terminal
+            .draw(|frame| draw(frame, browser, &status))
+            .map_err(|e| format!("Terminal error: {}", e))?;
+
+        let Event::Key(key) = event::read().map_err(|e| format!("Terminal error: {}", e))? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        status.clear();
+        match (key.code, browser.focus()) {
+            (KeyCode::Char('q') | KeyCode::Esc, _) => return Ok(()),
+            (KeyCode::Tab, _) => browser.toggle_focus(),
+            (KeyCode::Up | KeyCode::Char('k'), _) => browser.move_selection(-1),
+            (KeyCode::Down | KeyCode::Char('j'), _) => browser.move_selection(1),
+            (KeyCode::PageUp, _) => browser.move_selection(-10),
+            (KeyCode::PageDown, _) => browser.move_selection(10),
+            (KeyCode::Left | KeyCode::Char('h'), Pane::Tree) => browser.set_expanded(false),
+            (KeyCode::Right | KeyCode::Char('l'), Pane::Tree) => browser.set_expanded(true),
+            (KeyCode::Enter | KeyCode::Char(' '), Pane::Tree) => browser.toggle_expanded(),
+            (KeyCode::Enter, Pane::Diagnostics) => browser.jump_to_diagnostic(),
+            (KeyCode::Char('r'), _) => {
+                status = match browser.reload() {
+                    Ok(()) => format!("Reloaded, {} diagnostics", browser.diagnostics().len()),
+                    Err(e) => e,
+                };
+            }
+            (KeyCode::Char('e'), _) => {
+                if let Some((file, line)) = browser.location() {
+                    ratatui::restore();
+                    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+                    let result = std::process::Command::new(&editor)
+                        .arg(format!("+{}", line))
+                        .arg(&file)
+                        .status();
+                    *terminal = ratatui::init();
+                    status = match result {
+                        Ok(_) => match browser.reload() {
+                            Ok(()) => format!("Edited {}", file),
+                            Err(e) => e,
+                        },
+                        Err(e) => format!("Failed to run {}: {}", editor, e),
+                    };
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Render all panes.
+fn draw(frame: &mut Frame, browser: &Browser, status: &str) {
+    let [main, diagnostics, footer] = Layout::vertical([
+        Constraint::Min(8),
+        Constraint::Length(10),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [tree, details] =
+        Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(main);
+
+    draw_tree(frame, browser, tree);
+    draw_details(frame, browser, details);
+    draw_diagnostics(frame, browser, diagnostics);
+
+    let help = if status.is_empty() {
+        "q quit  Tab switch pane  ↑↓ move  ←→/Enter fold  Enter on diagnostic: jump  e edit  r reload"
+    } else {
+        status
+    };
+    frame.render_widget(Paragraph::new(help), footer);
+}
+
+/// Border block for a pane, highlighted when focused.
+fn pane_block(title: &str, focused: bool) -> Block<'_> {
+    let style = if focused {
+        Style::new().fg(Color::Cyan)
+    } else {
+        Style::new()
+    };
+    Block::bordered().title(title).border_style(style)
+}
+
+fn draw_tree(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let items: Vec<ListItem> = browser
+        .visible()
+        .into_iter()
+        .map(|node| {
+            let marker = match (
+                node.has_children,
+                browser.expanded.contains(&node.qualified_name),
+            ) {
+                (false, _) => " ",
+                (true, true) => "▾",
+                (true, false) => "▸",
+            };
+            ListItem::new(format!(
+                "{}{} {} ({})",
+                "  ".repeat(node.depth),
+                marker,
+                node.name,
+                node.kind
+            ))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane_block("Packages", browser.focus() == Pane::Tree))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(browser.tree_selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn draw_details(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let mut lines: Vec<Line> = browser.details().into_iter().map(Line::from).collect();
+    let excerpt = browser.source_excerpt();
+    if !excerpt.is_empty() {
+        let target = browser.location().map(|(_, line)| line);
+        lines.push(Line::from(""));
+        for (number, text) in excerpt {
+            let line = Line::from(format!("{:>5} │ {}", number, text));
+            lines.push(if Some(number) == target {
+                line.style(Style::new().add_modifier(Modifier::BOLD))
+            } else {
+                line.style(Style::new().fg(Color::DarkGray))
+            });
+        }
+    }
+    let paragraph = Paragraph::new(lines)
+        .block(pane_block("Details", false))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_diagnostics(frame: &mut Frame, browser: &Browser, area: Rect) {
+    let items: Vec<ListItem> = browser
+        .diagnostics()
+        .iter()
+        .map(|d| {
+            let (label, color) = match d.severity {
+                Severity::Error => ("error", Color::Red),
+                Severity::Warning => ("warning", Color::Yellow),
+                Severity::Info => ("info", Color::Blue),
+                Severity::Hint => ("hint", Color::Gray),
+            };
+            ListItem::new(format!(
+                "{}:{}:{}: {}: {}",
+                d.file, d.line, d.col, label, d.message
+            ))
+            .style(Style::new().fg(color))
+        })
+        .collect();
+    let title = format!("Diagnostics ({})", items.len());
+    let list = List::new(items)
+        .block(pane_block(&title, browser.focus() == Pane::Diagnostics))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    let mut state = ListState::default().with_selected(Some(browser.diag_selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
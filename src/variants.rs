@@ -0,0 +1,544 @@
+//! Configuration-aware analysis of product-line models.
+//!
+//! A `variation` definition or usage is a variation point; the usages it owns
+//! are its variants. A configuration file picks one variant per variation
+//! point:
+//!
+//! ```toml
+//! [variants]
+//! "Vehicle::engine" = "v6"
+//! "Vehicle::Transmission" = "manual"
+//! ```
+//!
+//! Resolving a configuration removes every unselected variant together with
+//! everything nested inside it. The selection must be complete (every
+//! variation point that remains has a selection) and consistent (names exist
+//! and nothing that remains references a removed variant).
+
+use super::AnalysisOptions;
+use super::DiagnosticInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use syster::hir::{HirSymbol, Severity, SymbolKind};
+use syster::ide::{Analysis, AnalysisHost};
+
+/// A variant selection loaded from a configuration file.
+#[derive(Debug, Clone, Default)]
+pub struct VariantConfig {
+    /// File the configuration was loaded from.
+    pub path: PathBuf,
+    /// Selected variant (simple name) by qualified variation point name.
+    pub variants: BTreeMap<String, String>,
+    /// Line (1-indexed) of each selection in the file.
+    lines: BTreeMap<String, u32>,
+}
+
+/// On-disk layout of a TOML configuration file.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    variants: BTreeMap<String, String>,
+}
+
+/// One variation point of a JSON selection matrix.
+#[derive(Serialize, Deserialize)]
+struct MatrixEntry {
+    variation_point: String,
+    #[serde(default)]
+    kind: String,
+    #[serde(default)]
+    file: String,
+    #[serde(default)]
+    line: u32,
+    #[serde(default)]
+    variants: Vec<String>,
+    #[serde(default)]
+    selected: Option<String>,
+}
+
+impl VariantConfig {
+    /// Load a configuration file.
+    ///
+    /// `.csv` and `.json` files are read as selection matrices written by
+    /// [`render_variant_matrix`]; anything else as TOML. Empty selections
+    /// count as missing.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let parse_error = |e: String| format!("Failed to parse {}: {}", path.display(), e);
+
+        let (variants, lines) = match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => parse_csv_matrix(&text).map_err(parse_error)?,
+            Some("json") => {
+                let entries: Vec<MatrixEntry> =
+                    serde_json::from_str(&text).map_err(|e| parse_error(e.to_string()))?;
+                let variants: BTreeMap<String, String> = entries
+                    .into_iter()
+                    .filter_map(|e| Some((e.variation_point, e.selected?)))
+                    .collect();
+                let lines = key_lines(&text, &variants);
+                (variants, lines)
+            }
+            _ => {
+                let file: ConfigFile =
+                    toml::from_str(&text).map_err(|e| parse_error(e.to_string()))?;
+                let lines = key_lines(&text, &file.variants);
+                (file.variants, lines)
+            }
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            variants: variants
+                .into_iter()
+                .filter(|(_, choice)| !choice.trim().is_empty())
+                .collect(),
+            lines,
+        })
+    }
+
+    /// Diagnostic pointing at the selection for `key`.
+    fn issue(&self, key: &str, severity: Severity, message: String) -> DiagnosticInfo {
+        let line = self.lines.get(key).copied().unwrap_or(1);
+        DiagnosticInfo {
+            file: self.path.to_string_lossy().to_string(),
+            line,
+            col: 1,
+            end_line: line,
+            end_col: 1,
+            message,
+            severity,
+            code: None,
+            blame: None,
+            owners: Vec::new(),
+            related: Vec::new(),
+        }
+    }
+}
+
+/// Line (1-indexed) of each key: the first line containing it quoted, or
+/// starting with it as a bare TOML key; 1 if not found.
+fn key_lines(text: &str, variants: &BTreeMap<String, String>) -> BTreeMap<String, u32> {
+    let declares = |line: &str, key: &str| {
+        line.contains(&format!("\"{}\"", key))
+            || line
+                .trim_start()
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+    };
+    variants
+        .keys()
+        .map(|key| {
+            let line = text
+                .lines()
+                .position(|line| declares(line, key))
+                .map_or(1, |i| i as u32 + 1);
+            (key.clone(), line)
+        })
+        .collect()
+}
+
+/// Selections and their lines from a CSV matrix: rows with anything in the
+/// `selected` column pick their variant.
+fn parse_csv_matrix(
+    text: &str,
+) -> Result<(BTreeMap<String, String>, BTreeMap<String, u32>), String> {
+    let mut lines = text.lines().enumerate();
+    let header: Vec<&str> = lines
+        .next()
+        .map(|(_, h)| h.split(',').map(str::trim).collect())
+        .unwrap_or_default();
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|h| *h == name)
+            .ok_or_else(|| format!("missing column '{}'", name))
+    };
+    let (point_col, variant_col, selected_col) = (
+        column("variation_point")?,
+        column("variant")?,
+        column("selected")?,
+    );
+
+    let mut variants = BTreeMap::new();
+    let mut key_lines = BTreeMap::new();
+    for (i, line) in lines {
+        let cells: Vec<&str> = line.split(',').map(str::trim).collect();
+        let cell = |c: usize| cells.get(c).copied().unwrap_or_default();
+        if cell(selected_col).is_empty() {
+            continue;
+        }
+        let (point, variant) = (cell(point_col), cell(variant_col));
+        if let Some(previous) = variants.insert(point.to_string(), variant.to_string()) {
+            return Err(format!(
+                "line {}: {} already has variant {} selected",
+                i + 1,
+                point,
+                previous
+            ));
+        }
+        key_lines.insert(point.to_string(), i as u32 + 1);
+    }
+    Ok((variants, key_lines))
+}
+
+/// A variation point and its variants.
+#[derive(Debug, Clone)]
+pub struct VariationPoint {
+    /// Qualified name of the variation definition or usage.
+    pub qualified_name: String,
+    /// Kind of the variation, e.g. `Part def`.
+    pub kind: String,
+    /// File declaring the variation point.
+    pub file: String,
+    /// Declaration line (1-indexed).
+    pub line: u32,
+    /// Declaration column (1-indexed).
+    pub col: u32,
+    /// Simple names of the variants, in declaration order.
+    pub variants: Vec<String>,
+}
+
+/// Every variation point declared outside the standard library, in
+/// qualified-name order.
+pub fn variation_points(analysis: &Analysis<'_>) -> Vec<VariationPoint> {
+    let index = analysis.symbol_index();
+    let mut points: BTreeMap<String, VariationPoint> = index
+        .all_symbols()
+        .filter(|sym| sym.is_variation)
+        .filter_map(|sym| {
+            let path = analysis.get_file_path(sym.file)?;
+            if path.contains("sysml.library") {
+                return None;
+            }
+            let point = VariationPoint {
+                qualified_name: sym.qualified_name.to_string(),
+                kind: sym.kind.display().to_string(),
+                file: path.to_string(),
+                line: sym.start_line + 1,
+                col: sym.start_col + 1,
+                variants: Vec::new(),
+            };
+            Some((point.qualified_name.clone(), point))
+        })
+        .collect();
+
+    let mut variants: Vec<&HirSymbol> = index
+        .all_symbols()
+        .filter(|sym| is_variant_kind(sym.kind) && !sym.name.is_empty())
+        .collect();
+    variants.sort_by_key(|sym| (sym.file, sym.start_line, sym.start_col));
+    for variant in variants {
+        let owner = variant.qualified_name.rsplit_once("::").map(|(o, _)| o);
+        if let Some(point) = owner.and_then(|o| points.get_mut(o)) {
+            point.variants.push(variant.name.to_string());
+        }
+    }
+
+    points.into_values().collect()
+}
+
+/// Whether an element of this kind can be a variant (a usage).
+fn is_variant_kind(kind: SymbolKind) -> bool {
+    matches!(
+        kind,
+        SymbolKind::PartUsage
+            | SymbolKind::ItemUsage
+            | SymbolKind::ActionUsage
+            | SymbolKind::PortUsage
+            | SymbolKind::AttributeUsage
+            | SymbolKind::ConnectionUsage
+            | SymbolKind::InterfaceUsage
+            | SymbolKind::AllocationUsage
+            | SymbolKind::RequirementUsage
+            | SymbolKind::ConstraintUsage
+            | SymbolKind::StateUsage
+            | SymbolKind::CalculationUsage
+            | SymbolKind::ReferenceUsage
+            | SymbolKind::OccurrenceUsage
+            | SymbolKind::FlowConnectionUsage
+            | SymbolKind::ViewUsage
+            | SymbolKind::ViewpointUsage
+            | SymbolKind::RenderingUsage
+    )
+}
+
+/// The outcome of applying a [`VariantConfig`] to a model.
+#[derive(Debug, Default)]
+pub struct Resolution {
+    /// Qualified name of the selected variant, by variation point.
+    pub selected: BTreeMap<String, String>,
+    /// Unselected variants; they are removed with everything inside them.
+    pub excluded: BTreeSet<String>,
+    /// Incomplete or inconsistent selections.
+    pub issues: Vec<DiagnosticInfo>,
+    /// Declaration positions (1-indexed) per file, and whether each
+    /// declaration is removed.
+    declarations: BTreeMap<String, Vec<(u32, u32, bool)>>,
+}
+
+impl Resolution {
+    /// Whether `qualified_name` is an unselected variant or nested inside one.
+    pub fn is_excluded(&self, qualified_name: &str) -> bool {
+        self.excluded.iter().any(|e| {
+            qualified_name == e
+                || qualified_name
+                    .strip_prefix(e.as_str())
+                    .is_some_and(|rest| rest.starts_with("::"))
+        })
+    }
+
+    /// Keep only the symbols in the resolved configuration.
+    pub fn select_symbols(&self, symbols: Vec<HirSymbol>) -> Vec<HirSymbol> {
+        symbols
+            .into_iter()
+            .filter(|sym| !self.is_excluded(&sym.qualified_name))
+            .collect()
+    }
+
+    /// Drop diagnostics reported inside removed elements.
+    ///
+    /// A diagnostic belongs to the closest declaration at or before it in
+    /// the same file.
+    pub fn retain_diagnostics(&self, diagnostics: Vec<DiagnosticInfo>) -> Vec<DiagnosticInfo> {
+        diagnostics
+            .into_iter()
+            .filter(|d| {
+                let Some(declarations) = self.declarations.get(&d.file) else {
+                    return true;
+                };
+                let owner = declarations
+                    .iter()
+                    .take_while(|(line, col, _)| (*line, *col) <= (d.line, d.col))
+                    .last();
+                !owner.is_some_and(|(_, _, excluded)| *excluded)
+            })
+            .collect()
+    }
+
+    /// Fail with every error if the configuration is incomplete or
+    /// inconsistent.
+    pub fn check(&self) -> Result<(), String> {
+        let errors: Vec<String> = self
+            .issues
+            .iter()
+            .filter(|d| matches!(d.severity, Severity::Error))
+            .map(|d| format!("{}:{}: {}", d.file, d.line, d.message))
+            .collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} problem(s) with the variant configuration:\n  {}",
+                errors.len(),
+                errors.join("\n  ")
+            ))
+        }
+    }
+}
+
+/// Apply `config` to the analyzed model.
+pub fn resolve_variants(analysis: &Analysis<'_>, config: &VariantConfig) -> Resolution {
+    let points = variation_points(analysis);
+    let mut resolution = Resolution::default();
+
+    // Selections: every key must name a variation point and one of its variants
+    for (key, choice) in &config.variants {
+        let Some(point) = points.iter().find(|p| &p.qualified_name == key) else {
+            resolution.issues.push(config.issue(
+                key,
+                Severity::Error,
+                format!("{} is not a variation point", key),
+            ));
+            continue;
+        };
+        let choice = choice.strip_prefix(&format!("{}::", key)).unwrap_or(choice);
+        if !point.variants.iter().any(|v| v == choice) {
+            resolution.issues.push(config.issue(
+                key,
+                Severity::Error,
+                format!(
+                    "{} is not a variant of {} (one of: {})",
+                    choice,
+                    key,
+                    point.variants.join(", ")
+                ),
+            ));
+            continue;
+        }
+        resolution
+            .selected
+            .insert(key.clone(), format!("{}::{}", key, choice));
+    }
+
+    // Removal: every variant not selected, for points with a valid selection
+    for point in &points {
+        if let Some(selected) = resolution.selected.get(&point.qualified_name) {
+            for variant in &point.variants {
+                let name = format!("{}::{}", point.qualified_name, variant);
+                if &name != selected {
+                    resolution.excluded.insert(name);
+                }
+            }
+        }
+    }
+
+    // Completeness: remaining points need a selection; removed ones must not have one
+    for point in &points {
+        let removed = resolution.is_excluded(&point.qualified_name);
+        let configured = config.variants.contains_key(&point.qualified_name);
+        if removed && configured {
+            resolution.issues.push(config.issue(
+                &point.qualified_name,
+                Severity::Warning,
+                format!(
+                    "selection for {} is ignored: it is inside an unselected variant",
+                    point.qualified_name
+                ),
+            ));
+        } else if !removed && !configured && !point.variants.is_empty() {
+            resolution.issues.push(DiagnosticInfo {
+                file: point.file.clone(),
+                line: point.line,
+                col: point.col,
+                end_line: point.line,
+                end_col: point.col,
+                message: format!(
+                    "no variant selected for {} (one of: {})",
+                    point.qualified_name,
+                    point.variants.join(", ")
+                ),
+                severity: Severity::Error,
+                code: None,
+                blame: None,
+                owners: Vec::new(),
+                related: Vec::new(),
+            });
+        }
+    }
+
+    // Consistency: nothing that remains may reference a removed element
+    let index = analysis.symbol_index();
+    for symbol in index.all_symbols() {
+        let Some(path) = analysis.get_file_path(symbol.file) else {
+            continue;
+        };
+        let removed = resolution.is_excluded(&symbol.qualified_name);
+        resolution
+            .declarations
+            .entry(path.to_string())
+            .or_default()
+            .push((symbol.start_line + 1, symbol.start_col + 1, removed));
+        if removed || path.contains("sysml.library") {
+            continue;
+        }
+        for target in super::closure::dependencies(index, symbol) {
+            if resolution.is_excluded(&target) {
+                resolution.issues.push(DiagnosticInfo {
+                    file: path.to_string(),
+                    line: symbol.start_line + 1,
+                    col: symbol.start_col + 1,
+                    end_line: symbol.end_line + 1,
+                    end_col: symbol.end_col + 1,
+                    message: format!(
+                        "{} references {}, which is not part of the selected configuration",
+                        symbol.qualified_name, target
+                    ),
+                    severity: Severity::Error,
+                    code: None,
+                    blame: None,
+                    owners: Vec::new(),
+                    related: Vec::new(),
+                });
+            }
+        }
+    }
+    for declarations in resolution.declarations.values_mut() {
+        declarations.sort();
+    }
+
+    resolution
+}
+
+/// Layout of a variant selection matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixFormat {
+    /// TOML configuration with an empty selection per variation point.
+    Text,
+    /// One row per variant with an empty `selected` column.
+    Csv,
+    /// One object per variation point with `selected: null`.
+    Json,
+}
+
+/// Render a selection matrix to be filled in and passed to `--config`.
+pub fn render_variant_matrix(
+    points: &[VariationPoint],
+    format: MatrixFormat,
+) -> Result<String, String> {
+    match format {
+        MatrixFormat::Text => {
+            let mut text = String::from(
+                "# Pick one variant per variation point and pass this file with --config\n\
+                 [variants]\n",
+            );
+            for point in points {
+                text.push_str(&format!(
+                    "\n# {} at {}:{}, one of: {}\n\"{}\" = \"\"\n",
+                    point.kind,
+                    point.file,
+                    point.line,
+                    point.variants.join(", "),
+                    point.qualified_name
+                ));
+            }
+            Ok(text)
+        }
+        MatrixFormat::Csv => {
+            let mut csv = String::from("variation_point,kind,variant,selected\n");
+            for point in points {
+                for variant in &point.variants {
+                    csv.push_str(&format!(
+                        "{},{},{},\n",
+                        point.qualified_name, point.kind, variant
+                    ));
+                }
+            }
+            Ok(csv)
+        }
+        MatrixFormat::Json => {
+            let entries: Vec<MatrixEntry> = points
+                .iter()
+                .map(|p| MatrixEntry {
+                    variation_point: p.qualified_name.clone(),
+                    kind: p.kind.clone(),
+                    file: p.file.clone(),
+                    line: p.line,
+                    variants: p.variants.clone(),
+                    selected: None,
+                })
+                .collect();
+            serde_json::to_string_pretty(&entries)
+                .map_err(|e| format!("Failed to serialize variant matrix: {}", e))
+        }
+    }
+}
+
+/// Analyze `input` and list its variation points.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `options` - Verbosity and standard library to load
+pub fn list_variation_points(
+    input: &Path,
+    options: &AnalysisOptions,
+) -> Result<Vec<VariationPoint>, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    super::load_input(&mut host, input, options.verbose)?;
+    Ok(variation_points(&host.analysis()))
+}
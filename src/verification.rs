@@ -0,0 +1,318 @@
+//! Verification plan of the model's verification cases.
+//!
+//! Every `verification def` and `verification` usage is listed with the
+//! requirements it verifies (`verify` in its objective) and its subjects,
+//! so the plan can be imported into a test management system. A usage
+//! without requirements or subjects of its own takes those of the
+//! verification definition typing it. Cases missing either are marked
+//! incomplete; the others have not been run yet. Rendered with the code
+//! generation templates in `src/templates/`.
+
+use super::codegen::{DocumentFormat, render_template};
+use super::{AnalysisOptions, single_line};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+use syster::FileId;
+use syster::hir::{HirSymbol, RelationshipKind, SymbolIndex};
+use syster::ide::AnalysisHost;
+
+/// Verification plan data, as passed to the templates.
+#[derive(Debug, Serialize)]
+pub struct VerificationPlan {
+    /// Verification cases, in qualified-name order.
+    pub cases: Vec<VerificationCase>,
+}
+
+/// A verification definition or usage.
+#[derive(Debug, Serialize)]
+pub struct VerificationCase {
+    /// Simple name.
+    pub name: String,
+    /// Fully qualified name.
+    pub qualified_name: String,
+    /// Whether the case is a `verification def`.
+    pub is_definition: bool,
+    /// Verification definition typing a usage, empty if none.
+    pub definition: String,
+    /// Documentation on a single line, empty if none.
+    pub doc: String,
+    /// Requirements under verification, in declaration order.
+    pub requirements: Vec<VerifiedRequirement>,
+    /// Subjects of the case, in declaration order.
+    pub subjects: Vec<VerificationSubject>,
+    /// Whether the case is ready to run.
+    pub status: VerificationStatus,
+    /// File declaring the case.
+    pub file: String,
+    /// Declaration line (1-indexed).
+    pub line: u32,
+}
+
+/// A requirement verified by a case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerifiedRequirement {
+    /// Short name (`R1` for `<'R1'>`), empty if none.
+    pub id: String,
+    /// Qualified name, or the reference as written if unresolved.
+    pub qualified_name: String,
+    /// Documentation on a single line, empty if none.
+    pub text: String,
+}
+
+/// The subject of a case.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerificationSubject {
+    /// Subject name.
+    pub name: String,
+    /// Type or subsetted feature (qualified if resolved), empty if none.
+    pub type_name: String,
+}
+
+/// Status of a verification case in the plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum VerificationStatus {
+    /// Verifies at least one requirement on a subject.
+    #[serde(rename = "Not run")]
+    NotRun,
+    /// Verifies no requirement or has no subject.
+    #[serde(rename = "Incomplete")]
+    Incomplete,
+}
+
+impl fmt::Display for VerificationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotRun => "Not run",
+            Self::Incomplete => "Incomplete",
+        })
+    }
+}
+
+impl VerificationPlan {
+    /// Cases that verify no requirement or have no subject.
+    pub fn incomplete(&self) -> impl Iterator<Item = &VerificationCase> {
+        self.cases
+            .iter()
+            .filter(|c| c.status == VerificationStatus::Incomplete)
+    }
+}
+
+/// Analyze `input` and collect its verification cases.
+///
+/// # Arguments
+/// * `input` - Source file or directory
+/// * `options` - Verbosity and standard library to load
+pub fn verification_plan(
+    input: &Path,
+    options: &AnalysisOptions,
+) -> Result<VerificationPlan, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    let library: HashSet<String> = host
+        .files()
+        .keys()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    super::load_input(&mut host, input, options.verbose)?;
+    let _analysis = host.analysis();
+    let index = host.symbol_index();
+
+    let mut sources = HashMap::new();
+    for path in host.files().keys() {
+        if let Some(id) = host.get_file_id_for_path(path) {
+            sources.insert(id, host.files()[path].source_text());
+        }
+    }
+    let keywords = |symbol: &HirSymbol| declaration_keywords(&sources, symbol);
+
+    let symbols: Vec<&HirSymbol> = index
+        .all_symbols()
+        .filter(|s| {
+            host.get_file_path(s.file)
+                .is_some_and(|path| !library.contains(path))
+        })
+        .collect();
+
+    let mut cases: BTreeMap<String, VerificationCase> = symbols
+        .iter()
+        .filter(|s| keywords(s).contains(&"verification"))
+        .map(|symbol| {
+            let is_definition = keywords(symbol).contains(&"def");
+            VerificationCase {
+                name: symbol.name.to_string(),
+                qualified_name: symbol.qualified_name.to_string(),
+                is_definition,
+                definition: if is_definition {
+                    String::new()
+                } else {
+                    targets(index, symbol, &[RelationshipKind::TypedBy])
+                        .into_iter()
+                        .next()
+                        .unwrap_or_default()
+                },
+                doc: single_line(symbol.doc.as_deref()),
+                requirements: Vec::new(),
+                subjects: Vec::new(),
+                status: VerificationStatus::Incomplete,
+                file: host
+                    .get_file_path(symbol.file)
+                    .unwrap_or_default()
+                    .to_string(),
+                line: symbol.start_line + 1,
+            }
+        })
+        .collect();
+
+    // Requirements and subjects belong to the innermost enclosing case
+    let mut ordered = symbols.clone();
+    ordered.sort_by_key(|s| (host.get_file_path(s.file), s.start_line, s.start_col));
+    for symbol in ordered {
+        let Some(case) = innermost_case(&cases, &symbol.qualified_name) else {
+            continue;
+        };
+        let case = cases.get_mut(&case).expect("case exists");
+
+        for target in targets(index, symbol, &[RelationshipKind::Verifies]) {
+            let requirement = index.lookup_qualified(&target);
+            let requirement = VerifiedRequirement {
+                id: requirement
+                    .and_then(|r| r.short_name.as_deref())
+                    .unwrap_or_default()
+                    .trim_matches('\'')
+                    .to_string(),
+                text: single_line(requirement.and_then(|r| r.doc.as_deref())),
+                qualified_name: target,
+            };
+            if !case.requirements.contains(&requirement) {
+                case.requirements.push(requirement);
+            }
+        }
+
+        let is_subject = symbol.qualified_name.rsplit_once("::").map(|(o, _)| o)
+            == Some(case.qualified_name.as_str())
+            && keywords(symbol).contains(&"subject");
+        if is_subject {
+            case.subjects.push(VerificationSubject {
+                name: symbol.name.to_string(),
+                type_name: targets(
+                    index,
+                    symbol,
+                    &[
+                        RelationshipKind::TypedBy,
+                        RelationshipKind::Subsets,
+                        RelationshipKind::Redefines,
+                    ],
+                )
+                .into_iter()
+                .next()
+                .unwrap_or_default(),
+            });
+        }
+    }
+
+    // Usages inherit from the verification definition typing them
+    let inherited: Vec<(String, Vec<VerifiedRequirement>, Vec<VerificationSubject>)> = cases
+        .values()
+        .filter_map(|case| {
+            let definition = cases.get(&case.definition)?;
+            Some((
+                case.qualified_name.clone(),
+                definition.requirements.clone(),
+                definition.subjects.clone(),
+            ))
+        })
+        .collect();
+    for (name, requirements, subjects) in inherited {
+        let case = cases.get_mut(&name).expect("case exists");
+        if case.requirements.is_empty() {
+            case.requirements = requirements;
+        }
+        if case.subjects.is_empty() {
+            case.subjects = subjects;
+        }
+    }
+
+    for case in cases.values_mut() {
+        if !case.requirements.is_empty() && !case.subjects.is_empty() {
+            case.status = VerificationStatus::NotRun;
+        }
+    }
+    if options.verbose {
+        tracing::info!("Verification plan: {} cases", cases.len());
+    }
+    Ok(VerificationPlan {
+        cases: cases.into_values().collect(),
+    })
+}
+
+/// Render the verification plan in `format`.
+pub fn render_verification_plan(
+    plan: &VerificationPlan,
+    format: DocumentFormat,
+) -> Result<String, String> {
+    match format {
+        DocumentFormat::Markdown => render_template(
+            plan,
+            "verification.md",
+            include_str!("templates/verification.md.tera"),
+        ),
+        DocumentFormat::Html => render_template(
+            plan,
+            "verification.html",
+            include_str!("templates/verification.html.tera"),
+        ),
+        DocumentFormat::Csv => render_template(
+            plan,
+            "verification.csv",
+            include_str!("templates/verification.csv.tera"),
+        ),
+    }
+}
+
+/// The innermost case `name` is nested in.
+fn innermost_case(cases: &BTreeMap<String, VerificationCase>, name: &str) -> Option<String> {
+    let mut current = name;
+    while let Some((owner, _)) = current.rsplit_once("::") {
+        if cases.contains_key(owner) {
+            return Some(owner.to_string());
+        }
+        current = owner;
+    }
+    None
+}
+
+/// Qualified names of the targets of the `kinds` relationships of
+/// `symbol`, as written where unresolved.
+fn targets(index: &SymbolIndex, symbol: &HirSymbol, kinds: &[RelationshipKind]) -> Vec<String> {
+    let resolver = index.resolver_for_scope(&symbol.qualified_name);
+    symbol
+        .relationships
+        .iter()
+        .filter(|rel| kinds.contains(&rel.kind))
+        .map(|rel| match &rel.resolved_target {
+            Some(target) => target.to_string(),
+            None => resolver
+                .resolve(&rel.target)
+                .symbol()
+                .map_or_else(|| rel.target.to_string(), |s| s.qualified_name.to_string()),
+        })
+        .collect()
+}
+
+/// The words before the name of `symbol` on its declaration line, e.g.
+/// `["verification", "def"]`.
+fn declaration_keywords<'a>(
+    sources: &'a HashMap<FileId, String>,
+    symbol: &HirSymbol,
+) -> Vec<&'a str> {
+    sources
+        .get(&symbol.file)
+        .and_then(|text| text.lines().nth(symbol.start_line as usize))
+        .and_then(|line| line.get(..symbol.start_col as usize))
+        .map(|head| head.split_whitespace().collect())
+        .unwrap_or_default()
+}
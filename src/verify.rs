@@ -0,0 +1,184 @@
+//! Consistency check between textual sources and an interchange file.
+//!
+//! Both sides are reduced to a name-based form (elements by qualified name
+//! and kind, relationships by kind and endpoint names) so that differences
+//! in element IDs or in how a tool wraps members do not count as divergence.
+
+use super::AnalysisOptions;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use syster::ide::AnalysisHost;
+use syster::interchange::{ElementId, ElementKind, Model, detect_format, model_from_symbols};
+
+/// Differences between the model from sources and an interchange model.
+#[derive(Debug, Default, Serialize)]
+pub struct ModelDiff {
+    /// Elements and relationships present only in the sources.
+    pub only_in_source: Vec<String>,
+    /// Elements and relationships present only in the interchange file.
+    pub only_in_interchange: Vec<String>,
+    /// Elements present in both but with a different kind.
+    pub kind_mismatches: Vec<String>,
+}
+
+impl ModelDiff {
+    /// Whether the two models match.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_source.is_empty()
+            && self.only_in_interchange.is_empty()
+            && self.kind_mismatches.is_empty()
+    }
+}
+
+/// Analyze `src`, read `interchange`, and diff the two models.
+///
+/// # Arguments
+/// * `src` - Source file or directory
+/// * `interchange` - Interchange file to check (format detected from extension)
+/// * `options` - Verbosity and standard library to load
+pub fn verify_against(
+    src: &Path,
+    interchange: &Path,
+    options: &AnalysisOptions,
+) -> Result<ModelDiff, String> {
+    let mut host = AnalysisHost::new();
+    if options.load_stdlib {
+        super::load_stdlib_files(&mut host, options.stdlib_path.as_deref(), options.verbose)?;
+    }
+    super::load_input(&mut host, src, options.verbose)?;
+
+    let analysis = host.analysis();
+    let symbols: Vec<_> = analysis
+        .symbol_index()
+        .all_symbols()
+        .filter(|sym| {
+            analysis
+                .get_file_path(sym.file)
+                .is_none_or(|path| !path.contains("sysml.library"))
+        })
+        .cloned()
+        .collect();
+    let source_model = model_from_symbols(&symbols);
+
+    let format = detect_format(interchange)
+        .ok_or_else(|| format!("Unknown interchange format: {}", interchange.display()))?;
+    let bytes = std::fs::read(interchange)
+        .map_err(|e| format!("Failed to read {}: {}", interchange.display(), e))?;
+    // .json files may hold standard JSON rather than JSON-LD
+    let delivered = if format.name() == "JSON-LD" && super::sysml_json::is_standard_json(&bytes) {
+        super::sysml_json::read(&bytes)?
+    } else {
+        format.read(&bytes).map_err(|e| e.to_string())?
+    };
+
+    if options.verbose {
+        tracing::info!(
+            "Comparing {} source elements with {} interchange elements",
+            source_model.elements.len(),
+            delivered.elements.len()
+        );
+    }
+
+    Ok(diff_models(&source_model, &delivered))
+}
+
+/// Diff two models by qualified name.
+pub fn diff_models(source: &Model, interchange: &Model) -> ModelDiff {
+    let source_elements = element_signatures(source);
+    let interchange_elements = element_signatures(interchange);
+
+    let mut diff = ModelDiff::default();
+    for (name, kind) in &source_elements {
+        match interchange_elements.get(name) {
+            None => diff.only_in_source.push(format!("{:?} {}", kind, name)),
+            Some(other) if kinds_differ(*kind, *other) => diff.kind_mismatches.push(format!(
+                "{}: {:?} in sources, {:?} in interchange",
+                name, kind, other
+            )),
+            Some(_) => {}
+        }
+    }
+    for (name, kind) in &interchange_elements {
+        if !source_elements.contains_key(name) {
+            diff.only_in_interchange
+                .push(format!("{:?} {}", kind, name));
+        }
+    }
+
+    let source_rels = relationship_signatures(source, &source_elements);
+    let interchange_rels = relationship_signatures(interchange, &interchange_elements);
+    diff.only_in_source
+        .extend(source_rels.difference(&interchange_rels).cloned());
+    diff.only_in_interchange
+        .extend(interchange_rels.difference(&source_rels).cloned());
+
+    diff
+}
+
+/// Named, comparable elements keyed by qualified name.
+fn element_signatures(model: &Model) -> BTreeMap<String, ElementKind> {
+    model
+        .elements
+        .values()
+        .filter(|e| e.name.is_some() && is_compared(e.kind))
+        .map(|e| (qualified_name(model, &e.id), e.kind))
+        .collect()
+}
+
+/// Relationships whose source is a compared element, as `Kind source -> target`.
+fn relationship_signatures(
+    model: &Model,
+    elements: &BTreeMap<String, ElementKind>,
+) -> BTreeSet<String> {
+    model
+        .relationships
+        .iter()
+        .filter_map(|rel| {
+            let source = qualified_name(model, &rel.source);
+            elements.contains_key(&source).then(|| {
+                format!(
+                    "{:?} {} -> {}",
+                    rel.kind,
+                    source,
+                    qualified_name(model, &rel.target)
+                )
+            })
+        })
+        .collect()
+}
+
+/// Qualified name built from the names of the element and its named owners.
+///
+/// Unknown IDs (e.g. references to library elements) are returned as-is.
+pub(crate) fn qualified_name(model: &Model, id: &ElementId) -> String {
+    let Some(element) = model.get(id) else {
+        return id.as_str().to_string();
+    };
+
+    let mut segments = Vec::new();
+    let mut current = Some(element);
+    while let Some(e) = current {
+        match &e.name {
+            Some(name) if !e.kind.is_relationship() => segments.push(name.to_string()),
+            _ => {}
+        }
+        current = e.owner.as_ref().and_then(|owner| model.get(owner));
+    }
+    segments.reverse();
+    segments.join("::")
+}
+
+/// Elements that take part in the comparison.
+pub(crate) fn is_compared(kind: ElementKind) -> bool {
+    !kind.is_relationship()
+        && !matches!(
+            kind,
+            ElementKind::Import | ElementKind::Comment | ElementKind::Namespace
+        )
+}
+
+/// Kinds differ unless one side could not classify the element.
+pub(crate) fn kinds_differ(a: ElementKind, b: ElementKind) -> bool {
+    a != b && a != ElementKind::Other && b != ElementKind::Other
+}
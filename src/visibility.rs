@@ -0,0 +1,130 @@
+//! Private-member stripping for supplier-facing exports.
+//!
+//! Members declared `private` are removed together with everything nested
+//! inside them. What remains must not reference anything that was removed;
+//! each such reference is reported as a leak and the export fails.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use syster::hir::HirSymbol;
+use syster::ide::AnalysisHost;
+use syster::parser::{Lexer, SyntaxKind};
+
+/// Remove private members from `symbols`, declared in the files of `host`.
+///
+/// Fails, listing every offending reference, if a remaining element
+/// references a removed one.
+pub fn strip_private(
+    host: &mut AnalysisHost,
+    symbols: Vec<HirSymbol>,
+) -> Result<Vec<HirSymbol>, String> {
+    let mut tokens = HashMap::new();
+    let private: Vec<String> = symbols
+        .iter()
+        .filter(|sym| {
+            let Some(path) = host.get_file_path(sym.file) else {
+                return false;
+            };
+            let tokens = tokens.entry(path.to_string()).or_insert_with(|| {
+                host.files()
+                    .get(Path::new(path))
+                    .map(|file| Tokens::new(&file.source_text()))
+                    .unwrap_or_default()
+            });
+            tokens.is_declared_private(sym.start_line, sym.start_col)
+        })
+        .map(|sym| sym.qualified_name.to_string())
+        .collect();
+
+    let is_stripped = |name: &str| {
+        private.iter().any(|p| {
+            name == p
+                || name
+                    .strip_prefix(p.as_str())
+                    .is_some_and(|rest| rest.starts_with("::"))
+        })
+    };
+
+    let (stripped, kept): (Vec<_>, Vec<_>) = symbols
+        .into_iter()
+        .partition(|sym| is_stripped(&sym.qualified_name));
+    let stripped: BTreeSet<String> = stripped
+        .iter()
+        .map(|sym| sym.qualified_name.to_string())
+        .collect();
+
+    let analysis = host.analysis();
+    let mut leaks = Vec::new();
+    for symbol in &kept {
+        for target in super::closure::dependencies(analysis.symbol_index(), symbol) {
+            if stripped.contains(&target) {
+                leaks.push(format!(
+                    "{} references private element {}",
+                    symbol.qualified_name, target
+                ));
+            }
+        }
+    }
+
+    if leaks.is_empty() {
+        Ok(kept)
+    } else {
+        Err(format!(
+            "{} reference(s) to private elements:\n  {}",
+            leaks.len(),
+            leaks.join("\n  ")
+        ))
+    }
+}
+
+/// The tokens of a file other than whitespace and comments.
+#[derive(Default)]
+struct Tokens {
+    text: String,
+    /// Byte offset and kind of each token.
+    tokens: Vec<(usize, SyntaxKind)>,
+}
+
+impl Tokens {
+    fn new(text: &str) -> Self {
+        let tokens = Lexer::new(text)
+            .filter(|token| !token.kind.is_trivia())
+            .map(|token| (u32::from(token.offset) as usize, token.kind))
+            .collect();
+        Self {
+            text: text.to_string(),
+            tokens,
+        }
+    }
+
+    /// Whether the declaration at `line`/`col` (0-indexed) carries `private`.
+    ///
+    /// Symbol positions point at the declared name (or at the start of the
+    /// declaration), so the keyword is looked for as the first token of the
+    /// declaration, i.e. after the last `;`, `{` or `}` before the position.
+    /// Being a token, a `private` in a comment or string doesn't count.
+    fn is_declared_private(&self, line: u32, col: u32) -> bool {
+        let mut lines = self.text.split_inclusive('\n');
+        let start: usize = lines.by_ref().take(line as usize).map(str::len).sum();
+        let Some(line_text) = lines.next() else {
+            return false;
+        };
+        let offset = start
+            + line_text
+                .char_indices()
+                .nth(col as usize)
+                .map_or(line_text.len(), |(i, _)| i);
+
+        let at = self.tokens.partition_point(|(start, _)| *start < offset);
+        let declaration = self.tokens[..at]
+            .iter()
+            .rposition(|(_, kind)| {
+                matches!(
+                    kind,
+                    SyntaxKind::SEMICOLON | SyntaxKind::L_BRACE | SyntaxKind::R_BRACE
+                )
+            })
+            .map_or(0, |separator| separator + 1);
+        self.tokens.get(declaration).map(|(_, kind)| *kind) == Some(SyntaxKind::PRIVATE_KW)
+    }
+}
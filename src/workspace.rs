@@ -0,0 +1,230 @@
+//! Saved workspaces (`.syster`).
+//!
+//! `syster workspace save` analyzes a model once and stores every loaded
+//! file — the model, its dependencies and the standard library — together
+//! with the symbol index and the analysis result. `syster workspace load`
+//! reopens it without walking directories, locating the standard library,
+//! parsing, indexing or re-running the checks: the diagnostics and symbols
+//! are read back as they were saved.
+//!
+//! syster-base has no serialized form of its own index, so the index is
+//! saved as [`SavedSymbol`]s: every symbol's name, kind, location,
+//! documentation and relationships, which is what browsing needs. An
+//! [`AnalysisHost`] can still be rebuilt from the saved sources with
+//! [`Workspace::to_host`], e.g. to re-run the analysis after an edit.
+//!
+//! # Format
+//!
+//! All integers are little-endian `u32`:
+//!
+//! ```text
+//! "SYWS" version
+//! file-count (path-length path-utf8 content-length content-utf8)*
+//! symbols-length symbols-json
+//! result-length result-json
+//! ```
+//!
+//! The symbols are stored as a JSON array of [`SavedSymbol`]s, the result
+//! as the JSON written by `--json`.
+
+use super::sidx::{Reader, put_len, put_u32};
+use super::{AnalysisOptions, AnalysisResult};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use syster::ide::AnalysisHost;
+
+/// File signature.
+const MAGIC: &[u8; 4] = b"SYWS";
+
+/// Format version, bumped on incompatible changes.
+const VERSION: u32 = 2;
+
+/// An analyzed model with the sources it was analyzed from.
+#[derive(Debug)]
+pub struct Workspace {
+    /// `(path, content)` of every loaded file, sorted by path.
+    pub files: Vec<(String, String)>,
+    /// The symbol index of the files, as [`index_symbols`] lists it.
+    pub symbols: Vec<SavedSymbol>,
+    /// The analysis of the files.
+    pub result: AnalysisResult,
+}
+
+/// A symbol of a saved index.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSymbol {
+    /// Simple name.
+    pub name: String,
+    /// Fully qualified name.
+    pub qualified_name: String,
+    /// Display kind, e.g. "Part def".
+    pub kind: String,
+    /// File the symbol is declared in.
+    pub file: String,
+    /// Declaration line (1-indexed).
+    pub line: u32,
+    /// Declaration column (1-indexed).
+    pub col: u32,
+    /// Documentation comment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    /// Typings, specializations and other relationships, in source order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub relationships: Vec<SavedRelationship>,
+}
+
+/// A relationship of a [`SavedSymbol`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedRelationship {
+    /// Display kind, e.g. "Typed by".
+    pub kind: String,
+    /// Qualified name of the target where the index resolved it, otherwise
+    /// the name as written.
+    pub target: String,
+}
+
+/// Every symbol indexed in `host`, ordered as a package tree: by qualified
+/// name, segment by segment.
+pub fn index_symbols(host: &mut AnalysisHost) -> Vec<SavedSymbol> {
+    let analysis = host.analysis();
+    let mut symbols: Vec<SavedSymbol> = analysis
+        .symbol_index()
+        .all_symbols()
+        .map(|sym| SavedSymbol {
+            name: sym.name.to_string(),
+            qualified_name: sym.qualified_name.to_string(),
+            kind: sym.kind.display().to_string(),
+            file: analysis
+                .get_file_path(sym.file)
+                .unwrap_or_default()
+                .to_string(),
+            line: sym.start_line + 1,
+            col: sym.start_col + 1,
+            doc: sym.doc.as_ref().map(|d| d.to_string()),
+            relationships: sym
+                .relationships
+                .iter()
+                .map(|rel| SavedRelationship {
+                    kind: rel.kind.display().to_string(),
+                    target: rel
+                        .resolved_target
+                        .as_ref()
+                        .unwrap_or(&rel.target)
+                        .to_string(),
+                })
+                .collect(),
+        })
+        .collect();
+    symbols.sort_by(|a, b| tree_order(&a.qualified_name, &b.qualified_name));
+    symbols
+}
+
+/// Order of qualified names in a package tree.
+pub(crate) fn tree_order(a: &str, b: &str) -> std::cmp::Ordering {
+    a.split("::").cmp(b.split("::"))
+}
+
+impl Workspace {
+    /// Analyze `input` as `options` say and keep the loaded files.
+    pub fn analyze(input: &Path, options: &AnalysisOptions) -> Result<Self, String> {
+        let mut host = AnalysisHost::new();
+        let result = super::analyze_into(&mut host, input, options)?;
+        let mut files: Vec<(String, String)> = host
+            .files()
+            .iter()
+            .map(|(path, file)| (path.to_string_lossy().to_string(), file.source_text()))
+            .collect();
+        files.sort();
+        let symbols = index_symbols(&mut host);
+        Ok(Self {
+            files,
+            symbols,
+            result,
+        })
+    }
+
+    /// Read a workspace written by [`Workspace::write`].
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_bytes(&bytes).map_err(|e| format!("Invalid workspace {}: {}", path.display(), e))
+    }
+
+    /// Write the workspace to `path`.
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_bytes()?)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Encode the workspace in the binary `.syster` format.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let symbols = serde_json::to_vec(&self.symbols)
+            .map_err(|e| format!("Failed to serialize symbols: {}", e))?;
+        let result = serde_json::to_vec(&self.result)
+            .map_err(|e| format!("Failed to serialize result: {}", e))?;
+
+        let mut bytes = MAGIC.to_vec();
+        put_u32(&mut bytes, VERSION);
+        put_len(&mut bytes, self.files.len())?;
+        for (path, content) in &self.files {
+            for s in [path, content] {
+                put_len(&mut bytes, s.len())?;
+                bytes.extend_from_slice(s.as_bytes());
+            }
+        }
+        for json in [symbols, result] {
+            put_len(&mut bytes, json.len())?;
+            bytes.extend(json);
+        }
+        Ok(bytes)
+    }
+
+    /// Decode a workspace in the binary `.syster` format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err("not a saved workspace".to_string());
+        }
+        let version = reader.u32()?;
+        if version != VERSION {
+            return Err(format!(
+                "unsupported version {} (expected {})",
+                version, VERSION
+            ));
+        }
+
+        let mut files = Vec::new();
+        for _ in 0..reader.u32()? {
+            files.push((read_string(&mut reader)?, read_string(&mut reader)?));
+        }
+        let symbols = serde_json::from_str(&read_string(&mut reader)?)
+            .map_err(|e| format!("invalid symbols: {}", e))?;
+        let result = serde_json::from_str(&read_string(&mut reader)?)
+            .map_err(|e| format!("invalid result: {}", e))?;
+        if reader.pos != bytes.len() {
+            return Err("trailing data".to_string());
+        }
+
+        Ok(Self {
+            files,
+            symbols,
+            result,
+        })
+    }
+
+    /// An analysis host with the workspace's files parsed again, for
+    /// re-running the analysis; its index is built on first use.
+    pub fn to_host(&self) -> AnalysisHost {
+        let mut host = AnalysisHost::new();
+        for (path, content) in &self.files {
+            host.set_file_content(path, content);
+        }
+        host
+    }
+}
+
+/// A length-prefixed UTF-8 string.
+fn read_string(reader: &mut Reader) -> Result<String, String> {
+    let len = reader.u32()? as usize;
+    String::from_utf8(reader.take(len)?.to_vec()).map_err(|_| "invalid UTF-8".to_string())
+}
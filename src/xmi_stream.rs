@@ -0,0 +1,276 @@
+//! Streaming XMI writer for large exports.
+//!
+//! [`Xmi::write`](syster::interchange::Xmi) serializes the whole document
+//! into one byte vector before anything reaches the output, which for a
+//! model of a million elements needs gigabytes on top of the model itself.
+//! This writer produces the same document, element by element, straight
+//! into any [`Write`] sink, so only the model has to fit in memory.
+
+use super::provenance::{Provenance, xmi_comment};
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
+use std::io::Write;
+use syster::interchange::model::PropertyValue;
+use syster::interchange::{Element, ElementKind, Model};
+
+/// Namespace URIs, as `Xmi.write` declares them.
+mod namespace {
+    pub const XMI: &str = "http://www.omg.org/XMI";
+    pub const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+    pub const KERML: &str = "https://www.omg.org/spec/KerML/20250201";
+    pub const SYSML: &str = "https://www.omg.org/spec/SysML/20250201";
+}
+
+/// Boolean properties written as attributes, in this order, before any
+/// other property.
+const FLAGS: &[&str] = &[
+    "isAbstract",
+    "isVariation",
+    "isDerived",
+    "isReadOnly",
+    "isParallel",
+    "isUnique",
+    "isOrdered",
+    "isComposite",
+    "isStandard",
+];
+
+/// Write `model` as XMI to `out`, with the provenance comment after the XML
+/// declaration if one is given.
+///
+/// The output is byte for byte what `Xmi.write` followed by
+/// [`embed_provenance`](super::embed_provenance) produces. `out` should be
+/// buffered.
+pub fn write_xmi<W: Write>(
+    model: &Model,
+    provenance: Option<&Provenance>,
+    out: W,
+) -> Result<(), String> {
+    let mut stream = XmiStream {
+        writer: Writer::new_with_indent(out, b' ', 2),
+        model,
+    };
+    stream.event(Event::Decl(BytesDecl::new("1.0", Some("ASCII"), None)))?;
+    if let Some(provenance) = provenance {
+        stream.raw(format!("\n{}", xmi_comment(provenance)).as_bytes())?;
+    }
+
+    let roots: Vec<&Element> = model.iter_roots().collect();
+    match roots.as_slice() {
+        [] => return Err("XML error: Model has no root elements".to_string()),
+        [root] => stream.root_element(root)?,
+        roots => stream.xmi_wrapper(roots)?,
+    }
+
+    stream.raw(b"\n")?;
+    stream
+        .writer
+        .get_mut()
+        .flush()
+        .map_err(|e| format!("Failed to write XMI: {}", e))
+}
+
+struct XmiStream<'a, W: Write> {
+    writer: Writer<W>,
+    model: &'a Model,
+}
+
+impl<W: Write> XmiStream<'_, W> {
+    fn event(&mut self, event: Event<'_>) -> Result<(), String> {
+        self.writer
+            .write_event(event)
+            .map_err(|e| format!("Failed to write XMI: {}", e))
+    }
+
+    fn raw(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.writer
+            .get_mut()
+            .write_all(bytes)
+            .map_err(|e| format!("Failed to write XMI: {}", e))
+    }
+
+    /// A single root as the document element.
+    fn root_element(&mut self, element: &Element) -> Result<(), String> {
+        let type_name = xmi_type(element);
+        let mut start = BytesStart::new(type_name.as_str());
+        start.push_attribute(("xmi:version", "2.0"));
+        start.push_attribute(("xmlns:xmi", namespace::XMI));
+        start.push_attribute(("xmlns:xsi", namespace::XSI));
+        namespace_attrs(&mut start, self.model);
+        element_attrs(&mut start, element);
+        self.element(start, &type_name, element, true)
+    }
+
+    /// Several roots inside an `xmi:XMI` document element.
+    fn xmi_wrapper(&mut self, roots: &[&Element]) -> Result<(), String> {
+        let mut start = BytesStart::new("xmi:XMI");
+        start.push_attribute(("xmi:version", "2.0"));
+        start.push_attribute(("xmlns:xmi", namespace::XMI));
+        start.push_attribute(("xmlns:xsi", namespace::XSI));
+        namespace_attrs(&mut start, self.model);
+        self.event(Event::Start(start))?;
+
+        for root in roots {
+            let type_name = xmi_type(root);
+            let mut start = BytesStart::new(type_name.as_str());
+            element_attrs(&mut start, root);
+            self.element(start, &type_name, root, false)?;
+        }
+
+        self.event(Event::End(BytesEnd::new("xmi:XMI")))
+    }
+
+    /// A child: relationships directly as `ownedRelationship`, anything
+    /// else as `ownedRelatedElement`.
+    fn owned(&mut self, element: &Element) -> Result<(), String> {
+        let tag = if element.kind.is_relationship() {
+            "ownedRelationship"
+        } else {
+            "ownedRelatedElement"
+        };
+        let type_name = xmi_type(element);
+        let mut start = BytesStart::new(tag);
+        start.push_attribute(("xsi:type", type_name.as_str()));
+        element_attrs(&mut start, element);
+        self.element(start, tag, element, true)
+    }
+
+    /// Write `start`, then the href child (if `with_href`) and owned
+    /// elements, then the end tag; an empty tag if there are none.
+    fn element(
+        &mut self,
+        start: BytesStart<'_>,
+        tag: &str,
+        element: &Element,
+        with_href: bool,
+    ) -> Result<(), String> {
+        let has_href = with_href && element.properties.get("href").is_some();
+        if !has_href && element.owned_elements.is_empty() {
+            return self.event(Event::Empty(start));
+        }
+
+        self.event(Event::Start(start))?;
+        if has_href {
+            self.href_child(element)?;
+        }
+        for child_id in &element.owned_elements {
+            if let Some(child) = self.model.get(child_id) {
+                self.owned(child)?;
+            }
+        }
+        self.event(Event::End(BytesEnd::new(tag)))
+    }
+
+    fn href_child(&mut self, element: &Element) -> Result<(), String> {
+        let Some(PropertyValue::String(href)) = element.properties.get("href") else {
+            return Ok(());
+        };
+        let tag = match element.properties.get("_href_tag") {
+            Some(PropertyValue::String(tag)) => tag.to_string(),
+            _ => href_tag(element.kind).to_string(),
+        };
+        let mut start = BytesStart::new(tag.as_str());
+        if let Some(PropertyValue::String(xsi_type)) = element.properties.get("_href_xsi_type") {
+            start.push_attribute(("xsi:type", xsi_type.as_ref()));
+        }
+        start.push_attribute(("href", href.as_ref()));
+        self.event(Event::Empty(start))
+    }
+}
+
+/// The element's XMI type, preferring the one it was read with.
+fn xmi_type(element: &Element) -> String {
+    match element.properties.get("_xsi_type") {
+        Some(PropertyValue::String(original)) => original.to_string(),
+        _ => element.kind.xmi_type().to_string(),
+    }
+}
+
+/// Namespaces as declared in the file the model was read from, or both
+/// KerML and SysML.
+fn namespace_attrs(start: &mut BytesStart<'_>, model: &Model) {
+    let declared = &model.metadata.declared_namespaces;
+    if declared.is_empty() {
+        start.push_attribute(("xmlns:kerml", namespace::KERML));
+        start.push_attribute(("xmlns:sysml", namespace::SYSML));
+        return;
+    }
+    if let Some(uri) = declared.get("kerml") {
+        start.push_attribute(("xmlns:kerml", uri.as_str()));
+    }
+    if let Some(uri) = declared.get("sysml") {
+        start.push_attribute(("xmlns:sysml", uri.as_str()));
+    }
+}
+
+fn element_attrs(start: &mut BytesStart<'_>, element: &Element) {
+    start.push_attribute(("xmi:id", element.id.as_str()));
+    start.push_attribute(("elementId", element.id.as_str()));
+
+    // SysML elements carry declaredName, KerML ones name
+    let sysml_naming = match element.properties.get("_xsi_type") {
+        Some(PropertyValue::String(xsi_type)) => xsi_type.starts_with("sysml:"),
+        _ => element.kind.is_sysml(),
+    };
+    if let Some(name) = &element.name {
+        let key = if sysml_naming { "declaredName" } else { "name" };
+        start.push_attribute((key, name.as_ref()));
+    }
+    if let Some(short_name) = &element.short_name {
+        let key = if sysml_naming {
+            "declaredShortName"
+        } else {
+            "shortName"
+        };
+        start.push_attribute((key, short_name.as_ref()));
+    }
+    if let Some(qualified_name) = &element.qualified_name {
+        start.push_attribute(("qualifiedName", qualified_name.as_ref()));
+    }
+
+    for flag in FLAGS {
+        if let Some(PropertyValue::Boolean(value)) = element.properties.get(*flag) {
+            start.push_attribute((*flag, if *value { "true" } else { "false" }));
+        }
+    }
+
+    // Escaped by hand, newlines included, and pushed as raw bytes so it is
+    // not escaped twice
+    if let Some(doc) = &element.documentation {
+        let escaped = super::escape_xml(doc).replace('\n', "&#xA;");
+        start.push_attribute((b"body" as &[u8], escaped.as_bytes()));
+    }
+
+    for (key, value) in &element.properties {
+        let key = key.as_ref();
+        if FLAGS.contains(&key)
+            || key == "href"
+            || key == "href_target_name"
+            || key.starts_with('_')
+        {
+            continue;
+        }
+        if let PropertyValue::String(value) = value {
+            start.push_attribute((key, value.as_ref()));
+        }
+    }
+}
+
+/// Tag of the href child for elements of this kind.
+fn href_tag(kind: ElementKind) -> &'static str {
+    match kind {
+        ElementKind::NamespaceImport => "importedNamespace",
+        ElementKind::MembershipImport => "importedMembership",
+        ElementKind::Membership => "memberElement",
+        ElementKind::Specialization => "superclassifier",
+        ElementKind::FeatureTyping => "type",
+        ElementKind::Subsetting
+        | ElementKind::ReferenceSubsetting
+        | ElementKind::CrossSubsetting => "subsettedFeature",
+        ElementKind::Redefinition => "redefinedFeature",
+        ElementKind::Disjoining => "disjoiningType",
+        ElementKind::Conjugation => "originalType",
+        ElementKind::FeatureChaining => "chainingFeature",
+        _ => "target",
+    }
+}
@@ -0,0 +1,32 @@
+//! Setup shared by the integration tests.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Write `(path, content)` pairs under `dir`, creating parent directories,
+/// and return the path of the first file.
+pub fn write_models(dir: &Path, files: &[(&str, &str)]) -> PathBuf {
+    for (name, content) in files {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(path, content).unwrap();
+    }
+    dir.join(files[0].0)
+}
+
+/// Run git in `dir`, panicking on failure.
+pub fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .expect("Should run git");
+    assert!(status.success(), "git {:?} failed", args);
+}
@@ -0,0 +1,211 @@
+//! Integration tests for the alias and re-export report.
+//!
+//! Tests alias chains (resolved, unresolved), transitive re-export chains
+//! with their members, finding the names an element is reachable as, and
+//! the `syster resolve-aliases` command.
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{AnalysisOptions, MatchKind, find_symbol, resolve_aliases};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[
+    (
+        "lib.sysml",
+        "package Lib {\n\
+         \x20   part def Engine;\n\
+         \x20   alias Motor for Engine;\n\
+         \x20   alias Drive for Motor;\n\
+         \x20   alias Broken for Nowhere;\n\
+         }\n",
+    ),
+    (
+        "facade.sysml",
+        "package Facade {\n\
+         \x20   public import Lib::*;\n\
+         }\n\
+         package Outer {\n\
+         \x20   public import Facade::*;\n\
+         }\n",
+    ),
+];
+
+#[test]
+fn test_alias_chains() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let report = resolve_aliases(temp_dir.path(), None, &AnalysisOptions::new()).unwrap();
+
+    let names: Vec<&str> = report.aliases.iter().map(|a| a.alias.as_str()).collect();
+    assert_eq!(names, vec!["Lib::Broken", "Lib::Drive", "Lib::Motor"]);
+
+    let drive = &report.aliases[1];
+    assert_eq!(drive.target.as_deref(), Some("Lib::Engine"));
+    assert_eq!(drive.chain.last().map(String::as_str), Some("Lib::Engine"));
+    assert_eq!(drive.line, 4);
+    assert!(drive.file.ends_with("lib.sysml"));
+
+    let broken = &report.aliases[0];
+    assert_eq!(broken.target, None);
+    assert_eq!(broken.chain, vec!["Nowhere"]);
+    assert!(!broken.is_circular());
+}
+
+#[test]
+fn test_reexport_chains() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let report = resolve_aliases(temp_dir.path(), None, &AnalysisOptions::new()).unwrap();
+
+    let chains: Vec<(String, Vec<String>)> = report
+        .reexports
+        .iter()
+        .map(|r| (r.scope.clone(), r.chain.clone()))
+        .collect();
+    assert!(chains.contains(&("Facade".to_string(), vec!["Lib".to_string()])));
+    assert!(chains.contains(&("Outer".to_string(), vec!["Facade".to_string()])));
+    assert!(chains.contains(&(
+        "Outer".to_string(),
+        vec!["Facade".to_string(), "Lib".to_string()]
+    )));
+
+    // Members of the last link carry their final targets
+    let outer_lib = report
+        .reexports
+        .iter()
+        .find(|r| r.scope == "Outer" && r.chain.len() == 2)
+        .unwrap();
+    let drive = outer_lib
+        .members
+        .iter()
+        .find(|m| m.name == "Drive")
+        .expect("Drive should be re-exported");
+    assert_eq!(drive.target.as_deref(), Some("Lib::Engine"));
+
+    // Scoped to Facade: Lib's aliases and Outer's re-exports are left out
+    let scoped = resolve_aliases(temp_dir.path(), Some("Facade"), &AnalysisOptions::new()).unwrap();
+    assert!(scoped.aliases.is_empty());
+    assert!(scoped.reexports.iter().all(|r| r.scope == "Facade"));
+}
+
+#[test]
+fn test_find_symbol_through_reexports() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let matches = find_symbol(temp_dir.path(), "Engine", &AnalysisOptions::new()).unwrap();
+
+    let paths: Vec<String> = matches.iter().map(|m| m.path()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "Lib::Engine",
+            "Facade::Engine -> Lib::Engine",
+            "Outer::Engine -> Facade::Engine -> Lib::Engine",
+        ]
+    );
+    assert_eq!(matches[0].via, MatchKind::Declaration);
+    assert_eq!(matches[0].line, 2);
+    assert_eq!(matches[2].via, MatchKind::Reexport);
+    assert!(matches[2].file.ends_with("facade.sysml"));
+    assert_eq!(matches[2].line, 5);
+    assert!(
+        matches
+            .iter()
+            .all(|m| m.target.as_deref() == Some("Lib::Engine"))
+    );
+}
+
+#[test]
+fn test_find_symbol_through_aliases() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let matches = find_symbol(temp_dir.path(), "Drive", &AnalysisOptions::new()).unwrap();
+    assert_eq!(matches.len(), 3);
+    assert_eq!(matches[0].via, MatchKind::Alias);
+    assert_eq!(matches[0].path(), "Lib::Drive -> Lib::Motor -> Lib::Engine");
+    assert_eq!(matches[0].target.as_deref(), Some("Lib::Engine"));
+
+    // A qualified name only matches itself
+    let matches = find_symbol(temp_dir.path(), "Outer::Drive", &AnalysisOptions::new()).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(
+        matches[0].path(),
+        "Outer::Drive -> Facade::Drive -> Lib::Drive -> Lib::Motor -> Lib::Engine"
+    );
+    assert!(
+        find_symbol(temp_dir.path(), "Lib::Outer", &AnalysisOptions::new())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_cli_resolve_aliases() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("resolve-aliases")
+        .arg("--src")
+        .arg(temp_dir.path())
+        .args(["--members", "--no-stdlib"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("Lib::Broken -> Nowhere  (unresolved)"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Outer -> Facade -> Lib"), "{}", stdout);
+    assert!(stdout.contains("Drive -> Lib::Engine"), "{}", stdout);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("resolve-aliases")
+        .arg("--src")
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--json"])
+        .output()
+        .expect("Should run CLI");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["aliases"].as_array().unwrap().len(), 3);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("resolve-aliases")
+        .arg("--src")
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--find", "Broken"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("Lib::Broken -> Nowhere  (unresolved)  ("),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Outer::Broken -> Facade::Broken -> Lib::Broken -> Nowhere  (unresolved)"),
+        "{}",
+        stdout
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("resolve-aliases")
+        .arg("--src")
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--find", "Wheel"])
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("✗ No declaration, alias or re-export named Wheel"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,194 @@
+//! Integration tests for allocation consistency.
+//!
+//! Tests parsing cardinalities, listing allocations with the elements their
+//! ends resolve to, flagging dangling and abstract allocations and logical
+//! functions allocated too often, and `syster allocations`.
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AllocationConfig, AllocationIssue, AnalysisOptions, check_allocations};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "system.sysml",
+    "package Logical {\n\
+         \x20   action sense;\n\
+         \x20   action compute {\n\
+         \x20       action filter;\n\
+         \x20   }\n\
+         \x20   action actuate;\n\
+         }\n\
+         package Physical {\n\
+         \x20   abstract part def Controller;\n\
+         \x20   part def Board;\n\
+         \x20   part vehicle {\n\
+         \x20       part board : Board;\n\
+         \x20   }\n\
+         \x20   part ecu : Board;\n\
+         \x20   allocate Logical::sense to vehicle.board;\n\
+         }\n\
+         package Allocations {\n\
+         \x20   allocation computeOnEcu allocate Logical::compute to Physical::ecu;\n\
+         \x20   allocate Logical::compute to Physical::vehicle;\n\
+         \x20   allocate Logical::actuate to Physical::Controller;\n\
+         \x20   allocate Logical::missing to Physical::ecu;\n\
+         }\n",
+)];
+
+fn logical() -> AllocationConfig {
+    AllocationConfig {
+        logical: vec!["Logical".to_string()],
+        ..AllocationConfig::default()
+    }
+}
+
+#[test]
+fn test_cardinality() {
+    let mut config = AllocationConfig::default();
+    assert_eq!(config.cardinality(), "1");
+
+    config.set_cardinality("1..*").unwrap();
+    assert_eq!((config.min, config.max), (1, None));
+    assert_eq!(config.cardinality(), "1..*");
+    config.set_cardinality(" 0..2 ").unwrap();
+    assert_eq!((config.min, config.max), (0, Some(2)));
+
+    let err = config.set_cardinality("3..1").unwrap_err();
+    assert!(err.contains("invalid cardinality '3..1'"), "{}", err);
+    assert!(config.set_cardinality("one").is_err());
+    assert_eq!(config.cardinality(), "0..2");
+}
+
+#[test]
+fn test_allocations_report() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let report = check_allocations(temp_dir.path(), &logical(), &AnalysisOptions::new()).unwrap();
+
+    let ends: Vec<(u32, Option<&str>, Option<&str>)> = report
+        .allocations
+        .iter()
+        .map(|a| {
+            (
+                a.line,
+                a.source_element.as_deref(),
+                a.target_element.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        ends,
+        vec![
+            (15, Some("Logical::sense"), Some("Physical::vehicle::board")),
+            (18, Some("Logical::compute"), Some("Physical::ecu")),
+            (19, Some("Logical::compute"), Some("Physical::vehicle")),
+            (20, Some("Logical::actuate"), Some("Physical::Controller")),
+            (21, None, Some("Physical::ecu")),
+        ]
+    );
+    assert_eq!(
+        report.allocations[1].name.as_deref(),
+        Some("Allocations::computeOnEcu")
+    );
+    assert_eq!(report.allocations[0].target, "vehicle.board");
+
+    let issues: Vec<String> = report
+        .issues
+        .iter()
+        .map(|issue| {
+            let text = issue.to_string();
+            text[text.find(": ").unwrap() + 2..].to_string()
+        })
+        .collect();
+    assert_eq!(
+        issues,
+        vec![
+            "'Logical::actuate' is allocated to abstract definition Physical::Controller",
+            "dangling allocation: 'Logical::missing' not found",
+            "logical function Logical::compute is allocated 2 times (expected 1)",
+        ]
+    );
+    assert!(matches!(
+        &report.issues[2],
+        AllocationIssue::Cardinality { line: 3, .. }
+    ));
+    assert!(!report.is_clean());
+}
+
+#[test]
+fn test_allocations_without_logical_packages() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let report = check_allocations(
+        temp_dir.path(),
+        &AllocationConfig::default(),
+        &AnalysisOptions::new(),
+    )
+    .unwrap();
+
+    assert_eq!(report.allocations.len(), 5);
+    assert_eq!(report.issues.len(), 2);
+    assert!(
+        report
+            .issues
+            .iter()
+            .all(|issue| !matches!(issue, AllocationIssue::Cardinality { .. }))
+    );
+}
+
+#[test]
+fn test_cli_allocations_from_project_file() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    fs::write(
+        temp_dir.path().join("syster.toml"),
+        "[allocations]\nlogical = [\"Logical\"]\ncardinality = \"1..*\"\n",
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .args(["allocations", "--no-stdlib"])
+            .args(args)
+            .arg("--src")
+            .arg(temp_dir.path())
+            .output()
+            .expect("Should run CLI")
+    };
+
+    let output = run(&["--json"]);
+    assert!(!output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["allocations"].as_array().unwrap().len(), 5);
+    let issues: Vec<&str> = report["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| issue["issue"].as_str().unwrap())
+        .collect();
+    assert_eq!(issues, vec!["abstract_target", "dangling"]);
+
+    let output = run(&["--cardinality", "1"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.starts_with("Source"), "stdout: {}", stdout);
+    assert!(
+        stdout.contains("Allocations::computeOnEcu"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(
+        stderr.contains("Logical::compute is allocated 2 times (expected 1)"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(
+        stderr.contains("✗ Checked 5 allocations: 3 issues"),
+        "stderr: {}",
+        stderr
+    );
+}
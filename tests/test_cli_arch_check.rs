@@ -0,0 +1,142 @@
+//! Integration tests for `syster arch-check`.
+//!
+//! Tests package coupling metrics, and that cycles, god packages and layer
+//! violations configured in `syster.toml` are flagged.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, ArchConfig, ArchSmell, LayerRule, arch_check};
+use tempfile::TempDir;
+
+/// `Application` depends on `Services` and `Hardware`, `Services` on
+/// `Hardware`, and `Hardware` on nothing.
+fn write_layers(dir: &Path) {
+    fs::write(
+        dir.join("application.sysml"),
+        "package Application { part def App { part service : Services::Logger; part board : Hardware::Board; } }",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("services.sysml"),
+        "package Services { part def Logger { part sink : Hardware::Board; } part def Clock; }",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("hardware.sysml"),
+        "package Hardware { part def Board; part def Pin; }",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_arch_check_metrics() {
+    let temp_dir = TempDir::new().unwrap();
+    write_layers(temp_dir.path());
+
+    let report = arch_check(
+        temp_dir.path(),
+        &ArchConfig::default(),
+        &AnalysisOptions::new(),
+    )
+    .unwrap();
+
+    assert!(report.is_clean(), "{:?}", report.smells);
+    let names: Vec<_> = report.packages.iter().map(|p| p.package.as_str()).collect();
+    assert_eq!(names, vec!["Application", "Hardware", "Services"]);
+
+    let application = &report.packages[0];
+    assert_eq!((application.fan_in, application.fan_out), (0, 2));
+    assert_eq!(application.instability, 1.0);
+    let hardware = &report.packages[1];
+    assert_eq!((hardware.fan_in, hardware.fan_out), (2, 0));
+    assert_eq!(hardware.instability, 0.0);
+    assert_eq!(hardware.cohesion, None);
+    assert_eq!(report.dependencies["Services"].len(), 1);
+}
+
+#[test]
+fn test_arch_check_cycle_and_god_package() {
+    let temp_dir = TempDir::new().unwrap();
+    write_layers(temp_dir.path());
+    fs::write(
+        temp_dir.path().join("hardware.sysml"),
+        "package Hardware { part def Board { part clock : Services::Clock; } part def Pin; }",
+    )
+    .unwrap();
+
+    let config = ArchConfig {
+        max_coupling: 2,
+        ..ArchConfig::default()
+    };
+    let report = arch_check(temp_dir.path(), &config, &AnalysisOptions::new()).unwrap();
+
+    assert!(report.smells.contains(&ArchSmell::Cycle {
+        packages: vec!["Hardware".to_string(), "Services".to_string()],
+    }));
+    assert!(report.smells.iter().any(|smell| matches!(
+        smell,
+        ArchSmell::GodPackage { package, coupling: 3, .. } if package == "Hardware"
+    )));
+}
+
+#[test]
+fn test_arch_check_layer_violation() {
+    let temp_dir = TempDir::new().unwrap();
+    write_layers(temp_dir.path());
+
+    let config = ArchConfig {
+        rules: vec![LayerRule {
+            package: "Application".to_string(),
+            must_not_depend_on: vec!["Hardware".to_string()],
+        }],
+        ..ArchConfig::default()
+    };
+    let report = arch_check(temp_dir.path(), &config, &AnalysisOptions::new()).unwrap();
+
+    assert_eq!(
+        report.smells,
+        vec![ArchSmell::LayerViolation {
+            package: "Application".to_string(),
+            depends_on: "Hardware".to_string(),
+            rule: "Application must not depend on Hardware".to_string(),
+            elements: vec!["Application::App::board".to_string()],
+        }]
+    );
+}
+
+#[test]
+fn test_cli_arch_check_reads_project_rules() {
+    let temp_dir = TempDir::new().unwrap();
+    write_layers(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["arch-check", "--no-stdlib", "--src"])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Services"));
+
+    fs::write(
+        temp_dir.path().join("syster.toml"),
+        "[arch]\n\n[[arch.rules]]\npackage = \"Application\"\nmust_not_depend_on = [\"Hardware\"]\n",
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["arch-check", "--no-stdlib", "--src"])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("Layer violation: Application depends on Hardware"),
+        "stderr: {}",
+        stderr
+    );
+}
@@ -0,0 +1,227 @@
+//! Integration tests for attestations of analysis results.
+//!
+//! Tests the in-toto statement binding a report to the digests of the
+//! sources it was produced from, the signed DSSE envelope, and `--attest`
+//! with and without `--attest-key`.
+
+#![cfg(feature = "signing")]
+
+mod common;
+
+use ed25519_dalek::pkcs8::{EncodePrivateKey, LineEnding};
+use ed25519_dalek::{Signature, SigningKey, Verifier};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::process::Command;
+use syster_cli::attestation::{PAYLOAD_TYPE, pre_auth_encoding};
+use syster_cli::{AnalysisOptions, Attestation};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[
+    ("vehicle.sysml", "package Vehicle {\n    part def Car;\n}\n"),
+    ("syster.toml", "[budgets]\nmax_file_lines = 100\n"),
+];
+
+fn sha256(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn base64_decode(text: &str) -> Vec<u8> {
+    const ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let (mut bits, mut count, mut bytes) = (0u32, 0, Vec::new());
+    for c in text.chars().filter(|c| *c != '=') {
+        bits = (bits << 6) | ALPHABET.find(c).unwrap() as u32;
+        count += 6;
+        if count >= 8 {
+            count -= 8;
+            bytes.push((bits >> count) as u8);
+            bits &= (1 << count) - 1;
+        }
+    }
+    bytes
+}
+
+#[test]
+fn test_attestation_statement() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let report = br#"{"error_count": 0}"#;
+
+    let attestation = Attestation::collect(
+        temp_dir.path(),
+        &AnalysisOptions::new(),
+        vec!["models".to_string(), "--json".to_string()],
+        "report.json",
+        report,
+    )
+    .unwrap();
+    let statement = attestation.statement();
+
+    assert_eq!(statement["_type"], "https://in-toto.io/Statement/v1");
+    assert_eq!(statement["predicateType"], "https://slsa.dev/provenance/v1");
+    assert_eq!(statement["subject"][0]["name"], "report.json");
+    assert_eq!(statement["subject"][0]["digest"]["sha256"], sha256(report));
+    let build = &statement["predicate"]["buildDefinition"];
+    assert_eq!(build["externalParameters"]["arguments"][1], "--json");
+    let dependencies = build["resolvedDependencies"].as_array().unwrap();
+    assert_eq!(dependencies.len(), 2);
+    assert_eq!(
+        dependencies[0]["name"],
+        temp_dir.path().to_string_lossy().as_ref()
+    );
+    let project_file = fs::read(temp_dir.path().join("syster.toml")).unwrap();
+    assert_eq!(dependencies[1]["digest"]["sha256"], sha256(&project_file));
+    assert_eq!(
+        statement["predicate"]["runDetails"]["builder"]["version"]["syster"],
+        env!("CARGO_PKG_VERSION")
+    );
+
+    // The input digest follows the sources
+    let again = Attestation::collect(
+        temp_dir.path(),
+        &AnalysisOptions::new(),
+        Vec::new(),
+        "report.json",
+        report,
+    )
+    .unwrap();
+    assert_eq!(again.sources, attestation.sources);
+    fs::write(
+        temp_dir.path().join("vehicle.sysml"),
+        "package Vehicle {\n    part def Truck;\n}\n",
+    )
+    .unwrap();
+    let changed = Attestation::collect(
+        temp_dir.path(),
+        &AnalysisOptions::new(),
+        Vec::new(),
+        "report.json",
+        report,
+    )
+    .unwrap();
+    assert_ne!(changed.sources[0], attestation.sources[0]);
+    assert_eq!(changed.sources[1], attestation.sources[1]);
+}
+
+#[test]
+fn test_signed_envelope() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let attestation = Attestation::collect(
+        temp_dir.path(),
+        &AnalysisOptions::new(),
+        Vec::new(),
+        "analysis.json",
+        b"{}",
+    )
+    .unwrap();
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+
+    let envelope = attestation.sign(&key);
+
+    assert_eq!(envelope["payloadType"], PAYLOAD_TYPE);
+    let payload = base64_decode(envelope["payload"].as_str().unwrap());
+    let statement: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+    assert_eq!(statement, attestation.statement());
+
+    let signature = &envelope["signatures"][0];
+    let public_key = key.verifying_key();
+    let keyid: String = public_key
+        .as_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    assert_eq!(signature["keyid"], keyid);
+    let bytes: [u8; 64] = base64_decode(signature["sig"].as_str().unwrap())
+        .try_into()
+        .unwrap();
+    assert!(
+        public_key
+            .verify(
+                &pre_auth_encoding(PAYLOAD_TYPE, &payload),
+                &Signature::from_bytes(&bytes)
+            )
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_cli_attest() {
+    let temp_dir = TempDir::new().unwrap();
+    let models = temp_dir.path().join("models");
+    fs::create_dir(&models).unwrap();
+    common::write_models(&models, MODELS);
+    let report = temp_dir.path().join("report.json");
+    let attestation = temp_dir.path().join("report.intoto.json");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&models)
+        .args(["--no-stdlib", "--json", "-o"])
+        .arg(&report)
+        .arg("--attest")
+        .arg(&attestation)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let statement: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&attestation).unwrap()).unwrap();
+    assert_eq!(statement["subject"][0]["name"], "report.json");
+    assert_eq!(
+        statement["subject"][0]["digest"]["sha256"],
+        sha256(&fs::read(&report).unwrap())
+    );
+    let arguments = &statement["predicate"]["buildDefinition"]["externalParameters"]["arguments"];
+    assert!(
+        arguments
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|a| a == "--attest"),
+        "{}",
+        arguments
+    );
+}
+
+#[test]
+fn test_cli_attest_signed() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let key_path = temp_dir.path().join("key.pem");
+    fs::write(
+        &key_path,
+        key.to_pkcs8_pem(LineEnding::LF).unwrap().as_bytes(),
+    )
+    .unwrap();
+    let attestation = temp_dir.path().join("analysis.intoto.json");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path().join("vehicle.sysml"))
+        .arg("--no-stdlib")
+        .arg("--attest")
+        .arg(&attestation)
+        .arg("--attest-key")
+        .arg(&key_path)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let envelope: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&attestation).unwrap()).unwrap();
+    assert_eq!(envelope["payloadType"], PAYLOAD_TYPE);
+    let payload = base64_decode(envelope["payload"].as_str().unwrap());
+    let statement: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+    assert_eq!(statement["subject"][0]["name"], "analysis.json");
+}
@@ -0,0 +1,82 @@
+//! Integration tests for shields.io badges.
+//!
+//! Tests the message and color of each metric, custom labels, and
+//! `syster badge`.
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, AnalysisResult, BadgeMetric, badge, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// Two definitions, one documented, and an unresolved reference.
+const MODEL: &str = "package Vehicle {\n\
+                     \x20   part def Car {\n\
+                     \x20       doc /* A car. */\n\
+                     \x20   }\n\
+                     \x20   part def Truck;\n\
+                     \x20   part wheel : Wheel;\n\
+                     }\n";
+
+fn analyze_model() -> AnalysisResult {
+    run_analysis_from_sources(
+        &[("car.sysml".to_string(), MODEL.to_string())],
+        &AnalysisOptions::new(),
+    )
+}
+
+#[test]
+fn test_badge_metrics() {
+    let result = analyze_model();
+
+    let errors = badge(&result, BadgeMetric::Errors, None);
+    assert_eq!(errors.schema_version, 1);
+    assert_eq!(errors.label, "errors");
+    assert_eq!(errors.message, "1");
+    assert_eq!(errors.color, "red");
+
+    let warnings = badge(&result, BadgeMetric::Warnings, None);
+    assert_eq!(warnings.message, "0");
+    assert_eq!(warnings.color, "brightgreen");
+
+    let docs = badge(&result, BadgeMetric::DocCoverage, Some("docs"));
+    assert_eq!(docs.label, "docs");
+    assert_eq!(docs.message, "50%");
+    assert_eq!(docs.color, "yellow");
+}
+
+#[test]
+fn test_badge_json_shape() {
+    let result = analyze_model();
+
+    let json = serde_json::to_value(badge(&result, BadgeMetric::Errors, None)).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "schemaVersion": 1,
+            "label": "errors",
+            "message": "1",
+            "color": "red",
+        })
+    );
+}
+
+#[test]
+fn test_cli_badge() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("car.sysml"), MODEL).unwrap();
+    let out = temp_dir.path().join("badge.json");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("badge")
+        .args(["--metric", "doc-coverage", "--src"])
+        .arg(temp_dir.path())
+        .arg("--no-stdlib")
+        .arg("-o")
+        .arg(&out)
+        .output()
+        .expect("Should run CLI");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(json["label"], "doc coverage");
+    assert_eq!(json["message"], "50%");
+}
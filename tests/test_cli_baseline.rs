@@ -0,0 +1,92 @@
+//! Integration tests for model baselines.
+//!
+//! Tests that `syster baseline create` stores an XMI snapshot with its hash
+//! under `.syster/baselines`, that comparing against it reports the changes
+//! made since, and that existing or modified baselines are refused.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, ChangeLevel, compare_baseline, create_baseline};
+use tempfile::TempDir;
+
+const MODEL: &str = "package Vehicles {\n    part def Engine;\n    part def Wheel;\n}\n";
+
+#[test]
+fn test_create_and_compare_unchanged() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("vehicles.sysml"), MODEL).unwrap();
+
+    let baseline = create_baseline(temp_dir.path(), "R1", false, &AnalysisOptions::new()).unwrap();
+    assert_eq!(baseline.tag, "R1");
+    assert!(baseline.hash.starts_with("sha256:"));
+    assert!(baseline.elements > 0);
+    let dir = temp_dir.path().join(".syster/baselines/R1");
+    assert!(dir.join("model.xmi").is_file());
+    assert!(dir.join("baseline.json").is_file());
+
+    let comparison = compare_baseline(temp_dir.path(), "R1", &AnalysisOptions::new()).unwrap();
+    assert_eq!(comparison.baseline, baseline);
+    assert_eq!(comparison.level, None);
+    assert!(comparison.changes.is_empty(), "{:?}", comparison.changes);
+
+    let err = create_baseline(temp_dir.path(), "R1", false, &AnalysisOptions::new()).unwrap_err();
+    assert!(err.contains("already exists"), "{}", err);
+    assert!(create_baseline(temp_dir.path(), "R1", true, &AnalysisOptions::new()).is_ok());
+}
+
+#[test]
+fn test_compare_reports_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("vehicles.sysml");
+    fs::write(&file, MODEL).unwrap();
+    create_baseline(temp_dir.path(), "R1", false, &AnalysisOptions::new()).unwrap();
+
+    fs::write(&file, MODEL.replace("part def Wheel;", "part def Brake;")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["baseline", "compare", "R1", "--no-stdlib", "--src"])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.starts_with("Baseline R1 ("), "{}", stdout);
+    assert!(
+        stdout.contains("major: removed public element Vehicles::Wheel"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("minor: added public element Vehicles::Brake"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("2 changes since baseline R1"), "{}", stdout);
+
+    let comparison = compare_baseline(temp_dir.path(), "R1", &AnalysisOptions::new()).unwrap();
+    assert_eq!(comparison.level, Some(ChangeLevel::Major));
+}
+
+#[test]
+fn test_compare_rejects_missing_or_modified_baseline() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("vehicles.sysml"), MODEL).unwrap();
+
+    let err = compare_baseline(temp_dir.path(), "R1", &AnalysisOptions::new()).unwrap_err();
+    assert!(err.contains("Baseline 'R1' not found"), "{}", err);
+
+    let err =
+        create_baseline(temp_dir.path(), "../R1", false, &AnalysisOptions::new()).unwrap_err();
+    assert!(err.contains("Invalid baseline tag"), "{}", err);
+
+    create_baseline(temp_dir.path(), "R1", false, &AnalysisOptions::new()).unwrap();
+    let snapshot = temp_dir.path().join(".syster/baselines/R1/model.xmi");
+    let tampered = fs::read_to_string(&snapshot)
+        .unwrap()
+        .replace("Engine", "Motor");
+    fs::write(&snapshot, tampered).unwrap();
+
+    let err = compare_baseline(temp_dir.path(), "R1", &AnalysisOptions::new()).unwrap_err();
+    assert!(err.contains("has been modified"), "{}", err);
+}
@@ -0,0 +1,86 @@
+//! Integration tests for blame annotation of diagnostics.
+//!
+//! Tests that `--blame` records the author, e-mail and date of the last
+//! change to each diagnostic's line, and leaves files outside git alone.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster_cli::{annotate_blame, run_analysis};
+use tempfile::TempDir;
+
+/// Commit a model with an unresolved type to a new repository.
+fn write_repo(dir: &Path) -> PathBuf {
+    let file = dir.join("bad.sysml");
+    fs::write(&file, "part def A;\npart def B { part x : Undefined; }\n").unwrap();
+    for args in [
+        &["init", "-q"][..],
+        &["add", "."],
+        &[
+            "-c",
+            "user.name=Ada Modeler",
+            "-c",
+            "user.email=ada@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "Add B",
+        ],
+    ] {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .status()
+            .expect("Should run git");
+        assert!(status.success());
+    }
+    file
+}
+
+#[test]
+fn test_annotate_blame() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = write_repo(temp_dir.path());
+
+    let mut result = run_analysis(&file, false, false, None).unwrap();
+    assert!(!result.diagnostics.is_empty());
+    annotate_blame(&mut result.diagnostics);
+
+    for diag in &result.diagnostics {
+        let blame = diag.blame.as_ref().expect("Should be blamed");
+        assert_eq!(blame.author, "Ada Modeler");
+        assert_eq!(blame.email, "ada@example.com");
+        assert_eq!(blame.commit.len(), 7);
+        assert!(blame.date.ends_with('Z'), "{}", blame.date);
+    }
+}
+
+#[test]
+fn test_annotate_blame_outside_git() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("bad.sysml");
+    fs::write(&file, "part def B { part x : Undefined; }\n").unwrap();
+
+    let mut result = run_analysis(&file, false, false, None).unwrap();
+    annotate_blame(&mut result.diagnostics);
+    assert!(result.diagnostics.iter().all(|d| d.blame.is_none()));
+}
+
+#[test]
+fn test_cli_blame_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = write_repo(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .args(["--no-stdlib", "--json", "--blame"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let diagnostics = json["diagnostics"].as_array().unwrap();
+    assert!(!diagnostics.is_empty(), "{}", stdout);
+    assert_eq!(diagnostics[0]["blame"]["author"], "Ada Modeler");
+}
@@ -0,0 +1,141 @@
+//! Integration tests for model size budgets.
+//!
+//! Tests loading the `[budgets]` table of `syster.toml`, the `L0012`–`L0014`
+//! errors where a model outgrows its budgets, and the budgets failing an
+//! analysis run from the command line.
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, AnalysisResult, Budgets, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// A 13-line model: `Vehicle` owns five elements, `Parts` three, and
+/// `piston` is nested four levels deep.
+const MODEL: &str = "package Vehicle {\n\
+                     \x20   part def Car {\n\
+                     \x20       part engine {\n\
+                     \x20           part piston;\n\
+                     \x20       }\n\
+                     \x20   }\n\
+                     \x20   part def Truck;\n\
+                     \x20   package Parts {\n\
+                     \x20       part def Wheel;\n\
+                     \x20       part def Axle;\n\
+                     \x20       part def Hub;\n\
+                     \x20   }\n\
+                     }\n";
+
+fn analyze_model(budgets: Budgets) -> AnalysisResult {
+    run_analysis_from_sources(
+        &[("vehicle.sysml".to_string(), MODEL.to_string())],
+        &AnalysisOptions::new().budgets(budgets),
+    )
+}
+
+#[test]
+fn test_load_budgets() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("syster.toml");
+
+    fs::write(&path, "[requirements]\nshall = false\n").unwrap();
+    assert_eq!(Budgets::load(&path).unwrap(), None);
+
+    fs::write(&path, "[budgets]\nmax_file_lines = 500\n").unwrap();
+    assert_eq!(
+        Budgets::load(&path).unwrap(),
+        Some(Budgets {
+            max_file_lines: Some(500),
+            ..Budgets::default()
+        })
+    );
+
+    fs::write(&path, "[budgets]\nmax_lines = 500\n").unwrap();
+    let err = Budgets::load(&path).unwrap_err();
+    assert!(err.contains("max_lines"), "{}", err);
+}
+
+#[test]
+fn test_budget_errors() {
+    let budgets = Budgets {
+        max_package_elements: Some(4),
+        max_file_lines: Some(10),
+        max_nesting_depth: Some(3),
+    };
+
+    let result = analyze_model(budgets);
+
+    let found: Vec<(u32, Option<&str>)> = result
+        .diagnostics
+        .iter()
+        .map(|d| (d.line, d.code.as_deref()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![(1, Some("L0012")), (4, Some("L0014")), (11, Some("L0013"))]
+    );
+    assert_eq!(result.error_count, 3);
+    assert_eq!(
+        result.diagnostics[0].message,
+        "package 'Vehicle' has 5 elements, over the budget of 4"
+    );
+    assert!(
+        result.diagnostics[1].message.ends_with(
+            "'Vehicle::Car::engine::piston' is nested 4 levels deep, over the budget of 3"
+        ),
+        "{}",
+        result.diagnostics[1].message
+    );
+    assert_eq!(
+        result.diagnostics[2].message,
+        "file has 13 lines, over the budget of 10"
+    );
+}
+
+#[test]
+fn test_within_budgets() {
+    let budgets = Budgets {
+        max_package_elements: Some(5),
+        max_file_lines: Some(13),
+        max_nesting_depth: Some(4),
+    };
+
+    let result = analyze_model(budgets);
+
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+}
+
+#[test]
+fn test_cli_budgets_from_project_file() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("vehicle.sysml"), MODEL).unwrap();
+
+    let run = || {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg(temp_dir.path())
+            .arg("--no-stdlib")
+            .output()
+            .expect("Should run CLI")
+    };
+    let output = run();
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    fs::write(
+        temp_dir.path().join("syster.toml"),
+        "[budgets]\nmax_file_lines = 10\n",
+    )
+    .unwrap();
+    let output = run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("error[L0013]: ")
+            && stderr.contains("file has 13 lines, over the budget of 10"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("L0012"), "{}", stderr);
+}
@@ -0,0 +1,240 @@
+//! Integration tests for the remote result cache.
+//!
+//! Tests the content-addressed cache key and the files it covers, reading
+//! results through and writing them back to a local stand-in for a cache
+//! server, falling back to analyzing when the server can't be reached, and
+//! `--cache-url`.
+
+#![cfg(feature = "interchange")]
+
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+use syster_cli::{AnalysisOptions, AnalysisResult, Profile, RemoteCache, cache_key};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicle.sysml",
+    "package Vehicle {\n    part def Car;\n    part car : Car;\n}\n",
+)];
+
+/// Serve `count` requests on a local port like a cache server: `PUT`
+/// stores the body under the path, `GET` returns it or 404. Returns the
+/// request lines and `Authorization` headers.
+fn cache_server(
+    mut entries: HashMap<String, String>,
+    count: usize,
+) -> (String, JoinHandle<Vec<(String, Option<String>)>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/cache", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for _ in 0..count {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let (mut length, mut expect_continue, mut authorization) = (0, false, None);
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                let header = header.trim().to_string();
+                if header.is_empty() {
+                    break;
+                }
+                let lower = header.to_lowercase();
+                if let Some(value) = lower.strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if lower.starts_with("authorization:") {
+                    authorization = Some(header["authorization:".len()..].trim().to_string());
+                }
+                expect_continue |= lower == "expect: 100-continue";
+            }
+            if expect_continue {
+                stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").unwrap();
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let line = line.trim().to_string();
+            let mut parts = line.split(' ');
+            let (method, path) = (parts.next().unwrap(), parts.next().unwrap().to_string());
+            let (status, response) = match method {
+                "PUT" => {
+                    entries.insert(path, String::from_utf8(body).unwrap());
+                    ("200 OK", String::new())
+                }
+                _ => match entries.get(&path) {
+                    Some(entry) => ("200 OK", entry.clone()),
+                    None => ("404 Not Found", String::new()),
+                },
+            };
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 {}\r\nContent-Type: application/json\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        response.len(),
+                        response
+                    )
+                    .as_bytes(),
+                )
+                .unwrap();
+            requests.push((line, authorization));
+        }
+        requests
+    });
+    (url, handle)
+}
+
+#[test]
+fn test_cache_key() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let options = AnalysisOptions::new();
+
+    let key = cache_key(temp_dir.path(), &options).unwrap();
+    assert!(key.starts_with("sha256-"), "{}", key);
+    assert_eq!(key.len(), "sha256-".len() + 64);
+    assert_eq!(cache_key(temp_dir.path(), &options).unwrap(), key);
+    // Progress output doesn't change the result
+    assert_eq!(
+        cache_key(temp_dir.path(), &options.clone().verbose(true)).unwrap(),
+        key
+    );
+
+    let strict = options.clone().profile(Profile::Strict);
+    assert_ne!(cache_key(temp_dir.path(), &strict).unwrap(), key);
+
+    fs::write(
+        temp_dir.path().join("vehicle.sysml"),
+        "package Vehicle {\n    part def Car;\n}\n",
+    )
+    .unwrap();
+    assert_ne!(cache_key(temp_dir.path(), &options).unwrap(), key);
+}
+
+#[test]
+fn test_cache_key_follows_the_walk() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    fs::write(temp_dir.path().join(".systerignore"), "build/\n").unwrap();
+    let options = AnalysisOptions::new();
+    let key = cache_key(temp_dir.path(), &options).unwrap();
+
+    // Files the walk skips don't change the result
+    fs::create_dir(temp_dir.path().join("build")).unwrap();
+    fs::write(
+        temp_dir.path().join("build/generated.sysml"),
+        "package Generated;\n",
+    )
+    .unwrap();
+    assert_eq!(cache_key(temp_dir.path(), &options).unwrap(), key);
+
+    // The rules choosing the files do
+    fs::write(temp_dir.path().join(".systerignore"), "scratch/\n").unwrap();
+    assert_ne!(cache_key(temp_dir.path(), &options).unwrap(), key);
+}
+
+#[test]
+fn test_read_through_and_write_back() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let options = AnalysisOptions::new();
+    let key = cache_key(temp_dir.path(), &options).unwrap();
+
+    // A miss analyzes and stores the result
+    let (url, server) = cache_server(HashMap::new(), 2);
+    let result = RemoteCache::new(&url, None)
+        .analyze(temp_dir.path(), &options)
+        .unwrap();
+    let requests: Vec<String> = server.join().unwrap().into_iter().map(|r| r.0).collect();
+    assert_eq!(
+        requests,
+        vec![
+            format!("GET /cache/{}.json HTTP/1.1", key),
+            format!("PUT /cache/{}.json HTTP/1.1", key),
+        ]
+    );
+    assert_eq!(result.file_count, 1);
+
+    // A hit is returned as stored, without analyzing
+    let mut stored: AnalysisResult = result.clone();
+    stored.symbol_count = 99;
+    let entries = HashMap::from([(
+        format!("/cache/{}.json", key),
+        serde_json::to_string(&stored).unwrap(),
+    )]);
+    let (url, server) = cache_server(entries, 1);
+    let result = RemoteCache::new(&format!("{}/", url), Some("secret".to_string()))
+        .analyze(temp_dir.path(), &options)
+        .unwrap();
+    let requests = server.join().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].1.as_deref(), Some("Bearer secret"));
+    assert_eq!(result.symbol_count, 99);
+}
+
+#[test]
+fn test_unreachable_cache() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    // Nothing listens on a port once its listener is gone
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let cache = RemoteCache::new(&format!("http://127.0.0.1:{}/cache", port), None);
+    let result = cache
+        .analyze(temp_dir.path(), &AnalysisOptions::new())
+        .unwrap();
+
+    assert_eq!(result.file_count, 1);
+    assert_eq!(result.error_count, 0);
+}
+
+#[test]
+fn test_cli_cache_url() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let (url, server) = cache_server(HashMap::new(), 3);
+    let run = || {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg(temp_dir.path())
+            .args(["--no-stdlib", "--json", "--cache-url", &url])
+            .env("SYSTER_CACHE_TOKEN", "ci-token")
+            .output()
+            .expect("Should run CLI")
+    };
+    let first = run();
+    assert!(
+        first.status.success(),
+        "{}",
+        String::from_utf8_lossy(&first.stderr)
+    );
+    let second = run();
+    assert!(second.status.success());
+    assert_eq!(first.stdout, second.stdout);
+
+    let requests = server.join().unwrap();
+    let methods: Vec<&str> = requests
+        .iter()
+        .map(|(line, _)| line.split(' ').next().unwrap())
+        .collect();
+    assert_eq!(methods, vec!["GET", "PUT", "GET"]);
+    assert!(
+        requests
+            .iter()
+            .all(|(_, auth)| auth.as_deref() == Some("Bearer ci-token"))
+    );
+}
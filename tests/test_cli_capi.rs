@@ -0,0 +1,136 @@
+//! Integration tests for the C API.
+//!
+//! Calls the `extern "C"` functions as a C caller would: analyzing a model
+//! and reading its diagnostics as JSON, exporting a model to bytes, and
+//! reporting failures and panics through `syster_last_error`.
+
+#![cfg(feature = "capi")]
+
+mod common;
+
+use std::ffi::{CStr, CString};
+use std::path::Path;
+use syster_cli::capi::{
+    catch_panic, syster_analysis_free, syster_analyze_path, syster_bytes_free, syster_error_count,
+    syster_export_model_bytes, syster_get_diagnostics_json, syster_last_error, syster_string_free,
+};
+use tempfile::TempDir;
+
+fn write_model(dir: &Path, body: &str) {
+    let model = format!("package Vehicle {{\n{}}}\n", body);
+    common::write_models(dir, &[("vehicle.sysml", &model)]);
+}
+
+fn c_path(path: &Path) -> CString {
+    CString::new(path.to_str().unwrap()).unwrap()
+}
+
+fn last_error() -> String {
+    let error = syster_last_error();
+    assert!(!error.is_null());
+    unsafe { CStr::from_ptr(error) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn test_analyze_path() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(
+        temp_dir.path(),
+        "    part def Car;\n    part wheel : Wheel;\n",
+    );
+    let path = c_path(temp_dir.path());
+
+    unsafe {
+        let analysis = syster_analyze_path(path.as_ptr(), false);
+        assert!(!analysis.is_null());
+        assert_eq!(syster_error_count(analysis), 1);
+
+        let json = syster_get_diagnostics_json(analysis);
+        assert!(!json.is_null());
+        let result: serde_json::Value =
+            serde_json::from_str(CStr::from_ptr(json).to_str().unwrap()).unwrap();
+        assert_eq!(result["error_count"], 1);
+        assert!(
+            result["diagnostics"][0]["message"]
+                .as_str()
+                .unwrap()
+                .contains("Wheel")
+        );
+
+        syster_string_free(json);
+        syster_analysis_free(analysis);
+    }
+}
+
+#[test]
+fn test_export_model_bytes() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path(), "    part def Car;\n");
+    let path = c_path(temp_dir.path());
+    let format = CString::new("jsonld").unwrap();
+
+    unsafe {
+        let mut bytes = std::ptr::null_mut();
+        let mut len = 0;
+        let status =
+            syster_export_model_bytes(path.as_ptr(), format.as_ptr(), false, &mut bytes, &mut len);
+        assert_eq!(status, 0);
+        assert!(!bytes.is_null());
+        let jsonld: serde_json::Value =
+            serde_json::from_slice(std::slice::from_raw_parts(bytes, len)).unwrap();
+        assert!(jsonld.to_string().contains("\"Car\""));
+        syster_bytes_free(bytes, len);
+    }
+}
+
+#[test]
+fn test_failures_set_last_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let missing = c_path(&temp_dir.path().join("missing.sysml"));
+
+    unsafe {
+        assert!(syster_analyze_path(std::ptr::null(), false).is_null());
+        assert_eq!(last_error(), "path is NULL");
+
+        let format = CString::new("pdf").unwrap();
+        let (mut bytes, mut len) = (std::ptr::null_mut(), 0);
+        let status = syster_export_model_bytes(
+            missing.as_ptr(),
+            format.as_ptr(),
+            false,
+            &mut bytes,
+            &mut len,
+        );
+        assert_eq!(status, -1);
+        assert!(bytes.is_null());
+        assert!(!last_error().is_empty());
+
+        assert!(syster_get_diagnostics_json(std::ptr::null()).is_null());
+        assert_eq!(last_error(), "analysis is NULL");
+        assert_eq!(syster_error_count(std::ptr::null()), -1);
+
+        // Freeing NULL is a no-op
+        syster_analysis_free(std::ptr::null_mut());
+        syster_string_free(std::ptr::null_mut());
+        syster_bytes_free(std::ptr::null_mut(), 0);
+    }
+}
+
+#[test]
+fn test_panics_fail_the_call() {
+    // As a panic inside syster_analyze_path would
+    let analysis = catch_panic(std::ptr::null_mut::<u8>(), || panic!("index out of bounds"));
+    assert!(analysis.is_null());
+    assert_eq!(last_error(), "panic: index out of bounds");
+
+    let status = catch_panic(-1, || -> i32 { panic!("{} files", 3) });
+    assert_eq!(status, -1);
+    assert_eq!(last_error(), "panic: 3 files");
+
+    // The thread is still usable afterwards
+    unsafe {
+        assert_eq!(syster_error_count(std::ptr::null()), -1);
+    }
+}
@@ -0,0 +1,186 @@
+//! Integration tests for clone detection.
+//!
+//! Tests that definitions of the same structure under other names are
+//! grouped, that edited copies are reported as near clones above the
+//! similarity threshold, and the `syster clones` command.
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{AnalysisOptions, CloneOptions, find_clones};
+use tempfile::TempDir;
+
+/// Write a vehicle definition, a renamed copy of it in another package and
+/// a copy with one more part.
+const MODELS: &[(&str, &str)] = &[
+    (
+        "fleet.sysml",
+        "package Fleet {\n\
+         \x20   attribute def Mass;\n\
+         \x20   port def FuelPort;\n\
+         \x20   part def Engine;\n\
+         \x20   part def Trailer;\n\
+         \x20   part def Car {\n\
+         \x20       attribute mass : Mass;\n\
+         \x20       attribute payload : Mass;\n\
+         \x20       port fuel : FuelPort;\n\
+         \x20       part engine : Engine;\n\
+         \x20   }\n\
+         }\n",
+    ),
+    (
+        "legacy.sysml",
+        "package Legacy {\n\
+         \x20   private import Fleet::*;\n\
+         \x20   part def Automobile {\n\
+         \x20       attribute weight : Mass;\n\
+         \x20       port tank : FuelPort;\n\
+         \x20       attribute load : Mass;\n\
+         \x20       part motor : Engine;\n\
+         \x20   }\n\
+         \x20   part def Truck {\n\
+         \x20       attribute weight : Mass;\n\
+         \x20       attribute load : Mass;\n\
+         \x20       port tank : FuelPort;\n\
+         \x20       part motor : Engine;\n\
+         \x20       part trailer : Trailer;\n\
+         \x20   }\n\
+         }\n",
+    ),
+];
+
+#[test]
+fn test_clones_modulo_names() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let report = find_clones(
+        temp_dir.path(),
+        &CloneOptions::default(),
+        &AnalysisOptions::new(),
+    )
+    .unwrap();
+
+    // The empty definitions are not compared
+    assert_eq!(report.definitions, 3);
+    assert_eq!(report.groups.len(), 1);
+    let group = &report.groups[0];
+    assert_eq!(group.kind, "Part def");
+    assert_eq!(group.members, 4);
+    assert_eq!(group.fingerprint.len(), 16);
+    let names: Vec<&str> = group
+        .definitions
+        .iter()
+        .map(|d| d.qualified_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Fleet::Car", "Legacy::Automobile"]);
+    assert!(group.definitions[1].file.ends_with("legacy.sysml"));
+    assert_eq!(group.definitions[1].line, 3);
+    assert_eq!(report.redundant_count(), 1);
+}
+
+#[test]
+fn test_near_clones() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let report = find_clones(
+        temp_dir.path(),
+        &CloneOptions::default(),
+        &AnalysisOptions::new(),
+    )
+    .unwrap();
+
+    // Truck shares 4 of the 5 features of the two
+    assert_eq!(report.near.len(), 1);
+    let near = &report.near[0];
+    assert_eq!(near.first.qualified_name, "Fleet::Car");
+    assert_eq!(near.second.qualified_name, "Legacy::Truck");
+    assert!((near.similarity - 0.8).abs() < 1e-9, "{}", near.similarity);
+
+    let options = CloneOptions {
+        similarity: 0.9,
+        ..CloneOptions::default()
+    };
+    let report = find_clones(temp_dir.path(), &options, &AnalysisOptions::new()).unwrap();
+    assert!(report.near.is_empty());
+    assert_eq!(report.groups.len(), 1);
+
+    let options = CloneOptions {
+        min_members: 5,
+        ..CloneOptions::default()
+    };
+    let report = find_clones(temp_dir.path(), &options, &AnalysisOptions::new()).unwrap();
+    assert_eq!(report.definitions, 1);
+    assert!(report.groups.is_empty() && report.near.is_empty());
+}
+
+#[test]
+fn test_invalid_similarity() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let options = CloneOptions {
+        similarity: 80.0,
+        ..CloneOptions::default()
+    };
+
+    let err = find_clones(temp_dir.path(), &options, &AnalysisOptions::new()).unwrap_err();
+
+    assert_eq!(
+        err,
+        "Invalid similarity 80: expected a fraction between 0 and 1"
+    );
+}
+
+#[test]
+fn test_cli_clones() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["clones", "--no-stdlib", "--src"])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stdout.contains(
+            "2 definitions of identical structure (Part def, 4 members):\n  Fleet::Car  ("
+        ),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Fleet::Car ~ Legacy::Truck  (80% shared, Part def)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stderr.contains(
+            "✓ Compared 3 definitions: 1 clone groups (1 redundant definitions), 1 near clones"
+        ),
+        "{}",
+        stderr
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args([
+            "clones",
+            "--no-stdlib",
+            "--json",
+            "--similarity",
+            "0.9",
+            "--src",
+        ])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["groups"][0]["definitions"][1]["qualified_name"],
+        "Legacy::Automobile"
+    );
+    assert_eq!(json["near"].as_array().unwrap().len(), 0);
+}
@@ -0,0 +1,196 @@
+//! Integration tests for package selection by dependency closure.
+//!
+//! Tests that `--export ... --package <QName>` exports only the selected
+//! packages plus the packages and elements they transitively depend on, and
+//! that `syster closure` lists or exports that same closure.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster::interchange::{ModelFormat, Xmi};
+use syster_cli::{AnalysisOptions, ExportFilter, dependency_closure, export_model_with_options};
+use tempfile::TempDir;
+
+/// Write a workspace where `Vehicles` depends on `Parts` (by import) and
+/// `Parts` depends on `Materials` (by typing), while `Unrelated` stands alone.
+fn write_workspace(dir: &Path) {
+    fs::write(
+        dir.join("vehicles.sysml"),
+        "package Vehicles { private import Parts::*; part def Car { part engine : Engine; } }",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("parts.sysml"),
+        "package Parts { part def Engine { attribute block : Materials::Steel; } part def Spare; }",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("materials.sysml"),
+        "package Materials { attribute def Steel; }",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("unrelated.sysml"),
+        "package Unrelated { part def Boat; }",
+    )
+    .unwrap();
+}
+
+/// Names of all named elements in an exported XMI model.
+fn exported_names(xmi: &[u8]) -> Vec<String> {
+    let model = Xmi.read(xmi).expect("Should read XMI");
+    model
+        .elements
+        .values()
+        .filter_map(|e| e.name.as_deref().map(str::to_string))
+        .collect()
+}
+
+#[test]
+fn test_export_package_includes_dependencies() {
+    let temp_dir = TempDir::new().unwrap();
+    write_workspace(temp_dir.path());
+
+    let (xmi, _) = export_model_with_options(
+        temp_dir.path(),
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter {
+            packages: vec!["Vehicles".to_string()],
+            ..ExportFilter::default()
+        },
+    )
+    .unwrap();
+    let names = exported_names(&xmi);
+
+    for expected in ["Vehicles", "Car", "Parts", "Engine", "Materials", "Steel"] {
+        assert!(
+            names.iter().any(|n| n == expected),
+            "missing {}: {:?}",
+            expected,
+            names
+        );
+    }
+    assert!(!names.iter().any(|n| n == "Unrelated" || n == "Boat"));
+}
+
+#[test]
+fn test_export_unknown_package_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    write_workspace(temp_dir.path());
+
+    let result = export_model_with_options(
+        temp_dir.path(),
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter {
+            packages: vec!["Nope".to_string()],
+            ..ExportFilter::default()
+        },
+    );
+    assert!(result.unwrap_err().contains("Unknown package: Nope"));
+}
+
+#[test]
+fn test_cli_export_repeated_package() {
+    let temp_dir = TempDir::new().unwrap();
+    write_workspace(temp_dir.path());
+    let xmi_path = temp_dir.path().join("delivery.xmi");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args([
+            "--export",
+            "xmi",
+            "--no-stdlib",
+            "--package",
+            "Materials",
+            "--package",
+            "Unrelated",
+            "-o",
+        ])
+        .arg(&xmi_path)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let names = exported_names(&fs::read(&xmi_path).unwrap());
+    assert!(names.iter().any(|n| n == "Steel"));
+    assert!(names.iter().any(|n| n == "Boat"));
+    assert!(!names.iter().any(|n| n == "Vehicles" || n == "Engine"));
+}
+
+#[test]
+fn test_dependency_closure_lists_packages() {
+    let temp_dir = TempDir::new().unwrap();
+    write_workspace(temp_dir.path());
+
+    let report = dependency_closure(
+        temp_dir.path(),
+        &["Parts".to_string()],
+        &AnalysisOptions::new(),
+    )
+    .unwrap();
+
+    assert_eq!(report.packages, vec!["Materials", "Parts"]);
+    let names: Vec<_> = report
+        .elements
+        .iter()
+        .map(|e| e.qualified_name.as_str())
+        .collect();
+    assert!(names.contains(&"Parts::Engine"));
+    assert!(names.contains(&"Materials::Steel"));
+    assert!(!names.iter().any(|n| n.starts_with("Vehicles")));
+    assert_eq!(report.library_count(), 0);
+}
+
+#[test]
+fn test_cli_closure_export() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir_all(&src).unwrap();
+    write_workspace(&src);
+    let xmi_path = temp_dir.path().join("closure.xmi");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["closure", "Parts", "--no-stdlib", "--src"])
+        .arg(&src)
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.contains("Closure of Parts"));
+    assert!(stdout.contains("Materials::Steel"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args([
+            "closure",
+            "Parts",
+            "--no-stdlib",
+            "--export",
+            "xmi",
+            "--src",
+        ])
+        .arg(&src)
+        .arg("-o")
+        .arg(&xmi_path)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let names = exported_names(&fs::read(&xmi_path).unwrap());
+    assert!(names.iter().any(|n| n == "Steel"));
+    assert!(!names.iter().any(|n| n == "Car"));
+}
@@ -0,0 +1,115 @@
+//! Integration tests for template-based code generation.
+//!
+//! Tests that `syster generate --template T --out DIR` renders Tera templates
+//! against the model's elements, following members and types by name.
+
+#![cfg(feature = "codegen")]
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, CodegenModel};
+use tempfile::TempDir;
+
+/// C header template listing every part definition as a struct.
+const STRUCTS_TEMPLATE: &str = "\
+{% for def in elements | filter(attribute=\"kind\", value=\"PartDefinition\") -%}
+typedef struct {
+{%- for name in def.members %}{% set m = by_name[name] %}
+    {{ m.types | first | split(pat=\"::\") | last }} {{ m.name | snake_case }};
+{%- endfor %}
+} {{ def.name }};
+{% endfor %}";
+
+/// Write a model with one part definition carrying typed attributes.
+const MODELS: &[(&str, &str)] = &[(
+    "telemetry.sysml",
+    "package Telemetry {\n\
+         \x20   attribute def Float;\n\
+         \x20   part def Packet {\n\
+         \x20       attribute batteryVoltage : Float;\n\
+         \x20       attribute cpuTemp : Float;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+#[test]
+fn test_model_exposes_members_and_types() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let model = CodegenModel::load(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let packet = &model.by_name["Telemetry::Packet"];
+
+    assert_eq!(packet.kind, "PartDefinition");
+    assert_eq!(
+        packet.members,
+        vec![
+            "Telemetry::Packet::batteryVoltage",
+            "Telemetry::Packet::cpuTemp"
+        ]
+    );
+    assert_eq!(
+        model.by_name["Telemetry::Packet::cpuTemp"].types,
+        vec!["Telemetry::Float"]
+    );
+}
+
+#[test]
+fn test_render_c_structs() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let model = CodegenModel::load(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let header = model.render("structs.h", STRUCTS_TEMPLATE).unwrap();
+
+    assert!(header.contains("typedef struct {"), "{}", header);
+    assert!(header.contains("    Float battery_voltage;"), "{}", header);
+    assert!(header.contains("    Float cpu_temp;"), "{}", header);
+    assert!(header.contains("} Packet;"), "{}", header);
+}
+
+#[test]
+fn test_cli_generate_writes_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    let out = temp_dir.path().join("gen");
+    fs::create_dir_all(&src).unwrap();
+    common::write_models(&src, MODELS);
+    let template = temp_dir.path().join("structs.h.tera");
+    fs::write(&template, STRUCTS_TEMPLATE).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["generate", "--no-stdlib", "--template"])
+        .arg(&template)
+        .arg("--out")
+        .arg(&out)
+        .arg("--src")
+        .arg(&src)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let header = fs::read_to_string(out.join("structs.h")).unwrap();
+    assert!(header.contains("Float cpu_temp;"), "{}", header);
+
+    // Template errors name the template
+    let broken = temp_dir.path().join("broken.txt.tera");
+    fs::write(&broken, "{{ nope.field }}").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["generate", "--no-stdlib", "--template"])
+        .arg(&broken)
+        .arg("--out")
+        .arg(&out)
+        .arg("--src")
+        .arg(&src)
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("broken.txt.tera"));
+}
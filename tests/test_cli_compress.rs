@@ -0,0 +1,138 @@
+//! Integration tests for compressed exports.
+//!
+//! Tests that `--compress` gzips or zstd-compresses text exports and writes
+//! KPAR entries with zstd, and that compressed files are detected and read
+//! back on import.
+
+#![cfg(feature = "interchange")]
+
+mod common;
+
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, Compression, ExportFilter, compress_export, export_model_with_options,
+    import_model,
+};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicles.sysml",
+    "package Vehicles {\n\
+         \x20   part def Engine;\n\
+         \x20   part def Vehicle {\n\
+         \x20       part engine : Engine;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+fn export(dir: &Path, format: &str) -> Vec<u8> {
+    let (bytes, _) = export_model_with_options(
+        dir,
+        format,
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter::default(),
+    )
+    .unwrap();
+    bytes
+}
+
+#[test]
+fn test_compressed_xmi() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    common::write_models(&src, MODELS);
+    let xmi = export(&src, "xmi");
+
+    let gzip = compress_export(xmi.clone(), "xmi", Compression::Gzip).unwrap();
+    assert!(gzip.starts_with(&[0x1f, 0x8b]));
+    let zstd = compress_export(xmi.clone(), "xmi", Compression::Zstd).unwrap();
+    assert!(zstd.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]));
+    assert_eq!(
+        compress_export(xmi.clone(), "xmi", Compression::None).unwrap(),
+        xmi
+    );
+
+    let plain = temp_dir.path().join("model.xmi");
+    fs::write(&plain, &xmi).unwrap();
+    let expected = import_model(&plain, None, false).unwrap();
+    for (name, bytes) in [("model.xmi.gz", gzip), ("model.xmi.zst", zstd)] {
+        let path = temp_dir.path().join(name);
+        fs::write(&path, bytes).unwrap();
+        let result = import_model(&path, None, false).unwrap();
+        assert_eq!(result.element_count, expected.element_count, "{}", name);
+        assert_eq!(result.error_count, 0, "{}", name);
+    }
+}
+
+#[test]
+fn test_zstd_kpar() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    common::write_models(&src, MODELS);
+    let kpar = export(&src, "kpar");
+
+    let compressed = compress_export(kpar.clone(), "kpar", Compression::Zstd).unwrap();
+    let mut archive = zip::ZipArchive::new(Cursor::new(&compressed)).unwrap();
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i).unwrap();
+        assert_eq!(entry.compression(), zip::CompressionMethod::Zstd);
+    }
+
+    let path = temp_dir.path().join("model.kpar");
+    fs::write(&path, compressed).unwrap();
+    let result = import_model(&path, None, false).unwrap();
+    assert_eq!(result.error_count, 0);
+    assert!(result.element_count >= 4);
+
+    // A KPAR is already an archive; only its entries can be compressed
+    let err = compress_export(kpar, "kpar", Compression::Gzip).unwrap_err();
+    assert!(err.contains("zstd"), "{}", err);
+}
+
+#[test]
+fn test_cli_compress() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let out = temp_dir.path().join("model.jsonld.zst");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args([
+            "--no-stdlib",
+            "--export",
+            "jsonld",
+            "--compress",
+            "zstd",
+            "-o",
+        ])
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    assert!(
+        fs::read(&out)
+            .unwrap()
+            .starts_with(&[0x28, 0xb5, 0x2f, 0xfd])
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("--import")
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    // --compress needs --export
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--compress", "gzip"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
@@ -0,0 +1,121 @@
+//! Integration tests for documentation coverage.
+//!
+//! Tests parsing `--require-docs` kinds, warnings for undocumented elements
+//! of the required kinds, and the `--min-doc-coverage` gate.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+use syster::hir::SymbolKind;
+use syster_cli::{AnalysisOptions, parse_doc_kind, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// Two of three definitions documented; one requirement without docs.
+const MODEL: &str = "package Vehicle {\n\
+                     \x20   part def Car {\n\
+                     \x20       doc /* A road vehicle. */\n\
+                     \x20   }\n\
+                     \x20   part def Engine {\n\
+                     \x20       doc /* Turns fuel into torque. */\n\
+                     \x20   }\n\
+                     \x20   requirement def MaxSpeed;\n\
+                     }\n";
+
+const MODELS: &[(&str, &str)] = &[("vehicle.sysml", MODEL)];
+
+fn run_cli(file: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(file)
+        .arg("--no-stdlib")
+        .args(args)
+        .output()
+        .expect("Should run CLI")
+}
+
+#[test]
+fn test_parse_doc_kind() {
+    assert_eq!(parse_doc_kind("partdef"), Ok(SymbolKind::PartDefinition));
+    assert_eq!(
+        parse_doc_kind("RequirementDef"),
+        Ok(SymbolKind::RequirementDefinition)
+    );
+    assert_eq!(
+        parse_doc_kind("use-case-def"),
+        Ok(SymbolKind::UseCaseDefinition)
+    );
+
+    let err = parse_doc_kind("widget").unwrap_err();
+    assert!(err.contains("Unknown element kind 'widget'"), "{}", err);
+    assert!(err.contains("partdef"), "{}", err);
+}
+
+#[test]
+fn test_required_kinds_warn_and_measure() {
+    let sources = [("vehicle.sysml".to_string(), MODEL.to_string())];
+
+    let result = run_analysis_from_sources(&sources, &AnalysisOptions::default());
+    assert_eq!(
+        (result.doc_coverage.documented, result.doc_coverage.total),
+        (2, 3)
+    );
+    assert!(
+        result
+            .diagnostics
+            .iter()
+            .all(|d| d.code.as_deref() != Some("L0005"))
+    );
+
+    let options = AnalysisOptions {
+        require_docs: vec![SymbolKind::RequirementDefinition],
+        ..Default::default()
+    };
+    let result = run_analysis_from_sources(&sources, &options);
+    assert_eq!(
+        (result.doc_coverage.documented, result.doc_coverage.total),
+        (0, 1)
+    );
+    let missing: Vec<_> = result
+        .diagnostics
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("L0005"))
+        .collect();
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].line, 8);
+    assert_eq!(
+        missing[0].message,
+        "requirement def 'Vehicle::MaxSpeed' has no documentation"
+    );
+}
+
+#[test]
+fn test_cli_min_doc_coverage_gate() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let output = run_cli(&file, &["--min-doc-coverage", "60"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(
+        stdout.contains("Documentation coverage: 2/3 (66.7%)"),
+        "{}",
+        stdout
+    );
+
+    let output = run_cli(&file, &["--min-doc-coverage", "80"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("Documentation coverage 66.7% is below the minimum of 80%"),
+        "{}",
+        stderr
+    );
+
+    let output = run_cli(
+        &file,
+        &["--json", "--require-docs", "partdef,requirementdef"],
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["doc_coverage"]["documented"], 2);
+    assert_eq!(json["doc_coverage"]["total"], 3);
+}
@@ -0,0 +1,112 @@
+//! Integration tests for crash report bundles.
+//!
+//! Tests source redaction, capturing a panic with the panic hook, the
+//! contents of the zip archive, and that `--crash-report` leaves runs that
+//! do not panic alone.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::io::Read;
+use std::process::Command;
+use syster_cli::{
+    CrashRedaction, crash_report, install_panic_hook, redact_source, write_crash_report,
+};
+use tempfile::TempDir;
+use zip::ZipArchive;
+
+const MODEL: &str = "package Secret {\n\
+                     \x20   // supplier: Acme\n\
+                     \x20   part def Engine { doc /* 300 kW */ }\n\
+                     \x20   part engine : Engine;\n\
+                     }\n";
+
+#[test]
+fn test_redact_source() {
+    let redacted = redact_source(MODEL);
+
+    for secret in ["Secret", "Engine", "Acme", "300 kW"] {
+        assert!(!redacted.contains(secret), "{}", redacted);
+    }
+    assert!(redacted.starts_with("package n1 {"), "{}", redacted);
+    assert!(redacted.contains("part def n2"), "{}", redacted);
+    assert!(redacted.contains("part n3 : n2;"), "{}", redacted);
+    assert_eq!(redacted.lines().count(), MODEL.lines().count());
+}
+
+#[test]
+fn test_crash_report_bundle() {
+    let temp_dir = TempDir::new().unwrap();
+    let model = temp_dir.path().join("vendor.sysml");
+    fs::write(&model, MODEL).unwrap();
+
+    install_panic_hook();
+    let path = model.to_string_lossy().to_string();
+    let message = format!("malformed vendor export in '{}':3", path);
+    assert!(std::panic::catch_unwind(|| panic!("{}", message)).is_err());
+    let args = vec!["syster".to_string(), format!("--src={}", path)];
+    let report = crash_report(Some(&model), &args, CrashRedaction::Names);
+
+    // Model paths in the message and the command line are redacted
+    assert_eq!(
+        report.message,
+        "malformed vendor export in '<model>.sysml':3"
+    );
+    assert_eq!(report.args, vec!["syster", "--src=<model>.sysml"]);
+    assert!(
+        report
+            .location
+            .as_deref()
+            .unwrap()
+            .contains("test_cli_crash.rs")
+    );
+    assert!(!report.backtrace.is_empty());
+    assert_eq!(report.file_size, Some(MODEL.len() as u64));
+
+    let bundle = temp_dir.path().join("crash.zip");
+    write_crash_report(&report, &bundle).unwrap();
+    let mut archive = ZipArchive::new(fs::File::open(&bundle).unwrap()).unwrap();
+    let mut read = |name: &str| {
+        let mut text = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        text
+    };
+    let json: serde_json::Value = serde_json::from_str(&read("crash.json")).unwrap();
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    assert_eq!(json["redaction"], "names");
+    assert!(!read("backtrace.txt").is_empty());
+    let file = read("file/vendor.sysml");
+    assert!(file.contains("part def"), "{}", file);
+    assert!(!file.contains("Secret"), "{}", file);
+
+    // Without content, only the path and size
+    let report = crash_report(Some(&model), &args, CrashRedaction::Content);
+    assert!(report.file_text.is_none());
+    assert!(report.file.is_some());
+    assert_eq!(report.args[1], "--src=<model>.sysml");
+}
+
+#[test]
+fn test_cli_crash_report_without_panic() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("model.sysml"),
+        "package P { part def A; }\n",
+    )
+    .unwrap();
+    let bundle = temp_dir.path().join("crash.zip");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .arg("--no-stdlib")
+        .arg("--crash-report")
+        .arg(&bundle)
+        .output()
+        .expect("Should run CLI");
+    assert!(output.status.success());
+    assert!(!bundle.exists());
+}
@@ -0,0 +1,71 @@
+//! Integration tests for the HTML diagnostics dashboard.
+//!
+//! Tests the summary and table of the page, the escaped source snippets
+//! with the offending span marked, and `--format html`.
+
+#![cfg(feature = "codegen")]
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{export_html, run_analysis};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "car.sysml",
+    "package Vehicle {\n\
+         \x20   doc /* Cars & <trucks> */\n\
+         \x20   part def Car;\n\
+         \x20   part wheel : Wheel;\n\
+         }\n",
+)];
+
+#[test]
+fn test_dashboard_summary_and_table() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let result = run_analysis(temp_dir.path(), false, false, None).unwrap();
+    let html = export_html(&result).unwrap();
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<b>1</b>files"), "{}", html);
+    assert!(html.contains("<b>1</b>errors"), "{}", html);
+    assert!(html.contains("<table id=\"diagnostics\">"));
+    assert!(html.contains("data-severity=\"error\""));
+    assert!(html.contains("href=\"#file-1\""));
+    assert!(html.contains("<section id=\"file-1\">"));
+    // Package chart
+    assert!(html.contains("<td>Vehicle</td>"), "{}", html);
+}
+
+#[test]
+fn test_dashboard_source_snippets() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let result = run_analysis(temp_dir.path(), false, false, None).unwrap();
+    let html = export_html(&result).unwrap();
+
+    // The reference is marked on its line, context lines are escaped
+    assert!(html.contains("<mark>Wheel</mark>"), "{}", html);
+    assert!(html.contains("Cars &amp; &lt;trucks&gt;"), "{}", html);
+    assert!(!html.contains("<trucks>"));
+}
+
+#[test]
+fn test_cli_format_html() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let report = temp_dir.path().join("report.html");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--format", "html", "-o"])
+        .arg(&report)
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    let html = fs::read_to_string(&report).unwrap();
+    assert!(html.contains("<h1>SysML Diagnostics</h1>"));
+    assert!(html.contains("car.sysml"));
+}
@@ -0,0 +1,235 @@
+//! Integration tests for decompile output layout and ordering.
+//!
+//! Tests that `--decompile --split-by-package` writes one SysML file per
+//! package, mirrors package nesting as folders, and writes a companion
+//! metadata file next to each SysML file, without packages whose names map
+//! to the same file overwriting each other; and that decompiled output is
+//! deterministic and honours `--sort`.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster::interchange::{Element, ElementId, ElementKind, Model, ModelFormat, Xmi};
+use syster_cli::{MemberOrder, decompile_model_ordered, decompile_model_split};
+use tempfile::TempDir;
+
+/// Add an element owned by `owner`, keeping both sides of the ownership in sync.
+fn add_owned(model: &mut Model, id: &str, kind: ElementKind, name: &str, owner: &str) {
+    model.add_element(
+        Element::new(ElementId::new(id), kind)
+            .with_name(name)
+            .with_owner(ElementId::new(owner)),
+    );
+    if let Some(parent) = model.get_mut(&ElementId::new(owner)) {
+        parent.owned_elements.push(ElementId::new(id));
+    }
+}
+
+/// Write a model with a grouping package `Vehicles` (containing `Parts` and
+/// `Systems`) and a standalone package `Units`.
+fn write_sample_xmi(path: &Path) {
+    let mut model = Model::new();
+    model.add_element(
+        Element::new(ElementId::new("vehicles"), ElementKind::Package).with_name("Vehicles"),
+    );
+    add_owned(
+        &mut model,
+        "parts",
+        ElementKind::Package,
+        "Parts",
+        "vehicles",
+    );
+    add_owned(
+        &mut model,
+        "wheel",
+        ElementKind::PartDefinition,
+        "Wheel",
+        "parts",
+    );
+    add_owned(
+        &mut model,
+        "systems",
+        ElementKind::Package,
+        "Systems",
+        "vehicles",
+    );
+    add_owned(
+        &mut model,
+        "car",
+        ElementKind::PartDefinition,
+        "Car",
+        "systems",
+    );
+    model.add_element(
+        Element::new(ElementId::new("units"), ElementKind::Package).with_name("Units"),
+    );
+    add_owned(
+        &mut model,
+        "mass",
+        ElementKind::AttributeDefinition,
+        "Mass",
+        "units",
+    );
+
+    let bytes = Xmi.write(&model).expect("Should write XMI");
+    fs::write(path, bytes).expect("Should write file");
+}
+
+#[test]
+fn test_decompile_split_layout() {
+    let temp_dir = TempDir::new().unwrap();
+    let xmi_path = temp_dir.path().join("model.xmi");
+    write_sample_xmi(&xmi_path);
+
+    let files = decompile_model_split(&xmi_path, None, false, MemberOrder::Source).unwrap();
+    let mut paths: Vec<PathBuf> = files.iter().map(|f| f.path.clone()).collect();
+    paths.sort();
+
+    assert_eq!(
+        paths,
+        vec![
+            PathBuf::from("Units.sysml"),
+            PathBuf::from("Vehicles/Parts.sysml"),
+            PathBuf::from("Vehicles/Systems.sysml"),
+        ]
+    );
+
+    let parts = files
+        .iter()
+        .find(|f| f.path.ends_with("Parts.sysml"))
+        .unwrap();
+    assert!(parts.sysml_text.contains("package Vehicles"));
+    assert!(parts.sysml_text.contains("Wheel"));
+    assert!(!parts.sysml_text.contains("Car"));
+    assert!(!parts.sysml_text.contains("Units"));
+    assert_eq!(
+        parts.metadata_path(),
+        PathBuf::from("Vehicles/Parts.metadata.json")
+    );
+}
+
+#[test]
+fn test_decompile_split_colliding_names() {
+    let temp_dir = TempDir::new().unwrap();
+    let xmi_path = temp_dir.path().join("model.xmi");
+    let mut model = Model::new();
+    for (id, name) in [("dotted", "A.B"), ("underscored", "A_B"), ("lower", "a_b")] {
+        model.add_element(Element::new(ElementId::new(id), ElementKind::Package).with_name(name));
+        add_owned(
+            &mut model,
+            &format!("{}-part", id),
+            ElementKind::PartDefinition,
+            &format!("{}Part", id),
+            id,
+        );
+    }
+    fs::write(&xmi_path, Xmi.write(&model).unwrap()).unwrap();
+
+    let files = decompile_model_split(&xmi_path, None, false, MemberOrder::Source).unwrap();
+    let mut paths: Vec<String> = files
+        .iter()
+        .map(|f| f.path.to_string_lossy().to_lowercase())
+        .collect();
+    paths.sort();
+    paths.dedup();
+    assert_eq!(paths.len(), 3, "{:?}", paths);
+    for name in ["dottedPart", "underscoredPart", "lowerPart"] {
+        assert_eq!(
+            files.iter().filter(|f| f.sysml_text.contains(name)).count(),
+            1,
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_cli_decompile_split_writes_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let xmi_path = temp_dir.path().join("model.xmi");
+    write_sample_xmi(&xmi_path);
+    let out_dir = temp_dir.path().join("project");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&xmi_path)
+        .args(["--decompile", "--split-by-package", "-o"])
+        .arg(&out_dir)
+        .output()
+        .expect("Should run CLI");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    for file in ["Units", "Vehicles/Parts", "Vehicles/Systems"] {
+        assert!(out_dir.join(format!("{}.sysml", file)).is_file());
+        assert!(out_dir.join(format!("{}.metadata.json", file)).is_file());
+    }
+}
+
+#[test]
+fn test_cli_split_by_package_requires_decompile() {
+    let temp_dir = TempDir::new().unwrap();
+    let xmi_path = temp_dir.path().join("model.xmi");
+    write_sample_xmi(&xmi_path);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&xmi_path)
+        .arg("--split-by-package")
+        .output()
+        .expect("Should run CLI");
+
+    assert!(!output.status.success());
+}
+
+/// Write a package whose members are declared in non-alphabetical order.
+fn write_unsorted_xmi(path: &Path) {
+    let mut model = Model::new();
+    model.add_element(Element::new(ElementId::new("pkg"), ElementKind::Package).with_name("Fleet"));
+    for (id, name) in [("z", "Zeppelin"), ("b", "Bicycle"), ("m", "Motorbike")] {
+        add_owned(&mut model, id, ElementKind::PartDefinition, name, "pkg");
+    }
+
+    let bytes = Xmi.write(&model).expect("Should write XMI");
+    fs::write(path, bytes).expect("Should write file");
+}
+
+#[test]
+fn test_decompile_is_deterministic() {
+    let temp_dir = TempDir::new().unwrap();
+    let xmi_path = temp_dir.path().join("model.xmi");
+    write_sample_xmi(&xmi_path);
+
+    let first = decompile_model_ordered(&xmi_path, None, false, MemberOrder::Source).unwrap();
+    for _ in 0..5 {
+        let again = decompile_model_ordered(&xmi_path, None, false, MemberOrder::Source).unwrap();
+        assert_eq!(again.sysml_text, first.sysml_text);
+        assert_eq!(again.metadata_json, first.metadata_json);
+    }
+}
+
+#[test]
+fn test_decompile_member_order() {
+    let temp_dir = TempDir::new().unwrap();
+    let xmi_path = temp_dir.path().join("fleet.xmi");
+    write_unsorted_xmi(&xmi_path);
+
+    let position = |text: &str, name: &str| text.find(name).unwrap();
+
+    let source = decompile_model_ordered(&xmi_path, None, false, MemberOrder::Source).unwrap();
+    let text = &source.sysml_text;
+    assert!(position(text, "Zeppelin") < position(text, "Bicycle"));
+    assert!(position(text, "Bicycle") < position(text, "Motorbike"));
+
+    let sorted =
+        decompile_model_ordered(&xmi_path, None, false, MemberOrder::Alphabetical).unwrap();
+    let text = &sorted.sysml_text;
+    assert!(position(text, "Bicycle") < position(text, "Motorbike"));
+    assert!(position(text, "Motorbike") < position(text, "Zeppelin"));
+
+    // Metadata keeps the source order so re-export restores it
+    assert_eq!(sorted.metadata_json, source.metadata_json);
+}
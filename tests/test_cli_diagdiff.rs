@@ -0,0 +1,89 @@
+//! Integration tests for `syster diag-diff`.
+//!
+//! Tests that diagnostics are sorted into new, fixed and persisting ones,
+//! that moved diagnostics still match within the line fuzz, and the exit
+//! code of the command.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, diff_diagnostics, export_json, run_analysis_from_sources};
+use tempfile::TempDir;
+
+fn analyze(model: &str) -> syster_cli::AnalysisResult {
+    run_analysis_from_sources(
+        &[("car.sysml".to_string(), model.to_string())],
+        &AnalysisOptions::new(),
+    )
+}
+
+/// Old run: `Wheel` and `Axle` unresolved.
+const OLD: &str = "package Vehicle {\n    part wheel : Wheel;\n    part axle : Axle;\n}\n";
+
+/// New run: two lines inserted, `Axle` fixed, `Brake` added.
+const NEW: &str = "package Vehicle {\n    part def Axle;\n    part def Body;\n    \
+                   part wheel : Wheel;\n    part axle : Axle;\n    part brake : Brake;\n}\n";
+
+#[test]
+fn test_diff_diagnostics() {
+    let old = analyze(OLD);
+    let new = analyze(NEW);
+
+    let diff = diff_diagnostics(&old, &new, 20);
+    assert_eq!(diff.new.len(), 1, "{:?}", diff);
+    assert!(diff.new[0].message.contains("Brake"));
+    assert_eq!(diff.fixed.len(), 1, "{:?}", diff);
+    assert!(diff.fixed[0].message.contains("Axle"));
+    assert_eq!(diff.persisting.len(), 1);
+    assert!(diff.persisting[0].message.contains("Wheel"));
+    assert_eq!(diff.persisting[0].line, 4);
+}
+
+#[test]
+fn test_line_fuzz() {
+    let old = analyze(OLD);
+    let new = analyze(NEW);
+
+    // Wheel moved two lines down: too far for a fuzz of 1
+    let diff = diff_diagnostics(&old, &new, 1);
+    assert_eq!(diff.new.len(), 2, "{:?}", diff);
+    assert_eq!(diff.fixed.len(), 2, "{:?}", diff);
+    assert!(diff.persisting.is_empty());
+}
+
+#[test]
+fn test_cli_diag_diff() {
+    let temp_dir = TempDir::new().unwrap();
+    let old_json = temp_dir.path().join("old.json");
+    let new_json = temp_dir.path().join("new.json");
+    fs::write(&old_json, export_json(&analyze(OLD)).unwrap()).unwrap();
+    fs::write(&new_json, export_json(&analyze(NEW)).unwrap()).unwrap();
+
+    let run = |old: &Path, new: &Path| {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg("diag-diff")
+            .args([old, new])
+            .output()
+            .expect("Should run CLI")
+    };
+
+    let output = run(&old_json, &new_json);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("New (1):"), "{}", stdout);
+    assert!(stdout.contains("Fixed (1):"), "{}", stdout);
+    assert!(stdout.contains("Persisting: 1"), "{}", stdout);
+
+    // Fixing Brake only fixes things
+    let fixed_json = temp_dir.path().join("fixed.json");
+    let fixed = NEW.replace("part brake : Brake;", "part def Brake;");
+    fs::write(&fixed_json, export_json(&analyze(&fixed)).unwrap()).unwrap();
+    let output = run(&new_json, &fixed_json);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("No new diagnostics (1 fixed, 1 persisting)"),
+        "{}",
+        stdout
+    );
+}
@@ -0,0 +1,99 @@
+//! Integration tests for workspace-wide duplicate-definition detection.
+//!
+//! Tests that `--duplicates` reports every definition of a qualified name
+//! defined in more than one file, with the other definitions as related
+//! information, at the requested severity.
+
+mod common;
+
+use std::process::Command;
+use syster::hir::Severity;
+use syster_cli::{AnalysisOptions, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// `Vehicle::Engine` copy-pasted into a second file, and package `Vehicle`
+/// spread over both.
+const MODELS: &[(&str, &str)] = &[
+    (
+        "engine.sysml",
+        "package Vehicle {\n    part def Engine;\n}\n",
+    ),
+    (
+        "engine_copy.sysml",
+        "package Vehicle {\n    part def Wheel;\n    part def Engine;\n}\n",
+    ),
+];
+
+fn sources() -> Vec<(String, String)> {
+    MODELS
+        .iter()
+        .map(|(name, text)| (name.to_string(), text.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_duplicates_across_files() {
+    let options = AnalysisOptions {
+        duplicates: Some(Severity::Warning),
+        ..Default::default()
+    };
+    let result = run_analysis_from_sources(&sources(), &options);
+
+    let duplicates: Vec<_> = result
+        .diagnostics
+        .iter()
+        .filter(|d| d.message.contains("duplicate definition"))
+        .collect();
+    assert_eq!(duplicates.len(), 2, "{:?}", result.diagnostics);
+    assert_eq!(result.warning_count, 2);
+    assert_eq!(result.error_count, 0);
+
+    let (first, second) = (duplicates[0], duplicates[1]);
+    assert!(first.message.contains("'Vehicle::Engine'"));
+    assert!(first.file.ends_with("engine.sysml"));
+    assert_eq!(first.line, 2);
+    assert!(second.file.ends_with("engine_copy.sysml"));
+    assert_eq!(second.line, 3);
+
+    // Each occurrence points at the other one
+    assert_eq!(first.related.len(), 1);
+    assert_eq!(first.related[0].file, second.file);
+    assert_eq!(first.related[0].line, 3);
+    assert_eq!(second.related[0].file, first.file);
+}
+
+#[test]
+fn test_duplicates_off_by_default() {
+    let result = run_analysis_from_sources(&sources(), &AnalysisOptions::default());
+    assert!(
+        result
+            .diagnostics
+            .iter()
+            .all(|d| !d.message.contains("duplicate definition") && d.related.is_empty())
+    );
+}
+
+#[test]
+fn test_cli_duplicates_as_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--json", "--duplicates", "error"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "stdout: {}", stdout);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(json["error_count"], 2);
+    let diagnostics = json["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics[0]["severity"], "error");
+    assert!(
+        diagnostics[0]["related"][0]["file"]
+            .as_str()
+            .unwrap()
+            .ends_with("engine_copy.sysml")
+    );
+}
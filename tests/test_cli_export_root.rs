@@ -0,0 +1,106 @@
+//! Integration tests for exporting a single element subtree.
+//!
+//! Tests that `--root` keeps the element and its contents, adds stubs
+//! marked `isExternal` for what they reference outside it, and rejects an
+//! unknown root.
+
+#![cfg(feature = "interchange")]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, ExportFilter, export_model_with_options};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicles.sysml",
+    "package Vehicles {\n\
+         \x20   part def Engine;\n\
+         \x20   part def Trailer;\n\
+         \x20   part def Vehicle {\n\
+         \x20       part engine : Engine;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+/// The named objects of a JSON-LD export, by name.
+fn export_root(dir: &Path, root: &str) -> Result<Vec<serde_json::Value>, String> {
+    let filter = ExportFilter {
+        root: Some(root.to_string()),
+        ..ExportFilter::default()
+    };
+    let (bytes, _) =
+        export_model_with_options(dir, "jsonld", &AnalysisOptions::default(), false, &filter)?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+    Ok(value
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|item| item.get("name").is_some())
+        .cloned()
+        .collect())
+}
+
+fn named<'a>(items: &'a [serde_json::Value], name: &str) -> Option<&'a serde_json::Value> {
+    items.iter().find(|item| item["name"] == name)
+}
+
+#[test]
+fn test_export_root_subtree() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let items = export_root(temp_dir.path(), "Vehicles::Vehicle").unwrap();
+    let vehicle = named(&items, "Vehicle").unwrap();
+    assert!(vehicle.get("isExternal").is_none());
+    assert!(named(&items, "engine").is_some());
+    // Referenced from inside, so kept as a stub
+    assert_eq!(named(&items, "Engine").unwrap()["isExternal"], true);
+    // Neither contained nor referenced
+    assert!(named(&items, "Trailer").is_none());
+    assert!(named(&items, "Vehicles").is_none());
+}
+
+#[test]
+fn test_export_unknown_root() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let err = export_root(temp_dir.path(), "Vehicles::Bicycle").unwrap_err();
+    assert!(err.contains("No element 'Vehicles::Bicycle'"), "{}", err);
+}
+
+#[test]
+fn test_cli_export_root() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let out = temp_dir.path().join("vehicle.jsonld");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args([
+            "--no-stdlib",
+            "--export",
+            "jsonld",
+            "--root",
+            "Vehicles::Vehicle",
+            "-o",
+        ])
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let jsonld = fs::read_to_string(&out).unwrap();
+    assert!(jsonld.contains("\"isExternal\": true"), "{}", jsonld);
+    assert!(!jsonld.contains("\"Trailer\""));
+
+    // --root only applies to exports
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--root", "Vehicles::Vehicle"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
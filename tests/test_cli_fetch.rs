@@ -0,0 +1,177 @@
+//! Integration tests for model package dependencies.
+//!
+//! Tests reading `[dependencies]` from `syster.toml`, `syster fetch` cloning
+//! git sources and downloading files into `.syster/deps`, and analysis
+//! resolving references into the fetched packages.
+
+mod common;
+
+use common::git;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{DependencySource, Manifest};
+use tempfile::TempDir;
+
+fn syster(dir: &Path, args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_syster"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .expect("Should run CLI")
+}
+
+#[test]
+fn test_manifest_dependencies() {
+    let temp_dir = TempDir::new().unwrap();
+    let manifest_path = temp_dir.path().join("syster.toml");
+    fs::write(
+        &manifest_path,
+        "[lint.naming]\nacronyms = [\"ECU\"]\n\n[dependencies]\n\
+         vehicles = { version = \"1.2.0\", git = \"https://example.com/vehicles.git\" }\n\
+         common = { path = \"../common\" }\n",
+    )
+    .unwrap();
+
+    let manifest = Manifest::discover(temp_dir.path()).unwrap().unwrap();
+    let names: Vec<&str> = manifest
+        .dependencies
+        .iter()
+        .map(|d| d.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["common", "vehicles"]);
+    assert_eq!(
+        manifest.dependencies[1].source,
+        DependencySource::Git {
+            url: "https://example.com/vehicles.git".to_string(),
+            rev: None
+        }
+    );
+    assert!(
+        manifest
+            .dependency_dir(&manifest.dependencies[1])
+            .ends_with(".syster/deps/vehicles-1.2.0")
+    );
+    let err = manifest.library_paths().unwrap_err();
+    assert_eq!(
+        err,
+        format!(
+            "Dependency 'common' not found at {}",
+            temp_dir.path().join("../common").display()
+        )
+    );
+
+    fs::write(
+        &manifest_path,
+        "[dependencies]\nboth = { url = \"https://example.com/a.sysml\", path = \"a\" }\n",
+    )
+    .unwrap();
+    let err = Manifest::load(&manifest_path).unwrap_err();
+    assert!(
+        err.contains("dependency 'both' needs exactly one of `git`, `url` or `path`"),
+        "{}",
+        err
+    );
+
+    // A version can't reach outside the cache directory
+    fs::write(
+        &manifest_path,
+        "[dependencies]\nvehicles = { version = \"x/../../../victim\", git = \"https://example.com/v.git\" }\n",
+    )
+    .unwrap();
+    let err = Manifest::load(&manifest_path).unwrap_err();
+    assert!(
+        err.contains("dependency 'vehicles': `version` 'x/../../../victim' may only contain"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_fetch_git_and_url_dependencies() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // A tagged dependency repository
+    let upstream = temp_dir.path().join("vehicles");
+    fs::create_dir(&upstream).unwrap();
+    git(&upstream, &["init", "-q"]);
+    fs::write(
+        upstream.join("vehicles.sysml"),
+        "package Vehicles { part def Car; }\n",
+    )
+    .unwrap();
+    git(&upstream, &["add", "."]);
+    git(&upstream, &["commit", "-q", "-m", "release"]);
+    git(&upstream, &["tag", "v1.0.0"]);
+    fs::write(upstream.join("vehicles.sysml"), "package Vehicles { }\n").unwrap();
+    git(&upstream, &["commit", "-q", "-am", "unreleased"]);
+
+    // A single-file package served over file:// URLs
+    let units = temp_dir.path().join("units.sysml");
+    fs::write(&units, "package Units { attribute def Speed; }\n").unwrap();
+
+    let project = temp_dir.path().join("project");
+    fs::create_dir(&project).unwrap();
+    fs::write(
+        project.join("syster.toml"),
+        format!(
+            "[dependencies]\nvehicles = {{ version = \"1.0.0\", git = \"{}\" }}\n\
+             units = {{ version = \"2.0\", url = \"file://{}\" }}\n",
+            upstream.display(),
+            units.display()
+        ),
+    )
+    .unwrap();
+    fs::write(
+        project.join("garage.sysml"),
+        "package Garage {\n    part car : Vehicles::Car {\n        attribute top : Units::Speed;\n    }\n}\n",
+    )
+    .unwrap();
+
+    let output = syster(&project, &["garage.sysml", "--no-stdlib"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("has not been fetched (run `syster fetch`)"),
+        "{}",
+        stderr
+    );
+
+    let output = syster(&project, &["fetch"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stdout: {}\nstderr: {}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Fetched vehicles"), "{}", stdout);
+    assert!(stdout.contains("Fetched units"), "{}", stdout);
+    let cached = project.join(".syster/deps/vehicles-1.0.0");
+    assert!(cached.join("vehicles.sysml").is_file());
+    assert!(!cached.join(".git").exists());
+    assert!(project.join(".syster/deps/units-2.0/units.sysml").is_file());
+
+    // Only the project's own file is analyzed and reported
+    let output = syster(&project, &["garage.sysml", "--no-stdlib"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Analyzed 1 files"), "{}", stdout);
+
+    let output = syster(&project, &["fetch"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Cached vehicles"), "{}", stdout);
+}
+
+#[test]
+fn test_fetch_without_manifest() {
+    let temp_dir = TempDir::new().unwrap();
+    let output = syster(temp_dir.path(), &["fetch"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("No syster.toml found"), "{}", stderr);
+}
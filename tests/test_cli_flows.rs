@@ -0,0 +1,186 @@
+//! Integration tests for port direction and flow consistency.
+//!
+//! Tests conjugated and delegated ports, end-to-end signal paths, flagging
+//! connections with clashing directions and flows without a producer or
+//! consumer, and `syster flows`.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, FlowIssue, PortDirection, check_flows};
+use tempfile::TempDir;
+
+fn write_model(dir: &Path, car: &str) {
+    let model = format!(
+        "package Signals {{\n\
+         \x20   item def Fuel;\n\
+         \x20   attribute def Speed;\n\
+         \x20   port def FuelPort {{\n\
+         \x20       out item fuel : Fuel;\n\
+         \x20   }}\n\
+         \x20   port def SpeedPort {{\n\
+         \x20       out attribute speed : Speed;\n\
+         \x20   }}\n\
+         }}\n\
+         package Vehicle {{\n\
+         \x20   import Signals::*;\n\
+         \x20   part def Tank {{\n\
+         \x20       port fuelOut : FuelPort;\n\
+         \x20   }}\n\
+         \x20   part def Pump {{\n\
+         \x20       port fuelIn : ~FuelPort;\n\
+         \x20       port fuelOut : FuelPort;\n\
+         \x20       flow from fuelIn to fuelOut;\n\
+         \x20   }}\n\
+         \x20   part def Injector {{\n\
+         \x20       port fuelIn : ~FuelPort;\n\
+         \x20   }}\n\
+         \x20   part def Engine {{\n\
+         \x20       port fuelIn : ~FuelPort;\n\
+         \x20       part injector : Injector;\n\
+         \x20       connect fuelIn to injector.fuelIn;\n\
+         \x20   }}\n\
+         \x20   part def Wheel {{\n\
+         \x20       port speedOut : SpeedPort;\n\
+         \x20   }}\n\
+         \x20   part def Gauge {{\n\
+         \x20       port speedIn : SpeedPort;\n\
+         \x20   }}\n\
+         \x20   part car {{\n\
+         \x20       part tank : Tank;\n\
+         \x20       part pump : Pump;\n\
+         \x20       part engine : Engine;\n\
+         \x20       part wheel : Wheel;\n\
+         \x20       part gauge : Gauge;\n\
+         \x20       connect tank.fuelOut to pump.fuelIn;\n\
+         \x20       flow of Fuel from pump.fuelOut to engine.fuelIn;\n\
+         {}\
+         \x20   }}\n\
+         }}\n",
+        car
+    );
+    common::write_models(dir, &[("vehicle.sysml", &model)]);
+}
+
+const BROKEN: &str = "        connect wheel.speedOut to gauge.speedIn;\n\
+                      \x20       flow of Fuel from engine.fuelIn to tank.fuelOut;\n\
+                      \x20       flow of Speed from pump.fuelOut to engine.fuelIn;\n\
+                      \x20       flow of Fuel from pump.fuelOut to tank.fuelOut;\n";
+
+#[test]
+fn test_signal_paths_through_conjugated_and_delegated_ports() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path(), "");
+
+    let report = check_flows(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    assert!(report.is_clean(), "{:?}", report.issues);
+    assert_eq!(report.connections, 4);
+    assert_eq!(report.signals.len(), 1);
+    let fuel = &report.signals[0];
+    assert_eq!(fuel.signal, "Signals::FuelPort::fuel");
+    assert_eq!(fuel.hops.len(), 4);
+    assert_eq!(fuel.hops[0].via, "flow from fuelIn to fuelOut");
+    assert_eq!(fuel.hops[0].line, 19);
+    assert_eq!(
+        fuel.paths,
+        vec![vec![
+            "Vehicle::Tank::fuelOut",
+            "Vehicle::Pump::fuelIn",
+            "Vehicle::Pump::fuelOut",
+            "Vehicle::Engine::fuelIn",
+            "Vehicle::Injector::fuelIn",
+        ]]
+    );
+}
+
+#[test]
+fn test_direction_and_flow_issues() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path(), BROKEN);
+
+    let report = check_flows(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    assert_eq!(report.connections, 8);
+    let issues: Vec<String> = report
+        .issues
+        .iter()
+        .map(|issue| {
+            let text = issue.to_string();
+            text[text.find(": ").unwrap() + 2..].to_string()
+        })
+        .collect();
+    assert_eq!(
+        issues,
+        vec![
+            "connect wheel.speedOut to gauge.speedIn: 'speed' is out at wheel.speedOut and out at gauge.speedIn",
+            "flow from engine.fuelIn to tank.fuelOut: no producer, engine.fuelIn produces nothing the flow carries",
+            "flow from pump.fuelOut to engine.fuelIn: no producer, pump.fuelOut produces nothing the flow carries",
+            "flow from pump.fuelOut to tank.fuelOut: no consumer, tank.fuelOut consumes nothing the flow carries",
+        ]
+    );
+    assert!(matches!(
+        &report.issues[0],
+        FlowIssue::Direction {
+            source_direction: PortDirection::Out,
+            target_direction: PortDirection::Out,
+            line: 43,
+            ..
+        }
+    ));
+    // The clean part of the model still traces end to end
+    assert_eq!(report.signals[0].paths[0].len(), 5);
+}
+
+#[test]
+fn test_model_without_ports() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("a.sysml"),
+        "package P {\n    part def A;\n    part a : A;\n    part b : A;\n    connect a to b;\n}\n",
+    )
+    .unwrap();
+
+    let report = check_flows(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    assert_eq!(report.connections, 1);
+    assert!(report.signals.is_empty());
+    assert!(report.is_clean());
+}
+
+#[test]
+fn test_cli_flows() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path(), "");
+
+    let run = || {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .args(["flows", "--no-stdlib", "--src"])
+            .arg(temp_dir.path())
+            .output()
+            .expect("Should run CLI")
+    };
+
+    let output = run();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stdout.starts_with("Signals::FuelPort::fuel\n  Vehicle::Tank::fuelOut → "),
+        "stdout: {}",
+        stdout
+    );
+    assert!(stderr.contains("✓ Checked 4 connections and flows: 1 signals, no issues"));
+
+    write_model(temp_dir.path(), BROKEN);
+    let output = run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("✗ Checked 8 connections and flows: 4 issues"),
+        "stderr: {}",
+        stderr
+    );
+}
@@ -0,0 +1,98 @@
+//! Integration tests for the glossary report.
+//!
+//! Tests that `syster glossary` lists named definitions with their
+//! documentation grouped by package, and can be restricted to definitions
+//! carrying a metadata annotation.
+
+#![cfg(feature = "codegen")]
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, CodegenModel, DocumentFormat, collect_glossary, render_glossary,
+};
+use tempfile::TempDir;
+
+/// Write two packages of definitions, one of them tagged as a glossary term.
+const MODELS: &[(&str, &str)] = &[(
+    "terms.sysml",
+    "package Terms {\n\
+         \x20   metadata def Term;\n\
+         \x20   part def Vehicle {\n\
+         \x20       doc /* A machine that transports people or cargo. */\n\
+         \x20       @Term;\n\
+         \x20   }\n\
+         \x20   attribute def Mass;\n\
+         \x20   part vehicle : Vehicle;\n\
+         \x20   package Power {\n\
+         \x20       part def battery { doc /* Stores electrical energy. */ }\n\
+         \x20   }\n\
+         }\n",
+)];
+
+#[test]
+fn test_glossary_groups_definitions_by_package() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let model = CodegenModel::load(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let glossary = collect_glossary(&model, None);
+
+    let packages: Vec<_> = glossary
+        .packages
+        .iter()
+        .map(|p| p.qualified_name.as_str())
+        .collect();
+    assert_eq!(packages, vec!["Terms", "Terms::Power"]);
+
+    let terms: Vec<_> = glossary.packages[0]
+        .terms
+        .iter()
+        .map(|t| t.name.as_str())
+        .collect();
+    assert_eq!(terms, vec!["Mass", "Term", "Vehicle"]);
+    assert_eq!(
+        glossary.packages[1].terms[0].doc,
+        "Stores electrical energy."
+    );
+}
+
+#[test]
+fn test_glossary_metadata_filter_and_csv() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let model = CodegenModel::load(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let glossary = collect_glossary(&model, Some("Term"));
+    assert_eq!(glossary.packages.len(), 1);
+    assert_eq!(glossary.packages[0].terms.len(), 1);
+
+    let csv = render_glossary(&glossary, DocumentFormat::Csv).unwrap();
+    assert_eq!(
+        csv.lines().nth(1),
+        Some("Terms,Vehicle,Terms::Vehicle,Part def,A machine that transports people or cargo.")
+    );
+}
+
+#[test]
+fn test_cli_glossary_markdown() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["glossary", "--no-stdlib", "--src"])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+
+    assert!(stdout.contains("## Terms::Power"), "{}", stdout);
+    assert!(
+        stdout.contains("| **Vehicle** | Part def | A machine that transports people or cargo. |"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("**vehicle**"), "{}", stdout);
+}
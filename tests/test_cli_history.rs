@@ -0,0 +1,100 @@
+//! Integration tests for analysis across git history.
+//!
+//! Tests that `syster history` analyzes each tagged revision from the git
+//! object database and reports the requested metrics as CSV or JSON.
+
+mod common;
+
+use common::git;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, HistoryMetric, analyze_history, history_csv};
+use tempfile::TempDir;
+
+/// A repository with tags v0.9, v1.0 (two parts) and v1.1 (an unresolved
+/// type added), models kept under `models/`.
+fn write_repo(dir: &Path) {
+    let models = dir.join("models");
+    fs::create_dir(&models).unwrap();
+    fs::write(dir.join("README.md"), "not a model").unwrap();
+    git(dir, &["init", "-q"]);
+
+    fs::write(models.join("a.sysml"), "part def A;").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "first"]);
+    git(dir, &["tag", "v0.9"]);
+
+    fs::write(models.join("b.sysml"), "part def B;").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "second"]);
+    git(dir, &["tag", "-a", "v1.0", "-m", "release"]);
+
+    fs::write(models.join("b.sysml"), "part def B { part x : Undefined; }").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "third"]);
+    git(dir, &["tag", "v1.1"]);
+
+    // Uncommitted changes are not analyzed
+    fs::write(models.join("c.sysml"), "part def C;").unwrap();
+}
+
+#[test]
+fn test_history_since_tag() {
+    let temp_dir = TempDir::new().unwrap();
+    write_repo(temp_dir.path());
+
+    let options = AnalysisOptions::new();
+    let points = analyze_history(&temp_dir.path().join("models"), Some("v1.0"), &options).unwrap();
+
+    let revisions: Vec<_> = points.iter().map(|p| p.revision.as_str()).collect();
+    assert_eq!(revisions, vec!["v1.0", "v1.1"]);
+    assert_eq!(points[0].file_count, 2);
+    assert_eq!(points[1].file_count, 2);
+    assert!(points[1].symbol_count > points[0].symbol_count);
+    assert_eq!(points[0].error_count + points[0].warning_count, 0);
+    assert!(points[1].error_count + points[1].warning_count > 0);
+
+    let csv = history_csv(&points, &[HistoryMetric::Files]);
+    let rows: Vec<_> = csv.lines().collect();
+    assert_eq!(rows[0], "revision,commit,date,files");
+    assert!(rows[1].starts_with("v1.0,") && rows[1].ends_with(",2"));
+}
+
+#[test]
+fn test_history_unknown_tag() {
+    let temp_dir = TempDir::new().unwrap();
+    write_repo(temp_dir.path());
+
+    let err = analyze_history(temp_dir.path(), Some("v9"), &AnalysisOptions::new()).unwrap_err();
+    assert_eq!(err, "Unknown tag: v9");
+}
+
+#[test]
+fn test_cli_history_json_metrics() {
+    let temp_dir = TempDir::new().unwrap();
+    write_repo(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args([
+            "history",
+            "--no-stdlib",
+            "--json",
+            "--metric",
+            "symbols,files",
+        ])
+        .arg("--src")
+        .arg(temp_dir.path().join("models"))
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+
+    let series: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let series = series.as_array().unwrap();
+    assert_eq!(series.len(), 3);
+    assert_eq!(series[0]["revision"], "v0.9");
+    assert_eq!(series[0]["files"], 1);
+    assert!(series[0].get("symbols").is_some());
+    assert!(series[0].get("errors").is_none());
+}
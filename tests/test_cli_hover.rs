@@ -0,0 +1,123 @@
+//! Integration tests for hover information.
+//!
+//! Tests `FILE:LINE:COL` parsing, the Markdown and structured fields
+//! returned for definitions, usages and type references, and the
+//! `syster hover` command.
+
+mod common;
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster_cli::{AnalysisOptions, SourcePosition, hover};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicles.sysml",
+    "package Vehicles {\n\
+         \x20   abstract part def Vehicle;\n\
+         \x20   part def Engine;\n\
+         \x20   part def Car :> Vehicle {\n\
+         \x20       doc /* A road vehicle. */\n\
+         \x20       part engine : Engine;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+fn at(file: &Path, line: u32, col: u32) -> SourcePosition {
+    SourcePosition {
+        file: file.to_path_buf(),
+        line,
+        col,
+    }
+}
+
+#[test]
+fn test_parse_position() {
+    let position: SourcePosition = "models/a.sysml:3:7".parse().unwrap();
+    assert_eq!(position.file, PathBuf::from("models/a.sysml"));
+    assert_eq!((position.line, position.col), (3, 7));
+
+    let position: SourcePosition = "C:\\models\\a.sysml:1:2".parse().unwrap();
+    assert_eq!(position.file, PathBuf::from("C:\\models\\a.sysml"));
+
+    for invalid in ["a.sysml:3", "a.sysml:0:1", ":1:1", "a.sysml:x:1"] {
+        let err = invalid.parse::<SourcePosition>().unwrap_err();
+        assert!(err.contains("expected FILE:LINE:COL"), "{}", err);
+    }
+}
+
+#[test]
+fn test_hover_definition_usage_and_reference() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let car = hover(None, &at(&file, 4, 14), &AnalysisOptions::new())
+        .unwrap()
+        .expect("Car should have hover info");
+    assert_eq!(car.qualified_name.as_deref(), Some("Vehicles::Car"));
+    assert_eq!(car.supertypes, vec!["Vehicles::Vehicle"]);
+    assert!(car.types.is_empty());
+    assert!(car.contents.starts_with("```sysml\n"), "{}", car.contents);
+    assert!(car.contents.contains("A road vehicle."), "{}", car.contents);
+    assert!(
+        car.contents
+            .contains("**Specializes:** `Vehicles::Vehicle`"),
+        "{}",
+        car.contents
+    );
+    assert_eq!((car.start_line, car.start_col), (4, 14));
+
+    let engine = hover(None, &at(&file, 6, 14), &AnalysisOptions::new())
+        .unwrap()
+        .expect("engine should have hover info");
+    assert_eq!(
+        engine.qualified_name.as_deref(),
+        Some("Vehicles::Car::engine")
+    );
+    assert_eq!(engine.types, vec!["Vehicles::Engine"]);
+    assert!(engine.contents.contains("**Type:** `Vehicles::Engine`"));
+
+    // On the type reference: describes the referenced definition
+    let reference = hover(
+        Some(temp_dir.path()),
+        &at(&file, 6, 24),
+        &AnalysisOptions::new(),
+    )
+    .unwrap()
+    .expect("type reference should have hover info");
+    assert_eq!(
+        reference.qualified_name.as_deref(),
+        Some("Vehicles::Engine")
+    );
+    assert_eq!(reference.start_line, 6);
+}
+
+#[test]
+fn test_cli_hover() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("hover")
+        .arg(format!("{}:6:14", file.display()))
+        .arg("--no-stdlib")
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("**Type:** `Vehicles::Engine`"),
+        "{}",
+        stdout
+    );
+
+    // Nothing at the position: nothing on stdout, `null` in JSON
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("hover")
+        .arg(format!("{}:8:2", file.display()))
+        .args(["--no-stdlib", "--json"])
+        .output()
+        .expect("Should run CLI");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "null");
+}
@@ -0,0 +1,118 @@
+//! Integration tests for localized report text.
+//!
+//! Tests language codes, translating diagnostics by their English pattern,
+//! localized summaries, and `--lang`.
+
+use std::fs;
+use std::process::Command;
+use syster::hir::Severity;
+use syster_cli::{
+    AnalysisOptions, Catalog, DiagnosticInfo, Lang, SummaryBy, render_summary, render_summary_in,
+    run_analysis_from_sources,
+};
+use tempfile::TempDir;
+
+fn diagnostic(code: &str, message: &str) -> DiagnosticInfo {
+    DiagnosticInfo {
+        file: "model.sysml".to_string(),
+        line: 1,
+        col: 1,
+        end_line: 1,
+        end_col: 2,
+        message: message.to_string(),
+        severity: Severity::Error,
+        code: Some(code.to_string()),
+        blame: None,
+        owners: Vec::new(),
+        related: Vec::new(),
+    }
+}
+
+#[test]
+fn test_lang_codes() {
+    for lang in Lang::ALL {
+        assert_eq!(lang.code().parse::<Lang>().unwrap(), lang);
+        assert_eq!(Catalog::new(lang).unwrap().lang(), lang);
+    }
+    assert_eq!("DE".parse::<Lang>().unwrap(), Lang::De);
+    assert!(
+        "xx".parse::<Lang>()
+            .unwrap_err()
+            .contains("Unknown language")
+    );
+}
+
+#[test]
+fn test_translate_diagnostic_messages() {
+    let german = Catalog::new(Lang::De).unwrap();
+    let undefined = diagnostic("E0001", "undefined reference: 'Engine'");
+
+    assert_eq!(
+        german.diagnostic_message(&undefined),
+        "nicht definierte Referenz: 'Engine'"
+    );
+    assert_eq!(german.severity(Severity::Error), "Fehler");
+    assert_eq!(
+        Catalog::english().diagnostic_message(&undefined),
+        "undefined reference: 'Engine'"
+    );
+
+    // Messages that do not match their code's pattern stay English
+    let other = diagnostic("E0001", "something else");
+    assert_eq!(german.diagnostic_message(&other), "something else");
+    let uncatalogued = diagnostic("L0001", "name 'x' should be PascalCase");
+    assert_eq!(
+        german.diagnostic_message(&uncatalogued),
+        "name 'x' should be PascalCase"
+    );
+}
+
+#[test]
+fn test_localized_summary() {
+    let sources = [(
+        "model.sysml".to_string(),
+        "package Vehicle { part def Car { part engine : Engine; } }\n".to_string(),
+    )];
+    let result = run_analysis_from_sources(&sources, &AnalysisOptions::new());
+
+    assert_eq!(
+        render_summary_in(&result, SummaryBy::Package, &Catalog::english()),
+        render_summary(&result, SummaryBy::Package)
+    );
+    let french = render_summary_in(
+        &result,
+        SummaryBy::Package,
+        &Catalog::new(Lang::Fr).unwrap(),
+    );
+    assert!(french.contains("Vehicle"), "{}", french);
+    assert!(!french.contains("symbols"), "{}", french);
+}
+
+#[test]
+fn test_cli_lang() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("model.sysml"),
+        "package Vehicle { part def Car { part engine : Engine; } }\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--lang", "de"])
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("Fehler[E0001]"), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("nicht definierte Referenz: 'Engine'"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(
+        stderr.contains("1 Dateien analysiert"),
+        "stderr: {}",
+        stderr
+    );
+}
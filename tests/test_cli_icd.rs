@@ -0,0 +1,103 @@
+//! Integration tests for the built-in interface control document.
+//!
+//! Tests that `syster icd` lists every port and interface definition with its
+//! features, directions and types, and the connections using it.
+
+#![cfg(feature = "codegen")]
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, CodegenModel, DocumentFormat, interface_control_document, render_icd,
+};
+use tempfile::TempDir;
+
+/// Write a model with one port definition used by a connection.
+const MODELS: &[(&str, &str)] = &[(
+    "power.sysml",
+    "package Power {\n\
+         \x20   attribute def Voltage;\n\
+         \x20   port def PowerPort {\n\
+         \x20       doc /* Regulated DC supply */\n\
+         \x20       in attribute voltage : Voltage;\n\
+         \x20       out attribute current : Voltage;\n\
+         \x20   }\n\
+         \x20   part def Battery { port supply : PowerPort; }\n\
+         \x20   part def Motor { port feed : PowerPort; }\n\
+         \x20   part def Drive {\n\
+         \x20       part battery : Battery;\n\
+         \x20       part motor : Motor;\n\
+         \x20       connection link connect battery.supply to motor.feed;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+#[test]
+fn test_icd_lists_features_and_directions() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let model = CodegenModel::load(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let report = interface_control_document(&model);
+
+    assert_eq!(report.interfaces.len(), 1);
+    let port = &report.interfaces[0];
+    assert_eq!(port.qualified_name, "Power::PowerPort");
+    assert_eq!(port.kind, "port");
+
+    let features: Vec<_> = port
+        .features
+        .iter()
+        .map(|f| (f.name.as_str(), f.direction.as_str(), f.type_name.as_str()))
+        .collect();
+    assert_eq!(
+        features,
+        vec![
+            ("voltage", "in", "Power::Voltage"),
+            ("current", "out", "Power::Voltage")
+        ]
+    );
+}
+
+#[test]
+fn test_icd_formats() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let model = CodegenModel::load(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let report = interface_control_document(&model);
+
+    let markdown = render_icd(&report, DocumentFormat::Markdown).unwrap();
+    assert!(markdown.contains("## PowerPort"), "{}", markdown);
+    assert!(
+        markdown.contains("| voltage | attribute | in | Power::Voltage |"),
+        "{}",
+        markdown
+    );
+
+    let html = render_icd(&report, DocumentFormat::Html).unwrap();
+    assert!(html.contains("<td>voltage</td>"), "{}", html);
+
+    let csv = render_icd(&report, DocumentFormat::Csv).unwrap();
+    let rows: Vec<_> = csv.lines().collect();
+    assert_eq!(rows.len(), 3, "{}", csv);
+    assert!(rows[1].starts_with("Power::PowerPort,port,voltage,attribute,in,"));
+}
+
+#[test]
+fn test_cli_icd_shows_connections() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["icd", "--no-stdlib", "--src"])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+
+    assert!(stdout.contains("Regulated DC supply"), "{}", stdout);
+    assert!(stdout.contains("Power::Drive::link"), "{}", stdout);
+}
@@ -0,0 +1,190 @@
+//! Integration tests for `--on-id-conflict`.
+//!
+//! Tests that importing a deliverable whose element IDs are already in the
+//! workspace fails by default, that each policy resolves the conflicts and
+//! reports what it did, and that the CLI merges files into one workspace.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster::ide::AnalysisHost;
+use syster_cli::{
+    IdConflictPolicy, ImportOptions, import_model_into_host, import_model_into_host_with_options,
+};
+use tempfile::TempDir;
+
+/// A package with part definitions, given as (name, ID) pairs.
+fn deliverable(dir: &Path, package: (&str, &str), parts: &[(&str, &str)]) -> PathBuf {
+    let mut xmi = format!(
+        "<?xml version=\"1.0\" encoding=\"ASCII\"?>\n\
+         <sysml:Package xmi:version=\"2.0\" xmlns:xmi=\"http://www.omg.org/XMI\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+         xmlns:sysml=\"https://www.omg.org/spec/SysML/20250201\" \
+         xmi:id=\"{id}\" elementId=\"{id}\" declaredName=\"{name}\" qualifiedName=\"{name}\">\n",
+        name = package.0,
+        id = package.1
+    );
+    for (name, id) in parts {
+        xmi.push_str(&format!(
+            "  <ownedRelatedElement xsi:type=\"sysml:PartDefinition\" xmi:id=\"{id}\" \
+             elementId=\"{id}\" declaredName=\"{name}\" qualifiedName=\"{package}::{name}\"/>\n",
+            package = package.0
+        ));
+    }
+    xmi.push_str("</sysml:Package>\n");
+    let path = dir.join(format!("{}.xmi", package.0));
+    fs::write(&path, xmi).unwrap();
+    path
+}
+
+/// Two suppliers' deliverables whose generators both numbered a part "2".
+fn deliverables(dir: &Path) -> (PathBuf, PathBuf) {
+    (
+        deliverable(dir, ("SupplierA", "a1"), &[("Engine", "2")]),
+        deliverable(dir, ("SupplierB", "b1"), &[("Wheel", "2"), ("Tire", "b3")]),
+    )
+}
+
+fn with_policy(on_id_conflict: IdConflictPolicy) -> ImportOptions {
+    ImportOptions {
+        on_id_conflict,
+        ..ImportOptions::default()
+    }
+}
+
+fn element_id(host: &mut AnalysisHost, qualified_name: &str) -> Option<String> {
+    host.analysis()
+        .symbol_index()
+        .all_symbols()
+        .find(|sym| sym.qualified_name.as_ref() == qualified_name)
+        .map(|sym| sym.element_id.to_string())
+}
+
+#[test]
+fn test_conflicting_import_fails_by_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let (a, b) = deliverables(temp_dir.path());
+    let mut host = AnalysisHost::new();
+    import_model_into_host(&mut host, &a, None, false).unwrap();
+
+    let err = import_model_into_host(&mut host, &b, None, false).unwrap_err();
+    assert!(
+        err.contains("1 imported element IDs are already in the workspace"),
+        "{}",
+        err
+    );
+    assert!(err.contains("SupplierB::Wheel has ID 2, already taken by SupplierA::Engine"));
+    assert_eq!(element_id(&mut host, "SupplierB::Wheel"), None);
+
+    // Importing the same elements again is not a conflict
+    let result = import_model_into_host(&mut host, &a, None, false).unwrap();
+    assert!(result.id_resolutions.is_empty());
+}
+
+#[test]
+fn test_id_conflict_policies() {
+    let temp_dir = TempDir::new().unwrap();
+    let (a, b) = deliverables(temp_dir.path());
+
+    let mut host = AnalysisHost::new();
+    import_model_into_host(&mut host, &a, None, false).unwrap();
+    let result = import_model_into_host_with_options(
+        &mut host,
+        &b,
+        None,
+        &with_policy(IdConflictPolicy::Rename),
+    )
+    .unwrap();
+    assert_eq!(result.id_resolutions.len(), 1);
+    let renamed = &result.id_resolutions[0];
+    assert_eq!(renamed.imported, "SupplierB::Wheel");
+    assert_eq!(renamed.existing, "SupplierA::Engine");
+    let new_id = renamed.new_id.clone().unwrap();
+    assert_ne!(new_id, "2");
+    assert!(
+        result
+            .messages
+            .iter()
+            .any(|m| m.starts_with("Renamed SupplierB::Wheel from ID 2"))
+    );
+    assert_eq!(
+        element_id(&mut host, "SupplierA::Engine").as_deref(),
+        Some("2")
+    );
+    assert_eq!(element_id(&mut host, "SupplierB::Wheel"), Some(new_id));
+
+    let mut host = AnalysisHost::new();
+    import_model_into_host(&mut host, &a, None, false).unwrap();
+    let result = import_model_into_host_with_options(
+        &mut host,
+        &b,
+        None,
+        &with_policy(IdConflictPolicy::Skip),
+    )
+    .unwrap();
+    assert_eq!(result.id_resolutions.len(), 1);
+    assert_eq!(result.element_count, 2);
+    assert_eq!(element_id(&mut host, "SupplierB::Wheel"), None);
+    assert_eq!(
+        element_id(&mut host, "SupplierB::Tire").as_deref(),
+        Some("b3")
+    );
+
+    let mut host = AnalysisHost::new();
+    import_model_into_host(&mut host, &a, None, false).unwrap();
+    let result = import_model_into_host_with_options(
+        &mut host,
+        &b,
+        None,
+        &with_policy(IdConflictPolicy::Overwrite),
+    )
+    .unwrap();
+    let overwritten = &result.id_resolutions[0];
+    assert_eq!(
+        element_id(&mut host, "SupplierB::Wheel").as_deref(),
+        Some("2")
+    );
+    assert_eq!(
+        element_id(&mut host, "SupplierA::Engine"),
+        overwritten.new_id.clone()
+    );
+}
+
+#[test]
+fn test_cli_merge_with_id_conflicts() {
+    let temp_dir = TempDir::new().unwrap();
+    let (a, b) = deliverables(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&a)
+        .args(["--no-stdlib", "--import-workspace", "--merge"])
+        .arg(&b)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already in the workspace"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&a)
+        .args(["--no-stdlib", "--import-workspace", "--merge"])
+        .arg(&b)
+        .args(["--on-id-conflict", "rename"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("Imported 5 elements"), "{}", stdout);
+    assert!(
+        stderr.contains("resolved 1 element ID conflicts"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("Renamed SupplierB::Wheel from ID 2"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,117 @@
+//! Integration tests for export ID policies.
+//!
+//! Tests name-based IDs (UUIDv5 in the default and a custom namespace, and
+//! qualified-name hashes), that only `preserve` restores metadata IDs, and
+//! the `--id-policy` flag.
+
+#![cfg(feature = "interchange")]
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, ExportFilter, IdPolicy, export_model_with_options};
+use tempfile::TempDir;
+
+/// A model whose `Vehicle::Car` has an ID in companion metadata.
+const MODELS: &[(&str, &str)] = &[
+    ("vehicle.sysml", "package Vehicle {\n    part def Car;\n}\n"),
+    (
+        "vehicle.metadata.json",
+        r#"{"version": 1, "source": {}, "elements": {"Vehicle::Car": {"originalId": "car-0001"}}}"#,
+    ),
+];
+
+fn export_xmi(dir: &Path, id_policy: IdPolicy, id_namespace: Option<&str>) -> String {
+    let filter = ExportFilter {
+        id_policy,
+        id_namespace: id_namespace.map(str::to_string),
+        ..ExportFilter::default()
+    };
+    let (bytes, _) =
+        export_model_with_options(dir, "xmi", &AnalysisOptions::default(), false, &filter).unwrap();
+    String::from_utf8(bytes).unwrap()
+}
+
+#[test]
+fn test_name_based_ids() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let xmi = export_xmi(temp_dir.path(), IdPolicy::UuidV5Namespace, None);
+    assert!(
+        xmi.contains("80a9ea7a-6aa1-5bf3-8902-f3cb9933c96d"),
+        "{}",
+        xmi
+    );
+    assert!(!xmi.contains("car-0001"));
+
+    let dns = Some("6ba7b810-9dad-11d1-80b4-00c04fd430c8");
+    let xmi = export_xmi(temp_dir.path(), IdPolicy::UuidV5Namespace, dns);
+    assert!(
+        xmi.contains("b603964d-4866-58b1-8282-16cf3154f192"),
+        "{}",
+        xmi
+    );
+
+    let xmi = export_xmi(temp_dir.path(), IdPolicy::QualifiedNameHash, None);
+    assert!(xmi.contains("125970ddc18940fe10d2109c1288f6ae"), "{}", xmi);
+
+    let filter = ExportFilter {
+        id_policy: IdPolicy::UuidV5Namespace,
+        id_namespace: Some("not-a-uuid".to_string()),
+        ..ExportFilter::default()
+    };
+    let err = export_model_with_options(
+        temp_dir.path(),
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &filter,
+    )
+    .unwrap_err();
+    assert!(err.starts_with("Invalid ID namespace"), "{}", err);
+}
+
+#[test]
+fn test_only_preserve_restores_ids() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let xmi = export_xmi(temp_dir.path(), IdPolicy::Preserve, None);
+    assert!(xmi.contains("car-0001"));
+
+    let xmi = export_xmi(temp_dir.path(), IdPolicy::UuidV4, None);
+    assert!(!xmi.contains("car-0001"));
+    assert!(!xmi.contains("80a9ea7a-6aa1-5bf3-8902-f3cb9933c96d"));
+}
+
+#[test]
+fn test_cli_id_policy() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args([
+            "--no-stdlib",
+            "--export",
+            "xmi",
+            "--id-policy",
+            "qualified-name-hash",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let xmi = String::from_utf8_lossy(&output.stdout);
+    assert!(xmi.contains("125970ddc18940fe10d2109c1288f6ae"), "{}", xmi);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--id-policy", "uuid-v4"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--export"), "{}", stderr);
+}
@@ -0,0 +1,128 @@
+//! Integration tests for element ID maps.
+//!
+//! Tests that IDs restored from companion metadata are listed with their
+//! locations, that the map agrees with an XMI export, and the CSV written by
+//! `syster ids`.
+
+#![cfg(feature = "interchange")]
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, ExportFilter, element_ids, element_ids_csv, export_model_with_options,
+};
+use tempfile::TempDir;
+
+/// A model whose `Vehicle::Car` has a known ID.
+const MODELS: &[(&str, &str)] = &[
+    (
+        "vehicle.sysml",
+        "package Vehicle {\n\
+         \x20   part def Engine;\n\
+         \x20   part def Car {\n\
+         \x20       part engine : Engine;\n\
+         \x20   }\n\
+         }\n",
+    ),
+    (
+        "vehicle.metadata.json",
+        r#"{"version": 1, "source": {}, "elements": {"Vehicle::Car": {"originalId": "car-0001"}}}"#,
+    ),
+];
+
+#[test]
+fn test_element_ids_restored_and_located() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let entries = element_ids(temp_dir.path(), &AnalysisOptions::default()).unwrap();
+    let names: Vec<&str> = entries.iter().map(|e| e.qualified_name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "Vehicle",
+            "Vehicle::Engine",
+            "Vehicle::Car",
+            "Vehicle::Car::engine"
+        ]
+    );
+
+    let car = &entries[2];
+    assert_eq!(car.element_id, "car-0001");
+    assert_eq!(car.kind, "Part def");
+    assert!(car.file.ends_with("vehicle.sysml"), "{}", car.file);
+    assert_eq!((car.line, car.col), (3, 5));
+    assert!(car.restored);
+    assert!(!entries[1].restored);
+}
+
+#[test]
+fn test_element_ids_match_export() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let entries = element_ids(temp_dir.path(), &AnalysisOptions::default()).unwrap();
+    let (bytes, _) = export_model_with_options(
+        temp_dir.path(),
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter::default(),
+    )
+    .unwrap();
+    let xmi = String::from_utf8(bytes).unwrap();
+    // Restored IDs are the ones exported; the others are regenerated
+    for entry in &entries {
+        assert_eq!(
+            xmi.contains(&entry.element_id),
+            entry.restored,
+            "{}",
+            entry.qualified_name
+        );
+    }
+
+    let csv = element_ids_csv(&entries);
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("qualified_name,element_id,kind,file,line,col,restored")
+    );
+    assert_eq!(lines.count(), entries.len());
+}
+
+#[test]
+fn test_cli_ids() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let output_path = temp_dir.path().join("ids.csv");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["ids", "--no-stdlib"])
+        .arg(temp_dir.path())
+        .arg("-o")
+        .arg(&output_path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let csv = fs::read_to_string(&output_path).unwrap();
+    let car = csv
+        .lines()
+        .find(|line| line.starts_with("Vehicle::Car,"))
+        .unwrap();
+    assert!(
+        car.starts_with("Vehicle::Car,car-0001,Part def,"),
+        "{}",
+        car
+    );
+    assert!(car.ends_with("vehicle.sysml,3,5,true"), "{}", car);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["ids", "--no-stdlib", "--json"])
+        .arg(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[2]["element_id"], "car-0001");
+}
@@ -0,0 +1,106 @@
+//! Integration tests for `.systerignore` files.
+//!
+//! Tests that ignored files and directories are skipped (with negated
+//! patterns and nested ignore files), `.gitignore` with and without
+//! `--respect-gitignore`.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+use syster::hir::Severity;
+use syster_cli::{AnalysisOptions, WalkOptions, analyze};
+use tempfile::TempDir;
+
+/// A model with build output, scratch models and a `.gitignore`d draft,
+/// each defining `Vehicle::Car` again.
+fn write_models(dir: &Path) {
+    let car = "package Vehicle {\n    part def Car;\n}\n";
+    common::write_models(
+        dir,
+        &[
+            ("car.sysml", car),
+            ("build/car.sysml", car),
+            ("scratch/old.sysml", car),
+            (
+                "scratch/keep.sysml",
+                "package Notes {\n    part def Keep;\n}\n",
+            ),
+            ("drafts/car.sysml", car),
+            (".systerignore", "build/\nscratch/*.sysml\n"),
+            ("scratch/.systerignore", "!keep.sysml\n"),
+            (".gitignore", "drafts/\n"),
+        ],
+    );
+}
+
+fn analyze_dir(dir: &Path, respect_gitignore: bool) -> syster_cli::AnalysisResult {
+    let options = AnalysisOptions {
+        duplicates: Some(Severity::Warning),
+        walk: WalkOptions {
+            respect_gitignore,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    analyze(dir, &options).unwrap()
+}
+
+fn duplicate_files(result: &syster_cli::AnalysisResult) -> Vec<&str> {
+    let mut files: Vec<&str> = result
+        .diagnostics
+        .iter()
+        .filter(|d| d.message.contains("duplicate definition"))
+        .map(|d| d.file.as_str())
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn test_systerignore() {
+    let temp_dir = TempDir::new().unwrap();
+    write_models(temp_dir.path());
+
+    // build/ and scratch/old.sysml are ignored, scratch/keep.sysml is
+    // re-included by the nested ignore file; drafts/ is only in .gitignore
+    let result = analyze_dir(temp_dir.path(), false);
+    assert_eq!(result.file_count, 3);
+    let duplicates = duplicate_files(&result);
+    assert_eq!(duplicates.len(), 2, "{:?}", result.diagnostics);
+    assert!(duplicates.iter().any(|f| f.contains("drafts")));
+    assert!(duplicates.iter().all(|f| !f.contains("build")));
+}
+
+#[test]
+fn test_respect_gitignore() {
+    let temp_dir = TempDir::new().unwrap();
+    write_models(temp_dir.path());
+
+    let result = analyze_dir(temp_dir.path(), true);
+    assert_eq!(result.file_count, 2);
+    assert!(
+        duplicate_files(&result).is_empty(),
+        "{:?}",
+        result.diagnostics
+    );
+}
+
+#[test]
+fn test_cli_respect_gitignore() {
+    let temp_dir = TempDir::new().unwrap();
+    write_models(temp_dir.path());
+
+    let run = |extra: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg(temp_dir.path())
+            .args(["--no-stdlib", "--json"])
+            .args(extra)
+            .output()
+            .expect("Should run CLI");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["file_count"].as_u64().unwrap()
+    };
+    assert_eq!(run(&[]), 3);
+    assert_eq!(run(&["--respect-gitignore"]), 2);
+}
@@ -0,0 +1,195 @@
+//! Integration tests for change impact analysis.
+//!
+//! Tests following ownership, typings, specializations and connections
+//! from a changed element with depths, limiting the depth, finding changes
+//! by comparing two versions, and `syster impact`.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, ChangeKind, ImpactReport, change_impact, diff_impact};
+use tempfile::TempDir;
+
+const SIGNALS: &str = "package Signals {\n\
+                       \x20   item def Fuel;\n\
+                       \x20   port def FuelPort {\n\
+                       \x20       out item fuel : Fuel;\n\
+                       \x20   }\n\
+                       }\n";
+
+fn write_model(dir: &Path, signals: &str, extra_part: &str, unrelated: &str) {
+    let vehicle = format!(
+        "package Parts {{\n\
+         \x20   import Signals::*;\n\
+         \x20   part def Tank {{\n\
+         \x20       port fuelOut : FuelPort;\n\
+         \x20   }}\n\
+         \x20   part def Engine {{\n\
+         \x20       port fuelIn : ~FuelPort;\n\
+         \x20   }}\n\
+         \x20   part def BigTank :> Tank;\n\
+         {}\
+         }}\n\
+         package Vehicle {{\n\
+         \x20   import Parts::*;\n\
+         \x20   part car {{\n\
+         \x20       part tank : Tank;\n\
+         \x20       part engine : Engine;\n\
+         \x20       connection feed connect tank.fuelOut to engine.fuelIn;\n\
+         \x20   }}\n\
+         {}\
+         }}\n",
+        extra_part, unrelated
+    );
+    common::write_models(
+        dir,
+        &[("signals.sysml", signals), ("vehicle.sysml", &vehicle)],
+    );
+}
+
+const UNRELATED: &str = "    part def Unrelated;\n";
+
+/// `(depth, via, from)` of an affected element, if it is affected.
+fn reached<'a>(report: &'a ImpactReport, name: &str) -> Option<(usize, &'a str, &'a str)> {
+    report
+        .elements
+        .iter()
+        .find(|e| e.qualified_name == name)
+        .map(|e| (e.depth, e.via.as_str(), e.from.as_str()))
+}
+
+#[test]
+fn test_impact_of_changed_signal() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path(), SIGNALS, "", UNRELATED);
+
+    let changed = vec!["Signals::FuelPort::fuel".to_string()];
+    let report = change_impact(temp_dir.path(), &changed, None, &AnalysisOptions::new()).unwrap();
+
+    assert_eq!(report.changes[0].change, ChangeKind::Changed);
+    assert_eq!(
+        reached(&report, "Signals::FuelPort"),
+        Some((1, "owns", "Signals::FuelPort::fuel"))
+    );
+    assert_eq!(
+        reached(&report, "Parts::Tank::fuelOut"),
+        Some((2, "typed by", "Signals::FuelPort"))
+    );
+    assert_eq!(
+        reached(&report, "Parts::Engine::fuelIn"),
+        Some((2, "typed by", "Signals::FuelPort"))
+    );
+    assert_eq!(reached(&report, "Parts::Tank").map(|r| r.0), Some(3));
+    assert_eq!(
+        reached(&report, "Parts::BigTank"),
+        Some((4, "specializes", "Parts::Tank"))
+    );
+    assert_eq!(
+        reached(&report, "Vehicle::car::feed").map(|r| (r.0, r.1)),
+        Some((3, "connects"))
+    );
+    assert!(reached(&report, "Vehicle::car::tank").is_some());
+    assert!(reached(&report, "Vehicle::Unrelated").is_none());
+    assert!(report.elements.windows(2).all(|w| w[0].depth <= w[1].depth));
+
+    let packages: Vec<(&str, usize)> = report
+        .packages
+        .iter()
+        .map(|p| (p.package.as_str(), p.depth))
+        .collect();
+    assert_eq!(packages, vec![("Parts", 2), ("Signals", 0), ("Vehicle", 3)]);
+    assert_eq!(report.packages[1].elements, 1);
+}
+
+#[test]
+fn test_impact_max_depth_and_unknown_element() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path(), SIGNALS, "", UNRELATED);
+
+    let changed = vec!["Signals::FuelPort".to_string()];
+    let report =
+        change_impact(temp_dir.path(), &changed, Some(1), &AnalysisOptions::new()).unwrap();
+    let names: Vec<&str> = report
+        .elements
+        .iter()
+        .map(|e| e.qualified_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["Parts::Engine::fuelIn", "Parts::Tank::fuelOut"]);
+
+    let changed = vec!["Signals::Missing".to_string()];
+    let err = change_impact(temp_dir.path(), &changed, None, &AnalysisOptions::new()).unwrap_err();
+    assert_eq!(err, "Unknown element: Signals::Missing");
+}
+
+#[test]
+fn test_impact_from_diff() {
+    let temp_dir = TempDir::new().unwrap();
+    let old = temp_dir.path().join("old");
+    let new = temp_dir.path().join("new");
+    write_model(&old, SIGNALS, "", UNRELATED);
+    write_model(
+        &new,
+        &SIGNALS.replace("out item fuel", "in item fuel"),
+        "    part def Pump;\n",
+        "",
+    );
+
+    let report = diff_impact(&old, &new, None, &AnalysisOptions::new()).unwrap();
+
+    let changes: Vec<(&str, ChangeKind)> = report
+        .changes
+        .iter()
+        .map(|c| (c.qualified_name.as_str(), c.change))
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("Parts::Pump", ChangeKind::Added),
+            ("Signals::FuelPort::fuel", ChangeKind::Changed),
+            ("Vehicle::Unrelated", ChangeKind::Removed),
+        ]
+    );
+    assert_eq!(reached(&report, "Parts::Tank").map(|r| r.0), Some(3));
+    assert!(reached(&report, "Parts::Pump").is_none());
+}
+
+#[test]
+fn test_cli_impact() {
+    let temp_dir = TempDir::new().unwrap();
+    let old = temp_dir.path().join("old");
+    let new = temp_dir.path().join("new");
+    write_model(&old, SIGNALS, "", UNRELATED);
+    write_model(&new, SIGNALS, "", "");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["impact", "--no-stdlib", "--changed", "Signals::FuelPort"])
+        .args(["--max-depth", "2", "--src"])
+        .arg(&new)
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(stdout.starts_with("Depth  Element"), "stdout: {}", stdout);
+    assert!(
+        stdout.contains("Parts::Tank::fuelOut"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(stdout.contains("typed by"), "stdout: {}", stdout);
+    assert!(stdout.contains("Packages:"), "stdout: {}", stdout);
+    assert!(stderr.contains("✓ 1 changes affect"), "stderr: {}", stderr);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["impact", "--no-stdlib", "--json", "--from-diff"])
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .expect("Should run CLI");
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["changes"][0]["qualified_name"], "Vehicle::Unrelated");
+    assert_eq!(report["changes"][0]["change"], "removed");
+    assert_eq!(report["elements"].as_array().unwrap().len(), 0);
+}
@@ -0,0 +1,153 @@
+//! Integration tests for `--continue-on-error` imports.
+//!
+//! Tests that unreadable XMI elements are cut out with everything nested in
+//! them and reported with their location, that a KPAR entry that cannot be
+//! read is skipped whole, and that the CLI only recovers when asked to.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{ImportOptions, import_model, import_model_with_options};
+use tempfile::TempDir;
+
+/// A package whose second part definition has an unknown entity in its
+/// name, and whose third reuses the first one's ID.
+const BROKEN_XMI: &str = r#"<?xml version="1.0" encoding="ASCII"?>
+<sysml:Package xmi:version="2.0" xmlns:xmi="http://www.omg.org/XMI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:sysml="https://www.omg.org/spec/SysML/20250201" xmi:id="p" elementId="p" declaredName="Vehicles">
+  <ownedRelatedElement xsi:type="sysml:PartDefinition" xmi:id="a" elementId="a" declaredName="Engine"/>
+  <ownedRelatedElement xsi:type="sysml:PartDefinition" xmi:id="b" elementId="b" declaredName="Broken &bogus; name">
+    <ownedRelatedElement xsi:type="sysml:PartUsage" xmi:id="c" elementId="c" declaredName="inner"/>
+  </ownedRelatedElement>
+  <ownedRelatedElement xsi:type="sysml:PartDefinition" xmi:id="a" elementId="a" declaredName="Again"/>
+  <ownedRelatedElement xsi:type="sysml:PartDefinition" xmi:id="d" elementId="d" declaredName="Wheel"/>
+</sysml:Package>
+"#;
+
+fn recovering() -> ImportOptions {
+    ImportOptions {
+        continue_on_error: true,
+        ..ImportOptions::default()
+    }
+}
+
+/// [`BROKEN_XMI`] with both problems fixed.
+fn readable_xmi() -> String {
+    BROKEN_XMI.replace(" &bogus;", "").replace(
+        r#"xmi:id="a" elementId="a" declaredName="Again""#,
+        r#"xmi:id="e" elementId="e" declaredName="Again""#,
+    )
+}
+
+fn write_kpar(path: &Path, entries: &[(&str, &str)]) {
+    let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+    for (name, content) in entries {
+        zip.start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn test_continue_on_error_skips_elements() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("broken.xmi");
+    fs::write(&input, BROKEN_XMI).unwrap();
+
+    assert!(import_model(&input, None, false).is_err());
+
+    let result = import_model_with_options(&input, None, &recovering()).unwrap();
+    assert_eq!(result.element_count, 3);
+    assert_eq!(result.skipped.len(), 2);
+
+    let bad_name = &result.skipped[0];
+    assert_eq!(bad_name.location, format!("{}:4:3", input.display()));
+    assert_eq!(bad_name.id.as_deref(), Some("b"));
+    assert_eq!(bad_name.nested, 1);
+    assert!(
+        bad_name.reason.contains("declaredName"),
+        "{}",
+        bad_name.reason
+    );
+
+    let duplicate = &result.skipped[1];
+    assert_eq!(duplicate.location, format!("{}:7:3", input.display()));
+    assert_eq!(duplicate.reason, "duplicate xmi:id a");
+    assert_eq!(duplicate.nested, 0);
+
+    assert!(
+        result
+            .messages
+            .iter()
+            .any(|m| m.starts_with("Skipped ownedRelatedElement (sysml:PartDefinition) 'b'"))
+    );
+
+    // A readable file is imported unchanged
+    let clean = temp_dir.path().join("clean.xmi");
+    fs::write(&clean, readable_xmi()).unwrap();
+    let result = import_model_with_options(&clean, None, &recovering()).unwrap();
+    assert!(result.skipped.is_empty());
+    assert_eq!(result.element_count, 6);
+}
+
+#[test]
+fn test_continue_on_error_skips_kpar_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let kpar = temp_dir.path().join("model.kpar");
+    let good = readable_xmi();
+    write_kpar(
+        &kpar,
+        &[
+            ("model/good.xmi", good.as_str()),
+            (
+                "model/broken.xmi",
+                "<sysml:Package><ownedRelatedElement></sysml:Package>",
+            ),
+        ],
+    );
+
+    assert!(import_model(&kpar, None, false).is_err());
+
+    let result = import_model_with_options(&kpar, None, &recovering()).unwrap();
+    assert_eq!(result.element_count, 6);
+    assert_eq!(result.skipped.len(), 1);
+    let entry = &result.skipped[0];
+    assert_eq!(entry.element, "entry model/broken.xmi");
+    assert_eq!(
+        entry.location,
+        format!("{}!model/broken.xmi", kpar.display())
+    );
+}
+
+#[test]
+fn test_cli_continue_on_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("broken.xmi");
+    fs::write(&input, BROKEN_XMI).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&input)
+        .arg("--import")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&input)
+        .args(["--import", "--continue-on-error", "--verbose"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("Imported 3 elements"), "{}", stdout);
+    assert!(
+        stderr.contains("skipped 2 unreadable elements and 1 elements nested in them"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains(&format!("at {}:4:3", input.display())));
+}
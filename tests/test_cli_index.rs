@@ -0,0 +1,133 @@
+//! Integration tests for pre-built symbol indexes.
+//!
+//! Tests the binary `.sidx` round trip, the declaration skeleton an index
+//! expands to, and resolving a model's references against an index built
+//! with `syster index build` instead of the dependency's sources.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, ModelIndex, build_index};
+use tempfile::TempDir;
+
+/// A dependency repository with a public re-export.
+fn write_dependency(dir: &Path) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+        dir.join("vehicles.sysml"),
+        "package Vehicles {\n\
+         \x20   public import Engines::*;\n\
+         \x20   abstract part def Vehicle;\n\
+         \x20   part def Car :> Vehicle {\n\
+         \x20       part engine : Engine { doc /* not indexed */ }\n\
+         \x20   }\n\
+         }\n\
+         package Engines {\n\
+         \x20   part def Engine;\n\
+         }\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_index_round_trip_and_skeleton() {
+    let temp_dir = TempDir::new().unwrap();
+    write_dependency(temp_dir.path());
+
+    let index = build_index(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let names: Vec<&str> = index
+        .symbols
+        .iter()
+        .map(|s| s.qualified_name.as_str())
+        .collect();
+    assert!(names.contains(&"Vehicles::Car::engine"), "{:?}", names);
+
+    let bytes = index.to_bytes();
+    assert!(bytes.starts_with(b"SIDX"));
+    assert_eq!(ModelIndex::from_bytes(&bytes).unwrap(), index);
+
+    let skeleton = index.to_sysml();
+    assert!(
+        skeleton.contains(
+            "part def Car :> Vehicles::Vehicle {\n        part engine : Engines::Engine;"
+        ),
+        "{}",
+        skeleton
+    );
+    assert!(
+        skeleton.contains("abstract part def Vehicle;"),
+        "{}",
+        skeleton
+    );
+    assert!(!skeleton.contains("doc"), "{}", skeleton);
+
+    let err = ModelIndex::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+    assert_eq!(err, "unexpected end of file");
+    let err = ModelIndex::from_bytes(b"PK\x03\x04").unwrap_err();
+    assert_eq!(err, "not a symbol index");
+}
+
+#[test]
+fn test_cli_check_against_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let dependency = temp_dir.path().join("dependency");
+    write_dependency(&dependency);
+    let index = temp_dir.path().join("deps.sidx");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["index", "build", "--no-stdlib", "--src"])
+        .arg(&dependency)
+        .arg("-o")
+        .arg(&index)
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.contains("Indexed"), "{}", stdout);
+
+    // The consumer only sees the index, not the dependency's sources
+    let model = temp_dir.path().join("garage.sysml");
+    fs::write(
+        &model,
+        "package Garage {\n    import Vehicles::*;\n    part car : Car;\n    part spare : Engine;\n}\n",
+    )
+    .unwrap();
+
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg(&model)
+            .arg("--no-stdlib")
+            .args(args)
+            .output()
+            .expect("Should run CLI")
+    };
+    let without = run(&[]);
+    assert!(!without.status.success());
+
+    let with = run(&["--index", index.to_str().unwrap()]);
+    let stderr = String::from_utf8_lossy(&with.stderr);
+    let stdout = String::from_utf8_lossy(&with.stdout);
+    assert!(with.status.success(), "stderr: {}", stderr);
+    assert!(stdout.contains("Analyzed 1 files"), "{}", stdout);
+}
+
+#[test]
+fn test_cli_rejects_invalid_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let model = temp_dir.path().join("model.sysml");
+    fs::write(&model, "part def A;\n").unwrap();
+    let index = temp_dir.path().join("bad.sidx");
+    fs::write(&index, "not an index").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&model)
+        .arg("--no-stdlib")
+        .arg("--index")
+        .arg(&index)
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("Invalid symbol index"), "{}", stderr);
+    assert!(stderr.contains("not a symbol index"), "{}", stderr);
+}
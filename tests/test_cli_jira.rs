@@ -0,0 +1,183 @@
+//! Integration tests for publishing diagnostics to Jira.
+//!
+//! Tests planning issues (severity mapping, fingerprints that survive moved
+//! lines and checkouts), the `[publish.jira]` settings, creating and
+//! updating issues against a local stand-in for Jira, and
+//! `syster publish jira --dry-run`.
+
+#![cfg(feature = "jira")]
+
+mod common;
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+use syster_cli::{AnalysisOptions, JiraConfig, analyze, plan_issues, publish_issues};
+use tempfile::TempDir;
+
+/// A model with an unresolved type; `blank_lines` move it down.
+fn write_model(dir: &Path, blank_lines: usize) {
+    let model = format!(
+        "{}package Vehicle {{\n    part def Car;\n    part wheel : Wheel;\n}}\n",
+        "\n".repeat(blank_lines)
+    );
+    common::write_models(dir, &[("car.sysml", &model)]);
+}
+
+/// Answer one HTTP request per response, in order, on a local port; the
+/// handle yields the request lines and bodies.
+fn jira(responses: Vec<&'static str>) -> (String, JoinHandle<Vec<(String, String)>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let (mut length, mut expect_continue) = (0, false);
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                let header = header.trim().to_lowercase();
+                if header.is_empty() {
+                    break;
+                }
+                if let Some(value) = header.strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                expect_continue |= header == "expect: 100-continue";
+            }
+            if expect_continue {
+                stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").unwrap();
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        response.len(),
+                        response
+                    )
+                    .as_bytes(),
+                )
+                .unwrap();
+            requests.push((
+                request_line.trim().to_string(),
+                String::from_utf8(body).unwrap(),
+            ));
+        }
+        requests
+    });
+    (url, handle)
+}
+
+#[test]
+fn test_plan_issues() {
+    let temp_dir = TempDir::new().unwrap();
+    let (a, b) = (temp_dir.path().join("a"), temp_dir.path().join("b"));
+    fs::create_dir(&a).unwrap();
+    fs::create_dir(&b).unwrap();
+    write_model(&a, 0);
+    write_model(&b, 3);
+    let options = AnalysisOptions::default();
+
+    let result = analyze(&a, &options).unwrap();
+    let issues = plan_issues(&result, &a, &JiraConfig::default());
+    assert_eq!(issues.len(), 1, "{:?}", issues);
+    let issue = &issues[0];
+    assert!(issue.fingerprint.starts_with("syster-"));
+    assert_eq!(issue.fingerprint.len(), "syster-".len() + 16);
+    assert_eq!(issue.priority, "High");
+    assert!(issue.summary.contains("Wheel"), "{}", issue.summary);
+    assert!(
+        issue.description.starts_with("car.sysml:3:"),
+        "{}",
+        issue.description
+    );
+
+    // Same diagnostic, other checkout and line: same fingerprint
+    let moved = plan_issues(&analyze(&b, &options).unwrap(), &b, &JiraConfig::default());
+    assert_eq!(moved[0].fingerprint, issue.fingerprint);
+    assert!(moved[0].description.starts_with("car.sysml:6:"));
+
+    let config = JiraConfig {
+        priorities: [("warning".to_string(), "Low".to_string())].into(),
+        ..JiraConfig::default()
+    };
+    assert!(plan_issues(&result, &a, &config).is_empty());
+}
+
+#[test]
+fn test_publish_creates_and_updates() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("syster.toml"),
+        "[publish.jira]\nproject = \"SYS\"\nissue_type = \"Task\"\n\
+         [publish.jira.priority]\nerror = \"Highest\"\n",
+    )
+    .unwrap();
+    write_model(temp_dir.path(), 0);
+    let mut config = JiraConfig::discover(temp_dir.path()).unwrap();
+    assert_eq!(config.project, "SYS");
+    assert_eq!(config.issue_type, "Task");
+
+    let result = analyze(temp_dir.path(), &AnalysisOptions::default()).unwrap();
+    let issues = plan_issues(&result, temp_dir.path(), &config);
+    assert_eq!(issues[0].priority, "Highest");
+
+    let (url, handle) = jira(vec![r#"{"issues": []}"#, r#"{"key": "SYS-1"}"#]);
+    config.url = url;
+    let summary = publish_issues(&issues, &config, "user:token").unwrap();
+    assert_eq!(summary.created, ["SYS-1"]);
+    let requests = handle.join().unwrap();
+    assert!(requests[0].0.starts_with("GET /rest/api/2/search?"));
+    assert!(requests[0].0.contains(&issues[0].fingerprint));
+    assert_eq!(requests[1].0, "POST /rest/api/2/issue HTTP/1.1");
+    let body: serde_json::Value = serde_json::from_str(&requests[1].1).unwrap();
+    assert_eq!(body["fields"]["project"]["key"], "SYS");
+    assert_eq!(body["fields"]["issuetype"]["name"], "Task");
+    assert_eq!(body["fields"]["labels"][1], issues[0].fingerprint.as_str());
+
+    let (url, handle) = jira(vec![r#"{"issues": [{"key": "SYS-1"}]}"#, ""]);
+    config.url = url;
+    let summary = publish_issues(&issues, &config, "user:token").unwrap();
+    assert_eq!(summary.updated, ["SYS-1"]);
+    let requests = handle.join().unwrap();
+    assert_eq!(requests[1].0, "PUT /rest/api/2/issue/SYS-1 HTTP/1.1");
+}
+
+#[test]
+fn test_cli_publish_jira_dry_run() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path(), 0);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["publish", "jira", "--project", "SYS", "--dry-run", "--src"])
+        .arg(temp_dir.path())
+        .arg("--no-stdlib")
+        .env_remove("JIRA_API_TOKEN")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[High]"), "{}", stdout);
+    assert!(stdout.contains("Would publish 1 issues"), "{}", stdout);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["publish", "jira", "--project", "SYS", "--src"])
+        .arg(temp_dir.path())
+        .arg("--no-stdlib")
+        .env_remove("JIRA_API_TOKEN")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("JIRA_API_TOKEN"), "{}", stderr);
+}
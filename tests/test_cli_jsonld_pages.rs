@@ -0,0 +1,151 @@
+//! Integration tests for paged JSON-LD exports.
+//!
+//! Tests that an export is split into pages of the requested size that
+//! together hold every object, that the index lists them with their hashes
+//! and the provenance record, and that the CLI writes them into the `-o`
+//! directory.
+
+#![cfg(feature = "interchange")]
+
+mod common;
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster::interchange::{JsonLd, ModelFormat};
+use syster_cli::{
+    AnalysisOptions, ExportFilter, PageIndex, export_model_with_options, paginate_jsonld,
+    write_jsonld_pages,
+};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicles.sysml",
+    "package Vehicles {\n\
+         \x20   part def Engine;\n\
+         \x20   part def Wheel;\n\
+         \x20   part def Vehicle {\n\
+         \x20       part engine : Engine;\n\
+         \x20       part wheels : Wheel[4];\n\
+         \x20   }\n\
+         }\n",
+)];
+
+fn export_jsonld(dir: &Path) -> Vec<u8> {
+    let (bytes, _) = export_model_with_options(
+        dir,
+        "jsonld",
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter::default(),
+    )
+    .unwrap();
+    bytes
+}
+
+fn ids(bytes: &[u8]) -> HashSet<String> {
+    let value: serde_json::Value = serde_json::from_slice(bytes).unwrap();
+    value
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|item| item["@id"].as_str().map(String::from))
+        .collect()
+}
+
+#[test]
+fn test_paginate_jsonld() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let bytes = export_jsonld(temp_dir.path());
+
+    let (index, pages) = paginate_jsonld(&bytes, 3).unwrap();
+    assert_eq!(index.pages.len(), pages.len());
+    assert_eq!(index.pages.len(), index.item_count.div_ceil(3));
+    assert_eq!(index.pages[0].file, "page-00001.jsonld");
+    assert!(index.pages.iter().all(|page| page.item_count <= 3));
+    assert_eq!(
+        index
+            .pages
+            .iter()
+            .map(|page| page.item_count)
+            .sum::<usize>(),
+        index.item_count
+    );
+    assert_eq!(index.provenance.as_ref().unwrap()["tool"], "syster");
+
+    // Every page is a JSON-LD document; together they hold every object
+    let mut paged = HashSet::new();
+    for page in &pages {
+        JsonLd.read(page).unwrap();
+        paged.extend(ids(page));
+    }
+    assert_eq!(paged, ids(&bytes));
+
+    assert!(paginate_jsonld(&bytes, 0).is_err());
+}
+
+#[test]
+fn test_write_jsonld_pages() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let bytes = export_jsonld(temp_dir.path());
+    let dir = temp_dir.path().join("pages");
+
+    let small = write_jsonld_pages(&bytes, 1, &dir).unwrap();
+    let large = write_jsonld_pages(&bytes, 1000, &dir).unwrap();
+    assert!(small.pages.len() > 1);
+    assert_eq!(large.pages.len(), 1);
+
+    // Pages of the earlier export are gone
+    let mut files: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    files.sort();
+    assert_eq!(files, vec!["index.jsonld", "page-00001.jsonld"]);
+
+    let index: PageIndex =
+        serde_json::from_slice(&fs::read(dir.join("index.jsonld")).unwrap()).unwrap();
+    assert_eq!(index, large);
+    assert_eq!(index.kind, "syster:PageIndex");
+}
+
+#[test]
+fn test_cli_page_size() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let out = temp_dir.path().join("out");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args([
+            "--no-stdlib",
+            "--export",
+            "jsonld",
+            "--page-size",
+            "2",
+            "-o",
+        ])
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let index: PageIndex =
+        serde_json::from_slice(&fs::read(out.join("index.jsonld")).unwrap()).unwrap();
+    assert_eq!(index.page_size, 2);
+    for page in &index.pages {
+        assert!(out.join(&page.file).is_file());
+    }
+
+    // Only JSON-LD exports are paged
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--export", "xmi", "--page-size", "2", "-o"])
+        .arg(temp_dir.path().join("xmi"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("only supported for jsonld"));
+}
@@ -0,0 +1,190 @@
+//! Integration tests for the `kpar` subcommands.
+//!
+//! Tests that `syster kpar inspect` reports archive contents and validation
+//! status, that `syster kpar extract` unpacks archives to disk, that
+//! `syster kpar pack` builds archives from a source directory, and that
+//! exported archives carry a provenance record.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use syster::interchange::{Element, ElementId, ElementKind, Kpar, Model, ModelFormat};
+use syster_cli::inspect_kpar;
+use syster_cli::kpar::parse_dependency;
+use tempfile::TempDir;
+
+/// Write a small two-element model as a KPAR archive.
+fn write_sample_kpar(path: &Path) {
+    let mut model = Model::new();
+    model.metadata.name = Some("Sample".to_string());
+    model.add_element(
+        Element::new(ElementId::new("pkg-1"), ElementKind::Package).with_name("Vehicles"),
+    );
+    model.add_element(
+        Element::new(ElementId::new("part-1"), ElementKind::PartDefinition)
+            .with_name("Car")
+            .with_owner(ElementId::new("pkg-1")),
+    );
+
+    let bytes = Kpar.write(&model).expect("Should write KPAR");
+    fs::write(path, bytes).expect("Should write file");
+}
+
+#[test]
+fn test_inspect_kpar_reports_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let kpar_path = temp_dir.path().join("sample.kpar");
+    write_sample_kpar(&kpar_path);
+
+    let inspection = inspect_kpar(&kpar_path, false).unwrap();
+
+    assert!(inspection.is_valid(), "problems: {:?}", inspection.problems);
+    assert!(inspection.manifest.is_some());
+    assert_eq!(inspection.manifest_files, vec!["model/main.xmi"]);
+    assert_eq!(inspection.element_count(), 2);
+}
+
+#[test]
+fn test_inspect_kpar_without_manifest_is_invalid() {
+    let temp_dir = TempDir::new().unwrap();
+    let kpar_path = temp_dir.path().join("broken.kpar");
+
+    let mut zip = zip::ZipWriter::new(fs::File::create(&kpar_path).unwrap());
+    zip.start_file("readme.md", zip::write::SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(b"no model here").unwrap();
+    zip.finish().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["kpar", "inspect"])
+        .arg(&kpar_path)
+        .output()
+        .expect("Should run CLI");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "Invalid archive should fail");
+    assert!(stdout.contains("missing META-INF/manifest.xml"));
+    assert!(stdout.contains("no model files"));
+}
+
+#[test]
+fn test_cli_kpar_extract() {
+    let temp_dir = TempDir::new().unwrap();
+    let kpar_path = temp_dir.path().join("sample.kpar");
+    write_sample_kpar(&kpar_path);
+    let dest = temp_dir.path().join("unpacked");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["kpar", "extract"])
+        .arg(&kpar_path)
+        .arg(&dest)
+        .output()
+        .expect("Should run CLI");
+
+    assert!(output.status.success(), "CLI should exit successfully");
+    assert!(dest.join("META-INF/manifest.xml").is_file());
+    assert!(dest.join("model/main.xmi").is_file());
+}
+
+#[test]
+fn test_parse_dependency() {
+    let dep = parse_dependency("ISQ@2.0").unwrap();
+    assert_eq!(dep.name, "ISQ");
+    assert_eq!(dep.version.as_deref(), Some("2.0"));
+
+    assert_eq!(parse_dependency("SI").unwrap().version, None);
+    assert!(parse_dependency("@1.0").is_err());
+    assert!(parse_dependency("SI@").is_err());
+}
+
+#[test]
+fn test_cli_kpar_pack() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("vehicles");
+    fs::create_dir_all(src.join("parts")).unwrap();
+    fs::write(
+        src.join("vehicle.sysml"),
+        "package Vehicles { part def Car; }",
+    )
+    .unwrap();
+    fs::write(
+        src.join("parts/wheel.sysml"),
+        "package Parts { part def Wheel; }",
+    )
+    .unwrap();
+    let kpar_path = temp_dir.path().join("model.kpar");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["kpar", "pack"])
+        .arg(&src)
+        .arg("-o")
+        .arg(&kpar_path)
+        .args(["--name", "Vehicles", "--version", "1.2"])
+        .args(["--dependency", "ISQ@2.0", "--no-stdlib"])
+        .output()
+        .expect("Should run CLI");
+
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let inspection = inspect_kpar(&kpar_path, false).unwrap();
+    assert!(inspection.is_valid(), "problems: {:?}", inspection.problems);
+
+    let names: Vec<_> = inspection.entries.iter().map(|e| e.name.as_str()).collect();
+    assert!(names.contains(&"model/main.xmi"));
+    assert!(names.contains(&"src/vehicle.sysml"));
+    assert!(names.contains(&"src/parts/wheel.sysml"));
+
+    let manifest = inspection.manifest.unwrap();
+    assert!(manifest.contains(r#"<package name="Vehicles" version="1.2">"#));
+    assert!(manifest.contains(r#"<dependency name="ISQ" version="2.0"/>"#));
+}
+
+#[test]
+fn test_cli_inspect_prints_provenance() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(
+        src.join("vehicle.sysml"),
+        "package Vehicles { part def Car; }",
+    )
+    .unwrap();
+    let kpar_path = temp_dir.path().join("model.kpar");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&src)
+        .args(["--export", "kpar", "--no-stdlib", "-o"])
+        .arg(&kpar_path)
+        .env("SOURCE_DATE_EPOCH", "1700000000")
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let inspection = inspect_kpar(&kpar_path, false).unwrap();
+    let provenance = inspection.provenance.expect("Should have provenance");
+    assert_eq!(provenance.tool, "syster");
+    assert_eq!(provenance.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(provenance.timestamp, "2023-11-14T22:13:20Z");
+    assert!(provenance.input_hash.starts_with("sha256:"));
+    assert_eq!(provenance.stdlib_version, None);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["kpar", "inspect"])
+        .arg(&kpar_path)
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Provenance:"), "stdout: {}", stdout);
+    assert!(stdout.contains("Exported:   2023-11-14T22:13:20Z"));
+}
@@ -0,0 +1,113 @@
+//! Integration tests for SysML language-version selection.
+//!
+//! Tests version parsing, the keywords reported for each release, and
+//! `--sysml-version` with the `syster.toml` fallback.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, SysmlVersion, analyze};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "car.sysml",
+    "package Vehicle {\n\
+         \x20   part def Car {\n\
+         \x20       constant attribute wheels = 4;\n\
+         \x20       readonly attribute mass = 1500;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+fn version_issues(dir: &Path, version: Option<SysmlVersion>) -> Vec<(u32, u32, String)> {
+    let options = AnalysisOptions {
+        sysml_version: version,
+        ..Default::default()
+    };
+    let result = analyze(dir, &options).unwrap();
+    result
+        .diagnostics
+        .into_iter()
+        .filter(|d| d.code.as_deref() == Some("L0006"))
+        .map(|d| (d.line, d.col, d.message))
+        .collect()
+}
+
+#[test]
+fn test_parse_sysml_version() {
+    assert_eq!(
+        "2024-09".parse::<SysmlVersion>().unwrap(),
+        SysmlVersion::V2024_09
+    );
+    assert_eq!(SysmlVersion::V2025_04.to_string(), "2025-04");
+    assert!(SysmlVersion::V2024_09 < SysmlVersion::V2025_04);
+
+    let err = "2023-02".parse::<SysmlVersion>().unwrap_err();
+    assert!(err.contains("expected one of: 2024-09, 2025-04"), "{}", err);
+}
+
+#[test]
+fn test_keywords_per_version() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    assert!(version_issues(temp_dir.path(), None).is_empty());
+
+    let old = version_issues(temp_dir.path(), Some(SysmlVersion::V2024_09));
+    assert_eq!(old.len(), 1, "{:?}", old);
+    assert_eq!((old[0].0, old[0].1), (3, 9));
+    assert!(
+        old[0]
+            .2
+            .contains("`constant` is not available in SysML 2024-09"),
+        "{}",
+        old[0].2
+    );
+
+    let new = version_issues(temp_dir.path(), Some(SysmlVersion::V2025_04));
+    assert_eq!(new.len(), 1, "{:?}", new);
+    assert_eq!((new[0].0, new[0].1), (4, 9));
+    assert!(new[0].2.contains("removed in 2025-04"), "{}", new[0].2);
+}
+
+#[test]
+fn test_cli_sysml_version() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    fs::write(
+        temp_dir.path().join("syster.toml"),
+        "sysml_version = \"2024-09\"\n",
+    )
+    .unwrap();
+
+    // From syster.toml
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--json"])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("`constant` is not available"), "{}", stdout);
+    assert!(
+        !stdout.contains("`readonly` is not available"),
+        "{}",
+        stdout
+    );
+
+    // The flag overrides it
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--json", "--sysml-version", "2025-04"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("`readonly` is not available"), "{}", stdout);
+    assert!(
+        !stdout.contains("`constant` is not available"),
+        "{}",
+        stdout
+    );
+}
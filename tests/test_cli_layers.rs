@@ -0,0 +1,114 @@
+//! Integration tests for layer dependency rules.
+//!
+//! Tests package glob matching, the `L0007` errors for references a layer
+//! may not make, and the `[layers]` table of `syster.toml`.
+
+mod common;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, LayerRules, run_analysis_from_sources};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[
+    (
+        "app.sysml",
+        "package App {\n\
+         \x20   package Ui {\n\
+         \x20       part def Screen {\n\
+         \x20           part vehicle : Domain::Vehicle;\n\
+         \x20           part bus : Hardware::Bus;\n\
+         \x20       }\n\
+         \x20   }\n\
+         }\n",
+    ),
+    ("domain.sysml", "package Domain { part def Vehicle; }\n"),
+    ("hardware.sysml", "package Hardware { part def Bus; }\n"),
+];
+
+fn rules(layers: &[(&str, &[&str])]) -> LayerRules {
+    let layers: BTreeMap<String, Vec<String>> = layers
+        .iter()
+        .map(|(layer, allowed)| {
+            (
+                layer.to_string(),
+                allowed.iter().map(|glob| glob.to_string()).collect(),
+            )
+        })
+        .collect();
+    LayerRules::new(layers).unwrap()
+}
+
+#[test]
+fn test_layer_globs() {
+    let rules = rules(&[("App::*", &["Domain::*", "Std"]), ("Core::**::Impl", &[])]);
+
+    assert!(rules.allows("App::Ui", "Domain::Model"));
+    assert!(rules.allows("App::Ui::Widgets", "Std::Units"));
+    assert!(rules.allows("App::Ui", "App::Services"));
+    assert!(!rules.allows("App::Ui", "Hardware"));
+    assert!(!rules.allows("App::Ui", "Domain"));
+    assert!(rules.allows("Hardware", "App::Ui"));
+    assert!(!rules.allows("Core::A::B::Impl", "Domain::Model"));
+
+    let err = LayerRules::new(BTreeMap::from([(String::new(), Vec::new())])).unwrap_err();
+    assert!(err.contains("empty package glob"), "{}", err);
+}
+
+#[test]
+fn test_layer_violation_reports_both_ends() {
+    let sources: Vec<(String, String)> = MODELS
+        .iter()
+        .map(|(name, text)| (name.to_string(), text.to_string()))
+        .collect();
+
+    let options = AnalysisOptions::new().layers(rules(&[("App::*", &["Domain::*", "Domain"])]));
+    let result = run_analysis_from_sources(&sources, &options);
+    let errors: Vec<_> = result
+        .diagnostics
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("L0007"))
+        .collect();
+
+    assert_eq!(errors.len(), 1, "{:?}", result.diagnostics);
+    assert_eq!(errors[0].line, 5);
+    assert!(errors[0].file.ends_with("app.sysml"));
+    assert!(
+        errors[0].message.contains(
+            "`App::Ui::Screen::bus` depends on `Hardware::Bus`, but layer App::* may only depend on Domain::*, Domain"
+        ),
+        "{}",
+        errors[0].message
+    );
+    assert_eq!(errors[0].related.len(), 1);
+    assert!(errors[0].related[0].file.ends_with("hardware.sysml"));
+    assert_eq!(errors[0].related[0].line, 1);
+    assert_eq!(result.error_count, 1);
+}
+
+#[test]
+fn test_cli_layers_from_project_file() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let run = || {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg(temp_dir.path())
+            .arg("--no-stdlib")
+            .output()
+            .expect("Should run CLI")
+    };
+    assert!(run().status.success());
+
+    fs::write(
+        temp_dir.path().join("syster.toml"),
+        "[layers]\n\"App::*\" = [\"Domain\"]\n",
+    )
+    .unwrap();
+    let output = run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("L0007"), "stderr: {}", stderr);
+    assert!(stderr.contains("Hardware::Bus"), "stderr: {}", stderr);
+}
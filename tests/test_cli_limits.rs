@@ -0,0 +1,103 @@
+//! Integration tests for file size and count limits.
+//!
+//! Tests size parsing, that oversized files are skipped, that too many
+//! files fail the load, and the `--max-file-size`/`--max-files` flags.
+
+mod common;
+
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, WalkOptions, analyze, parse_file_size};
+use tempfile::TempDir;
+
+/// Two small files and a large generated one.
+fn write_models(dir: &Path) {
+    let mut generated = String::from("package Generated {\n");
+    for i in 0..2000 {
+        generated.push_str(&format!("    part def P{};\n", i));
+    }
+    generated.push_str("}\n");
+    common::write_models(
+        dir,
+        &[
+            ("car.sysml", "package Vehicle {\n    part def Car;\n}\n"),
+            ("engine.sysml", "package Power {\n    part def Engine;\n}\n"),
+            ("generated.sysml", &generated),
+        ],
+    );
+}
+
+fn analyze_dir(dir: &Path, walk: WalkOptions) -> Result<syster_cli::AnalysisResult, String> {
+    let options = AnalysisOptions {
+        walk,
+        ..Default::default()
+    };
+    analyze(dir, &options)
+}
+
+#[test]
+fn test_parse_file_size() {
+    assert_eq!(parse_file_size("512").unwrap(), 512);
+    assert_eq!(parse_file_size("4k").unwrap(), 4096);
+    assert_eq!(parse_file_size("10M").unwrap(), 10 * 1024 * 1024);
+    assert_eq!(parse_file_size("1G").unwrap(), 1 << 30);
+    assert!(parse_file_size("10X").is_err());
+    assert!(parse_file_size("M").is_err());
+}
+
+#[test]
+fn test_file_limits() {
+    let temp_dir = TempDir::new().unwrap();
+    write_models(temp_dir.path());
+
+    let result = analyze_dir(
+        temp_dir.path(),
+        WalkOptions {
+            max_file_size: Some(1024),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(result.file_count, 2);
+
+    let err = analyze_dir(
+        temp_dir.path(),
+        WalkOptions {
+            max_files: Some(2),
+            ..Default::default()
+        },
+    )
+    .unwrap_err();
+    assert!(
+        err.contains("3 SysML/KerML files, more than the maximum of 2"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_cli_file_limits() {
+    let temp_dir = TempDir::new().unwrap();
+    write_models(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--json", "--max-file-size", "1K"])
+        .output()
+        .expect("Should run CLI");
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning: skipping"), "{}", stderr);
+    assert!(stderr.contains("generated.sysml"), "{}", stderr);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["file_count"], 2);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--max-files", "2"])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("more than the maximum of 2"), "{}", stderr);
+}
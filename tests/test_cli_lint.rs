@@ -0,0 +1,123 @@
+//! Integration tests for the naming-convention linter.
+//!
+//! Tests the default PascalCase/camelCase rules, patterns and acronym policy
+//! from `syster.toml`, and `syster lint --fix` renaming declarations
+//! together with their references.
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, NamingConfig, lint};
+use tempfile::TempDir;
+
+const MODEL: &str = "package vehicles {
+    part def engine_block;
+    part def Car {
+        part Wheel : engine_block;
+    }
+}
+";
+
+fn codes(report: &syster_cli::LintReport) -> Vec<&str> {
+    report
+        .diagnostics
+        .iter()
+        .map(|d| d.code.as_deref().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_default_conventions() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("model.sysml");
+    fs::write(&file, MODEL).unwrap();
+
+    let report = lint(&file, &AnalysisOptions::new(), &NamingConfig::default()).unwrap();
+    assert_eq!(report.file_count, 1);
+    assert_eq!(codes(&report), vec!["L0003", "L0001", "L0002"]);
+    assert_eq!(report.diagnostics[1].line, 2);
+    assert!(
+        report.diagnostics[1]
+            .message
+            .ends_with("(rename to 'EngineBlock')"),
+        "{}",
+        report.diagnostics[1].message
+    );
+
+    let renames: Vec<_> = report
+        .renames
+        .iter()
+        .map(|r| (r.qualified_name.as_str(), r.to.as_str()))
+        .collect();
+    assert_eq!(
+        renames,
+        vec![
+            ("vehicles", "Vehicles"),
+            ("vehicles::Car::Wheel", "wheel"),
+            ("vehicles::engine_block", "EngineBlock"),
+        ]
+    );
+}
+
+#[test]
+fn test_project_file_patterns_and_acronyms() {
+    let temp_dir = TempDir::new().unwrap();
+    let models = temp_dir.path().join("models");
+    fs::create_dir(&models).unwrap();
+    fs::write(
+        temp_dir.path().join("syster.toml"),
+        "[lint.naming]\nusage = \"^[a-z][a-z0-9_]*$\"\nacronyms = [\"ECU\"]\nacronym_style = \"capitalized\"\n",
+    )
+    .unwrap();
+    fs::write(
+        models.join("ecu.sysml"),
+        "part def VehicleECU;\npart main_ecu : VehicleECU;\npart backupEcu : VehicleECU;\n",
+    )
+    .unwrap();
+
+    let config = NamingConfig::discover(&models).unwrap();
+    let report = lint(&models, &AnalysisOptions::new(), &config).unwrap();
+    assert_eq!(codes(&report), vec!["L0004", "L0002"]);
+    assert!(report.diagnostics[1].message.contains("'backupEcu'"));
+
+    // camelCase cannot satisfy the snake_case pattern: no rename proposed
+    let renames: Vec<_> = report.renames.iter().map(|r| r.to.as_str()).collect();
+    assert_eq!(renames, vec!["VehicleEcu"]);
+
+    fs::write(
+        temp_dir.path().join("syster.toml"),
+        "[lint.naming]\nusage = \"(\"\n",
+    )
+    .unwrap();
+    let err = NamingConfig::discover(&models).unwrap_err();
+    assert!(err.contains("Invalid pattern '('"), "{}", err);
+}
+
+#[test]
+fn test_cli_fix_renames_references() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("model.sysml");
+    fs::write(&file, MODEL).unwrap();
+    fs::write(
+        temp_dir.path().join("use.sysml"),
+        "part def Garage { part block : vehicles::engine_block; }\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["lint", "--no-stdlib", "--fix", "--src"])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.contains("Renamed vehicles::engine_block to EngineBlock"));
+
+    let fixed = fs::read_to_string(&file).unwrap();
+    assert!(fixed.starts_with("package Vehicles {\n    part def EngineBlock;"));
+    assert!(fixed.contains("part wheel : EngineBlock;"), "{}", fixed);
+    let user = fs::read_to_string(temp_dir.path().join("use.sysml")).unwrap();
+    assert_eq!(
+        user,
+        "part def Garage { part block : Vehicles::EngineBlock; }\n"
+    );
+}
@@ -0,0 +1,99 @@
+//! Integration tests for log output.
+//!
+//! Tests that progress messages and warnings go to stderr, apart from the
+//! results on stdout, and the `--log-level`/`--log-format` flags.
+
+mod common;
+
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+/// A small model and one larger than 1K.
+fn write_models(dir: &Path) {
+    let mut generated = String::from("package Generated {\n");
+    for i in 0..200 {
+        generated.push_str(&format!("    part def P{};\n", i));
+    }
+    generated.push_str("}\n");
+    common::write_models(
+        dir,
+        &[
+            ("car.sysml", "package Vehicle {\n    part def Car;\n}\n"),
+            ("generated.sysml", &generated),
+        ],
+    );
+}
+
+fn run(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(dir)
+        .args(["--no-stdlib", "--json", "--max-file-size", "1K"])
+        .args(args)
+        .output()
+        .expect("Should run CLI")
+}
+
+#[test]
+fn test_cli_verbose_logs_to_stderr() {
+    let temp_dir = TempDir::new().unwrap();
+    write_models(temp_dir.path());
+
+    let output = run(temp_dir.path(), &["--verbose"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Scanning directory: "), "{}", stderr);
+    assert!(stderr.contains("Loading: "), "{}", stderr);
+    assert!(stderr.contains("warning: skipping "), "{}", stderr);
+    // stdout carries only the result
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["file_count"], 1);
+}
+
+#[test]
+fn test_cli_log_level() {
+    let temp_dir = TempDir::new().unwrap();
+    write_models(temp_dir.path());
+
+    let output = run(temp_dir.path(), &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning: skipping "), "{}", stderr);
+    assert!(!stderr.contains("Loading: "), "{}", stderr);
+
+    let output = run(temp_dir.path(), &["--log-level", "info"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Loading: "), "{}", stderr);
+
+    let output = run(temp_dir.path(), &["--verbose", "--log-level", "error"]);
+    assert!(output.status.success());
+    assert!(
+        output.stderr.is_empty(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_cli_log_format_json() {
+    let temp_dir = TempDir::new().unwrap();
+    write_models(temp_dir.path());
+
+    let output = run(temp_dir.path(), &["--verbose", "--log-format", "json"]);
+    assert!(output.status.success());
+    let events: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert!(events.iter().any(|event| {
+        event["level"] == "WARN"
+            && event["fields"]["message"]
+                .as_str()
+                .is_some_and(|m| m.contains("generated.sysml"))
+    }));
+    assert!(events.iter().any(|event| {
+        event["level"] == "INFO"
+            && event["fields"]["message"]
+                .as_str()
+                .is_some_and(|m| m.starts_with("Loading: "))
+    }));
+}
@@ -0,0 +1,104 @@
+//! Integration tests for metadata-driven selection.
+//!
+//! Tests that exported symbols carry their metadata annotations and that
+//! `--with-metadata <MetaDef>` narrows `--export-ast` and `--export` to the
+//! tagged elements, their contents and their enclosing namespaces.
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{AnalysisOptions, export_ast_with_options};
+use tempfile::TempDir;
+
+/// Write a model where only `Brakes` is tagged `#Safety`.
+const MODELS: &[(&str, &str)] = &[(
+    "vehicle.sysml",
+    "package Vehicle {\n\
+         \x20   metadata def Safety;\n\
+         \x20   metadata def Itar;\n\
+         \x20   #Safety part def Brakes { part caliper; }\n\
+         \x20   part def Radio;\n\
+         }\n",
+)];
+
+/// Qualified names of the symbols in an AST export.
+fn exported_names(json: &str) -> Vec<String> {
+    let value: serde_json::Value = serde_json::from_str(json).unwrap();
+    value["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|f| f["symbols"].as_array().unwrap().iter())
+        .map(|s| s["qualified_name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_export_ast_with_metadata() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let json = export_ast_with_options(&file, &AnalysisOptions::default(), &["Safety".to_string()])
+        .unwrap();
+    let names = exported_names(&json);
+
+    assert!(names.contains(&"Vehicle".to_string()));
+    assert!(names.contains(&"Vehicle::Brakes".to_string()));
+    assert!(names.contains(&"Vehicle::Brakes::caliper".to_string()));
+    assert!(!names.contains(&"Vehicle::Radio".to_string()));
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let brakes = value["files"][0]["symbols"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["name"] == "Brakes")
+        .unwrap();
+    assert_eq!(brakes["metadata"], serde_json::json!(["Safety"]));
+}
+
+#[cfg(feature = "interchange")]
+#[test]
+fn test_export_model_with_metadata() {
+    use syster::interchange::{ModelFormat, Xmi};
+    use syster_cli::{ExportFilter, export_model_with_options};
+
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let filter = ExportFilter {
+        with_metadata: vec!["Vehicle::Safety".to_string()],
+        ..ExportFilter::default()
+    };
+    let (xmi, _) =
+        export_model_with_options(&file, "xmi", &AnalysisOptions::default(), false, &filter)
+            .unwrap();
+    let model = Xmi.read(&xmi).unwrap();
+    let names: Vec<_> = model
+        .elements
+        .values()
+        .filter_map(|e| e.name.as_deref())
+        .collect();
+
+    assert!(names.contains(&"Brakes"), "{:?}", names);
+    assert!(names.contains(&"caliper"), "{:?}", names);
+    assert!(!names.contains(&"Radio"), "{:?}", names);
+}
+
+#[test]
+fn test_cli_export_ast_with_metadata() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .args(["--export-ast", "--no-stdlib", "--with-metadata", "Safety"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+
+    let names = exported_names(&stdout);
+    assert!(names.contains(&"Vehicle::Brakes".to_string()));
+    assert!(!names.contains(&"Vehicle::Radio".to_string()));
+}
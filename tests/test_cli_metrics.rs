@@ -0,0 +1,103 @@
+//! Integration tests for Prometheus metrics.
+//!
+//! Tests the exposition format of a run's metrics, and pushing them to a
+//! Pushgateway (a local stand-in server) from the library and with
+//! `--push-gateway`.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use syster_cli::{AnalysisOptions, prometheus_metrics, push_metrics, run_analysis_from_sources};
+use tempfile::TempDir;
+
+const MODEL: &str = "package Vehicle {\n    part def Car;\n    part wheel : Wheel;\n}\n";
+
+/// Accept one HTTP request on a local port; the handle yields its request
+/// line and body.
+fn pushgateway() -> (String, JoinHandle<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let (mut length, mut expect_continue) = (0, false);
+        loop {
+            let mut header = String::new();
+            reader.read_line(&mut header).unwrap();
+            let header = header.trim().to_lowercase();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            expect_continue |= header == "expect: 100-continue";
+        }
+        if expect_continue {
+            stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").unwrap();
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        (
+            request_line.trim().to_string(),
+            String::from_utf8(body).unwrap(),
+        )
+    });
+    (url, handle)
+}
+
+#[test]
+fn test_prometheus_metrics() {
+    let result = run_analysis_from_sources(
+        &[("car.sysml".to_string(), MODEL.to_string())],
+        &AnalysisOptions::new(),
+    );
+
+    let metrics = prometheus_metrics(&result, Duration::from_millis(1500));
+    assert!(metrics.contains("# TYPE syster_analysis_duration_seconds gauge\n"));
+    assert!(metrics.contains("syster_analysis_duration_seconds 1.500\n"));
+    assert!(metrics.contains("syster_files 1\n"));
+    assert!(metrics.contains("syster_diagnostics{severity=\"error\"} 1\n"));
+    assert!(metrics.contains("syster_diagnostics{severity=\"warning\"} 0\n"));
+    assert!(metrics.contains("syster_doc_coverage_ratio 0.0000\n"));
+}
+
+#[test]
+fn test_push_metrics() {
+    let (url, server) = pushgateway();
+    push_metrics(&format!("{}/", url), "syster_files 3\n").unwrap();
+
+    let (request_line, body) = server.join().unwrap();
+    assert_eq!(request_line, "PUT /metrics/job/syster HTTP/1.1");
+    assert_eq!(body, "syster_files 3\n");
+}
+
+#[test]
+fn test_cli_push_gateway() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("car.sysml"), MODEL).unwrap();
+    let (url, server) = pushgateway();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--push-gateway", &url])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+
+    let (_, body) = server.join().unwrap();
+    assert!(body.contains("syster_symbols "), "{}", body);
+    assert!(
+        body.contains("syster_diagnostics{severity=\"error\"} 1"),
+        "{}",
+        body
+    );
+}
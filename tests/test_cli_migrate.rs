@@ -0,0 +1,144 @@
+//! Integration tests for schema-versioned exports and `syster migrate`.
+//!
+//! Tests that exports are stamped with the current schema version, that
+//! unversioned files get their superseded kinds and relationship encodings
+//! upgraded and are stamped, and that files from a newer schema are refused.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster::interchange::{JsonLd, ModelFormat, RelationshipKind};
+use syster_cli::{Provenance, SCHEMA_VERSION, export_model, migrate_file};
+use tempfile::TempDir;
+
+/// A JSON-LD file as written before the schema was versioned: no
+/// provenance, an old kind name and list-valued relationship ends.
+fn write_legacy_jsonld(path: &Path) {
+    let items = serde_json::json!([
+        { "@type": "Package", "@id": "p", "name": "Vehicles",
+          "ownedMember": [{ "@id": "a" }, { "@id": "b" }] },
+        { "@type": "PartDefinition", "@id": "a", "name": "Vehicle", "owner": { "@id": "p" } },
+        { "@type": "PartDefinition", "@id": "b", "name": "Car", "owner": { "@id": "p" } },
+        { "@type": "Superclassing", "@id": "r",
+          "source": [{ "@id": "b" }], "target": [{ "@id": "a" }] }
+    ]);
+    fs::write(path, serde_json::to_vec_pretty(&items).unwrap()).unwrap();
+}
+
+#[test]
+fn test_exports_are_current() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("model.sysml");
+    fs::write(&src, "package Vehicles { part def Vehicle; }").unwrap();
+    let xmi = temp_dir.path().join("model.xmi");
+    fs::write(
+        &xmi,
+        export_model(&src, "xmi", false, false, None, false).unwrap(),
+    )
+    .unwrap();
+
+    let bytes = fs::read(&xmi).unwrap();
+    let provenance = Provenance::from_export(&bytes).unwrap();
+    assert_eq!(provenance.schema_version, SCHEMA_VERSION);
+
+    let (migrated, report) = migrate_file(&xmi).unwrap();
+    assert_eq!(migrated, bytes);
+    assert_eq!(report.from_version, SCHEMA_VERSION);
+    assert!(report.applied.is_empty());
+}
+
+#[test]
+fn test_migrate_legacy_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let legacy = temp_dir.path().join("old.jsonld");
+    write_legacy_jsonld(&legacy);
+    assert!(
+        JsonLd
+            .read(&fs::read(&legacy).unwrap())
+            .unwrap()
+            .relationships
+            .is_empty()
+    );
+
+    let (migrated, report) = migrate_file(&legacy).unwrap();
+    assert_eq!(report.from_version, 1);
+    assert_eq!(report.to_version, SCHEMA_VERSION);
+    let changes: Vec<usize> = report.applied.iter().map(|m| m.changes).collect();
+    assert_eq!(changes, vec![1, 2]);
+
+    let model = JsonLd.read(&migrated).unwrap();
+    assert_eq!(model.relationships.len(), 1);
+    assert_eq!(
+        model.relationships[0].kind,
+        RelationshipKind::Specialization
+    );
+    let provenance = Provenance::from_export(&migrated).unwrap();
+    assert_eq!(provenance.schema_version, SCHEMA_VERSION);
+
+    // XMI: attributes and tags, but not longer names sharing the prefix
+    let xmi = temp_dir.path().join("old.xmi");
+    fs::write(
+        &xmi,
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <kerml:ItemFlow xmi:id=\"f\">\n\
+         \x20 <ownedRelationship xsi:type=\"kerml:Generalization\" xmi:id=\"g\"/>\n\
+         \x20 <ownedRelatedElement xsi:type=\"kerml:ItemFlowEnd\" xmi:id=\"e\"/>\n\
+         </kerml:ItemFlow>\n",
+    )
+    .unwrap();
+    let (migrated, report) = migrate_file(&xmi).unwrap();
+    assert_eq!(report.applied[0].changes, 3);
+    let text = String::from_utf8(migrated.clone()).unwrap();
+    assert!(text.contains("<kerml:Flow xmi:id=\"f\">"));
+    assert!(text.contains("</kerml:Flow>"));
+    assert!(text.contains("xsi:type=\"kerml:Specialization\""));
+    assert!(text.contains("xsi:type=\"kerml:ItemFlowEnd\""));
+    assert_eq!(
+        Provenance::from_export(&migrated).unwrap().schema_version,
+        SCHEMA_VERSION
+    );
+}
+
+#[test]
+fn test_cli_migrate() {
+    let temp_dir = TempDir::new().unwrap();
+    let legacy = temp_dir.path().join("old.jsonld");
+    write_legacy_jsonld(&legacy);
+    let out = temp_dir.path().join("new.jsonld");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("migrate")
+        .arg(&legacy)
+        .arg("-o")
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("from schema 1 to"), "{}", stderr);
+    assert!(stderr.contains("(1 changes)"), "{}", stderr);
+    assert!(
+        fs::read_to_string(&out)
+            .unwrap()
+            .contains("\"Subclassification\"")
+    );
+
+    // Files from a newer schema are refused
+    let newer = temp_dir.path().join("newer.jsonld");
+    let items = serde_json::json!([
+        { "@type": "Package", "@id": "p", "name": "Vehicles" },
+        { "@type": "syster:Provenance", "tool": "syster", "version": "9.0.0",
+          "timestamp": "2030-01-01T00:00:00Z", "input_hash": "sha256:00",
+          "schema_version": SCHEMA_VERSION + 1 }
+    ]);
+    fs::write(&newer, serde_json::to_vec(&items).unwrap()).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("migrate")
+        .arg(&newer)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("upgrade syster"));
+}
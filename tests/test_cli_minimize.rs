@@ -0,0 +1,109 @@
+//! Integration tests for minimal diagnostic reproducers.
+//!
+//! Tests that `minimize` drops the files and declarations a diagnostic does
+//! not need, keeps the diagnostic, and that `syster minimize` reports it.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, minimize, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// One unresolved type among unrelated declarations and files.
+const MODELS: &[(&str, &str)] = &[
+    (
+        "vehicle.sysml",
+        "package Vehicle {\n\
+         \x20   private import Parts::*;\n\
+         \x20   part def Car {\n\
+         \x20       part wheels : Wheel[4];\n\
+         \x20       part engine : MissingEngine;\n\
+         \x20       attribute mass;\n\
+         \x20   }\n\
+         \x20   part def Truck { part wheels : Wheel[6]; }\n\
+         }\n",
+    ),
+    (
+        "parts.sysml",
+        "package Parts { part def Wheel; part def Spare; }\n",
+    ),
+    ("boats.sysml", "package Boats { part def Sail; }\n"),
+];
+
+fn options() -> AnalysisOptions<'static> {
+    AnalysisOptions::new().load_stdlib(false)
+}
+
+#[test]
+fn test_minimize_keeps_only_what_reproduces() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let reproducer = minimize(temp_dir.path(), "E0001", None, &options()).unwrap();
+
+    assert_eq!(reproducer.original_files, 3);
+    assert_eq!(reproducer.files.len(), 1);
+    let text = &reproducer.files[0].text;
+    assert_eq!(reproducer.files[0].path, Path::new("vehicle.sysml"));
+    assert!(text.contains("part engine : MissingEngine;"), "{}", text);
+    for gone in ["Truck", "wheels", "mass", "import"] {
+        assert!(!text.contains(gone), "{}", text);
+    }
+    assert!(reproducer.lines() < reproducer.original_lines);
+
+    let result =
+        run_analysis_from_sources(&[("vehicle.sysml".to_string(), text.clone())], &options());
+    assert!(
+        result
+            .diagnostics
+            .iter()
+            .any(|d| d.code.as_deref() == Some("E0001"))
+    );
+}
+
+#[test]
+fn test_minimize_by_message() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    fs::write(
+        temp_dir.path().join("boats.sysml"),
+        "package Boats { part def Sail { part mast : MissingMast; } }\n",
+    )
+    .unwrap();
+
+    let reproducer = minimize(temp_dir.path(), "E0001", Some("MissingMast"), &options()).unwrap();
+    assert_eq!(reproducer.files.len(), 1);
+    assert_eq!(reproducer.files[0].path, Path::new("boats.sysml"));
+}
+
+#[test]
+fn test_minimize_needs_the_diagnostic() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let err = minimize(temp_dir.path(), "E0004", None, &options()).unwrap_err();
+    assert!(err.contains("No E0004 diagnostic"), "{}", err);
+    let err = minimize(temp_dir.path(), "E0001", Some("Nowhere"), &options()).unwrap_err();
+    assert!(err.contains("matching 'Nowhere'"), "{}", err);
+}
+
+#[test]
+fn test_cli_minimize() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["minimize", "--preserve-error", "E0001", "--no-stdlib"])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(stdout.contains("// vehicle.sysml"), "stdout: {}", stdout);
+    assert!(stdout.contains("MissingEngine"), "stdout: {}", stdout);
+    assert!(!stdout.contains("Boats"), "stdout: {}", stdout);
+    assert!(stderr.contains("Minimized 3 files"), "stderr: {}", stderr);
+}
@@ -0,0 +1,102 @@
+//! Integration tests for notebook output and machine mode.
+//!
+//! Tests the Jupyter display-data bundle of an analysis, and that
+//! `--machine` leaves nothing on stdout but the requested document.
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{AnalysisOptions, export_notebook, notebook_analysis, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// A model with an unresolved type, in a file whose name needs escaping.
+const FILE: &str = "car&wheel.sysml";
+const MODEL: &str = "package Vehicle {\n    part def Car;\n    part wheel : Wheel;\n}\n";
+
+const MODELS: &[(&str, &str)] = &[(FILE, MODEL)];
+
+#[test]
+fn test_notebook_analysis() {
+    let result = run_analysis_from_sources(
+        &[(FILE.to_string(), MODEL.to_string())],
+        &AnalysisOptions::default(),
+    );
+
+    let output = notebook_analysis(&result).unwrap();
+    assert_eq!(output.command, "analyze");
+    assert!(
+        output.text.starts_with("✗ Analyzed 1 files: 1 errors"),
+        "{}",
+        output.text
+    );
+    assert!(
+        output.html.contains("<tr class=\"syster-error\">"),
+        "{}",
+        output.html
+    );
+    assert!(
+        output.html.contains("car&amp;wheel.sysml:3:"),
+        "{}",
+        output.html
+    );
+    assert!(!output.html.contains("car&wheel"));
+    assert_eq!(output.data["error_count"], 1);
+    assert_eq!(output.data, serde_json::to_value(&result).unwrap());
+
+    let bundle: serde_json::Value =
+        serde_json::from_str(&export_notebook(&result).unwrap()).unwrap();
+    assert_eq!(bundle["data"]["text/html"], output.html.as_str());
+    assert_eq!(bundle["data"]["text/plain"], output.text.as_str());
+    assert_eq!(bundle["data"]["application/json"], output.data);
+    assert_eq!(bundle["metadata"]["syster"]["command"], "analyze");
+}
+
+#[test]
+fn test_cli_machine_mode() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    // Without a format, machine mode writes the --json result and nothing else
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--machine", "--no-stdlib", "--verbose"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["error_count"], 1);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--machine", "--no-stdlib", "--format", "notebook-json"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let bundle: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(
+        bundle["data"]["text/html"]
+            .as_str()
+            .unwrap()
+            .contains("syster-diagnostics")
+    );
+}
+
+#[test]
+fn test_cli_machine_mode_rejects_subcommands() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["--machine", "badge", "--metric", "errors", "--src"])
+        .arg(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--machine only applies to analysis"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,72 @@
+//! Integration tests for analysis options.
+//!
+//! Tests the `AnalysisOptions` setters, the severity threshold, and that
+//! the deprecated positional wrappers still analyze the same way.
+
+mod common;
+
+use std::path::PathBuf;
+use syster::hir::{Severity, SymbolKind};
+use syster_cli::{AnalysisOptions, Profile, Shard, analyze, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// An unresolved reference (an error) and an undocumented definition.
+const MODEL: &str = "package Vehicle {\n    part def Car;\n    part wheel : Wheel;\n}\n";
+
+const MODELS: &[(&str, &str)] = &[("car.sysml", MODEL)];
+
+#[test]
+fn test_options_setters() {
+    let shard = Shard::new(1, 2).unwrap();
+    let options = AnalysisOptions::new()
+        .verbose(true)
+        .load_stdlib(true)
+        .stdlib_path("lib")
+        .shard(shard)
+        .profile(Profile::Strict)
+        .strict_parse(true)
+        .min_severity(Severity::Warning);
+
+    assert!(options.verbose);
+    assert!(options.load_stdlib);
+    assert_eq!(options.stdlib_path, Some(PathBuf::from("lib")));
+    assert_eq!(options.shard, Some(shard));
+    assert_eq!(options.profile, Profile::Strict);
+    assert!(options.strict_parse);
+    assert_eq!(options.min_severity, Some(Severity::Warning));
+}
+
+#[test]
+fn test_min_severity() {
+    let sources = [("car.sysml".to_string(), MODEL.to_string())];
+
+    let options = AnalysisOptions::new().require_docs(vec![SymbolKind::PartDefinition]);
+    let with_docs = run_analysis_from_sources(&sources, &options);
+    assert!(with_docs.warning_count > 0, "{:?}", with_docs.diagnostics);
+
+    let errors_only =
+        run_analysis_from_sources(&sources, &options.clone().min_severity(Severity::Error));
+    assert_eq!(errors_only.warning_count, 0);
+    assert_eq!(errors_only.error_count, with_docs.error_count);
+    assert!(errors_only.error_count > 0);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_wrappers() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let new = analyze(&file, &AnalysisOptions::default()).unwrap();
+    let old = syster_cli::run_analysis_with_options(
+        &file,
+        false,
+        false,
+        None,
+        &AnalysisOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(old.symbol_count, new.symbol_count);
+    assert_eq!(old.error_count, new.error_count);
+    assert_eq!(old.diagnostics.len(), new.diagnostics.len());
+}
@@ -0,0 +1,155 @@
+//! Integration tests for package ownership from `MODELOWNERS`.
+//!
+//! Tests rule parsing and precedence, owners on diagnostics and package
+//! breakdowns, and the `--owner` filter.
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, ModelOwners, analyze, run_analysis_from_sources};
+use tempfile::TempDir;
+
+const OWNERS: &str = "\
+# package glob   owners
+**               @systems
+Vehicle          @vehicle-team
+Vehicle::Power   @power-team @vehicle-team
+";
+
+/// One unresolved type in each of `Vehicle`, `Vehicle::Power` and `Ground`.
+const MODELS: &[(&str, &str)] = &[
+    (
+        "vehicle.sysml",
+        "package Vehicle {\n\
+         \x20   part def Car { part engine : MissingEngine; }\n\
+         \x20   package Power {\n\
+         \x20       part def Battery { part cell : MissingCell; }\n\
+         \x20   }\n\
+         }\n",
+    ),
+    (
+        "ground.sysml",
+        "package Ground { part def Station { part antenna : MissingAntenna; } }\n",
+    ),
+    ("MODELOWNERS", OWNERS),
+];
+
+#[test]
+fn test_parse_model_owners() {
+    let owners = ModelOwners::parse(OWNERS).unwrap();
+
+    assert_eq!(owners.owners_of("Vehicle"), ["@vehicle-team"]);
+    assert_eq!(owners.owners_of("Vehicle::Body"), ["@vehicle-team"]);
+    assert_eq!(
+        owners.owners_of("Vehicle::Power::Cells"),
+        ["@power-team", "@vehicle-team"]
+    );
+    assert_eq!(owners.owners_of("Ground"), ["@systems"]);
+    assert!(ModelOwners::default().owners_of("Ground").is_empty());
+
+    let err = ModelOwners::parse("Vehicle::*\n").unwrap_err();
+    assert!(err.contains("line 1: no owners for Vehicle::*"), "{}", err);
+}
+
+#[test]
+fn test_owners_annotate_and_filter() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let owners = ModelOwners::discover(temp_dir.path()).unwrap().unwrap();
+
+    let result = analyze(
+        temp_dir.path(),
+        &AnalysisOptions::new().owners(owners.clone()),
+    )
+    .unwrap();
+    let owned: Vec<(u32, Vec<String>)> = result
+        .diagnostics
+        .iter()
+        .filter(|d| d.file.ends_with("vehicle.sysml"))
+        .map(|d| (d.line, d.owners.clone()))
+        .collect();
+    assert!(
+        owned.contains(&(2, vec!["@vehicle-team".to_string()])),
+        "{:?}",
+        owned
+    );
+    assert!(
+        owned.contains(&(
+            4,
+            vec!["@power-team".to_string(), "@vehicle-team".to_string()]
+        )),
+        "{:?}",
+        owned
+    );
+    let ground = result.packages.iter().find(|p| p.name == "Ground").unwrap();
+    assert_eq!(ground.owners, vec!["@systems"]);
+
+    let options = AnalysisOptions::new()
+        .owners(owners)
+        .only_owners(vec!["vehicle-team".to_string()]);
+    let result = analyze(temp_dir.path(), &options).unwrap();
+    assert!(!result.diagnostics.is_empty());
+    assert!(
+        result
+            .diagnostics
+            .iter()
+            .all(|d| d.file.ends_with("vehicle.sysml"))
+    );
+    assert_eq!(result.error_count, result.diagnostics.len());
+    let packages: Vec<_> = result.packages.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(packages, vec!["Vehicle"]);
+}
+
+#[test]
+fn test_owner_of_diagnostic_deep_in_package_body() {
+    // The diagnostic is five lines below the name of `Vehicle`, after the
+    // nested `Power` has closed
+    let sources = [(
+        "vehicle.sysml".to_string(),
+        "package Vehicle {\n\
+         \x20   package Power {\n\
+         \x20       part def Battery;\n\
+         \x20   }\n\
+         \x20   part def Car;\n\
+         \x20   part def Truck { part engine : MissingEngine; }\n\
+         }\n"
+        .to_string(),
+    )];
+    let owners = ModelOwners::parse(OWNERS).unwrap();
+
+    let result = run_analysis_from_sources(&sources, &AnalysisOptions::new().owners(owners));
+
+    let diag = result
+        .diagnostics
+        .iter()
+        .find(|d| d.message.contains("MissingEngine"))
+        .unwrap();
+    assert_eq!(diag.line, 6);
+    assert_eq!(diag.owners, vec!["@vehicle-team"]);
+}
+
+#[test]
+fn test_cli_owner_filter() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--owner", "@systems"])
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("MissingAntenna"), "stderr: {}", stderr);
+    assert!(stderr.contains("[@systems]"), "stderr: {}", stderr);
+    assert!(!stderr.contains("MissingEngine"), "stderr: {}", stderr);
+
+    fs::remove_file(temp_dir.path().join("MODELOWNERS")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--owner", "@systems"])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--owner needs a MODELOWNERS file"));
+}
@@ -0,0 +1,200 @@
+//! Integration tests for the parametric dependency graph.
+//!
+//! Tests attributes read by calculations and constraints and written from
+//! calculation results, clusters with their fixed and free values, DOT
+//! rendering, and `syster parametrics`.
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{Access, AnalysisOptions, ParametricKind, parametric_graph};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "car.sysml",
+    "package Vehicle {\n\
+         \x20   calc def TotalMass {\n\
+         \x20       in a;\n\
+         \x20       in b;\n\
+         \x20       return a + b;\n\
+         \x20   }\n\
+         \x20   constraint def MassLimit {\n\
+         \x20       in m;\n\
+         \x20       m <= 2000\n\
+         \x20   }\n\
+         \x20   part def Car {\n\
+         \x20       attribute engineMass = 200;\n\
+         \x20       attribute bodyMass;\n\
+         \x20       attribute totalMass = total;\n\
+         \x20       calc total : TotalMass {\n\
+         \x20           in a = engineMass;\n\
+         \x20           in b = bodyMass;\n\
+         \x20       }\n\
+         \x20       constraint limit : MassLimit {\n\
+         \x20           in m = totalMass;\n\
+         \x20       }\n\
+         \x20       attribute length;\n\
+         \x20       attribute width = 2;\n\
+         \x20       constraint aspect { length == width * 2 }\n\
+         \x20       constraint square { length == width }\n\
+         \x20   }\n\
+         }\n",
+)];
+
+#[test]
+fn test_parametric_graph() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let graph = parametric_graph(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    let nodes: Vec<(&str, ParametricKind, bool)> = graph
+        .nodes
+        .iter()
+        .map(|n| (n.name.as_str(), n.kind, n.fixed))
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            ("Vehicle::Car::aspect", ParametricKind::Constraint, true),
+            ("Vehicle::Car::bodyMass", ParametricKind::Attribute, false),
+            ("Vehicle::Car::engineMass", ParametricKind::Attribute, true),
+            ("Vehicle::Car::length", ParametricKind::Attribute, false),
+            ("Vehicle::Car::limit", ParametricKind::Constraint, false),
+            ("Vehicle::Car::square", ParametricKind::Constraint, true),
+            ("Vehicle::Car::total", ParametricKind::Calculation, false),
+            ("Vehicle::Car::totalMass", ParametricKind::Attribute, true),
+            ("Vehicle::Car::width", ParametricKind::Attribute, true),
+        ]
+    );
+
+    let edges: Vec<(&str, &str, Access)> = graph
+        .edges
+        .iter()
+        .map(|e| {
+            (
+                &e.calculation["Vehicle::Car::".len()..],
+                &e.attribute["Vehicle::Car::".len()..],
+                e.access,
+            )
+        })
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            ("aspect", "length", Access::Reads),
+            ("aspect", "width", Access::Reads),
+            ("limit", "totalMass", Access::Reads),
+            ("square", "length", Access::Reads),
+            ("square", "width", Access::Reads),
+            ("total", "bodyMass", Access::Reads),
+            ("total", "engineMass", Access::Reads),
+            ("total", "totalMass", Access::Writes),
+        ]
+    );
+    assert_eq!(graph.nodes[6].line, 15);
+}
+
+#[test]
+fn test_over_constrained_clusters_and_dot() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let graph = parametric_graph(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    let clusters: Vec<(usize, usize, usize, bool)> = graph
+        .clusters
+        .iter()
+        .map(|c| (c.members.len(), c.fixed, c.free, c.is_over_constrained()))
+        .collect();
+    assert_eq!(clusters, vec![(5, 0, 1, false), (4, 2, 1, true)]);
+    let over: Vec<_> = graph.over_constrained().collect();
+    assert_eq!(
+        over[0].members,
+        vec![
+            "Vehicle::Car::aspect",
+            "Vehicle::Car::length",
+            "Vehicle::Car::square",
+            "Vehicle::Car::width",
+        ]
+    );
+
+    let dot = graph.to_dot();
+    assert!(dot.starts_with("digraph parametrics {\n"), "{}", dot);
+    assert!(dot.contains(
+        "    subgraph cluster_1 {\n        label=\"2 fixed, 1 free (over-constrained)\";\n        color=red;\n"
+    ));
+    assert!(dot.contains("        \"Vehicle::Car::total\" [shape=box];\n"));
+    assert!(
+        dot.contains(
+            "    \"Vehicle::Car::engineMass\" -> \"Vehicle::Car::total\" [label=reads];\n"
+        )
+    );
+    assert!(
+        dot.contains(
+            "    \"Vehicle::Car::total\" -> \"Vehicle::Car::totalMass\" [label=writes];\n"
+        )
+    );
+}
+
+#[test]
+fn test_model_without_calculations() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("a.sysml"),
+        "package P {\n    part def A {\n        attribute x = 1;\n    }\n}\n",
+    )
+    .unwrap();
+
+    let graph = parametric_graph(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    assert!(graph.nodes.is_empty());
+    assert!(graph.clusters.is_empty());
+    assert_eq!(
+        graph.to_dot(),
+        "digraph parametrics {\n    rankdir=LR;\n}\n"
+    );
+}
+
+#[test]
+fn test_cli_parametrics() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let run = |json: bool| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_syster"));
+        command.args(["parametrics", "--no-stdlib", "--src"]);
+        command.arg(temp_dir.path());
+        if json {
+            command.arg("--json");
+        }
+        command.output().expect("Should run CLI")
+    };
+
+    let output = run(false);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stdout.starts_with("digraph parametrics {"), "{}", stdout);
+    assert!(
+        stderr.contains(
+            "✗ Over-constrained: 2 equalities on 1 free attributes among Vehicle::Car::aspect, "
+        ),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains(
+            "✗ 4 calculations and constraints on 5 attributes in 2 clusters: 1 over-constrained"
+        ),
+        "{}",
+        stderr
+    );
+
+    let output = run(true);
+    let graph: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(graph["edges"][7]["access"], "writes");
+    assert_eq!(graph["nodes"][6]["kind"], "calculation");
+    assert_eq!(graph["clusters"][1]["fixed"], 2);
+}
@@ -0,0 +1,106 @@
+//! Integration tests for analysing files with syntax errors.
+//!
+//! Tests that a broken file still contributes the symbols the parser
+//! recovered, that `strict_parse` leaves it out, and the summary line.
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{AnalysisOptions, ParseRecovery, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// `engine.sysml` has a syntax error after `Engine`; `car.sysml` uses it.
+const MODELS: &[(&str, &str)] = &[
+    (
+        "engine.sysml",
+        "package Power {\n\
+         \x20   part def Engine;\n\
+         \x20   part def Motor {{\n\
+         }\n",
+    ),
+    (
+        "car.sysml",
+        "package Vehicle {\n\
+         \x20   import Power::*;\n\
+         \x20   part engine : Engine;\n\
+         }\n",
+    ),
+];
+
+fn analyze(strict_parse: bool) -> syster_cli::AnalysisResult {
+    let sources: Vec<(String, String)> = MODELS
+        .iter()
+        .map(|(name, text)| (name.to_string(), text.to_string()))
+        .collect();
+    let options = AnalysisOptions {
+        strict_parse,
+        ..Default::default()
+    };
+    run_analysis_from_sources(&sources, &options)
+}
+
+fn unresolved_engine(result: &syster_cli::AnalysisResult) -> bool {
+    result
+        .diagnostics
+        .iter()
+        .any(|d| d.code.as_deref() == Some("E0001") && d.message.contains("Engine"))
+}
+
+#[test]
+fn test_recovers_symbols_from_broken_file() {
+    let result = analyze(false);
+    assert_eq!(result.file_count, 2);
+    assert_eq!(result.syntax_errors.files, 1);
+    assert!(
+        result.syntax_errors.symbols > 0,
+        "{:?}",
+        result.syntax_errors
+    );
+    assert!(!unresolved_engine(&result), "{:?}", result.diagnostics);
+}
+
+#[test]
+fn test_strict_parse_excludes_broken_file() {
+    let result = analyze(true);
+    assert_eq!(result.file_count, 1);
+    assert_eq!(
+        result.syntax_errors,
+        ParseRecovery {
+            files: 1,
+            symbols: 0
+        }
+    );
+    assert!(unresolved_engine(&result), "{:?}", result.diagnostics);
+}
+
+#[test]
+fn test_cli_parse_recovery_summary() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .arg("--no-stdlib")
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("1 files with syntax errors,") && stdout.contains("symbols recovered"),
+        "{}",
+        stdout
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--strict-parse"])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("1 files with syntax errors, excluded"),
+        "{}",
+        stdout
+    );
+}
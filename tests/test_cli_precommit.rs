@@ -0,0 +1,99 @@
+//! Integration tests for the pre-commit integration.
+//!
+//! Tests that `--staged` checks the index version of staged files only, and
+//! that `syster install-hooks` writes a pre-commit hook without clobbering a
+//! foreign one.
+
+mod common;
+
+use common::git;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, run_analysis_staged};
+use tempfile::TempDir;
+
+/// A repository with a committed `a.sysml`.
+fn init_repo(dir: &Path) {
+    git(dir, &["init", "-q"]);
+    fs::write(dir.join("a.sysml"), "part def A;").unwrap();
+    git(dir, &["add", "."]);
+    git(dir, &["commit", "-q", "-m", "first"]);
+}
+
+#[test]
+fn test_staged_checks_index_version() {
+    let temp_dir = TempDir::new().unwrap();
+    init_repo(temp_dir.path());
+    let options = AnalysisOptions::new();
+
+    let result = run_analysis_staged(temp_dir.path(), &options).unwrap();
+    assert_eq!(result.file_count, 0);
+
+    // Stage a broken file, then fix it without staging the fix
+    let b = temp_dir.path().join("b.sysml");
+    fs::write(&b, "part def B { part x : Undefined; }").unwrap();
+    git(temp_dir.path(), &["add", "b.sysml"]);
+    fs::write(&b, "part def B { part x : A; }").unwrap();
+
+    let result = run_analysis_staged(temp_dir.path(), &options).unwrap();
+    assert_eq!(result.file_count, 1);
+    assert!(result.error_count + result.warning_count > 0);
+    assert!(
+        result
+            .diagnostics
+            .iter()
+            .all(|d| d.file.ends_with("b.sysml"))
+    );
+}
+
+#[test]
+fn test_install_hooks() {
+    let temp_dir = TempDir::new().unwrap();
+    init_repo(temp_dir.path());
+    let hook = temp_dir.path().join(".git/hooks/pre-commit");
+
+    let install = |force: bool| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_syster"));
+        command.arg("install-hooks").arg(temp_dir.path());
+        if force {
+            command.arg("--force");
+        }
+        command.output().expect("Should run CLI")
+    };
+
+    assert!(install(false).status.success());
+    let script = fs::read_to_string(&hook).unwrap();
+    assert!(script.starts_with("#!/bin/sh\n"), "{}", script);
+    assert!(script.contains("syster '.' --staged"), "{}", script);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&hook).unwrap().permissions().mode();
+        assert_eq!(mode & 0o111, 0o111);
+    }
+
+    // Reinstalling over our own hook is fine, a foreign hook needs --force
+    assert!(install(false).status.success());
+    fs::write(&hook, "#!/bin/sh\nmake lint\n").unwrap();
+    let output = install(false);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+    assert!(install(true).status.success());
+}
+
+#[test]
+fn test_cli_staged_without_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    init_repo(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--staged", "--no-stdlib"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.contains("Analyzed 0 files"), "{}", stdout);
+}
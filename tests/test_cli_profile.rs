@@ -0,0 +1,115 @@
+//! Integration tests for analysis profiles.
+//!
+//! Tests that `strict` enables the duplicate and documentation passes and
+//! fails on warnings, that `permissive` downgrades unresolved names to
+//! warnings, and the `--profile` flag.
+
+use std::fs;
+use std::process::Command;
+use syster::hir::Severity;
+use syster_cli::{AnalysisOptions, Profile, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// `Vehicle::Engine` defined in two files, and a reference to a type that
+/// does not exist.
+fn analyze(profile: Profile) -> syster_cli::AnalysisResult {
+    let sources = [
+        (
+            "engine.sysml".to_string(),
+            "package Vehicle {\n\
+             \x20   doc /* Vehicle parts. */\n\
+             \x20   part def Engine {\n\
+             \x20       doc /* Drives the wheels. */\n\
+             \x20   }\n\
+             }\n"
+            .to_string(),
+        ),
+        (
+            "car.sysml".to_string(),
+            "package Vehicle {\n\
+             \x20   part def Engine;\n\
+             \x20   part car : Chassis;\n\
+             }\n"
+            .to_string(),
+        ),
+    ];
+    let options = AnalysisOptions {
+        profile,
+        ..Default::default()
+    };
+    run_analysis_from_sources(&sources, &options)
+}
+
+#[test]
+fn test_strict_profile() {
+    let standard = analyze(Profile::Standard);
+    assert!(
+        standard
+            .diagnostics
+            .iter()
+            .all(|d| !d.message.contains("duplicate definition"))
+    );
+
+    let strict = analyze(Profile::Strict);
+    assert_eq!(strict.warning_count, 0);
+    let duplicates: Vec<_> = strict
+        .diagnostics
+        .iter()
+        .filter(|d| d.message.contains("duplicate definition"))
+        .collect();
+    assert_eq!(duplicates.len(), 2, "{:?}", strict.diagnostics);
+    assert!(
+        duplicates
+            .iter()
+            .all(|d| matches!(d.severity, Severity::Error))
+    );
+
+    // The undocumented copy of Engine is reported; the documented one is not
+    let missing_docs: Vec<_> = strict
+        .diagnostics
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("L0005"))
+        .collect();
+    assert_eq!(missing_docs.len(), 1, "{:?}", strict.diagnostics);
+    assert!(missing_docs[0].file.ends_with("car.sysml"));
+    assert!(strict.error_count > standard.error_count);
+}
+
+#[test]
+fn test_permissive_profile() {
+    let unresolved = |result: &syster_cli::AnalysisResult| {
+        result
+            .diagnostics
+            .iter()
+            .find(|d| d.code.as_deref() == Some("E0001") && d.message.contains("Chassis"))
+            .map(|d| d.severity)
+    };
+
+    let standard = analyze(Profile::Standard);
+    assert!(matches!(unresolved(&standard), Some(Severity::Error)));
+
+    let permissive = analyze(Profile::Permissive);
+    assert!(matches!(unresolved(&permissive), Some(Severity::Warning)));
+    assert_eq!(permissive.error_count, 0, "{:?}", permissive.diagnostics);
+}
+
+#[test]
+fn test_cli_profile() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("car.sysml"),
+        "package Vehicle {\n    part car : Chassis;\n}\n",
+    )
+    .unwrap();
+
+    let run = |profile: &str| {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg(temp_dir.path())
+            .args(["--no-stdlib", "--profile", profile])
+            .output()
+            .expect("Should run CLI")
+    };
+    assert!(!run("standard").status.success());
+    assert!(run("permissive").status.success());
+    assert!(!run("strict").status.success());
+}
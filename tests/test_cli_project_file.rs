@@ -0,0 +1,75 @@
+//! Integration tests for the project file, `syster.toml`.
+//!
+//! Tests finding the nearest project file, each module reading its own
+//! table, and keys no module reads being rejected wherever they are.
+
+use std::fs;
+use syster_cli::project::{PROJECT_FILE, find_project_file};
+use syster_cli::{Budgets, LayerRules, Manifest, NamingConfig};
+use tempfile::TempDir;
+
+#[test]
+fn test_find_project_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let models = temp_dir.path().join("models/vehicle");
+    fs::create_dir_all(&models).unwrap();
+    fs::write(models.join("car.sysml"), "package Car;\n").unwrap();
+    assert_eq!(find_project_file(&models), None);
+
+    fs::write(temp_dir.path().join(PROJECT_FILE), "").unwrap();
+    let found = find_project_file(&models.join("car.sysml")).unwrap();
+    assert_eq!(
+        found,
+        std::path::absolute(temp_dir.path().join("syster.toml")).unwrap()
+    );
+}
+
+#[test]
+fn test_tables_read_by_their_modules() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join(PROJECT_FILE);
+    fs::write(
+        &path,
+        "sysml_version = \"2025-04\"\n\n\
+         [lint.naming]\nacronyms = [\"ECU\"]\n\n\
+         [budgets]\nmax_file_lines = 500\n\n\
+         [dependencies]\ncommon = { path = \"../common\" }\n",
+    )
+    .unwrap();
+
+    assert_eq!(NamingConfig::load(&path).unwrap().acronyms, vec!["ECU"]);
+    assert_eq!(
+        Budgets::load(&path).unwrap().unwrap().max_file_lines,
+        Some(500)
+    );
+    assert_eq!(Manifest::load(&path).unwrap().dependencies.len(), 1);
+    assert!(LayerRules::load(&path).unwrap().is_none());
+}
+
+#[test]
+fn test_unknown_keys_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join(PROJECT_FILE);
+
+    // At the top level, every module reports it
+    fs::write(&path, "[budget]\nmax_file_lines = 500\n").unwrap();
+    let err = Budgets::load(&path).unwrap_err();
+    assert!(err.contains("unknown key `budget`"), "{}", err);
+    let err = NamingConfig::load(&path).unwrap_err();
+    assert!(err.contains("unknown key `budget`"), "{}", err);
+
+    // In a nested table
+    fs::write(&path, "[lint.nameing]\nacronyms = [\"ECU\"]\n").unwrap();
+    let err = NamingConfig::load(&path).unwrap_err();
+    assert!(err.contains("unknown key `lint.nameing`"), "{}", err);
+
+    // Inside a table
+    fs::write(
+        &path,
+        "[dependencies]\ncommon = { path = \"a\", branch = \"main\" }\n",
+    )
+    .unwrap();
+    let err = Manifest::load(&path).unwrap_err();
+    assert!(err.contains("[dependencies]"), "{}", err);
+    assert!(err.contains("branch"), "{}", err);
+}
@@ -0,0 +1,243 @@
+//! Integration tests for per-project analysis of monorepos.
+//!
+//! Tests finding the nested projects under an input, analyzing each with
+//! its own `syster.toml` and without the files of the projects nested in
+//! it, the aggregate, the `--per-project` flag, and exporting each project
+//! on its own.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster_cli::{AnalysisOptions, WalkOptions, analyze_projects, find_projects};
+use tempfile::TempDir;
+
+/// Write a repository with a shared model outside every project, a project
+/// with a file over its line budget, and a project with a nested one.
+fn write_repository(dir: &Path) {
+    fs::write(
+        dir.join("shared.sysml"),
+        "package Shared {\n    part def Frame;\n}\n",
+    )
+    .unwrap();
+
+    let avionics = dir.join("teams/avionics");
+    fs::create_dir_all(&avionics).unwrap();
+    fs::write(
+        avionics.join("syster.toml"),
+        "[budgets]\nmax_file_lines = 3\n",
+    )
+    .unwrap();
+    fs::write(
+        avionics.join("avionics.sysml"),
+        "package Avionics {\n    part def Radio;\n    part def Antenna;\n}\n",
+    )
+    .unwrap();
+
+    let battery = dir.join("teams/powertrain/battery");
+    fs::create_dir_all(&battery).unwrap();
+    fs::write(dir.join("teams/powertrain/syster.toml"), "").unwrap();
+    fs::write(
+        dir.join("teams/powertrain/powertrain.sysml"),
+        "package Powertrain {\n    part def Engine;\n}\n",
+    )
+    .unwrap();
+    fs::write(battery.join("syster.toml"), "").unwrap();
+    fs::write(
+        battery.join("battery.sysml"),
+        "package Battery {\n    part def Cell;\n}\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_find_projects() {
+    let temp_dir = TempDir::new().unwrap();
+    write_repository(temp_dir.path());
+
+    let projects = find_projects(temp_dir.path(), &WalkOptions::default()).unwrap();
+
+    let relative: Vec<PathBuf> = projects
+        .iter()
+        .map(|p| p.strip_prefix(temp_dir.path()).unwrap().to_path_buf())
+        .collect();
+    assert_eq!(
+        relative,
+        vec![
+            PathBuf::from("teams/avionics"),
+            PathBuf::from("teams/powertrain"),
+            PathBuf::from("teams/powertrain/battery"),
+        ]
+    );
+}
+
+#[test]
+fn test_analyze_projects() {
+    let temp_dir = TempDir::new().unwrap();
+    write_repository(temp_dir.path());
+
+    let report = analyze_projects(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    let projects: Vec<(&str, usize, usize)> = report
+        .projects
+        .iter()
+        .map(|p| (p.name.as_str(), p.result.file_count, p.result.error_count))
+        .collect();
+    assert_eq!(
+        projects,
+        vec![
+            (".", 1, 0),
+            ("teams/avionics", 1, 1),
+            ("teams/powertrain", 1, 0),
+            ("teams/powertrain/battery", 1, 0),
+        ]
+    );
+    // Only the avionics project has a line budget
+    let avionics = &report.projects[1].result;
+    assert_eq!(avionics.diagnostics[0].code.as_deref(), Some("L0013"));
+    assert!(avionics.diagnostics[0].file.ends_with("avionics.sysml"));
+
+    assert_eq!(report.aggregate.file_count, 4);
+    assert_eq!(report.aggregate.error_count, 1);
+    assert_eq!(report.aggregate.diagnostics.len(), 1);
+    assert_eq!(
+        report.aggregate.diagnostics[0].message,
+        avionics.diagnostics[0].message
+    );
+}
+
+#[test]
+fn test_analyze_projects_needs_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    write_repository(temp_dir.path());
+    let file = temp_dir.path().join("shared.sysml");
+
+    let err = analyze_projects(&file, &AnalysisOptions::new()).unwrap_err();
+
+    assert_eq!(
+        err,
+        format!("Per-project analysis needs a directory: {}", file.display())
+    );
+}
+
+#[test]
+fn test_cli_per_project() {
+    let temp_dir = TempDir::new().unwrap();
+    write_repository(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--per-project", "--no-stdlib"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(stderr.contains("error[L0013]: "), "{}", stderr);
+    assert!(
+        stdout.contains("  .: 1 files, 0 errors, 0 warnings"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("  teams/avionics: 1 files, 1 errors, 0 warnings"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stderr.contains("✗ Analyzed 4 files: 1 errors, 0 warnings"),
+        "{}",
+        stderr
+    );
+
+    let reports = temp_dir.path().join("reports");
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path().join("teams"))
+        .args(["--per-project", "--no-stdlib", "--json", "-o"])
+        .arg(&reports)
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    let read = |path: &str| -> serde_json::Value {
+        serde_json::from_str(&fs::read_to_string(reports.join(path)).unwrap()).unwrap()
+    };
+    assert_eq!(read("avionics/analysis.json")["error_count"], 1);
+    assert_eq!(read("powertrain/battery/analysis.json")["file_count"], 1);
+    assert_eq!(read("aggregate.json")["file_count"], 3);
+    // teams holds no syster.toml or model files of its own
+    assert!(!reports.join("analysis.json").exists());
+}
+
+#[cfg(feature = "interchange")]
+#[test]
+fn test_export_projects() {
+    let temp_dir = TempDir::new().unwrap();
+    write_repository(temp_dir.path());
+
+    let exports = syster_cli::export_projects(
+        temp_dir.path(),
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &syster_cli::ExportFilter::default(),
+    )
+    .unwrap();
+
+    let names: Vec<&str> = exports.projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            ".",
+            "teams/avionics",
+            "teams/powertrain",
+            "teams/powertrain/battery"
+        ]
+    );
+    let xmi = |name: &str| {
+        let project = exports.projects.iter().find(|p| p.name == name).unwrap();
+        String::from_utf8(project.bytes.clone()).unwrap()
+    };
+    // Each project's model holds its own packages only
+    assert!(xmi(".").contains("declaredName=\"Shared\""));
+    assert!(!xmi(".").contains("declaredName=\"Avionics\""));
+    assert!(xmi("teams/powertrain").contains("declaredName=\"Powertrain\""));
+    assert!(!xmi("teams/powertrain").contains("declaredName=\"Battery\""));
+    let aggregate = String::from_utf8(exports.aggregate).unwrap();
+    for package in ["Shared", "Avionics", "Powertrain", "Battery"] {
+        assert!(aggregate.contains(&format!("declaredName=\"{}\"", package)));
+    }
+}
+
+#[cfg(feature = "interchange")]
+#[test]
+fn test_cli_per_project_export() {
+    let temp_dir = TempDir::new().unwrap();
+    write_repository(temp_dir.path());
+    let models = temp_dir.path().join("models");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path().join("teams"))
+        .args(["--per-project", "--no-stdlib", "--export", "xmi", "-o"])
+        .arg(&models)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(models.join("avionics/model.xmi").is_file());
+    assert!(models.join("powertrain/model.xmi").is_file());
+    assert!(models.join("powertrain/battery/model.xmi").is_file());
+    assert!(models.join("aggregate.xmi").is_file());
+    assert!(!models.join("model.xmi").exists());
+
+    // The projects' models need a directory to go into
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--per-project", "--no-stdlib", "--export", "xmi"])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("needs -o DIR"), "{}", stderr);
+}
@@ -0,0 +1,151 @@
+//! Integration tests for redacted exports.
+//!
+//! Tests that `--export ... --redact` replaces names and documentation with
+//! pseudonyms that stay stable across runs, keeps element IDs, and writes a
+//! mapping file that reverses the redaction.
+
+#![cfg(feature = "interchange")]
+
+use std::collections::HashSet;
+use std::fs;
+use std::process::Command;
+use syster::interchange::{Element, ElementId, ElementKind, Model, ModelFormat, Xmi};
+use syster_cli::{
+    AnalysisOptions, ExportFilter, RedactionMap, export_model_with_options, redact_model,
+    unredact_model,
+};
+use tempfile::TempDir;
+
+/// Export `input` as redacted XMI, keeping the mapping in `map`.
+fn redacted_export(input: &std::path::Path, map: &std::path::Path) -> Model {
+    let filter = ExportFilter {
+        redaction_map: Some(map.to_path_buf()),
+        ..ExportFilter::default()
+    };
+    let (xmi, _) =
+        export_model_with_options(input, "xmi", &AnalysisOptions::default(), false, &filter)
+            .expect("Should export");
+    Xmi.read(&xmi).expect("Should read XMI")
+}
+
+#[test]
+fn test_redact_keeps_ids_and_structure() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("radar.sysml");
+    fs::write(
+        &file,
+        "package Radar { part def Antenna; part def Array { part feed : Antenna; } }",
+    )
+    .unwrap();
+
+    let (plain, _) = export_model_with_options(
+        &file,
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter::default(),
+    )
+    .unwrap();
+    let plain = Xmi.read(&plain).unwrap();
+    let redacted = redacted_export(&file, &temp_dir.path().join("map.json"));
+
+    assert_eq!(
+        plain.elements.keys().collect::<Vec<_>>(),
+        redacted.elements.keys().collect::<Vec<_>>()
+    );
+    assert_eq!(plain.relationships.len(), redacted.relationships.len());
+    for element in redacted.elements.values() {
+        let name = element.name.as_deref().unwrap_or_default();
+        assert!(
+            !["Radar", "Antenna", "Array", "feed"].contains(&name),
+            "{} was not redacted",
+            name
+        );
+        assert_eq!(element.owner, plain.elements[&element.id].owner);
+    }
+}
+
+#[test]
+fn test_redact_is_stable_and_reversible() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("radar.sysml");
+    let map_path = temp_dir.path().join("map.json");
+    fs::write(&file, "package Radar { part def Antenna; }").unwrap();
+
+    let first = redacted_export(&file, &map_path);
+    let second = redacted_export(&file, &map_path);
+    let names = |model: &Model| -> Vec<String> {
+        model
+            .elements
+            .values()
+            .filter_map(|e| e.qualified_name.as_deref().map(str::to_string))
+            .collect()
+    };
+    assert_eq!(names(&first), names(&second));
+
+    let map = RedactionMap::load(&map_path).unwrap();
+    let mut restored = second;
+    unredact_model(&mut restored, &map);
+    let restored = names(&restored);
+    assert!(
+        restored.contains(&"Radar::Antenna".to_string()),
+        "{:?}",
+        restored
+    );
+}
+
+#[test]
+fn test_redact_replaces_documentation() {
+    let mut model = Model::new();
+    let mut element = Element::new("e1", ElementKind::PartDefinition)
+        .with_name("Antenna")
+        .with_qualified_name("Radar::Antenna");
+    element.documentation = Some("Operates at 94 GHz".into());
+    model.add_element(element);
+    model.add_element(
+        Element::new("e2", ElementKind::PartDefinition)
+            .with_name("Real")
+            .with_qualified_name("ScalarValues::Real"),
+    );
+
+    let mut map = RedactionMap::default();
+    let keep = HashSet::from(["ScalarValues::Real".to_string()]);
+    redact_model(&mut model, &mut map, &keep);
+
+    let antenna = &model.elements[&ElementId::new("e1")];
+    assert_eq!(antenna.name.as_deref(), Some("N1"));
+    assert!(!antenna.documentation.as_deref().unwrap().contains("GHz"));
+    assert_eq!(
+        model.elements[&ElementId::new("e2")].name.as_deref(),
+        Some("Real")
+    );
+    assert_eq!(
+        map.documentation["Operates at 94 GHz"],
+        "Redacted documentation D1"
+    );
+}
+
+#[test]
+fn test_cli_redact_writes_map() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("radar.sysml");
+    let map_path = temp_dir.path().join("secret-map.json");
+    fs::write(&file, "package Radar { part def Antenna; }").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .args(["--export", "xmi", "--no-stdlib", "--redact", "--redact-map"])
+        .arg(&map_path)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("Antenna"));
+    let map = fs::read_to_string(&map_path).unwrap();
+    assert!(map.contains("\"Antenna\""));
+}
@@ -0,0 +1,282 @@
+//! Integration tests for SysML v2 API repositories.
+//!
+//! Tests turning a JSON-LD export into commit changes, and pushing a model
+//! to and pulling it from a local stand-in for a repository, from the
+//! library and with `syster push` and `syster pull`.
+
+#![cfg(feature = "interchange")]
+
+mod common;
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::thread::{self, JoinHandle};
+use syster_cli::{
+    AnalysisOptions, ExportFilter, MemberOrder, Repository, commit_changes,
+    export_model_with_options, pull_model, push_model,
+};
+use tempfile::TempDir;
+
+/// A request received by the stand-in repository.
+struct Request {
+    line: String,
+    authorization: Option<String>,
+    body: String,
+}
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicle.sysml",
+    "package Vehicle {\n    part def Car;\n    part def Engine;\n}\n",
+)];
+
+/// Answer one HTTP request per response, in order, on a local port.
+fn repository(responses: Vec<String>) -> (String, JoinHandle<Vec<Request>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/api", listener.local_addr().unwrap());
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let (mut length, mut expect_continue, mut authorization) = (0, false, None);
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                let header = header.trim().to_string();
+                if header.is_empty() {
+                    break;
+                }
+                let lower = header.to_lowercase();
+                if let Some(value) = lower.strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if lower.starts_with("authorization:") {
+                    authorization = Some(header["authorization:".len()..].trim().to_string());
+                }
+                expect_continue |= lower == "expect: 100-continue";
+            }
+            if expect_continue {
+                stream.write_all(b"HTTP/1.1 100 Continue\r\n\r\n").unwrap();
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            stream
+                .write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        response.len(),
+                        response
+                    )
+                    .as_bytes(),
+                )
+                .unwrap();
+            requests.push(Request {
+                line: line.trim().to_string(),
+                authorization,
+                body: String::from_utf8(body).unwrap(),
+            });
+        }
+        requests
+    });
+    (url, handle)
+}
+
+const PROJECTS: &str = r#"[{"@id": "p-1", "name": "Other"}, {"@id": "p-2", "name": "Vehicles"}]"#;
+const BRANCHES: &str = r#"[{"@id": "b-1", "name": "main"}]"#;
+const COMMIT: &str = r#"{"@type": "Commit", "@id": "c-1"}"#;
+
+/// The elements of `dir` as a repository lists them: the JSON-LD export
+/// without the provenance record.
+fn elements(dir: &Path) -> String {
+    let (jsonld, _) = export_model_with_options(
+        dir,
+        "jsonld",
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter::default(),
+    )
+    .unwrap();
+    let payloads: Vec<serde_json::Value> = commit_changes(&jsonld)
+        .unwrap()
+        .into_iter()
+        .map(|change| change["payload"].clone())
+        .collect();
+    serde_json::to_string(&payloads).unwrap()
+}
+
+#[test]
+fn test_commit_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let (jsonld, _) = export_model_with_options(
+        temp_dir.path(),
+        "jsonld",
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter::default(),
+    )
+    .unwrap();
+
+    let changes = commit_changes(&jsonld).unwrap();
+    assert!(changes.len() >= 3, "{:?}", changes);
+    for change in &changes {
+        assert_eq!(change["@type"], "DataVersion");
+        assert_eq!(change["identity"]["@id"], change["payload"]["@id"]);
+        assert!(change["payload"].get("@context").is_none());
+    }
+    // The provenance record has no @id and is left out
+    assert!(
+        !changes
+            .iter()
+            .any(|c| c["payload"]["@type"] == "syster:Provenance")
+    );
+    assert!(changes.iter().any(|c| c["payload"]["name"] == "Car"));
+}
+
+#[test]
+fn test_push_model() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let (url, handle) = repository(vec![PROJECTS.into(), BRANCHES.into(), COMMIT.into()]);
+    let report = push_model(
+        temp_dir.path(),
+        &AnalysisOptions::default(),
+        &ExportFilter::default(),
+        &Repository::new(&url, Some("secret".to_string())),
+        "Vehicles",
+        "main",
+    )
+    .unwrap();
+    assert_eq!(report.project_id, "p-2");
+    assert_eq!(report.branch_id, "b-1");
+    assert_eq!(report.commit_id, "c-1");
+
+    let requests = handle.join().unwrap();
+    assert_eq!(requests[0].line, "GET /api/projects HTTP/1.1");
+    assert_eq!(requests[1].line, "GET /api/projects/p-2/branches HTTP/1.1");
+    assert_eq!(
+        requests[2].line,
+        "POST /api/projects/p-2/commits?branchId=b-1 HTTP/1.1"
+    );
+    assert_eq!(requests[2].authorization.as_deref(), Some("Bearer secret"));
+    let commit: serde_json::Value = serde_json::from_str(&requests[2].body).unwrap();
+    assert_eq!(commit["@type"], "Commit");
+    assert_eq!(commit["change"].as_array().unwrap().len(), report.changes);
+}
+
+#[test]
+fn test_cli_push() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let (url, handle) = repository(vec![PROJECTS.into(), BRANCHES.into(), COMMIT.into()]);
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args([
+            "push",
+            "--no-stdlib",
+            "--project",
+            "p-2",
+            "--repo",
+            &url,
+            "--src",
+        ])
+        .arg(temp_dir.path())
+        .env("SYSML_API_TOKEN", "secret")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("to p-2 (main) as commit c-1"), "{}", stdout);
+    let requests = handle.join().unwrap();
+    assert_eq!(requests[0].authorization.as_deref(), Some("Bearer secret"));
+
+    let (url, _handle) = repository(vec![PROJECTS.into()]);
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args([
+            "push",
+            "--no-stdlib",
+            "--project",
+            "Missing",
+            "--repo",
+            &url,
+            "--src",
+        ])
+        .arg(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No project 'Missing'"), "{}", stderr);
+}
+
+#[test]
+fn test_pull_model() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let (url, handle) = repository(vec![PROJECTS.into(), elements(temp_dir.path())]);
+    let pulled = pull_model(&Repository::new(&url, None), "Vehicles", "c-1").unwrap();
+    assert_eq!(pulled.project_id, "p-2");
+    assert_eq!(pulled.commit_id, "c-1");
+    assert!(!pulled.model.elements.is_empty());
+    let requests = handle.join().unwrap();
+    assert_eq!(
+        requests[1].line,
+        "GET /api/projects/p-2/commits/c-1/elements HTTP/1.1"
+    );
+    assert_eq!(requests[1].authorization, None);
+
+    let decompiled = pulled.decompile(MemberOrder::Source).unwrap();
+    assert!(
+        decompiled.sysml_text.contains("part def Car"),
+        "{}",
+        decompiled.sysml_text
+    );
+    assert_eq!(decompiled.source_path, "p-2@c-1.sysml");
+
+    let result = pulled.analyze(&AnalysisOptions::default()).unwrap();
+    assert_eq!(result.error_count, 0, "{:?}", result.diagnostics);
+    assert!(result.symbol_count >= 3);
+}
+
+#[test]
+fn test_cli_pull_decompile() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir(&src).unwrap();
+    common::write_models(&src, MODELS);
+    let out = temp_dir.path().join("pulled.sysml");
+
+    let (url, _handle) = repository(vec![PROJECTS.into(), elements(&src)]);
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args([
+            "pull",
+            "--project",
+            "Vehicles",
+            "--commit",
+            "c-1",
+            "--decompile",
+            "--repo",
+            &url,
+            "-o",
+        ])
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("from commit c-1"), "{}", stdout);
+    assert!(
+        fs::read_to_string(&out)
+            .unwrap()
+            .contains("part def Engine")
+    );
+    assert!(temp_dir.path().join("pulled.metadata.json").exists());
+}
@@ -0,0 +1,143 @@
+//! Integration tests for requirement text quality.
+//!
+//! Tests each rule on requirement text, the `L0008`–`L0011` warnings at the
+//! requirements of a model, and switching rules in the `[requirements]`
+//! table of `syster.toml`.
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, RequirementRules, run_analysis_from_sources};
+use tempfile::TempDir;
+
+const MODEL: &str = "package Vehicle {\n\
+                     \x20   requirement def MaxSpeed {\n\
+                     \x20       doc /* The vehicle shall reach 180 km/h. */\n\
+                     \x20   }\n\
+                     \x20   requirement def Comfort {\n\
+                     \x20       doc /* The cabin is quiet, etc. Seats are adjustable. */\n\
+                     \x20   }\n\
+                     \x20   requirement def Undocumented;\n\
+                     \x20   part def Car {\n\
+                     \x20       doc /* Not a requirement, etc. */\n\
+                     \x20   }\n\
+                     }\n";
+
+fn codes(problems: &[(&str, String)]) -> Vec<String> {
+    problems.iter().map(|(code, _)| code.to_string()).collect()
+}
+
+#[test]
+fn test_requirement_rules() {
+    let rules = RequirementRules::default();
+
+    assert!(
+        rules
+            .check("The pump shall deliver 2.5 l/min at 3 bar, e.g. for cooling.")
+            .is_empty()
+    );
+    assert_eq!(
+        codes(&rules.check("The pump shall deliver enough coolant, etc.")),
+        vec!["L0009", "L0011"]
+    );
+    assert_eq!(
+        codes(&rules.check("The pump runs at 50 Hz. It shall stop in 2 s!")),
+        vec!["L0010"]
+    );
+    assert_eq!(
+        codes(&rules.check("The pump marshalls 5 parts fastidiously.")),
+        vec!["L0008"]
+    );
+    let vague = rules.check("The display shall be USER-FRIENDLY within 2 s.");
+    assert_eq!(vague[0].1, "uses the vague term \"user-friendly\"");
+
+    let only_vague = RequirementRules {
+        shall: false,
+        single_sentence: false,
+        measurable: false,
+        vague: vec!["TBD".to_string()],
+        ..RequirementRules::default()
+    };
+    assert_eq!(
+        codes(&only_vague.check("Speed is tbd. Etc.")),
+        vec!["L0009"]
+    );
+}
+
+#[test]
+fn test_requirement_warnings_at_requirements() {
+    let options = AnalysisOptions::new().requirements(RequirementRules::default());
+    let result = run_analysis_from_sources(
+        &[("vehicle.sysml".to_string(), MODEL.to_string())],
+        &options,
+    );
+    let warnings: Vec<_> = result
+        .diagnostics
+        .iter()
+        .filter(|d| {
+            matches!(
+                d.code.as_deref(),
+                Some("L0008" | "L0009" | "L0010" | "L0011")
+            )
+        })
+        .collect();
+
+    let found: Vec<(u32, &str)> = warnings
+        .iter()
+        .map(|d| (d.line, d.code.as_deref().unwrap()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![(5, "L0008"), (5, "L0009"), (5, "L0010"), (5, "L0011")],
+        "{:?}",
+        result.diagnostics
+    );
+    assert!(warnings.iter().all(|d| d.file.ends_with("vehicle.sysml")));
+    assert_eq!(
+        warnings[1].message,
+        "requirement def 'Vehicle::Comfort' uses the vague term \"etc.\""
+    );
+    assert_eq!(result.error_count, 0);
+    assert_eq!(result.warning_count, 4);
+}
+
+#[test]
+fn test_cli_requirement_rules_from_project_file() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("vehicle.sysml"), MODEL).unwrap();
+
+    let run = || {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg(temp_dir.path())
+            .arg("--no-stdlib")
+            .output()
+            .expect("Should run CLI")
+    };
+    let stderr = String::from_utf8_lossy(&run().stderr).to_string();
+    assert!(!stderr.contains("L0008"), "stderr: {}", stderr);
+
+    fs::write(
+        temp_dir.path().join("syster.toml"),
+        "[requirements]\nshall = false\nsingle_sentence = false\n",
+    )
+    .unwrap();
+    let output = run();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(stderr.contains("L0009"), "stderr: {}", stderr);
+    assert!(stderr.contains("L0011"), "stderr: {}", stderr);
+    assert!(!stderr.contains("L0008"), "stderr: {}", stderr);
+    assert!(!stderr.contains("L0010"), "stderr: {}", stderr);
+}
+
+#[test]
+fn test_invalid_requirements_table() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("syster.toml");
+
+    fs::write(&path, "[lint.naming]\nacronyms = [\"ECU\"]\n").unwrap();
+    assert_eq!(RequirementRules::load(&path).unwrap(), None);
+
+    fs::write(&path, "[requirements]\nshal = false\n").unwrap();
+    let err = RequirementRules::load(&path).unwrap_err();
+    assert!(err.contains("shal"), "{}", err);
+}
@@ -0,0 +1,158 @@
+//! Integration tests for `--resolve-refs`.
+//!
+//! Tests that references to elements an imported file does not contain are
+//! classified as external or unresolved, that supplementary directories and
+//! symbol indexes resolve them, and that the CLI reports the classification.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, ImportOptions, ReferenceStatus, build_index, import_model,
+    import_model_with_options,
+};
+use tempfile::TempDir;
+
+/// `Car` specializes a library element in another document, an element of
+/// a sibling deliverable, and an element missing from its own document.
+const VEHICLES_XMI: &str = r##"<?xml version="1.0" encoding="ASCII"?>
+<sysml:Package xmi:version="2.0" xmlns:xmi="http://www.omg.org/XMI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:sysml="https://www.omg.org/spec/SysML/20250201" xmi:id="vehicles" elementId="vehicles" declaredName="Vehicles" qualifiedName="Vehicles">
+  <ownedRelatedElement xsi:type="sysml:PartDefinition" xmi:id="car" elementId="car" declaredName="Car" qualifiedName="Vehicles::Car">
+    <ownedRelationship xsi:type="sysml:Subclassification" xmi:id="s1" elementId="s1">
+      <superclassifier href="Library.sysmlx#lib-1"/>
+    </ownedRelationship>
+    <ownedRelationship xsi:type="sysml:Subclassification" xmi:id="s2" elementId="s2" superclassifier="engine-id"/>
+    <ownedRelationship xsi:type="sysml:Subclassification" xmi:id="s3" elementId="s3">
+      <superclassifier href="#missing-1"/>
+    </ownedRelationship>
+  </ownedRelatedElement>
+</sysml:Package>
+"##;
+
+const PARTS_XMI: &str = r#"<?xml version="1.0" encoding="ASCII"?>
+<sysml:Package xmi:version="2.0" xmlns:xmi="http://www.omg.org/XMI" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:sysml="https://www.omg.org/spec/SysML/20250201" xmi:id="parts" elementId="parts" declaredName="Parts" qualifiedName="Parts">
+  <ownedRelatedElement xsi:type="sysml:PartDefinition" xmi:id="engine-id" elementId="engine-id" declaredName="Engine" qualifiedName="Parts::Engine"/>
+</sysml:Package>
+"#;
+
+/// The vehicles file, a directory with the parts deliverable, and an index
+/// of the library.
+fn write_sources(dir: &Path) -> (PathBuf, PathBuf, PathBuf) {
+    let vehicles = dir.join("vehicles.xmi");
+    fs::write(&vehicles, VEHICLES_XMI).unwrap();
+
+    let deps = dir.join("deps");
+    fs::create_dir(&deps).unwrap();
+    fs::write(deps.join("parts.xmi"), PARTS_XMI).unwrap();
+
+    let library = dir.join("library");
+    fs::create_dir(&library).unwrap();
+    fs::write(library.join("library.sysml"), "package Library;\n").unwrap();
+    let index = dir.join("library.sidx");
+    build_index(&library, &AnalysisOptions::new())
+        .unwrap()
+        .write(&index)
+        .unwrap();
+
+    (vehicles, deps, index)
+}
+
+fn status(result: &syster_cli::ImportResult, id: &str) -> ReferenceStatus {
+    result
+        .references
+        .iter()
+        .find(|r| r.id == id)
+        .unwrap_or_else(|| panic!("no reference to {}", id))
+        .status
+}
+
+#[test]
+fn test_references_are_classified() {
+    let temp_dir = TempDir::new().unwrap();
+    let (vehicles, _, _) = write_sources(temp_dir.path());
+
+    let result = import_model(&vehicles, None, false).unwrap();
+    assert_eq!(result.references.len(), 3);
+    assert_eq!(status(&result, "lib-1"), ReferenceStatus::External);
+    assert_eq!(status(&result, "engine-id"), ReferenceStatus::Unresolved);
+    assert_eq!(status(&result, "missing-1"), ReferenceStatus::Unresolved);
+    assert_eq!(result.error_count, 2);
+    assert!(
+        result.messages.contains(
+            &"Warning: Reference to 'missing-1' not found (from Vehicles::Car)".to_string()
+        ),
+        "{:?}",
+        result.messages
+    );
+}
+
+#[test]
+fn test_resolve_refs_from_directory_and_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let (vehicles, deps, index) = write_sources(temp_dir.path());
+
+    let options = ImportOptions {
+        resolve_refs: vec![deps.clone(), index.clone()],
+        ..ImportOptions::default()
+    };
+    let result = import_model_with_options(&vehicles, None, &options).unwrap();
+    assert_eq!(status(&result, "engine-id"), ReferenceStatus::Resolved);
+    assert_eq!(status(&result, "lib-1"), ReferenceStatus::Resolved);
+    assert_eq!(status(&result, "missing-1"), ReferenceStatus::Unresolved);
+    assert_eq!(result.error_count, 1);
+
+    let engine = result
+        .references
+        .iter()
+        .find(|r| r.id == "engine-id")
+        .unwrap();
+    assert_eq!(engine.resolved_in, Some(deps.display().to_string()));
+    let library = result.references.iter().find(|r| r.id == "lib-1").unwrap();
+    assert_eq!(library.resolved_in, Some(index.display().to_string()));
+}
+
+#[test]
+fn test_cli_reports_references() {
+    let temp_dir = TempDir::new().unwrap();
+    let (vehicles, deps, index) = write_sources(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&vehicles)
+        .arg("--import")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "3 references to elements outside the file: 0 resolved, 1 external, 2 unresolved"
+        ),
+        "{}",
+        stderr
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&vehicles)
+        .args(["--no-stdlib", "--import-workspace", "--resolve-refs"])
+        .arg(&deps)
+        .arg("--resolve-refs")
+        .arg(&index)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "3 references to elements outside the file: 2 resolved, 0 external, 1 unresolved"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("Reference to 'missing-1' not found"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,167 @@
+//! Integration tests for code-review output formats.
+//!
+//! Tests that diagnostics are written as reviewdog rdjson and as Gerrit
+//! robot comments, and that `--format` selects them on the command line.
+
+use std::fs;
+use std::process::Command;
+use syster::hir::Severity;
+use syster_cli::{
+    AnalysisResult, DiagnosticInfo, DocCoverage, ParseRecovery, RelatedInformation, export_gerrit,
+    export_rdjson,
+};
+use tempfile::TempDir;
+
+fn sample_result() -> AnalysisResult {
+    AnalysisResult {
+        file_count: 2,
+        symbol_count: 4,
+        error_count: 1,
+        warning_count: 1,
+        diagnostics: vec![
+            DiagnosticInfo {
+                file: "models/b.sysml".to_string(),
+                line: 3,
+                col: 5,
+                end_line: 3,
+                end_col: 14,
+                message: "Undefined reference: 'Missing'".to_string(),
+                severity: Severity::Error,
+                code: Some("E0001".to_string()),
+                blame: None,
+                owners: Vec::new(),
+                related: Vec::new(),
+            },
+            DiagnosticInfo {
+                file: "./models/a.sysml".to_string(),
+                line: 1,
+                col: 1,
+                end_line: 1,
+                end_col: 12,
+                message: "duplicate definition 'A'".to_string(),
+                severity: Severity::Warning,
+                code: None,
+                blame: None,
+                owners: Vec::new(),
+                related: vec![RelatedInformation {
+                    file: "models/b.sysml".to_string(),
+                    line: 1,
+                    col: 1,
+                    message: "also defined here".to_string(),
+                }],
+            },
+        ],
+        stdlib_diagnostics: Vec::new(),
+        doc_coverage: DocCoverage::default(),
+        syntax_errors: ParseRecovery::default(),
+        packages: Vec::new(),
+        files: Vec::new(),
+        top_offenders: Vec::new(),
+    }
+}
+
+#[test]
+fn test_rdjson_export() {
+    let json: serde_json::Value =
+        serde_json::from_str(&export_rdjson(&sample_result()).unwrap()).unwrap();
+    assert_eq!(json["source"]["name"], "syster");
+
+    let error = &json["diagnostics"][0];
+    assert_eq!(error["severity"], "ERROR");
+    assert_eq!(error["code"]["value"], "E0001");
+    assert_eq!(error["location"]["path"], "models/b.sysml");
+    assert_eq!(error["location"]["range"]["start"]["line"], 3);
+    assert_eq!(error["location"]["range"]["start"]["column"], 5);
+    assert_eq!(error["location"]["range"]["end"]["column"], 14);
+
+    let warning = &json["diagnostics"][1];
+    assert_eq!(warning["severity"], "WARNING");
+    assert_eq!(warning["location"]["path"], "models/a.sysml");
+    assert!(warning.get("code").is_none());
+    assert_eq!(
+        warning["related_locations"][0]["message"],
+        "also defined here"
+    );
+}
+
+#[test]
+fn test_gerrit_export() {
+    let json: serde_json::Value =
+        serde_json::from_str(&export_gerrit(&sample_result()).unwrap()).unwrap();
+    assert_eq!(json["message"], "syster: 1 errors, 1 warnings");
+
+    let comments = json["robot_comments"].as_object().unwrap();
+    assert_eq!(comments.len(), 2);
+    let error = &comments["models/b.sysml"][0];
+    assert_eq!(error["robot_id"], "syster");
+    assert!(
+        error["robot_run_id"]
+            .as_str()
+            .is_some_and(|id| !id.is_empty())
+    );
+    assert_eq!(error["line"], 3);
+    assert_eq!(error["range"]["start_character"], 4);
+    assert_eq!(error["range"]["end_character"], 13);
+    assert_eq!(
+        error["message"],
+        "error[E0001]: Undefined reference: 'Missing'"
+    );
+    assert_eq!(error["properties"]["code"], "E0001");
+
+    let warning = &comments["models/a.sysml"][0];
+    assert_eq!(
+        warning["message"],
+        "warning: duplicate definition 'A'\nnote: models/b.sysml:1:1: also defined here"
+    );
+}
+
+#[test]
+fn test_gerrit_multi_line_comment() {
+    let mut result = sample_result();
+    result.diagnostics[0].end_line = 6;
+    result.diagnostics[0].end_col = 2;
+
+    let json: serde_json::Value = serde_json::from_str(&export_gerrit(&result).unwrap()).unwrap();
+
+    // Gerrit rejects a comment whose line isn't the end of its range
+    let error = &json["robot_comments"]["models/b.sysml"][0];
+    assert_eq!(error["line"], 6);
+    assert_eq!(error["range"]["start_line"], 3);
+    assert_eq!(error["range"]["end_line"], 6);
+    assert_eq!(error["range"]["end_character"], 1);
+}
+
+#[test]
+fn test_cli_format_flag() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("b.sysml");
+    fs::write(&file, "part def B { part x : Undefined; }\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .args(["--no-stdlib", "--format", "rdjson"])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let diagnostics = json["diagnostics"].as_array().unwrap();
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics.iter().any(|d| d["severity"] == "ERROR"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .args(["--no-stdlib", "--format", "gerrit"])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["robot_comments"].as_object().unwrap().len(), 1);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .args(["--format", "rdjson", "--json"])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used with"));
+}
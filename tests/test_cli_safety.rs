@@ -0,0 +1,111 @@
+//! Integration tests for the safety-analysis worksheets.
+//!
+//! Tests that `syster safety fmea` lists the parts annotated with failure-mode
+//! metadata together with their functions and the elements connected to
+//! them, as CSV or as an XLSX workbook.
+
+#![cfg(all(feature = "codegen", feature = "interchange"))]
+
+mod common;
+
+use std::io::{Cursor, Read};
+use std::process::Command;
+use syster_cli::{AnalysisOptions, CodegenModel, fmea_table};
+use tempfile::TempDir;
+
+/// Write a drive train where only the battery is tagged for FMEA.
+const MODELS: &[(&str, &str)] = &[(
+    "drive.sysml",
+    "package Drive {\n\
+         \x20   metadata def FailureMode;\n\
+         \x20   port def PowerPort;\n\
+         \x20   #FailureMode part def Battery {\n\
+         \x20       port supply : PowerPort;\n\
+         \x20       action storeEnergy;\n\
+         \x20       action supplyPower;\n\
+         \x20   }\n\
+         \x20   part def Motor { port feed : PowerPort; }\n\
+         \x20   part def Vehicle {\n\
+         \x20       part battery : Battery;\n\
+         \x20       part motor : Motor;\n\
+         \x20       connection powerLink connect battery.supply to motor.feed;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+#[test]
+fn test_fmea_rows_and_connected_effects() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let model = CodegenModel::load(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let table = fmea_table(&model, "FailureMode");
+
+    assert_eq!(table.rows.len(), 1, "{:?}", table.rows);
+    let row = &table.rows[0];
+    assert_eq!(row.element, "Drive::Battery");
+    assert_eq!(row.functions, vec!["storeEnergy", "supplyPower"]);
+    assert_eq!(
+        row.connected_effects.len(),
+        1,
+        "{:?}",
+        row.connected_effects
+    );
+    assert!(
+        row.connected_effects[0].ends_with("(via Drive::Vehicle::powerLink)"),
+        "{:?}",
+        row.connected_effects
+    );
+    assert!(
+        row.connected_effects[0].contains("feed"),
+        "{:?}",
+        row.connected_effects
+    );
+}
+
+#[test]
+fn test_fmea_xlsx_workbook() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let model = CodegenModel::load(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let bytes = fmea_table(&model, "Drive::FailureMode").to_xlsx().unwrap();
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+    let mut sheet = String::new();
+    archive
+        .by_name("xl/worksheets/sheet1.xml")
+        .unwrap()
+        .read_to_string(&mut sheet)
+        .unwrap();
+    assert!(sheet.contains("<t xml:space=\"preserve\">Failure Mode</t>"));
+    assert!(
+        sheet.contains(
+            "<c r=\"A2\" t=\"inlineStr\"><is><t xml:space=\"preserve\">Drive::Battery</t>"
+        )
+    );
+    assert!(archive.by_name("xl/workbook.xml").is_ok());
+}
+
+#[test]
+fn test_cli_safety_fmea_csv() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["safety", "fmea", "--no-stdlib", "--src"])
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+
+    let rows: Vec<_> = stdout.lines().collect();
+    assert_eq!(rows.len(), 2, "{}", stdout);
+    assert!(rows[0].starts_with("Element,Kind,Function,Failure Mode,"));
+    assert!(
+        rows[1].starts_with("Drive::Battery,Part def,storeEnergy; supplyPower,,,,"),
+        "{}",
+        rows[1]
+    );
+}
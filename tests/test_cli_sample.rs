@@ -0,0 +1,142 @@
+//! Integration tests for random workspace samples.
+//!
+//! Tests that a sample is reproducible from its seed, closed over what its
+//! elements reference, and written as the original text by `syster sample`.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, analyze, sample};
+use tempfile::TempDir;
+
+/// `Vehicles` reaches `Parts` through an import and `Materials` through
+/// `Parts`; `Unrelated` stands alone.
+fn write_workspace(dir: &Path) {
+    fs::write(
+        dir.join("vehicles.sysml"),
+        "package Vehicles {\n\
+         \x20   private import Parts::*;\n\
+         \x20   part def Car {\n\
+         \x20       part engine : Engine;\n\
+         \x20       part wheels : Wheel[4];\n\
+         \x20   }\n\
+         \x20   part def Truck;\n\
+         }\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("parts.sysml"),
+        "package Parts {\n\
+         \x20   part def Engine { attribute block : Materials::Steel; }\n\
+         \x20   part def Wheel;\n\
+         \x20   part def Spare;\n\
+         }\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("materials.sysml"),
+        "package Materials { attribute def Steel; }\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("unrelated.sysml"),
+        "package Unrelated { part def Boat; part def Sail; }\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_sample_is_reproducible() {
+    let temp_dir = TempDir::new().unwrap();
+    write_workspace(temp_dir.path());
+
+    let first = sample(temp_dir.path(), 3, 42, &AnalysisOptions::new()).unwrap();
+    let second = sample(temp_dir.path(), 3, 42, &AnalysisOptions::new()).unwrap();
+
+    assert!(first.elements.len() >= 3, "{:?}", first.elements);
+    assert_eq!(first.drawn, second.drawn);
+    assert_eq!(first.elements, second.elements);
+    let texts = |s: &syster_cli::Sample| {
+        s.files
+            .iter()
+            .map(|f| (f.path.clone(), f.text.clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(texts(&first), texts(&second));
+}
+
+#[test]
+fn test_sample_closes_over_dependencies() {
+    let temp_dir = TempDir::new().unwrap();
+    write_workspace(temp_dir.path());
+
+    for seed in 0..8 {
+        let sample = sample(temp_dir.path(), 1, seed, &AnalysisOptions::new()).unwrap();
+        if sample.elements.contains("Parts::Engine::block") {
+            assert!(sample.elements.contains("Materials::Steel"));
+        }
+        if sample.elements.contains("Vehicles::Car::engine") {
+            for required in ["Vehicles::Car", "Parts", "Parts::Engine"] {
+                assert!(sample.elements.contains(required), "{:?}", sample.elements);
+            }
+        }
+
+        let out = TempDir::new().unwrap();
+        for file in &sample.files {
+            fs::write(out.path().join(&file.path), &file.text).unwrap();
+        }
+        let result = analyze(out.path(), &AnalysisOptions::new().load_stdlib(false)).unwrap();
+        assert_eq!(
+            result.error_count, 0,
+            "seed {}: {:?}",
+            seed, result.diagnostics
+        );
+    }
+}
+
+#[test]
+fn test_sample_keeps_original_text() {
+    let temp_dir = TempDir::new().unwrap();
+    write_workspace(temp_dir.path());
+
+    // More elements than there are: everything, unchanged
+    let sample = sample(temp_dir.path(), 1000, 7, &AnalysisOptions::new()).unwrap();
+    assert_eq!(sample.files.len(), 4);
+    for file in &sample.files {
+        let original = fs::read_to_string(temp_dir.path().join(&file.path)).unwrap();
+        assert_eq!(file.text, original, "{}", file.path.display());
+    }
+}
+
+#[test]
+fn test_cli_sample() {
+    let temp_dir = TempDir::new().unwrap();
+    write_workspace(temp_dir.path());
+    let out = temp_dir.path().join("out");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["sample", "--elements", "2", "--seed", "42", "--no-stdlib"])
+        .arg("--src")
+        .arg(temp_dir.path())
+        .arg("-o")
+        .arg(&out)
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("seed 42"), "stdout: {}", stdout);
+    assert!(fs::read_dir(&out).unwrap().count() > 0);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["sample", "--no-stdlib"])
+        .arg("--src")
+        .arg(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("-o DIR"));
+}
@@ -0,0 +1,208 @@
+//! Integration tests for test stubs of verification cases.
+//!
+//! Tests pytest and GoogleTest stubs with one test per verified requirement,
+//! file names from qualified names, clashing file names, and
+//! `syster generate-tests` keeping existing files.
+
+#![cfg(feature = "codegen")]
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, TestFramework, test_stubs, verification_plan};
+use tempfile::TempDir;
+
+/// Write a model with a verification definition typing a usage, a case
+/// verifying a requirement with quotes in its text, and an empty case.
+const MODELS: &[(&str, &str)] = &[(
+    "tests.sysml",
+    "package Requirements {\n\
+         \x20   requirement <R1> massRequirement {\n\
+         \x20       doc /* The vehicle mass shall not exceed 2000 kg. */\n\
+         \x20   }\n\
+         \x20   requirement <R2> speedRequirement {\n\
+         \x20       doc /* The vehicle shall reach \"120 km/h\". */\n\
+         \x20   }\n\
+         }\n\
+         package Tests {\n\
+         \x20   import Requirements::*;\n\
+         \x20   part def Vehicle;\n\
+         \x20   verification def MassTest {\n\
+         \x20       doc /* Weigh the vehicle */\n\
+         \x20       subject vehicle : Vehicle;\n\
+         \x20       objective {\n\
+         \x20           verify massRequirement;\n\
+         \x20       }\n\
+         \x20   }\n\
+         \x20   verification massTest : MassTest;\n\
+         \x20   verification speedTest {\n\
+         \x20       objective {\n\
+         \x20           verify Requirements::speedRequirement;\n\
+         \x20       }\n\
+         \x20   }\n\
+         \x20   verification def BrakeTest;\n\
+         }\n",
+)];
+
+#[test]
+fn test_pytest_stubs() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let plan = verification_plan(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    let stubs = test_stubs(&plan, TestFramework::Pytest).unwrap();
+
+    // The definition typing `massTest` is left to the usage
+    let files: Vec<&str> = stubs.iter().map(|s| s.file_name.as_str()).collect();
+    assert_eq!(
+        files,
+        vec![
+            "test_tests_brake_test.py",
+            "test_tests_mass_test.py",
+            "test_tests_speed_test.py",
+        ]
+    );
+    assert_eq!(stubs[1].case, "Tests::massTest");
+    assert_eq!(
+        stubs[1].content,
+        "\"\"\"Verification case Tests::massTest.\n\
+         \n\
+         Subject: vehicle : Tests::Vehicle\n\
+         \n\
+         Declared at tests.sysml:19; generated by `syster generate-tests`.\n\
+         \"\"\"\n\
+         \n\
+         import pytest\n\
+         \n\
+         \n\
+         def test_mass_requirement():\n\
+         \x20   \"\"\"The vehicle mass shall not exceed 2000 kg.\n\
+         \n\
+         \x20   Verifies R1 (Requirements::massRequirement).\n\
+         \x20   \"\"\"\n\
+         \x20   pytest.skip(\"not implemented\")\n"
+    );
+    assert!(
+        stubs[0].content.ends_with(
+            "def test_brake_test():\n\
+             \x20   \"\"\"Verification case Tests::BrakeTest.\"\"\"\n\
+             \x20   pytest.skip(\"not implemented\")\n"
+        ),
+        "{}",
+        stubs[0].content
+    );
+    assert!(
+        stubs[2]
+            .content
+            .contains("\"\"\"The vehicle shall reach \\\"120 km/h\\\".\n"),
+        "{}",
+        stubs[2].content
+    );
+}
+
+#[test]
+fn test_gtest_stubs() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let plan = verification_plan(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    let stubs = test_stubs(&plan, TestFramework::Gtest).unwrap();
+
+    assert_eq!(stubs[1].file_name, "tests_mass_test_test.cc");
+    assert_eq!(
+        stubs[1].content,
+        "// Verification case Tests::massTest.\n\
+         //\n\
+         // Subject: vehicle : Tests::Vehicle\n\
+         //\n\
+         // Declared at tests.sysml:19; generated by `syster generate-tests`.\n\
+         \n\
+         #include <gtest/gtest.h>\n\
+         \n\
+         // The vehicle mass shall not exceed 2000 kg.\n\
+         // Verifies R1 (Requirements::massRequirement).\n\
+         TEST(TestsMassTest, MassRequirement) {\n\
+         \x20   GTEST_SKIP() << \"not implemented\";\n\
+         }\n"
+    );
+    assert!(
+        stubs[0]
+            .content
+            .contains("TEST(TestsBrakeTest, BrakeTest) {\n")
+    );
+}
+
+#[test]
+fn test_clashing_file_names() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("a.sysml"),
+        "package T {\n    verification aB;\n    verification a_b;\n}\n",
+    )
+    .unwrap();
+    let plan = verification_plan(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    let err = test_stubs(&plan, TestFramework::Pytest).unwrap_err();
+
+    assert_eq!(
+        err,
+        "Test stubs of T::aB and T::a_b would both be written to test_t_a_b.py"
+    );
+}
+
+#[test]
+fn test_cli_generate_tests_keeps_existing_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let model = temp_dir.path().join("model");
+    fs::create_dir(&model).unwrap();
+    common::write_models(&model, MODELS);
+    let out = temp_dir.path().join("tests");
+
+    let run = |force: bool| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_syster"));
+        command.args(["generate-tests", "--no-stdlib", "--framework", "pytest"]);
+        command.arg("--src").arg(&model).arg("--out").arg(&out);
+        if force {
+            command.arg("--force");
+        }
+        command.output().expect("Should run CLI")
+    };
+
+    let output = run(false);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("✓ 3 test stubs written, 0 kept"),
+        "{}",
+        stderr
+    );
+    let stub = out.join("test_tests_mass_test.py");
+    assert!(
+        fs::read_to_string(&stub)
+            .unwrap()
+            .contains("def test_mass_requirement():")
+    );
+
+    // Implemented tests survive regeneration
+    fs::write(&stub, "def test_mass_requirement():\n    assert True\n").unwrap();
+    let output = run(false);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("✓ 0 test stubs written, 3 kept"),
+        "{}",
+        stderr
+    );
+    assert!(stdout.contains("use --force to overwrite"), "{}", stdout);
+    assert!(fs::read_to_string(&stub).unwrap().contains("assert True"));
+
+    let output = run(true);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("✓ 3 test stubs written, 0 kept"),
+        "{}",
+        stderr
+    );
+    assert!(fs::read_to_string(&stub).unwrap().contains("pytest.skip"));
+}
@@ -0,0 +1,212 @@
+//! Integration tests for SCIP index emission.
+//!
+//! Tests that `syster index build --format scip` writes one document per
+//! file with definition and reference occurrences, hover documentation and
+//! relationships, decoding the Protocol Buffers output field by field.
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, build_scip_index};
+use tempfile::TempDir;
+
+/// A decoded Protocol Buffers field value.
+#[derive(Debug, Clone)]
+enum Value {
+    Varint(u64),
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Value::Bytes(bytes) => bytes,
+            Value::Varint(_) => panic!("expected a length-delimited field"),
+        }
+    }
+
+    fn string(&self) -> String {
+        String::from_utf8(self.bytes().to_vec()).unwrap()
+    }
+}
+
+fn varint(bytes: &[u8], pos: &mut usize) -> u64 {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = bytes[*pos];
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte < 0x80 {
+            return value;
+        }
+        shift += 7;
+    }
+}
+
+/// Decode the fields of a message (varint and length-delimited only).
+fn decode(bytes: &[u8]) -> Vec<(u64, Value)> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let tag = varint(bytes, &mut pos);
+        let value = match tag & 7 {
+            0 => Value::Varint(varint(bytes, &mut pos)),
+            2 => {
+                let len = varint(bytes, &mut pos) as usize;
+                pos += len;
+                Value::Bytes(bytes[pos - len..pos].to_vec())
+            }
+            other => panic!("unexpected wire type {}", other),
+        };
+        fields.push((tag >> 3, value));
+    }
+    fields
+}
+
+fn field(message: &[(u64, Value)], number: u64) -> Vec<Value> {
+    message
+        .iter()
+        .filter(|(n, _)| *n == number)
+        .map(|(_, v)| v.clone())
+        .collect()
+}
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicles.sysml",
+    "package Vehicles {\n\
+         \x20   part def Engine;\n\
+         \x20   abstract part def Vehicle;\n\
+         \x20   part def Car :> Vehicle {\n\
+         \x20       doc /* A road vehicle. */\n\
+         \x20       part engine : Engine;\n\
+         \x20   }\n\
+         \x20   part def 'Fuel Tank';\n\
+         }\n",
+)];
+
+/// The single document of an index, decoded.
+fn document(index: &[u8]) -> Vec<(u64, Value)> {
+    let documents = field(&decode(index), 2);
+    assert_eq!(documents.len(), 1);
+    decode(documents[0].bytes())
+}
+
+#[test]
+fn test_scip_occurrences() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let index = build_scip_index(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let document = document(&index);
+
+    assert_eq!(field(&document, 1)[0].string(), "vehicles.sysml");
+    assert_eq!(field(&document, 4)[0].string(), "SysML");
+
+    // (symbol, roles, range)
+    let occurrences: Vec<(String, u64, Vec<u64>)> = field(&document, 2)
+        .iter()
+        .map(|o| {
+            let o = decode(o.bytes());
+            let range = field(&o, 1)[0].bytes().to_vec();
+            let mut pos = 0;
+            let mut values = Vec::new();
+            while pos < range.len() {
+                values.push(varint(&range, &mut pos));
+            }
+            let roles = match field(&o, 3).first() {
+                Some(Value::Varint(roles)) => *roles,
+                _ => 0,
+            };
+            (field(&o, 2)[0].string(), roles, values)
+        })
+        .collect();
+
+    assert!(
+        occurrences.contains(&(
+            "scip-syster . . . Vehicles/Car#".to_string(),
+            1,
+            vec![3, 13, 16]
+        )),
+        "{:?}",
+        occurrences
+    );
+    assert!(occurrences.contains(&(
+        "scip-syster . . . Vehicles/Car#engine.".to_string(),
+        1,
+        vec![5, 13, 19]
+    )));
+    // References to Vehicle and Engine
+    for target in ["Vehicles/Vehicle#", "Vehicles/Engine#"] {
+        let symbol = format!("scip-syster . . . {}", target);
+        assert!(
+            occurrences
+                .iter()
+                .any(|(s, roles, _)| *s == symbol && *roles == 0),
+            "{:?}",
+            occurrences
+        );
+    }
+    assert!(
+        occurrences
+            .iter()
+            .any(|(s, _, _)| s == "scip-syster . . . Vehicles/`Fuel Tank`#")
+    );
+}
+
+#[test]
+fn test_scip_hover_documentation() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let index = build_scip_index(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let document = document(&index);
+
+    let car = field(&document, 3)
+        .iter()
+        .map(|info| decode(info.bytes()))
+        .find(|info| field(info, 1)[0].string() == "scip-syster . . . Vehicles/Car#")
+        .expect("Car should have symbol information");
+    let docs: Vec<String> = field(&car, 3).iter().map(Value::string).collect();
+    assert_eq!(docs[0], "```sysml\npart def Vehicles::Car\n```");
+    assert!(docs[1].contains("A road vehicle."), "{:?}", docs);
+    assert_eq!(field(&car, 6)[0].string(), "Car");
+
+    let relationship = decode(field(&car, 4)[0].bytes());
+    assert_eq!(
+        field(&relationship, 1)[0].string(),
+        "scip-syster . . . Vehicles/Vehicle#"
+    );
+    assert!(matches!(field(&relationship, 3)[0], Value::Varint(1)));
+}
+
+#[test]
+fn test_cli_index_build_scip() {
+    let temp_dir = TempDir::new().unwrap();
+    let models = temp_dir.path().join("models");
+    fs::create_dir(&models).unwrap();
+    common::write_models(&models, MODELS);
+    let out = temp_dir.path().join("index.scip");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["index", "build", "--no-stdlib", "--format", "scip", "--src"])
+        .arg(&models)
+        .arg("-o")
+        .arg(&out)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("✓ Indexed"));
+
+    let index = decode(&fs::read(&out).unwrap());
+    let metadata = decode(field(&index, 1)[0].bytes());
+    let tool = decode(field(&metadata, 2)[0].bytes());
+    assert_eq!(field(&tool, 1)[0].string(), "syster");
+    let root = field(&metadata, 3)[0].string();
+    assert!(root.starts_with("file://"), "{}", root);
+    assert!(root.ends_with("models"), "{}", root);
+    assert_eq!(field(&index, 2).len(), 1);
+}
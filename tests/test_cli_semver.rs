@@ -0,0 +1,149 @@
+//! Integration tests for the semantic-versioning check.
+//!
+//! Tests that differences between two KPAR releases are classified as
+//! major, minor or patch changes, that the next version is suggested from
+//! the old manifest version, and that `syster semver-check` fails when the
+//! declared version bump is too small.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster_cli::{AnalysisOptions, ChangeLevel, PackManifest, pack_kpar, semver_check};
+use tempfile::TempDir;
+
+/// Pack `source` as release `version` of package `Vehicles`.
+fn release(dir: &Path, version: &str, source: &str) -> PathBuf {
+    let src = dir.join(format!("src-{}", version));
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("vehicles.sysml"), source).unwrap();
+    let manifest = PackManifest {
+        name: "Vehicles".to_string(),
+        version: Some(version.to_string()),
+        description: None,
+        dependencies: Vec::new(),
+    };
+    let bytes = pack_kpar(&src, &manifest, &AnalysisOptions::new()).unwrap();
+    let path = dir.join(format!("vehicles-{}.kpar", version));
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+const V1: &str = "package Vehicles {\n    part def Engine;\n    part def Wheel;\n    \
+                  part def Car {\n        part engine : Engine;\n    }\n}\n";
+
+#[test]
+fn test_breaking_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let old = release(temp_dir.path(), "1.2.0", V1);
+    let new = release(
+        temp_dir.path(),
+        "1.3.0",
+        "package Vehicles {\n    part def Engine;\n    part def Motor;\n    \
+         part def Car {\n        part engine : Motor;\n    }\n}\n",
+    );
+
+    let report = semver_check(&old, &new, None, None, false).unwrap();
+    assert_eq!(report.old_version.as_deref(), Some("1.2.0"));
+    assert_eq!(report.level, Some(ChangeLevel::Major));
+    assert_eq!(report.suggested_version.as_deref(), Some("2.0.0"));
+    assert_eq!(report.sufficient, Some(false));
+
+    let descriptions: Vec<(ChangeLevel, &str)> = report
+        .changes
+        .iter()
+        .map(|c| (c.level, c.description.as_str()))
+        .collect();
+    assert!(
+        descriptions.contains(&(ChangeLevel::Major, "removed public element Vehicles::Wheel")),
+        "{:?}",
+        descriptions
+    );
+    assert!(
+        descriptions.contains(&(ChangeLevel::Minor, "added public element Vehicles::Motor")),
+        "{:?}",
+        descriptions
+    );
+    assert!(
+        descriptions
+            .iter()
+            .any(|(level, d)| *level == ChangeLevel::Major
+                && d.starts_with("changed type of Vehicles::Car::engine")),
+        "{:?}",
+        descriptions
+    );
+    // Most severe first
+    assert_eq!(report.changes[0].level, ChangeLevel::Major);
+}
+
+#[test]
+fn test_additive_changes_before_1_0() {
+    let temp_dir = TempDir::new().unwrap();
+    let old = release(temp_dir.path(), "0.4.1", V1);
+    let new = release(
+        temp_dir.path(),
+        "0.4.2",
+        &V1.replace("part def Wheel;", "part def Wheel;\n    part def Trailer;"),
+    );
+
+    let report = semver_check(&old, &new, None, None, false).unwrap();
+    assert_eq!(report.level, Some(ChangeLevel::Minor));
+    assert_eq!(report.suggested_version.as_deref(), Some("0.4.2"));
+    assert_eq!(report.sufficient, Some(true));
+
+    let report = semver_check(&old, &old, None, Some("0.4.1"), false).unwrap();
+    assert_eq!(report.level, None);
+    assert!(report.changes.is_empty());
+    assert_eq!(report.sufficient, Some(true));
+
+    let err = semver_check(&old, &new, Some("latest"), None, false).unwrap_err();
+    assert!(err.contains("Unsupported version 'latest'"), "{}", err);
+}
+
+#[test]
+fn test_cli_fails_on_insufficient_bump() {
+    let temp_dir = TempDir::new().unwrap();
+    let old = release(temp_dir.path(), "1.2.0", V1);
+    let new = release(
+        temp_dir.path(),
+        "1.2.1",
+        &V1.replace("    part def Wheel;\n", ""),
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("semver-check")
+        .arg(&old)
+        .arg(&new)
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success());
+    assert!(
+        stdout.contains("major: removed public element Vehicles::Wheel"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(
+            "✗ Declared version 1.2.1 is insufficient: major changes need at least 2.0.0"
+        ),
+        "{}",
+        stdout
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("semver-check")
+        .arg(&old)
+        .arg(&new)
+        .args(["--new-version", "2.0.0"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("✓ Declared version 2.0.0 is sufficient"),
+        "{}",
+        stdout
+    );
+}
@@ -0,0 +1,172 @@
+//! Integration tests for sequence diagrams of behaviors.
+//!
+//! Tests action occurrences on the lifelines of the parts performing them,
+//! messages and flows between parts, rendering PlantUML, and
+//! `--export plantuml-sequence`.
+
+mod common;
+
+use std::fs;
+use syster_cli::{AnalysisOptions, SequenceStep, render_plantuml_sequence, sequence_diagrams};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicle.sysml",
+    "package Vehicle {\n\
+         \x20   item def Start;\n\
+         \x20   item def Ignition;\n\
+         \x20   part def Driver;\n\
+         \x20   part def Engine;\n\
+         \x20   action drive {\n\
+         \x20       action turnKey;\n\
+         \x20       message of Ignition from driver to engine;\n\
+         \x20       action start;\n\
+         \x20       action accelerate {\n\
+         \x20           action throttle;\n\
+         \x20       }\n\
+         \x20   }\n\
+         \x20   part car {\n\
+         \x20       part driver : Driver {\n\
+         \x20           perform drive.turnKey;\n\
+         \x20       }\n\
+         \x20       part engine : Engine {\n\
+         \x20           perform drive.start;\n\
+         \x20       }\n\
+         \x20       flow of Start from driver to engine;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+fn action(action: &str, lifeline: &str, line: u32) -> SequenceStep {
+    SequenceStep::Action {
+        action: action.to_string(),
+        lifeline: lifeline.to_string(),
+        line,
+    }
+}
+
+#[test]
+fn test_sequence_of_behavior() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let diagrams = sequence_diagrams(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    let behaviors: Vec<&str> = diagrams.iter().map(|d| d.behavior.as_str()).collect();
+    assert_eq!(behaviors, vec!["Vehicle::drive", "Vehicle::car"]);
+    let drive = &diagrams[0];
+    assert_eq!(drive.line, 6);
+    assert_eq!(drive.participants, vec!["driver", "engine", "drive"]);
+    assert_eq!(
+        drive.steps,
+        vec![
+            action("turnKey", "driver", 7),
+            SequenceStep::Message {
+                from: "driver".to_string(),
+                to: "engine".to_string(),
+                label: "Ignition".to_string(),
+                line: 8,
+            },
+            action("start", "engine", 9),
+            action("accelerate", "drive", 10),
+            action("throttle", "drive", 11),
+        ]
+    );
+
+    // Parts only contribute the messages and flows they declare
+    let car = &diagrams[1];
+    assert_eq!(car.participants, vec!["driver", "engine"]);
+    assert!(matches!(
+        &car.steps[..],
+        [SequenceStep::Message { label, line: 21, .. }] if label == "Start"
+    ));
+}
+
+#[test]
+fn test_render_plantuml_sequence() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let diagrams = sequence_diagrams(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+    let rendered = render_plantuml_sequence(&diagrams[..1]);
+
+    assert_eq!(
+        rendered,
+        "@startuml Vehicle__drive\n\
+         title Vehicle::drive\n\
+         participant driver\n\
+         participant engine\n\
+         participant drive\n\
+         hnote over driver : turnKey\n\
+         driver -> engine : Ignition\n\
+         hnote over engine : start\n\
+         hnote over drive : accelerate\n\
+         hnote over drive : throttle\n\
+         @enduml\n"
+    );
+    assert_eq!(rendered.matches("@startuml").count(), 1);
+    assert_eq!(
+        render_plantuml_sequence(&diagrams)
+            .matches("@enduml")
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn test_model_without_behaviors() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("a.sysml"),
+        "package P {\n    part def A;\n    part a : A;\n    action idle;\n}\n",
+    )
+    .unwrap();
+
+    let diagrams = sequence_diagrams(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    assert!(diagrams.is_empty());
+    assert_eq!(render_plantuml_sequence(&diagrams), "");
+}
+
+#[cfg(feature = "interchange")]
+#[test]
+fn test_cli_export_plantuml_sequence() {
+    use std::process::Command;
+
+    let temp_dir = TempDir::new().unwrap();
+    let model = temp_dir.path().join("model");
+    fs::create_dir(&model).unwrap();
+    common::write_models(&model, MODELS);
+    let out = temp_dir.path().join("vehicle.puml");
+
+    let run = |package: &str| {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg(&model)
+            .args(["--no-stdlib", "--export", "plantuml-sequence"])
+            .args(["--package", package, "-o"])
+            .arg(&out)
+            .output()
+            .expect("Should run CLI")
+    };
+
+    let output = run("Vehicle");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("✓ Rendered 2 sequence diagrams"),
+        "{}",
+        stderr
+    );
+    let rendered = fs::read_to_string(&out).unwrap();
+    assert!(rendered.starts_with("@startuml Vehicle__drive\n"));
+    assert!(rendered.contains("@startuml Vehicle__car\n"));
+    assert!(rendered.contains("driver -> engine : Start\n"));
+
+    let output = run("Vehicle::car");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("✓ Rendered 1 sequence diagrams"),
+        "{}",
+        stderr
+    );
+}
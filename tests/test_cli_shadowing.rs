@@ -0,0 +1,94 @@
+//! Integration tests for ambiguous-import and shadowing warnings.
+//!
+//! Tests that references to a name provided by several wildcard imports,
+//! and declarations hiding an imported name, are reported with the
+//! candidate imports, and that an explicit member import settles the
+//! ambiguity.
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, AnalysisResult, run_analysis_from_sources};
+use tempfile::TempDir;
+
+/// `Car` imports `Engine` from both `A` and `B`, and declares its own `Wheel`
+/// although `A` provides one.
+fn model(extra_import: &str) -> String {
+    format!(
+        "package A {{ part def Engine; part def Wheel; }}\n\
+         package B {{ part def Engine; }}\n\
+         package Car {{\n    import A::*;\n    import B::*;{}\n    \
+         part def Wheel;\n    part engine : Engine;\n}}\n",
+        extra_import
+    )
+}
+
+fn analyze_model(extra_import: &str) -> AnalysisResult {
+    run_analysis_from_sources(
+        &[("car.sysml".to_string(), model(extra_import))],
+        &AnalysisOptions::new(),
+    )
+}
+
+#[test]
+fn test_ambiguous_and_shadowed_imports() {
+    let result = analyze_model("");
+
+    let shadowed = result
+        .diagnostics
+        .iter()
+        .find(|d| d.message.contains("shadows"))
+        .expect("Should warn about Wheel");
+    assert_eq!(shadowed.line, 6);
+    assert_eq!(
+        shadowed.message,
+        "'Wheel' shadows imported A::Wheel (import A::*)"
+    );
+    assert_eq!(shadowed.related.len(), 1);
+    assert_eq!(shadowed.related[0].line, 4);
+
+    let ambiguous = result
+        .diagnostics
+        .iter()
+        .find(|d| d.message.contains("ambiguous name"))
+        .expect("Should warn about Engine");
+    assert_eq!(ambiguous.line, 7);
+    assert_eq!(
+        ambiguous.message,
+        "ambiguous name 'Engine': could be A::Engine (import A::*) or B::Engine (import B::*)"
+    );
+    let lines: Vec<u32> = ambiguous.related.iter().map(|r| r.line).collect();
+    assert_eq!(lines, vec![4, 5]);
+}
+
+#[test]
+fn test_explicit_import_resolves_ambiguity() {
+    let result = analyze_model("\n    import B::Engine;");
+    assert!(
+        result
+            .diagnostics
+            .iter()
+            .all(|d| !d.message.contains("ambiguous name")),
+        "{:?}",
+        result.diagnostics
+    );
+}
+
+#[test]
+fn test_cli_lists_import_locations() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("car.sysml");
+    fs::write(&file, model("")).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .arg("--no-stdlib")
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning: "), "{}", stderr);
+    assert!(
+        stderr.contains("car.sysml:5:12: 'B::Engine' imported here"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,168 @@
+//! Integration tests for export checksums and signatures.
+//!
+//! Tests that `--export ... --checksum` / `--sign` seal KPAR and XMI output,
+//! and that `syster verify-signature` accepts sealed files, rejects tampered
+//! ones and content appended after the seal, and checks signatures against a
+//! supplied public key.
+
+#![cfg(feature = "signing")]
+
+use ed25519_dalek::SigningKey;
+use ed25519_dalek::pkcs8::{EncodePrivateKey, EncodePublicKey, LineEnding};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{SignatureStatus, export_model, seal_xmi, verify_integrity};
+use tempfile::TempDir;
+
+/// Write a small source tree and return its directory.
+fn write_sources(dir: &Path) -> std::path::PathBuf {
+    let src = dir.join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(
+        src.join("vehicle.sysml"),
+        "package Vehicles { part def Engine; part def Car { part engine : Engine; } }",
+    )
+    .unwrap();
+    src
+}
+
+#[test]
+fn test_cli_checksum_kpar_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = write_sources(temp_dir.path());
+    let kpar_path = temp_dir.path().join("model.kpar");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&src)
+        .args(["--export", "kpar", "--checksum", "--no-stdlib", "-o"])
+        .arg(&kpar_path)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("verify-signature")
+        .arg(&kpar_path)
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.contains("signature: none"));
+}
+
+#[test]
+fn test_verify_detects_tampered_xmi() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = write_sources(temp_dir.path());
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+
+    let xmi = export_model(&src, "xmi", false, false, None, false).unwrap();
+    let sealed = seal_xmi(&xmi, Some(&key));
+    let xmi_path = temp_dir.path().join("model.xmi");
+    fs::write(&xmi_path, &sealed).unwrap();
+
+    let report = verify_integrity(&xmi_path, None).unwrap();
+    assert!(report.is_valid(), "{:?}", report);
+    assert!(matches!(
+        report.signature,
+        SignatureStatus::Valid { trusted: false, .. }
+    ));
+
+    // Rename an element after sealing
+    let tampered = String::from_utf8(sealed)
+        .unwrap()
+        .replacen("Engine", "Motor", 1);
+    fs::write(&xmi_path, tampered).unwrap();
+
+    let report = verify_integrity(&xmi_path, None).unwrap();
+    assert!(!report.is_valid());
+    assert_eq!(report.problems, vec!["checksum mismatch".to_string()]);
+}
+
+#[test]
+fn test_verify_rejects_content_after_seal() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = write_sources(temp_dir.path());
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+
+    let xmi = export_model(&src, "xmi", false, false, None, false).unwrap();
+    let mut sealed = seal_xmi(&xmi, Some(&key));
+    sealed.extend_from_slice(b"<!-- appended --><xmi:Extension/>\n");
+    let xmi_path = temp_dir.path().join("model.xmi");
+    fs::write(&xmi_path, &sealed).unwrap();
+
+    let report = verify_integrity(&xmi_path, None).unwrap();
+    assert!(!report.is_valid());
+    assert_eq!(
+        report.problems,
+        vec!["content after the integrity comment".to_string()]
+    );
+}
+
+#[test]
+fn test_cli_sign_checks_trusted_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = write_sources(temp_dir.path());
+
+    let key = SigningKey::from_bytes(&[7u8; 32]);
+    let key_path = temp_dir.path().join("signing.pem");
+    fs::write(
+        &key_path,
+        key.to_pkcs8_pem(LineEnding::LF).unwrap().as_bytes(),
+    )
+    .unwrap();
+    let public_path = temp_dir.path().join("signing.pub.pem");
+    fs::write(
+        &public_path,
+        key.verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap(),
+    )
+    .unwrap();
+    let other = SigningKey::from_bytes(&[9u8; 32]);
+    let other_path = temp_dir.path().join("other.pub.pem");
+    fs::write(
+        &other_path,
+        other
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap(),
+    )
+    .unwrap();
+
+    let kpar_path = temp_dir.path().join("model.kpar");
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&src)
+        .args(["--export", "kpar", "--no-stdlib", "--sign"])
+        .arg(&key_path)
+        .arg("-o")
+        .arg(&kpar_path)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let verify = |public: &Path| {
+        Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg("verify-signature")
+            .arg(&kpar_path)
+            .arg("--key")
+            .arg(public)
+            .output()
+            .expect("Should run CLI")
+    };
+
+    assert!(verify(&public_path).status.success());
+
+    let output = verify(&other_path);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("signed with a different key"));
+}
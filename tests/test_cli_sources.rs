@@ -0,0 +1,73 @@
+//! Integration tests for analysis of in-memory sources.
+//!
+//! Tests that sources resolve against each other, that diagnostics carry
+//! the given paths, and that analysis options apply.
+
+use syster::hir::Severity;
+use syster_cli::{AnalysisOptions, Profile, run_analysis_from_sources};
+
+fn sources() -> Vec<(String, String)> {
+    vec![
+        (
+            "models/engine.sysml".to_string(),
+            "package Power {\n    part def Engine;\n}\n".to_string(),
+        ),
+        (
+            "models/car.sysml".to_string(),
+            "package Vehicle {\n\
+             \x20   private import Power::*;\n\
+             \x20   part def Car {\n\
+             \x20       part engine : Engine;\n\
+             \x20       part chassis : Chassis;\n\
+             \x20   }\n\
+             }\n"
+            .to_string(),
+        ),
+    ]
+}
+
+#[test]
+fn test_sources_analysis() {
+    let result = run_analysis_from_sources(&sources(), &AnalysisOptions::default());
+
+    assert_eq!(result.file_count, 2);
+    assert!(result.symbol_count >= 5, "{}", result.symbol_count);
+    // `Engine` resolves across sources; only `Chassis` is missing
+    assert_eq!(result.error_count, 1, "{:?}", result.diagnostics);
+    let diag = &result.diagnostics[0];
+    assert_eq!(diag.file, "models/car.sysml");
+    assert_eq!(diag.line, 5);
+    assert!(diag.message.contains("Chassis"), "{}", diag.message);
+}
+
+#[test]
+fn test_sources_options() {
+    let options = AnalysisOptions {
+        profile: Profile::Permissive,
+        ..Default::default()
+    };
+    let result = run_analysis_from_sources(&sources(), &options);
+
+    assert_eq!(result.error_count, 0);
+    assert_eq!(result.warning_count, 1);
+    assert_eq!(result.diagnostics[0].severity, Severity::Warning);
+}
+
+#[test]
+fn test_sources_syntax_errors() {
+    let mut files = sources();
+    files.push((
+        "models/broken.sysml".to_string(),
+        "package Broken {\n    part def Wheel\n".to_string(),
+    ));
+
+    let result = run_analysis_from_sources(&files, &AnalysisOptions::default());
+    assert_eq!(result.syntax_errors.files, 1);
+
+    let strict = AnalysisOptions {
+        strict_parse: true,
+        ..Default::default()
+    };
+    let result = run_analysis_from_sources(&files, &strict);
+    assert_eq!(result.file_count, 2);
+}
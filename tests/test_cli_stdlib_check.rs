@@ -0,0 +1,166 @@
+//! Integration tests for the standard library self-check.
+//!
+//! Tests that `syster stdlib check` analyzes a library by itself, reports
+//! its genuine issues, accepts the known issues of `syster.toml` and warns
+//! about known issues that no longer occur.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{KnownIssue, KnownIssues, check_stdlib};
+use tempfile::TempDir;
+
+/// Write a library with a misspelled type and a missing one.
+fn write_library(dir: &Path, typo: bool) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+        dir.join("quantities.sysml"),
+        "standard library package Quantities {\n\
+         \x20   attribute def Mass;\n\
+         \x20   attribute def Length;\n\
+         }\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("vehicles.sysml"),
+        format!(
+            "standard library package Vehicles {{\n\
+             \x20   private import Quantities::*;\n\
+             \x20   part def Vehicle {{\n\
+             \x20       attribute mass : Mass;\n\
+             \x20       attribute length : {};\n\
+             \x20       attribute width : Width;\n\
+             \x20   }}\n\
+             }}\n",
+            if typo { "Lenght" } else { "Length" }
+        ),
+    )
+    .unwrap();
+}
+
+/// Write a project file accepting the missing type, and an issue that
+/// does not occur.
+fn write_known_issues(path: &Path) {
+    fs::write(
+        path,
+        "[[stdlib.known_issues]]\n\
+         code = \"E0001\"\n\
+         file = \"vehicles.sysml\"\n\
+         message = \"'Width'\"\n\
+         reason = \"Width is added in the next release\"\n\
+         \n\
+         [[stdlib.known_issues]]\n\
+         code = \"E0001\"\n\
+         file = \"missing.sysml\"\n",
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_check_stdlib() {
+    let temp_dir = TempDir::new().unwrap();
+    let library = temp_dir.path().join("lib");
+    write_library(&library, true);
+    let project = temp_dir.path().join("syster.toml");
+    write_known_issues(&project);
+    let known = KnownIssues::load(&project).unwrap();
+
+    let check = check_stdlib(Some(library.as_path()), &known, false).unwrap();
+
+    assert_eq!(check.files, 2);
+    assert_eq!(check.issues.len(), 1);
+    assert_eq!(check.issues[0].message, "undefined reference: 'Lenght'");
+    assert_eq!(check.issues[0].line, 5);
+    assert!(check.issues[0].file.ends_with("vehicles.sysml"));
+    assert_eq!(check.error_count(), 1);
+    assert!(check.false_positives.is_empty());
+    assert_eq!(check.known.len(), 1);
+    assert_eq!(check.known[0].message, "undefined reference: 'Width'");
+    assert_eq!(
+        check.stale,
+        vec![KnownIssue {
+            code: Some("E0001".to_string()),
+            file: Some("missing.sysml".to_string()),
+            message: None,
+            reason: String::new(),
+        }]
+    );
+}
+
+#[test]
+fn test_known_issues_reject_unknown_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("syster.toml");
+    fs::write(&project, "[[stdlib.known_issues]]\nline = 5\n").unwrap();
+
+    let err = KnownIssues::load(&project).unwrap_err();
+    assert!(err.starts_with("Failed to parse "), "{}", err);
+
+    // A project file without a [stdlib] table has no known issues
+    fs::write(&project, "[lint.naming]\nacronyms = [\"ECU\"]\n").unwrap();
+    assert!(KnownIssues::load(&project).unwrap().issues.is_empty());
+}
+
+#[test]
+fn test_cli_stdlib_check() {
+    let temp_dir = TempDir::new().unwrap();
+    let library = temp_dir.path().join("lib");
+    write_library(&library, true);
+    let project = temp_dir.path().join("syster.toml");
+    write_known_issues(&project);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["stdlib", "check"])
+        .arg(&library)
+        .arg("--known-issues")
+        .arg(&project)
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("error[E0001]: ")
+            && stderr.contains(":5:19: undefined reference: 'Lenght'"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("'Width'"), "{}", stderr);
+    assert!(
+        stderr.contains(
+            "warning: known issue matches no diagnostic: code = \"E0001\", file = \"missing.sysml\""
+        ),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("✗ ")
+            && stderr.contains(": 2 files, 1 issues (0 resolver false positives, 1 known)"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_cli_stdlib_check_json_discovers_known_issues() {
+    let temp_dir = TempDir::new().unwrap();
+    let library = temp_dir.path().join("lib");
+    write_library(&library, false);
+    write_known_issues(&temp_dir.path().join("syster.toml"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["stdlib", "check", "lib", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("✓ lib: 2 files, 0 issues (0 resolver false positives, 1 known)"),
+        "{}",
+        stderr
+    );
+    let check: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(check["issues"].as_array().unwrap().len(), 0);
+    assert_eq!(check["known"][0]["code"], "E0001");
+    assert_eq!(check["stale"][0]["file"], "missing.sysml");
+}
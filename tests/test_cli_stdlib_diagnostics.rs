@@ -0,0 +1,171 @@
+//! Integration tests for diagnostics inside the standard library.
+//!
+//! Tests that diagnostics in standard library files are set aside instead of
+//! reported and counted, that `--include-stdlib-diagnostics` reports them,
+//! and that the model's own diagnostics are unaffected, also when a
+//! [`LoadedStdlib`] is reused.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, LoadedStdlib, analyze};
+use tempfile::TempDir;
+
+/// Write a library referring to a type it does not declare, and a model
+/// using the library, with a dangling reference of its own if `broken`.
+fn write_files(dir: &Path, broken: bool) {
+    let library = dir.join("lib");
+    fs::create_dir(&library).unwrap();
+    fs::write(
+        library.join("quantities.sysml"),
+        "standard library package Quantities {\n\
+         \x20   attribute def Mass;\n\
+         \x20   attribute def Length {\n\
+         \x20       attribute width : Width;\n\
+         \x20   }\n\
+         }\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("model.sysml"),
+        format!(
+            "package Vehicle {{\n\
+             \x20   private import Quantities::*;\n\
+             \x20   part def Car {{\n\
+             \x20       attribute mass : Mass;\n\
+             \x20       attribute speed : {};\n\
+             \x20   }}\n\
+             }}\n",
+            if broken { "Speed" } else { "Length" }
+        ),
+    )
+    .unwrap();
+}
+
+fn options(dir: &Path) -> AnalysisOptions<'static> {
+    AnalysisOptions::new()
+        .load_stdlib(true)
+        .stdlib_path(dir.join("lib"))
+}
+
+#[test]
+fn test_stdlib_diagnostics_set_aside() {
+    let temp_dir = TempDir::new().unwrap();
+    write_files(temp_dir.path(), true);
+
+    let result = analyze(
+        &temp_dir.path().join("model.sysml"),
+        &options(temp_dir.path()),
+    )
+    .unwrap();
+
+    assert_eq!(result.error_count, 1, "{:?}", result.diagnostics);
+    assert_eq!(
+        result.diagnostics[0].message,
+        "undefined reference: 'Speed'"
+    );
+    assert!(result.diagnostics[0].file.ends_with("model.sysml"));
+    assert_eq!(result.stdlib_diagnostics.len(), 1);
+    assert_eq!(
+        result.stdlib_diagnostics[0].message,
+        "undefined reference: 'Width'"
+    );
+    assert!(
+        result.stdlib_diagnostics[0]
+            .file
+            .ends_with("quantities.sysml")
+    );
+}
+
+#[test]
+fn test_loaded_stdlib_reused() {
+    let temp_dir = TempDir::new().unwrap();
+    write_files(temp_dir.path(), true);
+    let stdlib = LoadedStdlib::load(&options(temp_dir.path())).unwrap();
+    let model = temp_dir.path().join("model.sysml");
+
+    for _ in 0..2 {
+        let result = stdlib.analyze(&model, &AnalysisOptions::new()).unwrap();
+        assert_eq!(result.error_count, 1, "{:?}", result.diagnostics);
+        assert!(result.diagnostics[0].file.ends_with("model.sysml"));
+        assert_eq!(result.stdlib_diagnostics.len(), 1);
+    }
+}
+
+#[test]
+fn test_include_stdlib_diagnostics() {
+    let temp_dir = TempDir::new().unwrap();
+    write_files(temp_dir.path(), false);
+
+    let options = options(temp_dir.path()).include_stdlib_diagnostics(true);
+    let result = analyze(&temp_dir.path().join("model.sysml"), &options).unwrap();
+
+    assert_eq!(result.error_count, 1, "{:?}", result.diagnostics);
+    assert_eq!(
+        result.diagnostics[0].message,
+        "undefined reference: 'Width'"
+    );
+    assert!(result.stdlib_diagnostics.is_empty());
+}
+
+#[test]
+fn test_cli_stdlib_diagnostics_not_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    write_files(temp_dir.path(), false);
+
+    let run = |json: bool| {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_syster"));
+        command.arg("model.sysml").args(["--stdlib-path", "lib"]);
+        if json {
+            command.arg("--json");
+        }
+        command
+            .current_dir(temp_dir.path())
+            .output()
+            .expect("Should run CLI")
+    };
+
+    let output = run(false);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(!stderr.contains("'Width'"), "{}", stderr);
+    assert!(
+        stdout.contains(
+            "1 diagnostics inside the standard library not reported (--include-stdlib-diagnostics)"
+        ),
+        "{}",
+        stdout
+    );
+
+    let output = run(true);
+    assert!(output.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["error_count"], 0);
+    assert_eq!(
+        result["stdlib_diagnostics"][0]["message"],
+        "undefined reference: 'Width'"
+    );
+}
+
+#[test]
+fn test_cli_include_stdlib_diagnostics() {
+    let temp_dir = TempDir::new().unwrap();
+    write_files(temp_dir.path(), false);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("model.sysml")
+        .args(["--stdlib-path", "lib", "--include-stdlib-diagnostics"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success());
+    assert!(
+        stderr.contains("error[E0001]: ") && stderr.contains("undefined reference: 'Width'"),
+        "{}",
+        stderr
+    );
+    assert!(!stdout.contains("not reported"), "{}", stdout);
+}
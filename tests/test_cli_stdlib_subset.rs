@@ -0,0 +1,134 @@
+//! Integration tests for loading a subset of the standard library.
+//!
+//! Tests that `--stdlib-subset auto` keeps the library files declaring the
+//! packages a model imports, refers to or implicitly specializes, and those
+//! they depend on in turn, and that the model still resolves against them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster_cli::{AnalysisOptions, StdlibSubset, analyze, needed_stdlib_files};
+use tempfile::TempDir;
+
+/// Write a small standard library of six packages, one per file.
+fn write_library(dir: &Path) {
+    fs::create_dir(dir).unwrap();
+    let files = [
+        (
+            "actions.sysml",
+            "standard library package Actions {\n    doc /* Unlike Parts::Part */\n    action def Action;\n}\n",
+        ),
+        (
+            "base.sysml",
+            "standard library package Base {\n    // See Actions::Action\n    attribute def DataValue;\n}\n",
+        ),
+        (
+            "isq.sysml",
+            "standard library package ISQ {\n    public import ISQBase::*;\n    attribute def MassValue;\n}\n",
+        ),
+        (
+            "isq_base.sysml",
+            "standard library package ISQBase {\n    attribute def LengthValue;\n}\n",
+        ),
+        (
+            "items.sysml",
+            "standard library package Items {\n    item def Item;\n}\n",
+        ),
+        (
+            "parts.sysml",
+            "standard library package Parts {\n    private import Items::Item;\n    part def Part :> Item;\n}\n",
+        ),
+    ];
+    for (name, text) in files {
+        fs::write(dir.join(name), text).unwrap();
+    }
+}
+
+const MODEL: &str = "package Vehicle {\n\
+                     \x20   private import ISQBase::*;\n\
+                     \x20   part def Car {\n\
+                     \x20       attribute length : LengthValue;\n\
+                     \x20   }\n\
+                     \x20   // action def Drive;\n\
+                     }\n";
+
+fn names(paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+        .collect()
+}
+
+#[test]
+fn test_needed_files_follow_imports_and_keywords() {
+    let temp_dir = TempDir::new().unwrap();
+    let library = temp_dir.path().join("lib");
+    write_library(&library);
+
+    let needed = needed_stdlib_files(&library, &[MODEL]).unwrap();
+
+    // Parts for `part def`, Items through Parts, Base always; not what
+    // comments mention
+    assert_eq!(
+        names(&needed),
+        vec!["base.sysml", "isq_base.sysml", "items.sysml", "parts.sysml"]
+    );
+}
+
+#[test]
+fn test_needed_files_of_quoted_qualified_names() {
+    let temp_dir = TempDir::new().unwrap();
+    let library = temp_dir.path().join("lib");
+    write_library(&library);
+
+    let model = "package P {\n    attribute m : 'ISQ'::MassValue;\n    action a;\n}\n";
+    let needed = needed_stdlib_files(&library, &[model]).unwrap();
+
+    assert_eq!(
+        names(&needed),
+        vec!["actions.sysml", "base.sysml", "isq.sysml", "isq_base.sysml"]
+    );
+}
+
+#[test]
+fn test_analyze_with_stdlib_subset() {
+    let temp_dir = TempDir::new().unwrap();
+    let library = temp_dir.path().join("lib");
+    write_library(&library);
+    let model = temp_dir.path().join("model.sysml");
+    fs::write(&model, MODEL).unwrap();
+
+    let options = AnalysisOptions::new()
+        .load_stdlib(true)
+        .stdlib_path(&library)
+        .stdlib_subset(StdlibSubset::Auto);
+    let result = analyze(&model, &options).unwrap();
+
+    assert_eq!(result.file_count, 1);
+    assert_eq!(result.error_count, 0, "{:?}", result.diagnostics);
+}
+
+#[test]
+fn test_cli_stdlib_subset_auto() {
+    let temp_dir = TempDir::new().unwrap();
+    let library = temp_dir.path().join("lib");
+    write_library(&library);
+    let model = temp_dir.path().join("model.sysml");
+    fs::write(&model, MODEL).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["--verbose", "--stdlib-subset", "auto", "--stdlib-path"])
+        .arg(&library)
+        .arg(&model)
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("Loading 4 of 6 standard library files"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("parts.sysml"), "{}", stderr);
+    assert!(!stderr.contains("actions.sysml"), "{}", stderr);
+}
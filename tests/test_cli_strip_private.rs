@@ -0,0 +1,155 @@
+//! Integration tests for private-member stripping in exports.
+//!
+//! Tests that `--export ... --strip-private` removes `private` members and
+//! their contents, whatever comments and strings say, and fails when a
+//! remaining element references one.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::process::Command;
+use syster::interchange::{ModelFormat, Xmi};
+use syster_cli::{AnalysisOptions, ExportFilter, export_model_with_options};
+use tempfile::TempDir;
+
+/// Filter that only strips private members.
+fn strip_private() -> ExportFilter {
+    ExportFilter {
+        strip_private: true,
+        ..ExportFilter::default()
+    }
+}
+
+#[test]
+fn test_strip_private_removes_members() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("vehicles.sysml");
+    fs::write(
+        &file,
+        "package Vehicles {\n\
+         \x20   private part def Prototype { attribute secretSauce; }\n\
+         \x20   part def Car;\n\
+         }\n",
+    )
+    .unwrap();
+
+    let (xmi, _) = export_model_with_options(
+        &file,
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &strip_private(),
+    )
+    .unwrap();
+    let model = Xmi.read(&xmi).expect("Should read XMI");
+    let names: Vec<_> = model
+        .elements
+        .values()
+        .filter_map(|e| e.name.as_deref())
+        .collect();
+
+    assert!(names.contains(&"Car"));
+    assert!(!names.contains(&"Prototype"));
+    assert!(!names.contains(&"secretSauce"));
+}
+
+#[test]
+fn test_strip_private_reads_tokens() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("vehicles.sysml");
+    fs::write(
+        &file,
+        "package Vehicles {\n\
+         \x20   /* ; */ private part def Secret;\n\
+         \x20   // private\n\
+         \x20   part def Car { attribute label = \"private\"; }\n\
+         }\n",
+    )
+    .unwrap();
+
+    let (xmi, _) = export_model_with_options(
+        &file,
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &strip_private(),
+    )
+    .unwrap();
+    let model = Xmi.read(&xmi).expect("Should read XMI");
+    let names: Vec<_> = model
+        .elements
+        .values()
+        .filter_map(|e| e.name.as_deref())
+        .collect();
+
+    // A separator inside a comment doesn't start a new declaration, and a
+    // `private` in a comment or string doesn't make one private
+    assert!(!names.contains(&"Secret"));
+    assert!(names.contains(&"Car"));
+    assert!(names.contains(&"label"));
+}
+
+#[test]
+fn test_strip_private_reports_leaks() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("vehicles.sysml");
+    fs::write(
+        &file,
+        "package Vehicles {\n\
+         \x20   private part def Secret;\n\
+         \x20   part def Car { part core : Secret; }\n\
+         }\n",
+    )
+    .unwrap();
+
+    let error = export_model_with_options(
+        &file,
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &strip_private(),
+    )
+    .unwrap_err();
+    assert!(
+        error.contains("Vehicles::Car::core references private element Vehicles::Secret"),
+        "{}",
+        error
+    );
+
+    // Without stripping, the same model exports fine
+    assert!(
+        export_model_with_options(
+            &file,
+            "xmi",
+            &AnalysisOptions::default(),
+            false,
+            &ExportFilter::default()
+        )
+        .is_ok()
+    );
+}
+
+#[test]
+fn test_cli_strip_private_fails_on_leak() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = temp_dir.path().join("vehicles.sysml");
+    fs::write(
+        &file,
+        "package Vehicles { private part def Secret; part def Car :> Secret; }",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .args(["--export", "xmi", "--strip-private", "--no-stdlib"])
+        .output()
+        .expect("Should run CLI");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Vehicles::Car references private element Vehicles::Secret"),
+        "stderr: {}",
+        stderr
+    );
+}
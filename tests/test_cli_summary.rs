@@ -0,0 +1,104 @@
+//! Integration tests for summary breakdowns.
+//!
+//! Tests the per-package and per-file counts of an analysis, the rendered
+//! tables, and `--summary-by`.
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, AnalysisResult, SummaryBy, render_summary, run_analysis_from_sources,
+};
+use tempfile::TempDir;
+
+/// `Vehicle` spans two files and has two unresolved references; `Power`
+/// is clean.
+const MODELS: &[(&str, &str)] = &[
+    (
+        "car.sysml",
+        "package Vehicle {\n\
+         \x20   part def Car;\n\
+         \x20   part wheel : Wheel;\n\
+         }\n\
+         package Power {\n\
+         \x20   part def Engine;\n\
+         }\n",
+    ),
+    (
+        "truck.sysml",
+        "package Vehicle {\n    part trailer : Trailer;\n}\n",
+    ),
+];
+
+fn analyze_models() -> AnalysisResult {
+    let sources: Vec<(String, String)> = MODELS
+        .iter()
+        .map(|(name, text)| (name.to_string(), text.to_string()))
+        .collect();
+    run_analysis_from_sources(&sources, &AnalysisOptions::new())
+}
+
+#[test]
+fn test_package_and_file_breakdowns() {
+    let result = analyze_models();
+
+    let packages: Vec<(&str, usize, usize)> = result
+        .packages
+        .iter()
+        .map(|p| (p.name.as_str(), p.symbols, p.errors))
+        .collect();
+    assert_eq!(packages.len(), 2, "{:?}", result.packages);
+    assert_eq!(packages[0].0, "Power");
+    assert_eq!((packages[0].1, packages[0].2), (2, 0));
+    assert_eq!(packages[1].0, "Vehicle");
+    assert_eq!(packages[1].2, 2);
+
+    assert_eq!(result.files.len(), 2);
+    assert!(result.files.iter().all(|f| f.errors == 1));
+    let symbols: usize = result.packages.iter().map(|p| p.symbols).sum();
+    assert_eq!(symbols, result.symbol_count);
+}
+
+#[test]
+fn test_render_summary() {
+    let result = analyze_models();
+
+    let by_package = render_summary(&result, SummaryBy::Package);
+    let lines: Vec<&str> = by_package.lines().collect();
+    assert_eq!(lines[0], "By package:");
+    // Worst first
+    assert!(
+        lines[1].trim_start().starts_with("Vehicle"),
+        "{}",
+        by_package
+    );
+    assert!(lines[1].ends_with("2 errors, 0 warnings"), "{}", by_package);
+    assert!(lines[2].trim_start().starts_with("Power"), "{}", by_package);
+
+    let by_severity = render_summary(&result, SummaryBy::Severity);
+    assert!(by_severity.contains("  error    2\n"), "{}", by_severity);
+    assert!(by_severity.contains("  warning  0\n"), "{}", by_severity);
+}
+
+#[test]
+fn test_cli_summary_by() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--summary-by", "file"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("By file:"), "{}", stdout);
+    assert!(stdout.contains("truck.sysml"), "{}", stdout);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--json"])
+        .output()
+        .expect("Should run CLI");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["packages"].as_array().unwrap().len(), 2);
+}
@@ -0,0 +1,122 @@
+//! Integration tests for SysML v2 standard JSON.
+//!
+//! Tests that `--export json` writes plain API element records with
+//! ownership spelled out through memberships, and that such files are read
+//! back on import and decompilation while JSON-LD `.json` files still are.
+
+#![cfg(feature = "interchange")]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, ExportFilter, decompile_model, export_model_with_options, import_model,
+};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicles.sysml",
+    "package Vehicles {\n\
+         \x20   part def Engine;\n\
+         \x20   part def Vehicle {\n\
+         \x20       part engine : Engine;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+fn export(dir: &Path, format: &str) -> Vec<u8> {
+    let (bytes, _) = export_model_with_options(
+        dir,
+        format,
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter::default(),
+    )
+    .unwrap();
+    bytes
+}
+
+fn record<'a>(records: &'a [serde_json::Value], key: &str, value: &str) -> &'a serde_json::Value {
+    records
+        .iter()
+        .find(|record| record[key] == value)
+        .unwrap_or_else(|| panic!("no record with {} {}", key, value))
+}
+
+#[test]
+fn test_export_standard_json() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let value: serde_json::Value =
+        serde_json::from_slice(&export(temp_dir.path(), "json")).unwrap();
+    let records = value.as_array().unwrap();
+    assert!(records.iter().all(|r| r["elementId"] == r["@id"]));
+    assert!(records.iter().all(|r| r.get("@context").is_none()));
+    assert!(records.iter().all(|r| r.get("ownedMember").is_none()));
+
+    let vehicle = record(records, "declaredName", "Vehicle");
+    let engine = record(records, "declaredName", "engine");
+    assert_eq!(engine["@type"], "PartUsage");
+    assert_eq!(engine["owner"]["@id"], vehicle["@id"]);
+
+    // engine is owned through a FeatureMembership listed by Vehicle
+    let membership_id = engine["owningRelationship"]["@id"].as_str().unwrap();
+    let membership = record(records, "@id", membership_id);
+    assert_eq!(membership["@type"], "FeatureMembership");
+    assert_eq!(membership["owningRelatedElement"]["@id"], vehicle["@id"]);
+    assert_eq!(membership["ownedRelatedElement"][0]["@id"], engine["@id"]);
+    assert!(
+        vehicle["ownedRelationship"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|r| r["@id"] == membership_id)
+    );
+}
+
+#[test]
+fn test_import_standard_json() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let json = temp_dir.path().join("vehicles.json");
+    fs::write(&json, export(temp_dir.path(), "json")).unwrap();
+
+    let jsonld = temp_dir.path().join("vehicles-ld.json");
+    fs::write(&jsonld, export(temp_dir.path(), "jsonld")).unwrap();
+    let standard = import_model(&json, None, false).unwrap();
+    let linked = import_model(&jsonld, None, false).unwrap();
+    assert_eq!(standard.element_count, linked.element_count);
+    assert_eq!(standard.relationship_count, linked.relationship_count);
+    assert_eq!(standard.error_count, 0);
+
+    let result = decompile_model(&json, None, false).unwrap();
+    assert!(result.sysml_text.contains("package Vehicles"));
+    assert!(result.sysml_text.contains("part def Vehicle"));
+    assert!(result.sysml_text.contains("part engine : Engine"));
+}
+
+#[test]
+fn test_cli_export_json() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let out = temp_dir.path().join("model.json");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--export", "json", "-o"])
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    assert!(fs::read_to_string(&out).unwrap().contains("\"elementId\""));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("--import")
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+}
@@ -0,0 +1,100 @@
+//! Integration tests for ctags and etags output.
+//!
+//! Tests that `syster index build --format ctags|etags` tags every named
+//! declaration under its simple and qualified name, with the kind letters
+//! and byte offsets editors expect.
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, TagsFormat, build_tags};
+use tempfile::TempDir;
+
+const MODEL: &str = "package Vehicles {\n    part def Engine;\n    part def Car {\n        part engine : Engine;\n    }\n}\n";
+
+fn write_model(dir: &Path) -> String {
+    let file = common::write_models(dir, &[("vehicles.sysml", MODEL)]);
+    file.to_string_lossy().to_string()
+}
+
+#[test]
+fn test_ctags() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = write_model(temp_dir.path());
+    let tags = build_tags(temp_dir.path(), TagsFormat::Ctags, &AnalysisOptions::new()).unwrap();
+    let lines: Vec<&str> = tags.lines().collect();
+
+    assert_eq!(lines[0], "!_TAG_FILE_FORMAT\t2\t/extended format/");
+    assert!(lines.contains(&"!_TAG_KIND_DESCRIPTION!SysML\tP,partdef\t/part def/"));
+    assert!(lines.contains(&format!("Car\t{}\t3;\"\tP\tline:3", file).as_str()));
+    assert!(lines.contains(&format!("Vehicles::Car\t{}\t3;\"\tP\tline:3", file).as_str()));
+    assert!(lines.contains(&format!("engine\t{}\t4;\"\tp\tline:4", file).as_str()));
+    assert!(lines.contains(&format!("Vehicles\t{}\t1;\"\tK\tline:1", file).as_str()));
+
+    // Tags are sorted by name after the pseudo-tags
+    let names: Vec<&str> = lines
+        .iter()
+        .filter(|l| !l.starts_with("!_"))
+        .map(|l| l.split('\t').next().unwrap())
+        .collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+}
+
+#[test]
+fn test_etags() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = write_model(temp_dir.path());
+    let tags = build_tags(temp_dir.path(), TagsFormat::Etags, &AnalysisOptions::new()).unwrap();
+
+    let header = format!("\x0c\n{},", file);
+    assert!(tags.starts_with(&header), "{:?}", tags);
+    let rest = &tags[header.len()..];
+    let (size, section) = rest.split_once('\n').unwrap();
+    assert_eq!(size.parse::<usize>().unwrap(), section.len());
+
+    // Byte offset of the start of line 3
+    let offset = MODEL.find("    part def Car").unwrap();
+    assert!(
+        section.contains(&format!("    part def Car\x7fCar\x01{},{}\n", 3, offset)),
+        "{:?}",
+        section
+    );
+    assert!(section.contains(&format!(
+        "    part def Car\x7fVehicles::Car\x01{},{}\n",
+        3, offset
+    )));
+}
+
+#[test]
+fn test_cli_index_build_ctags() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path());
+    let out = temp_dir.path().join("tags");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args([
+            "index",
+            "build",
+            "--no-stdlib",
+            "--format",
+            "ctags",
+            "--src",
+        ])
+        .arg(temp_dir.path())
+        .arg("-o")
+        .arg(&out)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let tags = fs::read_to_string(&out).unwrap();
+    assert!(tags.contains("\nEngine\t"), "{}", tags);
+    assert!(tags.contains("!_TAG_PROGRAM_NAME\tsyster\t//"));
+}
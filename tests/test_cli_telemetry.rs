@@ -0,0 +1,130 @@
+//! Integration tests for OpenTelemetry span export.
+//!
+//! Tests the collector URL spans are sent to, and `--otlp-endpoint` against
+//! a local stand-in collector and an unreachable one.
+
+#![cfg(feature = "otel")]
+
+mod common;
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::process::Command;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use syster_cli::traces_endpoint;
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "car.sysml",
+    "package Vehicle {\n    part def Car;\n    part wheel : Wheel;\n}\n",
+)];
+
+/// Answer HTTP requests on a local port with 200; the receiver yields their
+/// request lines.
+fn collector() -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            loop {
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+                    break;
+                }
+                let mut length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    let header = header.trim().to_lowercase();
+                    if header.is_empty() {
+                        break;
+                    }
+                    if let Some(value) = header.strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                    .unwrap();
+                if sender.send(request_line.trim().to_string()).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    (url, receiver)
+}
+
+#[test]
+fn test_traces_endpoint() {
+    assert_eq!(
+        traces_endpoint("http://collector:4318"),
+        "http://collector:4318/v1/traces"
+    );
+    assert_eq!(
+        traces_endpoint("http://collector:4318/"),
+        "http://collector:4318/v1/traces"
+    );
+    assert_eq!(
+        traces_endpoint("http://collector:4318/v1/traces"),
+        "http://collector:4318/v1/traces"
+    );
+}
+
+#[test]
+fn test_cli_otlp_endpoint() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let (url, requests) = collector();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--otlp-endpoint", &url])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+
+    let requests: Vec<String> = requests.try_iter().collect();
+    assert!(!requests.is_empty(), "no spans exported");
+    assert!(
+        requests
+            .iter()
+            .all(|line| line == "POST /v1/traces HTTP/1.1"),
+        "{:?}",
+        requests
+    );
+}
+
+#[test]
+fn test_cli_otlp_endpoint_unreachable() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("car.sysml"),
+        "package Vehicle {\n    part def Car;\n}\n",
+    )
+    .unwrap();
+    // Bind and drop a listener to get a port nothing listens on
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .arg("--no-stdlib")
+        .arg("--otlp-endpoint")
+        .arg(format!("http://127.0.0.1:{}", port))
+        .output()
+        .expect("Should run CLI");
+
+    // Failing to export spans doesn't fail the analysis
+    assert!(output.status.success());
+}
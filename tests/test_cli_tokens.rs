@@ -0,0 +1,124 @@
+//! Integration tests for semantic token export.
+//!
+//! Tests the lexical and declaration classes, resolved and unresolved
+//! references, and the `syster tokens` command.
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{AnalysisOptions, SemanticToken, TokenKind, semantic_tokens};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicles.sysml",
+    "package Vehicles {\n\
+         \x20   // Engines first\n\
+         \x20   part def Engine;\n\
+         \x20   part def Car {\n\
+         \x20       attribute mass = 1500;\n\
+         \x20       part engine : Engine;\n\
+         \x20       part wheel : Wheel;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+fn token_at(tokens: &[SemanticToken], line: u32, col: u32) -> &SemanticToken {
+    tokens
+        .iter()
+        .find(|t| (t.line, t.col) == (line, col))
+        .unwrap_or_else(|| panic!("no token at {}:{} in {:?}", line, col, tokens))
+}
+
+#[test]
+fn test_tokens_lexical_and_declarations() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+    let tokens = semantic_tokens(None, &file, &AnalysisOptions::new()).unwrap();
+
+    let package = token_at(&tokens, 1, 1);
+    assert_eq!(package.kind, TokenKind::Keyword);
+    assert_eq!((package.end_line, package.end_col), (1, 8));
+
+    let vehicles = token_at(&tokens, 1, 9);
+    assert_eq!(vehicles.kind, TokenKind::Namespace);
+    assert_eq!(vehicles.target.as_deref(), Some("Vehicles"));
+
+    assert_eq!(token_at(&tokens, 2, 5).kind, TokenKind::Comment);
+    assert_eq!(token_at(&tokens, 3, 14).kind, TokenKind::Type);
+    assert_eq!(token_at(&tokens, 5, 9).kind, TokenKind::Keyword);
+
+    let mass = token_at(&tokens, 5, 19);
+    assert_eq!(mass.kind, TokenKind::Attribute);
+    assert_eq!(mass.target.as_deref(), Some("Vehicles::Car::mass"));
+    assert_eq!(token_at(&tokens, 5, 26).kind, TokenKind::Number);
+
+    assert_eq!(token_at(&tokens, 6, 14).kind, TokenKind::Property);
+    assert!(
+        tokens
+            .windows(2)
+            .all(|w| (w[0].line, w[0].col) <= (w[1].line, w[1].col))
+    );
+}
+
+#[test]
+fn test_tokens_resolved_and_unresolved_references() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+    let tokens = semantic_tokens(Some(temp_dir.path()), &file, &AnalysisOptions::new()).unwrap();
+
+    let engine = token_at(&tokens, 6, 23);
+    assert_eq!(engine.kind, TokenKind::Reference);
+    assert_eq!(engine.target.as_deref(), Some("Vehicles::Engine"));
+    assert_eq!((engine.end_line, engine.end_col), (6, 29));
+
+    let wheel = token_at(&tokens, 7, 22);
+    assert_eq!(wheel.kind, TokenKind::UnresolvedReference);
+    assert_eq!(wheel.target.as_deref(), Some("Wheel"));
+}
+
+#[test]
+fn test_cli_tokens() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("tokens")
+        .arg(&file)
+        .args(["--format", "json", "--no-stdlib"])
+        .output()
+        .expect("Should run CLI");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let tokens = json.as_array().expect("tokens should be an array");
+    assert!(
+        tokens.iter().any(|t| t["kind"] == "unresolved-reference"
+            && t["target"] == "Wheel"
+            && t["line"] == 7)
+    );
+    assert!(
+        tokens
+            .iter()
+            .any(|t| t["kind"] == "keyword" && t.get("target").is_none())
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("tokens")
+        .arg(&file)
+        .arg("--no-stdlib")
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("6:23-6:29 reference Vehicles::Engine"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout
+            .lines()
+            .next()
+            .unwrap()
+            .starts_with("1:1-1:8 keyword")
+    );
+}
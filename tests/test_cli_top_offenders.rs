@@ -0,0 +1,86 @@
+//! Integration tests for the top-offenders report.
+//!
+//! Tests that the files with the most errors and warnings are picked worst
+//! first and clean files left out, and `--top-offenders` in text and JSON.
+
+mod common;
+
+use std::process::Command;
+use syster_cli::{AnalysisOptions, run_analysis_from_sources, top_offenders};
+use tempfile::TempDir;
+
+/// `bad.sysml` has three unresolved references, `poor.sysml` one, and
+/// `clean.sysml` none.
+const MODELS: &[(&str, &str)] = &[
+    (
+        "bad.sysml",
+        "package A {\n    part a : X;\n    part b : Y;\n    part c : Z;\n}\n",
+    ),
+    ("poor.sysml", "package B {\n    part a : X;\n}\n"),
+    ("clean.sysml", "package C {\n    part def D;\n}\n"),
+];
+
+fn sources() -> Vec<(String, String)> {
+    MODELS
+        .iter()
+        .map(|(name, text)| (name.to_string(), text.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_top_offenders() {
+    let result = run_analysis_from_sources(&sources(), &AnalysisOptions::new());
+
+    let top = top_offenders(&result, 5);
+    assert_eq!(top.len(), 2, "{:?}", top);
+    assert!(top[0].name.ends_with("bad.sysml"));
+    assert_eq!(top[0].errors, 3);
+    assert!(top[1].name.ends_with("poor.sysml"));
+
+    let top = top_offenders(&result, 1);
+    assert_eq!(top.len(), 1);
+    assert!(top[0].name.ends_with("bad.sysml"));
+}
+
+#[test]
+fn test_cli_top_offenders_text() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--top-offenders", "1"])
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Top 1 files:"), "{}", stdout);
+    assert!(
+        stdout.contains("bad.sysml: 3 errors, 0 warnings"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("poor.sysml:"), "{}", stdout);
+}
+
+#[test]
+fn test_cli_top_offenders_json() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let run = |extra: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+            .arg(temp_dir.path())
+            .args(["--no-stdlib", "--json"])
+            .args(extra)
+            .output()
+            .expect("Should run CLI");
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    assert!(run(&[]).get("top_offenders").is_none());
+
+    let json = run(&["--top-offenders", "2"]);
+    let top = json["top_offenders"].as_array().unwrap();
+    assert_eq!(top.len(), 2);
+    assert_eq!(top[0]["errors"], 3);
+    assert_eq!(top[1]["errors"], 1);
+}
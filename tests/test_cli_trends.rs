@@ -0,0 +1,131 @@
+//! Integration tests for the model statistics database.
+//!
+//! Tests recording runs with their git revision, reading them back in
+//! order, rendering trend charts, and `syster metrics record` / `syster
+//! metrics chart`.
+
+#![cfg(feature = "trends")]
+
+mod common;
+
+use common::git;
+use std::fs;
+use std::process::Command;
+use std::time::Duration;
+use syster_cli::{
+    AnalysisOptions, HistoryMetric, MetricsDb, MetricsRun, analyze, git_revision, trend_chart,
+};
+use tempfile::TempDir;
+
+fn run(recorded_at: &str, revision: Option<&str>, file_count: usize) -> MetricsRun {
+    MetricsRun {
+        recorded_at: recorded_at.to_string(),
+        revision: revision.map(str::to_string),
+        file_count,
+        symbol_count: 10 * file_count,
+        error_count: 1,
+        warning_count: 0,
+        doc_coverage: 50.0,
+        duration: 0.25,
+    }
+}
+
+#[test]
+fn test_record_runs_with_git_revision() {
+    let temp_dir = TempDir::new().unwrap();
+    let models = temp_dir.path().join("models");
+    fs::create_dir(&models).unwrap();
+    fs::write(models.join("a.sysml"), "part def A { part b : B; }").unwrap();
+    git(temp_dir.path(), &["init", "-q"]);
+    git(temp_dir.path(), &["add", "."]);
+    git(temp_dir.path(), &["commit", "-q", "-m", "first"]);
+
+    let options = AnalysisOptions {
+        load_stdlib: false,
+        ..Default::default()
+    };
+    let result = analyze(&models, &options).unwrap();
+    let revision = git_revision(&models);
+    assert!(revision.as_deref().is_some_and(|r| r.len() >= 7));
+
+    let path = temp_dir.path().join("metrics.sqlite");
+    let first = MetricsRun::new(&result, Duration::from_millis(120), revision.clone());
+    MetricsDb::open(&path).unwrap().record(&first).unwrap();
+    // Reopening keeps the recorded runs
+    let db = MetricsDb::open(&path).unwrap();
+    db.record(&run("2030-01-01T00:00:00Z", None, 3)).unwrap();
+
+    let runs = db.runs().unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[0], first);
+    assert_eq!(runs[0].revision, revision);
+    assert_eq!(runs[0].file_count, 1);
+    assert!(runs[0].error_count > 0);
+    assert_eq!(runs[1].revision, None);
+    assert_eq!(runs[1].file_count, 3);
+}
+
+#[test]
+fn test_git_revision_outside_work_tree() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("a.sysml"), "part def A;").unwrap();
+
+    assert_eq!(git_revision(&temp_dir.path().join("a.sysml")), None);
+}
+
+#[test]
+fn test_trend_chart() {
+    let runs = vec![
+        run("2026-01-01T10:00:00Z", Some("abc1234"), 1),
+        run("2026-02-01T10:00:00Z", Some("<def>"), 4),
+        run("2026-03-01T10:00:00Z", None, 2),
+    ];
+
+    let html = trend_chart(&runs, &[HistoryMetric::Files, HistoryMetric::Docs]);
+    assert!(html.contains("3 runs from 2026-01-01T10:00:00Z to 2026-03-01T10:00:00Z"));
+    assert_eq!(html.matches("<svg").count(), 2);
+    assert!(html.contains("<h2>files</h2>") && html.contains("<h2>docs</h2>"));
+    assert!(!html.contains("<h2>symbols</h2>"));
+    assert_eq!(html.matches("<circle").count(), 6);
+    assert!(html.contains("2026-01-01T10:00:00Z (abc1234): 1</title>"));
+    assert!(html.contains("(&lt;def&gt;): 4</title>"));
+    assert!(html.contains("50.0%"));
+
+    let empty = trend_chart(&[], &HistoryMetric::ALL);
+    assert!(empty.contains("No runs recorded yet."));
+    assert!(!empty.contains("<svg"));
+}
+
+#[test]
+fn test_cli_metrics_record_and_chart() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("a.sysml"), "part def A;").unwrap();
+    let db = temp_dir.path().join("metrics.sqlite");
+    let html = temp_dir.path().join("trends.html");
+
+    for _ in 0..2 {
+        let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+            .args(["--no-stdlib", "metrics", "record", "--db"])
+            .arg(&db)
+            .arg("--src")
+            .arg(temp_dir.path())
+            .output()
+            .expect("Should run CLI");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(output.status.success(), "stdout: {}", stdout);
+        assert!(stdout.contains("✓ Recorded 1 files, 0 errors, 0 warnings"));
+    }
+    assert_eq!(MetricsDb::open(&db).unwrap().runs().unwrap().len(), 2);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["metrics", "chart", "--metric", "files,errors", "--db"])
+        .arg(&db)
+        .arg("--out")
+        .arg(&html)
+        .output()
+        .expect("Should run CLI");
+    assert!(output.status.success());
+    let html = fs::read_to_string(&html).unwrap();
+    assert!(html.contains("2 runs from"));
+    assert_eq!(html.matches("<svg").count(), 2);
+}
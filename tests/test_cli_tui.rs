@@ -0,0 +1,137 @@
+//! Integration tests for the interactive browser.
+//!
+//! Tests the navigation state behind `syster tui` (tree folding, symbol
+//! details, jumping from a diagnostic to its source and browsing a saved
+//! workspace) without a terminal.
+
+#![cfg(feature = "tui")]
+
+use std::fs;
+use syster_cli::tui::{Browser, Pane};
+use syster_cli::{AnalysisOptions, Workspace};
+use tempfile::TempDir;
+
+/// Load a small model with one unresolved type.
+fn load(dir: &TempDir) -> Browser {
+    fs::write(
+        dir.path().join("vehicles.sysml"),
+        "package Vehicles {\n\
+         \x20   part def Engine;\n\
+         \x20   part def Car {\n\
+         \x20       part engine : Engine;\n\
+         \x20       part wheel : Wheel;\n\
+         \x20   }\n\
+         }\n",
+    )
+    .unwrap();
+    Browser::load(dir.path(), &AnalysisOptions::new()).expect("Should load")
+}
+
+/// Qualified names of the visible tree rows.
+fn rows(browser: &Browser) -> Vec<String> {
+    browser
+        .visible()
+        .iter()
+        .map(|n| n.qualified_name.clone())
+        .collect()
+}
+
+#[test]
+fn test_tree_folding() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut browser = load(&temp_dir);
+
+    assert_eq!(
+        rows(&browser),
+        vec!["Vehicles", "Vehicles::Car", "Vehicles::Engine"]
+    );
+
+    browser.move_selection(1);
+    browser.toggle_expanded();
+    assert!(rows(&browser).contains(&"Vehicles::Car::engine".to_string()));
+
+    browser.move_selection(-1);
+    browser.set_expanded(false);
+    assert_eq!(rows(&browser), vec!["Vehicles"]);
+}
+
+#[test]
+fn test_details_show_relationships() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut browser = load(&temp_dir);
+
+    browser.move_selection(1);
+    browser.set_expanded(true);
+    browser.move_selection(1);
+
+    let details = browser.details().join("\n");
+    assert!(details.contains("Vehicles::Car::engine"), "{}", details);
+    assert!(details.contains("Vehicles::Engine"), "{}", details);
+    assert!(details.contains("vehicles.sysml:4:"), "{}", details);
+}
+
+#[test]
+fn test_jump_to_diagnostic() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut browser = load(&temp_dir);
+    assert!(
+        browser
+            .diagnostics()
+            .iter()
+            .any(|d| d.message.contains("Wheel"))
+    );
+
+    browser.toggle_focus();
+    assert_eq!(browser.focus(), Pane::Diagnostics);
+    browser.jump_to_diagnostic();
+
+    assert_eq!(browser.focus(), Pane::Tree);
+    assert_eq!(
+        browser.selected().map(|n| n.qualified_name.as_str()),
+        Some("Vehicles::Car::wheel")
+    );
+    let excerpt = browser.source_excerpt();
+    assert!(
+        excerpt
+            .iter()
+            .any(|(line, text)| *line == 5 && text.contains("Wheel"))
+    );
+}
+
+#[test]
+fn test_browse_saved_workspace() {
+    let temp_dir = TempDir::new().unwrap();
+    load(&temp_dir);
+    let bytes = Workspace::analyze(temp_dir.path(), &AnalysisOptions::new())
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+
+    // Browsing reads the saved index and sources, not the model files
+    fs::remove_file(temp_dir.path().join("vehicles.sysml")).unwrap();
+    let workspace = Workspace::from_bytes(&bytes).unwrap();
+    let mut browser = Browser::from_workspace(&workspace);
+
+    assert_eq!(
+        rows(&browser),
+        vec!["Vehicles", "Vehicles::Car", "Vehicles::Engine"]
+    );
+    browser.move_selection(1);
+    browser.set_expanded(true);
+    browser.move_selection(1);
+    let details = browser.details().join("\n");
+    assert!(details.contains("Vehicles::Engine"), "{}", details);
+
+    browser.toggle_focus();
+    browser.jump_to_diagnostic();
+    assert_eq!(
+        browser.selected().map(|n| n.qualified_name.as_str()),
+        Some("Vehicles::Car::wheel")
+    );
+    assert!(
+        browser
+            .source_excerpt()
+            .iter()
+            .any(|(line, text)| *line == 5 && text.contains("Wheel"))
+    );
+}
@@ -0,0 +1,201 @@
+//! Integration tests for `syster validate-interchange`.
+//!
+//! Tests that payload elements are validated against the schema named by
+//! their `@type`, with `$ref`s resolved across the bundle, that violations
+//! are reported with JSON pointers, and the CLI's output and exit status.
+
+#![cfg(feature = "interchange")]
+
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster_cli::{SchemaBundle, validate_interchange};
+use tempfile::TempDir;
+
+const BASE: &str = "https://www.omg.org/spec/SysML/20250201";
+
+/// A small bundle in the layout of the published one: a file per type,
+/// referring to each other by relative `$id`, plus a file of `$defs`.
+fn write_bundle(dir: &Path) -> PathBuf {
+    let bundle = dir.join("schemas");
+    fs::create_dir(&bundle).unwrap();
+    let schemas = [
+        (
+            "Element.json",
+            json!({
+                "$id": format!("{}/Element", BASE),
+                "type": "object",
+                "required": ["@id", "@type"],
+                "properties": {
+                    "@id": {"type": "string", "pattern": "^[0-9a-f-]+$"},
+                    "@type": {"type": "string"},
+                    "declaredName": {"type": ["string", "null"]},
+                    "ownedRelationship": {"type": "array", "items": {"$ref": "Identified"}}
+                }
+            }),
+        ),
+        (
+            "Identified.json",
+            json!({
+                "$id": format!("{}/Identified", BASE),
+                "type": "object",
+                "required": ["@id"],
+                "properties": {"@id": {"type": "string"}},
+                "additionalProperties": false
+            }),
+        ),
+        (
+            "PartDefinition.json",
+            json!({
+                "$id": format!("{}/PartDefinition", BASE),
+                "allOf": [{"$ref": "Element"}],
+                "properties": {
+                    "@type": {"const": "PartDefinition"},
+                    "isAbstract": {"type": "boolean"}
+                }
+            }),
+        ),
+        (
+            "Kernel.json",
+            json!({
+                "$id": format!("{}/Kernel", BASE),
+                "$defs": {
+                    "Package": {"$ref": "Element"}
+                }
+            }),
+        ),
+    ];
+    for (name, schema) in schemas {
+        fs::write(
+            bundle.join(name),
+            serde_json::to_string_pretty(&schema).unwrap(),
+        )
+        .unwrap();
+    }
+    bundle
+}
+
+fn write_payload(dir: &Path, name: &str, payload: &Value) -> PathBuf {
+    let path = dir.join(name);
+    fs::write(&path, serde_json::to_string_pretty(payload).unwrap()).unwrap();
+    path
+}
+
+fn malformed() -> Value {
+    json!([
+        {
+            "@id": "a1",
+            "@type": "PartDefinition",
+            "isAbstract": "yes",
+            "ownedRelationship": [{"@id": "b2", "extra": 1}]
+        },
+        {"@id": "XYZ", "@type": "Package"},
+        {"@id": "c3", "@type": "Mystery"}
+    ])
+}
+
+#[test]
+fn test_valid_payload() {
+    let temp_dir = TempDir::new().unwrap();
+    let bundle = write_bundle(temp_dir.path());
+    assert_eq!(SchemaBundle::load(&bundle).unwrap().len(), 4);
+
+    let payload = write_payload(
+        temp_dir.path(),
+        "model.json",
+        &json!([
+            {
+                "@id": "a1",
+                "@type": "PartDefinition",
+                "declaredName": "Engine",
+                "isAbstract": true,
+                "ownedRelationship": [{"@id": "b2"}]
+            },
+            {"@id": "c3", "@type": "Package", "declaredName": null}
+        ]),
+    );
+    let report = validate_interchange(&payload, &bundle).unwrap();
+    assert_eq!(report.elements, 2);
+    assert!(report.violations.is_empty(), "{:?}", report.violations);
+
+    let err = validate_interchange(&payload, &temp_dir.path().join("missing")).unwrap_err();
+    assert!(err.contains("is not a directory"), "{}", err);
+}
+
+#[test]
+fn test_violations_have_json_pointers() {
+    let temp_dir = TempDir::new().unwrap();
+    let bundle = write_bundle(temp_dir.path());
+    let payload = write_payload(temp_dir.path(), "model.json", &malformed());
+
+    let report = validate_interchange(&payload, &bundle).unwrap();
+    let violations: Vec<String> = report.violations.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        violations,
+        [
+            "/0/ownedRelationship/0/extra: unexpected property 'extra'",
+            "/0/isAbstract: expected boolean, found string",
+            "/1/@id: does not match pattern ^[0-9a-f-]+$",
+            "/2: no schema for @type 'Mystery' in the bundle",
+        ]
+    );
+
+    // A single element is validated at the root
+    let single = write_payload(temp_dir.path(), "single.json", &json!({"@type": "Package"}));
+    let report = validate_interchange(&single, &bundle).unwrap();
+    assert_eq!(report.elements, 1);
+    assert_eq!(
+        report.violations[0].to_string(),
+        "(root): missing required property '@id'"
+    );
+}
+
+#[test]
+fn test_cli_validate_interchange() {
+    let temp_dir = TempDir::new().unwrap();
+    let bundle = write_bundle(temp_dir.path());
+    let payload = write_payload(temp_dir.path(), "model.json", &malformed());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("validate-interchange")
+        .arg(&payload)
+        .arg("--schema-bundle")
+        .arg(&bundle)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("/0/isAbstract: expected boolean, found string"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("✗ 4 schema violations"), "{}", stdout);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("validate-interchange")
+        .arg(&payload)
+        .arg("--schema-bundle")
+        .arg(&bundle)
+        .arg("--json")
+        .output()
+        .unwrap();
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["elements"], 3);
+    assert_eq!(report["violations"][3]["pointer"], "/2");
+
+    let valid = write_payload(
+        temp_dir.path(),
+        "valid.json",
+        &json!([{"@id": "a1", "@type": "Package"}]),
+    );
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("validate-interchange")
+        .arg(&valid)
+        .arg("--schema-bundle")
+        .arg(&bundle)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+}
@@ -0,0 +1,222 @@
+//! Integration tests for configuration-aware analysis.
+//!
+//! Tests that `--config variants.toml` analyzes and exports only the selected
+//! variants, reports incomplete or inconsistent selections, and that the
+//! selection matrix from `syster variants list` can be filled in and fed back.
+
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, MatrixFormat, VariantConfig, analyze, list_variation_points,
+    render_variant_matrix, run_analysis,
+};
+use tempfile::TempDir;
+
+/// Write a car with an engine variation point.
+const MODELS: &[(&str, &str)] = &[(
+    "car.sysml",
+    "package Vehicle {\n\
+         \x20   part def Engine;\n\
+         \x20   part def FourCylinder :> Engine;\n\
+         \x20   part def SixCylinder :> Engine;\n\
+         \x20   part def Car {\n\
+         \x20       variation part engine : Engine {\n\
+         \x20           variant part v4 : FourCylinder;\n\
+         \x20           variant part v6 : SixCylinder;\n\
+         \x20       }\n\
+         \x20   }\n\
+         }\n",
+)];
+
+/// Write a configuration file with the given `[variants]` entries.
+fn write_config(dir: &Path, entries: &str) -> PathBuf {
+    let file = dir.join("variants.toml");
+    fs::write(&file, format!("[variants]\n{}", entries)).unwrap();
+    file
+}
+
+#[test]
+fn test_analysis_with_complete_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+    let config = VariantConfig::load(&write_config(
+        temp_dir.path(),
+        "\"Vehicle::Car::engine\" = \"v6\"\n",
+    ))
+    .unwrap();
+
+    let all = run_analysis(&file, false, false, None).unwrap();
+    let configured = analyze(&file, &AnalysisOptions::new().config(&config)).unwrap();
+
+    assert_eq!(configured.error_count, 0, "{:?}", configured.diagnostics);
+    assert_eq!(configured.symbol_count, all.symbol_count - 1);
+}
+
+#[test]
+fn test_incomplete_and_inconsistent_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let empty = VariantConfig::load(&write_config(temp_dir.path(), "")).unwrap();
+    let result = analyze(&file, &AnalysisOptions::new().config(&empty)).unwrap();
+    assert_eq!(result.error_count, 1);
+    assert_eq!(
+        result.diagnostics[0].message,
+        "no variant selected for Vehicle::Car::engine (one of: v4, v6)"
+    );
+
+    let wrong = VariantConfig::load(&write_config(
+        temp_dir.path(),
+        "\"Vehicle::Car::engine\" = \"v8\"\n\"Vehicle::Car\" = \"sedan\"\n",
+    ))
+    .unwrap();
+    let result = analyze(&file, &AnalysisOptions::new().config(&wrong)).unwrap();
+    let messages: Vec<_> = result
+        .diagnostics
+        .iter()
+        .map(|d| (d.line, d.message.as_str()))
+        .collect();
+    assert!(
+        messages.contains(&(
+            2,
+            "v8 is not a variant of Vehicle::Car::engine (one of: v4, v6)"
+        )),
+        "{:?}",
+        messages
+    );
+    assert!(
+        messages.contains(&(3, "Vehicle::Car is not a variation point")),
+        "{:?}",
+        messages
+    );
+}
+
+#[cfg(feature = "interchange")]
+#[test]
+fn test_export_with_config() {
+    use syster::interchange::{ModelFormat, Xmi};
+    use syster_cli::{ExportFilter, export_model_with_options};
+
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+    let config = VariantConfig::load(&write_config(
+        temp_dir.path(),
+        "\"Vehicle::Car::engine\" = \"v4\"\n",
+    ))
+    .unwrap();
+
+    let filter = ExportFilter {
+        config: Some(config),
+        ..ExportFilter::default()
+    };
+    let (xmi, _) =
+        export_model_with_options(&file, "xmi", &AnalysisOptions::default(), false, &filter)
+            .unwrap();
+    let model = Xmi.read(&xmi).unwrap();
+    let names: Vec<_> = model
+        .elements
+        .values()
+        .filter_map(|e| e.name.as_deref())
+        .collect();
+
+    assert!(names.contains(&"v4"), "{:?}", names);
+    assert!(!names.contains(&"v6"), "{:?}", names);
+}
+
+#[test]
+fn test_cli_config_reports_missing_selection() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+    let config = write_config(temp_dir.path(), "");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .arg("--no-stdlib")
+        .arg("--config")
+        .arg(&config)
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success());
+    assert!(
+        format!("{}{}", stdout, stderr).contains("no variant selected for Vehicle::Car::engine"),
+        "stdout: {}\nstderr: {}",
+        stdout,
+        stderr
+    );
+}
+
+#[test]
+fn test_variant_matrix_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let points = list_variation_points(&file, &AnalysisOptions::new()).unwrap();
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].qualified_name, "Vehicle::Car::engine");
+    assert_eq!(points[0].variants, vec!["v4", "v6"]);
+
+    // Mark the v6 row of the CSV matrix
+    let csv = render_variant_matrix(&points, MatrixFormat::Csv).unwrap();
+    let filled: String = csv
+        .lines()
+        .map(|line| {
+            if line.ends_with(",v6,") {
+                format!("{}x\n", line)
+            } else {
+                format!("{}\n", line)
+            }
+        })
+        .collect();
+    let csv_path = temp_dir.path().join("matrix.csv");
+    fs::write(&csv_path, filled).unwrap();
+    let config = VariantConfig::load(&csv_path).unwrap();
+    assert_eq!(config.variants["Vehicle::Car::engine"], "v6");
+
+    // Fill in the JSON matrix
+    let json = render_variant_matrix(&points, MatrixFormat::Json).unwrap();
+    let json_path = temp_dir.path().join("matrix.json");
+    fs::write(&json_path, json.replace("null", "\"v4\"")).unwrap();
+    let config = VariantConfig::load(&json_path).unwrap();
+    assert_eq!(config.variants["Vehicle::Car::engine"], "v4");
+}
+
+#[test]
+fn test_cli_variants_list_feeds_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let file = common::write_models(temp_dir.path(), MODELS);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["variants", "list", "--no-stdlib", "--src"])
+        .arg(&file)
+        .output()
+        .expect("Should run CLI");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {}", stdout);
+    assert!(stdout.contains("one of: v4, v6"), "{}", stdout);
+    assert!(
+        stdout.contains("\"Vehicle::Car::engine\" = \"\""),
+        "{}",
+        stdout
+    );
+
+    let config = temp_dir.path().join("variants.toml");
+    fs::write(&config, stdout.replace("= \"\"", "= \"v6\"")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(&file)
+        .arg("--no-stdlib")
+        .arg("--config")
+        .arg(&config)
+        .output()
+        .expect("Should run CLI");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
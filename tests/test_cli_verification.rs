@@ -0,0 +1,224 @@
+//! Integration tests for the verification plan.
+//!
+//! Tests that `syster verification-report` lists every verification case
+//! with the requirements it verifies and its subjects, inherited from the
+//! definition typing a usage, and marks incomplete cases.
+
+#![cfg(feature = "codegen")]
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, DocumentFormat, VerificationStatus, VerificationSubject,
+    render_verification_plan, verification_plan,
+};
+use tempfile::TempDir;
+
+/// Write a model with a verification definition, a usage of it, and a
+/// case without a subject.
+const MODELS: &[(&str, &str)] = &[(
+    "tests.sysml",
+    "package Requirements {\n\
+         \x20   requirement <R1> massRequirement {\n\
+         \x20       doc /* The vehicle mass shall not exceed 2000 kg. */\n\
+         \x20   }\n\
+         \x20   requirement <R2> speedRequirement {\n\
+         \x20       doc /* The vehicle shall reach 120 km/h. */\n\
+         \x20   }\n\
+         }\n\
+         package Tests {\n\
+         \x20   import Requirements::*;\n\
+         \x20   part def Vehicle;\n\
+         \x20   verification def MassTest {\n\
+         \x20       doc /* Weigh the vehicle */\n\
+         \x20       subject vehicle : Vehicle;\n\
+         \x20       objective {\n\
+         \x20           verify massRequirement;\n\
+         \x20       }\n\
+         \x20   }\n\
+         \x20   verification massTest : MassTest;\n\
+         \x20   verification speedTest {\n\
+         \x20       objective {\n\
+         \x20           verify Requirements::speedRequirement;\n\
+         \x20       }\n\
+         \x20   }\n\
+         }\n",
+)];
+
+#[test]
+fn test_verification_plan() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let plan = verification_plan(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    let cases: Vec<(&str, bool, &str, VerificationStatus)> = plan
+        .cases
+        .iter()
+        .map(|c| {
+            (
+                c.qualified_name.as_str(),
+                c.is_definition,
+                c.definition.as_str(),
+                c.status,
+            )
+        })
+        .collect();
+    assert_eq!(
+        cases,
+        vec![
+            ("Tests::MassTest", true, "", VerificationStatus::NotRun),
+            (
+                "Tests::massTest",
+                false,
+                "Tests::MassTest",
+                VerificationStatus::NotRun
+            ),
+            (
+                "Tests::speedTest",
+                false,
+                "",
+                VerificationStatus::Incomplete
+            ),
+        ]
+    );
+
+    let mass = &plan.cases[0];
+    assert_eq!(mass.line, 12);
+    assert_eq!(mass.doc, "Weigh the vehicle");
+    assert_eq!(mass.requirements.len(), 1);
+    assert_eq!(mass.requirements[0].id, "R1");
+    assert_eq!(
+        mass.requirements[0].qualified_name,
+        "Requirements::massRequirement"
+    );
+    assert_eq!(
+        mass.requirements[0].text,
+        "The vehicle mass shall not exceed 2000 kg."
+    );
+    assert_eq!(
+        mass.subjects,
+        vec![VerificationSubject {
+            name: "vehicle".to_string(),
+            type_name: "Tests::Vehicle".to_string(),
+        }]
+    );
+
+    // The usage takes the requirements and subject of its definition
+    assert_eq!(plan.cases[1].requirements, mass.requirements);
+    assert_eq!(plan.cases[1].subjects, mass.subjects);
+
+    let incomplete: Vec<&str> = plan.incomplete().map(|c| c.name.as_str()).collect();
+    assert_eq!(incomplete, vec!["speedTest"]);
+    assert_eq!(plan.cases[2].requirements[0].id, "R2");
+}
+
+#[test]
+fn test_verification_plan_formats() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let plan = verification_plan(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    let markdown = render_verification_plan(&plan, DocumentFormat::Markdown).unwrap();
+    assert!(
+        markdown.starts_with("# Verification Plan\n"),
+        "{}",
+        markdown
+    );
+    assert!(markdown.contains("## MassTest\n"), "{}", markdown);
+    assert!(
+        markdown.contains("`Tests::massTest` (verification : `Tests::MassTest`)"),
+        "{}",
+        markdown
+    );
+    assert!(
+        markdown.contains("- **Subject:** `vehicle` : `Tests::Vehicle`"),
+        "{}",
+        markdown
+    );
+    assert!(
+        markdown.contains(
+            "| R1 | `Requirements::massRequirement` | The vehicle mass shall not exceed 2000 kg. |"
+        ),
+        "{}",
+        markdown
+    );
+    assert!(
+        markdown.contains("- **Status:** Incomplete"),
+        "{}",
+        markdown
+    );
+
+    let csv = render_verification_plan(&plan, DocumentFormat::Csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "case,kind,definition,subject,subject_type,requirement_id,requirement,requirement_text,status,file,line"
+    );
+    assert_eq!(lines.len(), 4);
+    assert!(
+        lines[2].starts_with(
+            "Tests::massTest,verification,Tests::MassTest,vehicle,Tests::Vehicle,R1,\
+             Requirements::massRequirement,The vehicle mass shall not exceed 2000 kg.,Not run,"
+        ),
+        "{}",
+        lines[2]
+    );
+    assert!(lines[3].starts_with("Tests::speedTest,verification,,,,R2,"));
+    assert!(lines[3].ends_with(",20"), "{}", lines[3]);
+
+    let html = render_verification_plan(&plan, DocumentFormat::Html).unwrap();
+    assert!(html.contains("<h2>speedTest</h2>"));
+    assert!(html.contains("class=\"incomplete\""));
+}
+
+#[test]
+fn test_model_without_verification_cases() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("a.sysml"),
+        "package P {\n    requirement def R;\n    part def A;\n}\n",
+    )
+    .unwrap();
+
+    let plan = verification_plan(temp_dir.path(), &AnalysisOptions::new()).unwrap();
+
+    assert!(plan.cases.is_empty());
+    let markdown = render_verification_plan(&plan, DocumentFormat::Markdown).unwrap();
+    assert!(markdown.contains("No verification cases found."));
+    let csv = render_verification_plan(&plan, DocumentFormat::Csv).unwrap();
+    assert_eq!(csv.lines().count(), 1);
+}
+
+#[test]
+fn test_cli_verification_report() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let out = temp_dir.path().join("plan.csv");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["verification-report", "--no-stdlib", "--format", "csv"])
+        .arg("--src")
+        .arg(temp_dir.path())
+        .arg("-o")
+        .arg(&out)
+        .output()
+        .expect("Should run CLI");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("warning: Tests::speedTest is incomplete: no subject"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("✓ 3 verification cases verifying 2 requirements (1 incomplete)"),
+        "{}",
+        stderr
+    );
+    let csv = fs::read_to_string(&out).unwrap();
+    assert!(csv.starts_with("case,kind,definition,"));
+    assert!(csv.contains("Tests::MassTest,verification def,,vehicle,"));
+}
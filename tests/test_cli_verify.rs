@@ -0,0 +1,90 @@
+//! Integration tests for the `verify` subcommand.
+//!
+//! Tests that `syster verify <src> --against <file>` passes when an exported
+//! model matches its sources and fails when the two have diverged.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::process::Command;
+use syster::interchange::{Element, ElementId, ElementKind, Model};
+use syster_cli::{AnalysisOptions, diff_models, export_model, verify_against};
+use tempfile::TempDir;
+
+#[test]
+fn test_verify_matches_exported_model() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(
+        src.join("vehicle.sysml"),
+        "package Vehicles { part def Engine; part def Car { part engine : Engine; } }",
+    )
+    .unwrap();
+
+    let xmi = export_model(&src, "xmi", false, false, None, false).unwrap();
+    let xmi_path = temp_dir.path().join("model.xmi");
+    fs::write(&xmi_path, xmi).unwrap();
+
+    let diff = verify_against(&src, &xmi_path, &AnalysisOptions::new()).unwrap();
+    assert!(diff.is_empty(), "Unexpected differences: {:?}", diff);
+}
+
+#[test]
+fn test_cli_verify_detects_divergence() {
+    let temp_dir = TempDir::new().unwrap();
+    let src = temp_dir.path().join("src");
+    fs::create_dir_all(&src).unwrap();
+    let source_file = src.join("vehicle.sysml");
+    fs::write(&source_file, "package Vehicles { part def Car; }").unwrap();
+
+    let xmi = export_model(&src, "xmi", false, false, None, false).unwrap();
+    let xmi_path = temp_dir.path().join("model.xmi");
+    fs::write(&xmi_path, xmi).unwrap();
+
+    // Sources move on after the model was delivered
+    fs::write(
+        &source_file,
+        "package Vehicles { part def Car; part def Truck; }",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("verify")
+        .arg(&src)
+        .arg("--against")
+        .arg(&xmi_path)
+        .arg("--no-stdlib")
+        .output()
+        .expect("Should run CLI");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!output.status.success(), "Divergent model should fail");
+    assert!(stdout.contains("only in sources: PartDefinition Vehicles::Truck"));
+}
+
+#[test]
+fn test_diff_models_reports_kind_mismatch() {
+    let mut source = Model::new();
+    source.add_element(Element::new(ElementId::new("a"), ElementKind::Package).with_name("P"));
+    source.add_element(
+        Element::new(ElementId::new("b"), ElementKind::PartDefinition)
+            .with_name("X")
+            .with_owner(ElementId::new("a")),
+    );
+
+    // Same names, different IDs and a different kind for X
+    let mut delivered = Model::new();
+    delivered.add_element(Element::new(ElementId::new("1"), ElementKind::Package).with_name("P"));
+    delivered.add_element(
+        Element::new(ElementId::new("2"), ElementKind::ItemDefinition)
+            .with_name("X")
+            .with_owner(ElementId::new("1")),
+    );
+
+    let diff = diff_models(&source, &delivered);
+    assert!(diff.only_in_source.is_empty());
+    assert!(diff.only_in_interchange.is_empty());
+    assert_eq!(diff.kind_mismatches.len(), 1);
+    assert!(diff.kind_mismatches[0].starts_with("P::X"));
+}
@@ -0,0 +1,133 @@
+//! Integration tests for directory walking.
+//!
+//! Tests that symlink loops and files reachable through several paths are
+//! loaded once, `--no-follow-symlinks`, and `--max-depth`, also when
+//! exporting.
+
+#![cfg(unix)]
+
+mod common;
+
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, WalkOptions, analyze};
+use tempfile::TempDir;
+
+/// `model/car.sysml` and `model/parts/engine.sysml`, a link to the engine
+/// file, and a link from `parts` back to `model`.
+fn write_model(dir: &Path) {
+    common::write_models(
+        dir,
+        &[
+            (
+                "car.sysml",
+                "package Vehicle {\n    import Power::*;\n    part engine : Engine;\n}\n",
+            ),
+            (
+                "parts/engine.sysml",
+                "package Power {\n    part def Engine;\n}\n",
+            ),
+        ],
+    );
+    let parts = dir.join("parts");
+    symlink(parts.join("engine.sysml"), dir.join("motor.sysml")).unwrap();
+    symlink(dir, parts.join("up")).unwrap();
+}
+
+fn analyze_dir(dir: &Path, walk: WalkOptions) -> syster_cli::AnalysisResult {
+    let options = AnalysisOptions {
+        walk,
+        ..Default::default()
+    };
+    analyze(dir, &options).unwrap()
+}
+
+#[test]
+fn test_symlinks_loaded_once() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path());
+
+    // The loop through `parts/up` is skipped and the linked engine file is
+    // not loaded a second time, so Engine is not a duplicate
+    let result = analyze_dir(temp_dir.path(), WalkOptions::default());
+    assert_eq!(result.file_count, 2);
+    assert_eq!(result.error_count, 0, "{:?}", result.diagnostics);
+
+    let result = analyze_dir(
+        temp_dir.path(),
+        WalkOptions {
+            follow_symlinks: false,
+            ..Default::default()
+        },
+    );
+    assert_eq!(result.file_count, 2);
+    assert_eq!(result.error_count, 0, "{:?}", result.diagnostics);
+}
+
+#[test]
+fn test_max_depth() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path());
+
+    let result = analyze_dir(
+        temp_dir.path(),
+        WalkOptions {
+            follow_symlinks: false,
+            max_depth: Some(1),
+            ..Default::default()
+        },
+    );
+    assert_eq!(result.file_count, 1);
+    assert!(
+        result
+            .diagnostics
+            .iter()
+            .any(|d| d.message.contains("Engine")),
+        "{:?}",
+        result.diagnostics
+    );
+}
+
+#[test]
+fn test_cli_walk_options() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--json"])
+        .output()
+        .expect("Should run CLI");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["file_count"], 2);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--json", "--no-follow-symlinks"])
+        .args(["--max-depth", "1"])
+        .output()
+        .expect("Should run CLI");
+    assert!(!output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["file_count"], 1);
+}
+
+#[cfg(feature = "interchange")]
+#[test]
+fn test_cli_export_walk_options() {
+    let temp_dir = TempDir::new().unwrap();
+    write_model(temp_dir.path());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--export", "xmi", "--no-follow-symlinks"])
+        .args(["--max-depth", "1"])
+        .output()
+        .expect("Should run CLI");
+    assert!(output.status.success());
+    let xmi = String::from_utf8(output.stdout).unwrap();
+    assert!(xmi.contains("declaredName=\"Vehicle\""), "{}", xmi);
+    assert!(!xmi.contains("declaredName=\"Power\""), "{}", xmi);
+}
@@ -0,0 +1,117 @@
+//! Integration tests for saved workspaces.
+//!
+//! Tests the binary `.syster` round trip, symbol index included, rejecting
+//! files that aren't workspaces, and reopening a workspace with
+//! `syster workspace load` after its sources are gone.
+
+mod common;
+
+use std::fs;
+use std::process::Command;
+use syster_cli::{AnalysisOptions, Workspace};
+use tempfile::TempDir;
+
+/// A model with one unresolved reference.
+const MODELS: &[(&str, &str)] = &[(
+    "car.sysml",
+    "package Vehicle {\n\
+         \x20   part def Engine;\n\
+         \x20   part def Car {\n\
+         \x20       part engine : Engine;\n\
+         \x20       part chassis : Chassis;\n\
+         \x20   }\n\
+         }\n",
+)];
+
+#[test]
+fn test_workspace_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let workspace = Workspace::analyze(temp_dir.path(), &AnalysisOptions::default()).unwrap();
+    assert_eq!(workspace.files.len(), 1);
+    assert!(workspace.files[0].0.ends_with("car.sysml"));
+    assert!(workspace.files[0].1.contains("part def Engine;"));
+    assert_eq!(workspace.result.error_count, 1);
+
+    let bytes = workspace.to_bytes().unwrap();
+    assert!(bytes.starts_with(b"SYWS"));
+    let loaded = Workspace::from_bytes(&bytes).unwrap();
+    assert_eq!(loaded.files, workspace.files);
+    assert_eq!(loaded.result.symbol_count, workspace.result.symbol_count);
+    assert_eq!(loaded.result.diagnostics.len(), 1);
+    assert!(loaded.result.diagnostics[0].message.contains("Chassis"));
+    assert_eq!(loaded.symbols, workspace.symbols);
+    let car = loaded
+        .symbols
+        .iter()
+        .find(|s| s.qualified_name == "Vehicle::Car")
+        .expect("Car should be indexed");
+    assert_eq!((car.kind.as_str(), car.line), ("Part def", 3));
+
+    let host = loaded.to_host();
+    assert_eq!(host.files().len(), 1);
+}
+
+#[test]
+fn test_workspace_rejects_invalid_files() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let bytes = Workspace::analyze(temp_dir.path(), &AnalysisOptions::default())
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+
+    let err = Workspace::from_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+    assert_eq!(err, "unexpected end of file");
+    let err = Workspace::from_bytes(b"SIDX\x01\x00\x00\x00").unwrap_err();
+    assert_eq!(err, "not a saved workspace");
+
+    let path = temp_dir.path().join("car.sysml");
+    let err = Workspace::read(&path).unwrap_err();
+    assert!(err.starts_with("Invalid workspace"), "{}", err);
+}
+
+#[test]
+fn test_cli_workspace_save_and_load() {
+    let temp_dir = TempDir::new().unwrap();
+    let model = temp_dir.path().join("model");
+    fs::create_dir(&model).unwrap();
+    common::write_models(&model, MODELS);
+    let saved = temp_dir.path().join("ws.syster");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["workspace", "save", "--no-stdlib", "--src"])
+        .arg(&model)
+        .arg("-o")
+        .arg(&saved)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Saved 1 files, 1 diagnostics"),
+        "{}",
+        stdout
+    );
+
+    // Loading doesn't need the sources
+    fs::remove_dir_all(&model).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["workspace", "load"])
+        .arg(&saved)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Chassis"), "{}", stderr);
+    assert!(stderr.contains("✗ Loaded 1 files: 1 errors"), "{}", stderr);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .args(["workspace", "load", "--json"])
+        .arg(&saved)
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error_count"], 1);
+}
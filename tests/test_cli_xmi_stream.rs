@@ -0,0 +1,173 @@
+//! Integration tests for streamed XMI exports.
+//!
+//! Tests that the streaming writer produces exactly what the in-memory XMI
+//! writer does, that exports write into any sink and surface its errors,
+//! and that the CLI streams into `--output` without leaving a partial file
+//! behind on failure.
+
+#![cfg(feature = "interchange")]
+
+mod common;
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+use syster::interchange::{ModelFormat, Xmi};
+use syster_cli::{
+    AnalysisOptions, ExportFilter, IdPolicy, Provenance, SCHEMA_VERSION, embed_provenance,
+    export_model_to, write_xmi,
+};
+use tempfile::TempDir;
+
+const MODELS: &[(&str, &str)] = &[(
+    "vehicles.sysml",
+    "package Vehicles {\n\
+         \x20   doc /* Road vehicles & \"their\" parts */\n\
+         \x20   abstract part def Engine;\n\
+         \x20   part def Vehicle {\n\
+         \x20       part engine : Engine;\n\
+         \x20   }\n\
+         }\n\
+         package Materials {\n\
+         \x20   attribute def Steel;\n\
+         }\n",
+)];
+
+fn export_to(dir: &Path, out: impl Write) -> Result<(), String> {
+    export_model_to(
+        dir,
+        "xmi",
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter {
+            id_policy: IdPolicy::QualifiedNameHash,
+            ..ExportFilter::default()
+        },
+        out,
+    )
+    .map(|_| ())
+}
+
+/// A sink that fails once it has taken `limit` bytes.
+struct FailingSink {
+    written: usize,
+    limit: usize,
+}
+
+impl Write for FailingSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written + buf.len() > self.limit {
+            return Err(io::Error::other("disk full"));
+        }
+        self.written += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_stream_matches_xmi_writer() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let mut exported = Vec::new();
+    export_to(temp_dir.path(), &mut exported).unwrap();
+    let model = Xmi.read(&exported).unwrap();
+    // Two packages, so the roots are wrapped in xmi:XMI
+    assert_eq!(model.iter_roots().count(), 2);
+
+    let mut streamed = Vec::new();
+    write_xmi(&model, None, &mut streamed).unwrap();
+    assert_eq!(streamed, Xmi.write(&model).unwrap());
+
+    let provenance = Provenance {
+        tool: "syster".to_string(),
+        version: "0.0.0".to_string(),
+        timestamp: "2026-01-01T00:00:00Z".to_string(),
+        input_hash: "sha256:00".to_string(),
+        stdlib_version: None,
+        schema_version: SCHEMA_VERSION,
+    };
+    let mut streamed = Vec::new();
+    write_xmi(&model, Some(&provenance), &mut streamed).unwrap();
+    assert_eq!(
+        streamed,
+        embed_provenance(Xmi.write(&model).unwrap(), "xmi", &provenance).unwrap()
+    );
+
+    // A single root is the document element
+    let vehicles = fs::read_to_string(temp_dir.path().join("vehicles.sysml")).unwrap();
+    let single = temp_dir.path().join("single");
+    fs::create_dir(&single).unwrap();
+    fs::write(
+        single.join("vehicles.sysml"),
+        &vehicles[..vehicles.find("package Materials").unwrap()],
+    )
+    .unwrap();
+    let mut exported = Vec::new();
+    export_to(&single, &mut exported).unwrap();
+    let model = Xmi.read(&exported).unwrap();
+    let mut streamed = Vec::new();
+    write_xmi(&model, None, &mut streamed).unwrap();
+    assert_eq!(streamed, Xmi.write(&model).unwrap());
+}
+
+#[test]
+fn test_export_model_to_sink() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+
+    let mut bytes = Vec::new();
+    export_to(temp_dir.path(), &mut bytes).unwrap();
+    assert!(Provenance::from_export(&bytes).is_some());
+    assert!(bytes.ends_with(b"</xmi:XMI>\n"));
+
+    let err = export_to(
+        temp_dir.path(),
+        FailingSink {
+            written: 0,
+            limit: 200,
+        },
+    )
+    .unwrap_err();
+    assert!(err.contains("disk full"), "{}", err);
+}
+
+#[test]
+fn test_cli_streams_to_output() {
+    let temp_dir = TempDir::new().unwrap();
+    common::write_models(temp_dir.path(), MODELS);
+    let out = temp_dir.path().join("model.xmi");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--export", "xmi", "-o"])
+        .arg(&out)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let model = Xmi.read(&fs::read(&out).unwrap()).unwrap();
+    assert_eq!(model.iter_roots().count(), 2);
+
+    // A failed export leaves no file behind
+    let failed = temp_dir.path().join("failed.xmi");
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args([
+            "--no-stdlib",
+            "--export",
+            "xmi",
+            "--package",
+            "Missing",
+            "-o",
+        ])
+        .arg(&failed)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown package"));
+    assert!(!failed.exists());
+}
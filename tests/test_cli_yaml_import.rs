@@ -0,0 +1,88 @@
+//! Integration tests for YAML import.
+//!
+//! Tests that a YAML export reads back as the same model, under either
+//! extension or an explicit format, and that the CLI imports and decompiles
+//! it.
+
+#![cfg(feature = "interchange")]
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use syster_cli::{
+    AnalysisOptions, ExportFilter, decompile_model, export_model_with_options, import_model,
+};
+use tempfile::TempDir;
+
+/// Export a small model as YAML to `dir/<name>`.
+fn write_yaml(dir: &Path, name: &str) -> std::path::PathBuf {
+    let src = dir.join("src");
+    fs::create_dir(&src).unwrap();
+    fs::write(
+        src.join("vehicles.sysml"),
+        "package Vehicles {\n\
+         \x20   part def Engine;\n\
+         \x20   part def Vehicle {\n\
+         \x20       part engine : Engine;\n\
+         \x20   }\n\
+         }\n",
+    )
+    .unwrap();
+    let (bytes, _) = export_model_with_options(
+        &src,
+        "yaml",
+        &AnalysisOptions::default(),
+        false,
+        &ExportFilter::default(),
+    )
+    .unwrap();
+    let path = dir.join(name);
+    fs::write(&path, bytes).unwrap();
+    path
+}
+
+#[test]
+fn test_import_yaml() {
+    let temp_dir = TempDir::new().unwrap();
+    let yaml = write_yaml(temp_dir.path(), "vehicles.yaml");
+
+    let result = import_model(&yaml, None, false).unwrap();
+    assert_eq!(result.error_count, 0, "{:?}", result.messages);
+    // Vehicles, Engine, Vehicle and engine
+    assert_eq!(result.element_count, 4);
+    assert!(result.relationship_count >= 1);
+
+    // An explicit format wins over the extension
+    let renamed = temp_dir.path().join("vehicles.txt");
+    fs::copy(&yaml, &renamed).unwrap();
+    let result = import_model(&renamed, Some("yaml"), false).unwrap();
+    assert_eq!(result.element_count, 4);
+}
+
+#[test]
+fn test_decompile_yml() {
+    let temp_dir = TempDir::new().unwrap();
+    let yml = write_yaml(temp_dir.path(), "vehicles.yml");
+
+    let result = decompile_model(&yml, None, false).unwrap();
+    assert_eq!(result.element_count, 4);
+    assert!(result.sysml_text.contains("package Vehicles"));
+    assert!(result.sysml_text.contains("part def Vehicle"));
+    assert!(result.sysml_text.contains("part engine : Engine"));
+}
+
+#[test]
+fn test_cli_import_workspace_yaml() {
+    let temp_dir = TempDir::new().unwrap();
+    let yaml = write_yaml(temp_dir.path(), "vehicles.yaml");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg("--import-workspace")
+        .arg(&yaml)
+        .arg("--no-stdlib")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Element IDs preserved"), "{}", stdout);
+}
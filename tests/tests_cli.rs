@@ -5,7 +5,10 @@
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
-use syster_cli::{export_ast, export_json, run_analysis};
+use syster_cli::{
+    AnalysisOptions, Shard, WalkOptions, analyze, analyze_shards, export_ast,
+    export_ast_with_options, export_json, load_json_result, merge_results, run_analysis,
+};
 use tempfile::TempDir;
 
 // ============================================================================
@@ -731,6 +734,30 @@ fn test_export_ast_directory() {
     assert_eq!(parsed["files"].as_array().unwrap().len(), 2);
 }
 
+#[test]
+fn test_export_ast_walks_and_parses_as_options_say() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join("nested")).unwrap();
+    fs::write(temp_dir.path().join("car.sysml"), "part def Car;").unwrap();
+    fs::write(temp_dir.path().join("broken.sysml"), "part def Broken {").unwrap();
+    fs::write(temp_dir.path().join("nested/deep.sysml"), "part def Deep;").unwrap();
+
+    let options = AnalysisOptions {
+        strict_parse: true,
+        walk: WalkOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let json = export_ast_with_options(temp_dir.path(), &options, &[]).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let files = parsed["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1, "{}", json);
+    assert!(files[0]["path"].as_str().unwrap().ends_with("car.sysml"));
+}
+
 #[test]
 fn test_export_ast_includes_supertypes() {
     let temp_dir = TempDir::new().unwrap();
@@ -856,6 +883,171 @@ fn test_export_ast_empty_file() {
     assert!(parsed["files"][0]["symbols"].as_array().unwrap().is_empty());
 }
 
+// ============================================================================
+// SHARDING TESTS
+// ============================================================================
+
+#[test]
+fn test_shard_parse() {
+    let shard: Shard = "2/4".parse().unwrap();
+    assert_eq!(shard.index, 2);
+    assert_eq!(shard.count, 4);
+
+    assert!("0/4".parse::<Shard>().is_err());
+    assert!("5/4".parse::<Shard>().is_err());
+    assert!("1/0".parse::<Shard>().is_err());
+    assert!("abc".parse::<Shard>().is_err());
+}
+
+#[test]
+fn test_shards_cover_every_file_once() {
+    let temp_dir = TempDir::new().unwrap();
+    for i in 0..5 {
+        let mut file = fs::File::create(temp_dir.path().join(format!("f{}.sysml", i))).unwrap();
+        writeln!(file, "part def P{};", i).unwrap();
+    }
+
+    let full = run_analysis(temp_dir.path(), false, false, None).unwrap();
+
+    let shards: Vec<_> = (1..=3)
+        .map(|i| {
+            let shard = Shard::new(i, 3).unwrap();
+            analyze(temp_dir.path(), &AnalysisOptions::new().shard(shard)).unwrap()
+        })
+        .collect();
+
+    let total_files: usize = shards.iter().map(|r| r.file_count).sum();
+    assert_eq!(total_files, full.file_count);
+
+    let merged = merge_results(shards);
+    assert_eq!(merged.file_count, full.file_count);
+    assert_eq!(merged.symbol_count, full.symbol_count);
+    assert_eq!(merged.error_count, full.error_count);
+}
+
+#[test]
+fn test_shards_report_their_own_broken_files() {
+    let temp_dir = TempDir::new().unwrap();
+    for i in 0..5 {
+        let body = if i == 1 || i == 2 { "{" } else { ";" };
+        fs::write(
+            temp_dir.path().join(format!("f{}.sysml", i)),
+            format!("part def P{}{}", i, body),
+        )
+        .unwrap();
+    }
+
+    // Shard 1 of 3 holds f0 and f3, shard 2 f1 and f4, shard 3 f2
+    for strict_parse in [false, true] {
+        let broken: Vec<usize> = (1..=3)
+            .map(|i| {
+                let options = AnalysisOptions {
+                    strict_parse,
+                    ..AnalysisOptions::new().shard(Shard::new(i, 3).unwrap())
+                };
+                analyze(temp_dir.path(), &options)
+                    .unwrap()
+                    .syntax_errors
+                    .files
+            })
+            .collect();
+        assert_eq!(broken, vec![0, 1, 1], "strict_parse: {}", strict_parse);
+    }
+}
+
+#[test]
+fn test_analyze_shards_in_one_process() {
+    let temp_dir = TempDir::new().unwrap();
+    for i in 0..5 {
+        let body = if i == 1 {
+            "{ part x : Undefined; }"
+        } else {
+            ";"
+        };
+        fs::write(
+            temp_dir.path().join(format!("f{}.sysml", i)),
+            format!("part def P{}{}", i, body),
+        )
+        .unwrap();
+    }
+
+    let options = AnalysisOptions::new();
+    let shards = analyze_shards(temp_dir.path(), &options, 3).unwrap();
+    assert_eq!(shards.len(), 3);
+    for (i, result) in shards.iter().enumerate() {
+        let separate = analyze(
+            temp_dir.path(),
+            &options.clone().shard(Shard::new(i + 1, 3).unwrap()),
+        )
+        .unwrap();
+        assert_eq!(result.file_count, separate.file_count);
+        assert_eq!(result.symbol_count, separate.symbol_count);
+        assert_eq!(result.diagnostics.len(), separate.diagnostics.len());
+    }
+    // f1 falls to shard 2
+    assert!(shards[1].error_count > 0);
+    assert_eq!(shards[0].error_count + shards[2].error_count, 0);
+
+    assert!(analyze_shards(temp_dir.path(), &options, 0).is_err());
+}
+
+#[test]
+fn test_cli_shards_write_one_report_each() {
+    let temp_dir = TempDir::new().unwrap();
+    for i in 0..3 {
+        fs::write(
+            temp_dir.path().join(format!("f{}.sysml", i)),
+            format!("part def P{};", i),
+        )
+        .unwrap();
+    }
+    let out_dir = temp_dir.path().join("shards");
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_syster"))
+        .arg(temp_dir.path())
+        .args(["--no-stdlib", "--json", "--shards", "2", "-o"])
+        .arg(&out_dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    let results: Vec<_> = (1..=2)
+        .map(|i| load_json_result(&out_dir.join(format!("shard-{}.json", i))).unwrap())
+        .collect();
+    assert_eq!(results[0].file_count, 2);
+    assert_eq!(results[1].file_count, 1);
+    assert!(!out_dir.join("shard-3.json").exists());
+}
+
+#[test]
+fn test_merge_reports_from_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let good = temp_dir.path().join("good.sysml");
+    let bad = temp_dir.path().join("bad.sysml");
+    fs::write(&good, "part def A;").unwrap();
+    fs::write(&bad, "part def B { part x : Undefined; }").unwrap();
+
+    let mut paths = Vec::new();
+    for (name, input) in [("a.json", &good), ("b.json", &bad)] {
+        let result = run_analysis(input, false, false, None).unwrap();
+        let path = temp_dir.path().join(name);
+        fs::write(&path, export_json(&result).unwrap()).unwrap();
+        paths.push(path);
+    }
+
+    let results = paths.iter().map(|p| load_json_result(p).unwrap()).collect();
+    let merged = merge_results(results);
+
+    assert_eq!(merged.file_count, 2);
+    assert!(merged.error_count > 0 || merged.warning_count > 0);
+    assert!(
+        merged
+            .diagnostics
+            .iter()
+            .all(|d| d.file.ends_with("bad.sysml"))
+    );
+}
+
 // ============================================================================
 // INTERCHANGE EXPORT TESTS
 // ============================================================================
@@ -863,7 +1055,7 @@ fn test_export_ast_empty_file() {
 #[cfg(feature = "interchange")]
 mod interchange_tests {
     use super::*;
-    use syster_cli::export_model;
+    use syster_cli::{ExportFilter, export_model, export_model_with_options};
 
     #[test]
     fn test_export_model_xmi() {
@@ -1815,4 +2007,108 @@ mod interchange_tests {
             "Should have exactly 1 AttributeDefinition"
         );
     }
+
+    #[test]
+    fn test_export_restores_ids_from_nested_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("b").join("c");
+        fs::create_dir_all(temp_dir.path().join("a")).unwrap();
+        fs::create_dir_all(&nested).unwrap();
+
+        fs::write(
+            temp_dir.path().join("a").join("x.sysml"),
+            "package P { part def A; }",
+        )
+        .unwrap();
+        fs::write(nested.join("y.sysml"), "package Q { part def B; }").unwrap();
+        fs::write(
+            nested.join("y.metadata.json"),
+            r#"{"version": 1, "source": {}, "elements": {"Q::B": {"originalId": "orig-b"}}}"#,
+        )
+        .unwrap();
+
+        let (bytes, stats) = export_model_with_options(
+            temp_dir.path(),
+            "xmi",
+            &AnalysisOptions::default(),
+            false,
+            &ExportFilter::default(),
+        )
+        .unwrap();
+        let xmi = String::from_utf8(bytes).unwrap();
+
+        assert!(
+            xmi.contains("orig-b"),
+            "Nested metadata ID should be restored"
+        );
+        assert_eq!(stats.metadata_files.len(), 1);
+
+        let x = stats
+            .files
+            .iter()
+            .find(|f| f.file.ends_with("x.sysml"))
+            .unwrap();
+        assert_eq!(x.restored, 0);
+        assert_eq!(x.generated, 2);
+
+        let y = stats
+            .files
+            .iter()
+            .find(|f| f.file.ends_with("y.sysml"))
+            .unwrap();
+        assert_eq!(y.restored, 1);
+        assert_eq!(y.generated, 1);
+        assert_eq!(stats.restored(), 1);
+    }
+
+    #[test]
+    fn test_export_embeds_provenance() {
+        use syster::interchange::{JsonLd, ModelFormat, Xmi};
+        use syster_cli::Provenance;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.sysml");
+        fs::write(&file_path, "package TestPackage { part def A; }").unwrap();
+
+        let xmi = export_model(&file_path, "xmi", false, false, None, false).unwrap();
+        let jsonld = export_model(&file_path, "jsonld", false, false, None, false).unwrap();
+
+        let from_xmi = Provenance::from_export(&xmi).expect("XMI should carry provenance");
+        let from_jsonld =
+            Provenance::from_export(&jsonld).expect("JSON-LD should carry provenance");
+        assert_eq!(from_xmi.tool, "syster");
+        assert_eq!(from_xmi.input_hash, from_jsonld.input_hash);
+
+        // The record must not change the model readers see
+        let model = Xmi.read(&xmi).expect("Should read XMI");
+        assert!(
+            model
+                .elements
+                .values()
+                .any(|e| e.name.as_deref() == Some("A"))
+        );
+        let model = JsonLd.read(&jsonld).expect("Should read JSON-LD");
+        assert!(
+            model
+                .elements
+                .values()
+                .any(|e| e.name.as_deref() == Some("A"))
+        );
+    }
+
+    #[test]
+    fn test_provenance_input_hash_tracks_sources() {
+        use syster_cli::Provenance;
+
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("test.sysml");
+        fs::write(&file_path, "package P;").unwrap();
+        let before = Provenance::collect(&file_path, false, None).unwrap();
+        let again = Provenance::collect(&file_path, false, None).unwrap();
+        fs::write(&file_path, "package Q;").unwrap();
+        let after = Provenance::collect(&file_path, false, None).unwrap();
+
+        assert_eq!(before.input_hash, again.input_hash);
+        assert_ne!(before.input_hash, after.input_hash);
+    }
 }